@@ -14,34 +14,24 @@
 
 //! Foreign API External Definition
 
+use crate::foreign_middleware::{
+	ForeignCheckMiddleware, ForeignCheckMiddlewareFn, ForeignMiddlewareRegistry,
+};
+#[cfg(feature = "qr")]
+use crate::impls::render_qr_part;
 use crate::keychain::Keychain;
-use crate::libwallet::api_impl::foreign;
+use crate::libwallet::api_impl::{foreign, owner};
 use crate::libwallet::{
-	BlockFees, CbData, Error, NodeClient, NodeVersionInfo, Slate, VersionInfo, WalletInst,
+	Amount, BlockFees, CbData, EncodedPaymentRequest, Error, ErrorKind, FeeContribution, JobAction,
+	NodeClient, NodeVersionInfo, Slate, SlateValidation, VersionAdvisory, VersionInfo, WalletInst,
 	WalletLCProvider,
 };
 use crate::util::secp::key::SecretKey;
 use crate::util::Mutex;
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use ed25519_dalek::Signature as DalekSignature;
 use std::sync::Arc;
 
-/// ForeignAPI Middleware Check callback
-pub type ForeignCheckMiddleware =
-	fn(ForeignCheckMiddlewareFn, Option<NodeVersionInfo>, Option<&Slate>) -> Result<(), Error>;
-
-/// Middleware Identifiers for each function
-pub enum ForeignCheckMiddlewareFn {
-	/// check_version
-	CheckVersion,
-	/// build_coinbase
-	BuildCoinbase,
-	/// verify_slate_messages
-	VerifySlateMessages,
-	/// receive_tx
-	ReceiveTx,
-	/// finalize_invoice_tx
-	FinalizeInvoiceTx,
-}
-
 /// Main interface into all wallet API functions.
 /// Wallet APIs are split into two seperate blocks of functionality
 /// called the ['Owner'](struct.Owner.html) and ['Foreign'](struct.Foreign.html) APIs
@@ -67,9 +57,21 @@ where
 	/// Flag to normalize some output during testing. Can mostly be ignored.
 	pub doctest_mode: bool,
 	/// foreign check middleware
-	middleware: Option<ForeignCheckMiddleware>,
+	middleware: ForeignMiddlewareRegistry,
 	/// Stored keychain mask (in case the stored wallet seed is tokenized)
 	keychain_mask: Option<SecretKey>,
+	/// Whether this is the foundation wallet, and may build foundation outputs
+	foundation_wallet: bool,
+	/// URL notified of each transaction received
+	receive_callback: Option<String>,
+	/// Tag recorded against each transaction received
+	deposit_tag: Option<String>,
+	/// Accounts receives without a destination account are spread across
+	receive_shards: Option<Vec<String>>,
+	/// What's offered when asked to contribute an input to a receive
+	fee_contribution: Option<FeeContribution>,
+	/// Upgrade advisory passed on to counterparties
+	version_advisory: Option<VersionAdvisory>,
 }
 
 impl<'a, L, C, K> Foreign<'a, L, C, K>
@@ -93,7 +95,8 @@ where
 	/// whose seed has been XORed with a token value (such as when running the foreign
 	/// and owner listeners in the same instance)
 	/// * middleware - Option middleware which containts the NodeVersionInfo and can call
-	/// a predefined function with the slate to check if the operation should continue
+	/// a predefined function with the slate to check if the operation should continue. Several
+	/// checks can be run with [`set_middleware`](struct.Foreign.html#method.set_middleware)
 	///
 	/// # Returns
 	/// * An instance of the ForeignApi holding a reference to the provided wallet
@@ -163,14 +166,160 @@ where
 		keychain_mask: Option<SecretKey>,
 		middleware: Option<ForeignCheckMiddleware>,
 	) -> Self {
+		let mut registry = ForeignMiddlewareRegistry::new();
+		if let Some(m) = middleware {
+			registry.register(m);
+		}
 		Foreign {
 			wallet_inst,
 			doctest_mode: false,
-			middleware,
+			middleware: registry,
 			keychain_mask,
+			foundation_wallet: false,
+			receive_callback: None,
+			deposit_tag: None,
+			receive_shards: None,
+			fee_contribution: None,
+			version_advisory: None,
 		}
 	}
 
+	/// Marks this instance of the Foreign API as belonging to the foundation wallet.
+	/// [`build_foundation`](struct.Foreign.html#method.build_foundation) is rejected
+	/// unless this is set.
+	///
+	/// # Arguments
+	/// * `foundation_wallet` - Whether the wallet is the foundation wallet
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_foundation_wallet(&mut self, foundation_wallet: bool) {
+		self.foundation_wallet = foundation_wallet;
+	}
+
+	/// Sets a URL to notify after each successful
+	/// [`receive_tx`](struct.Foreign.html#method.receive_tx). A signed
+	/// [`ReceiveNotification`](../epic_wallet_libwallet/api_impl/types/struct.ReceiveNotification.html)
+	/// with the slate id, amount and proof addresses is posted to it as JSON, without waiting
+	/// for the transaction to be confirmed. The notification is queued as a job, posted and
+	/// retried if need be by the listener's job worker, see
+	/// [`Owner::process_jobs`](struct.Owner.html#method.process_jobs).
+	///
+	/// # Arguments
+	/// * `url` - The callback URL, or `None` to stop sending notifications
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_receive_callback(&mut self, url: Option<String>) {
+		self.receive_callback = url;
+	}
+
+	/// Sets a tag to record in the transaction log entry of each transaction received by
+	/// [`receive_tx`](struct.Foreign.html#method.receive_tx), as its `deposit_tag`. Giving each
+	/// user their own tag lets a single wallet tell apart the deposits of many users, as if
+	/// each had an address of their own.
+	///
+	/// # Arguments
+	/// * `tag` - The tag, of up to 64 letters, digits, '-', '_' or '.', or `None` to stop
+	/// tagging transactions
+	/// # Returns
+	/// * Ok(()) if the tag was set
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if the tag is invalid
+	pub fn set_deposit_tag(&mut self, tag: Option<String>) -> Result<(), Error> {
+		if let Some(ref t) = tag {
+			foreign::validate_deposit_tag(t)?;
+		}
+		self.deposit_tag = tag;
+		Ok(())
+	}
+
+	/// Signs a response of the foreign API listener with the payment proof address of the
+	/// wallet's active account, so a sender given that address can check they reached the
+	/// intended recipient, even through relays they don't trust. The signature covers a hash
+	/// of the request body and the exact bytes of the response body, and is checked with
+	/// [`verify_response_signature`](../epic_wallet_libwallet/api_impl/foreign/fn.verify_response_signature.html).
+	///
+	/// # Arguments
+	/// * `request` - The body of the request answered
+	/// * `response` - The body of the response, as it will be sent
+	/// # Returns
+	/// * Ok with the signing payment proof address and the signature
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	pub fn sign_response(
+		&self,
+		request: &[u8],
+		response: &[u8],
+	) -> Result<(DalekPublicKey, DalekSignature), Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		foreign::sign_response(&mut **w, (&self.keychain_mask).as_ref(), request, response)
+	}
+
+	/// Spreads transactions received by [`receive_tx`](struct.Foreign.html#method.receive_tx)
+	/// without a destination account across the given accounts, each going to the one holding
+	/// the least, so a busy wallet's funds aren't all tied up in a single account. See
+	/// [`Owner::rebalance_shards`](struct.Owner.html#method.rebalance_shards) for evening them
+	/// out.
+	///
+	/// # Arguments
+	/// * `shards` - Names of the shard accounts, or `None` to receive into the active account
+	/// # Returns
+	/// * Nothing
+	pub fn set_receive_shards(&mut self, shards: Option<Vec<String>>) {
+		self.receive_shards = shards;
+	}
+
+	/// Offers to contribute an input of this wallet's to transactions received by
+	/// [`receive_tx`](struct.Foreign.html#method.receive_tx), when the sender asks for one by
+	/// setting the slate's `receiver_fee`. Mixing in an input of the receiver's makes it harder
+	/// to tell from the transaction which outputs were paid, and lets merchants pay part of
+	/// their customers' fees, which is taken out of the input. The largest part of the fee
+	/// offered is reported by [`check_version`](struct.Foreign.html#method.check_version), so
+	/// senders know what they can ask for, and receives asking for more of the fee than offered
+	/// are refused.
+	///
+	/// Contributed inputs stay locked until their transactions confirm or are cancelled, and
+	/// anyone can send a slate asking for one, so at most `max_pending` of them are locked at
+	/// once. Past that, receives asking for part of the fee are refused, and ones only asking
+	/// for an input go ahead without one.
+	///
+	/// # Arguments
+	/// * `fee_contribution` - What to offer, see
+	/// [`FeeContribution`](../epic_wallet_libwallet/types/struct.FeeContribution.html), or
+	/// `None` to never contribute
+	/// # Returns
+	/// * Nothing
+	pub fn set_fee_contribution(&mut self, fee_contribution: Option<FeeContribution>) {
+		self.fee_contribution = fee_contribution;
+	}
+
+	/// Passes an upgrade advisory on to counterparties in the version info returned by
+	/// [`check_version`](struct.Foreign.html#method.check_version), alongside this wallet's
+	/// release, so they can warn users of releases it finds outdated. Advisories are fetched
+	/// and checked with `fetch_version_advisory` in the impls crate.
+	///
+	/// # Arguments
+	/// * `version_advisory` - The advisory, or `None` to pass none on
+	/// # Returns
+	/// * Nothing
+	pub fn set_version_advisory(&mut self, version_advisory: Option<VersionAdvisory>) {
+		self.version_advisory = version_advisory;
+	}
+
+	/// Replaces the checks run before each call, including any middleware passed to
+	/// [`new`](struct.Foreign.html#method.new). See the
+	/// [`foreign_middleware`](foreign_middleware/index.html) module for the built-in checks and
+	/// how to add others.
+	///
+	/// # Arguments
+	/// * `middleware` - The checks to run, in order
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_middleware(&mut self, middleware: ForeignMiddlewareRegistry) {
+		self.middleware = middleware;
+	}
+
 	/// Return the version capabilities of the running ForeignApi Node
 	/// # Arguments
 	/// None
@@ -188,16 +337,19 @@ where
 	/// ```
 
 	pub fn check_version(&self) -> Result<VersionInfo, Error> {
-		if let Some(m) = self.middleware.as_ref() {
+		if !self.middleware.is_empty() {
 			let mut w_lock = self.wallet_inst.lock();
 			let w = w_lock.lc_provider()?.wallet_inst()?;
-			m(
+			self.middleware.check(
 				ForeignCheckMiddlewareFn::CheckVersion,
 				w.w2n_client().get_version_info(),
 				None,
 			)?;
 		}
-		Ok(foreign::check_version())
+		Ok(foreign::check_version(
+			self.fee_contribution.as_ref().map(|c| c.max_fee),
+			self.version_advisory.clone(),
+		))
 	}
 
 	/// Builds a new unconfirmed coinbase output in the wallet, generally for inclusion in a
@@ -253,8 +405,8 @@ where
 	pub fn build_coinbase(&self, block_fees: &BlockFees) -> Result<CbData, Error> {
 		let mut w_lock = self.wallet_inst.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		if let Some(m) = self.middleware.as_ref() {
-			m(
+		if !self.middleware.is_empty() {
+			self.middleware.check(
 				ForeignCheckMiddlewareFn::BuildCoinbase,
 				w.w2n_client().get_version_info(),
 				None,
@@ -268,11 +420,29 @@ where
 		)
 	}
 
+	/// Builds a new unconfirmed foundation reward output in the wallet, returning the output
+	/// and kernel to be included in a block at the given height. Only available when this
+	/// instance has been marked as the foundation wallet via
+	/// [`set_foundation_wallet`](struct.Foreign.html#method.set_foundation_wallet); all other
+	/// wallets return [`ErrorKind::NotFoundationWallet`](../epic_wallet_libwallet/enum.ErrorKind.html).
+	///
+	/// # Arguments
+	///
+	/// * `block_fees` - A [`BlockFees`](../epic_wallet_libwallet/api_impl/types/struct.BlockFees.html)
+	/// struct, set up as for [`build_coinbase`](struct.Foreign.html#method.build_coinbase)
+	///
+	/// # Returns
+	/// * `Ok`([`cb_data`](../epic_wallet_libwallet/api_impl/types/struct.CbData.html)`)` if successful.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+
 	pub fn build_foundation(&self, block_fees: &BlockFees) -> Result<CbData, Error> {
+		if !self.foundation_wallet {
+			return Err(ErrorKind::NotFoundationWallet.into());
+		}
 		let mut w_lock = self.wallet_inst.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		if let Some(m) = self.middleware.as_ref() {
-			m(
+		if !self.middleware.is_empty() {
+			self.middleware.check(
 				ForeignCheckMiddlewareFn::BuildCoinbase,
 				w.w2n_client().get_version_info(),
 				None,
@@ -325,10 +495,10 @@ where
 	/// ```
 
 	pub fn verify_slate_messages(&self, slate: &Slate) -> Result<(), Error> {
-		if let Some(m) = self.middleware.as_ref() {
+		if !self.middleware.is_empty() {
 			let mut w_lock = self.wallet_inst.lock();
 			let w = w_lock.lc_provider()?.wallet_inst()?;
-			m(
+			self.middleware.check(
 				ForeignCheckMiddlewareFn::VerifySlateMessages,
 				w.w2n_client().get_version_info(),
 				Some(slate),
@@ -337,6 +507,128 @@ where
 		foreign::verify_slate_messages(slate)
 	}
 
+	/// Checks a slate supplied by another party without acting on it, so relays, escrow services
+	/// and the like can screen slates before passing them on. Nothing is stored and the wallet's
+	/// keys aren't used; the slate is checked for:
+	///
+	/// * Structure: participant data fits the number of participants, and the transaction has a
+	/// single kernel whose fee and lock height match the slate's.
+	/// * Version: the slate's version and block header version are ones this wallet can process.
+	/// * Fee: the fee covers the transaction's current weight and doesn't exceed the amount.
+	/// * Signatures: any partial signatures, and any participant message signatures, verify.
+	///
+	/// # Arguments
+	///
+	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html).
+	///
+	/// # Returns
+	/// * Ok with a [`SlateValidation`](../epic_wallet_libwallet/api_impl/types/struct.SlateValidation.html)
+	/// reporting the result of each check, along with why any failed. A slate that fails checks
+	/// is not an error.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Foreign.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env_foreign!(wallet, wallet_config);
+	///
+	/// let mut api_foreign = Foreign::new(wallet.clone(), None, None);
+	///
+	/// # let slate = Slate::blank(2);
+	/// // Receive a slate via some means
+	///
+	/// let res = api_foreign.validate_slate(&slate);
+	///
+	/// if let Ok(validation) = res {
+	///		if !validation.valid {
+	///			// Reject the slate, reporting validation.errors
+	///			// ...
+	///		}
+	/// }
+	/// ```
+
+	pub fn validate_slate(&self, slate: &Slate) -> Result<SlateValidation, Error> {
+		if !self.middleware.is_empty() {
+			let mut w_lock = self.wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			// the slate's compatibility is reported rather than refused
+			self.middleware.check(
+				ForeignCheckMiddlewareFn::ValidateSlate,
+				w.w2n_client().get_version_info(),
+				None,
+			)?;
+		}
+		Ok(foreign::validate_slate(slate))
+	}
+
+	/// Creates a request to be paid the given amount, which the payer's wallet pays with
+	/// [`Owner::pay_request`](struct.Owner.html#method.pay_request). The request is signed with
+	/// the payment proof address of the wallet's active account, which the payment's proof
+	/// then names, and expires after
+	/// [`PAYMENT_REQUEST_TTL_SECS`](../epic_wallet_libwallet/payment_request/constant.PAYMENT_REQUEST_TTL_SECS.html).
+	/// The transaction paying it takes the request's id as its slate id, so the payment can be
+	/// found with [`Owner::retrieve_txs`](struct.Owner.html#method.retrieve_txs) once received.
+	///
+	/// # Arguments
+	/// * `amount` - The amount to request
+	/// * `memo` - What the payment is for, of up to 100 bytes, passed on as the payer's message
+	///
+	/// # Returns
+	/// * Ok with an [`EncodedPaymentRequest`](../epic_wallet_libwallet/api_impl/types/struct.EncodedPaymentRequest.html)
+	/// holding the request, armored and as a QR code. The QR code is left empty when the
+	/// wallet was built without the `qr` feature
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Foreign.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env_foreign!(wallet, wallet_config);
+	///
+	/// let mut api_foreign = Foreign::new(wallet.clone(), None, None);
+	///
+	/// let res = api_foreign.create_payment_request(
+	///		Amount::from_freemans(100_000_000),
+	///		Some("order #42".to_owned()),
+	/// );
+	///
+	/// if let Ok(r) = res {
+	///		// pass r.armored or r.qr on to the payer
+	///		// ...
+	/// }
+	/// ```
+
+	pub fn create_payment_request(
+		&self,
+		amount: Amount,
+		memo: Option<String>,
+	) -> Result<EncodedPaymentRequest, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		if !self.middleware.is_empty() {
+			self.middleware.check(
+				ForeignCheckMiddlewareFn::CreatePaymentRequest,
+				w.w2n_client().get_version_info(),
+				None,
+			)?;
+		}
+		let request = foreign::create_payment_request(
+			&mut **w,
+			(&self.keychain_mask).as_ref(),
+			amount,
+			memo,
+		)?;
+		let armored = request.armor();
+		#[cfg(feature = "qr")]
+		let qr = render_qr_part(&armored)?;
+		#[cfg(not(feature = "qr"))]
+		let qr = String::new();
+		Ok(EncodedPaymentRequest {
+			request,
+			armored,
+			qr,
+		})
+	}
+
 	/// Recieve a tranaction created by another party, returning the modified
 	/// [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html) object, modified with
 	/// the recipient's output for the transaction amount, and public signature data. This slate can
@@ -374,6 +666,10 @@ where
 	/// # Remarks
 	///
 	/// * This method will store a partially completed transaction in the wallet's transaction log.
+	/// * The participant messages of both parties, and the sender's payment proof address if a
+	/// payment proof was requested, are kept on the stored
+	/// [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) and returned by
+	/// [`retrieve_txs`](struct.Owner.html#method.retrieve_txs).
 	///
 	/// # Example
 	/// Set up as in [new](struct.Foreign.html#method.new) method above.
@@ -401,21 +697,51 @@ where
 	) -> Result<Slate, Error> {
 		let mut w_lock = self.wallet_inst.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		if let Some(m) = self.middleware.as_ref() {
-			m(
+		if !self.middleware.is_empty() {
+			self.middleware.check(
 				ForeignCheckMiddlewareFn::ReceiveTx,
 				w.w2n_client().get_version_info(),
 				Some(slate),
 			)?;
 		}
-		foreign::receive_tx(
+		let shard = match (dest_acct_name, self.receive_shards.as_ref()) {
+			(None, Some(shards)) => Some(owner::select_receive_shard(&mut **w, shards, 1)?),
+			_ => None,
+		};
+		let dest_acct_name = shard.as_ref().map(|s| s.as_str()).or(dest_acct_name);
+		let ret_slate = foreign::receive_tx(
 			&mut **w,
 			(&self.keychain_mask).as_ref(),
 			slate,
 			dest_acct_name,
 			message,
+			self.fee_contribution.as_ref(),
 			self.doctest_mode,
-		)
+		)?;
+		if let Some(ref tag) = self.deposit_tag {
+			foreign::tag_deposit(&mut **w, (&self.keychain_mask).as_ref(), &ret_slate, tag)?;
+		}
+		if let Some(url) = self.receive_callback.clone() {
+			// queued rather than posted, so the reply to the sender isn't
+			// held up and a failed notification is retried
+			let queued = foreign::receive_notification(
+				&mut **w,
+				(&self.keychain_mask).as_ref(),
+				&ret_slate,
+				dest_acct_name,
+			)
+			.and_then(|n| {
+				let body = serde_json::to_value(&n).map_err(|e| {
+					ErrorKind::JobQueue(format!("serializing receive notification: {}", e))
+				})?;
+				let action = JobAction::Webhook { url, body };
+				owner::queue_job(&mut **w, (&self.keychain_mask).as_ref(), action, false)
+			});
+			if let Err(e) = queued {
+				warn!("Unable to queue receive notification: {}", e);
+			}
+		}
+		Ok(ret_slate)
 	}
 
 	/// Finalizes an invoice transaction initiated by this wallet's Owner api.
@@ -451,7 +777,7 @@ where
 	/// // . . .
 	/// // Issue the invoice tx via the owner API
 	/// let args = IssueInvoiceTxArgs {
-	///		amount: 10_000_000_000,
+	///		amount: Amount::from_freemans(10_000_000_000),
 	///		..Default::default()
 	/// };
 	/// let result = api_owner.issue_invoice_tx(None, args);
@@ -468,8 +794,8 @@ where
 	pub fn finalize_invoice_tx(&self, slate: &Slate) -> Result<Slate, Error> {
 		let mut w_lock = self.wallet_inst.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		if let Some(m) = self.middleware.as_ref() {
-			m(
+		if !self.middleware.is_empty() {
+			self.middleware.check(
 				ForeignCheckMiddlewareFn::FinalizeInvoiceTx,
 				w.w2n_client().get_version_info(),
 				Some(slate),
@@ -499,7 +825,7 @@ macro_rules! doctest_helper_setup_doc_env_foreign {
 		use api::{Foreign, Owner};
 		use config::WalletConfig;
 		use impls::{DefaultLCProvider, DefaultWalletImpl, HTTPNodeClient};
-		use libwallet::{BlockFees, IssueInvoiceTxArgs, Slate, WalletInst};
+		use libwallet::{Amount, BlockFees, IssueInvoiceTxArgs, Slate, WalletInst};
 
 		let dir = tempdir().map_err(|e| format!("{:#?}", e)).unwrap();
 		let dir = dir
@@ -0,0 +1,268 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks run before each Foreign API call, any of which can refuse it.
+//!
+//! Checks implement [`ForeignMiddleware`](trait.ForeignMiddleware.html) and are
+//! added to a [`ForeignMiddlewareRegistry`](struct.ForeignMiddlewareRegistry.html),
+//! which runs them in the order they were registered and stops at the first
+//! to refuse the call. The registry is given to the API with
+//! [`Foreign::set_middleware`](../struct.Foreign.html#method.set_middleware).
+//!
+//! Built-in checks cover slate compatibility with the node
+//! ([`VersionCheck`](struct.VersionCheck.html)), the amounts that may be received
+//! ([`AmountPolicy`](struct.AmountPolicy.html)) and who they may be received from
+//! ([`SenderAllowlist`](struct.SenderAllowlist.html)).
+//!
+//! Anyone packaging the wallet can compile in their own checks by implementing
+//! the trait and registering them alongside the built-ins, then passing the
+//! registry to the listener:
+//!
+//! ```
+//! use epic_wallet_api::{
+//! 	ForeignCheckMiddlewareFn, ForeignMiddleware, ForeignMiddlewareRegistry, VersionCheck,
+//! };
+//! use epic_wallet_libwallet::{Error, ErrorKind, NodeVersionInfo, Slate};
+//!
+//! /// Refuses slates without a payment proof
+//! struct RequireProofs;
+//!
+//! impl ForeignMiddleware for RequireProofs {
+//! 	fn check(
+//! 		&self,
+//! 		call: ForeignCheckMiddlewareFn,
+//! 		_node_version_info: Option<&NodeVersionInfo>,
+//! 		slate: Option<&Slate>,
+//! 	) -> Result<(), Error> {
+//! 		match (call, slate) {
+//! 			(ForeignCheckMiddlewareFn::ReceiveTx, Some(s)) if s.payment_proof.is_none() => {
+//! 				Err(ErrorKind::PolicyRefused("a payment proof is required".to_owned()).into())
+//! 			}
+//! 			_ => Ok(()),
+//! 		}
+//! 	}
+//! }
+//!
+//! let mut middleware = ForeignMiddlewareRegistry::new();
+//! middleware.register(VersionCheck);
+//! middleware.register(RequireProofs);
+//! ```
+
+use crate::libwallet::{
+	address, Amount, Error, ErrorKind, NodeVersionInfo, Slate, EPIC_BLOCK_HEADER_VERSION,
+};
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use std::sync::Arc;
+
+/// ForeignAPI Middleware Check callback
+pub type ForeignCheckMiddleware =
+	fn(ForeignCheckMiddlewareFn, Option<NodeVersionInfo>, Option<&Slate>) -> Result<(), Error>;
+
+/// Middleware Identifiers for each function
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForeignCheckMiddlewareFn {
+	/// check_version
+	CheckVersion,
+	/// build_coinbase
+	BuildCoinbase,
+	/// verify_slate_messages
+	VerifySlateMessages,
+	/// validate_slate
+	ValidateSlate,
+	/// receive_tx
+	ReceiveTx,
+	/// finalize_invoice_tx
+	FinalizeInvoiceTx,
+	/// create_payment_request
+	CreatePaymentRequest,
+}
+
+/// A check run before Foreign API calls
+pub trait ForeignMiddleware: Send + Sync {
+	/// Check a call about to be made, along with the version info of the
+	/// wallet's node and the slate passed in, if any. Returning an error
+	/// refuses the call with that error.
+	fn check(
+		&self,
+		call: ForeignCheckMiddlewareFn,
+		node_version_info: Option<&NodeVersionInfo>,
+		slate: Option<&Slate>,
+	) -> Result<(), Error>;
+}
+
+impl ForeignMiddleware for ForeignCheckMiddleware {
+	fn check(
+		&self,
+		call: ForeignCheckMiddlewareFn,
+		node_version_info: Option<&NodeVersionInfo>,
+		slate: Option<&Slate>,
+	) -> Result<(), Error> {
+		self(call, node_version_info.cloned(), slate)
+	}
+}
+
+/// The checks to run before Foreign API calls, in order
+#[derive(Clone, Default)]
+pub struct ForeignMiddlewareRegistry {
+	middleware: Vec<Arc<dyn ForeignMiddleware>>,
+}
+
+impl ForeignMiddlewareRegistry {
+	/// An empty registry, which allows every call
+	pub fn new() -> ForeignMiddlewareRegistry {
+		ForeignMiddlewareRegistry::default()
+	}
+
+	/// Add a check, to run after those already registered
+	pub fn register<M>(&mut self, middleware: M)
+	where
+		M: ForeignMiddleware + 'static,
+	{
+		self.middleware.push(Arc::new(middleware));
+	}
+
+	/// Whether there are no checks to run
+	pub fn is_empty(&self) -> bool {
+		self.middleware.is_empty()
+	}
+
+	/// Run each check in turn, stopping at the first to refuse the call
+	pub fn check(
+		&self,
+		call: ForeignCheckMiddlewareFn,
+		node_version_info: Option<NodeVersionInfo>,
+		slate: Option<&Slate>,
+	) -> Result<(), Error> {
+		for m in self.middleware.iter() {
+			m.check(call, node_version_info.as_ref(), slate)?;
+		}
+		Ok(())
+	}
+}
+
+/// Refuses slates built for an older block header version than the node's
+/// chain expects. Coinbases can be built regardless.
+pub struct VersionCheck;
+
+impl ForeignMiddleware for VersionCheck {
+	fn check(
+		&self,
+		call: ForeignCheckMiddlewareFn,
+		node_version_info: Option<&NodeVersionInfo>,
+		slate: Option<&Slate>,
+	) -> Result<(), Error> {
+		if call == ForeignCheckMiddlewareFn::BuildCoinbase {
+			return Ok(());
+		}
+		let bhv = node_version_info
+			.map(|n| n.block_header_version)
+			.unwrap_or(2);
+		if let Some(s) = slate {
+			if bhv > 3 && s.version_info.block_header_version < EPIC_BLOCK_HEADER_VERSION {
+				Err(ErrorKind::Compatibility(
+					"Incoming Slate is not compatible with this wallet. \
+					 Please upgrade the node or use a different one."
+						.into(),
+				))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Limits the amounts, in freemans, that can be received in a single
+/// transaction
+pub struct AmountPolicy {
+	/// Smallest amount received, if any
+	pub min_amount: Option<u64>,
+	/// Largest amount received, if any
+	pub max_amount: Option<u64>,
+}
+
+impl ForeignMiddleware for AmountPolicy {
+	fn check(
+		&self,
+		call: ForeignCheckMiddlewareFn,
+		_node_version_info: Option<&NodeVersionInfo>,
+		slate: Option<&Slate>,
+	) -> Result<(), Error> {
+		let slate = match (call, slate) {
+			(ForeignCheckMiddlewareFn::ReceiveTx, Some(s)) => s,
+			_ => return Ok(()),
+		};
+		if let Some(min) = self.min_amount {
+			if slate.amount < min {
+				return Err(ErrorKind::PolicyRefused(format!(
+					"amount {} is below the minimum of {}",
+					slate.amount,
+					Amount::from_freemans(min)
+				))
+				.into());
+			}
+		}
+		if let Some(max) = self.max_amount {
+			if slate.amount > max {
+				return Err(ErrorKind::PolicyRefused(format!(
+					"amount {} is above the maximum of {}",
+					slate.amount,
+					Amount::from_freemans(max)
+				))
+				.into());
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Only receives transactions carrying a payment proof whose sender is one
+/// of the given addresses
+pub struct SenderAllowlist {
+	senders: Vec<DalekPublicKey>,
+}
+
+impl SenderAllowlist {
+	/// Allow the given onion v3 payment proof addresses
+	pub fn new(addresses: &[String]) -> Result<SenderAllowlist, Error> {
+		let senders = addresses
+			.iter()
+			.map(|a| address::pubkey_from_onion_v3(a))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(SenderAllowlist { senders })
+	}
+}
+
+impl ForeignMiddleware for SenderAllowlist {
+	fn check(
+		&self,
+		call: ForeignCheckMiddlewareFn,
+		_node_version_info: Option<&NodeVersionInfo>,
+		slate: Option<&Slate>,
+	) -> Result<(), Error> {
+		let slate = match (call, slate) {
+			(ForeignCheckMiddlewareFn::ReceiveTx, Some(s)) => s,
+			_ => return Ok(()),
+		};
+		match slate.payment_proof.as_ref() {
+			Some(p) if self.senders.contains(&p.sender_address) => Ok(()),
+			Some(_) => Err(ErrorKind::PolicyRefused(
+				"the sender's payment proof address is not allowed".to_owned(),
+			)
+			.into()),
+			None => Err(ErrorKind::PolicyRefused(
+				"a payment proof from an allowed sender is required".to_owned(),
+			)
+			.into()),
+		}
+	}
+}
@@ -0,0 +1,165 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handles to the Owner and Foreign APIs which are cheap to clone and can be
+//! shared between threads and tasks, for embedding the wallet in servers.
+//!
+//! Both hold a `'static` API instance behind an `Arc`, so clones refer to the
+//! same instance, its settings and, for the Owner API, its updater thread.
+//!
+//! Locking:
+//! * Every call still locks the wallet instance it was created with for as
+//! long as it's working with the wallet, as with the APIs themselves, so calls
+//! through any number of handles (or other holders of the same wallet
+//! instance) run one at a time where they touch the wallet.
+//! * [`OwnerApi`](struct.OwnerApi.html) derefs to [`Owner`](../struct.Owner.html),
+//! whose settings are already held behind their own locks, and adds none.
+//! * [`ForeignApi`](struct.ForeignApi.html) guards its [`Foreign`](../struct.Foreign.html)
+//! instance with a read-write lock. Calls made through
+//! [`call`](struct.ForeignApi.html#method.call) share the read lock, and
+//! changes to its settings through
+//! [`configure`](struct.ForeignApi.html#method.configure) wait for them to
+//! finish. Calls shouldn't configure the handle they're made through, which
+//! would deadlock.
+
+use crate::keychain::Keychain;
+use crate::libwallet::{NodeClient, WalletInst, WalletLCProvider};
+use crate::util::secp::key::SecretKey;
+use crate::util::{Mutex, RwLock};
+use crate::{Foreign, ForeignCheckMiddleware, Owner};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A shareable handle to an [`Owner`](../struct.Owner.html) API instance.
+/// Clones refer to the same instance
+pub struct OwnerApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	inner: Arc<Owner<L, C, K>>,
+}
+
+impl<L, C, K> OwnerApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	/// A handle to a new Owner API instance on the wallet instance, as
+	/// [`Owner::new`](../struct.Owner.html#method.new)
+	pub fn new(wallet_inst: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>) -> Self {
+		OwnerApi::from_owner(Owner::new(wallet_inst))
+	}
+
+	/// A handle to an Owner API instance that's already been set up
+	pub fn from_owner(owner: Owner<L, C, K>) -> Self {
+		OwnerApi {
+			inner: Arc::new(owner),
+		}
+	}
+}
+
+impl<L, C, K> Clone for OwnerApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	fn clone(&self) -> Self {
+		OwnerApi {
+			inner: self.inner.clone(),
+		}
+	}
+}
+
+impl<L, C, K> Deref for OwnerApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	type Target = Owner<L, C, K>;
+
+	fn deref(&self) -> &Owner<L, C, K> {
+		&self.inner
+	}
+}
+
+/// A shareable handle to a [`Foreign`](../struct.Foreign.html) API instance.
+/// Clones refer to the same instance, so settings changed through one apply
+/// to calls through all of them
+pub struct ForeignApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	inner: Arc<RwLock<Foreign<'static, L, C, K>>>,
+}
+
+impl<L, C, K> ForeignApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	/// A handle to a new Foreign API instance on the wallet instance, as
+	/// [`Foreign::new`](../struct.Foreign.html#method.new)
+	pub fn new(
+		wallet_inst: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+		keychain_mask: Option<SecretKey>,
+		middleware: Option<ForeignCheckMiddleware>,
+	) -> Self {
+		ForeignApi::from_foreign(Foreign::new(wallet_inst, keychain_mask, middleware))
+	}
+
+	/// A handle to a Foreign API instance that's already been set up
+	pub fn from_foreign(foreign: Foreign<'static, L, C, K>) -> Self {
+		ForeignApi {
+			inner: Arc::new(RwLock::new(foreign)),
+		}
+	}
+
+	/// Make calls on the API instance, alongside calls through other handles
+	pub fn call<F, T>(&self, f: F) -> T
+	where
+		F: FnOnce(&Foreign<'static, L, C, K>) -> T,
+	{
+		f(&self.inner.read())
+	}
+
+	/// Change the settings of the API instance, once calls in progress
+	/// through any handle have finished
+	pub fn configure<F, T>(&self, f: F) -> T
+	where
+		F: FnOnce(&mut Foreign<'static, L, C, K>) -> T,
+	{
+		f(&mut self.inner.write())
+	}
+}
+
+impl<L, C, K> Clone for ForeignApi<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	fn clone(&self) -> Self {
+		ForeignApi {
+			inner: self.inner.clone(),
+		}
+	}
+}
@@ -38,7 +38,9 @@ extern crate serde_json;
 extern crate log;
 
 mod foreign;
+pub mod foreign_middleware;
 mod foreign_rpc;
+mod handles;
 
 mod owner;
 mod owner_rpc;
@@ -46,17 +48,24 @@ mod owner_rpc_s;
 
 mod types;
 
-pub use crate::foreign::{Foreign, ForeignCheckMiddleware, ForeignCheckMiddlewareFn};
+pub use crate::foreign::Foreign;
+pub use crate::foreign_middleware::{
+	AmountPolicy, ForeignCheckMiddleware, ForeignCheckMiddlewareFn, ForeignMiddleware,
+	ForeignMiddlewareRegistry, SenderAllowlist, VersionCheck,
+};
 pub use crate::foreign_rpc::ForeignRpc;
+pub use crate::handles::{ForeignApi, OwnerApi};
 pub use crate::owner::Owner;
 pub use crate::owner_rpc::OwnerRpc;
 pub use crate::owner_rpc_s::OwnerRpcS;
 
 pub use crate::foreign_rpc::foreign_rpc as foreign_rpc_client;
+#[cfg(feature = "doctest")]
 pub use crate::foreign_rpc::run_doctest_foreign;
+#[cfg(feature = "doctest")]
 pub use crate::owner_rpc::run_doctest_owner;
 
 pub use types::{
-	ECDHPubkey, EncryptedRequest, EncryptedResponse, EncryptionErrorResponse, PubAddress, RpcId,
-	Token,
+	ECDHPubkey, EncryptedBody, EncryptedRequest, EncryptedResponse, EncryptionErrorResponse,
+	PubAddress, RpcId, Token, REQUEST_TIMESTAMP,
 };
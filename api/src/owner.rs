@@ -16,28 +16,56 @@
 
 use chrono::prelude::*;
 use ed25519_dalek::PublicKey as DalekPublicKey;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use uuid::Uuid;
 
 use crate::config::{TorConfig, WalletConfig};
+use crate::core::core::feijoada::PoWType;
 use crate::core::core::Transaction;
 use crate::core::global;
-use crate::impls::create_sender;
+use crate::impls::{
+	create_sender, post_webhook, send_response_slate, validate_destination, ACME_SUPPORT,
+	HTTP_SUPPORT, OS_KEYSTORE_SUPPORT, PKCS11_SUPPORT, QR_SUPPORT, TOR_SUPPORT,
+};
 use crate::keychain::{Identifier, Keychain};
 use crate::libwallet::api_impl::owner_updater::{start_updater_log_thread, StatusMessage};
 use crate::libwallet::api_impl::{owner, owner_updater};
 use crate::libwallet::{
-	address, AcctPathMapping, Error, ErrorKind, InitTxArgs, IssueInvoiceTxArgs, NodeClient,
-	NodeHeightResult, OutputCommitMapping, PaymentProof, Slate, TxLogEntry, WalletInfo, WalletInst,
-	WalletLCProvider,
+	address, AccountKeyExport, AcctPathMapping, AuditEntry, BalanceHistoryEntry, CancelPreview,
+	Capabilities, CleanedCoinbase, CsvImport, Denomination, DestinationValidation, Error,
+	ErrorKind, ExpectedCommitment, FinalityState, IdempotentResult, InitTxArgs, IssueInvoiceTxArgs,
+	Job, JobAction, JobResult, KernelStatus, LockedOutput, MerchantInvoice, MiningRewardSummary,
+	NodeClient, NodeHeightResult, NodePing, NodeStatus, OutputCommitMapping, OutputData,
+	OutputLineage, OutputPage, OutputStatus, PaymentProof, PaymentRequest, PaymentUri, QueuedSend,
+	QueuedSendResult, Reservation, RewoundOutput, ShardBalance, Slate, TxDetails, TxFilter,
+	TxFinality, TxLogEntry, TxLogPage, TxTemplate, WalletEvent, WalletInfo, WalletInst,
+	WalletLCProvider, WalletStateExport, WalletStateImport, ACCOUNT_KEY_EXPORT_WARNING,
 };
 use crate::util::logger::LoggingConfig;
 use crate::util::secp::key::SecretKey;
+use crate::util::secp::pedersen;
 use crate::util::{from_hex, static_secp_instance, Mutex, ZeroingString};
+use epic_wallet_util::ct_eq;
+use std::env;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, Sender};
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Environment variable holding the recovery phrase used by
+/// [`init_wallet_from_env`](struct.Owner.html#method.init_wallet_from_env) when none is given
+pub const PROVISIONING_MNEMONIC_ENV: &str = "EPIC_WALLET_MNEMONIC";
+
+/// Environment variable holding the password used by
+/// [`init_wallet_from_env`](struct.Owner.html#method.init_wallet_from_env) when none is given
+pub const PROVISIONING_PASSWORD_ENV: &str = "EPIC_WALLET_PASSWORD";
+
+/// How often a transaction waited on by
+/// [`wait_tx_finality`](struct.Owner.html#method.wait_tx_finality) or
+/// [`watch_tx_finality`](struct.Owner.html#method.watch_tx_finality) is checked on the chain
+pub const FINALITY_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Main interface into all wallet API functions.
 /// Wallet APIs are split into two seperate blocks of functionality
@@ -77,6 +105,26 @@ where
 	/// Optional TOR configuration, holding address of sender and
 	/// data directory
 	tor_config: Mutex<Option<TorConfig>>,
+	/// Minimum number of confirmations outputs must have before they can
+	/// be selected for spending, regardless of `minimum_confirmations`
+	min_reorg_safe_confirmations: Mutex<Option<u64>>,
+	/// Largest part of the fee paid when paying an invoice
+	max_invoice_fee: Mutex<Option<u64>>,
+	/// Held while making a call with an idempotency key, so concurrent
+	/// retries of it can't both go through
+	idempotency_lock: Mutex<()>,
+	/// One-shot token allowing a single call to `init_wallet_from_env`
+	provisioning_token: Mutex<Option<ZeroingString>>,
+	/// Whether `set_output_status` may be used
+	allow_output_status_override: Mutex<bool>,
+	/// Whether webhooks may be queued, so long as they can be posted
+	allow_webhooks: Mutex<bool>,
+	/// Held while making queued sends, so concurrent calls can't make the
+	/// same one twice
+	send_queue_lock: Mutex<()>,
+	/// Held while running queued jobs, so concurrent calls can't run the
+	/// same one twice
+	job_queue_lock: Mutex<()>,
 }
 
 impl<L, C, K> Owner<L, C, K>
@@ -181,6 +229,14 @@ where
 			status_tx: Mutex::new(Some(tx)),
 			updater_messages,
 			tor_config: Mutex::new(None),
+			min_reorg_safe_confirmations: Mutex::new(None),
+			max_invoice_fee: Mutex::new(None),
+			idempotency_lock: Mutex::new(()),
+			provisioning_token: Mutex::new(None),
+			allow_output_status_override: Mutex::new(false),
+			allow_webhooks: Mutex::new(true),
+			send_queue_lock: Mutex::new(()),
+			job_queue_lock: Mutex::new(()),
 		}
 	}
 
@@ -197,6 +253,118 @@ where
 		*lock = tor_config;
 	}
 
+	/// Set the reorg safety policy for this instance of the OwnerAPI. When set, outputs
+	/// confirmed fewer than `min_reorg_safe_confirmations` blocks ago are never selected as
+	/// inputs by [`init_send_tx`](struct.Owner.html#method.init_send_tx),
+	/// [`process_invoice_tx`](struct.Owner.html#method.process_invoice_tx) or
+	/// [`create_tx_template`](struct.Owner.html#method.create_tx_template), even if the
+	/// `minimum_confirmations` given in the
+	/// [`InitTxArgs`](../epic_wallet_libwallet/api_impl/types/struct.InitTxArgs.html) is lower.
+	/// This protects against spending outputs that could be undone by a shallow fork.
+	///
+	/// The policy can be bypassed for an individual transaction by setting
+	/// `override_reorg_safety` in its `InitTxArgs`.
+	///
+	/// # Arguments
+	/// * `min_reorg_safe_confirmations` - The minimum depth of spendable outputs, or `None`
+	/// to disable the policy
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_min_reorg_safe_confirmations(&self, min_reorg_safe_confirmations: Option<u64>) {
+		let mut lock = self.min_reorg_safe_confirmations.lock();
+		*lock = min_reorg_safe_confirmations;
+	}
+
+	/// Set the invoice fee policy for this instance of the OwnerAPI. When set,
+	/// [`process_invoice_tx`](struct.Owner.html#method.process_invoice_tx) refuses to pay
+	/// invoices that would cost more than `max_invoice_fee` in fees, or more than the
+	/// `max_invoice_fee` given in the
+	/// [`InitTxArgs`](../epic_wallet_libwallet/api_impl/types/struct.InitTxArgs.html) if that
+	/// is lower.
+	///
+	/// # Arguments
+	/// * `max_invoice_fee` - The largest fee paid for an invoice, in freemans, or `None` to
+	/// disable the policy
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_max_invoice_fee(&self, max_invoice_fee: Option<u64>) {
+		let mut lock = self.max_invoice_fee.lock();
+		*lock = max_invoice_fee;
+	}
+
+	/// Set the one-shot token that allows a single call to
+	/// [`init_wallet_from_env`](struct.Owner.html#method.init_wallet_from_env). Without
+	/// one, provisioning is disabled.
+	///
+	/// # Arguments
+	/// * `token` - The provisioning token, or `None` to disable provisioning
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_provisioning_token(&self, token: Option<ZeroingString>) {
+		let mut lock = self.provisioning_token.lock();
+		*lock = token;
+	}
+
+	/// Set whether [`set_output_status`](struct.Owner.html#method.set_output_status) may be
+	/// used to override the status of outputs by hand. Disabled by default.
+	///
+	/// # Arguments
+	/// * `allow` - Whether to allow overrides
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_allow_output_status_override(&self, allow: bool) {
+		let mut lock = self.allow_output_status_override.lock();
+		*lock = allow;
+	}
+
+	/// Set whether webhooks may be queued with [`queue_job`](struct.Owner.html#method.queue_job),
+	/// and callbacks made by [`watch_tx_finality`](struct.Owner.html#method.watch_tx_finality).
+	/// Allowed by default, in wallets built with the `http` feature.
+	///
+	/// # Arguments
+	/// * `allow` - Whether to allow webhooks
+	/// # Returns
+	/// * Nothing
+
+	pub fn set_allow_webhooks(&self, allow: bool) {
+		let mut lock = self.allow_webhooks.lock();
+		*lock = allow;
+	}
+
+	/// Whether webhooks are allowed, and can be posted by this build
+	fn webhooks_enabled(&self) -> bool {
+		HTTP_SUPPORT && *self.allow_webhooks.lock()
+	}
+
+	/// Raise the args' minimum confirmations to the configured reorg-safe depth,
+	/// unless the caller has explicitly overridden the policy
+	fn apply_reorg_safety(&self, args: &mut InitTxArgs) {
+		if args.override_reorg_safety.unwrap_or(false) {
+			return;
+		}
+		if let Some(min) = *self.min_reorg_safe_confirmations.lock() {
+			if args.minimum_confirmations < min {
+				debug!(
+					"Raising minimum confirmations from {} to reorg-safe depth {}",
+					args.minimum_confirmations, min
+				);
+				args.minimum_confirmations = min;
+			}
+		}
+	}
+
+	/// Lower the args' largest invoice fee to the configured one, if that's
+	/// lower or none was given
+	fn apply_invoice_fee_policy(&self, args: &mut InitTxArgs) {
+		if let Some(max) = *self.max_invoice_fee.lock() {
+			args.max_invoice_fee = Some(args.max_invoice_fee.map_or(max, |m| m.min(max)));
+		}
+	}
+
 	/// Returns a list of accounts stored in the wallet (i.e. mappings between
 	/// user-specified labels and BIP32 derivation paths.
 	/// # Arguments
@@ -339,34 +507,26 @@ where
 		owner::set_active_account(&mut **w, label)
 	}
 
-	/// Returns a list of outputs from the active account in the wallet.
+	/// Freezes an account, blocking all spends from it until it's unfrozen, e.g. to place a
+	/// compliance hold without shutting down the wallet. Spends are refused when the account's
+	/// coins are selected, with an `AccountFrozen` error, so this covers sends, invoice payments,
+	/// estimates and templates alike. The account can still receive, and be queried.
+	///
+	/// The freeze is kept in the wallet, and recorded along with its reason in the audit trail
+	/// returned by [`retrieve_audit_log`](struct.Owner.html#method.retrieve_audit_log).
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `include_spent` - If `true`, outputs that have been marked as 'spent'
-	/// in the wallet will be returned. If `false`, spent outputs will omitted
-	/// from the results.
-	/// * `refresh_from_node` - If true, the wallet will attempt to contact
-	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
-	/// provided during wallet instantiation). If `false`, the results will
-	/// contain output information that may be out-of-date (from the last time
-	/// the wallet's output set was refreshed against the node).
-	/// Note this setting is ignored if the updater process is running via a call to
-	/// [`start_updater`](struct.Owner.html#method.start_updater)
-	/// * `tx_id` - If `Some(i)`, only return the outputs associated with
-	/// the transaction log entry of id `i`.
+	/// * `label` - The human readable label of the account, as listed by
+	/// [`accounts`](struct.Owner.html#method.accounts).
+	/// * `reason` - Why the account is frozen, kept in the audit trail. Can't be empty.
 	///
 	/// # Returns
-	/// * `(bool, Vec<OutputCommitMapping>)` - A tuple:
-	/// * The first `bool` element indicates whether the data was successfully
-	/// refreshed from the node (note this may be false even if the `refresh_from_node`
-	/// argument was set to `true`.
-	/// * The second element contains a vector of
-	/// [OutputCommitMapping](../epic_wallet_libwallet/types/struct.OutputCommitMapping.html)
-	/// of which each element is a mapping between the wallet's internal
-	/// [OutputData](../epic_wallet_libwallet/types/struct.Output.html)
-	/// and the Output commitment as identified in the chain's UTXO set
+	/// * Ok with the account's [`AcctPathMapping`](../epic_wallet_libwallet/types/struct.AcctPathMapping.html),
+	/// now frozen
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as when the account is already frozen.
 	///
 	/// # Example
 	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
@@ -374,69 +534,38 @@ where
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
 	/// let api_owner = Owner::new(wallet.clone());
-	/// let show_spent = false;
-	/// let update_from_node = true;
-	/// let tx_id = None;
 	///
-	/// let result = api_owner.retrieve_outputs(None, show_spent, update_from_node, tx_id);
+	/// let result = api_owner.freeze_account(None, "default", "compliance hold");
 	///
-	/// if let Ok((was_updated, output_mappings)) = result {
-	///		//...
+	/// if let Ok(account) = result {
+	///		assert!(account.frozen);
 	/// }
 	/// ```
 
-	pub fn retrieve_outputs(
+	pub fn freeze_account(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		include_spent: bool,
-		refresh_from_node: bool,
-		show_full_history: bool,
-		tx_id: Option<u32>,
-	) -> Result<(bool, Vec<OutputCommitMapping>), Error> {
-		let tx = {
-			let t = self.status_tx.lock();
-			t.clone()
-		};
-		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
-			true => false,
-			false => refresh_from_node,
-		};
-		owner::retrieve_outputs(
-			self.wallet_inst.clone(),
-			keychain_mask,
-			&tx,
-			include_spent,
-			refresh_from_node,
-			show_full_history,
-			tx_id,
-		)
+		label: &str,
+		reason: &str,
+	) -> Result<AcctPathMapping, Error> {
+		owner::set_account_frozen(self.wallet_inst.clone(), keychain_mask, label, true, reason)
 	}
 
-	/// Returns a list of [Transaction Log Entries](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
-	/// from the active account in the wallet.
+	/// Unfreezes an account frozen by [`freeze_account`](struct.Owner.html#method.freeze_account),
+	/// allowing spends from it again. The change is recorded along with its reason in the audit
+	/// trail.
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `refresh_from_node` - If true, the wallet will attempt to contact
-	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
-	/// provided during wallet instantiation). If `false`, the results will
-	/// contain transaction information that may be out-of-date (from the last time
-	/// the wallet's output set was refreshed against the node).
-	/// Note this setting is ignored if the updater process is running via a call to
-	/// [`start_updater`](struct.Owner.html#method.start_updater)
-	/// * `tx_id` - If `Some(i)`, only return the transactions associated with
-	/// the transaction log entry of id `i`.
-	/// * `tx_slate_id` - If `Some(uuid)`, only return transactions associated with
-	/// the given [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html) uuid.
+	/// * `label` - The human readable label of the account.
+	/// * `reason` - Why the account is unfrozen, kept in the audit trail. Can't be empty.
 	///
 	/// # Returns
-	/// * `(bool, Vec<TxLogEntry)` - A tuple:
-	/// * The first `bool` element indicates whether the data was successfully
-	/// refreshed from the node (note this may be false even if the `refresh_from_node`
-	/// argument was set to `true`.
-	/// * The second element contains the set of retrieved
-	/// [TxLogEntries](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// * Ok with the account's [`AcctPathMapping`](../epic_wallet_libwallet/types/struct.AcctPathMapping.html),
+	/// no longer frozen
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as when the account isn't frozen.
 	///
 	/// # Example
 	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
@@ -444,252 +573,3003 @@ where
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
 	/// let api_owner = Owner::new(wallet.clone());
-	/// let update_from_node = true;
-	/// let tx_id = None;
-	/// let tx_slate_id = None;
 	///
-	/// // Return all TxLogEntries
-	/// let result = api_owner.retrieve_txs(None, update_from_node, tx_id, tx_slate_id);
+	/// let result = api_owner.freeze_account(None, "default", "compliance hold");
 	///
-	/// if let Ok((was_updated, tx_log_entries)) = result {
-	///		//...
+	/// if let Ok(_) = result {
+	///		let result = api_owner.unfreeze_account(None, "default", "hold lifted");
 	/// }
 	/// ```
 
-	pub fn retrieve_txs(
+	pub fn unfreeze_account(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		refresh_from_node: bool,
-		tx_id: Option<u32>,
-		tx_slate_id: Option<Uuid>,
-	) -> Result<(bool, Vec<TxLogEntry>), Error> {
-		let tx = {
-			let t = self.status_tx.lock();
-			t.clone()
-		};
-		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
-			true => false,
-			false => refresh_from_node,
-		};
-		let mut res = owner::retrieve_txs(
+		label: &str,
+		reason: &str,
+	) -> Result<AcctPathMapping, Error> {
+		owner::set_account_frozen(
 			self.wallet_inst.clone(),
 			keychain_mask,
-			&tx,
-			refresh_from_node,
-			tx_id,
-			tx_slate_id,
-		)?;
-		if self.doctest_mode {
-			res.1 = res
-				.1
-				.into_iter()
-				.map(|mut t| {
-					t.confirmation_ts = Some(Utc.ymd(2019, 1, 15).and_hms(16, 1, 26));
-					t.creation_ts = Utc.ymd(2019, 1, 15).and_hms(16, 1, 26);
-					t
-				})
-				.collect();
-		}
-		Ok(res)
+			label,
+			false,
+			reason,
+		)
 	}
 
-	/// Returns summary information from the active account in the wallet.
+	/// Exports the public key material of an account, the wallet's nearest equivalent to an
+	/// extended public key, so an auditor can check the wallet's key usage without being given
+	/// its seed. The outputs of the account are keyed by the children of the exported path.
+	///
+	/// Switch commitments tie each output's commitment to its private key, so the commitments
+	/// the account's outputs should have can't be derived from the export alone. They're given
+	/// by [`derive_expected_commitments`](struct.Owner.html#method.derive_expected_commitments)
+	/// instead.
+	///
+	/// Nothing exported can spend funds, but it lets whoever holds it pick out and link the
+	/// account's outputs on chain, so the caller must accept the warning in
+	/// [`ACCOUNT_KEY_EXPORT_WARNING`](../epic_wallet_libwallet/types/constant.ACCOUNT_KEY_EXPORT_WARNING.html)
+	/// by passing `accept_warning`.
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `refresh_from_node` - If true, the wallet will attempt to contact
-	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
-	/// provided during wallet instantiation). If `false`, the results will
-	/// contain transaction information that may be out-of-date (from the last time
-	/// the wallet's output set was refreshed against the node).
-	/// Note this setting is ignored if the updater process is running via a call to
-	/// [`start_updater`](struct.Owner.html#method.start_updater)
-	/// * `minimum_confirmations` - The minimum number of confirmations an output
-	/// should have before it's included in the 'amount_currently_spendable' total
+	/// * `label` - The human readable label of the account.
+	/// * `accept_warning` - Must be `true`, to show the warning has been read.
 	///
 	/// # Returns
-	/// * (`bool`, [`WalletInfo`](../epic_wallet_libwallet/types/struct.WalletInfo.html)) - A tuple:
-	/// * The first `bool` element indicates whether the data was successfully
-	/// refreshed from the node (note this may be false even if the `refresh_from_node`
-	/// argument was set to `true`.
-	/// * The second element contains the Summary [`WalletInfo`](../epic_wallet_libwallet/types/struct.WalletInfo.html)
+	/// * Ok with the account's [`AccountKeyExport`](../epic_wallet_libwallet/api_impl/types/struct.AccountKeyExport.html)
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as when the warning isn't accepted or the account doesn't exist.
 	///
 	/// # Example
 	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
 	/// ```
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
-	/// let mut api_owner = Owner::new(wallet.clone());
-	/// let update_from_node = true;
-	/// let minimum_confirmations=10;
+	/// let api_owner = Owner::new(wallet.clone());
 	///
-	/// // Return summary info for active account
-	/// let result = api_owner.retrieve_summary_info(None, update_from_node, minimum_confirmations);
+	/// let result = api_owner.export_account_key(None, "default", true);
 	///
-	/// if let Ok((was_updated, summary_info)) = result {
+	/// if let Ok(export) = result {
 	///		//...
 	/// }
 	/// ```
 
-	pub fn retrieve_summary_info(
+	pub fn export_account_key(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		refresh_from_node: bool,
-		minimum_confirmations: u64,
-	) -> Result<(bool, WalletInfo), Error> {
-		let tx = {
-			let t = self.status_tx.lock();
-			t.clone()
-		};
-		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
-			true => false,
-			false => refresh_from_node,
-		};
-		owner::retrieve_summary_info(
-			self.wallet_inst.clone(),
-			keychain_mask,
-			&tx,
-			refresh_from_node,
-			minimum_confirmations,
-		)
+		label: &str,
+		accept_warning: bool,
+	) -> Result<AccountKeyExport, Error> {
+		if !accept_warning {
+			return Err(ErrorKind::AccountKeyExport(ACCOUNT_KEY_EXPORT_WARNING.to_owned()).into());
+		}
+		owner::export_account_key(self.wallet_inst.clone(), keychain_mask, label)
 	}
 
-	/// Initiates a new transaction as the sender, creating a new
-	/// [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html) object containing
-	/// the sender's inputs, change outputs, and public signature data. This slate can
-	/// then be sent to the recipient to continue the transaction via the
-	/// [Foreign API's `receive_tx`](struct.Foreign.html#method.receive_tx) method.
-	///
-	/// When a transaction is created, the wallet must also lock inputs (and create unconfirmed
-	/// outputs) corresponding to the transaction created in the slate, so that the wallet doesn't
-	/// attempt to re-spend outputs that are already included in a transaction before the transaction
-	/// is confirmed. This method also returns a function that will perform that locking, and it is
-	/// up to the caller to decide the best time to call the lock function
-	/// (via the [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs) method).
-	/// If the exchange method is intended to be synchronous (such as via a direct http call,)
-	/// then the lock call can wait until the response is confirmed. If it is asynchronous, (such
-	/// as via file transfer,) the lock call should happen immediately (before the file is sent
-	/// to the recipient).
+	/// Derives the commitments outputs of an account would have, for each of a range of child
+	/// indices under the path given by
+	/// [`export_account_key`](struct.Owner.html#method.export_account_key) and each of the given
+	/// values. An auditor can match them against the outputs on chain to check the wallet's
+	/// outputs are all derived from its seed, and that it reports the outputs it holds. At most
+	/// 1000 commitments are derived at once.
 	///
-	/// If the `send_args` [`InitTxSendArgs`](../epic_wallet_libwallet/types/struct.InitTxSendArgs.html),
-	/// of the [`args`](../epic_wallet_libwallet/types/struct.InitTxArgs.html), field is Some, this
-	/// function will attempt to perform a synchronous send to the recipient specified in the `dest`
-	/// field according to the `method` field, and will also finalize and post the transaction if
-	/// the `finalize` field is set.
+	/// The caller must accept the same warning as for
+	/// [`export_account_key`](struct.Owner.html#method.export_account_key).
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
-	/// transaction initialization arguments. See struct documentation for further detail.
+	/// * `label` - The human readable label of the account.
+	/// * `from_index` - The first child index to derive commitments for.
+	/// * `count` - The number of child indices to derive commitments for.
+	/// * `values` - The values to derive commitments for, at each child index.
+	/// * `accept_warning` - Must be `true`, to show the warning has been read.
 	///
 	/// # Returns
-	/// * a result containing:
-	/// * The transaction [Slate](../epic_wallet_libwallet/slate/struct.Slate.html),
-	/// which can be forwarded to the recieving party by any means. Once the caller is relatively
-	/// certain that the transaction has been sent to the recipient, the associated wallet
-	/// transaction outputs should be locked via a call to
-	/// [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs). This must be called before calling
-	/// [`finalize_tx`](struct.Owner.html#method.finalize_tx).
+	/// * Ok with a vector of [`ExpectedCommitment`](../epic_wallet_libwallet/api_impl/types/struct.ExpectedCommitment.html),
+	/// by child index then value, each noting whether the wallet holds an output with it
 	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
 	///
-	/// # Remarks
-	///
-	/// * This method requires an active connection to a node, and will fail with error if a node
-	/// cannot be contacted to refresh output statuses.
-	/// * This method will store a partially completed transaction in the wallet's transaction log,
-	/// which will be updated on the corresponding call to [`finalize_tx`](struct.Owner.html#method.finalize_tx).
-	///
 	/// # Example
-	/// Set up as in [new](struct.Owner.html#method.new) method above.
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
 	/// ```
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
-	/// let mut api_owner = Owner::new(wallet.clone());
-	/// // Attempt to create a transaction using the 'default' account
-	/// let args = InitTxArgs {
-	/// 	src_acct_name: None,
-	/// 	amount: 2_000_000_000,
-	/// 	minimum_confirmations: 2,
-	/// 	max_outputs: 500,
-	/// 	num_change_outputs: 1,
-	/// 	selection_strategy_is_use_all: false,
-	/// 	message: Some("Have some Epics. Love, Yeastplume".to_owned()),
-	/// 	..Default::default()
-	/// };
-	/// let result = api_owner.init_send_tx(
-	/// 	None,
-	/// 	args,
-	/// );
+	/// let api_owner = Owner::new(wallet.clone());
 	///
-	/// if let Ok(slate) = result {
-	/// 	// Send slate somehow
-	/// 	// ...
+	/// let result = api_owner.derive_expected_commitments(None, "default", 0, 10, &[100000000], true);
+	///
+	/// if let Ok(commits) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn derive_expected_commitments(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		label: &str,
+		from_index: u32,
+		count: u32,
+		values: &[u64],
+		accept_warning: bool,
+	) -> Result<Vec<ExpectedCommitment>, Error> {
+		if !accept_warning {
+			return Err(ErrorKind::AccountKeyExport(ACCOUNT_KEY_EXPORT_WARNING.to_owned()).into());
+		}
+		owner::derive_expected_commitments(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			label,
+			from_index,
+			count,
+			values,
+		)
+	}
+
+	/// Attempts to rewind the range proof of an output with the wallet's keys, recovering the
+	/// output's value and key if it belongs to the wallet. Useful in support cases and to recover
+	/// an individual output found on chain without a full
+	/// [`scan`](struct.Owner.html#method.scan). Nothing is changed in the wallet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `commit` - Commitment of the output, in hex.
+	/// * `proof` - Range proof of the output, in hex, as given by the node's output API.
+	///
+	/// # Returns
+	/// * Ok with a [`RewoundOutput`](../epic_wallet_libwallet/api_impl/types/struct.RewoundOutput.html),
+	/// giving the value, key path and account of the output if it's the wallet's, and the wallet's
+	/// record of it if it has one
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as the commitment or proof not being valid hex.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.rewind_rangeproof(
+	/// 	None,
+	/// 	"089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+	/// 	"6a9e4ab7ea6e55a9a1e8bd1b5c2d5a73b0d3eb5e6b4b1c8f6b4b2a4d4c0a6b1c",
+	/// );
+	///
+	/// if let Ok(rewound) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn rewind_rangeproof(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		commit: &str,
+		proof: &str,
+	) -> Result<RewoundOutput, Error> {
+		owner::rewind_rangeproof(self.wallet_inst.clone(), keychain_mask, commit, proof)
+	}
+
+	/// Imports an output the wallet is missing, such as after losing its database, given the
+	/// output's key path and value, so it can be spent without a full
+	/// [`scan`](struct.Owner.html#method.scan). The output's commitment is worked out from the
+	/// wallet's keys and must be found unspent on chain at the given height before it's added.
+	/// It's recorded as received, or as a coinbase reward, in the transaction log, and its account
+	/// is restored if the wallet doesn't have it.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `key_path` - Derivation path of the output's key, such as `m/0/0/4`, as given by
+	/// [`rewind_rangeproof`](struct.Owner.html#method.rewind_rangeproof).
+	/// * `value` - Value of the output, in nanoepics.
+	/// * `height` - Height of the block the output was created in.
+	///
+	/// # Returns
+	/// * Ok with the imported [`OutputData`](../epic_wallet_libwallet/types/struct.OutputData.html)
+	/// if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as the output already being in the wallet, or no unspent output with that key and
+	/// value being on chain at that height.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.import_output(None, "m/0/0/4", 1457920000, 5);
+	///
+	/// if let Ok(output) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn import_output(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		key_path: &str,
+		value: u64,
+		height: u64,
+	) -> Result<OutputData, Error> {
+		owner::import_output(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			key_path,
+			value,
+			height,
+		)
+	}
+
+	/// Exports the wallet's accounts and unspent outputs, as of the last block it scanned, so a
+	/// new instance of the same wallet can be bootstrapped with
+	/// [`import_wallet_state`](struct.Owner.html#method.import_wallet_state) instead of scanning
+	/// the whole chain, such as when migrating to another server. The export is signed with the
+	/// wallet's payment proof address, which only another wallet with the same seed has.
+	///
+	/// The export lists the wallet's outputs and their values, so should be kept private.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with the signed
+	/// [`WalletStateExport`](../epic_wallet_libwallet/api_impl/types/struct.WalletStateExport.html)
+	/// if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as the wallet not having been scanned against the chain yet.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.export_wallet_state(None);
+	///
+	/// if let Ok(export) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn export_wallet_state(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<WalletStateExport, Error> {
+		owner::export_wallet_state(self.wallet_inst.clone(), keychain_mask)
+	}
+
+	/// Bootstraps a new wallet from a state export of another instance with the same seed, as
+	/// made by [`export_wallet_state`](struct.Owner.html#method.export_wallet_state). The export's
+	/// signature must verify against this wallet's own payment proof address, and each exported
+	/// output is only added once it's found unspent on chain at its height. The wallet then
+	/// carries on scanning from the export's height, rather than restoring from the whole chain.
+	///
+	/// State can only be imported into a wallet that doesn't have any outputs yet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `export` - The signed state export.
+	///
+	/// # Returns
+	/// * Ok with a
+	/// [`WalletStateImport`](../epic_wallet_libwallet/api_impl/types/struct.WalletStateImport.html)
+	/// listing the outputs added, and those not found on chain, if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as the signature not verifying, the export being from a wallet with a different seed,
+	/// or the wallet already having outputs.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.export_wallet_state(None);
+	///
+	/// if let Ok(export) = result {
+	///		// on the new instance
+	///		let result = api_owner.import_wallet_state(None, &export);
+	/// }
+	/// ```
+
+	pub fn import_wallet_state(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		export: &WalletStateExport,
+	) -> Result<WalletStateImport, Error> {
+		owner::import_wallet_state(self.wallet_inst.clone(), keychain_mask, export)
+	}
+
+	/// Imports the unspent outputs of another wallet with the same seed, such as grin-wallet or
+	/// another fork of this wallet, from the CSV it exported, so they can be spent without a full
+	/// [`scan`](struct.Owner.html#method.scan). Each output is found on chain by its commitment,
+	/// worked out from its key id and value if the CSV doesn't have one, and is only added if
+	/// it's unspent and its range proof rewinds with this wallet's keys.
+	///
+	/// Columns are matched by header, ignoring case, spaces and punctuation, so both the field
+	/// names of [`retrieve_outputs`](struct.Owner.html#method.retrieve_outputs) (`commit`,
+	/// `key_id`, `value`, `height`, `status`) and the headings of the `outputs` table
+	/// (`Output Commitment`, `Block Height`) are understood. The CSV needs a commitment column,
+	/// or key id and value columns; key ids can be given as hex or as key paths such as
+	/// `m/0/0/4`. Outputs with a status other than unspent or locked are skipped.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `csv` - The CSV text.
+	/// * `units` - Units of amounts in the CSV that aren't followed by their own, e.g.
+	/// `Denomination::Freeman` for values taken from the API.
+	///
+	/// # Returns
+	/// * Ok with a [`CsvImport`](../epic_wallet_libwallet/api_impl/types/struct.CsvImport.html)
+	/// of the outputs added, and the rows skipped with the reason why, if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as the CSV not having the columns needed.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	/// use epic_wallet_libwallet::Denomination;
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let csv = "key_id,value,height\nm/0/0/4,14.5792,5\n";
+	/// let result = api_owner.import_outputs_csv(None, csv, Denomination::Epic);
+	///
+	/// if let Ok(import) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn import_outputs_csv(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		csv: &str,
+		units: Denomination,
+	) -> Result<CsvImport<OutputData>, Error> {
+		owner::import_outputs_csv(self.wallet_inst.clone(), keychain_mask, csv, units)
+	}
+
+	/// Imports the transaction log of another wallet with the same seed, such as grin-wallet or
+	/// another fork of this wallet, from the CSV it exported, into the active account. Entries
+	/// the wallet made when restoring outputs, whether by
+	/// [`import_outputs_csv`](struct.Owner.html#method.import_outputs_csv) or a
+	/// [`scan`](struct.Owner.html#method.scan), are filled in from the rows matching them rather
+	/// than added again, so outputs are best imported first.
+	///
+	/// Columns are matched as for outputs, so both the field names of
+	/// [`retrieve_txs`](struct.Owner.html#method.retrieve_txs) and the headings of the `txs` table
+	/// are understood. The type, creation time, confirmed, amount credited and amount debited
+	/// columns are needed. Transactions that are neither confirmed nor cancelled are skipped, as
+	/// are those already in the wallet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `csv` - The CSV text.
+	/// * `units` - Units of amounts in the CSV that aren't followed by their own.
+	///
+	/// # Returns
+	/// * Ok with a [`CsvImport`](../epic_wallet_libwallet/api_impl/types/struct.CsvImport.html)
+	/// of the transaction log entries added or filled in, and the rows skipped with the reason
+	/// why, if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as the CSV not having the columns needed.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	/// use epic_wallet_libwallet::Denomination;
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let csv = "Type,Creation Time,Confirmed?,Amount Credited,Amount Debited\n\
+	///            Received Tx,2020-01-02 03:04:05,true,1.5,0.0\n";
+	/// let result = api_owner.import_txs_csv(None, csv, Denomination::Epic);
+	///
+	/// if let Ok(import) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn import_txs_csv(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		csv: &str,
+		units: Denomination,
+	) -> Result<CsvImport<TxLogEntry>, Error> {
+		owner::import_txs_csv(self.wallet_inst.clone(), keychain_mask, csv, units)
+	}
+
+	/// Returns a list of outputs from the active account in the wallet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `include_spent` - If `true`, outputs that have been marked as 'spent'
+	/// in the wallet will be returned. If `false`, spent outputs will omitted
+	/// from the results.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain output information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `tx_id` - If `Some(i)`, only return the outputs associated with
+	/// the transaction log entry of id `i`.
+	///
+	/// # Returns
+	/// * `(bool, Vec<OutputCommitMapping>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains a vector of
+	/// [OutputCommitMapping](../epic_wallet_libwallet/types/struct.OutputCommitMapping.html)
+	/// of which each element is a mapping between the wallet's internal
+	/// [OutputData](../epic_wallet_libwallet/types/struct.Output.html)
+	/// and the Output commitment as identified in the chain's UTXO set
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let show_spent = false;
+	/// let update_from_node = true;
+	/// let tx_id = None;
+	///
+	/// let result = api_owner.retrieve_outputs(None, show_spent, update_from_node, tx_id);
+	///
+	/// if let Ok((was_updated, output_mappings)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_outputs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		include_spent: bool,
+		refresh_from_node: bool,
+		show_full_history: bool,
+		tx_id: Option<u32>,
+	) -> Result<(bool, Vec<OutputCommitMapping>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_outputs(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			include_spent,
+			refresh_from_node,
+			show_full_history,
+			tx_id,
+		)
+	}
+
+	/// Returns a page of outputs from the active account in the wallet. Unlike
+	/// [`retrieve_outputs`](struct.Owner.html#method.retrieve_outputs), outputs are read from
+	/// the wallet as they are returned, so a wallet with a very large number of outputs can be
+	/// walked through without loading all of them at once.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `include_spent` - If `true`, outputs that have been marked as 'spent'
+	/// in the wallet will be returned. If `false`, spent outputs will omitted
+	/// from the results.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain output information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `cursor` - `None` to retrieve the first page, or the `next_cursor` of the previous
+	/// page to retrieve the one following it.
+	/// * `limit` - The maximum number of outputs to return, which must be greater than 0.
+	///
+	/// # Returns
+	/// * `(bool, OutputPage)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains an [OutputPage](../epic_wallet_libwallet/api_impl/types/struct.OutputPage.html)
+	/// holding the retrieved outputs, and the cursor of the next page if there are more outputs.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Retrieve the first 100 unspent outputs
+	/// let result = api_owner.retrieve_outputs_page(None, false, true, None, 100);
+	///
+	/// if let Ok((was_updated, page)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_outputs_page(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		include_spent: bool,
+		refresh_from_node: bool,
+		cursor: Option<String>,
+		limit: usize,
+	) -> Result<(bool, OutputPage), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_outputs_page(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			include_spent,
+			refresh_from_node,
+			cursor,
+			limit,
+		)
+	}
+
+	/// Returns every output of the active account, including spent and deleted ones, each
+	/// grouped with its spend event and the states it was recorded in before its latest one.
+	/// This allows reconstructing how each output moved through the wallet: the transaction
+	/// that created it, the transaction that locked and spent it (or cancelled or reverted it)
+	/// and the height at which the spend happened.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain output information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `tx_id` - If `Some(i)`, only return the outputs the transaction log entry of id `i`
+	/// created, locked, spent or deleted, including outputs unlocked again by its cancellation.
+	///
+	/// # Returns
+	/// * `(bool, Vec<OutputLineage>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains an
+	/// [OutputLineage](../epic_wallet_libwallet/api_impl/types/struct.OutputLineage.html)
+	/// for each output, with the tx log entry that spent or deleted it, the spend height
+	/// and its earlier states, oldest first
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.retrieve_output_lineage(None, true, None);
+	///
+	/// if let Ok((was_updated, lineage)) = result {
+	///		let spent = lineage.iter().filter(|l| l.spent_by.is_some()).count();
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_output_lineage(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+	) -> Result<(bool, Vec<OutputLineage>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::retrieve_output_lineage(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			tx_id,
+		)?;
+		if self.doctest_mode {
+			for l in res.1.iter_mut() {
+				l.spent_by = l.spent_by.take().map(doctest_tx_log_entry);
+			}
+		}
+		Ok(res)
+	}
+
+	/// Returns the locked outputs of the active account, each with the transaction log entry
+	/// that locked it and since when. Outputs are locked while a transaction spending them is
+	/// in progress, and a lock left behind by a transaction that will never complete keeps
+	/// funds unavailable, showing up as insufficient funds when sending.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain output information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	///
+	/// # Returns
+	/// * `(bool, Vec<LockedOutput>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains a
+	/// [LockedOutput](../epic_wallet_libwallet/api_impl/types/struct.LockedOutput.html)
+	/// for each locked output, with the locking tx log entry and whether the lock is stale
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.get_locked_outputs(None, true);
+	///
+	/// if let Ok((was_updated, locked)) = result {
+	///		let stale = locked.iter().filter(|l| l.stale).count();
+	///		//...
+	/// }
+	/// ```
+
+	pub fn get_locked_outputs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+	) -> Result<(bool, Vec<LockedOutput>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::get_locked_outputs(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+		)?;
+		if self.doctest_mode {
+			for l in res.1.iter_mut() {
+				l.locked_by = l.locked_by.take().map(doctest_tx_log_entry);
+				l.locked_since = l.locked_by.as_ref().map(|t| t.creation_ts);
+			}
+		}
+		Ok(res)
+	}
+
+	/// Releases the lock on an output, for clearing a stale lock without cancelling a
+	/// transaction, such as one left by a transaction that is gone from the log. Before
+	/// unlocking, the node is asked whether the output is still unspent on chain, so a node
+	/// must be reachable. A lock held by a transaction that this wallet posted is never
+	/// released, and one held by any other pending transaction only with `include_pending`;
+	/// [`cancel_tx`](struct.Owner.html#method.cancel_tx) is the usual way to release those.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `commit` - Commitment of the locked output, in hex.
+	/// * `include_pending` - Also release a lock held by a pending transaction that wasn't
+	/// posted. That transaction can then no longer be completed.
+	///
+	/// # Returns
+	/// * Ok([LockedOutput](../epic_wallet_libwallet/api_impl/types/struct.LockedOutput.html))
+	/// describing the lock that was released, if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as the output not being locked or the unlock being unsafe.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.force_unlock(
+	/// 	None,
+	/// 	"089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+	/// 	false,
+	/// );
+	///
+	/// if let Ok(unlocked) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn force_unlock(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		commit: &str,
+		include_pending: bool,
+	) -> Result<LockedOutput, Error> {
+		let mut res = owner::force_unlock_output(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			commit,
+			include_pending,
+		)?;
+		if self.doctest_mode {
+			res.locked_by = res.locked_by.take().map(doctest_tx_log_entry);
+			res.locked_since = res.locked_by.as_ref().map(|t| t.creation_ts);
+		}
+		Ok(res)
+	}
+
+	/// Overrides the status of an output of the active account, for support staff fixing a
+	/// rare divergence between the wallet and the chain, such as an output stuck `Locked`,
+	/// without editing the wallet database by hand. Nothing is checked with the node, so
+	/// [`force_unlock`](struct.Owner.html#method.force_unlock) or a
+	/// [`scan`](struct.Owner.html#method.scan) should be preferred where they apply.
+	///
+	/// Overrides must be enabled with
+	/// [`set_allow_output_status_override`](struct.Owner.html#method.set_allow_output_status_override)
+	/// (`owner_api_allow_output_status_override` in the wallet configuration). Each one is
+	/// recorded, along with its reason, in the audit trail returned by
+	/// [`retrieve_audit_log`](struct.Owner.html#method.retrieve_audit_log).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `commit` - Commitment of the output, in hex.
+	/// * `status` - The new status: `Unconfirmed`, `Unspent`, `Locked` or `Spent`.
+	/// * `reason` - Why the status is overridden, kept in the audit trail. Can't be empty.
+	///
+	/// # Returns
+	/// * Ok with the updated [`OutputData`](../epic_wallet_libwallet/types/struct.OutputData.html)
+	/// if successful. Outputs set to `Spent` keep the height they were first seen spent at, or
+	/// get the wallet's last confirmed height, and outputs set to `Unconfirmed` or `Unspent` are
+	/// no longer held by a transaction.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as overrides not being enabled, no reason being given, or the output
+	/// not being in the account or already having the status.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// use libwallet::OutputStatus;
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// api_owner.set_allow_output_status_override(true);
+	///
+	/// let result = api_owner.set_output_status(
+	/// 	None,
+	/// 	"089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+	/// 	OutputStatus::Unspent,
+	/// 	"ticket 1234: lock left by a transaction missing from the log",
+	/// );
+	///
+	/// if let Ok(output) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn set_output_status(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		commit: &str,
+		status: OutputStatus,
+		reason: &str,
+	) -> Result<OutputData, Error> {
+		if !*self.allow_output_status_override.lock() {
+			return Err(ErrorKind::OutputStatusOverride(
+				"overriding output statuses is not enabled".to_owned(),
+			)
+			.into());
+		}
+		owner::set_output_status(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			commit,
+			status,
+			reason,
+		)
+	}
+
+	/// Returns the audit trail of changes made to the wallet by hand, such as those made by
+	/// [`set_output_status`](struct.Owner.html#method.set_output_status), oldest first.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with a vector of
+	/// [`AuditEntry`s](../epic_wallet_libwallet/types/struct.AuditEntry.html) if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.retrieve_audit_log(None);
+	///
+	/// if let Ok(entries) = result {
+	///		for e in entries {
+	///			println!("{} {} {} -> {}: {}", e.timestamp, e.target, e.before, e.after, e.reason);
+	///		}
+	/// }
+	/// ```
+
+	pub fn retrieve_audit_log(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Vec<AuditEntry>, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::retrieve_audit_log(&mut **w, keychain_mask)
+	}
+
+	/// Adds a tag, such as `cold` or `customer-funds`, to unspent outputs of the active account.
+	/// Tags group outputs within an account; setting `selection_tag` in the
+	/// [`InitTxArgs`](../epic_wallet_libwallet/api_impl/types/struct.InitTxArgs.html) of
+	/// [`init_send_tx`](struct.Owner.html#method.init_send_tx) restricts the inputs of a
+	/// transaction to outputs carrying that tag, and its change outputs are tagged the same way.
+	/// An output can carry any number of tags.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `commits` - Commitments of the outputs to tag, in hex.
+	/// * `tag` - The tag, of 1 to 32 characters and without whitespace.
+	///
+	/// # Returns
+	/// * Ok with a vector of the updated
+	/// [`OutputCommitMapping`s](../epic_wallet_libwallet/api_impl/types/struct.OutputCommitMapping.html),
+	/// if successful. Tagging an output that already carries the tag leaves it unchanged.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as an invalid tag or a commitment that isn't an unspent output of the
+	/// account. No output is tagged in that case.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.tag_outputs(
+	/// 	None,
+	/// 	vec!["089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e".to_owned()],
+	/// 	"cold",
+	/// );
+	///
+	/// if let Ok(outputs) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn tag_outputs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		commits: Vec<String>,
+		tag: &str,
+	) -> Result<Vec<OutputCommitMapping>, Error> {
+		owner::update_output_tags(self.wallet_inst.clone(), keychain_mask, &commits, tag, true)
+	}
+
+	/// Removes a tag from unspent outputs of the active account, as added by
+	/// [`tag_outputs`](struct.Owner.html#method.tag_outputs).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `commits` - Commitments of the outputs to untag, in hex.
+	/// * `tag` - The tag to remove.
+	///
+	/// # Returns
+	/// * Ok with a vector of the updated
+	/// [`OutputCommitMapping`s](../epic_wallet_libwallet/api_impl/types/struct.OutputCommitMapping.html),
+	/// if successful. Outputs not carrying the tag are left unchanged.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.untag_outputs(
+	/// 	None,
+	/// 	vec!["089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e".to_owned()],
+	/// 	"cold",
+	/// );
+	///
+	/// if let Ok(outputs) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn untag_outputs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		commits: Vec<String>,
+		tag: &str,
+	) -> Result<Vec<OutputCommitMapping>, Error> {
+		owner::update_output_tags(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&commits,
+			tag,
+			false,
+		)
+	}
+
+	/// Reserves an amount of the active account's balance for a payment that hasn't been built
+	/// yet, such as a pending withdrawal. While the reservation is held, transactions are only
+	/// created by [`init_send_tx`](struct.Owner.html#method.init_send_tx) and
+	/// [`process_invoice_tx`](struct.Owner.html#method.process_invoice_tx) if the account keeps
+	/// enough funds to cover it. To pay out the reservation itself, pass its id as
+	/// `reservation_id` in the
+	/// [`InitTxArgs`](../epic_wallet_libwallet/api_impl/types/struct.InitTxArgs.html); it is
+	/// released once the transaction is created.
+	///
+	/// Reservations are checked against the account's unspent outputs (other than immature
+	/// coinbase) and unconfirmed change, as last refreshed, and aren't tied to any output.
+	/// Calls are serialized on the wallet, so concurrent reservations can't overcommit the
+	/// account. Transaction fees aren't reserved, so reserve a little more than the payment
+	/// amount.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `amount` - The amount to reserve, in freemans.
+	/// * `label` - An optional label, such as the id of the withdrawal request.
+	///
+	/// # Returns
+	/// * Ok with the new [`Reservation`](../epic_wallet_libwallet/types/struct.Reservation.html)
+	/// if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as the account's unreserved funds not covering the amount.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.reserve_funds(None, 2_000_000_000, Some("withdrawal 17".to_owned()));
+	///
+	/// if let Ok(reservation) = result {
+	///		// pay it out later with `reservation_id: Some(reservation.id)`
+	/// }
+	/// ```
+
+	pub fn reserve_funds(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		amount: u64,
+		label: Option<String>,
+	) -> Result<Reservation, Error> {
+		owner::reserve_funds(self.wallet_inst.clone(), keychain_mask, amount, label)
+	}
+
+	/// Releases a reservation made with [`reserve_funds`](struct.Owner.html#method.reserve_funds)
+	/// without paying it out, such as when a withdrawal is abandoned.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `id` - The id of the reservation.
+	///
+	/// # Returns
+	/// * Ok with the released [`Reservation`](../epic_wallet_libwallet/types/struct.Reservation.html)
+	/// if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as the active account holding no such reservation.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// if let Ok(reservation) = api_owner.reserve_funds(None, 2_000_000_000, None) {
+	/// 	let result = api_owner.release_reservation(None, reservation.id);
+	/// }
+	/// ```
+
+	pub fn release_reservation(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		id: Uuid,
+	) -> Result<Reservation, Error> {
+		owner::release_reservation(self.wallet_inst.clone(), keychain_mask, &id)
+	}
+
+	/// Returns the reservations of the active account made with
+	/// [`reserve_funds`](struct.Owner.html#method.reserve_funds) that haven't been paid out or
+	/// released, oldest first.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with a vector of [`Reservation`s](../epic_wallet_libwallet/types/struct.Reservation.html)
+	/// if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.retrieve_reservations(None);
+	///
+	/// if let Ok(reservations) = result {
+	/// 	let reserved: u64 = reservations.iter().map(|r| r.amount).sum();
+	/// }
+	/// ```
+
+	pub fn retrieve_reservations(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Vec<Reservation>, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		// Test keychain mask, to keep API consistent
+		let _ = w.keychain(keychain_mask)?;
+		owner::retrieve_reservations(&mut **w)
+	}
+
+	/// Returns the foundation reward outputs built by this wallet, as
+	/// [`OutputCommitMapping`s](../epic_wallet_libwallet/api_impl/types/struct.OutputCommitMapping.html)
+	/// ordered by the height of the block that included them. Foundation rewards are only built
+	/// by a wallet configured as the foundation wallet (via `foundation_wallet` in its
+	/// configuration), so this list is empty for any other wallet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain output information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `start_height` - If `Some(h)`, only return rewards at or above height `h`
+	/// * `end_height` - If `Some(h)`, only return rewards at or below height `h`
+	///
+	/// # Returns
+	/// * `(bool, Vec<OutputCommitMapping>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the foundation reward outputs, spent or unspent
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Foundation rewards included in blocks 1000 to 2000
+	/// let result = api_owner.retrieve_foundation_rewards(None, true, Some(1000), Some(2000));
+	///
+	/// if let Ok((was_updated, rewards)) = result {
+	///		let total: u64 = rewards.iter().map(|m| m.output.value).sum();
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_foundation_rewards(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		start_height: Option<u64>,
+		end_height: Option<u64>,
+	) -> Result<(bool, Vec<OutputCommitMapping>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_foundation_rewards(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			start_height,
+			end_height,
+		)
+	}
+
+	/// Returns a list of [Transaction Log Entries](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// from the active account in the wallet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `tx_id` - If `Some(i)`, only return the transactions associated with
+	/// the transaction log entry of id `i`.
+	/// * `tx_slate_id` - If `Some(uuid)`, only return transactions associated with
+	/// the given [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html) uuid.
+	///
+	/// # Returns
+	/// * `(bool, Vec<TxLogEntry)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the set of retrieved
+	/// [TxLogEntries](../epic_wallet_libwallet/types/struct.TxLogEntry.html).
+	/// Confirmed entries include the height of the confirming block and the number of
+	/// confirmations as of the wallet's last refresh. The confirming block's hash is
+	/// looked up from the node (once per entry) when the data was refreshed.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let update_from_node = true;
+	/// let tx_id = None;
+	/// let tx_slate_id = None;
+	///
+	/// // Return all TxLogEntries
+	/// let result = api_owner.retrieve_txs(None, update_from_node, tx_id, tx_slate_id);
+	///
+	/// if let Ok((was_updated, tx_log_entries)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_txs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+		tx_slate_id: Option<Uuid>,
+	) -> Result<(bool, Vec<TxLogEntry>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::retrieve_txs(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			tx_id,
+			tx_slate_id,
+		)?;
+		if self.doctest_mode {
+			res.1 = res.1.into_iter().map(doctest_tx_log_entry).collect();
+		}
+		Ok(res)
+	}
+
+	/// Returns a page of [Transaction Log Entries](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// from the active account in the wallet, in the order they were created. Unlike
+	/// [`retrieve_txs`](struct.Owner.html#method.retrieve_txs), entries are read from the wallet
+	/// as they are returned, so a wallet with a very large transaction log can be walked through
+	/// without loading the whole log at once.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `cursor` - `None` to retrieve the first page, or the `next_cursor` of the previous
+	/// page to retrieve the one following it.
+	/// * `limit` - The maximum number of entries to return, which must be greater than 0.
+	///
+	/// # Returns
+	/// * `(bool, TxLogPage)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains a [TxLogPage](../epic_wallet_libwallet/api_impl/types/struct.TxLogPage.html)
+	/// holding the retrieved entries, and the cursor of the next page if there are more entries.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Walk through the whole transaction log, 100 entries at a time
+	/// let mut cursor = None;
+	/// loop {
+	///		let (_, page) = api_owner.retrieve_txs_page(None, false, cursor, 100).unwrap();
+	///		for tx in page.txs {
+	///			//...
+	///		}
+	///		match page.next_cursor {
+	///			Some(c) => cursor = Some(c),
+	///			None => break,
+	///		}
+	/// }
+	/// ```
+
+	pub fn retrieve_txs_page(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		cursor: Option<String>,
+		limit: usize,
+	) -> Result<(bool, TxLogPage), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::retrieve_txs_page(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			cursor,
+			limit,
+		)?;
+		if self.doctest_mode {
+			res.1.txs = res.1.txs.into_iter().map(doctest_tx_log_entry).collect();
+		}
+		Ok(res)
+	}
+
+	/// Returns the [Transaction Log Entries](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// of the active account created within a time range, filtered by the wallet rather than
+	/// the caller. Entries are returned in the order they were created, following their `seq`
+	/// rather than their timestamps, so the order holds even if the system clock was changed
+	/// between them.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `from` - If set, only entries created at or after this time are returned.
+	/// * `until` - If set, only entries created before this time are returned.
+	///
+	/// # Returns
+	/// * `(bool, Vec<TxLogEntry>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the entries created within the range.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, including if `from` is after `until`.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	/// use chrono::{Duration, Utc};
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Transactions of the last day
+	/// let since = Utc::now() - Duration::days(1);
+	/// let result = api_owner.retrieve_txs_by_time(None, true, Some(since), None);
+	///
+	/// if let Ok((was_updated, tx_log_entries)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_txs_by_time(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		from: Option<DateTime<Utc>>,
+		until: Option<DateTime<Utc>>,
+	) -> Result<(bool, Vec<TxLogEntry>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::retrieve_txs_by_time(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			from,
+			until,
+		)?;
+		if self.doctest_mode {
+			res.1 = res.1.into_iter().map(doctest_tx_log_entry).collect();
+		}
+		Ok(res)
+	}
+
+	/// Returns the [Transaction Log Entries](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// of the active account matching a [`TxFilter`](../epic_wallet_libwallet/tx_filter/enum.TxFilter.html),
+	/// evaluated by the wallet rather than the caller. Filters requiring a particular slate id or
+	/// transaction type are answered from the wallet's indexes, without reading every entry.
+	/// Entries are returned in the order they were created.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `filter` - The condition the entries have to meet, such as
+	/// `{"and": [{"type": "sent"}, {"amount_gt": "100000000"}]}` in JSON.
+	///
+	/// # Returns
+	/// * `(bool, Vec<TxLogEntry>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the entries matching the filter.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	/// use epic_wallet_libwallet::{TxFilter, TxLogEntryType};
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Unconfirmed sends of more than 1 epic
+	/// let filter = TxFilter::And(vec![
+	/// 	TxFilter::Type(TxLogEntryType::TxSent),
+	/// 	TxFilter::Confirmed(false),
+	/// 	TxFilter::AmountGt(100_000_000),
+	/// ]);
+	/// let result = api_owner.retrieve_txs_by_filter(None, true, &filter);
+	///
+	/// if let Ok((was_updated, tx_log_entries)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_txs_by_filter(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		filter: &TxFilter,
+	) -> Result<(bool, Vec<TxLogEntry>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::retrieve_txs_by_filter(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			filter,
+		)?;
+		if self.doctest_mode {
+			res.1 = res.1.into_iter().map(doctest_tx_log_entry).collect();
+		}
+		Ok(res)
+	}
+
+	/// Returns the sent transactions of the active account that a send of an amount to a
+	/// destination would likely repeat, e.g. after a client retried a send it didn't see succeed.
+	/// These are the ones sent to the same destination for the same amount that are either
+	/// unconfirmed or were created less than
+	/// [`DUPLICATE_PAYMENT_WINDOW_SECS`](../epic_wallet_libwallet/types/constant.DUPLICATE_PAYMENT_WINDOW_SECS.html)
+	/// ago. [`init_send_tx`](struct.Owner.html#method.init_send_tx) refuses a send with a
+	/// `destination` when there are any, unless `allow_duplicate` is set, so a client can use
+	/// this to ask the user before sending.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `amount` - The amount to be sent, in nanoepic, not including the fee.
+	/// * `destination` - Where the send is to be made, as given in the `destination` or
+	/// `send_args.dest` of its [`InitTxArgs`](../epic_wallet_libwallet/api_impl/types/struct.InitTxArgs.html).
+	///
+	/// # Returns
+	/// * Ok with the matching [Transaction Log Entries](../epic_wallet_libwallet/types/struct.TxLogEntry.html),
+	/// oldest first, empty if the send doesn't look like a repeat
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.check_duplicate_payment(None, 100_000_000, "http://192.168.0.2:3415");
+	///
+	/// if let Ok(earlier_sends) = result {
+	///		if !earlier_sends.is_empty() {
+	///			// ask before sending again
+	///		}
+	/// }
+	/// ```
+
+	pub fn check_duplicate_payment(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		amount: u64,
+		destination: &str,
+	) -> Result<Vec<TxLogEntry>, Error> {
+		let mut res = owner::check_duplicate_payment(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			amount,
+			destination,
+		)?;
+		if self.doctest_mode {
+			res = res.into_iter().map(doctest_tx_log_entry).collect();
+		}
+		Ok(res)
+	}
+
+	/// Returns the mining rewards received by the active account, as
+	/// [`MiningRewardSummary`s](../epic_wallet_libwallet/api_impl/types/struct.MiningRewardSummary.html)
+	/// totalling the confirmed coinbase rewards for each proof of work algorithm. The
+	/// algorithm of each rewarded block is requested from the node when the wallet is refreshed,
+	/// and rewards the node hasn't reported an algorithm for are totalled with a `pow_type`
+	/// of `None`.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain reward information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `pow_type` - If `Some(t)`, only return rewards for blocks mined with algorithm `t`
+	/// * `start_height` - If `Some(h)`, only include rewards at or above height `h`
+	/// * `end_height` - If `Some(h)`, only include rewards at or below height `h`
+	///
+	/// # Returns
+	/// * `(bool, Vec<MiningRewardSummary>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains one summary per proof of work algorithm
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Rewards for blocks 1000 to 2000, by algorithm
+	/// let result = api_owner.retrieve_mining_rewards(None, true, None, Some(1000), Some(2000));
+	///
+	/// if let Ok((was_updated, rewards)) = result {
+	///		for r in rewards {
+	///			//...
+	///		}
+	/// }
+	/// ```
+
+	pub fn retrieve_mining_rewards(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		pow_type: Option<PoWType>,
+		start_height: Option<u64>,
+		end_height: Option<u64>,
+	) -> Result<(bool, Vec<MiningRewardSummary>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_mining_rewards(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			pow_type,
+			start_height,
+			end_height,
+		)
+	}
+
+	/// Returns the coinbase outputs the wallet removed from the active account because they were
+	/// still unconfirmed more than the configured cleanup window (`unconfirmed_coinbase_cleanup_window`
+	/// blocks, 50 by default) after the block they were built for, typically because that block
+	/// lost a race with another block at the same height. Each removal is recorded in the
+	/// transaction log as a `RevertedCoinbase` entry.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation), removing any outputs that are now past the cleanup
+	/// window before returning.
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	///
+	/// # Returns
+	/// * `(bool, Vec<CleanedCoinbase>)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains a [CleanedCoinbase](../epic_wallet_libwallet/api_impl/types/struct.CleanedCoinbase.html)
+	/// for each removed output, with its tx log entry and the output as it was last stored
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.retrieve_cleaned_coinbase(None, true);
+	///
+	/// if let Ok((was_updated, cleaned)) = result {
+	///		let lost: u64 = cleaned.iter().map(|c| c.tx.amount_credited.freemans()).sum();
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_cleaned_coinbase(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+	) -> Result<(bool, Vec<CleanedCoinbase>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::retrieve_cleaned_coinbase(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+		)?;
+		if self.doctest_mode {
+			for c in res.1.iter_mut() {
+				c.tx = doctest_tx_log_entry(c.tx.clone());
+			}
+		}
+		Ok(res)
+	}
+
+	/// Returns summary information from the active account in the wallet.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `minimum_confirmations` - The minimum number of confirmations an output
+	/// should have before it's included in the 'amount_currently_spendable' total
+	///
+	/// # Returns
+	/// * (`bool`, [`WalletInfo`](../epic_wallet_libwallet/types/struct.WalletInfo.html)) - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the Summary [`WalletInfo`](../epic_wallet_libwallet/types/struct.WalletInfo.html)
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let update_from_node = true;
+	/// let minimum_confirmations=10;
+	///
+	/// // Return summary info for active account
+	/// let result = api_owner.retrieve_summary_info(None, update_from_node, minimum_confirmations);
+	///
+	/// if let Ok((was_updated, summary_info)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_summary_info(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		minimum_confirmations: u64,
+	) -> Result<(bool, WalletInfo), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_summary_info(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			minimum_confirmations,
+			false,
+		)
+	}
+
+	/// Returns summary information from the active account in the wallet, as
+	/// [`retrieve_summary_info`](struct.Owner.html#method.retrieve_summary_info) does, along
+	/// with a [`WalletInfoBreakdown`](../epic_wallet_libwallet/types/struct.WalletInfoBreakdown.html)
+	/// splitting the totals between mining rewards, foundation rewards and regular receipts, and
+	/// listing immature amounts by the height at which they become spendable.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `minimum_confirmations` - The minimum number of confirmations an output
+	/// should have before it's included in the 'amount_currently_spendable' total
+	///
+	/// # Returns
+	/// * (`bool`, [`WalletInfo`](../epic_wallet_libwallet/types/struct.WalletInfo.html)) - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the Summary [`WalletInfo`](../epic_wallet_libwallet/types/struct.WalletInfo.html),
+	/// with its `breakdown` filled in
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	///
+	/// // Return detailed summary info for active account
+	/// let result = api_owner.retrieve_detailed_summary_info(None, true, 10);
+	///
+	/// if let Ok((was_updated, summary_info)) = result {
+	///		let breakdown = summary_info.breakdown.unwrap();
+	///		//...
+	/// }
+	/// ```
+
+	pub fn retrieve_detailed_summary_info(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		minimum_confirmations: u64,
+	) -> Result<(bool, WalletInfo), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_summary_info(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			minimum_confirmations,
+			true,
+		)
+	}
+
+	/// Returns the confirmed balance of the active account at regular height intervals,
+	/// reconstructed from the wallet's stored outputs. This is intended for charting
+	/// balance over time without needing to replay the whole transaction log.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `from_height` - The first height to report the balance at
+	/// * `to_height` - The last height to report the balance at. If `None`, the wallet's
+	/// last confirmed height is used.
+	/// * `step` - The number of blocks between reported heights. The balance at `to_height`
+	/// is always included.
+	///
+	/// # Returns
+	/// * (`bool`, `Vec<`[`BalanceHistoryEntry`](../epic_wallet_libwallet/types/struct.BalanceHistoryEntry.html)`>`) - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element contains the balance at each height, in ascending height order
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	///
+	/// // Return the balance every 1000 blocks up to the current height
+	/// let result = api_owner.get_balance_history(None, true, 0, None, 1000);
+	///
+	/// if let Ok((was_updated, history)) = result {
+	///		//...
+	/// }
+	/// ```
+
+	pub fn get_balance_history(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		from_height: u64,
+		to_height: Option<u64>,
+		step: u64,
+	) -> Result<(bool, Vec<BalanceHistoryEntry>), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::get_balance_history(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			from_height,
+			to_height,
+			step,
+		)
+	}
+
+	/// Initiates a new transaction as the sender, creating a new
+	/// [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html) object containing
+	/// the sender's inputs, change outputs, and public signature data. This slate can
+	/// then be sent to the recipient to continue the transaction via the
+	/// [Foreign API's `receive_tx`](struct.Foreign.html#method.receive_tx) method.
+	///
+	/// When a transaction is created, the wallet must also lock inputs (and create unconfirmed
+	/// outputs) corresponding to the transaction created in the slate, so that the wallet doesn't
+	/// attempt to re-spend outputs that are already included in a transaction before the transaction
+	/// is confirmed. This method also returns a function that will perform that locking, and it is
+	/// up to the caller to decide the best time to call the lock function
+	/// (via the [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs) method).
+	/// If the exchange method is intended to be synchronous (such as via a direct http call,)
+	/// then the lock call can wait until the response is confirmed. If it is asynchronous, (such
+	/// as via file transfer,) the lock call should happen immediately (before the file is sent
+	/// to the recipient).
+	///
+	/// If the `send_args` [`InitTxSendArgs`](../epic_wallet_libwallet/types/struct.InitTxSendArgs.html),
+	/// of the [`args`](../epic_wallet_libwallet/types/struct.InitTxArgs.html), field is Some, this
+	/// function will attempt to perform a synchronous send to the recipient specified in the `dest`
+	/// field according to the `method` field, and will also finalize and post the transaction if
+	/// the `finalize` field is set.
+	///
+	/// If `wait_for_maturity` is also set, a send the wallet can't fund yet, but will be able to
+	/// once enough of its coinbase outputs mature within that many blocks, is queued rather than
+	/// refused. The call then fails with `SendQueued`, naming the queued send, which is made by
+	/// [`process_send_queue`](struct.Owner.html#method.process_send_queue) once the funds are
+	/// spendable.
+	///
+	/// A send with a `destination`, or `send_args`, fails with `DuplicatePayment` if it sends
+	/// the same amount to the same destination as a sent transaction that is unconfirmed or was
+	/// created recently, as after a client retried a send it didn't see succeed. Set
+	/// `allow_duplicate` to make it anyway, or see
+	/// [`check_duplicate_payment`](struct.Owner.html#method.check_duplicate_payment).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
+	/// transaction initialization arguments. See struct documentation for further detail.
+	///
+	/// # Returns
+	/// * a result containing:
+	/// * The transaction [Slate](../epic_wallet_libwallet/slate/struct.Slate.html),
+	/// which can be forwarded to the recieving party by any means. Once the caller is relatively
+	/// certain that the transaction has been sent to the recipient, the associated wallet
+	/// transaction outputs should be locked via a call to
+	/// [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs). This must be called before calling
+	/// [`finalize_tx`](struct.Owner.html#method.finalize_tx). Its `fee_breakdown` shows how the
+	/// fee was worked out from the numbers of inputs, outputs and kernels, and is also kept on
+	/// the transaction log entry once the outputs are locked.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Remarks
+	///
+	/// * This method requires an active connection to a node, and will fail with error if a node
+	/// cannot be contacted to refresh output statuses.
+	/// * This method will store a partially completed transaction in the wallet's transaction log,
+	/// which will be updated on the corresponding call to [`finalize_tx`](struct.Owner.html#method.finalize_tx).
+	///
+	/// # Example
+	/// Set up as in [new](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// // Attempt to create a transaction using the 'default' account
+	/// let args = InitTxArgs::builder()
+	/// 	.amount(Amount::from_freemans(2_000_000_000))
+	/// 	.minimum_confirmations(2)
+	/// 	.strategy(SelectionStrategy::Smallest)
+	/// 	.message("Have some Epics. Love, Yeastplume".to_owned())
+	/// 	.build()
+	/// 	.unwrap();
+	/// let result = api_owner.init_send_tx(
+	/// 	None,
+	/// 	args,
+	/// );
+	///
+	/// if let Ok(slate) = result {
+	/// 	// Send slate somehow
+	/// 	// ...
 	/// 	// Lock our outputs if we're happy the slate was (or is being) sent
 	/// 	api_owner.tx_lock_outputs(None, &slate, 0);
 	/// }
 	/// ```
 
-	pub fn init_send_tx(
+	pub fn init_send_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		args: InitTxArgs,
+	) -> Result<Slate, Error> {
+		let mut args = args;
+		self.apply_reorg_safety(&mut args);
+		if args.wait_for_maturity.is_some() && args.send_args.is_none() {
+			return Err(ErrorKind::SendQueue(
+				"waiting for funds to mature needs send arguments to deliver the send".to_owned(),
+			)
+			.into());
+		}
+		let send_args = args.send_args.clone();
+		let mut slate = {
+			let mut w_lock = self.wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			let queue_args = args.clone();
+			match owner::init_send_tx(&mut **w, keychain_mask, args, self.doctest_mode) {
+				Ok(s) => s,
+				Err(e) if queue_args.wait_for_maturity.is_some() => {
+					let queued = owner::queue_send_tx(&mut **w, keychain_mask, queue_args, e)?;
+					return Err(ErrorKind::SendQueued {
+						id: queued.id.to_string(),
+						ready_height: queued.ready_height,
+					}
+					.into());
+				}
+				Err(e) => return Err(e),
+			}
+		};
+		// Helper functionality. If send arguments exist, attempt to send
+		match send_args {
+			Some(sa) => {
+				//TODO: in case of keybase, the response might take 60s and leave the service hanging
+				match sa.method.as_ref() {
+					"http" | "keybase" => {}
+					_ => {
+						error!("unsupported payment method: {}", sa.method);
+						return Err(ErrorKind::ClientCallback(
+							"unsupported payment method".to_owned(),
+						)
+						.into());
+					}
+				};
+				let tor_config_lock = self.tor_config.lock();
+				let comm_adapter =
+					create_sender(&sa.method, &sa.dest, tor_config_lock.clone(), None)
+						.map_err(|e| ErrorKind::GenericError(format!("{}", e)))?;
+				slate = comm_adapter.send_tx(&slate)?;
+				self.tx_lock_outputs(keychain_mask, &slate, 0)?;
+				let slate = match sa.finalize {
+					true => self.finalize_tx(keychain_mask, &slate)?,
+					false => slate,
+				};
+
+				if sa.post_tx {
+					// queued first, so the post is retried should it fail or
+					// be interrupted
+					let action = JobAction::PostTx {
+						tx_slate_id: slate.id,
+						fluff: sa.fluff,
+					};
+					let r = queue_and_run_job(self.wallet_inst.clone(), keychain_mask, action)?;
+					if let Some(e) = r.error {
+						return Err(ErrorKind::JobQueue(format!(
+							"posting transaction {} failed, it's tried again by job {}: {}",
+							slate.id, r.job.id, e
+						))
+						.into());
+					}
+				}
+				Ok(slate)
+			}
+			None => Ok(slate),
+		}
+	}
+
+	/// Pays a payment request created by the payee's
+	/// [Foreign API's `create_payment_request`](struct.Foreign.html#method.create_payment_request),
+	/// as [`init_send_tx`](struct.Owner.html#method.init_send_tx) would send its amount. The request
+	/// is refused if it's expired, isn't signed by its proof address, or has already been paid
+	/// by this wallet, unless that payment was cancelled.
+	///
+	/// The transaction takes the request's id as its slate id, and asks for a payment proof
+	/// naming the request's proof address, so it proves the request was paid. It's sent,
+	/// finalized and posted as given by the `send_args` of the `args`, or returned to be sent by
+	/// other means as with `init_send_tx`.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `request` - The armored request, as read by
+	/// [`parse_payment_request`](struct.Owner.html#method.parse_payment_request)
+	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
+	/// transaction initialization arguments, built with `amount_from_invoice` to take the amount
+	/// from the request, or with an amount the request must match. The message defaults to the
+	/// request's memo, and a `template` can't be given.
+	///
+	/// # Returns
+	/// * Ok with the transaction [Slate](../epic_wallet_libwallet/slate/struct.Slate.html), as
+	/// returned by `init_send_tx`
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [new](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// # let request = "EPICREQUEST:AE";
+	/// // Read the request from the payee somehow
+	///
+	/// let args = InitTxArgs::builder()
+	/// 	.amount_from_invoice()
+	/// 	.minimum_confirmations(2)
+	/// 	.build()
+	/// 	.unwrap();
+	/// let result = api_owner.pay_request(None, request, args);
+	///
+	/// if let Ok(slate) = result {
+	/// 	// Send slate to the payee
+	/// 	// ...
+	/// 	api_owner.tx_lock_outputs(None, &slate, 0);
+	/// }
+	/// ```
+
+	pub fn pay_request(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		request: &str,
+		args: InitTxArgs,
+	) -> Result<Slate, Error> {
+		let mut args = args;
+		args.payment_request = Some(self.parse_payment_request(request)?);
+		self.init_send_tx(keychain_mask, args)
+	}
+
+	/// Creates a template of a transaction without creating the transaction itself or
+	/// locking any outputs. The template contains the exact inputs the wallet would select,
+	/// the outputs the transaction would create (the recipient's output as well as any change
+	/// outputs) and the fee, so that it can be reviewed by other parties before the
+	/// transaction is created.
+	///
+	/// Once approved, the template can be passed back via the `template` field of
+	/// [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html) to
+	/// [`init_send_tx`](struct.Owner.html#method.init_send_tx), which will only create the
+	/// transaction if the wallet would still select the same inputs and pay the same fee.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
+	/// transaction initialization arguments. The `estimate_only`, `send_args` and `template`
+	/// fields are ignored.
+	///
+	/// # Returns
+	/// * Ok([`TxTemplate`](../epic_wallet_libwallet/types/struct.TxTemplate.html)) if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Remarks
+	///
+	/// * This method requires an active connection to a node, and will fail with error if a node
+	/// cannot be contacted to refresh output statuses.
+	///
+	/// # Example
+	/// Set up as in [new](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let args = InitTxArgs::builder()
+	/// 	.amount(Amount::from_freemans(2_000_000_000))
+	/// 	.minimum_confirmations(2)
+	/// 	.strategy(SelectionStrategy::Smallest)
+	/// 	.build()
+	/// 	.unwrap();
+	/// let result = api_owner.create_tx_template(None, args);
+	///
+	/// if let Ok(template) = result {
+	/// 	// Have the template reviewed
+	/// 	// ...
+	/// 	// Then create the transaction it describes
+	/// 	let args = InitTxArgs::builder().template(template).build().unwrap();
+	/// 	let result = api_owner.init_send_tx(None, args);
+	/// }
+	/// ```
+
+	pub fn create_tx_template(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		args: InitTxArgs,
+	) -> Result<TxTemplate, Error> {
+		let mut args = args;
+		self.apply_reorg_safety(&mut args);
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::create_tx_template(&mut **w, keychain_mask, args)
+	}
+
+	/// Issues a new invoice transaction slate, essentially a `request for payment`.
+	/// The slate created by this function will contain the amount, an output for the amount,
+	/// as well as round 1 of singature creation complete. The slate should then be send
+	/// to the payer, who should add their inputs and signature data and return the slate
+	/// via the [Foreign API's `finalize_invoice_tx`](struct.Foreign.html#method.finalize_invoice_tx) method.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `args` - [`IssueInvoiceTxArgs`](../epic_wallet_libwallet/types/struct.IssueInvoiceTxArgs.html),
+	/// invoice transaction initialization arguments. See struct documentation for further detail.
+	///
+	/// # Returns
+	/// * ``Ok([`slate`](../epic_wallet_libwallet/slate/struct.Slate.html))` if successful,
+	/// containing the updated slate.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	///
+	/// let args = IssueInvoiceTxArgs::builder()
+	/// 	.amount(Amount::from_freemans(60_000_000_000))
+	/// 	.build()
+	/// 	.unwrap();
+	/// let result = api_owner.issue_invoice_tx(None, args);
+	///
+	/// if let Ok(slate) = result {
+	///		// if okay, send to the payer to add their inputs
+	///		// . . .
+	/// }
+	/// ```
+	pub fn issue_invoice_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		args: IssueInvoiceTxArgs,
+	) -> Result<Slate, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::issue_invoice_tx(&mut **w, keychain_mask, args, self.doctest_mode)
+	}
+
+	/// Issues an invoice for the merchant API, which e-commerce plugins use to
+	/// take payments without handling slates themselves. The invoice is an
+	/// invoice transaction as issued by
+	/// [`issue_invoice_tx`](struct.Owner.html#method.issue_invoice_tx), with the memo
+	/// recorded in the transaction log so it's returned whenever the invoice is
+	/// looked up by its id.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `amount` - The amount to invoice, in freemans
+	/// * `memo` - An optional memo, e.g. an order number, included with the invoice as the
+	/// invoicer's participant message
+	///
+	/// # Returns
+	/// * Ok([`MerchantInvoice`](../epic_wallet_libwallet/api_impl/types/struct.MerchantInvoice.html))
+	/// if successful, holding the invoice slate to be passed on to the customer
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.create_merchant_invoice(None, 60_000_000_000, Some("order 42".to_owned()));
+	///
+	/// if let Ok(invoice) = result {
+	///		// hand invoice.slate to the customer, and keep invoice.id to check on it
+	///		// . . .
+	/// }
+	/// ```
+	pub fn create_merchant_invoice(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		amount: u64,
+		memo: Option<String>,
+	) -> Result<MerchantInvoice, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::create_merchant_invoice(&mut **w, keychain_mask, amount, memo, self.doctest_mode)
+	}
+
+	/// Looks up an invoice issued by
+	/// [`create_merchant_invoice`](struct.Owner.html#method.create_merchant_invoice), returning
+	/// its payment status. An invoice is `paid` once the customer's payment has been finalized,
+	/// and `confirmed` once it's been confirmed on chain.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation) to check whether the payment has been confirmed.
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `id` - The invoice id, which is the id of its slate
+	///
+	/// # Returns
+	/// * Ok([`MerchantInvoice`](../epic_wallet_libwallet/api_impl/types/struct.MerchantInvoice.html))
+	/// if successful, without the invoice slate
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if the invoice
+	/// doesn't exist or an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let id = Uuid::parse_str("0436430c-2b02-624c-2032-570501212b00").unwrap();
+	///
+	/// let result = api_owner.retrieve_merchant_invoice(None, true, id);
+	///
+	/// if let Ok(invoice) = result {
+	///		// . . .
+	/// }
+	/// ```
+	pub fn retrieve_merchant_invoice(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		id: Uuid,
+	) -> Result<MerchantInvoice, Error> {
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		owner::retrieve_merchant_invoice(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			refresh_from_node,
+			id,
+		)
+	}
+
+	/// Returns the balances of the accounts a busy wallet's load is sharded across. Spreading
+	/// receives and sends over several accounts keeps any one account's outputs from being
+	/// tied up by transactions in progress; see
+	/// [`init_sharded_send_tx`](struct.Owner.html#method.init_sharded_send_tx) and
+	/// [`rebalance_shards`](struct.Owner.html#method.rebalance_shards).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node to update output statuses first. Ignored while the updater thread is running.
+	/// * `shards` - Names of the shard accounts
+	/// * `minimum_confirmations` - The minimum number of confirmations an output
+	/// should have before it's included in the spendable amounts
+	///
+	/// # Returns
+	/// * (`bool`, `Vec<`[`ShardBalance`](../epic_wallet_libwallet/api_impl/types/struct.ShardBalance.html)`>`) -
+	/// whether the data was refreshed from the node, and the balance of each shard in the order given
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as a shard account not existing.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let shards = vec!["default".to_owned()];
+	/// let result = api_owner.shard_balances(None, true, &shards, 10);
+	///
+	/// if let Ok((was_updated, balances)) = result {
+	///		// . . .
+	/// }
+	/// ```
+	pub fn shard_balances(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		refresh_from_node: bool,
+		shards: &[String],
+		minimum_confirmations: u64,
+	) -> Result<(bool, Vec<ShardBalance>), Error> {
+		let mut validated = false;
+		if refresh_from_node && !self.updater_running.load(Ordering::Relaxed) {
+			let tx = {
+				let t = self.status_tx.lock();
+				t.clone()
+			};
+			validated =
+				owner::update_wallet_state(self.wallet_inst.clone(), keychain_mask, &tx, false)?;
+		}
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		let balances = owner::shard_balances(&mut **w, shards, minimum_confirmations)?;
+		Ok((validated, balances))
+	}
+
+	/// Initiates a send from whichever of the shard accounts has the most to spend, as
+	/// [`init_send_tx`](struct.Owner.html#method.init_send_tx) would with that account as
+	/// `src_acct_name`. Any `src_acct_name` in `args` is replaced.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `shards` - Names of the shard accounts to send from
+	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
+	/// transaction initialization arguments, as for `init_send_tx`.
+	///
+	/// # Returns
+	/// * The transaction [Slate](../epic_wallet_libwallet/slate/struct.Slate.html), as returned by
+	/// `init_send_tx`, whose outputs are locked by
+	/// [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs) as usual
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let shards = vec!["default".to_owned()];
+	/// let args = InitTxArgs {
+	/// 	amount: Amount::from_freemans(2_000_000_000),
+	/// 	minimum_confirmations: 2,
+	/// 	max_outputs: 500,
+	/// 	num_change_outputs: 1,
+	/// 	selection_strategy_is_use_all: false,
+	/// 	..Default::default()
+	/// };
+	/// let result = api_owner.init_sharded_send_tx(None, &shards, args);
+	///
+	/// if let Ok(slate) = result {
+	/// 	// Send slate somehow
+	/// 	// ...
+	/// 	api_owner.tx_lock_outputs(None, &slate, 0);
+	/// }
+	/// ```
+	pub fn init_sharded_send_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		shards: &[String],
+		args: InitTxArgs,
+	) -> Result<Slate, Error> {
+		let mut args = args;
+		{
+			let mut w_lock = self.wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			args.src_acct_name = Some(owner::select_send_shard(
+				&mut **w,
+				shards,
+				args.minimum_confirmations,
+			)?);
+		}
+		self.init_send_tx(keychain_mask, args)
+	}
+
+	/// Evens out the shard accounts, with a self-send of half the difference between the amounts
+	/// the richest and poorest of them can spend. The transaction is finalized and posted, so
+	/// calling this periodically keeps funds spread across the shards as receives and sends
+	/// drift them apart.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `shards` - Names of the shard accounts
+	/// * `minimum_confirmations` - The minimum number of confirmations an output
+	/// should have to be spent, and counted as spendable
+	/// * `min_amount` - The smallest amount, in freemans, worth moving
+	///
+	/// # Returns
+	/// * Ok(Some([Slate](../epic_wallet_libwallet/slate/struct.Slate.html))) with the posted
+	/// self-send, or Ok(None) if the shards are already within `min_amount` of even
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let shards = vec!["default".to_owned()];
+	/// let result = api_owner.rebalance_shards(None, &shards, 10, 1_000_000_000);
+	///
+	/// if let Ok(Some(slate)) = result {
+	///		// . . .
+	/// }
+	/// ```
+	pub fn rebalance_shards(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		shards: &[String],
+		minimum_confirmations: u64,
+		min_amount: u64,
+	) -> Result<Option<Slate>, Error> {
+		owner::rebalance_shards(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			shards,
+			minimum_confirmations,
+			min_amount,
+			self.doctest_mode,
+		)
+	}
+
+	/// Processes an invoice tranaction created by another party, essentially
+	/// a `request for payment`. The incoming slate should contain a requested
+	/// amount, an output created by the invoicer convering the amount, and
+	/// part 1 of signature creation completed. This function will add inputs
+	/// equalling the amount + fees, as well as perform round 1 and 2 of signature
+	/// creation.
+	///
+	/// Callers should note that no prompting of the user will be done by this function
+	/// it is up to the caller to present the request for payment to the user
+	/// and verify that payment should go ahead.
+	///
+	/// The invoice is refused unless it holds the invoicer's output alone, with no inputs
+	/// and no fee set. It's also refused if an `amount` is given in the args and the invoice
+	/// is for a different one, or if the payer's part of the fee would be more than the
+	/// `max_invoice_fee` given in the args or set with
+	/// [`set_max_invoice_fee`](struct.Owner.html#method.set_max_invoice_fee).
+	///
+	/// This function also stores the final transaction in the user's wallet files for retrieval
+	/// via the [`get_stored_tx`](struct.Owner.html#method.get_stored_tx) function.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html). The
+	/// payer should have filled in round 1 and 2.
+	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
+	/// transaction initialization arguments. See struct documentation for further detail.
+	///
+	/// # Returns
+	/// * ``Ok([`slate`](../epic_wallet_libwallet/slate/struct.Slate.html))` if successful,
+	/// containing the updated slate.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	///
+	/// // . . .
+	/// // The slate has been recieved from the invoicer, somehow
+	/// # let slate = Slate::blank(2);
+	/// let args = InitTxArgs::builder()
+	/// 	.amount_from_invoice()
+	/// 	.minimum_confirmations(2)
+	/// 	.strategy(SelectionStrategy::Smallest)
+	/// 	.build()
+	/// 	.unwrap();
+	///
+	/// let result = api_owner.process_invoice_tx(None, &slate, args);
+	///
+	/// if let Ok(slate) = result {
+	///	// If result okay, send back to the invoicer
+	///	// . . .
+	///	}
+	/// ```
+
+	pub fn process_invoice_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		slate: &Slate,
+		args: InitTxArgs,
+	) -> Result<Slate, Error> {
+		let mut args = args;
+		self.apply_reorg_safety(&mut args);
+		self.apply_invoice_fee_policy(&mut args);
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::process_invoice_tx(&mut **w, keychain_mask, slate, args, self.doctest_mode)
+	}
+
+	/// Locks the outputs associated with the inputs to the transaction in the given
+	/// [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html),
+	/// making them unavailable for use in further transactions. This function is called
+	/// by the sender, (or more generally, all parties who have put inputs into the transaction,)
+	/// and must be called before the corresponding call to [`finalize_tx`](struct.Owner.html#method.finalize_tx)
+	/// that completes the transaction.
+	///
+	/// Outputs will generally remain locked until they are removed from the chain,
+	/// at which point they will become `Spent`. It is commonplace for transactions not to complete
+	/// for various reasons over which a particular wallet has no control. For this reason,
+	/// [`cancel_tx`](struct.Owner.html#method.cancel_tx) can be used to manually unlock outputs
+	/// and return them to the `Unspent` state.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html). All
+	/// * `participant_id` - The participant id, generally 0 for the party putting in funds, 1 for the
+	/// party receiving.
+	/// elements in the `input` vector of the `tx` field that are found in the wallet's currently
+	/// active account will be set to status `Locked`
+	///
+	/// # Returns
+	/// * Ok(()) if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let args = InitTxArgs {
+	/// 	src_acct_name: None,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
+	/// 	minimum_confirmations: 10,
+	/// 	max_outputs: 500,
+	/// 	num_change_outputs: 1,
+	/// 	selection_strategy_is_use_all: false,
+	/// 	message: Some("Remember to lock this when we're happy this is sent".to_owned()),
+	/// 	..Default::default()
+	/// };
+	/// let result = api_owner.init_send_tx(
+	/// 	None,
+	/// 	args,
+	/// );
+	///
+	/// if let Ok(slate) = result {
+	///		// Send slate somehow
+	///		// ...
+	///		// Lock our outputs if we're happy the slate was (or is being) sent
+	///		api_owner.tx_lock_outputs(None, &slate, 0);
+	/// }
+	/// ```
+
+	pub fn tx_lock_outputs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		slate: &Slate,
+		participant_id: usize,
+	) -> Result<(), Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::tx_lock_outputs(&mut **w, keychain_mask, slate, participant_id)
+	}
+
+	/// Finalizes a transaction, after all parties
+	/// have filled in both rounds of Slate generation. This step adds
+	/// all participants partial signatures to create the final signature,
+	/// resulting in a final transaction that is ready to post to a node.
+	///
+	/// Note that this function DOES NOT POST the transaction to a node
+	/// for validation. This is done in separately via the
+	/// [`post_tx`](struct.Owner.html#method.post_tx) function.
+	///
+	/// This function also stores the final transaction in the user's wallet files for retrieval
+	/// via the [`get_stored_tx`](struct.Owner.html#method.get_stored_tx) function.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html). All
+	/// participants must have filled in both rounds, and the sender should have locked their
+	/// outputs (via the [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs) function).
+	///
+	/// # Returns
+	/// * ``Ok([`slate`](../epic_wallet_libwallet/slate/struct.Slate.html))` if successful,
+	/// containing the new finalized slate.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let args = InitTxArgs {
+	/// 	src_acct_name: None,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
+	/// 	minimum_confirmations: 10,
+	/// 	max_outputs: 500,
+	/// 	num_change_outputs: 1,
+	/// 	selection_strategy_is_use_all: false,
+	/// 	message: Some("Finalize this tx now".to_owned()),
+	/// 	..Default::default()
+	/// };
+	/// let result = api_owner.init_send_tx(
+	/// 	None,
+	/// 	args,
+	/// );
+	///
+	/// if let Ok(slate) = result {
+	///		// Send slate somehow
+	///		// ...
+	///		// Lock our outputs if we're happy the slate was (or is being) sent
+	///		let res = api_owner.tx_lock_outputs(None, &slate, 0);
+	///		//
+	///		// Retrieve slate back from recipient
+	///		//
+	///		let res = api_owner.finalize_tx(None, &slate);
+	/// }
+	/// ```
+
+	pub fn finalize_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		slate: &Slate,
+	) -> Result<Slate, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::finalize_tx(&mut **w, keychain_mask, &slate)
+	}
+
+	/// Posts a completed transaction to the listening node for validation and inclusion in a block
+	/// for mining.
+	///
+	/// If the transaction was finalized by this wallet, its transaction log entry is saved with a
+	/// `post_state` of `ReadyToPost` before the transaction is posted and marked `Posted` once the
+	/// node accepts it. A post interrupted in between is finished the next time the node is
+	/// pinged, see [`recover_pending_posts`](struct.Owner.html#method.recover_pending_posts).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `tx` - A completed [`Transaction`](../epic_core/core/transaction/struct.Transaction.html),
+	/// typically the `tx` field in the transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html).
+	/// * `fluff` - Instruct the node whether to use the Dandelion protocol when posting the
+	/// transaction. If `true`, the node should skip the Dandelion phase and broadcast the
+	/// transaction to all peers immediately. If `false`, the node will follow dandelion logic and
+	/// initiate the stem phase.
+	///
+	/// # Returns
+	/// * `Ok(())` if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let args = InitTxArgs {
+	/// 	src_acct_name: None,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
+	/// 	minimum_confirmations: 10,
+	/// 	max_outputs: 500,
+	/// 	num_change_outputs: 1,
+	/// 	selection_strategy_is_use_all: false,
+	/// 	message: Some("Post this tx".to_owned()),
+	/// 	..Default::default()
+	/// };
+	/// let result = api_owner.init_send_tx(
+	/// 	None,
+	/// 	args,
+	/// );
+	///
+	/// if let Ok(slate) = result {
+	///		// Send slate somehow
+	///		// ...
+	///		// Lock our outputs if we're happy the slate was (or is being) sent
+	///		let res = api_owner.tx_lock_outputs(None, &slate, 0);
+	///		//
+	///		// Retrieve slate back from recipient
+	///		//
+	///		let res = api_owner.finalize_tx(None, &slate);
+	///		let res = api_owner.post_tx(None, &slate.tx, true);
+	/// }
+	/// ```
+
+	pub fn post_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		tx: &Transaction,
+		fluff: bool,
+	) -> Result<(), Error> {
+		owner::post_and_record_tx(self.wallet_inst.clone(), keychain_mask, tx, fluff)
+	}
+
+	/// Posts a completed transaction as [`post_tx`](struct.Owner.html#method.post_tx) does, unless
+	/// the node can't be reached. The transaction is then queued with a `post_state` of `Queued`
+	/// and posted by [`recover_pending_posts`](struct.Owner.html#method.recover_pending_posts)
+	/// once the node is back, so a wallet without a node can still finish transactions.
+	///
+	/// Only transactions finalized by this wallet, whose transaction data it stores, can be
+	/// queued. Queued transactions are posted without skipping the Dandelion phase, whatever
+	/// `fluff` was given.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `tx` - A completed [`Transaction`](../epic_core/core/transaction/struct.Transaction.html),
+	/// typically the `tx` field in the transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html).
+	/// * `fluff` - Instruct the node whether to use the Dandelion protocol when posting the
+	/// transaction, as in [`post_tx`](struct.Owner.html#method.post_tx).
+	///
+	/// # Returns
+	/// * Ok(`true`) if the transaction was posted, or Ok(`false`) if it was queued
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// including the node refusing the transaction.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let args = InitTxArgs {
+	/// 	src_acct_name: None,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
+	/// 	minimum_confirmations: 10,
+	/// 	max_outputs: 500,
+	/// 	num_change_outputs: 1,
+	/// 	selection_strategy_is_use_all: false,
+	/// 	..Default::default()
+	/// };
+	/// let result = api_owner.init_send_tx(None, args);
+	///
+	/// if let Ok(slate) = result {
+	///		// Send slate somehow, lock outputs and retrieve the slate back
+	///		// ...
+	///		let res = api_owner.finalize_tx(None, &slate);
+	///		match api_owner.post_or_queue_tx(None, &slate.tx, false) {
+	///			Ok(true) => println!("Posted"),
+	///			Ok(false) => println!("Queued until the node is back"),
+	///			Err(e) => println!("Not posted: {}", e),
+	///		}
+	/// }
+	/// ```
+
+	pub fn post_or_queue_tx(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		args: InitTxArgs,
-	) -> Result<Slate, Error> {
-		let send_args = args.send_args.clone();
-		let mut slate = {
+		tx: &Transaction,
+		fluff: bool,
+	) -> Result<bool, Error> {
+		owner::post_or_queue_tx(self.wallet_inst.clone(), keychain_mask, tx, fluff)
+	}
+
+	/// Returns the transactions waiting to be posted: those queued by
+	/// [`post_or_queue_tx`](struct.Owner.html#method.post_or_queue_tx) while the node couldn't be
+	/// reached, and any whose post was interrupted. They're posted by
+	/// [`recover_pending_posts`](struct.Owner.html#method.recover_pending_posts).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) of each
+	/// waiting transaction, in any account, with a `post_state` of `Queued` or `ReadyToPost`
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.retrieve_post_queue(None);
+	///
+	/// if let Ok(queue) = result {
+	///		for tx in queue {
+	///			println!("{}: {:?}", tx.id, tx.post_state);
+	///		}
+	/// }
+	/// ```
+
+	pub fn retrieve_post_queue(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Vec<TxLogEntry>, Error> {
+		owner::retrieve_post_queue(self.wallet_inst.clone(), keychain_mask)
+	}
+
+	/// Finishes transaction posts that were queued while the node couldn't be reached, or
+	/// interrupted, e.g. by a crash, after the transaction was saved as ready to post but before
+	/// the node's acknowledgment was recorded. This is done automatically by
+	/// [`node_ping`](struct.Owner.html#method.node_ping) once the node answers, and by the
+	/// updater thread.
+	///
+	/// Transactions the node already has are marked as posted and the others are posted again.
+	/// Those the node refuses are cancelled, unlocking their inputs. If the node can't be reached
+	/// nothing is changed, and the posts are left for a later attempt.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) of each
+	/// resolved transaction, either with a `post_state` of `Posted` or cancelled
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.recover_pending_posts(None);
+	///
+	/// if let Ok(resolved) = result {
+	///		for tx in resolved {
+	///			println!("{}: {:?}", tx.id, tx.post_state);
+	///		}
+	/// }
+	/// ```
+
+	pub fn recover_pending_posts(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Vec<TxLogEntry>, Error> {
+		owner::recover_pending_posts(self.wallet_inst.clone(), keychain_mask)
+	}
+
+	/// Returns the sends queued by [`init_send_tx`](struct.Owner.html#method.init_send_tx) until
+	/// enough of the wallet's coinbase outputs mature to fund them, as asked by its
+	/// `wait_for_maturity` argument.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with each [`QueuedSend`](../epic_wallet_libwallet/types/struct.QueuedSend.html), oldest
+	/// first
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.retrieve_send_queue(None);
+	///
+	/// if let Ok(queue) = result {
+	///		for queued in queue {
+	///			println!("{}: ready at {}", queued.id, queued.ready_height);
+	///		}
+	/// }
+	/// ```
+
+	pub fn retrieve_send_queue(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Vec<QueuedSend>, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::retrieve_send_queue(&mut **w, keychain_mask)
+	}
+
+	/// Removes a send from the queue kept by [`init_send_tx`](struct.Owner.html#method.init_send_tx)
+	/// while waiting for funds to mature, so it's never made.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `id` - Id of the queued send, as returned in the `SendQueued` error.
+	///
+	/// # Returns
+	/// * Ok with the removed [`QueuedSend`](../epic_wallet_libwallet/types/struct.QueuedSend.html)
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as when there's no queued send with that id.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// if let Ok(queue) = api_owner.retrieve_send_queue(None) {
+	///		for queued in queue {
+	///			let _ = api_owner.cancel_queued_send(None, &queued.id);
+	///		}
+	/// }
+	/// ```
+
+	pub fn cancel_queued_send(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		id: &Uuid,
+	) -> Result<QueuedSend, Error> {
+		let _lock = self.send_queue_lock.lock();
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::cancel_queued_send(&mut **w, keychain_mask, id)
+	}
+
+	/// Makes the queued sends whose funds are expected to have matured, as
+	/// [`init_send_tx`](struct.Owner.html#method.init_send_tx) would with their `send_args`. This
+	/// is done regularly by the Owner API listener.
+	///
+	/// A send still short of funds stays queued if they're expected to mature before the
+	/// `wait_for_maturity` blocks it was queued with have passed, and is dropped otherwise. Sends
+	/// that fail for any other reason are dropped too. Nothing is made while the node can't be
+	/// reached.
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with a [`QueuedSendResult`](../epic_wallet_libwallet/types/struct.QueuedSendResult.html)
+	/// for each send made or dropped
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.process_send_queue(None);
+	///
+	/// if let Ok(results) = result {
+	///		for r in results {
+	///			println!("{}: {:?} {:?}", r.queued.id, r.tx_slate_id, r.error);
+	///		}
+	/// }
+	/// ```
+
+	pub fn process_send_queue(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Vec<QueuedSendResult>, Error> {
+		let _lock = self.send_queue_lock.lock();
+		let due = owner::due_queued_sends(self.wallet_inst.clone(), keychain_mask)?;
+		let mut results = vec![];
+		for queued in due {
+			let mut args = queued.args.clone();
+			args.wait_for_maturity = None;
+			let res = self.init_send_tx(keychain_mask, args);
 			let mut w_lock = self.wallet_inst.lock();
 			let w = w_lock.lc_provider()?.wallet_inst()?;
-			owner::init_send_tx(&mut **w, keychain_mask, args, self.doctest_mode)?
-		};
-		// Helper functionality. If send arguments exist, attempt to send
-		match send_args {
-			Some(sa) => {
-				//TODO: in case of keybase, the response might take 60s and leave the service hanging
-				match sa.method.as_ref() {
-					"http" | "keybase" => {}
-					_ => {
-						error!("unsupported payment method: {}", sa.method);
-						return Err(ErrorKind::ClientCallback(
-							"unsupported payment method".to_owned(),
-						)
-						.into());
-					}
-				};
-				let tor_config_lock = self.tor_config.lock();
-				let comm_adapter = create_sender(&sa.method, &sa.dest, tor_config_lock.clone())
-					.map_err(|e| ErrorKind::GenericError(format!("{}", e)))?;
-				slate = comm_adapter.send_tx(&slate)?;
-				self.tx_lock_outputs(keychain_mask, &slate, 0)?;
-				let slate = match sa.finalize {
-					true => self.finalize_tx(keychain_mask, &slate)?,
-					false => slate,
-				};
-
-				if sa.post_tx {
-					self.post_tx(keychain_mask, &slate.tx, sa.fluff)?;
-				}
-				Ok(slate)
+			if let Some(r) = owner::resolve_queued_send(&mut **w, keychain_mask, queued, res)? {
+				results.push(r);
 			}
-			None => Ok(slate),
 		}
+		Ok(results)
 	}
 
-	/// Issues a new invoice transaction slate, essentially a `request for payment`.
-	/// The slate created by this function will contain the amount, an output for the amount,
-	/// as well as round 1 of singature creation complete. The slate should then be send
-	/// to the payer, who should add their inputs and signature data and return the slate
-	/// via the [Foreign API's `finalize_invoice_tx`](struct.Foreign.html#method.finalize_invoice_tx) method.
+	/// Queues a job in the wallet, to be run by
+	/// [`process_jobs`](struct.Owner.html#method.process_jobs). The job stays queued until
+	/// it's done, so it survives the wallet being closed or crashing before it is. A failed job
+	/// is tried again later, waiting twice as long after each failure, and after
+	/// [`JOB_MAX_ATTEMPTS`](../epic_wallet_libwallet/types/constant.JOB_MAX_ATTEMPTS.html)
+	/// failures is kept as a dead letter until it's retried or deleted.
+	///
+	/// [`init_send_tx`](struct.Owner.html#method.init_send_tx) queues the posting of the
+	/// transactions it posts, and [`watch_tx_finality`](struct.Owner.html#method.watch_tx_finality)
+	/// its callbacks.
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `args` - [`IssueInvoiceTxArgs`](../epic_wallet_libwallet/types/struct.IssueInvoiceTxArgs.html),
-	/// invoice transaction initialization arguments. See struct documentation for further detail.
+	/// * `action` - The [`JobAction`](../epic_wallet_libwallet/types/enum.JobAction.html) to take.
 	///
 	/// # Returns
-	/// * ``Ok([`slate`](../epic_wallet_libwallet/slate/struct.Slate.html))` if successful,
-	/// containing the updated slate.
+	/// * Ok with the queued [`Job`](../epic_wallet_libwallet/types/struct.Job.html)
 	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
 	///
 	/// # Example
@@ -697,119 +3577,138 @@ where
 	/// ```
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
-	/// let mut api_owner = Owner::new(wallet.clone());
+	/// use libwallet::JobAction;
 	///
-	/// let args = IssueInvoiceTxArgs {
-	/// 	amount: 60_000_000_000,
-	/// 	..Default::default()
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let action = JobAction::Webhook {
+	///		url: "http://localhost:8080/received".to_owned(),
+	///		body: serde_json::json!({ "amount": "1000000" }),
 	/// };
-	/// let result = api_owner.issue_invoice_tx(None, args);
-	///
-	/// if let Ok(slate) = result {
-	///		// if okay, send to the payer to add their inputs
-	///		// . . .
-	/// }
+	/// let result = api_owner.queue_job(None, action);
 	/// ```
-	pub fn issue_invoice_tx(
+
+	pub fn queue_job(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		args: IssueInvoiceTxArgs,
-	) -> Result<Slate, Error> {
+		action: JobAction,
+	) -> Result<Job, Error> {
+		if let JobAction::Webhook { .. } = action {
+			if !self.webhooks_enabled() {
+				return Err(ErrorKind::JobQueue("webhooks are not enabled".to_owned()))?;
+			}
+		}
 		let mut w_lock = self.wallet_inst.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		owner::issue_invoice_tx(&mut **w, keychain_mask, args, self.doctest_mode)
+		owner::queue_job(&mut **w, keychain_mask, action, false)
 	}
 
-	/// Processes an invoice tranaction created by another party, essentially
-	/// a `request for payment`. The incoming slate should contain a requested
-	/// amount, an output created by the invoicer convering the amount, and
-	/// part 1 of signature creation completed. This function will add inputs
-	/// equalling the amount + fees, as well as perform round 1 and 2 of signature
-	/// creation.
+	/// Returns the jobs queued by [`queue_job`](struct.Owner.html#method.queue_job), dead letters
+	/// included.
 	///
-	/// Callers should note that no prompting of the user will be done by this function
-	/// it is up to the caller to present the request for payment to the user
-	/// and verify that payment should go ahead.
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
 	///
-	/// This function also stores the final transaction in the user's wallet files for retrieval
-	/// via the [`get_stored_tx`](struct.Owner.html#method.get_stored_tx) function.
+	/// # Returns
+	/// * Ok with each [`Job`](../epic_wallet_libwallet/types/struct.Job.html), oldest first
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.retrieve_jobs(None);
+	///
+	/// if let Ok(jobs) = result {
+	///		for job in jobs.iter().filter(|j| j.dead) {
+	///			println!("{}: {:?}", job.id, job.last_error);
+	///		}
+	/// }
+	/// ```
+
+	pub fn retrieve_jobs(&self, keychain_mask: Option<&SecretKey>) -> Result<Vec<Job>, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::retrieve_jobs(&mut **w, keychain_mask)
+	}
+
+	/// Has a queued job, such as a dead letter, run again by the next
+	/// [`process_jobs`](struct.Owner.html#method.process_jobs), with its count of attempts reset.
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html). The
-	/// payer should have filled in round 1 and 2.
-	/// * `args` - [`InitTxArgs`](../epic_wallet_libwallet/types/struct.InitTxArgs.html),
-	/// transaction initialization arguments. See struct documentation for further detail.
+	/// * `id` - Id of the job.
 	///
 	/// # Returns
-	/// * ``Ok([`slate`](../epic_wallet_libwallet/slate/struct.Slate.html))` if successful,
-	/// containing the updated slate.
-	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	/// * Ok with the [`Job`](../epic_wallet_libwallet/types/struct.Job.html), as it's requeued
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as when there's no job with that id.
 	///
 	/// # Example
 	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
 	/// ```
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
-	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let api_owner = Owner::new(wallet.clone());
+	/// if let Ok(jobs) = api_owner.retrieve_jobs(None) {
+	///		for job in jobs.iter().filter(|j| j.dead) {
+	///			let _ = api_owner.retry_job(None, &job.id);
+	///		}
+	/// }
+	/// ```
+
+	pub fn retry_job(&self, keychain_mask: Option<&SecretKey>, id: &Uuid) -> Result<Job, Error> {
+		let _lock = self.job_queue_lock.lock();
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::retry_job(&mut **w, keychain_mask, id)
+	}
+
+	/// Removes a job from the queue, so it's never run.
 	///
-	/// // . . .
-	/// // The slate has been recieved from the invoicer, somehow
-	/// # let slate = Slate::blank(2);
-	/// let args = InitTxArgs {
-	///		src_acct_name: None,
-	///		amount: slate.amount,
-	///		minimum_confirmations: 2,
-	///		max_outputs: 500,
-	///		num_change_outputs: 1,
-	///		selection_strategy_is_use_all: false,
-	///		..Default::default()
-	///	};
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `id` - Id of the job.
 	///
-	/// let result = api_owner.process_invoice_tx(None, &slate, args);
+	/// # Returns
+	/// * Ok with the removed [`Job`](../epic_wallet_libwallet/types/struct.Job.html)
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// such as when there's no job with that id.
 	///
-	/// if let Ok(slate) = result {
-	///	// If result okay, send back to the invoicer
-	///	// . . .
-	///	}
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// if let Ok(jobs) = api_owner.retrieve_jobs(None) {
+	///		for job in jobs.iter().filter(|j| j.dead) {
+	///			let _ = api_owner.delete_job(None, &job.id);
+	///		}
+	/// }
 	/// ```
 
-	pub fn process_invoice_tx(
-		&self,
-		keychain_mask: Option<&SecretKey>,
-		slate: &Slate,
-		args: InitTxArgs,
-	) -> Result<Slate, Error> {
+	pub fn delete_job(&self, keychain_mask: Option<&SecretKey>, id: &Uuid) -> Result<Job, Error> {
+		let _lock = self.job_queue_lock.lock();
 		let mut w_lock = self.wallet_inst.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		owner::process_invoice_tx(&mut **w, keychain_mask, slate, args, self.doctest_mode)
+		owner::delete_job(&mut **w, keychain_mask, id)
 	}
 
-	/// Locks the outputs associated with the inputs to the transaction in the given
-	/// [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html),
-	/// making them unavailable for use in further transactions. This function is called
-	/// by the sender, (or more generally, all parties who have put inputs into the transaction,)
-	/// and must be called before the corresponding call to [`finalize_tx`](struct.Owner.html#method.finalize_tx)
-	/// that completes the transaction.
-	///
-	/// Outputs will generally remain locked until they are removed from the chain,
-	/// at which point they will become `Spent`. It is commonplace for transactions not to complete
-	/// for various reasons over which a particular wallet has no control. For this reason,
-	/// [`cancel_tx`](struct.Owner.html#method.cancel_tx) can be used to manually unlock outputs
-	/// and return them to the `Unspent` state.
+	/// Runs the queued jobs that are due, those not yet tried and those whose wait after a
+	/// failure is over. This is done regularly by the Owner and Foreign API listeners.
 	///
 	/// # Arguments
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html). All
-	/// * `participant_id` - The participant id, generally 0 for the party putting in funds, 1 for the
-	/// party receiving.
-	/// elements in the `input` vector of the `tx` field that are found in the wallet's currently
-	/// active account will be set to status `Locked`
 	///
 	/// # Returns
-	/// * Ok(()) if successful
+	/// * Ok with a [`JobResult`](../epic_wallet_libwallet/api_impl/types/struct.JobResult.html)
+	/// for each job run
 	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
 	///
 	/// # Example
@@ -817,120 +3716,172 @@ where
 	/// ```
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
-	/// let mut api_owner = Owner::new(wallet.clone());
-	/// let args = InitTxArgs {
-	/// 	src_acct_name: None,
-	/// 	amount: 2_000_000_000,
-	/// 	minimum_confirmations: 10,
-	/// 	max_outputs: 500,
-	/// 	num_change_outputs: 1,
-	/// 	selection_strategy_is_use_all: false,
-	/// 	message: Some("Remember to lock this when we're happy this is sent".to_owned()),
-	/// 	..Default::default()
-	/// };
-	/// let result = api_owner.init_send_tx(
-	/// 	None,
-	/// 	args,
-	/// );
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.process_jobs(None);
 	///
-	/// if let Ok(slate) = result {
-	///		// Send slate somehow
-	///		// ...
-	///		// Lock our outputs if we're happy the slate was (or is being) sent
-	///		api_owner.tx_lock_outputs(None, &slate, 0);
+	/// if let Ok(results) = result {
+	///		for r in results {
+	///			println!("{}: {:?}", r.job.id, r.error);
+	///		}
 	/// }
 	/// ```
 
-	pub fn tx_lock_outputs(
-		&self,
-		keychain_mask: Option<&SecretKey>,
-		slate: &Slate,
-		participant_id: usize,
-	) -> Result<(), Error> {
-		let mut w_lock = self.wallet_inst.lock();
-		let w = w_lock.lc_provider()?.wallet_inst()?;
-		owner::tx_lock_outputs(&mut **w, keychain_mask, slate, participant_id)
+	pub fn process_jobs(&self, keychain_mask: Option<&SecretKey>) -> Result<Vec<JobResult>, Error> {
+		let _lock = self.job_queue_lock.lock();
+		let due = {
+			let mut w_lock = self.wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			owner::due_jobs(&mut **w, keychain_mask)?
+		};
+		let mut results = vec![];
+		for job in due {
+			results.push(run_job(self.wallet_inst.clone(), keychain_mask, job)?);
+		}
+		Ok(results)
 	}
 
-	/// Finalizes a transaction, after all parties
-	/// have filled in both rounds of Slate generation. This step adds
-	/// all participants partial signatures to create the final signature,
-	/// resulting in a final transaction that is ready to post to a node.
+	/// Makes an API call at most once for a given idempotency key. The first successful result
+	/// of the call is stored in the wallet, and returned without calling `f` again when the call
+	/// is retried with the same `method` and key, for instance by an orchestration layer that
+	/// timed out waiting for the first reply. Failed calls aren't stored, and can be retried.
 	///
-	/// Note that this function DOES NOT POST the transaction to a node
-	/// for validation. This is done in separately via the
-	/// [`post_tx`](struct.Owner.html#method.post_tx) function.
+	/// A hash of the call's arguments is stored with its result, and reusing the key for a call
+	/// with other arguments is an error. If the call succeeds but its result can't be stored,
+	/// the result is still returned, with a warning logged.
 	///
-	/// This function also stores the final transaction in the user's wallet files for retrieval
-	/// via the [`get_stored_tx`](struct.Owner.html#method.get_stored_tx) function.
+	/// The JSON-RPC versions of `init_send_tx`, `finalize_tx` and `post_tx` take an optional
+	/// `idempotency_key` that is passed to this function.
 	///
 	/// # Arguments
+	///
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `slate` - The transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html). All
-	/// participants must have filled in both rounds, and the sender should have locked their
-	/// outputs (via the [`tx_lock_outputs`](struct.Owner.html#method.tx_lock_outputs) function).
+	/// * `method` - The name of the call being made. Keys are only matched within a method.
+	/// * `idempotency_key` - A unique key chosen by the caller for this call. If `None`, `f` is
+	/// always called.
+	/// * `args` - The arguments of the call, which a retry must repeat.
+	/// * `f` - The call to make.
 	///
 	/// # Returns
-	/// * ``Ok([`slate`](../epic_wallet_libwallet/slate/struct.Slate.html))` if successful,
-	/// containing the new finalized slate.
-	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	/// * The result of `f`, or the stored result of the first call with the same key
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered,
+	/// including `IdempotencyKeyReused` if the key was used for a call with other arguments.
 	///
 	/// # Example
 	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
 	/// ```
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	/// # use libwallet::{SlateVersion, VersionedSlate};
 	///
-	/// let mut api_owner = Owner::new(wallet.clone());
+	/// let api_owner = Owner::new(wallet.clone());
 	/// let args = InitTxArgs {
 	/// 	src_acct_name: None,
-	/// 	amount: 2_000_000_000,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
 	/// 	minimum_confirmations: 10,
 	/// 	max_outputs: 500,
 	/// 	num_change_outputs: 1,
 	/// 	selection_strategy_is_use_all: false,
-	/// 	message: Some("Finalize this tx now".to_owned()),
 	/// 	..Default::default()
 	/// };
-	/// let result = api_owner.init_send_tx(
+	/// // A retry with the same key returns the slate created by the first call
+	/// let result = api_owner.with_idempotency_key(
 	/// 	None,
-	/// 	args,
+	/// 	"init_send_tx",
+	/// 	Some("withdrawal-42"),
+	/// 	&args,
+	/// 	|| {
+	/// 		api_owner
+	/// 			.init_send_tx(None, args.clone())
+	/// 			.map(|s| VersionedSlate::into_version(s, SlateVersion::V3))
+	/// 	},
 	/// );
-	///
-	/// if let Ok(slate) = result {
-	///		// Send slate somehow
-	///		// ...
-	///		// Lock our outputs if we're happy the slate was (or is being) sent
-	///		let res = api_owner.tx_lock_outputs(None, &slate, 0);
-	///		//
-	///		// Retrieve slate back from recipient
-	///		//
-	///		let res = api_owner.finalize_tx(None, &slate);
-	/// }
 	/// ```
 
-	pub fn finalize_tx(
+	pub fn with_idempotency_key<A, T, F>(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		slate: &Slate,
-	) -> Result<Slate, Error> {
-		let mut w_lock = self.wallet_inst.lock();
-		let w = w_lock.lc_provider()?.wallet_inst()?;
-		owner::finalize_tx(&mut **w, keychain_mask, &slate)
+		method: &str,
+		idempotency_key: Option<&str>,
+		args: &A,
+		f: F,
+	) -> Result<T, Error>
+	where
+		A: Serialize,
+		T: Serialize + DeserializeOwned,
+		F: FnOnce() -> Result<T, Error>,
+	{
+		let key = match idempotency_key {
+			Some(k) => k,
+			None => return f(),
+		};
+		let args_hash = IdempotentResult::hash_args(args)?;
+		let _guard = self.idempotency_lock.lock();
+		if let Some(r) = owner::get_idempotent_result(self.wallet_inst.clone(), method, key)? {
+			// results stored by earlier versions have no hash to check against
+			if r.args_hash.map_or(false, |h| h != args_hash) {
+				return Err(
+					ErrorKind::IdempotencyKeyReused(method.to_owned(), key.to_owned()).into(),
+				);
+			}
+			return serde_json::from_value(r.result).map_err(|e| {
+				ErrorKind::GenericError(format!("Invalid stored result for {}: {}", key, e)).into()
+			});
+		}
+		let res = f()?;
+		// the call has been made, so its result is returned even if it can't
+		// be stored for a retry
+		let stored = serde_json::to_value(&res)
+			.map_err(|e| {
+				Error::from(ErrorKind::GenericError(format!(
+					"Unable to serialize result: {}",
+					e
+				)))
+			})
+			.and_then(|value| {
+				owner::save_idempotent_result(
+					self.wallet_inst.clone(),
+					keychain_mask,
+					method,
+					key,
+					args_hash,
+					value,
+				)
+			});
+		if let Err(e) = stored {
+			warn!(
+				"Unable to store the {} result for idempotency key {}: {}",
+				method, key, e
+			);
+		}
+		Ok(res)
 	}
 
-	/// Posts a completed transaction to the listening node for validation and inclusion in a block
-	/// for mining.
+	/// Cancels a transaction. This entails:
+	/// * Setting the transaction status to either `TxSentCancelled` or `TxReceivedCancelled`
+	/// * Deleting all change outputs or recipient outputs associated with the transaction
+	/// * Setting the status of all assocatied inputs from `Locked` to `Spent` so they can be
+	/// used in new transactions.
+	///
+	/// Transactions can be cancelled by transaction log id or slate id (call with either set to
+	/// Some, not both)
+	///
+	/// Unless `force` is set, the node is first asked whether the transaction went through, and
+	/// the cancellation is refused if its kernel is on chain or waiting in the node's pool, or if
+	/// it was posted and the pool couldn't be checked. Cancelling such a transaction would unlock
+	/// inputs that are already spent and delete outputs that exist.
+	/// [`preview_cancel_tx`](struct.Owner.html#method.preview_cancel_tx) shows what cancelling
+	/// would do beforehand.
 	///
 	/// # Arguments
+	///
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `tx` - A completed [`Transaction`](../epic_core/core/transaction/struct.Transaction.html),
-	/// typically the `tx` field in the transaction [`Slate`](../epic_wallet_libwallet/slate/struct.Slate.html).
-	/// * `fluff` - Instruct the node whether to use the Dandelion protocol when posting the
-	/// transaction. If `true`, the node should skip the Dandelion phase and broadcast the
-	/// transaction to all peers immediately. If `false`, the node will follow dandelion logic and
-	/// initiate the stem phase.
+	/// * `tx_id` - If present, cancel by the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) id
+	/// for the transaction.
+	///
+	/// * `tx_slate_id` - If present, cancel by the Slate id.
+	///
+	/// * `force` - Cancel even if the node shows the transaction may have gone through.
 	///
 	/// # Returns
 	/// * `Ok(())` if successful
@@ -944,12 +3895,12 @@ where
 	/// let mut api_owner = Owner::new(wallet.clone());
 	/// let args = InitTxArgs {
 	/// 	src_acct_name: None,
-	/// 	amount: 2_000_000_000,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
 	/// 	minimum_confirmations: 10,
 	/// 	max_outputs: 500,
 	/// 	num_change_outputs: 1,
 	/// 	selection_strategy_is_use_all: false,
-	/// 	message: Some("Post this tx".to_owned()),
+	/// 	message: Some("Cancel this tx".to_owned()),
 	/// 	..Default::default()
 	/// };
 	/// let result = api_owner.init_send_tx(
@@ -963,50 +3914,58 @@ where
 	///		// Lock our outputs if we're happy the slate was (or is being) sent
 	///		let res = api_owner.tx_lock_outputs(None, &slate, 0);
 	///		//
-	///		// Retrieve slate back from recipient
+	///		// We didn't get the slate back, or something else went wrong
 	///		//
-	///		let res = api_owner.finalize_tx(None, &slate);
-	///		let res = api_owner.post_tx(None, &slate.tx, true);
+	///		let res = api_owner.cancel_tx(None, None, Some(slate.id.clone()), false);
 	/// }
 	/// ```
 
-	pub fn post_tx(
+	pub fn cancel_tx(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		tx: &Transaction,
-		fluff: bool,
+		tx_id: Option<u32>,
+		tx_slate_id: Option<Uuid>,
+		force: bool,
 	) -> Result<(), Error> {
-		let client = {
-			let mut w_lock = self.wallet_inst.lock();
-			let w = w_lock.lc_provider()?.wallet_inst()?;
-			// Test keychain mask, to keep API consistent
-			let _ = w.keychain(keychain_mask)?;
-			w.w2n_client().clone()
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
 		};
-		owner::post_tx(&client, tx, fluff)
+		owner::cancel_tx(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			tx_id,
+			tx_slate_id,
+			force,
+		)
 	}
 
-	/// Cancels a transaction. This entails:
-	/// * Setting the transaction status to either `TxSentCancelled` or `TxReceivedCancelled`
-	/// * Deleting all change outputs or recipient outputs associated with the transaction
-	/// * Setting the status of all assocatied inputs from `Locked` to `Spent` so they can be
-	/// used in new transactions.
+	/// Reports what [`cancel_tx`](struct.Owner.html#method.cancel_tx) would do to a
+	/// transaction without cancelling it: the inputs it would unlock, the outputs it would
+	/// delete, and whether the transaction might still confirm. A transaction whose kernel the
+	/// node already has on chain, or that was posted and may be waiting in the node's mempool,
+	/// went through or may yet go through, and cancelling it would leave the wallet out of step
+	/// with the chain.
 	///
-	/// Transactions can be cancelled by transaction log id or slate id (call with either set to
-	/// Some, not both)
+	/// A posted transaction counts as one that may confirm even if the node's pool doesn't
+	/// hold it, as other nodes' pools might. A received transaction always may, since the
+	/// sender finalizes and posts it.
 	///
 	/// # Arguments
 	///
 	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
 	/// being used.
-	/// * `tx_id` - If present, cancel by the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) id
+	/// * `tx_id` - If present, preview by the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) id
 	/// for the transaction.
 	///
-	/// * `tx_slate_id` - If present, cancel by the Slate id.
+	/// * `tx_slate_id` - If present, preview by the Slate id.
 	///
 	/// # Returns
-	/// * `Ok(())` if successful
-	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	/// * Ok([CancelPreview](../epic_wallet_libwallet/api_impl/types/struct.CancelPreview.html))
+	/// if the transaction can be cancelled
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, including when the transaction can't be cancelled at all.
 	///
 	/// # Example
 	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
@@ -1016,12 +3975,11 @@ where
 	/// let mut api_owner = Owner::new(wallet.clone());
 	/// let args = InitTxArgs {
 	/// 	src_acct_name: None,
-	/// 	amount: 2_000_000_000,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
 	/// 	minimum_confirmations: 10,
 	/// 	max_outputs: 500,
 	/// 	num_change_outputs: 1,
 	/// 	selection_strategy_is_use_all: false,
-	/// 	message: Some("Cancel this tx".to_owned()),
 	/// 	..Default::default()
 	/// };
 	/// let result = api_owner.init_send_tx(
@@ -1030,28 +3988,27 @@ where
 	/// );
 	///
 	/// if let Ok(slate) = result {
-	///		// Send slate somehow
-	///		// ...
-	///		// Lock our outputs if we're happy the slate was (or is being) sent
 	///		let res = api_owner.tx_lock_outputs(None, &slate, 0);
-	///		//
-	///		// We didn't get the slate back, or something else went wrong
-	///		//
-	///		let res = api_owner.cancel_tx(None, None, Some(slate.id.clone()));
+	///		let preview = api_owner.preview_cancel_tx(None, None, Some(slate.id.clone()));
+	///		if let Ok(p) = preview {
+	///			if !p.may_confirm {
+	///				let res = api_owner.cancel_tx(None, None, Some(slate.id.clone()), false);
+	///			}
+	///		}
 	/// }
 	/// ```
 
-	pub fn cancel_tx(
+	pub fn preview_cancel_tx(
 		&self,
 		keychain_mask: Option<&SecretKey>,
 		tx_id: Option<u32>,
 		tx_slate_id: Option<Uuid>,
-	) -> Result<(), Error> {
+	) -> Result<CancelPreview, Error> {
 		let tx = {
 			let t = self.status_tx.lock();
 			t.clone()
 		};
-		owner::cancel_tx(
+		owner::preview_cancel_tx(
 			self.wallet_inst.clone(),
 			keychain_mask,
 			&tx,
@@ -1060,6 +4017,54 @@ where
 		)
 	}
 
+	/// Cancels, in a single batch, every unconfirmed transaction sent or received by the active
+	/// account that was created more than `older_than_minutes` ago. Each is cancelled as in
+	/// [`cancel_tx`](struct.Owner.html#method.cancel_tx), releasing its locked outputs for use in
+	/// new transactions.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `older_than_minutes` - Only cancel transactions created at least this many minutes ago.
+	///
+	/// # Returns
+	/// * `Ok(Vec<TxLogEntry>)` with the cancelled
+	/// [`TxLogEntries`](../epic_wallet_libwallet/types/struct.TxLogEntry.html) if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// // Give up on anything that hasn't completed within a day
+	/// let result = api_owner.cancel_stale_txs(None, 24 * 60);
+	///
+	/// if let Ok(cancelled) = result {
+	///		// ...
+	/// }
+	/// ```
+
+	pub fn cancel_stale_txs(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		older_than_minutes: u64,
+	) -> Result<Vec<TxLogEntry>, Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		owner::cancel_stale_txs(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			older_than_minutes,
+		)
+	}
+
 	/// Retrieves the stored transaction associated with a TxLogEntry. Can be used even after the
 	/// transaction has completed.
 	///
@@ -1080,30 +4085,108 @@ where
 	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
 	///
 	/// let api_owner = Owner::new(wallet.clone());
-	/// let update_from_node = true;
-	/// let tx_id = None;
-	/// let tx_slate_id = None;
+	/// let update_from_node = true;
+	/// let tx_id = None;
+	/// let tx_slate_id = None;
+	///
+	/// // Return all TxLogEntries
+	/// let result = api_owner.retrieve_txs(None, update_from_node, tx_id, tx_slate_id);
+	///
+	/// if let Ok((was_updated, tx_log_entries)) = result {
+	///		let stored_tx = api_owner.get_stored_tx(None, &tx_log_entries[0]).unwrap();
+	///		//...
+	/// }
+	/// ```
+
+	// TODO: Should be accepting an id, not an entire entry struct
+	pub fn get_stored_tx(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		tx_log_entry: &TxLogEntry,
+	) -> Result<Option<Transaction>, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		// Test keychain mask, to keep API consistent
+		let _ = w.keychain(keychain_mask)?;
+		owner::get_stored_tx(&**w, tx_log_entry)
+	}
+
+	/// Returns everything recorded about a single transaction of the active account in one
+	/// call: its tx log entry, the outputs it spent and created with their commitments, values
+	/// and statuses, the stored transaction body, the kernel excess, the payment proof if it's
+	/// complete, and the other tx log entries recorded for the same slate. This saves stitching
+	/// together [`retrieve_txs`](struct.Owner.html#method.retrieve_txs),
+	/// [`retrieve_outputs`](struct.Owner.html#method.retrieve_outputs) and
+	/// [`get_stored_tx`](struct.Owner.html#method.get_stored_tx).
+	///
+	/// # Arguments
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `refresh_from_node` - If true, the wallet will attempt to contact
+	/// a node (via the [`NodeClient`](../epic_wallet_libwallet/types/trait.NodeClient.html)
+	/// provided during wallet instantiation). If `false`, the results will
+	/// contain transaction information that may be out-of-date (from the last time
+	/// the wallet's output set was refreshed against the node).
+	/// Note this setting is ignored if the updater process is running via a call to
+	/// [`start_updater`](struct.Owner.html#method.start_updater)
+	/// * `tx_id` - Id of the transaction log entry
+	///
+	/// # Returns
+	/// * `(bool, TxDetails)` - A tuple:
+	/// * The first `bool` element indicates whether the data was successfully
+	/// refreshed from the node (note this may be false even if the `refresh_from_node`
+	/// argument was set to `true`.
+	/// * The second element is the
+	/// [TxDetails](../epic_wallet_libwallet/api_impl/types/struct.TxDetails.html) of the transaction
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if the transaction
+	/// doesn't exist or another error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
 	///
-	/// // Return all TxLogEntries
-	/// let result = api_owner.retrieve_txs(None, update_from_node, tx_id, tx_slate_id);
+	/// let result = api_owner.get_tx_details(None, true, 0);
 	///
-	/// if let Ok((was_updated, tx_log_entries)) = result {
-	///		let stored_tx = api_owner.get_stored_tx(None, &tx_log_entries[0]).unwrap();
+	/// if let Ok((was_updated, details)) = result {
+	///		let spent = details.inputs.len();
 	///		//...
 	/// }
 	/// ```
 
-	// TODO: Should be accepting an id, not an entire entry struct
-	pub fn get_stored_tx(
+	pub fn get_tx_details(
 		&self,
 		keychain_mask: Option<&SecretKey>,
-		tx_log_entry: &TxLogEntry,
-	) -> Result<Option<Transaction>, Error> {
-		let mut w_lock = self.wallet_inst.lock();
-		let w = w_lock.lc_provider()?.wallet_inst()?;
-		// Test keychain mask, to keep API consistent
-		let _ = w.keychain(keychain_mask)?;
-		owner::get_stored_tx(&**w, tx_log_entry)
+		refresh_from_node: bool,
+		tx_id: u32,
+	) -> Result<(bool, TxDetails), Error> {
+		let tx = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let refresh_from_node = match self.updater_running.load(Ordering::Relaxed) {
+			true => false,
+			false => refresh_from_node,
+		};
+		let mut res = owner::get_tx_details(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			&tx,
+			refresh_from_node,
+			tx_id,
+		)?;
+		if self.doctest_mode {
+			res.1.tx = doctest_tx_log_entry(res.1.tx);
+			res.1.slate_history = res
+				.1
+				.slate_history
+				.into_iter()
+				.map(doctest_tx_log_entry)
+				.collect();
+		}
+		Ok(res)
 	}
 
 	/// Verifies all messages in the slate match their public keys.
@@ -1132,7 +4215,7 @@ where
 	/// let mut api_owner = Owner::new(wallet.clone());
 	/// let args = InitTxArgs {
 	/// 	src_acct_name: None,
-	/// 	amount: 2_000_000_000,
+	/// 	amount: Amount::from_freemans(2_000_000_000),
 	/// 	minimum_confirmations: 10,
 	/// 	max_outputs: 500,
 	/// 	num_change_outputs: 1,
@@ -1298,6 +4381,371 @@ where
 		Ok(res)
 	}
 
+	/// Reports the status of the wallet's configured node, along with how far the wallet's
+	/// own view of the chain lags behind it. Useful for diagnosing why a wallet's balance
+	/// isn't updating, e.g. because the node can't be reached or is still syncing, or because
+	/// the wallet hasn't been refreshed since the node's chain moved on.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with a [`NodeStatus`](../epic_wallet_libwallet/api_impl/types/struct.NodeStatus.html)
+	/// if successful. If the node couldn't be contacted, `node_reachable` will be `false` and
+	/// the node-reported fields will be empty.
+	/// `clock_skew_secs` is how far the wallet's clock is ahead of the timestamp of the node's
+	/// chain tip, and `clock_skewed` is set when that exceeds
+	/// [`CLOCK_SKEW_WARNING_SECS`](../epic_wallet_libwallet/types/constant.CLOCK_SKEW_WARNING_SECS.html)
+	/// either way, as a hint that the system clock (or the node's sync state) should be checked
+	/// before trusting displayed times and lock heights.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.node_status(None);
+	///
+	/// if let Ok(status) = result {
+	///		if let Some(lag) = status.sync_lag {
+	///			println!("Wallet is {} blocks behind the node", lag);
+	///		}
+	///		if status.clock_skewed {
+	///			println!("System clock may be wrong");
+	///		}
+	///		//...
+	/// }
+	/// ```
+
+	pub fn node_status(&self, keychain_mask: Option<&SecretKey>) -> Result<NodeStatus, Error> {
+		let mut res = {
+			let mut w_lock = self.wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			owner::node_status(&mut **w, keychain_mask)?
+		};
+		if self.doctest_mode && res.header_hash.is_some() {
+			// return a consistent hash and times for doctest
+			res.header_hash =
+				Some("d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d".to_owned());
+			res.tip_timestamp = Some(Utc.ymd(2019, 1, 15).and_hms(16, 1, 26));
+			res.clock_skew_secs = Some(12);
+			res.clock_skewed = false;
+		}
+		Ok(res)
+	}
+
+	/// Checks whether the wallet's configured node can be reached, and how long it takes to
+	/// answer. Unlike [`node_status`](struct.Owner.html#method.node_status) this makes a single
+	/// call to the node, so is suited to polling a flaky connection.
+	///
+	/// As opening a wallet doesn't contact the node, the first ping to reach it after opening
+	/// also makes the checks left over from opening: an error is returned if the node runs a
+	/// different chain (mainnet or floonet) than the wallet is configured for, and any
+	/// transaction post interrupted by an earlier crash is finished, see
+	/// [`recover_pending_posts`](struct.Owner.html#method.recover_pending_posts).
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	///
+	/// # Returns
+	/// * Ok with a [`NodePing`](../epic_wallet_libwallet/api_impl/types/struct.NodePing.html)
+	/// if successful. If the node couldn't be reached, `reachable` will be `false`, the node's
+	/// height and hash empty, and `error` will say why.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.node_ping(None);
+	///
+	/// if let Ok(ping) = result {
+	///		if !ping.reachable {
+	///			println!("Node unreachable, showing stored wallet data");
+	///		}
+	///		//...
+	/// }
+	/// ```
+
+	pub fn node_ping(&self, keychain_mask: Option<&SecretKey>) -> Result<NodePing, Error> {
+		let mut res = owner::node_ping(self.wallet_inst.clone(), keychain_mask)?;
+		if self.doctest_mode {
+			// return a consistent hash and latency for doctest
+			if res.reachable {
+				res.header_hash = Some(
+					"d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d".to_owned(),
+				);
+			}
+			res.latency_ms = 12;
+		}
+		Ok(res)
+	}
+
+	/// Looks up a transaction kernel on the chain by its excess, returning the height at
+	/// which it was included if found. If the kernel belongs to a transaction in the wallet's
+	/// transaction log that isn't yet confirmed, that transaction is marked as confirmed.
+	///
+	/// This provides a way of confirming a transaction that doesn't depend on the wallet
+	/// owning any of its outputs.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `excess` - The kernel excess to look up
+	///
+	/// # Returns
+	/// * Ok with a [`KernelStatus`](../epic_wallet_libwallet/types/struct.KernelStatus.html)
+	/// if successful. The `height` field is `None` if the kernel was not found on the chain.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.retrieve_txs(None, true, None, None);
+	///
+	/// if let Ok((_, txs)) = result {
+	///		for tx in txs {
+	///			if let Some(excess) = tx.kernel_excess {
+	///				let result = api_owner.get_kernel_status(None, &excess);
+	///			}
+	///		}
+	/// }
+	/// ```
+
+	pub fn get_kernel_status(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		excess: &pedersen::Commitment,
+	) -> Result<KernelStatus, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::get_kernel_status(&mut **w, keychain_mask, excess)
+	}
+
+	/// Waits for a transaction to reach a number of confirmations, so payments can be settled
+	/// without polling the transaction log. The transaction kernel is looked up on the chain
+	/// every [`FINALITY_POLL_INTERVAL`](constant.FINALITY_POLL_INTERVAL.html), and the call
+	/// returns as soon as the transaction is final, or is invalidated: cancelled without reaching
+	/// the chain, or dropped from the chain by a reorg after its kernel was seen there. A kernel
+	/// re-mined at another height by a reorg counts its confirmations from the new height.
+	///
+	/// If neither happens within `timeout`, the latest status is returned with a `Pending`
+	/// state, and the call can simply be made again. The wallet isn't held between checks.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `tx_id` - Id of the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// to wait on, in the active account.
+	/// * `target_confirmations` - Number of confirmations at which the transaction is final.
+	/// * `timeout` - How long to wait at most. If calling via the JSON-RPC api, this represents
+	/// milliseconds.
+	///
+	/// # Returns
+	/// * Ok with a [`TxFinality`](../epic_wallet_libwallet/api_impl/types/struct.TxFinality.html)
+	/// if successful.
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, including when the transaction has no kernel excess to look up.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// use epic_wallet_libwallet::FinalityState;
+	/// use std::time::Duration;
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.wait_tx_finality(None, 3, 10, Duration::from_secs(0));
+	///
+	/// if let Ok(finality) = result {
+	///		if finality.state == FinalityState::Final {
+	///			// settle the payment
+	///		}
+	/// }
+	/// ```
+
+	pub fn wait_tx_finality(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		tx_id: u32,
+		target_confirmations: u64,
+		timeout: Duration,
+	) -> Result<TxFinality, Error> {
+		wait_for_finality(
+			self.wallet_inst.clone(),
+			keychain_mask,
+			tx_id,
+			target_confirmations,
+			Instant::now() + timeout,
+		)
+	}
+
+	/// Watches a transaction in the background as in
+	/// [`wait_tx_finality`](struct.Owner.html#method.wait_tx_finality), and posts its
+	/// [`TxFinality`](../epic_wallet_libwallet/api_impl/types/struct.TxFinality.html) as JSON
+	/// to `callback_url` once it's final or invalidated, or with a `Pending` state once
+	/// `timeout` has passed. The callback is made once per call, as a queued job, so a failed
+	/// callback is retried as [`process_jobs`](struct.Owner.html#method.process_jobs) runs it
+	/// again.
+	///
+	/// The transaction is checked once before returning, so a transaction that can't be watched
+	/// is reported straight away.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `tx_id` - Id of the [`TxLogEntry`](../epic_wallet_libwallet/types/struct.TxLogEntry.html)
+	/// to watch, in the active account.
+	/// * `target_confirmations` - Number of confirmations at which the transaction is final.
+	/// * `callback_url` - http(s) URL the result is posted to.
+	/// * `timeout` - How long to watch at most. If calling via the JSON-RPC api, this represents
+	/// milliseconds.
+	///
+	/// # Returns
+	/// * Ok if the watch was started
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// use std::time::Duration;
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.watch_tx_finality(
+	/// 	None,
+	/// 	3,
+	/// 	10,
+	/// 	"http://localhost:8080/settled",
+	/// 	Duration::from_secs(3600),
+	/// );
+	/// ```
+
+	pub fn watch_tx_finality(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		tx_id: u32,
+		target_confirmations: u64,
+		callback_url: &str,
+		timeout: Duration,
+	) -> Result<(), Error> {
+		if !self.webhooks_enabled() {
+			return Err(ErrorKind::FinalityWatch(
+				"webhooks are not enabled".to_owned(),
+			))?;
+		}
+		if !callback_url.starts_with("http") {
+			return Err(ErrorKind::FinalityWatch(format!(
+				"callback url {} must be http or https",
+				callback_url
+			)))?;
+		}
+		{
+			let mut w_lock = self.wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			owner::tx_finality(&mut **w, keychain_mask, tx_id, target_confirmations, None)?;
+		}
+		let wallet_inst = self.wallet_inst.clone();
+		let keychain_mask = keychain_mask.cloned();
+		let url = callback_url.to_owned();
+		let deadline = Instant::now() + timeout;
+		let _ = thread::Builder::new()
+			.name("wallet-finality-watcher".to_string())
+			.spawn(move || {
+				let finality = match wait_for_finality(
+					wallet_inst.clone(),
+					(&keychain_mask).as_ref(),
+					tx_id,
+					target_confirmations,
+					deadline,
+				) {
+					Ok(f) => f,
+					Err(e) => {
+						warn!("Watching transaction {} failed: {}", tx_id, e);
+						return;
+					}
+				};
+				let body = match serde_json::to_value(&finality) {
+					Ok(b) => b,
+					Err(e) => {
+						warn!("Unable to serialize transaction {} finality: {}", tx_id, e);
+						return;
+					}
+				};
+				let action = JobAction::Webhook { url, body };
+				match queue_and_run_job(wallet_inst, (&keychain_mask).as_ref(), action) {
+					Ok(JobResult {
+						job,
+						error: Some(e),
+					}) => warn!("Finality callback failed, retried by job {}: {}", job.id, e),
+					Ok(_) => {}
+					Err(e) => warn!("Finality callback failed: {}", e),
+				}
+			})?;
+		Ok(())
+	}
+
+	/// Reports which optional subsystems this build of the wallet has, and which of them are
+	/// enabled, so clients such as GUIs can adapt to the wallet they're talking to rather than
+	/// probing for missing methods. Subsystems behind cargo features are enabled whenever
+	/// they're built in, while tor is enabled once a configuration is set with
+	/// [`set_tor_config`](struct.Owner.html#method.set_tor_config), and webhooks, built with the
+	/// `http` feature, unless disallowed with
+	/// [`set_allow_webhooks`](struct.Owner.html#method.set_allow_webhooks).
+	///
+	/// # Arguments
+	///
+	/// * None
+	///
+	/// # Returns
+	/// * Ok with the [`Capabilities`](../epic_wallet_libwallet/api_impl/types/struct.Capabilities.html)
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// let result = api_owner.get_capabilities();
+	///
+	/// if let Ok(c) = result {
+	///		if !c.tor.enabled {
+	///			// hide tor addresses
+	///		}
+	/// }
+	/// ```
+
+	pub fn get_capabilities(&self) -> Result<Capabilities, Error> {
+		let tor_enabled = self.tor_config.lock().is_some();
+		Ok(owner::get_capabilities(
+			TOR_SUPPORT,
+			tor_enabled,
+			HTTP_SUPPORT,
+			*self.allow_webhooks.lock(),
+			QR_SUPPORT,
+			ACME_SUPPORT,
+			OS_KEYSTORE_SUPPORT,
+			PKCS11_SUPPORT,
+		))
+	}
+
 	// LIFECYCLE FUNCTIONS
 
 	/// Retrieve the top-level directory for the wallet. This directory should contain the
@@ -1524,11 +4972,107 @@ where
 		)
 	}
 
+	/// Creates, or recovers, a wallet and opens it in a single call, for bootstrapping
+	/// containerized deployments without running `init` interactively. The call must present
+	/// the provisioning token set with
+	/// [`set_provisioning_token`](struct.Owner.html#method.set_provisioning_token), which is
+	/// consumed once the wallet has been created, so provisioning can only happen once.
+	///
+	/// The recovery phrase and password not supplied as arguments are read from the
+	/// `EPIC_WALLET_MNEMONIC` and `EPIC_WALLET_PASSWORD` environment variables of the wallet
+	/// process. Without a recovery phrase, a wallet with a new random seed is created.
+	///
+	/// # Arguments
+	///
+	/// * `name`: Reserved for future use, use `None` for the time being.
+	/// * `provisioning_token`: The configured provisioning token.
+	/// * `mnemonic`: Recovery phrase to restore the wallet from, if not taken from the
+	/// environment.
+	/// * `password`: The password to encrypt the `wallet.seed` file with, if not taken from the
+	/// environment.
+	///
+	/// # Returns
+	/// * Ok with the `keychain_mask` of the opened wallet if successful, as returned by
+	/// [`open_wallet`](struct.Owner.html#method.open_wallet)
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, `ProvisioningDenied` if the token is wrong or was already used.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// api_owner.set_provisioning_token(Some(ZeroingString::from("bootstrap-token")));
+	///
+	/// let pw = ZeroingString::from("my_password");
+	/// let result = api_owner.init_wallet_from_env(None, "bootstrap-token", None, Some(pw));
+	///
+	/// if let Ok(m) = result {
+	///		// use this mask in all subsequent calls
+	///		let mask = m;
+	/// }
+	/// ```
+
+	pub fn init_wallet_from_env(
+		&self,
+		name: Option<&str>,
+		provisioning_token: &str,
+		mnemonic: Option<ZeroingString>,
+		password: Option<ZeroingString>,
+	) -> Result<Option<SecretKey>, Error> {
+		let mut token = self.provisioning_token.lock();
+		let valid = match token.as_ref() {
+			None => {
+				return Err(ErrorKind::ProvisioningDenied(
+					"provisioning is not enabled, or has already been done".to_owned(),
+				)
+				.into());
+			}
+			// compare without stopping at the first difference
+			Some(t) => ct_eq(t.as_bytes(), provisioning_token.as_bytes()),
+		};
+		if !valid {
+			return Err(
+				ErrorKind::ProvisioningDenied("invalid provisioning token".to_owned()).into(),
+			);
+		}
+		let mnemonic = mnemonic.or(env::var(PROVISIONING_MNEMONIC_ENV)
+			.ok()
+			.map(ZeroingString::from));
+		let password = match password.or(env::var(PROVISIONING_PASSWORD_ENV)
+			.ok()
+			.map(ZeroingString::from))
+		{
+			Some(p) => p,
+			None => {
+				return Err(ErrorKind::ProvisioningDenied(format!(
+					"no password given, and {} is not set",
+					PROVISIONING_PASSWORD_ENV
+				))
+				.into());
+			}
+		};
+		let mnemonic_length = match mnemonic {
+			Some(_) => 0,
+			None => 32,
+		};
+		self.create_wallet(name, mnemonic, mnemonic_length, password.clone())?;
+		*token = None;
+		self.open_wallet(name, password, true)
+	}
+
 	/// `Opens` a wallet, populating the internal keychain with the encrypted seed, and optionally
 	/// returning a `keychain_mask` token to the caller to provide in all future calls.
 	/// If using a mask, the seed will be stored in-memory XORed against the `keychain_mask`, and
 	/// will not be useable if the mask is not provided.
 	///
+	/// Opening doesn't contact the node, so a wallet can be opened, and its stored history
+	/// viewed, while the node is unreachable. Checking the node runs the same chain as the wallet,
+	/// and finishing any transaction post interrupted by an earlier crash, are left to
+	/// [`node_ping`](struct.Owner.html#method.node_ping), which clients should call once the
+	/// wallet is open.
+	///
 	/// # Arguments
 	///
 	/// * `name`: Reserved for future use, use `None` for the time being.
@@ -1594,9 +5138,12 @@ where
 				.unwrap(),
 			)?));
 		}
-		let mut w_lock = self.wallet_inst.lock();
-		let lc = w_lock.lc_provider()?;
-		lc.open_wallet(name, password, use_mask, self.doctest_mode)
+		let mask = {
+			let mut w_lock = self.wallet_inst.lock();
+			let lc = w_lock.lc_provider()?;
+			lc.open_wallet(name, password, use_mask, self.doctest_mode)?
+		};
+		Ok(mask)
 	}
 
 	/// `Close` a wallet, removing the master seed from memory.
@@ -1672,6 +5219,110 @@ where
 		lc.get_mnemonic(name, password)
 	}
 
+	/// Return the BIP39 mnemonic of the open wallet, for re-verifying a backup. Unlike
+	/// [`get_mnemonic`](struct.Owner.html#method.get_mnemonic), the wallet must be open and the
+	/// caller must hold its `keychain_mask`, and the password has to be entered again even so.
+	/// This is what the Owner RPC's `get_mnemonic` uses.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `name`: Reserved for future use, use `None` for the time being.
+	/// * `password`: The password used to encrypt the seed file.
+	///
+	/// # Returns
+	/// * Ok(BIP-39 mneminc) if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as the wallet not being open, an invalid mask or a wrong password.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	///	let pw = ZeroingString::from("my_password");
+	/// let res = api_owner.reveal_mnemonic(None, None, pw);
+	///
+	/// if let Ok(mne) = res {
+	///		// ...
+	/// }
+	/// ```
+	pub fn reveal_mnemonic(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		name: Option<&str>,
+		password: ZeroingString,
+	) -> Result<ZeroingString, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let lc = w_lock.lc_provider()?;
+		{
+			// only for an open wallet, with its mask
+			let w = lc.wallet_inst()?;
+			let _ = w.keychain(keychain_mask)?;
+		}
+		lc.get_mnemonic(name, password)
+	}
+
+	/// Replaces the keychain mask of the open wallet with a new random one, without closing
+	/// the wallet. Once this returns, the old mask no longer unlocks the keychain and the
+	/// returned mask must be used in all subsequent calls, which limits how long a leaked
+	/// mask is of any use in a long-lived session.
+	///
+	/// A running [`updater`](struct.Owner.html#method.start_updater) holds on to the mask it
+	/// was started with, so it has to be stopped before rotating and started again with the
+	/// new mask afterwards.
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - The current wallet secret mask.
+	///
+	/// # Returns
+	/// * Ok(Some(SecretKey)) with the new mask if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is
+	/// encountered, such as the wallet not being open, an invalid mask, a wallet opened without
+	/// a mask or a running updater.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.rotate_keychain_mask(None);
+	///
+	/// if let Ok(m) = result {
+	///		// use the new mask from here on
+	///		let mask = m;
+	/// }
+	/// ```
+	pub fn rotate_keychain_mask(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<Option<SecretKey>, Error> {
+		if self.updater_running.load(Ordering::Relaxed) {
+			return Err(ErrorKind::GenericError(
+				"Stop the updater before rotating the keychain mask".to_owned(),
+			)
+			.into());
+		}
+		if keychain_mask.is_none() {
+			return Err(ErrorKind::GenericError(
+				"Wallet was opened without a keychain mask, there is none to rotate".to_owned(),
+			)
+			.into());
+		}
+		let mut w_lock = self.wallet_inst.lock();
+		let lc = w_lock.lc_provider()?;
+		let w = lc.wallet_inst()?;
+		// unmasks the keychain, failing for anything but the current mask
+		let k = w.keychain(keychain_mask)?;
+		w.set_keychain(Box::new(k), true, self.doctest_mode)
+	}
+
 	/// Changes a wallet's password, meaning the old seed file is decrypted with the old password,
 	/// and a new seed file is created with the same mnemonic and encrypted with the new password.
 	///
@@ -1831,6 +5482,71 @@ where
 		Ok(())
 	}
 
+	/// Starts a background wallet update thread which, rather than updating at a fixed
+	/// frequency, polls the node's chain tip at the frequency specified and only performs
+	/// the wallet update process (as described in
+	/// [`start_updater`](struct.Owner.html#method.start_updater)) when the tip has advanced
+	/// or changed since the last successful update.
+	///
+	/// As retrieving the chain tip is much cheaper than a full update, this allows a short
+	/// polling interval, so confirmations appear soon after a block is found while keeping
+	/// the load on the node low.
+	///
+	/// The thread is stopped via [`stop_updater`](struct.Owner.html#method.stop_updater).
+	///
+	/// # Arguments
+	///
+	/// * `keychain_mask` - Wallet secret mask to XOR against the stored wallet seed before using, if
+	/// being used.
+	/// * `poll_frequency`: The frequency at which to check the node's chain tip. If calling
+	/// via the JSON-RPC api, this represents milliseconds.
+	///
+	/// # Returns
+	/// * Ok if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// use std::time::Duration;
+	///
+	/// // Set up as above
+	/// # let api_owner = Owner::new(wallet.clone());
+	///
+	/// let res = api_owner.start_tip_updater(None, Duration::from_secs(5));
+	///
+	/// if let Ok(_) = res {
+	///   // ...
+	/// }
+	/// ```
+
+	pub fn start_tip_updater(
+		&self,
+		keychain_mask: Option<&SecretKey>,
+		poll_frequency: Duration,
+	) -> Result<(), Error> {
+		let updater_inner = self.updater.clone();
+		let tx_inner = {
+			let t = self.status_tx.lock();
+			t.clone()
+		};
+		let keychain_mask = match keychain_mask {
+			Some(m) => Some(m.clone()),
+			None => None,
+		};
+		let _ = thread::Builder::new()
+			.name("wallet-tip-updater".to_string())
+			.spawn(move || {
+				let u = updater_inner.lock();
+				if let Err(e) = u.run_on_tip_change(poll_frequency, keychain_mask, &tx_inner) {
+					error!("Wallet state updater failed with error: {:?}", e);
+				}
+			})?;
+		Ok(())
+	}
+
 	/// Stops the background update thread. If the updater is currently updating, the
 	/// thread will stop after the next update
 	///
@@ -1912,6 +5628,42 @@ where
 		Ok(q.split_off(index))
 	}
 
+	/// Subscribe to the wallet's events: outputs being confirmed, transactions
+	/// being cancelled, reorgs being detected and refreshes completing. Every
+	/// subscription receives all events emitted after it was made, including
+	/// those of refreshes by the updater thread, and lasts across the wallet
+	/// being closed and opened again. Dropping the receiver unsubscribes.
+	///
+	/// # Returns
+	/// * Ok with a receiver of [`WalletEvent`](../epic_wallet_libwallet/events/enum.WalletEvent.html)s
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if an error is encountered.
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// use epic_wallet_libwallet::WalletEvent;
+	///
+	/// // Set up as above
+	/// # let api_owner = Owner::new(wallet.clone());
+	///
+	/// let events = api_owner.subscribe_events().unwrap();
+	///
+	/// // e.g. from an application's event loop
+	/// while let Ok(event) = events.try_recv() {
+	/// 	if let WalletEvent::OutputConfirmed { value, .. } = event {
+	/// 		println!("Received {}", value);
+	/// 	}
+	/// }
+	/// ```
+
+	pub fn subscribe_events(&self) -> Result<Receiver<WalletEvent>, Error> {
+		let mut w_lock = self.wallet_inst.lock();
+		let lc = w_lock.lc_provider()?;
+		Ok(lc.events().subscribe())
+	}
+
 	/// Retrieve the public proof "addresses" associated with the active account at the
 	/// given derivation path.
 	///
@@ -2015,6 +5767,161 @@ where
 		address::pubkey_from_onion_v3(address_v3)
 	}
 
+	/// Checks a destination before a send is made to it, so a user can be told of a mistyped or
+	/// offline destination before committing to the send. Works out how the destination is sent
+	/// to and, for a wallet listening at an http(s) URL or a Tor onion address, asks it for its
+	/// version as a send would, reporting whether it answered, the slate versions it supports and
+	/// the one a send would use. Onion addresses are reached through a Tor process started for
+	/// the check, using the configuration given by
+	/// [`set_tor_config`](struct.Owner.html#method.set_tor_config). Slatepack addresses are
+	/// recognised, but can't be sent to yet.
+	///
+	/// # Arguments
+	///
+	/// * `dest` - The destination, an http(s) URL, an onion address with or without its
+	/// `http://` and `.onion`, or a slatepack address
+	///
+	/// # Returns
+	/// * Ok with a [`DestinationValidation`](../epic_wallet_libwallet/api_impl/types/struct.DestinationValidation.html),
+	/// whose `valid` is `false` and `error` says why if a send to the destination would fail
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if the destination
+	/// isn't any of these
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let res = api_owner.validate_destination("http://192.168.0.2:3415");
+	///
+	/// if let Ok(validation) = res {
+	///		if !validation.valid {
+	///			// warn the user, with validation.error
+	///		}
+	/// }
+	/// ```
+
+	pub fn validate_destination(&self, dest: &str) -> Result<DestinationValidation, Error> {
+		let tor_config = self.tor_config.lock().clone();
+		validate_destination(dest, tor_config)
+	}
+
+	/// Reads an `epic:` payment link, as described in the
+	/// [`payment_uri`](../epic_wallet_libwallet/payment_uri/index.html) module,
+	/// into the details needed to pay it.
+	///
+	/// # Arguments
+	///
+	/// * `uri` - The payment link, e.g.
+	/// `epic:<onion v3 address>?amount=1.5&message=thanks&proof=1`
+	///
+	/// # Returns
+	/// * Ok([`PaymentUri`](../epic_wallet_libwallet/payment_uri/struct.PaymentUri.html))
+	/// with the destination, the amount in freemans and any message or
+	/// payment proof request, if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if the link
+	/// is malformed, or has a destination the wallet can't send to
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let result = api_owner.parse_payment_uri(
+	///  "epic:2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid?amount=1.5&proof=1"
+	/// );
+	///
+	/// if let Ok(p) = result {
+	///   assert_eq!(p.amount, Some(150_000_000));
+	///   assert!(p.request_payment_proof);
+	/// }
+	/// ```
+
+	pub fn parse_payment_uri(&self, uri: &str) -> Result<PaymentUri, Error> {
+		uri.parse()
+	}
+
+	/// Reads an armored payment request, as created by the
+	/// [Foreign API's `create_payment_request`](struct.Foreign.html#method.create_payment_request),
+	/// so its amount, memo and expiry can be shown to the payer before it's paid with
+	/// [`pay_request`](struct.Owner.html#method.pay_request).
+	///
+	/// # Arguments
+	///
+	/// * `request` - The armored request, `EPICREQUEST:<data>`
+	///
+	/// # Returns
+	/// * Ok([`PaymentRequest`](../epic_wallet_libwallet/payment_request/struct.PaymentRequest.html))
+	/// if the request is well formed and signed by its proof address, whether or not it has
+	/// expired
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if it isn't
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	/// # let request = "EPICREQUEST:AE";
+	///
+	/// let result = api_owner.parse_payment_request(request);
+	///
+	/// if let Ok(r) = result {
+	///   if r.is_expired() {
+	///     // ask the payee for a new request
+	///   }
+	/// }
+	/// ```
+
+	pub fn parse_payment_request(&self, request: &str) -> Result<PaymentRequest, Error> {
+		request.parse()
+	}
+
+	/// Writes an `epic:` payment link for the given details, e.g. for a
+	/// merchant to show as a link or QR code.
+	///
+	/// # Arguments
+	///
+	/// * `payment_uri` - The [`PaymentUri`](../epic_wallet_libwallet/payment_uri/struct.PaymentUri.html)
+	/// holding the destination to be paid, with the amount in freemans and
+	/// any message or payment proof request
+	///
+	/// # Returns
+	/// * Ok(String) holding the payment link, if successful
+	/// * or [`libwallet::Error`](../epic_wallet_libwallet/struct.Error.html) if the
+	/// destination isn't an onion v3 address or http(s) endpoint, or the amount is zero
+	///
+	/// # Example
+	/// Set up as in [`new`](struct.Owner.html#method.new) method above.
+	/// ```
+	/// # epic_wallet_api::doctest_helper_setup_doc_env!(wallet, wallet_config);
+	///
+	/// use epic_wallet_libwallet::PaymentUri;
+	///
+	/// let api_owner = Owner::new(wallet.clone());
+	///
+	/// let payment_uri = PaymentUri {
+	///  dest: "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid".to_owned(),
+	///  amount: Some(150_000_000),
+	///  message: Some("order 42".to_owned()),
+	///  request_payment_proof: true,
+	/// };
+	/// let result = api_owner.create_payment_uri(&payment_uri);
+	///
+	/// if let Ok(uri) = result {
+	///   //...
+	/// }
+	/// ```
+
+	pub fn create_payment_uri(&self, payment_uri: &PaymentUri) -> Result<String, Error> {
+		payment_uri.validate()?;
+		Ok(payment_uri.to_string())
+	}
+
 	/// Returns a single, exportable [PaymentProof](../grin_wallet_libwallet/api_impl/types/struct.PaymentProof.html)
 	/// from a completed transaction within the wallet.
 	///
@@ -2142,6 +6049,116 @@ where
 	}
 }
 
+/// Replace the timestamps and block hashes of a tx log entry with fixed values,
+/// so doctest output doesn't depend on when or on which chain it was run
+fn doctest_tx_log_entry(mut t: TxLogEntry) -> TxLogEntry {
+	t.confirmation_ts = Some(Utc.ymd(2019, 1, 15).and_hms(16, 1, 26));
+	t.creation_ts = Utc.ymd(2019, 1, 15).and_hms(16, 1, 26);
+	if t.confirmation_hash.is_some() {
+		t.confirmation_hash =
+			Some("d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d".to_owned());
+	}
+	t
+}
+
+/// Do what a queued job is for and record the outcome, holding the wallet only
+/// while reading and updating it
+fn run_job<L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	job: Job,
+) -> Result<JobResult, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient,
+	K: Keychain,
+{
+	let res = match job.action {
+		JobAction::PostTx {
+			ref tx_slate_id,
+			fluff,
+		} => {
+			let tx = {
+				let mut w_lock = wallet_inst.lock();
+				let w = w_lock.lc_provider()?.wallet_inst()?;
+				owner::job_tx_to_post(&mut **w, keychain_mask, tx_slate_id)
+			};
+			match tx {
+				Ok(Some(tx)) => {
+					owner::post_and_record_tx(wallet_inst.clone(), keychain_mask, &tx, fluff)
+				}
+				Ok(None) => Ok(()),
+				Err(e) => Err(e),
+			}
+		}
+		JobAction::SendResponseSlate {
+			ref method,
+			ref dest,
+			ref slate,
+		} => send_response_slate(method, dest, &Slate::from(slate.clone())),
+		JobAction::Webhook { ref url, ref body } => post_webhook(url, body),
+	};
+	let mut w_lock = wallet_inst.lock();
+	let w = w_lock.lc_provider()?.wallet_inst()?;
+	owner::resolve_job(&mut **w, keychain_mask, job, &res)
+}
+
+/// Queue a job and run it straight away, so it's retried should it fail or
+/// be interrupted
+fn queue_and_run_job<L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	action: JobAction,
+) -> Result<JobResult, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient,
+	K: Keychain,
+{
+	let job = {
+		let mut w_lock = wallet_inst.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		owner::queue_job(&mut **w, keychain_mask, action, true)?
+	};
+	run_job(wallet_inst, keychain_mask, job)
+}
+
+/// Check a transaction every [`FINALITY_POLL_INTERVAL`](constant.FINALITY_POLL_INTERVAL.html)
+/// until it's final or invalidated, or until the deadline passes
+fn wait_for_finality<L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	tx_id: u32,
+	target_confirmations: u64,
+	deadline: Instant,
+) -> Result<TxFinality, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient,
+	K: Keychain,
+{
+	let mut seen_height = None;
+	loop {
+		let finality = {
+			let mut w_lock = wallet_inst.lock();
+			let w = w_lock.lc_provider()?.wallet_inst()?;
+			owner::tx_finality(
+				&mut **w,
+				keychain_mask,
+				tx_id,
+				target_confirmations,
+				seen_height,
+			)?
+		};
+		let now = Instant::now();
+		if finality.state != FinalityState::Pending || now >= deadline {
+			return Ok(finality);
+		}
+		seen_height = finality.kernel_height.or(seen_height);
+		thread::sleep(std::cmp::min(FINALITY_POLL_INTERVAL, deadline - now));
+	}
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! doctest_helper_setup_doc_env {
@@ -2163,7 +6180,9 @@ macro_rules! doctest_helper_setup_doc_env {
 		use api::{Foreign, Owner};
 		use config::WalletConfig;
 		use impls::{DefaultLCProvider, DefaultWalletImpl, HTTPNodeClient};
-		use libwallet::{BlockFees, InitTxArgs, IssueInvoiceTxArgs, Slate, WalletInst};
+		use libwallet::{
+			Amount, BlockFees, InitTxArgs, IssueInvoiceTxArgs, SelectionStrategy, Slate, WalletInst,
+		};
 		use uuid::Uuid;
 		let dir = tempdir().map_err(|e| format!("{:#?}", e)).unwrap();
 		let dir = dir
@@ -19,9 +19,9 @@ use crate::core::core::Transaction;
 use crate::keychain::{Identifier, Keychain};
 use crate::libwallet::slate_versions::v3::TransactionV3;
 use crate::libwallet::{
-	AcctPathMapping, ErrorKind, InitTxArgs, IssueInvoiceTxArgs, NodeClient, NodeHeightResult,
-	OutputCommitMapping, Slate, SlateVersion, TxLogEntry, VersionedSlate, WalletInfo,
-	WalletLCProvider,
+	AcctPathMapping, Amount, ErrorKind, InitTxArgs, IssueInvoiceTxArgs, NodeClient,
+	NodeHeightResult, OutputCommitMapping, Slate, SlateVersion, TxLogEntry, VersionedSlate,
+	WalletInfo, WalletLCProvider,
 };
 use crate::util::{from_hex, Mutex};
 use crate::{Owner, OwnerRpcS};
@@ -56,6 +56,7 @@ pub trait OwnerRpc: Sync + Send {
 		"result": {
 			"Ok": [
 				{
+					"frozen": false,
 					"label": "default",
 					"path": "0200000000000000000000000000000000"
 				}
@@ -163,12 +164,16 @@ pub trait OwnerRpc: Sync + Send {
 							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
 							"height": "1",
 							"is_coinbase": true,
+							"is_foundation": false,
 							"key_id": "0300000000000000000000000000000000",
 							"lock_height": "4",
 							"mmr_index": null,
 							"n_child": 0,
 							"root_key_id": "0200000000000000000000000000000000",
+							"spent_by_tx": null,
+							"spent_height": null,
 							"status": "Unspent",
+							"tags": [],
 							"tx_log_entry": 0,
 							"value": "1457920000"
 						}
@@ -179,12 +184,16 @@ pub trait OwnerRpc: Sync + Send {
 							"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
 							"height": "2",
 							"is_coinbase": true,
+							"is_foundation": false,
 							"key_id": "0300000000000000000000000100000000",
 							"lock_height": "5",
 							"mmr_index": null,
 							"n_child": 1,
 							"root_key_id": "0200000000000000000000000000000000",
+							"spent_by_tx": null,
+							"spent_height": null,
 							"status": "Unspent",
+							"tags": [],
 							"tx_log_entry": 1,
 							"value": "1457920000"
 						}
@@ -234,10 +243,19 @@ pub trait OwnerRpc: Sync + Send {
 			  "confirmation_ts": "2019-01-15T16:01:26Z",
 			  "confirmed": true,
 			  "creation_ts": "2019-01-15T16:01:26Z",
+			  "seq": 1,
 			  "fee": null,
 			  "id": 0,
 			  "kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
 			  "kernel_lookup_min_height": 1,
+			  "sender_proof_address": null,
+			  "post_state": null,
+			  "state": "Confirmed",
+			  "confirmation_height": "1",
+			  "confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+			  "pow_type": "Cuckatoo",
+			  "confirmations": "2",
+			  "deposit_tag": null,
 			  "messages": null,
 			  "num_inputs": 0,
 			  "num_outputs": 1,
@@ -254,10 +272,19 @@ pub trait OwnerRpc: Sync + Send {
 			  "confirmation_ts": "2019-01-15T16:01:26Z",
 			  "confirmed": true,
 			  "creation_ts": "2019-01-15T16:01:26Z",
+			  "seq": 2,
 			  "fee": null,
 			  "id": 1,
 			  "kernel_excess": "08bae42ff7d5fa5aca058fd0889dd1e40df16bf3ee2eea6e5db720c0a6d638a7f8",
 			  "kernel_lookup_min_height": 2,
+			  "sender_proof_address": null,
+			  "post_state": null,
+			  "state": "Confirmed",
+			  "confirmation_height": "2",
+			  "confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+			  "pow_type": "Cuckatoo",
+			  "confirmations": "1",
+			  "deposit_tag": null,
 			  "messages": null,
 			  "num_inputs": 0,
 			  "num_outputs": 1,
@@ -313,6 +340,7 @@ pub trait OwnerRpc: Sync + Send {
 					"amount_currently_spendable": "1457920000",
 					"amount_immature": "4373760000",
 					"amount_locked": "0",
+					"breakdown": null,
 					"last_confirmed_height": "4",
 					"minimum_confirmations": "1",
 					"total": "5831680000"
@@ -370,6 +398,17 @@ pub trait OwnerRpc: Sync + Send {
 		"Ok": {
 		  "amount": "60000000",
 		  "fee": "800000",
+		  "fee_breakdown": {
+			  "num_inputs": 1,
+			  "num_outputs": 2,
+			  "num_kernels": 1,
+			  "input_weight": 1,
+			  "output_weight": 4,
+			  "kernel_weight": 1,
+			  "weight": 8,
+			  "fee_base": "100000",
+			  "fee": "800000"
+		  },
 		  "height": "4",
 		  "id": "0436430c-2b02-624c-2032-570501212b00",
 		  "lock_height": "0",
@@ -597,6 +636,17 @@ pub trait OwnerRpc: Sync + Send {
 			"Ok": {
 				"amount": "60000000",
 				"fee": "800000",
+				"fee_breakdown": {
+					"num_inputs": 1,
+					"num_outputs": 2,
+					"num_kernels": 1,
+					"input_weight": 1,
+					"output_weight": 4,
+					"kernel_weight": 1,
+					"weight": 8,
+					"fee_base": "100000",
+					"fee": "800000"
+				},
 				"height": "4",
 				"id": "0436430c-2b02-624c-2032-570501212b00",
 				"lock_height": "0",
@@ -1360,7 +1410,7 @@ where
 	}
 
 	fn cancel_tx(&self, tx_id: Option<u32>, tx_slate_id: Option<Uuid>) -> Result<(), ErrorKind> {
-		Owner::cancel_tx(self, None, tx_id, tx_slate_id).map_err(|e| e.kind())
+		Owner::cancel_tx(self, None, tx_id, tx_slate_id, false).map_err(|e| e.kind())
 	}
 
 	fn get_stored_tx(&self, tx: &TxLogEntry) -> Result<Option<TransactionV3>, ErrorKind> {
@@ -1387,6 +1437,7 @@ where
 }
 
 /// helper to set up a real environment to run integrated doctests
+#[cfg(feature = "doctest")]
 pub fn run_doctest_owner(
 	request: serde_json::Value,
 	test_dir: &str,
@@ -1523,6 +1574,7 @@ pub fn run_doctest_owner(
 			&None,
 			true,
 			1,
+			false,
 		)
 		.unwrap();
 		assert!(wallet_refreshed);
@@ -1548,7 +1600,7 @@ pub fn run_doctest_owner(
 		};
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount,
+			amount: Amount::from_freemans(amount),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -1569,6 +1621,7 @@ pub fn run_doctest_owner(
 				&slate,
 				None,
 				None,
+				None,
 				true,
 			)
 			.unwrap();
@@ -13,24 +13,32 @@
 // limitations under the License.
 
 //! JSON-RPC Stub generation for the Owner API
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::config::{TorConfig, WalletConfig};
+use crate::core::core::feijoada::PoWType;
 use crate::core::core::Transaction;
 use crate::core::global;
 use crate::keychain::{Identifier, Keychain};
 use crate::libwallet::slate_versions::v3::TransactionV3;
 use crate::libwallet::{
-	AcctPathMapping, ErrorKind, InitTxArgs, IssueInvoiceTxArgs, NodeClient, NodeHeightResult,
-	OutputCommitMapping, PaymentProof, Slate, SlateVersion, StatusMessage, TxLogEntry,
-	VersionedSlate, WalletInfo, WalletLCProvider,
+	AccountKeyExport, AcctPathMapping, AuditEntry, BalanceHistoryEntry, CancelPreview,
+	Capabilities, CleanedCoinbase, CsvImport, Denomination, DestinationValidation, ErrorKind,
+	ExpectedCommitment, InitTxArgs, IssueInvoiceTxArgs, Job, JobResult, KernelStatus, LockedOutput,
+	MiningRewardSummary, NodeClient, NodeHeightResult, NodePing, NodeStatus, OutputCommitMapping,
+	OutputData, OutputLineage, OutputPage, OutputStatus, PaymentProof, PaymentRequest, PaymentUri,
+	QueuedSend, QueuedSendResult, Reservation, RewoundOutput, ShardBalance, Slate, SlateVersion,
+	StatusMessage, TxDetails, TxFilter, TxFinality, TxLogEntry, TxLogPage, TxTemplate,
+	VersionedSlate, WalletInfo, WalletLCProvider, WalletStateExport, WalletStateImport,
 };
 use crate::util::logger::LoggingConfig;
 use crate::util::secp::key::{PublicKey, SecretKey};
-use crate::util::{static_secp_instance, ZeroingString};
+use crate::util::secp::pedersen;
+use crate::util::{from_hex, static_secp_instance, ZeroingString};
 use crate::{ECDHPubkey, Owner, PubAddress, Token};
 use easy_jsonrpc_mw;
-use rand::thread_rng;
+use epic_wallet_util::random_secret_key;
 use std::time::Duration;
 
 /// Public definition used to generate Owner jsonrpc api.
@@ -64,6 +72,7 @@ pub trait OwnerRpcS {
 		"result": {
 			"Ok": [
 				{
+					"frozen": false,
 					"label": "default",
 					"path": "0200000000000000000000000000000000"
 				}
@@ -144,7 +153,7 @@ pub trait OwnerRpcS {
 	fn set_active_account(&self, token: Token, label: &String) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::retrieve_outputs](struct.Owner.html#method.retrieve_outputs).
+	Networked version of [Owner::freeze_account](struct.Owner.html#method.freeze_account).
 
 	# Json rpc example
 
@@ -153,12 +162,11 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "retrieve_outputs",
+		"method": "freeze_account",
 		"params": {
 			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"include_spent": false,
-			"refresh_from_node": true,
-			"tx_id": null
+			"label": "default",
+			"reason": "compliance hold"
 		},
 		"id": 1
 	}
@@ -169,145 +177,69 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": [
-				true,
-				[
-					{
-						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
-						"output": {
-							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
-							"height": "1",
-							"is_coinbase": true,
-							"key_id": "0300000000000000000000000000000000",
-							"lock_height": "4",
-							"mmr_index": null,
-							"n_child": 0,
-							"root_key_id": "0200000000000000000000000000000000",
-							"status": "Unspent",
-							"tx_log_entry": 0,
-							"value": "1457920000"
-						}
-					},
-					{
-						"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
-						"output": {
-							"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
-							"height": "2",
-							"is_coinbase": true,
-							"key_id": "0300000000000000000000000100000000",
-							"lock_height": "5",
-							"mmr_index": null,
-							"n_child": 1,
-							"root_key_id": "0200000000000000000000000000000000",
-							"status": "Unspent",
-							"tx_log_entry": 1,
-							"value": "1457920000"
-						}
-					}
-				]
-			]
+			"Ok": {
+				"frozen": true,
+				"label": "default",
+				"path": "0200000000000000000000000000000000"
+			}
 		}
 	}
 	# "#
-	# , true, 2, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
-	fn retrieve_outputs(
+
+	fn freeze_account(
 		&self,
 		token: Token,
-		include_spent: bool,
-		refresh_from_node: bool,
-		tx_id: Option<u32>,
-	) -> Result<(bool, Vec<OutputCommitMapping>), ErrorKind>;
+		label: String,
+		reason: String,
+	) -> Result<AcctPathMapping, ErrorKind>;
 
 	/**
-	Networked version of [Owner::retrieve_txs](struct.Owner.html#method.retrieve_txs).
+	Networked version of [Owner::unfreeze_account](struct.Owner.html#method.unfreeze_account).
 
 	# Json rpc example
 
 	```
-		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
-		# r#"
-		{
-			"jsonrpc": "2.0",
-			"method": "retrieve_txs",
-			"params": {
-				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-				"refresh_from_node": true,
-				"tx_id": null,
-				"tx_slate_id": null
-			},
-			"id": 1
-		}
-		# "#
-		# ,
-		# r#"
-		{
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "unfreeze_account",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"label": "default",
+			"reason": "hold lifted"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
 		"id": 1,
 		"jsonrpc": "2.0",
-	  "result": {
-		"Ok": [
-		  true,
-		  [
-			{
-			  "amount_credited": "1457920000",
-			  "amount_debited": "0",
-			  "confirmation_ts": "2019-01-15T16:01:26Z",
-			  "confirmed": true,
-			  "creation_ts": "2019-01-15T16:01:26Z",
-			  "fee": null,
-			  "id": 0,
-			  "kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
-			  "kernel_lookup_min_height": 1,
-			  "messages": null,
-			  "num_inputs": 0,
-			  "num_outputs": 1,
-			  "parent_key_id": "0200000000000000000000000000000000",
-			  "stored_tx": null,
-			  "ttl_cutoff_height": null,
-			  "tx_slate_id": null,
-			  "payment_proof": null,
-			  "tx_type": "ConfirmedCoinbase"
-			},
-			{
-			  "amount_credited": "1457920000",
-			  "amount_debited": "0",
-			  "confirmation_ts": "2019-01-15T16:01:26Z",
-			  "confirmed": true,
-			  "creation_ts": "2019-01-15T16:01:26Z",
-			  "fee": null,
-			  "id": 1,
-			  "kernel_excess": "08bae42ff7d5fa5aca058fd0889dd1e40df16bf3ee2eea6e5db720c0a6d638a7f8",
-			  "kernel_lookup_min_height": 2,
-			  "messages": null,
-			  "num_inputs": 0,
-			  "num_outputs": 1,
-			  "parent_key_id": "0200000000000000000000000000000000",
-			  "stored_tx": null,
-			  "ttl_cutoff_height": null,
-			  "payment_proof": null,
-			  "tx_slate_id": null,
-			  "tx_type": "ConfirmedCoinbase"
+		"result": {
+			"Err": {
+				"AccountFreeze": "account 'default' is already unfrozen"
 			}
-		  ]
-		]
-	  }
+		}
 	}
 	# "#
-	# , true, 2, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn retrieve_txs(
+	fn unfreeze_account(
 		&self,
 		token: Token,
-		refresh_from_node: bool,
-		tx_id: Option<u32>,
-		tx_slate_id: Option<Uuid>,
-	) -> Result<(bool, Vec<TxLogEntry>), ErrorKind>;
+		label: String,
+		reason: String,
+	) -> Result<AcctPathMapping, ErrorKind>;
 
 	/**
-	Networked version of [Owner::retrieve_summary_info](struct.Owner.html#method.retrieve_summary_info).
+	Networked version of [Owner::export_account_key](struct.Owner.html#method.export_account_key).
 
 	# Json rpc example
 
@@ -316,11 +248,11 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "retrieve_summary_info",
+		"method": "export_account_key",
 		"params": {
 			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"refresh_from_node": true,
-			"minimum_confirmations": 1
+			"label": "default",
+			"accept_warning": false
 		},
 		"id": 1
 	}
@@ -328,392 +260,3630 @@ pub trait OwnerRpcS {
 	# ,
 	# r#"
 	{
-	"id": 1,
+		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": [
-				true,
-				{
-					"amount_awaiting_confirmation": "0",
-					"amount_awaiting_finalization": "0",
-					"amount_currently_spendable": "1457920000",
-					"amount_immature": "4373760000",
-					"amount_locked": "0",
-					"last_confirmed_height": "4",
-					"minimum_confirmations": "1",
-					"total": "5831680000"
-				}
+			"Err": {
+				"AccountKeyExport": "the export lets anyone holding it identify and link every output of the account, only share it with a trusted auditor and pass accept_warning to go ahead"
+			}
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
 
-			]
+	fn export_account_key(
+		&self,
+		token: Token,
+		label: String,
+		accept_warning: bool,
+	) -> Result<AccountKeyExport, ErrorKind>;
+
+	/**
+	Networked version of [Owner::derive_expected_commitments](struct.Owner.html#method.derive_expected_commitments).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "derive_expected_commitments",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"label": "default",
+			"from_index": 0,
+			"count": 1001,
+			"values": [1457920000],
+			"accept_warning": true
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"AccountKeyExport": "at most 1000 commitments can be derived at once"
+			}
 		}
 	}
 	# "#
-	# ,true, 4, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
-	 */
+	*/
 
-	fn retrieve_summary_info(
+	fn derive_expected_commitments(
 		&self,
 		token: Token,
-		refresh_from_node: bool,
-		minimum_confirmations: u64,
-	) -> Result<(bool, WalletInfo), ErrorKind>;
+		label: String,
+		from_index: u32,
+		count: u32,
+		values: Vec<u64>,
+		accept_warning: bool,
+	) -> Result<Vec<ExpectedCommitment>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::init_send_tx](struct.Owner.html#method.init_send_tx).
+	Networked version of [Owner::rewind_rangeproof](struct.Owner.html#method.rewind_rangeproof).
 
 	# Json rpc example
 
 	```
-		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
-		# r#"
-		{
-			"jsonrpc": "2.0",
-			"method": "init_send_tx",
-			"params": {
-				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-				"args": {
-					"src_acct_name": null,
-					"amount": "60000000",
-					"minimum_confirmations": 2,
-					"max_outputs": 500,
-					"num_change_outputs": 1,
-					"selection_strategy_is_use_all": true,
-					"message": "my message",
-					"target_slate_version": null,
-					"payment_proof_recipient_address": "d03c09e9c19bb74aa9ea44e0fe5ae237a9bf40bddf0941064a80913a4459c8bb",
-					"ttl_blocks": null,
-					"send_args": null
-				}
-			},
-			"id": 1
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "rewind_rangeproof",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+			"proof": "not a proof"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"RangeProofRewind": "proof isn't valid hex"
+			}
 		}
-		# "#
-		# ,
-		# r#"
-		{
-	  "id": 1,
-	  "jsonrpc": "2.0",
-	  "result": {
-		"Ok": {
-		  "amount": "60000000",
-		  "fee": "800000",
-		  "height": "4",
-		  "id": "0436430c-2b02-624c-2032-570501212b00",
-		  "lock_height": "0",
-		  "num_participants": 2,
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
 
-		  "participant_data": [
-			{
-			  "id": "0",
-			  "message": "my message",
-			  "message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bea065fb74c27c31d611427ac5fa1459d1db340d7475e2967f19e2fa95687d88c",
-			  "part_sig": null,
-			  "public_blind_excess": "039fbac4782fa1600aa704c38073eece85e3a085a90446ded19a9fec90e432b330",
-			  "public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+	fn rewind_rangeproof(
+		&self,
+		token: Token,
+		commit: String,
+		proof: String,
+	) -> Result<RewoundOutput, ErrorKind>;
+
+	/**
+	Networked version of [Owner::import_output](struct.Owner.html#method.import_output).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "import_output",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"key_path": "m/0/0",
+			"value": 1457920000,
+			"height": 1
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"ImportOutput": "key path m/0/0 isn't an output's, such as m/0/0/4"
 			}
-		  ],
-		  "payment_proof": {
-			  "receiver_address": "d03c09e9c19bb74aa9ea44e0fe5ae237a9bf40bddf0941064a80913a4459c8bb",
-			  "receiver_signature": null,
-			  "sender_address": "32cdd63928854f8b2628b1dce4626ddcdf35d56cb7cfdf7d64cca5822b78d4d3"
-			},
-			"ttl_cutoff_height": null,
-		  "tx": {
-			"body": {
-			"inputs": [
-			  {
-				"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
-				"features": "Coinbase"
-			  }
-			],
-			"kernels": [
-			  {
-				"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-				"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-				"features": "Plain",
-				"fee": "800000",
-				"lock_height": "0"
-			  }
-			],
-			"outputs": [
-			  {
-				"commit": "0832ca73c2049ee0c8f555c6297aa3658eb3f8ce711dfd63d6d5234cf3191c7756",
-				"features": "Plain",
-				"proof": "1393386202b2ba345e131efb1dfc3730c000ee6b6f3cb8d56e3d0680796b11940abeaed75e18458b544d28765b530a7ca2ed170b42c8bd5e07d4451b0cfa59c20fee43c9107091a328a9f745a255702ea17ac50f5e92b2daf51a7851e8ffde5e11a6837b5b6058aee5ab1e68bcd756646f36b38c6262aa20ff9c194d2e3e155b608216186fee66ef8ca269d31a015c63cca3cf5f0d40e4cccd13d390ed1aeed7a7c7d0709e9b509844462098d97e983ba6963ee3a68955d5317ecec47caeb8911784598be71d319ba8441c36388bf4a10ba2538f4f54f648b22a8f1e1f713dec36376936100b0fc5fb5ad4f51811ec96a76b564c3ee08305f5a2ad79a80152a03eb86d4dcd854a23818621d80771ceb169e45a692b45db77667beecd545e08b8afe8f8a3d049ae18e1cee5522769cd6b0131e036ee81d70df4cd679959fd82684bf9e1f4784325ef271eec2fb73ef4a9569fd76f7b1e55d8e2e87a5daa5ad5357cb401af13c2c695afc4b6a8a2004da1b0f5ebe7cb70cb2e15f0f3ca41eaca969abcb452f7a15fe9d004e66ff646e423366713632f1dedcb33bac1abbc47f1cf2b280f04cf85a7291bb4ecb2c1c252d65e933f5819ba4984b1018ec11ae36d2445af56900b9b6e746f84ddd6b06baab9d7c8f82f0b0bc7a61ade6eabe762ac0d3afe4b2102518361a9e54a4d9d51e4a25ccf1d40c36f6444d2271d03d91eb0f1f6895345c8758a7375926cf0ab75212ef7b4a0efa59a31decd995be2933e3da51efec22365521b8942f997789f9618cbbb422607c2414fc64bc558eca27df5fe7156954b98335a5cc63e6bfe7e076149c93e2314dd626f48bf6721b506b81962b6ca81bff28c7e216f49fcbf989045f97452f3b4ccdcaa7ca5a4ce0bd3f5e16440c6c0b73a42bfa6cfe8e31265b73b81b81c2d54e4f7aefb16ebfa1273adbfd57c08a6"
-			  }
-			]
-			},
-			"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-		  },
-		  "version_info": {
-				"orig_version": 3,
-				"version": 3,
-				"block_header_version": 6
-		  }
 		}
-	  }
 	}
-		# "#
-		# ,true, 4, false, false, false, false);
+	# "#
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn init_send_tx(&self, token: Token, args: InitTxArgs) -> Result<VersionedSlate, ErrorKind>;
+	fn import_output(
+		&self,
+		token: Token,
+		key_path: String,
+		value: u64,
+		height: u64,
+	) -> Result<OutputData, ErrorKind>;
 
 	/**
-	Networked version of [Owner::issue_invoice_tx](struct.Owner.html#method.issue_invoice_tx).
+	Networked version of [Owner::export_wallet_state](struct.Owner.html#method.export_wallet_state).
 
 	# Json rpc example
 
 	```
-		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
-		# r#"
-		{
-			"jsonrpc": "2.0",
-			"method": "issue_invoice_tx",
-			"params": {
-				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-				"args": {
-					"amount": "60000000",
-					"message": "Please give me your epics",
-					"dest_acct_name": null,
-					"target_slate_version": null
-				}
-			},
-			"id": 1
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "export_wallet_state",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"StateImport": "the wallet hasn't been scanned against the chain yet"
+			}
 		}
-		# "#
-		# ,
-		# r#"
-		{
-			"id": 1,
-			"jsonrpc": "2.0",
-			"result": {
-				"Ok": {
-					"amount": "60000000",
-					"fee": "0",
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn export_wallet_state(&self, token: Token) -> Result<WalletStateExport, ErrorKind>;
+
+	/**
+	Networked version of [Owner::import_wallet_state](struct.Owner.html#method.import_wallet_state).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "import_wallet_state",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"export": {
+				"state": {
+					"version": 2,
+					"created_at": "2026-01-01T00:00:00Z",
 					"height": "4",
-					"id": "0436430c-2b02-624c-2032-570501212b00",
-					"lock_height": "0",
-					"ttl_cutoff_height": null,
-					"num_participants": 2,
-					"payment_proof": null,
-					"participant_data": [
-						{
-							"id": "1",
-							"message": "Please give me your epics",
-							"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bb06e3894e0db51e6015d2181f101d06722094128dbc316f7186b57edd68731cb",
-							"part_sig": null,
-							"public_blind_excess": "035ca9d2d82e0e31bc2add7ef9200066b257d9f72cb16c0e57455277b90e2b3503",
-							"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
-						}
-					],
-					"tx": {
-						"body": {
-							"inputs": [],
-							"kernels": [
-								{
-									"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-									"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-									"features": "Plain",
-									"fee": "0",
-									"lock_height": "0"
-								}
-							],
-							"outputs": [
-								{
-									"commit": "083fe68fb96a0941f70e9412b4483621326abee9eccf10e7a7efef2a9b4e97df25",
-									"features": "Plain",
-									"proof": "2df8ba188baa701a7c07e23de108e8318797ba029319b4557db3c1c8af917f6361d9871520e33423131d5771b06566aba2469f1e9fcc8eda8203a5c241759e45041af26939c57946372bf330d09670eb30a08cae27b3015dac2efb518fbe2325487d8417f4014cb854aec9e4e1770d2f5d7a6e32e2bc904245505ebd952eda10b5c401f315a8cb969da4cc2dc1e656f33d870ca07ffbc45f58cae9f28b836d4bf3c1786b805ba9d8789cc211998981a8b4115aa7383ddbd10e656fc0a590c3e2cf8dd07e414217d9a1d1af32bcbd933448d0a89033cb93ba2eb0d3b973136d61ee7f109d0476ed3475b7328eff5e9d3362b5db4621682a443a382f7ef09304f9bff422885d23f62f9d7d1a9bbf888e5ba5678e347182770cbbf41cfb3002269607f085881ce0f0df01f34f34433ef04dd6008f9a0c13e47e6e386d62151386dfd20bdf812ae2fb580edc38f38bfc9cc543d1023889ee646d4e75a7382caa3bb00b970062ffdc1fc643ce56d25e2e73b556162c8441d5a667b36b840cc244f69395b46900dd1edc562ed741c239804588e94c071b621766b55f738802c376012fa577e0d82bdf7bf2f229a867d91ed177bacde44faadb6901066f84e21a5fb0b73ed7ef9ef4a1e2c65e6a28a0ae834a99ed1694889d885fc8e90c8e7507078603a9705cc3c57b8b0125ad385cb5ec564f9ca69b530307d91ef2c6bb49a39e30d9e68f2f67d99915d87d1a7776f4c0b61913ea661ebe320b8e99919c69d9dbdc527e787d46e772da9ab9f9cc60e43b41fb0981b6b882ed7a535451158c711210fe25e68d12719192c3d332aea9e047a0f7a292b8e6f13fd76ed47afbadf070392cc3f4a4ebb8ec9853587e30ad9b9794717c87bf962e2ab99ec543f5a24efda0cfc2bf51f23c8132aee6058189925febe1d9d3a145f580ef9835db3c1f3b6e97bd36331e"
-								}
-							]
-						},
-						"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-					},
-					"version_info": {
-						"orig_version": 3,
-						"version": 3,
-						"block_header_version": 6
-					}
-				}
+					"header_hash": "0000000000000000000000000000000000000000000000000000000000000000",
+					"accounts": [],
+					"outputs": []
+				},
+				"proof_address": "32cdd63928854f8b2628b1dce4626ddcdf35d56cb7cfdf7d64cca5822b78d4d3",
+				"signature": "b9ac5e18fd13ce72923cc47796bd5af09b5247c52da3634c9b934d4e111a43f53f1c55e3f3be36a79450e18f8989d81a0c21c4b2c16c208753a9971a5ffee406"
+			}
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"StateImport": "the export's version isn't supported"
 			}
 		}
-		# "#
-		# ,true, 4, false, false, false, false);
+	}
+	# "#
+	# , true, 0, false, false, false, false);
 	```
 	*/
 
-	fn issue_invoice_tx(
+	fn import_wallet_state(
 		&self,
 		token: Token,
-		args: IssueInvoiceTxArgs,
-	) -> Result<VersionedSlate, ErrorKind>;
+		export: WalletStateExport,
+	) -> Result<WalletStateImport, ErrorKind>;
 
 	/**
-	Networked version of [Owner::process_invoice_tx](struct.Owner.html#method.process_invoice_tx).
+	Networked version of [Owner::import_outputs_csv](struct.Owner.html#method.import_outputs_csv).
 
 	# Json rpc example
 
 	```
-		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
-		# r#"
-		{
-			"jsonrpc": "2.0",
-			"method": "process_invoice_tx",
-			"params": {
-				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-				"slate": {
-					"amount": "60000000",
-					"fee": "0",
-					"height": "4",
-					"id": "0436430c-2b02-624c-2032-570501212b00",
-					"lock_height": "0",
-					"ttl_cutoff_height": null,
-					"num_participants": 2,
-					"payment_proof": null,
-					"participant_data": [
-						{
-							"id": "1",
-							"message": "Please give me your epics",
-							"message_sig": "1b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fd2599ab38942986602e943f684a85992893a6d34367dc7cc2b403a5dcfcdbcd9",
-							"part_sig": null,
-							"public_blind_excess": "028e95921cc0d5be5922362265d352c9bdabe51a9e1502a3f0d4a10387f1893f40",
-							"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
-						}
-					],
-					"tx": {
-						"body": {
-							"inputs": [],
-							"kernels": [
-								{
-									"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-									"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-									"features": "Plain",
-									"fee": "0",
-									"lock_height": "0"
-								}
-							],
-							"outputs": [
-								{
-									"commit": "09cf47204446c326e361a1a92f34b174deff732daaedb80d7339fbe3db5ca2f6ba",
-									"features": "Plain",
-									"proof": "8f511614315626b5f39224482351d766f5a8ef136262befc050d839be8479b0a13470cd88f4436346d213d83847a4055c6e0ac63681556470349a1aab47034a3015eb64d8163955998e2dd4165dd24386b1e279974b05deb5d46ba2bc321f7000c0784f8f10690605ffe717119d045e02b141ed12d8fc6d20930483a8af889ef533495eb442fcff36d98ebc104f13fc645c28431b3296e4a11f7c991ff97f9abbc2f8886762d7f29fdacb31d52c6850e6ccf5386117d89e8ea4ca3071c56c218dd5d3bcd65f6c06ed9f51f848507ca1d594f41796d1cf99f68a5c3f0c5dd9873602284cff31269b102fcc6c68607565faaf0adb04ed4ff3ea5d41f3b5235ac6cb90e4046c808c9c48c27172c891b20085c56a99913ef47fd8b3dc4920cef50534b9319a7cefe0df10a0206a634ac837e11da92df83ff58b1a14de81313400988aa48b946fcbe1b81f0e79e13f7c6c639b1c10983b424bda08d0ce593a20f1f47e0aa01473e7144f116b76d9ebc60599053d8f1542d60747793d99064e51fce8f8866390325d48d6e8e3bbdbc1822c864303451525c6cb4c6902f105a70134186fb32110d8192fc2528a9483fc8a4001f4bdeab1dd7b3d1ccb9ae2e746a78013ef74043f0b2436f0ca49627af1768b7c791c669bd331fd18c16ef88ad0a29861db70f2f76f3e74fde5accb91b73573e31333333223693d6fbc786e740c085e4fc6e7bde0a3f54e9703f816c54f012d3b1f41ec4d253d9337af61e7f1f1383bd929421ac346e3d2771dfee0b60503b33938e7c83eb37af3b6bf66041a3519a2b4cb557b34e3b9afcf95524f9a011425a34d32e7b6e9f255291094930acae26e8f7a1e4e6bc405d0f88e919f354f3ba85356a34f1aba5f7da1fad88e2692f4129cc1fb80a2122b2d996c6ccf7f08d8248e511d92af9ce49039de728848a2dc74101f4e94a"
-								}
-							]
-						},
-						"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-					},
-					"version_info": {
-						"orig_version": 3,
-						"version": 3,
-						"block_header_version": 6
-					}
-				},
-				"args": {
-					"src_acct_name": null,
-					"amount": "0",
-					"minimum_confirmations": 2,
-					"max_outputs": 500,
-					"num_change_outputs": 1,
-					"selection_strategy_is_use_all": true,
-					"message": "Ok, here are your epics",
-					"target_slate_version": null,
-					"payment_proof_recipient_address": null,
-					"ttl_blocks": null,
-					"send_args": null
-				}
-			},
-			"id": 1
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "import_outputs_csv",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"csv": "Block Height,Value\n5,14.5792\n",
+			"units": "epic"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"CsvImport": "outputs need a commitment column, or key id and value columns"
+			}
 		}
-		# "#
-		# ,
-		# r#"
-		{
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn import_outputs_csv(
+		&self,
+		token: Token,
+		csv: String,
+		units: Denomination,
+	) -> Result<CsvImport<OutputData>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::import_txs_csv](struct.Owner.html#method.import_txs_csv).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "import_txs_csv",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"csv": "Type,Creation Time,Confirmed?,Amount Credited,Amount Debited\nReceived Tx,2020-01-02 03:04:05,false,1.5,0.0\nMined,2020-01-02 03:04:05,true,1.5,0.0\n",
+			"units": "epic"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
 			"Ok": {
-				"amount": "60000000",
-				"fee": "800000",
-				"height": "4",
-				"id": "0436430c-2b02-624c-2032-570501212b00",
-				"lock_height": "0",
-				"ttl_cutoff_height": null,
-				"num_participants": 2,
-				"payment_proof": null,
-				"participant_data": [
+				"imported": [],
+				"skipped": [
 					{
-						"id": "1",
-						"message": "Please give me your epics",
-						"message_sig": "1b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fd2599ab38942986602e943f684a85992893a6d34367dc7cc2b403a5dcfcdbcd9",
-						"part_sig": null,
-						"public_blind_excess": "028e95921cc0d5be5922362265d352c9bdabe51a9e1502a3f0d4a10387f1893f40",
-						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+						"line": 2,
+						"reason": "unconfirmed transactions aren't imported, finish or cancel it first"
 					},
 					{
-						"id": "0",
-						"message": "Ok, here are your epics",
-						"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bec8c1cac6cb5770a3c62c9bb95063581cc08bfccd72dac72be8ec4ba5374a9f3",
-						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bcec20532cbe7ce0a3152b61566785684fea3534b7f834f02f733fa524123ee54",
-						"public_blind_excess": "02802124f21ba02769a3f05ecfe9662e8783fa0bd1a7b7d63cf3aea0ebc0d7af3a",
-						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+						"line": 3,
+						"reason": "'Mined' isn't a transaction type"
 					}
-				],
-				"tx": {
-					"body": {
-						"inputs": [
+				]
+			}
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn import_txs_csv(
+		&self,
+		token: Token,
+		csv: String,
+		units: Denomination,
+	) -> Result<CsvImport<TxLogEntry>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_outputs](struct.Owner.html#method.retrieve_outputs).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_outputs",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"include_spent": false,
+			"refresh_from_node": true,
+			"tx_id": null
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[
+					{
+						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+						"output": {
+							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+							"height": "1",
+							"is_coinbase": true,
+							"is_foundation": false,
+							"key_id": "0300000000000000000000000000000000",
+							"lock_height": "4",
+							"mmr_index": null,
+							"n_child": 0,
+							"root_key_id": "0200000000000000000000000000000000",
+							"spent_by_tx": null,
+							"spent_height": null,
+							"status": "Unspent",
+							"tags": [],
+							"tx_log_entry": 0,
+							"value": "1457920000"
+						}
+					},
+					{
+						"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
+						"output": {
+							"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
+							"height": "2",
+							"is_coinbase": true,
+							"is_foundation": false,
+							"key_id": "0300000000000000000000000100000000",
+							"lock_height": "5",
+							"mmr_index": null,
+							"n_child": 1,
+							"root_key_id": "0200000000000000000000000000000000",
+							"spent_by_tx": null,
+							"spent_height": null,
+							"status": "Unspent",
+							"tags": [],
+							"tx_log_entry": 1,
+							"value": "1457920000"
+						}
+					}
+				]
+			]
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+	fn retrieve_outputs(
+		&self,
+		token: Token,
+		include_spent: bool,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+	) -> Result<(bool, Vec<OutputCommitMapping>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_outputs_page](struct.Owner.html#method.retrieve_outputs_page).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_outputs_page",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"include_spent": false,
+			"refresh_from_node": true,
+			"cursor": null,
+			"limit": 1
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				{
+					"next_cursor": "6f3a0300000000000000000000000000000000",
+					"outputs": [
+						{
+							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+							"output": {
+								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+								"height": "1",
+								"is_coinbase": true,
+								"is_foundation": false,
+								"key_id": "0300000000000000000000000000000000",
+								"lock_height": "4",
+								"mmr_index": null,
+								"n_child": 0,
+								"root_key_id": "0200000000000000000000000000000000",
+								"spent_by_tx": null,
+								"spent_height": null,
+								"status": "Unspent",
+								"tags": [],
+								"tx_log_entry": 0,
+								"value": "1457920000"
+							}
+						}
+					]
+				}
+			]
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+	fn retrieve_outputs_page(
+		&self,
+		token: Token,
+		include_spent: bool,
+		refresh_from_node: bool,
+		cursor: Option<String>,
+		limit: usize,
+	) -> Result<(bool, OutputPage), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_output_lineage](struct.Owner.html#method.retrieve_output_lineage).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_output_lineage",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"tx_id": null
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[
+					{
+						"history": [
 							{
 								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
-								"features": "Coinbase"
+								"height": "1",
+								"is_coinbase": true,
+								"is_foundation": false,
+								"key_id": "0300000000000000000000000000000000",
+								"lock_height": "4",
+								"mmr_index": null,
+								"n_child": 0,
+								"root_key_id": "0200000000000000000000000000000000",
+								"spent_by_tx": null,
+								"spent_height": null,
+								"status": "Unconfirmed",
+								"tags": [],
+								"tx_log_entry": null,
+								"value": "1457920000"
 							}
 						],
-						"kernels": [
-							{
-								"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-								"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-								"features": "Plain",
-								"fee": "800000",
-								"lock_height": "0"
-							}
-						],
-						"outputs": [
-							{
-								"commit": "0832ca73c2049ee0c8f555c6297aa3658eb3f8ce711dfd63d6d5234cf3191c7756",
-								"features": "Plain",
-								"proof": "1393386202b2ba345e131efb1dfc3730c000ee6b6f3cb8d56e3d0680796b11940abeaed75e18458b544d28765b530a7ca2ed170b42c8bd5e07d4451b0cfa59c20fee43c9107091a328a9f745a255702ea17ac50f5e92b2daf51a7851e8ffde5e11a6837b5b6058aee5ab1e68bcd756646f36b38c6262aa20ff9c194d2e3e155b608216186fee66ef8ca269d31a015c63cca3cf5f0d40e4cccd13d390ed1aeed7a7c7d0709e9b509844462098d97e983ba6963ee3a68955d5317ecec47caeb8911784598be71d319ba8441c36388bf4a10ba2538f4f54f648b22a8f1e1f713dec36376936100b0fc5fb5ad4f51811ec96a76b564c3ee08305f5a2ad79a80152a03eb86d4dcd854a23818621d80771ceb169e45a692b45db77667beecd545e08b8afe8f8a3d049ae18e1cee5522769cd6b0131e036ee81d70df4cd679959fd82684bf9e1f4784325ef271eec2fb73ef4a9569fd76f7b1e55d8e2e87a5daa5ad5357cb401af13c2c695afc4b6a8a2004da1b0f5ebe7cb70cb2e15f0f3ca41eaca969abcb452f7a15fe9d004e66ff646e423366713632f1dedcb33bac1abbc47f1cf2b280f04cf85a7291bb4ecb2c1c252d65e933f5819ba4984b1018ec11ae36d2445af56900b9b6e746f84ddd6b06baab9d7c8f82f0b0bc7a61ade6eabe762ac0d3afe4b2102518361a9e54a4d9d51e4a25ccf1d40c36f6444d2271d03d91eb0f1f6895345c8758a7375926cf0ab75212ef7b4a0efa59a31decd995be2933e3da51efec22365521b8942f997789f9618cbbb422607c2414fc64bc558eca27df5fe7156954b98335a5cc63e6bfe7e076149c93e2314dd626f48bf6721b506b81962b6ca81bff28c7e216f49fcbf989045f97452f3b4ccdcaa7ca5a4ce0bd3f5e16440c6c0b73a42bfa6cfe8e31265b73b81b81c2d54e4f7aefb16ebfa1273adbfd57c08a6"
-							},
-							{
-								"commit": "09cf47204446c326e361a1a92f34b174deff732daaedb80d7339fbe3db5ca2f6ba",
-								"features": "Plain",
-								"proof": "8f511614315626b5f39224482351d766f5a8ef136262befc050d839be8479b0a13470cd88f4436346d213d83847a4055c6e0ac63681556470349a1aab47034a3015eb64d8163955998e2dd4165dd24386b1e279974b05deb5d46ba2bc321f7000c0784f8f10690605ffe717119d045e02b141ed12d8fc6d20930483a8af889ef533495eb442fcff36d98ebc104f13fc645c28431b3296e4a11f7c991ff97f9abbc2f8886762d7f29fdacb31d52c6850e6ccf5386117d89e8ea4ca3071c56c218dd5d3bcd65f6c06ed9f51f848507ca1d594f41796d1cf99f68a5c3f0c5dd9873602284cff31269b102fcc6c68607565faaf0adb04ed4ff3ea5d41f3b5235ac6cb90e4046c808c9c48c27172c891b20085c56a99913ef47fd8b3dc4920cef50534b9319a7cefe0df10a0206a634ac837e11da92df83ff58b1a14de81313400988aa48b946fcbe1b81f0e79e13f7c6c639b1c10983b424bda08d0ce593a20f1f47e0aa01473e7144f116b76d9ebc60599053d8f1542d60747793d99064e51fce8f8866390325d48d6e8e3bbdbc1822c864303451525c6cb4c6902f105a70134186fb32110d8192fc2528a9483fc8a4001f4bdeab1dd7b3d1ccb9ae2e746a78013ef74043f0b2436f0ca49627af1768b7c791c669bd331fd18c16ef88ad0a29861db70f2f76f3e74fde5accb91b73573e31333333223693d6fbc786e740c085e4fc6e7bde0a3f54e9703f816c54f012d3b1f41ec4d253d9337af61e7f1f1383bd929421ac346e3d2771dfee0b60503b33938e7c83eb37af3b6bf66041a3519a2b4cb557b34e3b9afcf95524f9a011425a34d32e7b6e9f255291094930acae26e8f7a1e4e6bc405d0f88e919f354f3ba85356a34f1aba5f7da1fad88e2692f4129cc1fb80a2122b2d996c6ccf7f08d8248e511d92af9ce49039de728848a2dc74101f4e94a"
+						"output": {
+							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+							"output": {
+								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+								"height": "1",
+								"is_coinbase": true,
+								"is_foundation": false,
+								"key_id": "0300000000000000000000000000000000",
+								"lock_height": "4",
+								"mmr_index": null,
+								"n_child": 0,
+								"root_key_id": "0200000000000000000000000000000000",
+								"spent_by_tx": null,
+								"spent_height": null,
+								"status": "Unspent",
+								"tags": [],
+								"tx_log_entry": 0,
+								"value": "1457920000"
 							}
-						]
-					},
-					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-				},
-				"version_info": {
-					"orig_version": 3,
-					"version": 3,
-					"block_header_version": 6
+						},
+						"spent_by": null,
+						"spent_height": null
+					}
+				]
+			]
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	*/
+	fn retrieve_output_lineage(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+	) -> Result<(bool, Vec<OutputLineage>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_locked_outputs](struct.Owner.html#method.get_locked_outputs).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_locked_outputs",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[]
+			]
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	*/
+	fn get_locked_outputs(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+	) -> Result<(bool, Vec<LockedOutput>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::force_unlock](struct.Owner.html#method.force_unlock).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "force_unlock",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+			"include_pending": false
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"UnlockRefused": "no locked output 089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e in this account"
+			}
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	*/
+	fn force_unlock(
+		&self,
+		token: Token,
+		commit: String,
+		include_pending: bool,
+	) -> Result<LockedOutput, ErrorKind>;
+
+	/**
+	Networked version of [Owner::set_output_status](struct.Owner.html#method.set_output_status).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "set_output_status",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+			"status": "Unspent",
+			"reason": "ticket 1234: lock left by a transaction missing from the log"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"OutputStatusOverride": "overriding output statuses is not enabled"
+			}
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	*/
+	fn set_output_status(
+		&self,
+		token: Token,
+		commit: String,
+		status: OutputStatus,
+		reason: String,
+	) -> Result<OutputData, ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_audit_log](struct.Owner.html#method.retrieve_audit_log).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_audit_log",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": []
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	*/
+	fn retrieve_audit_log(&self, token: Token) -> Result<Vec<AuditEntry>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::tag_outputs](struct.Owner.html#method.tag_outputs).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "tag_outputs",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"commits": ["089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e"],
+			"tag": "cold"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				{
+					"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+					"output": {
+						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+						"height": "1",
+						"is_coinbase": true,
+						"is_foundation": false,
+						"key_id": "0300000000000000000000000000000000",
+						"lock_height": "4",
+						"mmr_index": null,
+						"n_child": 0,
+						"root_key_id": "0200000000000000000000000000000000",
+						"spent_by_tx": null,
+						"spent_height": null,
+						"status": "Unspent",
+						"tags": ["cold"],
+						"tx_log_entry": 0,
+						"value": "1457920000"
+					}
 				}
+			]
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+	fn tag_outputs(
+		&self,
+		token: Token,
+		commits: Vec<String>,
+		tag: String,
+	) -> Result<Vec<OutputCommitMapping>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::untag_outputs](struct.Owner.html#method.untag_outputs).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "untag_outputs",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"commits": ["099be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e"],
+			"tag": "cold"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"OutputTag": "no unspent output 099be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e in this account"
+			}
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+	fn untag_outputs(
+		&self,
+		token: Token,
+		commits: Vec<String>,
+		tag: String,
+	) -> Result<Vec<OutputCommitMapping>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::reserve_funds](struct.Owner.html#method.reserve_funds).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "reserve_funds",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"amount": 0,
+			"label": "withdrawal 17"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"Reservation": "amount to reserve must be greater than 0"
 			}
 		}
 	}
 	# "#
-	# ,true, 4, false, false, false, false);
+	# , true, 4, false, false, false, false);
+	```
+	*/
+	fn reserve_funds(
+		&self,
+		token: Token,
+		amount: u64,
+		label: Option<String>,
+	) -> Result<Reservation, ErrorKind>;
+
+	/**
+	Networked version of [Owner::release_reservation](struct.Owner.html#method.release_reservation).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "release_reservation",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"id": "0436430c-2b02-624c-2032-570501212b00"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"Reservation": "no reservation 0436430c-2b02-624c-2032-570501212b00 in this account"
+			}
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
+	fn release_reservation(&self, token: Token, id: Uuid) -> Result<Reservation, ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_reservations](struct.Owner.html#method.retrieve_reservations).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_reservations",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": []
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
+	fn retrieve_reservations(&self, token: Token) -> Result<Vec<Reservation>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_foundation_rewards](struct.Owner.html#method.retrieve_foundation_rewards).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_foundation_rewards",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"start_height": 1,
+			"end_height": 4
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[]
+			]
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
+	fn retrieve_foundation_rewards(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		start_height: Option<u64>,
+		end_height: Option<u64>,
+	) -> Result<(bool, Vec<OutputCommitMapping>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_mining_rewards](struct.Owner.html#method.retrieve_mining_rewards).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_mining_rewards",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"pow_type": "Cuckatoo",
+			"start_height": 1,
+			"end_height": 4
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[
+					{
+						"amount": "5831680000",
+						"num_blocks": "4",
+						"pow_type": "Cuckatoo"
+					}
+				]
+			]
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
+	fn retrieve_mining_rewards(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		pow_type: Option<PoWType>,
+		start_height: Option<u64>,
+		end_height: Option<u64>,
+	) -> Result<(bool, Vec<MiningRewardSummary>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_cleaned_coinbase](struct.Owner.html#method.retrieve_cleaned_coinbase).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_cleaned_coinbase",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[]
+			]
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
+	fn retrieve_cleaned_coinbase(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+	) -> Result<(bool, Vec<CleanedCoinbase>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_txs](struct.Owner.html#method.retrieve_txs).
+
+	# Json rpc example
+
+	```
+		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+		# r#"
+		{
+			"jsonrpc": "2.0",
+			"method": "retrieve_txs",
+			"params": {
+				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+				"refresh_from_node": true,
+				"tx_id": null,
+				"tx_slate_id": null
+			},
+			"id": 1
+		}
+		# "#
+		# ,
+		# r#"
+		{
+		"id": 1,
+		"jsonrpc": "2.0",
+	  "result": {
+		"Ok": [
+		  true,
+		  [
+			{
+			  "amount_credited": "1457920000",
+			  "amount_debited": "0",
+			  "confirmation_ts": "2019-01-15T16:01:26Z",
+			  "confirmed": true,
+			  "creation_ts": "2019-01-15T16:01:26Z",
+			  "seq": 1,
+			  "fee": null,
+			  "id": 0,
+			  "kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
+			  "kernel_lookup_min_height": 1,
+			  "sender_proof_address": null,
+			  "post_state": null,
+			  "state": "Confirmed",
+			  "confirmation_height": "1",
+			  "confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+			  "pow_type": "Cuckatoo",
+			  "confirmations": "2",
+			  "deposit_tag": null,
+			  "messages": null,
+			  "num_inputs": 0,
+			  "num_outputs": 1,
+			  "parent_key_id": "0200000000000000000000000000000000",
+			  "stored_tx": null,
+			  "ttl_cutoff_height": null,
+			  "tx_slate_id": null,
+			  "payment_proof": null,
+			  "tx_type": "ConfirmedCoinbase"
+			},
+			{
+			  "amount_credited": "1457920000",
+			  "amount_debited": "0",
+			  "confirmation_ts": "2019-01-15T16:01:26Z",
+			  "confirmed": true,
+			  "creation_ts": "2019-01-15T16:01:26Z",
+			  "seq": 2,
+			  "fee": null,
+			  "id": 1,
+			  "kernel_excess": "08bae42ff7d5fa5aca058fd0889dd1e40df16bf3ee2eea6e5db720c0a6d638a7f8",
+			  "kernel_lookup_min_height": 2,
+			  "sender_proof_address": null,
+			  "post_state": null,
+			  "state": "Confirmed",
+			  "confirmation_height": "2",
+			  "confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+			  "pow_type": "Cuckatoo",
+			  "confirmations": "1",
+			  "deposit_tag": null,
+			  "messages": null,
+			  "num_inputs": 0,
+			  "num_outputs": 1,
+			  "parent_key_id": "0200000000000000000000000000000000",
+			  "stored_tx": null,
+			  "ttl_cutoff_height": null,
+			  "payment_proof": null,
+			  "tx_slate_id": null,
+			  "tx_type": "ConfirmedCoinbase"
+			}
+		  ]
+		]
+	  }
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+
+	fn retrieve_txs(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+		tx_slate_id: Option<Uuid>,
+	) -> Result<(bool, Vec<TxLogEntry>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_txs_page](struct.Owner.html#method.retrieve_txs_page).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_txs_page",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"cursor": null,
+			"limit": 1
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				{
+					"next_cursor": "743a02000000000000000000000000000000000000000000000000",
+					"txs": [
+						{
+							"amount_credited": "1457920000",
+							"amount_debited": "0",
+							"confirmation_ts": "2019-01-15T16:01:26Z",
+							"confirmed": true,
+							"creation_ts": "2019-01-15T16:01:26Z",
+							"seq": 1,
+							"fee": null,
+							"id": 0,
+							"kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
+							"kernel_lookup_min_height": 1,
+							"sender_proof_address": null,
+							"post_state": null,
+							"state": "Confirmed",
+							"confirmation_height": "1",
+							"confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+							"pow_type": "Cuckatoo",
+							"confirmations": "2",
+							"deposit_tag": null,
+							"messages": null,
+							"num_inputs": 0,
+							"num_outputs": 1,
+							"parent_key_id": "0200000000000000000000000000000000",
+							"stored_tx": null,
+							"ttl_cutoff_height": null,
+							"tx_slate_id": null,
+							"payment_proof": null,
+							"tx_type": "ConfirmedCoinbase"
+						}
+					]
+				}
+			]
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+
+	fn retrieve_txs_page(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		cursor: Option<String>,
+		limit: usize,
+	) -> Result<(bool, TxLogPage), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_txs_by_time](struct.Owner.html#method.retrieve_txs_by_time).
+	Times are RFC3339 strings.
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_txs_by_time",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"from": "2019-01-01T00:00:00Z",
+			"until": null
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[
+					{
+						"amount_credited": "1457920000",
+						"amount_debited": "0",
+						"confirmation_ts": "2019-01-15T16:01:26Z",
+						"confirmed": true,
+						"creation_ts": "2019-01-15T16:01:26Z",
+						"seq": 1,
+						"fee": null,
+						"id": 0,
+						"kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
+						"kernel_lookup_min_height": 1,
+						"sender_proof_address": null,
+						"post_state": null,
+						"state": "Confirmed",
+						"confirmation_height": "1",
+						"confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+						"pow_type": "Cuckatoo",
+						"confirmations": "2",
+						"deposit_tag": null,
+						"messages": null,
+						"num_inputs": 0,
+						"num_outputs": 1,
+						"parent_key_id": "0200000000000000000000000000000000",
+						"stored_tx": null,
+						"ttl_cutoff_height": null,
+						"tx_slate_id": null,
+						"payment_proof": null,
+						"tx_type": "ConfirmedCoinbase"
+					},
+					{
+						"amount_credited": "1457920000",
+						"amount_debited": "0",
+						"confirmation_ts": "2019-01-15T16:01:26Z",
+						"confirmed": true,
+						"creation_ts": "2019-01-15T16:01:26Z",
+						"seq": 2,
+						"fee": null,
+						"id": 1,
+						"kernel_excess": "08bae42ff7d5fa5aca058fd0889dd1e40df16bf3ee2eea6e5db720c0a6d638a7f8",
+						"kernel_lookup_min_height": 2,
+						"sender_proof_address": null,
+						"post_state": null,
+						"state": "Confirmed",
+						"confirmation_height": "2",
+						"confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+						"pow_type": "Cuckatoo",
+						"confirmations": "1",
+						"deposit_tag": null,
+						"messages": null,
+						"num_inputs": 0,
+						"num_outputs": 1,
+						"parent_key_id": "0200000000000000000000000000000000",
+						"stored_tx": null,
+						"ttl_cutoff_height": null,
+						"tx_slate_id": null,
+						"payment_proof": null,
+						"tx_type": "ConfirmedCoinbase"
+					}
+				]
+			]
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+
+	fn retrieve_txs_by_time(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		from: Option<DateTime<Utc>>,
+		until: Option<DateTime<Utc>>,
+	) -> Result<(bool, Vec<TxLogEntry>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_txs_by_filter](struct.Owner.html#method.retrieve_txs_by_filter).
+	The filter is given as a JSON object, as described in
+	[`TxFilter`](../epic_wallet_libwallet/tx_filter/enum.TxFilter.html).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_txs_by_filter",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"filter": {
+				"and": [
+					{"type": "coinbase"},
+					{"min_height": "2"}
+				]
+			}
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[
+					{
+						"amount_credited": "1457920000",
+						"amount_debited": "0",
+						"confirmation_ts": "2019-01-15T16:01:26Z",
+						"confirmed": true,
+						"creation_ts": "2019-01-15T16:01:26Z",
+						"seq": 2,
+						"fee": null,
+						"id": 1,
+						"kernel_excess": "08bae42ff7d5fa5aca058fd0889dd1e40df16bf3ee2eea6e5db720c0a6d638a7f8",
+						"kernel_lookup_min_height": 2,
+						"sender_proof_address": null,
+						"post_state": null,
+						"state": "Confirmed",
+						"confirmation_height": "2",
+						"confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+						"pow_type": "Cuckatoo",
+						"confirmations": "1",
+						"deposit_tag": null,
+						"messages": null,
+						"num_inputs": 0,
+						"num_outputs": 1,
+						"parent_key_id": "0200000000000000000000000000000000",
+						"stored_tx": null,
+						"ttl_cutoff_height": null,
+						"tx_slate_id": null,
+						"payment_proof": null,
+						"tx_type": "ConfirmedCoinbase"
+					}
+				]
+			]
+		}
+	}
+	# "#
+	# , true, 2, false, false, false, false);
+	```
+	*/
+
+	fn retrieve_txs_by_filter(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		filter: TxFilter,
+	) -> Result<(bool, Vec<TxLogEntry>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::check_duplicate_payment](struct.Owner.html#method.check_duplicate_payment).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "check_duplicate_payment",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"amount": 100000000,
+			"destination": "http://192.168.0.2:3415"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": []
+		}
+	}
+	# "#
+	# , true, 4, false, false, false, false);
+	```
+	*/
+
+	fn check_duplicate_payment(
+		&self,
+		token: Token,
+		amount: u64,
+		destination: String,
+	) -> Result<Vec<TxLogEntry>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_summary_info](struct.Owner.html#method.retrieve_summary_info).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_summary_info",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"minimum_confirmations": 1
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+	"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				{
+					"amount_awaiting_confirmation": "0",
+					"amount_awaiting_finalization": "0",
+					"amount_currently_spendable": "1457920000",
+					"amount_immature": "4373760000",
+					"amount_locked": "0",
+					"breakdown": null,
+					"last_confirmed_height": "4",
+					"minimum_confirmations": "1",
+					"total": "5831680000"
+				}
+
+			]
+		}
+	}
+	# "#
+	# ,true, 4, false, false, false, false);
+	```
+	 */
+
+	fn retrieve_summary_info(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		minimum_confirmations: u64,
+	) -> Result<(bool, WalletInfo), ErrorKind>;
+
+	/**
+	Networked version of [Owner::retrieve_detailed_summary_info](struct.Owner.html#method.retrieve_detailed_summary_info).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "retrieve_detailed_summary_info",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"minimum_confirmations": 1
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+	"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				{
+					"amount_awaiting_confirmation": "0",
+					"amount_awaiting_finalization": "0",
+					"amount_currently_spendable": "1457920000",
+					"amount_immature": "4373760000",
+					"amount_locked": "0",
+					"breakdown": {
+						"coinbase": {
+							"amount_awaiting_confirmation": "0",
+							"amount_currently_spendable": "1457920000",
+							"amount_immature": "4373760000",
+							"amount_locked": "0",
+							"total": "5831680000"
+						},
+						"foundation": {
+							"amount_awaiting_confirmation": "0",
+							"amount_currently_spendable": "0",
+							"amount_immature": "0",
+							"amount_locked": "0",
+							"total": "0"
+						},
+						"regular": {
+							"amount_awaiting_confirmation": "0",
+							"amount_currently_spendable": "0",
+							"amount_immature": "0",
+							"amount_locked": "0",
+							"total": "0"
+						},
+						"immature_schedule": [
+							{
+								"amount": "1457920000",
+								"lock_height": "5",
+								"num_outputs": 1
+							},
+							{
+								"amount": "1457920000",
+								"lock_height": "6",
+								"num_outputs": 1
+							},
+							{
+								"amount": "1457920000",
+								"lock_height": "7",
+								"num_outputs": 1
+							}
+						]
+					},
+					"last_confirmed_height": "4",
+					"minimum_confirmations": "1",
+					"total": "5831680000"
+				}
+			]
+		}
+	}
+	# "#
+	# ,true, 4, false, false, false, false);
+	```
+	 */
+
+	fn retrieve_detailed_summary_info(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		minimum_confirmations: u64,
+	) -> Result<(bool, WalletInfo), ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_balance_history](struct.Owner.html#method.get_balance_history).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_balance_history",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"from_height": 1,
+			"to_height": 4,
+			"step": 1
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				[
+					{
+						"height": "1",
+						"total": "1457920000"
+					},
+					{
+						"height": "2",
+						"total": "2915840000"
+					},
+					{
+						"height": "3",
+						"total": "4373760000"
+					},
+					{
+						"height": "4",
+						"total": "5831680000"
+					}
+				]
+			]
+		}
+	}
+	# "#
+	# ,true, 4, false, false, false, false);
+	```
+	 */
+
+	fn get_balance_history(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		from_height: u64,
+		to_height: Option<u64>,
+		step: u64,
+	) -> Result<(bool, Vec<BalanceHistoryEntry>), ErrorKind>;
+
+	/**
+	Networked version of [Owner::init_send_tx](struct.Owner.html#method.init_send_tx).
+
+	An optional `idempotency_key` may be given; a retried request carrying the same key
+	and arguments returns the result of the original call instead of performing it again,
+	and reusing the key with other arguments is an error. See
+	[Owner::with_idempotency_key](struct.Owner.html#method.with_idempotency_key).
+
+	# Json rpc example
+
+	```
+		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+		# r#"
+		{
+			"jsonrpc": "2.0",
+			"method": "init_send_tx",
+			"params": {
+				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+				"args": {
+					"src_acct_name": null,
+					"amount": "60000000",
+					"minimum_confirmations": 2,
+					"max_outputs": 500,
+					"num_change_outputs": 1,
+					"selection_strategy_is_use_all": true,
+					"message": "my message",
+					"target_slate_version": null,
+					"payment_proof_recipient_address": "d03c09e9c19bb74aa9ea44e0fe5ae237a9bf40bddf0941064a80913a4459c8bb",
+					"ttl_blocks": null,
+					"send_args": null
+				},
+				"idempotency_key": "withdrawal-42"
+			},
+			"id": 1
+		}
+		# "#
+		# ,
+		# r#"
+		{
+	  "id": 1,
+	  "jsonrpc": "2.0",
+	  "result": {
+		"Ok": {
+		  "amount": "60000000",
+		  "fee": "800000",
+		  "fee_breakdown": {
+			  "num_inputs": 1,
+			  "num_outputs": 2,
+			  "num_kernels": 1,
+			  "input_weight": 1,
+			  "output_weight": 4,
+			  "kernel_weight": 1,
+			  "weight": 8,
+			  "fee_base": "100000",
+			  "fee": "800000"
+		  },
+		  "height": "4",
+		  "id": "0436430c-2b02-624c-2032-570501212b00",
+		  "lock_height": "0",
+		  "num_participants": 2,
+
+		  "participant_data": [
+			{
+			  "id": "0",
+			  "message": "my message",
+			  "message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bea065fb74c27c31d611427ac5fa1459d1db340d7475e2967f19e2fa95687d88c",
+			  "part_sig": null,
+			  "public_blind_excess": "039fbac4782fa1600aa704c38073eece85e3a085a90446ded19a9fec90e432b330",
+			  "public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+			}
+		  ],
+		  "payment_proof": {
+			  "receiver_address": "d03c09e9c19bb74aa9ea44e0fe5ae237a9bf40bddf0941064a80913a4459c8bb",
+			  "receiver_signature": null,
+			  "sender_address": "32cdd63928854f8b2628b1dce4626ddcdf35d56cb7cfdf7d64cca5822b78d4d3"
+			},
+			"ttl_cutoff_height": null,
+		  "tx": {
+			"body": {
+			"inputs": [
+			  {
+				"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+				"features": "Coinbase"
+			  }
+			],
+			"kernels": [
+			  {
+				"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+				"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+				"features": "Plain",
+				"fee": "800000",
+				"lock_height": "0"
+			  }
+			],
+			"outputs": [
+			  {
+				"commit": "0832ca73c2049ee0c8f555c6297aa3658eb3f8ce711dfd63d6d5234cf3191c7756",
+				"features": "Plain",
+				"proof": "1393386202b2ba345e131efb1dfc3730c000ee6b6f3cb8d56e3d0680796b11940abeaed75e18458b544d28765b530a7ca2ed170b42c8bd5e07d4451b0cfa59c20fee43c9107091a328a9f745a255702ea17ac50f5e92b2daf51a7851e8ffde5e11a6837b5b6058aee5ab1e68bcd756646f36b38c6262aa20ff9c194d2e3e155b608216186fee66ef8ca269d31a015c63cca3cf5f0d40e4cccd13d390ed1aeed7a7c7d0709e9b509844462098d97e983ba6963ee3a68955d5317ecec47caeb8911784598be71d319ba8441c36388bf4a10ba2538f4f54f648b22a8f1e1f713dec36376936100b0fc5fb5ad4f51811ec96a76b564c3ee08305f5a2ad79a80152a03eb86d4dcd854a23818621d80771ceb169e45a692b45db77667beecd545e08b8afe8f8a3d049ae18e1cee5522769cd6b0131e036ee81d70df4cd679959fd82684bf9e1f4784325ef271eec2fb73ef4a9569fd76f7b1e55d8e2e87a5daa5ad5357cb401af13c2c695afc4b6a8a2004da1b0f5ebe7cb70cb2e15f0f3ca41eaca969abcb452f7a15fe9d004e66ff646e423366713632f1dedcb33bac1abbc47f1cf2b280f04cf85a7291bb4ecb2c1c252d65e933f5819ba4984b1018ec11ae36d2445af56900b9b6e746f84ddd6b06baab9d7c8f82f0b0bc7a61ade6eabe762ac0d3afe4b2102518361a9e54a4d9d51e4a25ccf1d40c36f6444d2271d03d91eb0f1f6895345c8758a7375926cf0ab75212ef7b4a0efa59a31decd995be2933e3da51efec22365521b8942f997789f9618cbbb422607c2414fc64bc558eca27df5fe7156954b98335a5cc63e6bfe7e076149c93e2314dd626f48bf6721b506b81962b6ca81bff28c7e216f49fcbf989045f97452f3b4ccdcaa7ca5a4ce0bd3f5e16440c6c0b73a42bfa6cfe8e31265b73b81b81c2d54e4f7aefb16ebfa1273adbfd57c08a6"
+			  }
+			]
+			},
+			"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		  },
+		  "version_info": {
+				"orig_version": 3,
+				"version": 3,
+				"block_header_version": 6
+		  }
+		}
+	  }
+	}
+		# "#
+		# ,true, 4, false, false, false, false);
+	```
+	*/
+
+	fn init_send_tx(
+		&self,
+		token: Token,
+		args: InitTxArgs,
+		idempotency_key: Option<String>,
+	) -> Result<VersionedSlate, ErrorKind>;
+
+	/**
+	Networked version of [Owner::create_tx_template](struct.Owner.html#method.create_tx_template).
+
+	# Json rpc example
+
+	```
+		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+		# r#"
+		{
+			"jsonrpc": "2.0",
+			"method": "create_tx_template",
+			"params": {
+				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+				"args": {
+					"src_acct_name": null,
+					"amount": "60000000",
+					"minimum_confirmations": 2,
+					"max_outputs": 500,
+					"num_change_outputs": 1,
+					"selection_strategy_is_use_all": true,
+					"message": "my message",
+					"target_slate_version": null,
+					"payment_proof_recipient_address": null,
+					"ttl_blocks": null,
+					"send_args": null
+				}
+			},
+			"id": 1
+		}
+		# "#
+		# ,
+		# r#"
+		{
+			"id": 1,
+			"jsonrpc": "2.0",
+			"result": {
+				"Ok": {
+					"amount": "60000000",
+					"fee": "800000",
+					"height": "4",
+					"inputs": [
+						{
+							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+							"value": "1457920000"
+						}
+					],
+					"max_outputs": 500,
+					"message": "my message",
+					"minimum_confirmations": "2",
+					"num_change_outputs": 1,
+					"outputs": [
+						{
+							"is_change": false,
+							"value": "60000000"
+						},
+						{
+							"is_change": true,
+							"value": "1397120000"
+						}
+					],
+					"payment_proof_recipient_address": null,
+					"selection_strategy_is_use_all": true,
+					"selection_tag": null,
+					"src_acct_name": null,
+					"ttl_blocks": null
+				}
+			}
+		}
+		# "#
+		# ,true, 4, false, false, false, false);
+	```
+	*/
+
+	fn create_tx_template(&self, token: Token, args: InitTxArgs) -> Result<TxTemplate, ErrorKind>;
+
+	/**
+	Networked version of [Owner::issue_invoice_tx](struct.Owner.html#method.issue_invoice_tx).
+
+	# Json rpc example
+
+	```
+		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+		# r#"
+		{
+			"jsonrpc": "2.0",
+			"method": "issue_invoice_tx",
+			"params": {
+				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+				"args": {
+					"amount": "60000000",
+					"message": "Please give me your epics",
+					"dest_acct_name": null,
+					"target_slate_version": null
+				}
+			},
+			"id": 1
+		}
+		# "#
+		# ,
+		# r#"
+		{
+			"id": 1,
+			"jsonrpc": "2.0",
+			"result": {
+				"Ok": {
+					"amount": "60000000",
+					"fee": "0",
+					"height": "4",
+					"id": "0436430c-2b02-624c-2032-570501212b00",
+					"lock_height": "0",
+					"ttl_cutoff_height": null,
+					"num_participants": 2,
+					"payment_proof": null,
+					"participant_data": [
+						{
+							"id": "1",
+							"message": "Please give me your epics",
+							"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bb06e3894e0db51e6015d2181f101d06722094128dbc316f7186b57edd68731cb",
+							"part_sig": null,
+							"public_blind_excess": "035ca9d2d82e0e31bc2add7ef9200066b257d9f72cb16c0e57455277b90e2b3503",
+							"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+						}
+					],
+					"tx": {
+						"body": {
+							"inputs": [],
+							"kernels": [
+								{
+									"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+									"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+									"features": "Plain",
+									"fee": "0",
+									"lock_height": "0"
+								}
+							],
+							"outputs": [
+								{
+									"commit": "083fe68fb96a0941f70e9412b4483621326abee9eccf10e7a7efef2a9b4e97df25",
+									"features": "Plain",
+									"proof": "2df8ba188baa701a7c07e23de108e8318797ba029319b4557db3c1c8af917f6361d9871520e33423131d5771b06566aba2469f1e9fcc8eda8203a5c241759e45041af26939c57946372bf330d09670eb30a08cae27b3015dac2efb518fbe2325487d8417f4014cb854aec9e4e1770d2f5d7a6e32e2bc904245505ebd952eda10b5c401f315a8cb969da4cc2dc1e656f33d870ca07ffbc45f58cae9f28b836d4bf3c1786b805ba9d8789cc211998981a8b4115aa7383ddbd10e656fc0a590c3e2cf8dd07e414217d9a1d1af32bcbd933448d0a89033cb93ba2eb0d3b973136d61ee7f109d0476ed3475b7328eff5e9d3362b5db4621682a443a382f7ef09304f9bff422885d23f62f9d7d1a9bbf888e5ba5678e347182770cbbf41cfb3002269607f085881ce0f0df01f34f34433ef04dd6008f9a0c13e47e6e386d62151386dfd20bdf812ae2fb580edc38f38bfc9cc543d1023889ee646d4e75a7382caa3bb00b970062ffdc1fc643ce56d25e2e73b556162c8441d5a667b36b840cc244f69395b46900dd1edc562ed741c239804588e94c071b621766b55f738802c376012fa577e0d82bdf7bf2f229a867d91ed177bacde44faadb6901066f84e21a5fb0b73ed7ef9ef4a1e2c65e6a28a0ae834a99ed1694889d885fc8e90c8e7507078603a9705cc3c57b8b0125ad385cb5ec564f9ca69b530307d91ef2c6bb49a39e30d9e68f2f67d99915d87d1a7776f4c0b61913ea661ebe320b8e99919c69d9dbdc527e787d46e772da9ab9f9cc60e43b41fb0981b6b882ed7a535451158c711210fe25e68d12719192c3d332aea9e047a0f7a292b8e6f13fd76ed47afbadf070392cc3f4a4ebb8ec9853587e30ad9b9794717c87bf962e2ab99ec543f5a24efda0cfc2bf51f23c8132aee6058189925febe1d9d3a145f580ef9835db3c1f3b6e97bd36331e"
+								}
+							]
+						},
+						"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+					},
+					"version_info": {
+						"orig_version": 3,
+						"version": 3,
+						"block_header_version": 6
+					}
+				}
+			}
+		}
+		# "#
+		# ,true, 4, false, false, false, false);
+	```
+	*/
+
+	fn issue_invoice_tx(
+		&self,
+		token: Token,
+		args: IssueInvoiceTxArgs,
+	) -> Result<VersionedSlate, ErrorKind>;
+
+	/**
+	Networked version of [Owner::process_invoice_tx](struct.Owner.html#method.process_invoice_tx).
+
+	# Json rpc example
+
+	```
+		# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+		# r#"
+		{
+			"jsonrpc": "2.0",
+			"method": "process_invoice_tx",
+			"params": {
+				"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+				"slate": {
+					"amount": "60000000",
+					"fee": "0",
+					"height": "4",
+					"id": "0436430c-2b02-624c-2032-570501212b00",
+					"lock_height": "0",
+					"ttl_cutoff_height": null,
+					"num_participants": 2,
+					"payment_proof": null,
+					"participant_data": [
+						{
+							"id": "1",
+							"message": "Please give me your epics",
+							"message_sig": "1b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fd2599ab38942986602e943f684a85992893a6d34367dc7cc2b403a5dcfcdbcd9",
+							"part_sig": null,
+							"public_blind_excess": "028e95921cc0d5be5922362265d352c9bdabe51a9e1502a3f0d4a10387f1893f40",
+							"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+						}
+					],
+					"tx": {
+						"body": {
+							"inputs": [],
+							"kernels": [
+								{
+									"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+									"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+									"features": "Plain",
+									"fee": "0",
+									"lock_height": "0"
+								}
+							],
+							"outputs": [
+								{
+									"commit": "09cf47204446c326e361a1a92f34b174deff732daaedb80d7339fbe3db5ca2f6ba",
+									"features": "Plain",
+									"proof": "8f511614315626b5f39224482351d766f5a8ef136262befc050d839be8479b0a13470cd88f4436346d213d83847a4055c6e0ac63681556470349a1aab47034a3015eb64d8163955998e2dd4165dd24386b1e279974b05deb5d46ba2bc321f7000c0784f8f10690605ffe717119d045e02b141ed12d8fc6d20930483a8af889ef533495eb442fcff36d98ebc104f13fc645c28431b3296e4a11f7c991ff97f9abbc2f8886762d7f29fdacb31d52c6850e6ccf5386117d89e8ea4ca3071c56c218dd5d3bcd65f6c06ed9f51f848507ca1d594f41796d1cf99f68a5c3f0c5dd9873602284cff31269b102fcc6c68607565faaf0adb04ed4ff3ea5d41f3b5235ac6cb90e4046c808c9c48c27172c891b20085c56a99913ef47fd8b3dc4920cef50534b9319a7cefe0df10a0206a634ac837e11da92df83ff58b1a14de81313400988aa48b946fcbe1b81f0e79e13f7c6c639b1c10983b424bda08d0ce593a20f1f47e0aa01473e7144f116b76d9ebc60599053d8f1542d60747793d99064e51fce8f8866390325d48d6e8e3bbdbc1822c864303451525c6cb4c6902f105a70134186fb32110d8192fc2528a9483fc8a4001f4bdeab1dd7b3d1ccb9ae2e746a78013ef74043f0b2436f0ca49627af1768b7c791c669bd331fd18c16ef88ad0a29861db70f2f76f3e74fde5accb91b73573e31333333223693d6fbc786e740c085e4fc6e7bde0a3f54e9703f816c54f012d3b1f41ec4d253d9337af61e7f1f1383bd929421ac346e3d2771dfee0b60503b33938e7c83eb37af3b6bf66041a3519a2b4cb557b34e3b9afcf95524f9a011425a34d32e7b6e9f255291094930acae26e8f7a1e4e6bc405d0f88e919f354f3ba85356a34f1aba5f7da1fad88e2692f4129cc1fb80a2122b2d996c6ccf7f08d8248e511d92af9ce49039de728848a2dc74101f4e94a"
+								}
+							]
+						},
+						"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+					},
+					"version_info": {
+						"orig_version": 3,
+						"version": 3,
+						"block_header_version": 6
+					}
+				},
+				"args": {
+					"src_acct_name": null,
+					"amount": "0",
+					"minimum_confirmations": 2,
+					"max_outputs": 500,
+					"num_change_outputs": 1,
+					"selection_strategy_is_use_all": true,
+					"message": "Ok, here are your epics",
+					"target_slate_version": null,
+					"payment_proof_recipient_address": null,
+					"ttl_blocks": null,
+					"send_args": null
+				}
+			},
+			"id": 1
+		}
+		# "#
+		# ,
+		# r#"
+		{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": {
+				"amount": "60000000",
+				"fee": "800000",
+				"fee_breakdown": {
+					"num_inputs": 1,
+					"num_outputs": 2,
+					"num_kernels": 1,
+					"input_weight": 1,
+					"output_weight": 4,
+					"kernel_weight": 1,
+					"weight": 8,
+					"fee_base": "100000",
+					"fee": "800000"
+				},
+				"height": "4",
+				"id": "0436430c-2b02-624c-2032-570501212b00",
+				"lock_height": "0",
+				"ttl_cutoff_height": null,
+				"num_participants": 2,
+				"payment_proof": null,
+				"participant_data": [
+					{
+						"id": "1",
+						"message": "Please give me your epics",
+						"message_sig": "1b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fd2599ab38942986602e943f684a85992893a6d34367dc7cc2b403a5dcfcdbcd9",
+						"part_sig": null,
+						"public_blind_excess": "028e95921cc0d5be5922362265d352c9bdabe51a9e1502a3f0d4a10387f1893f40",
+						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+					},
+					{
+						"id": "0",
+						"message": "Ok, here are your epics",
+						"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bec8c1cac6cb5770a3c62c9bb95063581cc08bfccd72dac72be8ec4ba5374a9f3",
+						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841bcec20532cbe7ce0a3152b61566785684fea3534b7f834f02f733fa524123ee54",
+						"public_blind_excess": "02802124f21ba02769a3f05ecfe9662e8783fa0bd1a7b7d63cf3aea0ebc0d7af3a",
+						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+					}
+				],
+				"tx": {
+					"body": {
+						"inputs": [
+							{
+								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+								"features": "Coinbase"
+							}
+						],
+						"kernels": [
+							{
+								"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+								"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+								"features": "Plain",
+								"fee": "800000",
+								"lock_height": "0"
+							}
+						],
+						"outputs": [
+							{
+								"commit": "0832ca73c2049ee0c8f555c6297aa3658eb3f8ce711dfd63d6d5234cf3191c7756",
+								"features": "Plain",
+								"proof": "1393386202b2ba345e131efb1dfc3730c000ee6b6f3cb8d56e3d0680796b11940abeaed75e18458b544d28765b530a7ca2ed170b42c8bd5e07d4451b0cfa59c20fee43c9107091a328a9f745a255702ea17ac50f5e92b2daf51a7851e8ffde5e11a6837b5b6058aee5ab1e68bcd756646f36b38c6262aa20ff9c194d2e3e155b608216186fee66ef8ca269d31a015c63cca3cf5f0d40e4cccd13d390ed1aeed7a7c7d0709e9b509844462098d97e983ba6963ee3a68955d5317ecec47caeb8911784598be71d319ba8441c36388bf4a10ba2538f4f54f648b22a8f1e1f713dec36376936100b0fc5fb5ad4f51811ec96a76b564c3ee08305f5a2ad79a80152a03eb86d4dcd854a23818621d80771ceb169e45a692b45db77667beecd545e08b8afe8f8a3d049ae18e1cee5522769cd6b0131e036ee81d70df4cd679959fd82684bf9e1f4784325ef271eec2fb73ef4a9569fd76f7b1e55d8e2e87a5daa5ad5357cb401af13c2c695afc4b6a8a2004da1b0f5ebe7cb70cb2e15f0f3ca41eaca969abcb452f7a15fe9d004e66ff646e423366713632f1dedcb33bac1abbc47f1cf2b280f04cf85a7291bb4ecb2c1c252d65e933f5819ba4984b1018ec11ae36d2445af56900b9b6e746f84ddd6b06baab9d7c8f82f0b0bc7a61ade6eabe762ac0d3afe4b2102518361a9e54a4d9d51e4a25ccf1d40c36f6444d2271d03d91eb0f1f6895345c8758a7375926cf0ab75212ef7b4a0efa59a31decd995be2933e3da51efec22365521b8942f997789f9618cbbb422607c2414fc64bc558eca27df5fe7156954b98335a5cc63e6bfe7e076149c93e2314dd626f48bf6721b506b81962b6ca81bff28c7e216f49fcbf989045f97452f3b4ccdcaa7ca5a4ce0bd3f5e16440c6c0b73a42bfa6cfe8e31265b73b81b81c2d54e4f7aefb16ebfa1273adbfd57c08a6"
+							},
+							{
+								"commit": "09cf47204446c326e361a1a92f34b174deff732daaedb80d7339fbe3db5ca2f6ba",
+								"features": "Plain",
+								"proof": "8f511614315626b5f39224482351d766f5a8ef136262befc050d839be8479b0a13470cd88f4436346d213d83847a4055c6e0ac63681556470349a1aab47034a3015eb64d8163955998e2dd4165dd24386b1e279974b05deb5d46ba2bc321f7000c0784f8f10690605ffe717119d045e02b141ed12d8fc6d20930483a8af889ef533495eb442fcff36d98ebc104f13fc645c28431b3296e4a11f7c991ff97f9abbc2f8886762d7f29fdacb31d52c6850e6ccf5386117d89e8ea4ca3071c56c218dd5d3bcd65f6c06ed9f51f848507ca1d594f41796d1cf99f68a5c3f0c5dd9873602284cff31269b102fcc6c68607565faaf0adb04ed4ff3ea5d41f3b5235ac6cb90e4046c808c9c48c27172c891b20085c56a99913ef47fd8b3dc4920cef50534b9319a7cefe0df10a0206a634ac837e11da92df83ff58b1a14de81313400988aa48b946fcbe1b81f0e79e13f7c6c639b1c10983b424bda08d0ce593a20f1f47e0aa01473e7144f116b76d9ebc60599053d8f1542d60747793d99064e51fce8f8866390325d48d6e8e3bbdbc1822c864303451525c6cb4c6902f105a70134186fb32110d8192fc2528a9483fc8a4001f4bdeab1dd7b3d1ccb9ae2e746a78013ef74043f0b2436f0ca49627af1768b7c791c669bd331fd18c16ef88ad0a29861db70f2f76f3e74fde5accb91b73573e31333333223693d6fbc786e740c085e4fc6e7bde0a3f54e9703f816c54f012d3b1f41ec4d253d9337af61e7f1f1383bd929421ac346e3d2771dfee0b60503b33938e7c83eb37af3b6bf66041a3519a2b4cb557b34e3b9afcf95524f9a011425a34d32e7b6e9f255291094930acae26e8f7a1e4e6bc405d0f88e919f354f3ba85356a34f1aba5f7da1fad88e2692f4129cc1fb80a2122b2d996c6ccf7f08d8248e511d92af9ce49039de728848a2dc74101f4e94a"
+							}
+						]
+					},
+					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+				},
+				"version_info": {
+					"orig_version": 3,
+					"version": 3,
+					"block_header_version": 6
+				}
+			}
+		}
+	}
+	# "#
+	# ,true, 4, false, false, false, false);
+	```
+	*/
+
+	fn process_invoice_tx(
+		&self,
+		token: Token,
+		slate: VersionedSlate,
+		args: InitTxArgs,
+	) -> Result<VersionedSlate, ErrorKind>;
+
+	/**
+	Networked version of [Owner::pay_request](struct.Owner.html#method.pay_request).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "pay_request",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"request": "EPICSLATE:AAAA",
+			"args": {
+				"src_acct_name": null,
+				"amount": "0",
+				"minimum_confirmations": 2,
+				"max_outputs": 500,
+				"num_change_outputs": 1,
+				"selection_strategy_is_use_all": true,
+				"message": null,
+				"target_slate_version": null,
+				"payment_proof_recipient_address": null,
+				"ttl_blocks": null,
+				"send_args": null
+			}
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"PaymentRequest": "not an armored payment request"
+			}
+		}
+	}
+	# "#
+	# ,true, 4, false, false, false, false);
+	```
+	*/
+
+	fn pay_request(
+		&self,
+		token: Token,
+		request: String,
+		args: InitTxArgs,
+	) -> Result<VersionedSlate, ErrorKind>;
+
+	/**
+	Networked version of [Owner::tx_lock_outputs](struct.Owner.html#method.tx_lock_outputs).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "tx_lock_outputs",
+		"id": 1,
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"slate": {
+				"amount": "1457920000",
+				"fee": "8000000",
+				"height": "4",
+				"id": "0436430c-2b02-624c-2032-570501212b00",
+				"lock_height": "4",
+				"ttl_cutoff_height": null,
+				"num_participants": 2,
+				"payment_proof": null,
+				"participant_data": [
+				{
+					"id": "0",
+					"message": "my message",
+					"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b1d4c1358be398f801eb90d933774b5218fa7e769b11c4c640402253353656f75",
+					"part_sig": null,
+					"public_blind_excess": "034b4df2f0558b73ea72a1ca5c4ab20217c66bbe0829056fca7abe76888e9349ee",
+					"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+				}
+				],
+				"tx": {
+					"body": {
+						"inputs": [
+						{
+							"commit": "08e1da9e6dc4d6e808a718b2f110a991dd775d65ce5ae408a4e1f002a4961aa9e7",
+							"features": "Coinbase"
+						}
+						],
+						"kernels": [
+						{
+							"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+							"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+							"features": "HeightLocked",
+							"fee": "8000000",
+							"lock_height": "4"
+						}
+						],
+						"outputs": [
+						{
+							"commit": "094be57c91787fc2033d5d97fae099f1a6ddb37ea48370f1a138f09524c767fdd3",
+							"features": "Plain",
+							"proof": "2a42e9e902b70ce44e1fccb14de87ee0a97100bddf12c6bead1b9c5f4eb60300f29c13094fa12ffeee238fb4532b18f6b61cf51b23c1c7e1ad2e41560dc27edc0a2b9e647a0b3e4e806fced5b65e61d0f1f5197d3e2285c632d359e27b6b9206b2caffea4f67e0c7a2812e7a22c134b98cf89bd43d9f28b8bec25cce037a0ac5b1ae8f667e54e1250813a5263004486b4465ad4e641ab2b535736ea26535a11013564f08f483b7dab1c2bcc3ee38eadf2f7850eff7e3459a4bbabf9f0cf6c50d0c0a4120565cd4a2ce3e354c11721cd695760a24c70e0d5a0dfc3c5dcd51dfad6de2c237a682f36dc0b271f21bb3655e5333016aaa42c2efa1446e5f3c0a79ec417c4d30f77556951cb0f05dbfafb82d9f95951a9ea241fda2a6388f73ace036b98acce079f0e4feebccc96290a86dcc89118a901210b245f2d114cf94396e4dbb461e82aa26a0581389707957968c7cdc466213bb1cd417db207ef40c05842ab67a01a9b96eb1430ebc26e795bb491258d326d5174ad549401059e41782121e506744af8af9d8e493644a87d613600888541cbbe538c625883f3eb4aa3102c5cfcc25de8e97af8927619ce6a731b3b8462d51d993066b935b0648d2344ad72e4fd70f347fbd81041042e5ea31cc7b2e3156a920b80ecba487b950ca32ca95fae85b759c936246ecf441a9fdd95e8fee932d6782cdec686064018c857efc47fb4b2a122600d5fdd79af2486f44df7e629184e1c573bc0a9b3feb40b190ef2861a1ab45e2ac2201b9cd42e495deea247269820ed32389a2810ad6c0f9a296d2a2d9c54089fed50b7f5ecfcd33ab9954360e1d7f5598c32128cfcf2a1d8bf14616818da8a5343bfa88f0eedf392e9d4ab1ace1b60324129cd4852c2e27813a9cf71a6ae6229a4fcecc1a756b3e664c5f50af333082616815a3bec8fc0b75b8e4e767d719"
+						}
+						]
+					},
+					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+				},
+				"version_info": {
+					"orig_version": 3,
+					"version": 3,
+					"block_header_version": 6
+				}
+			},
+			"participant_id": 0
+		}
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"id": 1,
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# ,true, 5 ,true, false, false, false);
+
+	```
+	 */
+	fn tx_lock_outputs(
+		&self,
+		token: Token,
+		slate: VersionedSlate,
+		participant_id: usize,
+	) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::finalize_tx](struct.Owner.html#method.finalize_tx).
+
+	An optional `idempotency_key` may be given; a retried request carrying the same key
+	and arguments returns the result of the original call instead of performing it again,
+	and reusing the key with other arguments is an error. See
+	[Owner::with_idempotency_key](struct.Owner.html#method.with_idempotency_key).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "finalize_tx",
+		"id": 1,
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"slate": {
+				"version_info": {
+					"version": 3,
+					"orig_version": 3,
+					"block_header_version": 6
+				},
+				"num_participants": 2,
+				"id": "0436430c-2b02-624c-2032-570501212b00",
+				"payment_proof": null,
+				"tx": {
+					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000",
+					"body": {
+						"inputs": [
+							{
+								"features": "Coinbase",
+								"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e"
+							},
+							{
+								"features": "Coinbase",
+								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e"
+							}
+						],
+						"outputs": [
+							{
+								"features": "Plain",
+								"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
+								"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
+							},
+							{
+								"features": "Plain",
+								"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
+								"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
+							}
+						],
+						"kernels": [
+							{
+								"features": "Plain",
+								"fee": "700000",
+								"lock_height": "0",
+								"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+								"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
+							}
+						]
+					}
+				},
+				"amount": "600000000",
+				"fee": "700000",
+				"height": "5",
+				"lock_height": "0",
+				"ttl_cutoff_height": null,
+				"participant_data": [
+					{
+						"id": "0",
+						"public_blind_excess": "028e1bbb43e6038efc42054778d0a1aa184b2cf4d51acb40a2a8dc049788d97bd2",
+						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f",
+						"part_sig": null,
+						"message": null,
+						"message_sig": null
+					},
+					{
+						"id": "1",
+						"public_blind_excess": "03e14bacb4cfeda43edf6c1b0ffced9a358a119c7936bc68af724477eb91d9e4eb",
+						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f",
+						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b129340313ed7db02f6fd9a16c23ae8d5801af4fdc2ea580e2dec26e3821d5c17",
+						"message": null,
+						"message_sig": null
+					}
+				]
+			},
+			"idempotency_key": null
+		}
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"id": 1,
+		"result": {
+		"Ok": {
+				"amount": "600000000",
+				"fee": "700000",
+				"height": "5",
+				"id": "0436430c-2b02-624c-2032-570501212b00",
+				"lock_height": "0",
+				"ttl_cutoff_height": null,
+				"num_participants": 2,
+				"payment_proof": null,
+				"participant_data": [
+					{
+						"id": "0",
+						"message": null,
+						"message_sig": null,
+						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b93f888685e13250c5cb6b830ff898264ce247c73d3ab47845c01bcc6455ecbe5",
+						"public_blind_excess": "028e1bbb43e6038efc42054778d0a1aa184b2cf4d51acb40a2a8dc049788d97bd2",
+						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+					},
+					{
+						"id": "1",
+						"message": null,
+						"message_sig": null,
+						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b129340313ed7db02f6fd9a16c23ae8d5801af4fdc2ea580e2dec26e3821d5c17",
+						"public_blind_excess": "03e14bacb4cfeda43edf6c1b0ffced9a358a119c7936bc68af724477eb91d9e4eb",
+						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+					}
+				],
+				"tx": {
+					"body": {
+					"inputs": [
+						{
+							"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
+							"features": "Coinbase"
+						},
+						{
+							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+							"features": "Coinbase"
+						}
+					],
+					"kernels": [
+						{
+							"excess": "08d09187cb93cf5d6b97b28e8ca529912bf35ec8773d3e9af9b3c174a270dc7f05",
+							"excess_sig": "66074d25a751c4743342c90ad8ead9454daa00d9b9aed29bca321036d16c4b4da58bc9999cea000f52b45347c1c46a3a4f3f70719696a09289ede2a9c87b27fd",
+							"features": "Plain",
+							"fee": "700000",
+							"lock_height": "0"
+						}
+					],
+					"outputs": [
+						{
+							"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
+							"features": "Plain",
+							"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
+						},
+						{
+							"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
+							"features": "Plain",
+							"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
+						}
+					]
+					},
+					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+				},
+				"version_info": {
+					"orig_version": 3,
+					"version": 3,
+					"block_header_version": 6
+				}
+			}
+		}
+	}
+	# "#
+	# , true, 5, true, true, false, false);
+	```
+	 */
+	fn finalize_tx(
+		&self,
+		token: Token,
+		slate: VersionedSlate,
+		idempotency_key: Option<String>,
+	) -> Result<VersionedSlate, ErrorKind>;
+
+	/**
+	Networked version of [Owner::post_tx](struct.Owner.html#method.post_tx).
+
+	An optional `idempotency_key` may be given; a retried request carrying the same key
+	and arguments returns the result of the original call instead of performing it again,
+	and reusing the key with other arguments is an error. See
+	[Owner::with_idempotency_key](struct.Owner.html#method.with_idempotency_key).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"id": 1,
+		"method": "post_tx",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"tx": {
+			"offset": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"body": {
+				"inputs": [
+					{
+						"features": "Coinbase",
+						"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e"
+					},
+					{
+						"features": "Coinbase",
+						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e"
+					}
+				],
+				"kernels": [
+					{
+						"features": "Plain",
+						"fee": "700000",
+						"lock_height": "0",
+						"excess": "08d09187cb93cf5d6b97b28e8ca529912bf35ec8773d3e9af9b3c174a270dc7f05",
+						"excess_sig": "66074d25a751c4743342c90ad8ead9454daa00d9b9aed29bca321036d16c4b4da58bc9999cea000f52b45347c1c46a3a4f3f70719696a09289ede2a9c87b27fd"
+					}
+				],
+				"outputs": [
+					{
+						"features": "Plain",
+						"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
+						"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
+					},
+					{
+						"features": "Plain",
+						"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
+						"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
+					}
+				]
+
+			}
+		},
+		"fluff": false,
+		"idempotency_key": null
+		}
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 5, true, true, true, false);
+	```
+	 */
+
+	fn post_tx(
+		&self,
+		token: Token,
+		tx: TransactionV3,
+		fluff: bool,
+		idempotency_key: Option<String>,
+	) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::cancel_tx](struct.Owner.html#method.cancel_tx).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "cancel_tx",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"tx_id": null,
+			"tx_slate_id": "0436430c-2b02-624c-2032-570501212b00",
+			"force": false
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 5, true, true, false, false);
+	```
+	 */
+	fn cancel_tx(
+		&self,
+		token: Token,
+		tx_id: Option<u32>,
+		tx_slate_id: Option<Uuid>,
+		force: bool,
+	) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::preview_cancel_tx](struct.Owner.html#method.preview_cancel_tx).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "preview_cancel_tx",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"tx_id": 99,
+			"tx_slate_id": null
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"TransactionDoesntExist": "99"
+			}
+		}
+	}
+	# "#
+	# , true, 5, true, true, false, false);
+	```
+	 */
+	fn preview_cancel_tx(
+		&self,
+		token: Token,
+		tx_id: Option<u32>,
+		tx_slate_id: Option<Uuid>,
+	) -> Result<CancelPreview, ErrorKind>;
+
+	/**
+	Networked version of [Owner::cancel_stale_txs](struct.Owner.html#method.cancel_stale_txs).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "cancel_stale_txs",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"older_than_minutes": 60
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": []
+		}
+	}
+	# "#
+	# , true, 5, true, true, false, false);
+	```
+	 */
+	fn cancel_stale_txs(
+		&self,
+		token: Token,
+		older_than_minutes: u64,
+	) -> Result<Vec<TxLogEntry>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_stored_tx](struct.Owner.html#method.get_stored_tx).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_stored_tx",
+		"id": 1,
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"tx": {
+				"amount_credited": "59993000000",
+				"amount_debited": "120000000000",
+				"confirmation_ts": "2019-01-15T16:01:26Z",
+				"confirmed": false,
+				"creation_ts": "2019-01-15T16:01:26Z",
+				"fee": "7000000",
+				"id": 5,
+				"messages": {
+					"messages": [
+						{
+							"id": "0",
+							"message": null,
+							"message_sig": null,
+							"public_key": "033ac2158fa0077f087de60c19d8e431753baa5b63b6e1477f05a2a6e7190d4592"
+						},
+						{
+							"id": "1",
+							"message": null,
+							"message_sig": null,
+							"public_key": "024f9bc78c984c78d6e916d3a00746aa30fa1172124c8dbc0cbddcb7b486719bc7"
+						}
+					]
+				},
+				"num_inputs": 2,
+				"num_outputs": 1,
+				"parent_key_id": "0200000000000000000000000000000000",
+				"stored_tx": "0436430c-2b02-624c-2032-570501212b00.epictx",
+				"tx_slate_id": "0436430c-2b02-624c-2032-570501212b00",
+				"tx_type": "TxSent",
+				"kernel_excess": null,
+				"kernel_lookup_min_height": null
+			}
+		}
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"id": 1,
+		"result": {
+			"Ok": {
+				"body": {
+				"inputs": [
+					{
+						"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
+						"features": "Coinbase"
+					},
+					{
+						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+						"features": "Coinbase"
+					}
+				],
+				"kernels": [
+					{
+						"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+						"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+						"features": "Plain",
+						"fee": "700000",
+						"lock_height": "0"
+					}
+				],
+				"outputs": [
+					{
+						"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
+						"features": "Plain",
+						"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
+					},
+					{
+						"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
+						"features": "Plain",
+						"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
+					}
+				]
+				},
+				"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
+			}
+		}
+	}
+	# "#
+	# , true, 5, true, true, false, false);
+	```
+	 */
+	fn get_stored_tx(
+		&self,
+		token: Token,
+		tx: &TxLogEntry,
+	) -> Result<Option<TransactionV3>, ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_tx_details](struct.Owner.html#method.get_tx_details).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_tx_details",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"tx_id": 0
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": [
+				true,
+				{
+					"inputs": [],
+					"kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
+					"outputs": [
+						{
+							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+							"output": {
+								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
+								"height": "1",
+								"is_coinbase": true,
+								"is_foundation": false,
+								"key_id": "0300000000000000000000000000000000",
+								"lock_height": "4",
+								"mmr_index": null,
+								"n_child": 0,
+								"root_key_id": "0200000000000000000000000000000000",
+								"spent_by_tx": null,
+								"spent_height": null,
+								"status": "Unspent",
+								"tags": [],
+								"tx_log_entry": 0,
+								"value": "1457920000"
+							}
+						}
+					],
+					"payment_proof": null,
+					"slate_history": [],
+					"stored_tx": null,
+					"tx": {
+						"amount_credited": "1457920000",
+						"amount_debited": "0",
+						"confirmation_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+						"confirmation_height": "1",
+						"confirmation_ts": "2019-01-15T16:01:26Z",
+						"confirmations": "1",
+						"confirmed": true,
+						"creation_ts": "2019-01-15T16:01:26Z",
+						"deposit_tag": null,
+						"fee": null,
+						"id": 0,
+						"kernel_excess": "09a89280fa8d888358ab730383f00a3d990b7f2c6b17fc960501f30aac8e014478",
+						"kernel_lookup_min_height": 1,
+						"messages": null,
+						"num_inputs": 0,
+						"num_outputs": 1,
+						"parent_key_id": "0200000000000000000000000000000000",
+						"payment_proof": null,
+						"post_state": null,
+						"state": "Confirmed",
+						"pow_type": "Cuckatoo",
+						"sender_proof_address": null,
+						"seq": 1,
+						"stored_tx": null,
+						"ttl_cutoff_height": null,
+						"tx_slate_id": null,
+						"tx_type": "ConfirmedCoinbase"
+					}
+				}
+			]
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	*/
+	fn get_tx_details(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		tx_id: u32,
+	) -> Result<(bool, TxDetails), ErrorKind>;
+
+	/**
+	Networked version of [Owner::verify_slate_messages](struct.Owner.html#method.verify_slate_messages).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "verify_slate_messages",
+		"id": 1,
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"slate": {
+				"amount": "1457920000",
+				"fee": "8000000",
+				"height": "4",
+				"id": "0436430c-2b02-624c-2032-570501212b00",
+				"lock_height": "4",
+				"ttl_cutoff_height": null,
+				"num_participants": 2,
+				"participant_data": [
+				{
+					"id": "0",
+					"message": "my message",
+					"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b1d4c1358be398f801eb90d933774b5218fa7e769b11c4c640402253353656f75",
+					"part_sig": null,
+					"public_blind_excess": "034b4df2f0558b73ea72a1ca5c4ab20217c66bbe0829056fca7abe76888e9349ee",
+					"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
+				}
+				],
+				"tx": {
+					"body": {
+						"inputs": [
+						{
+							"commit": "08e1da9e6dc4d6e808a718b2f110a991dd775d65ce5ae408a4e1f002a4961aa9e7",
+							"features": "Coinbase"
+						}
+						],
+						"kernels": [
+						{
+							"excess": "000000000000000000000000000000000000000000000000000000000000000000",
+							"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+							"features": "HeightLocked",
+							"fee": "8000000",
+							"lock_height": "4"
+						}
+						],
+						"outputs": [
+						{
+							"commit": "094be57c91787fc2033d5d97fae099f1a6ddb37ea48370f1a138f09524c767fdd3",
+							"features": "Plain",
+							"proof": "2a42e9e902b70ce44e1fccb14de87ee0a97100bddf12c6bead1b9c5f4eb60300f29c13094fa12ffeee238fb4532b18f6b61cf51b23c1c7e1ad2e41560dc27edc0a2b9e647a0b3e4e806fced5b65e61d0f1f5197d3e2285c632d359e27b6b9206b2caffea4f67e0c7a2812e7a22c134b98cf89bd43d9f28b8bec25cce037a0ac5b1ae8f667e54e1250813a5263004486b4465ad4e641ab2b535736ea26535a11013564f08f483b7dab1c2bcc3ee38eadf2f7850eff7e3459a4bbabf9f0cf6c50d0c0a4120565cd4a2ce3e354c11721cd695760a24c70e0d5a0dfc3c5dcd51dfad6de2c237a682f36dc0b271f21bb3655e5333016aaa42c2efa1446e5f3c0a79ec417c4d30f77556951cb0f05dbfafb82d9f95951a9ea241fda2a6388f73ace036b98acce079f0e4feebccc96290a86dcc89118a901210b245f2d114cf94396e4dbb461e82aa26a0581389707957968c7cdc466213bb1cd417db207ef40c05842ab67a01a9b96eb1430ebc26e795bb491258d326d5174ad549401059e41782121e506744af8af9d8e493644a87d613600888541cbbe538c625883f3eb4aa3102c5cfcc25de8e97af8927619ce6a731b3b8462d51d993066b935b0648d2344ad72e4fd70f347fbd81041042e5ea31cc7b2e3156a920b80ecba487b950ca32ca95fae85b759c936246ecf441a9fdd95e8fee932d6782cdec686064018c857efc47fb4b2a122600d5fdd79af2486f44df7e629184e1c573bc0a9b3feb40b190ef2861a1ab45e2ac2201b9cd42e495deea247269820ed32389a2810ad6c0f9a296d2a2d9c54089fed50b7f5ecfcd33ab9954360e1d7f5598c32128cfcf2a1d8bf14616818da8a5343bfa88f0eedf392e9d4ab1ace1b60324129cd4852c2e27813a9cf71a6ae6229a4fcecc1a756b3e664c5f50af333082616815a3bec8fc0b75b8e4e767d719"
+						}
+						]
+					},
+					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000",
+					"payment_proof": null
+				},
+				"version_info": {
+					"orig_version": 3,
+					"version": 3,
+					"block_header_version": 6
+				}
+			}
+		}
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"id": 1,
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# ,true, 0 ,false, false, false, false);
+	```
+	*/
+	fn verify_slate_messages(&self, token: Token, slate: VersionedSlate) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::scan](struct.Owner.html#method.scan).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "scan",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"start_height": 1,
+			"delete_unconfirmed": false
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 1, false, false, false, false);
+	```
+	 */
+	fn scan(
+		&self,
+		token: Token,
+		start_height: Option<u64>,
+		delete_unconfirmed: bool,
+	) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::node_height](struct.Owner.html#method.node_height).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "node_height",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": {
+				"header_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+				"height": "5",
+				"updated_from_node": true
+			}
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	 */
+	fn node_height(&self, token: Token) -> Result<NodeHeightResult, ErrorKind>;
+
+	/**
+	Networked version of [Owner::node_status](struct.Owner.html#method.node_status).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "node_status",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": {
+				"node_reachable": true,
+				"node_height": "5",
+				"header_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+				"last_confirmed_height": "5",
+				"sync_lag": "0",
+				"node_version": null,
+				"tip_timestamp": "2019-01-15T16:01:26Z",
+				"clock_skew_secs": 12,
+				"clock_skewed": false
+			}
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	 */
+	fn node_status(&self, token: Token) -> Result<NodeStatus, ErrorKind>;
+
+	/**
+	Networked version of [Owner::node_ping](struct.Owner.html#method.node_ping).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "node_ping",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": {
+				"reachable": true,
+				"height": "5",
+				"header_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
+				"latency_ms": 12,
+				"error": null
+			}
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	 */
+	fn node_ping(&self, token: Token) -> Result<NodePing, ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_kernel_status](struct.Owner.html#method.get_kernel_status).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_kernel_status",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"excess": "08e1da9e6dc4d6e808a718b2f110a991dd775d65ce5ae408a4e1f002a4961aa9e7"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": {
+				"confirmed": false,
+				"excess": "08e1da9e6dc4d6e808a718b2f110a991dd775d65ce5ae408a4e1f002a4961aa9e7",
+				"height": null,
+				"mmr_index": null,
+				"tx_log_entry": null
+			}
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	 */
+	fn get_kernel_status(&self, token: Token, excess: String) -> Result<KernelStatus, ErrorKind>;
+
+	/**
+	Networked version of [Owner::wait_tx_finality](struct.Owner.html#method.wait_tx_finality).
+	The `timeout` is given in milliseconds.
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "wait_tx_finality",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"tx_id": 99,
+			"target_confirmations": 10,
+			"timeout": 0
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"TransactionDoesntExist": "99"
+			}
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	 */
+	fn wait_tx_finality(
+		&self,
+		token: Token,
+		tx_id: u32,
+		target_confirmations: u64,
+		timeout: u32,
+	) -> Result<TxFinality, ErrorKind>;
+
+	/**
+	Networked version of [Owner::watch_tx_finality](struct.Owner.html#method.watch_tx_finality).
+	The `timeout` is given in milliseconds.
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "watch_tx_finality",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"tx_id": 3,
+			"target_confirmations": 10,
+			"callback_url": "ftp://localhost/settled",
+			"timeout": 3600000
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"FinalityWatch": "callback url ftp://localhost/settled must be http or https"
+			}
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	 */
+	fn watch_tx_finality(
+		&self,
+		token: Token,
+		tx_id: u32,
+		target_confirmations: u64,
+		callback_url: String,
+		timeout: u32,
+	) -> Result<(), ErrorKind>;
+
+	/**
+		Initializes the secure JSON-RPC API. This function must be called and a shared key
+		established before any other OwnerAPI JSON-RPC function can be called.
+
+		The shared key will be derived using ECDH with the provided public key on the secp256k1 curve. This
+		function will return its public key used in the derivation, which the caller should multiply by its
+		private key to derive the shared key.
+
+		Once the key is established, all further requests and responses are encrypted and decrypted with the
+		following parameters:
+		* AES-256 in GCM mode with 128-bit tags and 96 bit nonces
+		* 12 byte nonce which must be included in each request/response to use on the decrypting side
+		* Empty vector for additional data
+		* Suffix length = AES-256 GCM mode tag length = 16 bytes
+		*
+
+		Fully-formed JSON-RPC requests (as documented) should be encrypted using these parameters, encoded
+		into base64 and included with the one-time nonce in a request for the `encrypted_request_v3` method
+		as follows:
+
+		```
+		# let s = r#"
+		{
+			 "jsonrpc": "2.0",
+			 "method": "encrypted_request_v3",
+			 "id": "1",
+			 "params": {
+					"nonce": "ef32...",
+					"body_enc": "e0bcd..."
+			 }
+		}
+		# "#;
+		```
+
+		With a typical response being:
+
+		```
+		# let s = r#"{
+		{
+			 "jsonrpc": "2.0",
+			 "method": "encrypted_response_v3",
+			 "id": "1",
+			 "Ok": {
+					"nonce": "340b...",
+					"body_enc": "3f09c..."
+			 }
+		}
+		# }"#;
+		```
+
+		Errors returned by the called function are encrypted in the response like any other result.
+		Errors in the encryption layer itself can't be, and are returned unencrypted, with the id of
+		the `encrypted_request_v3` request and one of the following codes:
+		* `-32001` - The shared key hasn't been established with `init_secure_api`
+		* `-32002` - The request is malformed, its nonce isn't 12 bytes, or it can't be decrypted
+		with the shared key
+		* `-32003` - The response couldn't be encrypted
+		* `-32004` - The request was made outside the replay window (`owner_api_replay_window_secs`,
+		5 minutes either side of the wallet's time by default), or was already made with the same
+		nonce and token. Every request must be encrypted with a fresh nonce, and should carry the
+		time it was made, in seconds since the epoch, as a top level `timestamp` field of the
+		request before it's encrypted. Requests without one are still accepted, for older
+		clients, but are only refused as replays within the window of when they're received
+
+	*/
+
+	fn init_secure_api(&self, ecdh_pubkey: ECDHPubkey) -> Result<ECDHPubkey, ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_top_level_directory](struct.Owner.html#method.get_top_level_directory).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_top_level_directory",
+		"params": {
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": "/doctest/dir"
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	*/
+
+	fn get_top_level_directory(&self) -> Result<String, ErrorKind>;
+
+	/**
+	Networked version of [Owner::set_top_level_directory](struct.Owner.html#method.set_top_level_directory).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "set_top_level_directory",
+		"params": {
+			"dir": "/home/wallet_user/my_wallet_dir"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	*/
+
+	fn set_top_level_directory(&self, dir: String) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::create_config](struct.Owner.html#method.create_config).
+
+	# Json rpc example
+
+	Both the `wallet_config` and `logging_config` parameters can be `null`, the examples
+	below are for illustration. Note that the values provided for `log_file_path` and `data_file_dir`
+	will be ignored and replaced with the actual values based on the value of `get_top_level_directory`
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "create_config",
+		"params": {
+			"chain_type": "Mainnet",
+			"wallet_config": {
+				"chain_type": null,
+				"api_listen_interface": "127.0.0.1",
+				"api_listen_port": 3415,
+				"owner_api_listen_port": 3420,
+				"api_secret_path": null,
+				"node_api_secret_path": null,
+				"check_node_api_http_addr": "http://127.0.0.1:3413",
+				"owner_api_include_foreign": false,
+				"data_file_dir": "/path/to/data/file/dir",
+				"no_commit_cache": null,
+				"tls_certificate_file": null,
+				"tls_certificate_key": null,
+				"dark_background_color_scheme": null,
+				"keybase_notify_ttl": null
+			},
+			"logging_config": {
+				"log_to_stdout": false,
+				"stdout_log_level": "Info",
+				"log_to_file": true,
+				"file_log_level": "Debug",
+				"log_file_path": "/path/to/log/file",
+				"log_file_append": true,
+				"log_max_size": null,
+				"log_max_files": null,
+				"tui_running": null
+			},
+			"tor_config" : {
+				"use_tor_listener": true,
+				"socks_proxy_addr": "127.0.0.1:9050",
+				"send_config_dir": "."
+			}
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 5, false, false, false, false);
+	```
+	*/
+	fn create_config(
+		&self,
+		chain_type: global::ChainTypes,
+		wallet_config: Option<WalletConfig>,
+		logging_config: Option<LoggingConfig>,
+		tor_config: Option<TorConfig>,
+	) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::create_wallet](struct.Owner.html#method.create_wallet).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "create_wallet",
+		"params": {
+			"name": null,
+			"mnemonic": null,
+			"mnemonic_length": 0,
+			"password": "my_secret_password"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn create_wallet(
+		&self,
+		name: Option<String>,
+		mnemonic: Option<String>,
+		mnemonic_length: u32,
+		password: String,
+	) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::open_wallet](struct.Owner.html#method.open_wallet).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "open_wallet",
+		"params": {
+			"name": null,
+			"password": "my_secret_password"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": "d096b3cb75986b3b13f80b8f5243a9edf0af4c74ac37578c5a12cfb5b59b1868"
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn open_wallet(&self, name: Option<String>, password: String) -> Result<Token, ErrorKind>;
+
+	/**
+	Networked version of [Owner::init_wallet_from_env](struct.Owner.html#method.init_wallet_from_env).
+
+	Creates or recovers the wallet and opens it, for bootstrapping a containerized wallet
+	without an interactive `init`. Only allowed with the one-shot provisioning token the
+	owner listener was started with (`EPIC_WALLET_PROVISIONING_TOKEN`), and only once. The
+	`mnemonic` and `password` may be `null`, in which case they are read from the
+	`EPIC_WALLET_MNEMONIC` and `EPIC_WALLET_PASSWORD` environment variables of the wallet
+	process; without a mnemonic a new random seed is created.
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "init_wallet_from_env",
+		"params": {
+			"name": null,
+			"provisioning_token": "bootstrap-token",
+			"mnemonic": null,
+			"password": "my_secret_password"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Err": {
+				"ProvisioningDenied": "provisioning is not enabled, or has already been done"
+			}
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn init_wallet_from_env(
+		&self,
+		name: Option<String>,
+		provisioning_token: String,
+		mnemonic: Option<String>,
+		password: Option<String>,
+	) -> Result<Token, ErrorKind>;
+
+	/**
+	Networked version of [Owner::close_wallet](struct.Owner.html#method.close_wallet).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "close_wallet",
+		"params": {
+			"name": null
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
 	```
 	*/
 
-	fn process_invoice_tx(
-		&self,
-		token: Token,
-		slate: VersionedSlate,
-		args: InitTxArgs,
-	) -> Result<VersionedSlate, ErrorKind>;
+	fn close_wallet(&self, name: Option<String>) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::tx_lock_outputs](struct.Owner.html#method.tx_lock_outputs).
+	Networked version of [Owner::reveal_mnemonic](struct.Owner.html#method.reveal_mnemonic).
+
+	Guarded: the wallet must be open, the call must carry the session `token`, and the wallet
+	password has to be given again.
 
 	# Json rpc example
 
@@ -722,89 +3892,42 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "tx_lock_outputs",
-		"id": 1,
+		"method": "get_mnemonic",
 		"params": {
 			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"slate": {
-				"amount": "1457920000",
-				"fee": "8000000",
-				"height": "4",
-				"id": "0436430c-2b02-624c-2032-570501212b00",
-				"lock_height": "4",
-				"ttl_cutoff_height": null,
-				"num_participants": 2,
-				"payment_proof": null,
-				"participant_data": [
-				{
-					"id": "0",
-					"message": "my message",
-					"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b1d4c1358be398f801eb90d933774b5218fa7e769b11c4c640402253353656f75",
-					"part_sig": null,
-					"public_blind_excess": "034b4df2f0558b73ea72a1ca5c4ab20217c66bbe0829056fca7abe76888e9349ee",
-					"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
-				}
-				],
-				"tx": {
-					"body": {
-						"inputs": [
-						{
-							"commit": "08e1da9e6dc4d6e808a718b2f110a991dd775d65ce5ae408a4e1f002a4961aa9e7",
-							"features": "Coinbase"
-						}
-						],
-						"kernels": [
-						{
-							"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-							"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-							"features": "HeightLocked",
-							"fee": "8000000",
-							"lock_height": "4"
-						}
-						],
-						"outputs": [
-						{
-							"commit": "094be57c91787fc2033d5d97fae099f1a6ddb37ea48370f1a138f09524c767fdd3",
-							"features": "Plain",
-							"proof": "2a42e9e902b70ce44e1fccb14de87ee0a97100bddf12c6bead1b9c5f4eb60300f29c13094fa12ffeee238fb4532b18f6b61cf51b23c1c7e1ad2e41560dc27edc0a2b9e647a0b3e4e806fced5b65e61d0f1f5197d3e2285c632d359e27b6b9206b2caffea4f67e0c7a2812e7a22c134b98cf89bd43d9f28b8bec25cce037a0ac5b1ae8f667e54e1250813a5263004486b4465ad4e641ab2b535736ea26535a11013564f08f483b7dab1c2bcc3ee38eadf2f7850eff7e3459a4bbabf9f0cf6c50d0c0a4120565cd4a2ce3e354c11721cd695760a24c70e0d5a0dfc3c5dcd51dfad6de2c237a682f36dc0b271f21bb3655e5333016aaa42c2efa1446e5f3c0a79ec417c4d30f77556951cb0f05dbfafb82d9f95951a9ea241fda2a6388f73ace036b98acce079f0e4feebccc96290a86dcc89118a901210b245f2d114cf94396e4dbb461e82aa26a0581389707957968c7cdc466213bb1cd417db207ef40c05842ab67a01a9b96eb1430ebc26e795bb491258d326d5174ad549401059e41782121e506744af8af9d8e493644a87d613600888541cbbe538c625883f3eb4aa3102c5cfcc25de8e97af8927619ce6a731b3b8462d51d993066b935b0648d2344ad72e4fd70f347fbd81041042e5ea31cc7b2e3156a920b80ecba487b950ca32ca95fae85b759c936246ecf441a9fdd95e8fee932d6782cdec686064018c857efc47fb4b2a122600d5fdd79af2486f44df7e629184e1c573bc0a9b3feb40b190ef2861a1ab45e2ac2201b9cd42e495deea247269820ed32389a2810ad6c0f9a296d2a2d9c54089fed50b7f5ecfcd33ab9954360e1d7f5598c32128cfcf2a1d8bf14616818da8a5343bfa88f0eedf392e9d4ab1ace1b60324129cd4852c2e27813a9cf71a6ae6229a4fcecc1a756b3e664c5f50af333082616815a3bec8fc0b75b8e4e767d719"
-						}
-						]
-					},
-					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-				},
-				"version_info": {
-					"orig_version": 3,
-					"version": 3,
-					"block_header_version": 6
-				}
-			},
-			"participant_id": 0
-		}
+			"name": null,
+			"password": ""
+		},
+		"id": 1
 	}
 	# "#
 	# ,
 	# r#"
 	{
-		"jsonrpc": "2.0",
 		"id": 1,
+		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": "fat twenty mean degree forget shell check candy immense awful flame next during february bulb bike sun wink theory day kiwi embrace peace lunch"
 		}
 	}
 	# "#
-	# ,true, 5 ,true, false, false, false);
-
+	# , true, 0, false, false, false, false);
 	```
-	 */
-	fn tx_lock_outputs(
+	*/
+
+	fn get_mnemonic(
 		&self,
 		token: Token,
-		slate: VersionedSlate,
-		participant_id: usize,
-	) -> Result<(), ErrorKind>;
+		name: Option<String>,
+		password: String,
+	) -> Result<String, ErrorKind>;
 
 	/**
-	Networked version of [Owner::finalize_tx](struct.Owner.html#method.finalize_tx).
+	Networked version of [Owner::rotate_keychain_mask](struct.Owner.html#method.rotate_keychain_mask).
+
+	Issues a new `token` for the open wallet and invalidates the one passed in, without closing
+	the wallet. Long-lived sessions can call this periodically so that a leaked token stops
+	working. The updater has to be stopped while rotating.
 
 	# Json rpc example
 
@@ -813,168 +3936,70 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "finalize_tx",
-		"id": 1,
+		"method": "rotate_keychain_mask",
 		"params": {
-			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"slate": {
-				"version_info": {
-					"version": 3,
-					"orig_version": 3,
-					"block_header_version": 6
-				},
-				"num_participants": 2,
-				"id": "0436430c-2b02-624c-2032-570501212b00",
-				"payment_proof": null,
-				"tx": {
-					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000",
-					"body": {
-						"inputs": [
-							{
-								"features": "Coinbase",
-								"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e"
-							},
-							{
-								"features": "Coinbase",
-								"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e"
-							}
-						],
-						"outputs": [
-							{
-								"features": "Plain",
-								"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
-								"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
-							},
-							{
-								"features": "Plain",
-								"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
-								"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
-							}
-						],
-						"kernels": [
-							{
-								"features": "Plain",
-								"fee": "700000",
-								"lock_height": "0",
-								"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-								"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
-							}
-						]
-					}
-				},
-				"amount": "600000000",
-				"fee": "700000",
-				"height": "5",
-				"lock_height": "0",
-				"ttl_cutoff_height": null,
-				"participant_data": [
-					{
-						"id": "0",
-						"public_blind_excess": "028e1bbb43e6038efc42054778d0a1aa184b2cf4d51acb40a2a8dc049788d97bd2",
-						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f",
-						"part_sig": null,
-						"message": null,
-						"message_sig": null
-					},
-					{
-						"id": "1",
-						"public_blind_excess": "03e14bacb4cfeda43edf6c1b0ffced9a358a119c7936bc68af724477eb91d9e4eb",
-						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f",
-						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b129340313ed7db02f6fd9a16c23ae8d5801af4fdc2ea580e2dec26e3821d5c17",
-						"message": null,
-						"message_sig": null
-					}
-				]
-			}
-		}
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
+		"id": 1
 	}
 	# "#
 	# ,
 	# r#"
 	{
-		"jsonrpc": "2.0",
 		"id": 1,
+		"jsonrpc": "2.0",
 		"result": {
-		"Ok": {
-				"amount": "600000000",
-				"fee": "700000",
-				"height": "5",
-				"id": "0436430c-2b02-624c-2032-570501212b00",
-				"lock_height": "0",
-				"ttl_cutoff_height": null,
-				"num_participants": 2,
-				"payment_proof": null,
-				"participant_data": [
-					{
-						"id": "0",
-						"message": null,
-						"message_sig": null,
-						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b93f888685e13250c5cb6b830ff898264ce247c73d3ab47845c01bcc6455ecbe5",
-						"public_blind_excess": "028e1bbb43e6038efc42054778d0a1aa184b2cf4d51acb40a2a8dc049788d97bd2",
-						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
-					},
-					{
-						"id": "1",
-						"message": null,
-						"message_sig": null,
-						"part_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b129340313ed7db02f6fd9a16c23ae8d5801af4fdc2ea580e2dec26e3821d5c17",
-						"public_blind_excess": "03e14bacb4cfeda43edf6c1b0ffced9a358a119c7936bc68af724477eb91d9e4eb",
-						"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
-					}
-				],
-				"tx": {
-					"body": {
-					"inputs": [
-						{
-							"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
-							"features": "Coinbase"
-						},
-						{
-							"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
-							"features": "Coinbase"
-						}
-					],
-					"kernels": [
-						{
-							"excess": "08d09187cb93cf5d6b97b28e8ca529912bf35ec8773d3e9af9b3c174a270dc7f05",
-							"excess_sig": "66074d25a751c4743342c90ad8ead9454daa00d9b9aed29bca321036d16c4b4da58bc9999cea000f52b45347c1c46a3a4f3f70719696a09289ede2a9c87b27fd",
-							"features": "Plain",
-							"fee": "700000",
-							"lock_height": "0"
-						}
-					],
-					"outputs": [
-						{
-							"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
-							"features": "Plain",
-							"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
-						},
-						{
-							"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
-							"features": "Plain",
-							"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
-						}
-					]
-					},
-					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-				},
-				"version_info": {
-					"orig_version": 3,
-					"version": 3,
-					"block_header_version": 6
-				}
-			}
+			"Ok": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+
+	fn rotate_keychain_mask(&self, token: Token) -> Result<Token, ErrorKind>;
+
+	/**
+	Networked version of [Owner::change_password](struct.Owner.html#method.change_password).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "change_password",
+		"params": {
+			"name": null,
+			"old": "",
+			"new": "new_password"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": null
 		}
 	}
 	# "#
-	# , true, 5, true, true, false, false);
+	# , true, 0, false, false, false, false);
 	```
-	 */
-	fn finalize_tx(&self, token: Token, slate: VersionedSlate)
-		-> Result<VersionedSlate, ErrorKind>;
+	*/
+	fn change_password(
+		&self,
+		name: Option<String>,
+		old: String,
+		new: String,
+	) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::post_tx](struct.Owner.html#method.post_tx).
+	Networked version of [Owner::delete_wallet](struct.Owner.html#method.delete_wallet).
 
 	# Json rpc example
 
@@ -983,49 +4008,11 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"id": 1,
-		"method": "post_tx",
+		"method": "delete_wallet",
 		"params": {
-			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"tx": {
-			"offset": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"body": {
-				"inputs": [
-					{
-						"features": "Coinbase",
-						"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e"
-					},
-					{
-						"features": "Coinbase",
-						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e"
-					}
-				],
-				"kernels": [
-					{
-						"features": "Plain",
-						"fee": "700000",
-						"lock_height": "0",
-						"excess": "08d09187cb93cf5d6b97b28e8ca529912bf35ec8773d3e9af9b3c174a270dc7f05",
-						"excess_sig": "66074d25a751c4743342c90ad8ead9454daa00d9b9aed29bca321036d16c4b4da58bc9999cea000f52b45347c1c46a3a4f3f70719696a09289ede2a9c87b27fd"
-					}
-				],
-				"outputs": [
-					{
-						"features": "Plain",
-						"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
-						"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
-					},
-					{
-						"features": "Plain",
-						"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
-						"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
-					}
-				]
-
-			}
+			"name": null
 		},
-		"fluff": false
-		}
+		"id": 1
 	}
 	# "#
 	# ,
@@ -1038,14 +4025,13 @@ pub trait OwnerRpcS {
 		}
 	}
 	# "#
-	# , true, 5, true, true, true, false);
+	# , true, 0, false, false, false, false);
 	```
-	 */
-
-	fn post_tx(&self, token: Token, tx: TransactionV3, fluff: bool) -> Result<(), ErrorKind>;
+	*/
+	fn delete_wallet(&self, name: Option<String>) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::cancel_tx](struct.Owner.html#method.cancel_tx).
+	Networked version of [Owner::start_updated](struct.Owner.html#method.start_updater).
 
 	# Json rpc example
 
@@ -1054,11 +4040,10 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "cancel_tx",
+		"method": "start_updater",
 		"params": {
 			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"tx_id": null,
-			"tx_slate_id": "0436430c-2b02-624c-2032-570501212b00"
+			"frequency": 30000
 		},
 		"id": 1
 	}
@@ -1073,18 +4058,14 @@ pub trait OwnerRpcS {
 		}
 	}
 	# "#
-	# , true, 5, true, true, false, false);
+	# , true, 0, false, false, false, false);
 	```
-	 */
-	fn cancel_tx(
-		&self,
-		token: Token,
-		tx_id: Option<u32>,
-		tx_slate_id: Option<Uuid>,
-	) -> Result<(), ErrorKind>;
+	*/
+
+	fn start_updater(&self, token: Token, frequency: u32) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::get_stored_tx](struct.Owner.html#method.get_stored_tx).
+	Networked version of [Owner::start_tip_updater](struct.Owner.html#method.start_tip_updater).
 
 	# Json rpc example
 
@@ -1093,102 +4074,32 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "get_stored_tx",
-		"id": 1,
+		"method": "start_tip_updater",
 		"params": {
 			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"tx": {
-				"amount_credited": "59993000000",
-				"amount_debited": "120000000000",
-				"confirmation_ts": "2019-01-15T16:01:26Z",
-				"confirmed": false,
-				"creation_ts": "2019-01-15T16:01:26Z",
-				"fee": "7000000",
-				"id": 5,
-				"messages": {
-					"messages": [
-						{
-							"id": "0",
-							"message": null,
-							"message_sig": null,
-							"public_key": "033ac2158fa0077f087de60c19d8e431753baa5b63b6e1477f05a2a6e7190d4592"
-						},
-						{
-							"id": "1",
-							"message": null,
-							"message_sig": null,
-							"public_key": "024f9bc78c984c78d6e916d3a00746aa30fa1172124c8dbc0cbddcb7b486719bc7"
-						}
-					]
-				},
-				"num_inputs": 2,
-				"num_outputs": 1,
-				"parent_key_id": "0200000000000000000000000000000000",
-				"stored_tx": "0436430c-2b02-624c-2032-570501212b00.epictx",
-				"tx_slate_id": "0436430c-2b02-624c-2032-570501212b00",
-				"tx_type": "TxSent",
-				"kernel_excess": null,
-				"kernel_lookup_min_height": null
-			}
-		}
+			"poll_frequency": 5000
+		},
+		"id": 1
 	}
 	# "#
 	# ,
 	# r#"
 	{
-		"jsonrpc": "2.0",
 		"id": 1,
+		"jsonrpc": "2.0",
 		"result": {
-			"Ok": {
-				"body": {
-				"inputs": [
-					{
-						"commit": "09d8836ffd38ffca42567ef965fdcf1f35b05aeb357664d70cd482438ca0ca0c9e",
-						"features": "Coinbase"
-					},
-					{
-						"commit": "089be87c488db1e7c783b19272a83b23bce56a5263163554b345c6f7ffedac517e",
-						"features": "Coinbase"
-					}
-				],
-				"kernels": [
-					{
-						"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-						"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-						"features": "Plain",
-						"fee": "700000",
-						"lock_height": "0"
-					}
-				],
-				"outputs": [
-					{
-						"commit": "091454e23b4dbc71f546a41035d69f4c87d0f6efb5ceb119cc0d2eef80ba1928d7",
-						"features": "Plain",
-						"proof": "1a32a93de1dad833b4ae66d042784c435f60ac452f769d2d778772b3e2f2ca9fb16191636222b35866f273935f657ff37e1d38b877e12b7bcce98b1aa71f47a701b9ed8c648e2d6ab18ac0f8f7cf4a7c0aebb2c15681a684ec6f4d385e5db20e7bf9e6f3d8554ada1b82ac2fa9b77cb0a4c4c6b6c740d938fc0c6031a1cc0c0839701e6dab439c4dcdb32ca87d510b582efbabe8f8b783a330bc2c4451d1c2949a6ad901d40f7abc6103fadebba22016a955eaec4a0215398afbc7d22a4ad5bf3103446f4fe5440ded3bd9db607a69b8ca7c005c09e82fa367febc532b8d5c573e2bcc65a972bf76cea98943d9baaf209c84b4b70d56444c22cd334c7299000122de110f957b7af1f4d7f3816e053c94731113fd098bd2c0ccbe4c19152dd07a8d137b453e5a9d19cca576b494f448c5673babf9122297e4d2f4bd4a5a768c4da040527816d6ff91edb57da4053df167a44d2d5cf194bf30a47bcdd4ff541638b3db02e8ac882fb00767bf50fe5bf1b6077c8ad4f163ce75f21c99f708a9bcc0676034351e5ca68894550fcca5ee868d3d9d87e164612f09c79f2676a4acd8a8266e0f794c49318f8a1595ee1ff4e55e9cf5f3361cc473a032bd3bbd36a085f0c03f9b451b472d6a6a7ea9d858fd42a49c2e68c25bf8f18dd8e691168fe6f10602c6ec04cbc2601afa479294da84ecb79bc9b225d8758f682a2df52882c586ead779711258a9443e43365df9d326ca9052615ce33efac4bd0452a18d5b294b9fcf86e860786a692bfbd84a8bf3a751adedd978b969177cd8897871c43cd28df40a4beefcc86b10e6822ba18673f396294c799e756c8a5f03c92499127ec567e9f5b794442c63be7119ce741e4e056f502ca4809f7c76dd6dad754a1b31201ca2e2540e125637e1da5d16c61e3bea90ded06892076268893c167e0faed26172f304900e"
-					},
-					{
-						"commit": "09414416856d650cd42abad97943f8ea32ff19e7d5d10201ff790d1ca941f578ed",
-						"features": "Plain",
-						"proof": "bdd12075099d53912b42073fd9c2841f2e21dff01656e7f909e1bbd30ada9a18b2f645128676ecddaecbffdcce43e9ff0e850acbce0f9a1e3fc525a7424d09040da752a8db0c8173f31ec4696bf007bf76801f63cedeadc66f4198836494de20a3d48150776c819d2e0a8ef376622d8a1cef78cd6928b3aa38883f51594fa50c3a772c539071c1c05ac4fce08768076618e2d5c7b3d46e28f1459f84f143a943957a4294011b093caf0e077020caf0668b379525df35f626641be6e81d7b711f1b32a98596c1829b9671d574f793e7f9f08c9118bdda60577053456caace5071cc14b10a67205e1c263bb53990fcf4fbcaea9cae652bd9e7ad6c1573ff96cd9271ecf0fabb895cea13b80d59bf7093fa03907911f526cb60df2bf0d3e2d4b81be4bbae55c466d6b221fa70cb145e6550e37856d080304e104fb23be97ae1499b4d3a2f7a4550545a03c20d374c081ac4f592477e23a20f418bcc59d9b02f665b898400a74350b88d793d383a0dc57618d58711e85e221383abb170c4a7f1640f30f2fc8258074f882b56453befecf3a61ed194a8ad98d1f6ab38c565b7cde60a7bb258066d9c5363c6bd618a9b3473b70a516ad4a67c2571e62fec4970eb4df902143aa130d333825f0a4cde9f93d8249c32f26bfadb26be8a5ceb6b5b6cdd076baa1cbde1973d83e64a1b35075dba69682e51cedfb82484276d56cf9e0601a272c0148ce070c6019ab2882405900164871f6b59d2c2a9f5d92674fe58cd9e036752eae8fb58e0fc29e3d59330ac92c1f263988f67add07a22770c381f29a602785244dbd46e4416ca56f25fe0cdd21714bcdf58c28329e22124247416b8de61297b6bd1630b93692a3a81c3107689f35cf4be5a8472b31552973ef2bcee5a298a858a768eefd0e31a3936790dd1c6e1379fffa0235c188b2c0f8b8b41abb84c32c608"
-					}
-				]
-				},
-				"offset": "d202964900000000d302964900000000d402964900000000d502964900000000"
-			}
+			"Ok": null
 		}
 	}
 	# "#
-	# , true, 5, true, true, false, false);
+	# , true, 0, false, false, false, false);
 	```
-	 */
-	fn get_stored_tx(
-		&self,
-		token: Token,
-		tx: &TxLogEntry,
-	) -> Result<Option<TransactionV3>, ErrorKind>;
+	*/
+
+	fn start_tip_updater(&self, token: Token, poll_frequency: u32) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::verify_slate_messages](struct.Owner.html#method.verify_slate_messages).
+	Networked version of [Owner::stop_updater](struct.Owner.html#method.stop_updater).
 
 	# Json rpc example
 
@@ -1197,82 +4108,28 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "verify_slate_messages",
-		"id": 1,
-		"params": {
-			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"slate": {
-				"amount": "1457920000",
-				"fee": "8000000",
-				"height": "4",
-				"id": "0436430c-2b02-624c-2032-570501212b00",
-				"lock_height": "4",
-				"ttl_cutoff_height": null,
-				"num_participants": 2,
-				"participant_data": [
-				{
-					"id": "0",
-					"message": "my message",
-					"message_sig": "8f07ddd5e9f5179cff19486034181ed76505baaad53e5d994064127b56c5841b1d4c1358be398f801eb90d933774b5218fa7e769b11c4c640402253353656f75",
-					"part_sig": null,
-					"public_blind_excess": "034b4df2f0558b73ea72a1ca5c4ab20217c66bbe0829056fca7abe76888e9349ee",
-					"public_nonce": "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f"
-				}
-				],
-				"tx": {
-					"body": {
-						"inputs": [
-						{
-							"commit": "08e1da9e6dc4d6e808a718b2f110a991dd775d65ce5ae408a4e1f002a4961aa9e7",
-							"features": "Coinbase"
-						}
-						],
-						"kernels": [
-						{
-							"excess": "000000000000000000000000000000000000000000000000000000000000000000",
-							"excess_sig": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-							"features": "HeightLocked",
-							"fee": "8000000",
-							"lock_height": "4"
-						}
-						],
-						"outputs": [
-						{
-							"commit": "094be57c91787fc2033d5d97fae099f1a6ddb37ea48370f1a138f09524c767fdd3",
-							"features": "Plain",
-							"proof": "2a42e9e902b70ce44e1fccb14de87ee0a97100bddf12c6bead1b9c5f4eb60300f29c13094fa12ffeee238fb4532b18f6b61cf51b23c1c7e1ad2e41560dc27edc0a2b9e647a0b3e4e806fced5b65e61d0f1f5197d3e2285c632d359e27b6b9206b2caffea4f67e0c7a2812e7a22c134b98cf89bd43d9f28b8bec25cce037a0ac5b1ae8f667e54e1250813a5263004486b4465ad4e641ab2b535736ea26535a11013564f08f483b7dab1c2bcc3ee38eadf2f7850eff7e3459a4bbabf9f0cf6c50d0c0a4120565cd4a2ce3e354c11721cd695760a24c70e0d5a0dfc3c5dcd51dfad6de2c237a682f36dc0b271f21bb3655e5333016aaa42c2efa1446e5f3c0a79ec417c4d30f77556951cb0f05dbfafb82d9f95951a9ea241fda2a6388f73ace036b98acce079f0e4feebccc96290a86dcc89118a901210b245f2d114cf94396e4dbb461e82aa26a0581389707957968c7cdc466213bb1cd417db207ef40c05842ab67a01a9b96eb1430ebc26e795bb491258d326d5174ad549401059e41782121e506744af8af9d8e493644a87d613600888541cbbe538c625883f3eb4aa3102c5cfcc25de8e97af8927619ce6a731b3b8462d51d993066b935b0648d2344ad72e4fd70f347fbd81041042e5ea31cc7b2e3156a920b80ecba487b950ca32ca95fae85b759c936246ecf441a9fdd95e8fee932d6782cdec686064018c857efc47fb4b2a122600d5fdd79af2486f44df7e629184e1c573bc0a9b3feb40b190ef2861a1ab45e2ac2201b9cd42e495deea247269820ed32389a2810ad6c0f9a296d2a2d9c54089fed50b7f5ecfcd33ab9954360e1d7f5598c32128cfcf2a1d8bf14616818da8a5343bfa88f0eedf392e9d4ab1ace1b60324129cd4852c2e27813a9cf71a6ae6229a4fcecc1a756b3e664c5f50af333082616815a3bec8fc0b75b8e4e767d719"
-						}
-						]
-					},
-					"offset": "d202964900000000d302964900000000d402964900000000d502964900000000",
-					"payment_proof": null
-				},
-				"version_info": {
-					"orig_version": 3,
-					"version": 3,
-					"block_header_version": 6
-				}
-			}
-		}
+		"method": "stop_updater",
+		"params": null,
+		"id": 1
 	}
 	# "#
 	# ,
 	# r#"
 	{
-		"jsonrpc": "2.0",
 		"id": 1,
+		"jsonrpc": "2.0",
 		"result": {
 			"Ok": null
 		}
 	}
 	# "#
-	# ,true, 0 ,false, false, false, false);
+	# , true, 0, false, false, false, false);
 	```
 	*/
-	fn verify_slate_messages(&self, token: Token, slate: VersionedSlate) -> Result<(), ErrorKind>;
+	fn stop_updater(&self) -> Result<(), ErrorKind>;
 
 	/**
-	Networked version of [Owner::scan](struct.Owner.html#method.scan).
+	Networked version of [Owner::get_updater_messages](struct.Owner.html#method.get_updater_messages).
 
 	# Json rpc example
 
@@ -1281,11 +4138,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "scan",
+		"method": "get_updater_messages",
 		"params": {
-			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"start_height": 1,
-			"delete_unconfirmed": false
+			"count": 1
 		},
 		"id": 1
 	}
@@ -1296,22 +4151,18 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": []
 		}
 	}
 	# "#
-	# , true, 1, false, false, false, false);
+	# , true, 0, false, false, false, false);
 	```
-	 */
-	fn scan(
-		&self,
-		token: Token,
-		start_height: Option<u64>,
-		delete_unconfirmed: bool,
-	) -> Result<(), ErrorKind>;
+	*/
+
+	fn get_updater_messages(&self, count: u32) -> Result<Vec<StatusMessage>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::node_height](struct.Owner.html#method.node_height).
+	Networked version of [Owner::get_public_proof_address](struct.Owner.html#method.get_public_proof_address).
 
 	# Json rpc example
 
@@ -1320,9 +4171,10 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "node_height",
+		"method": "get_public_proof_address",
 		"params": {
-			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"derivation_index": 0
 		},
 		"id": 1
 	}
@@ -1333,75 +4185,55 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": {
-				"header_hash": "d4b3d3c40695afd8c7760f8fc423565f7d41310b7a4e1c4a4a7950a66f16240d",
-				"height": "5",
-				"updated_from_node": true
-			}
+			"Ok": "32cdd63928854f8b2628b1dce4626ddcdf35d56cb7cfdf7d64cca5822b78d4d3"
 		}
 	}
 	# "#
-	# , true, 5, false, false, false, false);
+	# , true, 0, false, false, false, false);
 	```
-	 */
-	fn node_height(&self, token: Token) -> Result<NodeHeightResult, ErrorKind>;
-
-	/**
-		Initializes the secure JSON-RPC API. This function must be called and a shared key
-		established before any other OwnerAPI JSON-RPC function can be called.
-
-		The shared key will be derived using ECDH with the provided public key on the secp256k1 curve. This
-		function will return its public key used in the derivation, which the caller should multiply by its
-		private key to derive the shared key.
-
-		Once the key is established, all further requests and responses are encrypted and decrypted with the
-		following parameters:
-		* AES-256 in GCM mode with 128-bit tags and 96 bit nonces
-		* 12 byte nonce which must be included in each request/response to use on the decrypting side
-		* Empty vector for additional data
-		* Suffix length = AES-256 GCM mode tag length = 16 bytes
-		*
+	*/
 
-		Fully-formed JSON-RPC requests (as documented) should be encrypted using these parameters, encoded
-		into base64 and included with the one-time nonce in a request for the `encrypted_request_v3` method
-		as follows:
+	fn get_public_proof_address(
+		&self,
+		token: Token,
+		derivation_index: u32,
+	) -> Result<PubAddress, ErrorKind>;
 
-		```
-		# let s = r#"
-		{
-			 "jsonrpc": "2.0",
-			 "method": "encrypted_request_v3",
-			 "id": "1",
-			 "params": {
-					"nonce": "ef32...",
-					"body_enc": "e0bcd..."
-			 }
-		}
-		# "#;
-		```
+	/**
+	Networked version of [Owner::proof_address_from_onion_v3](struct.Owner.html#method.proof_address_from_onion_v3).
 
-		With a typical response being:
+	# Json rpc example
 
-		```
-		# let s = r#"{
-		{
-			 "jsonrpc": "2.0",
-			 "method": "encrypted_response_v3",
-			 "id": "1",
-			 "Ok": {
-					"nonce": "340b...",
-					"body_enc": "3f09c..."
-			 }
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "proof_address_from_onion_v3",
+		"params": {
+			"address_v3": "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid"
+		},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": "d03c09e9c19bb74aa9ea44e0fe5ae237a9bf40bddf0941064a80913a4459c8bb"
 		}
-		# }"#;
-		```
-
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
 	*/
 
-	fn init_secure_api(&self, ecdh_pubkey: ECDHPubkey) -> Result<ECDHPubkey, ErrorKind>;
+	fn proof_address_from_onion_v3(&self, address_v3: String) -> Result<PubAddress, ErrorKind>;
 
 	/**
-	Networked version of [Owner::get_top_level_directory](struct.Owner.html#method.get_top_level_directory).
+	Networked version of [Owner::validate_destination](struct.Owner.html#method.validate_destination).
 
 	# Json rpc example
 
@@ -1410,8 +4242,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "get_top_level_directory",
+		"method": "validate_destination",
 		"params": {
+			"dest": "ftp://192.168.0.2:3415"
 		},
 		"id": 1
 	}
@@ -1422,18 +4255,20 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": "/doctest/dir"
+			"Err": {
+				"WalletComms": "ftp://192.168.0.2:3415 is not an http(s) URL, onion address or slatepack address"
+			}
 		}
 	}
 	# "#
-	# , true, 5, false, false, false, false);
+	# , true, 0, false, false, false, false);
 	```
 	*/
 
-	fn get_top_level_directory(&self) -> Result<String, ErrorKind>;
+	fn validate_destination(&self, dest: String) -> Result<DestinationValidation, ErrorKind>;
 
 	/**
-	Networked version of [Owner::set_top_level_directory](struct.Owner.html#method.set_top_level_directory).
+	Networked version of [Owner::parse_payment_uri](struct.Owner.html#method.parse_payment_uri).
 
 	# Json rpc example
 
@@ -1442,9 +4277,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "set_top_level_directory",
+		"method": "parse_payment_uri",
 		"params": {
-			"dir": "/home/wallet_user/my_wallet_dir"
+			"uri": "epic:2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid?amount=1.5&message=order%2042&proof=1"
 		},
 		"id": 1
 	}
@@ -1455,65 +4290,34 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": {
+				"amount": "150000000",
+				"dest": "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid",
+				"message": "order 42",
+				"request_payment_proof": true
+			}
 		}
 	}
 	# "#
-	# , true, 5, false, false, false, false);
+	# , true, 0, false, false, false, false);
 	```
 	*/
 
-	fn set_top_level_directory(&self, dir: String) -> Result<(), ErrorKind>;
+	fn parse_payment_uri(&self, uri: String) -> Result<PaymentUri, ErrorKind>;
 
 	/**
-	Networked version of [Owner::create_config](struct.Owner.html#method.create_config).
+	Networked version of [Owner::parse_payment_request](struct.Owner.html#method.parse_payment_request).
 
 	# Json rpc example
 
-	Both the `wallet_config` and `logging_config` parameters can be `null`, the examples
-	below are for illustration. Note that the values provided for `log_file_path` and `data_file_dir`
-	will be ignored and replaced with the actual values based on the value of `get_top_level_directory`
-
 	```
 	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "create_config",
+		"method": "parse_payment_request",
 		"params": {
-			"chain_type": "Mainnet",
-			"wallet_config": {
-				"chain_type": null,
-				"api_listen_interface": "127.0.0.1",
-				"api_listen_port": 3415,
-				"owner_api_listen_port": 3420,
-				"api_secret_path": null,
-				"node_api_secret_path": null,
-				"check_node_api_http_addr": "http://127.0.0.1:3413",
-				"owner_api_include_foreign": false,
-				"data_file_dir": "/path/to/data/file/dir",
-				"no_commit_cache": null,
-				"tls_certificate_file": null,
-				"tls_certificate_key": null,
-				"dark_background_color_scheme": null,
-				"keybase_notify_ttl": null
-			},
-			"logging_config": {
-				"log_to_stdout": false,
-				"stdout_log_level": "Info",
-				"log_to_file": true,
-				"file_log_level": "Debug",
-				"log_file_path": "/path/to/log/file",
-				"log_file_append": true,
-				"log_max_size": null,
-				"log_max_files": null,
-				"tui_running": null
-			},
-			"tor_config" : {
-				"use_tor_listener": true,
-				"socks_proxy_addr": "127.0.0.1:9050",
-				"send_config_dir": "."
-			}
+			"request": "EPICREQUEST:not base32!"
 		},
 		"id": 1
 	}
@@ -1524,23 +4328,20 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Err": {
+				"PaymentRequest": "request is not base32 encoded"
+			}
 		}
 	}
 	# "#
-	# , true, 5, false, false, false, false);
+	# , true, 0, false, false, false, false);
 	```
 	*/
-	fn create_config(
-		&self,
-		chain_type: global::ChainTypes,
-		wallet_config: Option<WalletConfig>,
-		logging_config: Option<LoggingConfig>,
-		tor_config: Option<TorConfig>,
-	) -> Result<(), ErrorKind>;
+
+	fn parse_payment_request(&self, request: String) -> Result<PaymentRequest, ErrorKind>;
 
 	/**
-	Networked version of [Owner::create_wallet](struct.Owner.html#method.create_wallet).
+	Networked version of [Owner::create_payment_uri](struct.Owner.html#method.create_payment_uri).
 
 	# Json rpc example
 
@@ -1549,12 +4350,14 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "create_wallet",
+		"method": "create_payment_uri",
 		"params": {
-			"name": null,
-			"mnemonic": null,
-			"mnemonic_length": 0,
-			"password": "my_secret_password"
+			"payment_uri": {
+				"dest": "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid",
+				"amount": "150000000",
+				"message": "order 42",
+				"request_payment_proof": true
+			}
 		},
 		"id": 1
 	}
@@ -1565,7 +4368,7 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": "epic:2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid?amount=1.5&message=order%2042&proof=1"
 		}
 	}
 	# "#
@@ -1573,16 +4376,10 @@ pub trait OwnerRpcS {
 	```
 	*/
 
-	fn create_wallet(
-		&self,
-		name: Option<String>,
-		mnemonic: Option<String>,
-		mnemonic_length: u32,
-		password: String,
-	) -> Result<(), ErrorKind>;
+	fn create_payment_uri(&self, payment_uri: PaymentUri) -> Result<String, ErrorKind>;
 
 	/**
-	Networked version of [Owner::open_wallet](struct.Owner.html#method.open_wallet).
+	Networked version of [Owner::shard_balances](struct.Owner.html#method.shard_balances).
 
 	# Json rpc example
 
@@ -1591,10 +4388,12 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "open_wallet",
+		"method": "shard_balances",
 		"params": {
-			"name": null,
-			"password": "my_secret_password"
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"refresh_from_node": true,
+			"shards": ["default"],
+			"minimum_confirmations": 1
 		},
 		"id": 1
 	}
@@ -1605,18 +4404,34 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": "d096b3cb75986b3b13f80b8f5243a9edf0af4c74ac37578c5a12cfb5b59b1868"
+			"Ok": [
+				true,
+				[
+					{
+						"account": "default",
+						"amount_awaiting_confirmation": "0",
+						"amount_currently_spendable": "1457920000",
+						"amount_locked": "0"
+					}
+				]
+			]
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn open_wallet(&self, name: Option<String>, password: String) -> Result<Token, ErrorKind>;
+	fn shard_balances(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		shards: Vec<String>,
+		minimum_confirmations: u64,
+	) -> Result<(bool, Vec<ShardBalance>), ErrorKind>;
 
 	/**
-	Networked version of [Owner::close_wallet](struct.Owner.html#method.close_wallet).
+	Networked version of [Owner::rebalance_shards](struct.Owner.html#method.rebalance_shards).
 
 	# Json rpc example
 
@@ -1625,9 +4440,12 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "close_wallet",
+		"method": "rebalance_shards",
 		"params": {
-			"name": null
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"shards": ["default"],
+			"minimum_confirmations": 1,
+			"min_amount": 100000000
 		},
 		"id": 1
 	}
@@ -1642,14 +4460,20 @@ pub trait OwnerRpcS {
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn close_wallet(&self, name: Option<String>) -> Result<(), ErrorKind>;
+	fn rebalance_shards(
+		&self,
+		token: Token,
+		shards: Vec<String>,
+		minimum_confirmations: u64,
+		min_amount: u64,
+	) -> Result<Option<VersionedSlate>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::get_mnemonic](struct.Owner.html#method.get_mnemonic).
+	Networked version of [Owner::retrieve_post_queue](struct.Owner.html#method.retrieve_post_queue).
 
 	# Json rpc example
 
@@ -1658,10 +4482,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "get_mnemonic",
+		"method": "retrieve_post_queue",
 		"params": {
-			"name": null,
-			"password": ""
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
 		},
 		"id": 1
 	}
@@ -1672,18 +4495,18 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": "fat twenty mean degree forget shell check candy immense awful flame next during february bulb bike sun wink theory day kiwi embrace peace lunch"
+			"Ok": []
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn get_mnemonic(&self, name: Option<String>, password: String) -> Result<String, ErrorKind>;
+	fn retrieve_post_queue(&self, token: Token) -> Result<Vec<TxLogEntry>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::change_password](struct.Owner.html#method.change_password).
+	Networked version of [Owner::retrieve_send_queue](struct.Owner.html#method.retrieve_send_queue).
 
 	# Json rpc example
 
@@ -1692,11 +4515,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "change_password",
-		"params": {
-			"name": null,
-			"old": "",
-			"new": "new_password"
+		"method": "retrieve_send_queue",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
 		},
 		"id": 1
 	}
@@ -1707,22 +4528,18 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": []
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
-	fn change_password(
-		&self,
-		name: Option<String>,
-		old: String,
-		new: String,
-	) -> Result<(), ErrorKind>;
+
+	fn retrieve_send_queue(&self, token: Token) -> Result<Vec<QueuedSend>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::delete_wallet](struct.Owner.html#method.delete_wallet).
+	Networked version of [Owner::cancel_queued_send](struct.Owner.html#method.cancel_queued_send).
 
 	# Json rpc example
 
@@ -1731,9 +4548,10 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "delete_wallet",
+		"method": "cancel_queued_send",
 		"params": {
-			"name": null
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"id": "0436430c-2b02-624c-2032-570501212b00"
 		},
 		"id": 1
 	}
@@ -1744,17 +4562,20 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Err": {
+				"SendQueue": "no queued send 0436430c-2b02-624c-2032-570501212b00"
+			}
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
-	fn delete_wallet(&self, name: Option<String>) -> Result<(), ErrorKind>;
+
+	fn cancel_queued_send(&self, token: Token, id: Uuid) -> Result<QueuedSend, ErrorKind>;
 
 	/**
-	Networked version of [Owner::start_updated](struct.Owner.html#method.start_updater).
+	Networked version of [Owner::process_send_queue](struct.Owner.html#method.process_send_queue).
 
 	# Json rpc example
 
@@ -1763,10 +4584,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "start_updater",
+		"method": "process_send_queue",
 		"params": {
-			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"frequency": 30000
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
 		},
 		"id": 1
 	}
@@ -1777,18 +4597,18 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": []
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn start_updater(&self, token: Token, frequency: u32) -> Result<(), ErrorKind>;
+	fn process_send_queue(&self, token: Token) -> Result<Vec<QueuedSendResult>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::stop_updater](struct.Owner.html#method.stop_updater).
+	Networked version of [Owner::retrieve_jobs](struct.Owner.html#method.retrieve_jobs).
 
 	# Json rpc example
 
@@ -1797,8 +4617,10 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "stop_updater",
-		"params": null,
+		"method": "retrieve_jobs",
+		"params": {
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
+		},
 		"id": 1
 	}
 	# "#
@@ -1808,17 +4630,18 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": null
+			"Ok": []
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
-	fn stop_updater(&self) -> Result<(), ErrorKind>;
+
+	fn retrieve_jobs(&self, token: Token) -> Result<Vec<Job>, ErrorKind>;
 
 	/**
-	Networked version of [Owner::get_updater_messages](struct.Owner.html#method.get_updater_messages).
+	Networked version of [Owner::retry_job](struct.Owner.html#method.retry_job).
 
 	# Json rpc example
 
@@ -1827,9 +4650,10 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "get_updater_messages",
+		"method": "retry_job",
 		"params": {
-			"count": 1
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
+			"id": "0436430c-2b02-624c-2032-570501212b00"
 		},
 		"id": 1
 	}
@@ -1840,18 +4664,20 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": []
+			"Err": {
+				"JobQueue": "no job 0436430c-2b02-624c-2032-570501212b00"
+			}
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn get_updater_messages(&self, count: u32) -> Result<Vec<StatusMessage>, ErrorKind>;
+	fn retry_job(&self, token: Token, id: Uuid) -> Result<Job, ErrorKind>;
 
 	/**
-	Networked version of [Owner::get_public_proof_address](struct.Owner.html#method.get_public_proof_address).
+	Networked version of [Owner::delete_job](struct.Owner.html#method.delete_job).
 
 	# Json rpc example
 
@@ -1860,10 +4686,10 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "get_public_proof_address",
+		"method": "delete_job",
 		"params": {
 			"token": "d202964900000000d302964900000000d402964900000000d502964900000000",
-			"derivation_index": 0
+			"id": "0436430c-2b02-624c-2032-570501212b00"
 		},
 		"id": 1
 	}
@@ -1874,22 +4700,20 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": "32cdd63928854f8b2628b1dce4626ddcdf35d56cb7cfdf7d64cca5822b78d4d3"
+			"Err": {
+				"JobQueue": "no job 0436430c-2b02-624c-2032-570501212b00"
+			}
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn get_public_proof_address(
-		&self,
-		token: Token,
-		derivation_index: u32,
-	) -> Result<PubAddress, ErrorKind>;
+	fn delete_job(&self, token: Token, id: Uuid) -> Result<Job, ErrorKind>;
 
 	/**
-	Networked version of [Owner::proof_address_from_onion_v3](struct.Owner.html#method.proof_address_from_onion_v3).
+	Networked version of [Owner::process_jobs](struct.Owner.html#method.process_jobs).
 
 	# Json rpc example
 
@@ -1898,9 +4722,9 @@ pub trait OwnerRpcS {
 	# r#"
 	{
 		"jsonrpc": "2.0",
-		"method": "proof_address_from_onion_v3",
+		"method": "process_jobs",
 		"params": {
-			"address_v3": "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid"
+			"token": "d202964900000000d302964900000000d402964900000000d502964900000000"
 		},
 		"id": 1
 	}
@@ -1911,15 +4735,15 @@ pub trait OwnerRpcS {
 		"id": 1,
 		"jsonrpc": "2.0",
 		"result": {
-			"Ok": "d03c09e9c19bb74aa9ea44e0fe5ae237a9bf40bddf0941064a80913a4459c8bb"
+			"Ok": []
 		}
 	}
 	# "#
-	# , true, 0, false, false, false, false);
+	# , true, 4, false, false, false, false);
 	```
 	*/
 
-	fn proof_address_from_onion_v3(&self, address_v3: String) -> Result<PubAddress, ErrorKind>;
+	fn process_jobs(&self, token: Token) -> Result<Vec<JobResult>, ErrorKind>;
 
 	/**
 	Networked version of [Owner::retrieve_payment_proof](struct.Owner.html#method.retrieve_payment_proof).
@@ -2047,6 +4871,77 @@ pub trait OwnerRpcS {
 	```
 	*/
 	fn set_tor_config(&self, tor_config: Option<TorConfig>) -> Result<(), ErrorKind>;
+
+	/**
+	Networked version of [Owner::get_capabilities](struct.Owner.html#method.get_capabilities).
+
+	# Json rpc example
+
+	```
+	# epic_wallet_api::doctest_helper_json_rpc_owner_assert_response!(
+	# r#"
+	{
+		"jsonrpc": "2.0",
+		"method": "get_capabilities",
+		"params": {},
+		"id": 1
+	}
+	# "#
+	# ,
+	# r#"
+	{
+		"id": 1,
+		"jsonrpc": "2.0",
+		"result": {
+			"Ok": {
+				"tor": {
+					"compiled": true,
+					"enabled": false
+				},
+				"qr": {
+					"compiled": true,
+					"enabled": true
+				},
+				"acme": {
+					"compiled": true,
+					"enabled": true
+				},
+				"swaps": {
+					"compiled": false,
+					"enabled": false
+				},
+				"webhooks": {
+					"compiled": true,
+					"enabled": true
+				},
+				"grpc": {
+					"compiled": false,
+					"enabled": false
+				},
+				"hardware_signing": {
+					"compiled": false,
+					"enabled": false
+				},
+				"os_keystore": {
+					"compiled": false,
+					"enabled": false
+				},
+				"pkcs11": {
+					"compiled": false,
+					"enabled": false
+				},
+				"deterministic_nonces": {
+					"compiled": false,
+					"enabled": false
+				}
+			}
+		}
+	}
+	# "#
+	# , true, 0, false, false, false, false);
+	```
+	*/
+	fn get_capabilities(&self) -> Result<Capabilities, ErrorKind>;
 }
 
 impl<L, C, K> OwnerRpcS for Owner<L, C, K>
@@ -2064,29 +4959,307 @@ where
 			.map_err(|e| e.kind())
 	}
 
-	fn set_active_account(&self, token: Token, label: &String) -> Result<(), ErrorKind> {
-		Owner::set_active_account(self, (&token.keychain_mask).as_ref(), label)
-			.map_err(|e| e.kind())
+	fn set_active_account(&self, token: Token, label: &String) -> Result<(), ErrorKind> {
+		Owner::set_active_account(self, (&token.keychain_mask).as_ref(), label)
+			.map_err(|e| e.kind())
+	}
+
+	fn freeze_account(
+		&self,
+		token: Token,
+		label: String,
+		reason: String,
+	) -> Result<AcctPathMapping, ErrorKind> {
+		Owner::freeze_account(self, (&token.keychain_mask).as_ref(), &label, &reason)
+			.map_err(|e| e.kind())
+	}
+
+	fn unfreeze_account(
+		&self,
+		token: Token,
+		label: String,
+		reason: String,
+	) -> Result<AcctPathMapping, ErrorKind> {
+		Owner::unfreeze_account(self, (&token.keychain_mask).as_ref(), &label, &reason)
+			.map_err(|e| e.kind())
+	}
+
+	fn export_account_key(
+		&self,
+		token: Token,
+		label: String,
+		accept_warning: bool,
+	) -> Result<AccountKeyExport, ErrorKind> {
+		Owner::export_account_key(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&label,
+			accept_warning,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn derive_expected_commitments(
+		&self,
+		token: Token,
+		label: String,
+		from_index: u32,
+		count: u32,
+		values: Vec<u64>,
+		accept_warning: bool,
+	) -> Result<Vec<ExpectedCommitment>, ErrorKind> {
+		Owner::derive_expected_commitments(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&label,
+			from_index,
+			count,
+			&values,
+			accept_warning,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn rewind_rangeproof(
+		&self,
+		token: Token,
+		commit: String,
+		proof: String,
+	) -> Result<RewoundOutput, ErrorKind> {
+		Owner::rewind_rangeproof(self, (&token.keychain_mask).as_ref(), &commit, &proof)
+			.map_err(|e| e.kind())
+	}
+
+	fn import_output(
+		&self,
+		token: Token,
+		key_path: String,
+		value: u64,
+		height: u64,
+	) -> Result<OutputData, ErrorKind> {
+		Owner::import_output(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&key_path,
+			value,
+			height,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn export_wallet_state(&self, token: Token) -> Result<WalletStateExport, ErrorKind> {
+		Owner::export_wallet_state(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn import_wallet_state(
+		&self,
+		token: Token,
+		export: WalletStateExport,
+	) -> Result<WalletStateImport, ErrorKind> {
+		Owner::import_wallet_state(self, (&token.keychain_mask).as_ref(), &export)
+			.map_err(|e| e.kind())
+	}
+
+	fn import_outputs_csv(
+		&self,
+		token: Token,
+		csv: String,
+		units: Denomination,
+	) -> Result<CsvImport<OutputData>, ErrorKind> {
+		Owner::import_outputs_csv(self, (&token.keychain_mask).as_ref(), &csv, units)
+			.map_err(|e| e.kind())
+	}
+
+	fn import_txs_csv(
+		&self,
+		token: Token,
+		csv: String,
+		units: Denomination,
+	) -> Result<CsvImport<TxLogEntry>, ErrorKind> {
+		Owner::import_txs_csv(self, (&token.keychain_mask).as_ref(), &csv, units)
+			.map_err(|e| e.kind())
+	}
+
+	fn retrieve_outputs(
+		&self,
+		token: Token,
+		include_spent: bool,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+	) -> Result<(bool, Vec<OutputCommitMapping>), ErrorKind> {
+		Owner::retrieve_outputs(
+			self,
+			(&token.keychain_mask).as_ref(),
+			include_spent,
+			refresh_from_node,
+			false,
+			tx_id,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn retrieve_outputs_page(
+		&self,
+		token: Token,
+		include_spent: bool,
+		refresh_from_node: bool,
+		cursor: Option<String>,
+		limit: usize,
+	) -> Result<(bool, OutputPage), ErrorKind> {
+		Owner::retrieve_outputs_page(
+			self,
+			(&token.keychain_mask).as_ref(),
+			include_spent,
+			refresh_from_node,
+			cursor,
+			limit,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn retrieve_output_lineage(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		tx_id: Option<u32>,
+	) -> Result<(bool, Vec<OutputLineage>), ErrorKind> {
+		Owner::retrieve_output_lineage(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			tx_id,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn get_locked_outputs(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+	) -> Result<(bool, Vec<LockedOutput>), ErrorKind> {
+		Owner::get_locked_outputs(self, (&token.keychain_mask).as_ref(), refresh_from_node)
+			.map_err(|e| e.kind())
+	}
+
+	fn force_unlock(
+		&self,
+		token: Token,
+		commit: String,
+		include_pending: bool,
+	) -> Result<LockedOutput, ErrorKind> {
+		Owner::force_unlock(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&commit,
+			include_pending,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn set_output_status(
+		&self,
+		token: Token,
+		commit: String,
+		status: OutputStatus,
+		reason: String,
+	) -> Result<OutputData, ErrorKind> {
+		Owner::set_output_status(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&commit,
+			status,
+			&reason,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn retrieve_audit_log(&self, token: Token) -> Result<Vec<AuditEntry>, ErrorKind> {
+		Owner::retrieve_audit_log(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn tag_outputs(
+		&self,
+		token: Token,
+		commits: Vec<String>,
+		tag: String,
+	) -> Result<Vec<OutputCommitMapping>, ErrorKind> {
+		Owner::tag_outputs(self, (&token.keychain_mask).as_ref(), commits, &tag)
+			.map_err(|e| e.kind())
+	}
+
+	fn untag_outputs(
+		&self,
+		token: Token,
+		commits: Vec<String>,
+		tag: String,
+	) -> Result<Vec<OutputCommitMapping>, ErrorKind> {
+		Owner::untag_outputs(self, (&token.keychain_mask).as_ref(), commits, &tag)
+			.map_err(|e| e.kind())
+	}
+
+	fn reserve_funds(
+		&self,
+		token: Token,
+		amount: u64,
+		label: Option<String>,
+	) -> Result<Reservation, ErrorKind> {
+		Owner::reserve_funds(self, (&token.keychain_mask).as_ref(), amount, label)
+			.map_err(|e| e.kind())
+	}
+
+	fn release_reservation(&self, token: Token, id: Uuid) -> Result<Reservation, ErrorKind> {
+		Owner::release_reservation(self, (&token.keychain_mask).as_ref(), id).map_err(|e| e.kind())
+	}
+
+	fn retrieve_reservations(&self, token: Token) -> Result<Vec<Reservation>, ErrorKind> {
+		Owner::retrieve_reservations(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
 	}
 
-	fn retrieve_outputs(
+	fn retrieve_foundation_rewards(
 		&self,
 		token: Token,
-		include_spent: bool,
 		refresh_from_node: bool,
-		tx_id: Option<u32>,
+		start_height: Option<u64>,
+		end_height: Option<u64>,
 	) -> Result<(bool, Vec<OutputCommitMapping>), ErrorKind> {
-		Owner::retrieve_outputs(
+		Owner::retrieve_foundation_rewards(
 			self,
 			(&token.keychain_mask).as_ref(),
-			include_spent,
 			refresh_from_node,
-			false,
-			tx_id,
+			start_height,
+			end_height,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn retrieve_mining_rewards(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		pow_type: Option<PoWType>,
+		start_height: Option<u64>,
+		end_height: Option<u64>,
+	) -> Result<(bool, Vec<MiningRewardSummary>), ErrorKind> {
+		Owner::retrieve_mining_rewards(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			pow_type,
+			start_height,
+			end_height,
 		)
 		.map_err(|e| e.kind())
 	}
 
+	fn retrieve_cleaned_coinbase(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+	) -> Result<(bool, Vec<CleanedCoinbase>), ErrorKind> {
+		Owner::retrieve_cleaned_coinbase(self, (&token.keychain_mask).as_ref(), refresh_from_node)
+			.map_err(|e| e.kind())
+	}
+
 	fn retrieve_txs(
 		&self,
 		token: Token,
@@ -2104,6 +5277,65 @@ where
 		.map_err(|e| e.kind())
 	}
 
+	fn retrieve_txs_page(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		cursor: Option<String>,
+		limit: usize,
+	) -> Result<(bool, TxLogPage), ErrorKind> {
+		Owner::retrieve_txs_page(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			cursor,
+			limit,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn retrieve_txs_by_time(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		from: Option<DateTime<Utc>>,
+		until: Option<DateTime<Utc>>,
+	) -> Result<(bool, Vec<TxLogEntry>), ErrorKind> {
+		Owner::retrieve_txs_by_time(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			from,
+			until,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn retrieve_txs_by_filter(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		filter: TxFilter,
+	) -> Result<(bool, Vec<TxLogEntry>), ErrorKind> {
+		Owner::retrieve_txs_by_filter(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			&filter,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn check_duplicate_payment(
+		&self,
+		token: Token,
+		amount: u64,
+		destination: String,
+	) -> Result<Vec<TxLogEntry>, ErrorKind> {
+		Owner::check_duplicate_payment(self, (&token.keychain_mask).as_ref(), amount, &destination)
+			.map_err(|e| e.kind())
+	}
+
 	fn retrieve_summary_info(
 		&self,
 		token: Token,
@@ -2119,11 +5351,63 @@ where
 		.map_err(|e| e.kind())
 	}
 
-	fn init_send_tx(&self, token: Token, args: InitTxArgs) -> Result<VersionedSlate, ErrorKind> {
-		let slate = Owner::init_send_tx(self, (&token.keychain_mask).as_ref(), args)
-			.map_err(|e| e.kind())?;
-		let version = SlateVersion::V3;
-		Ok(VersionedSlate::into_version(slate, version))
+	fn retrieve_detailed_summary_info(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		minimum_confirmations: u64,
+	) -> Result<(bool, WalletInfo), ErrorKind> {
+		Owner::retrieve_detailed_summary_info(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			minimum_confirmations,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn get_balance_history(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		from_height: u64,
+		to_height: Option<u64>,
+		step: u64,
+	) -> Result<(bool, Vec<BalanceHistoryEntry>), ErrorKind> {
+		Owner::get_balance_history(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			from_height,
+			to_height,
+			step,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn init_send_tx(
+		&self,
+		token: Token,
+		args: InitTxArgs,
+		idempotency_key: Option<String>,
+	) -> Result<VersionedSlate, ErrorKind> {
+		let mask = (&token.keychain_mask).as_ref();
+		Owner::with_idempotency_key(
+			self,
+			mask,
+			"init_send_tx",
+			idempotency_key.as_ref().map(|k| k.as_str()),
+			&args,
+			|| {
+				let slate = Owner::init_send_tx(self, mask, args.clone())?;
+				Ok(VersionedSlate::into_version(slate, SlateVersion::V3))
+			},
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn create_tx_template(&self, token: Token, args: InitTxArgs) -> Result<TxTemplate, ErrorKind> {
+		Owner::create_tx_template(self, (&token.keychain_mask).as_ref(), args).map_err(|e| e.kind())
 	}
 
 	fn issue_invoice_tx(
@@ -2154,19 +5438,36 @@ where
 		Ok(VersionedSlate::into_version(out_slate, version))
 	}
 
+	fn pay_request(
+		&self,
+		token: Token,
+		request: String,
+		args: InitTxArgs,
+	) -> Result<VersionedSlate, ErrorKind> {
+		let slate = Owner::pay_request(self, (&token.keychain_mask).as_ref(), &request, args)
+			.map_err(|e| e.kind())?;
+		Ok(VersionedSlate::into_version(slate, SlateVersion::V3))
+	}
+
 	fn finalize_tx(
 		&self,
 		token: Token,
 		in_slate: VersionedSlate,
+		idempotency_key: Option<String>,
 	) -> Result<VersionedSlate, ErrorKind> {
-		let out_slate = Owner::finalize_tx(
+		let mask = (&token.keychain_mask).as_ref();
+		Owner::with_idempotency_key(
 			self,
-			(&token.keychain_mask).as_ref(),
-			&Slate::from(in_slate),
+			mask,
+			"finalize_tx",
+			idempotency_key.as_ref().map(|k| k.as_str()),
+			&in_slate,
+			|| {
+				let out_slate = Owner::finalize_tx(self, mask, &Slate::from(in_slate.clone()))?;
+				Ok(VersionedSlate::into_version(out_slate, SlateVersion::V3))
+			},
 		)
-		.map_err(|e| e.kind())?;
-		let version = SlateVersion::V3;
-		Ok(VersionedSlate::into_version(out_slate, version))
+		.map_err(|e| e.kind())
 	}
 
 	fn tx_lock_outputs(
@@ -2189,8 +5490,34 @@ where
 		token: Token,
 		tx_id: Option<u32>,
 		tx_slate_id: Option<Uuid>,
+		force: bool,
 	) -> Result<(), ErrorKind> {
-		Owner::cancel_tx(self, (&token.keychain_mask).as_ref(), tx_id, tx_slate_id)
+		Owner::cancel_tx(
+			self,
+			(&token.keychain_mask).as_ref(),
+			tx_id,
+			tx_slate_id,
+			force,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn preview_cancel_tx(
+		&self,
+		token: Token,
+		tx_id: Option<u32>,
+		tx_slate_id: Option<Uuid>,
+	) -> Result<CancelPreview, ErrorKind> {
+		Owner::preview_cancel_tx(self, (&token.keychain_mask).as_ref(), tx_id, tx_slate_id)
+			.map_err(|e| e.kind())
+	}
+
+	fn cancel_stale_txs(
+		&self,
+		token: Token,
+		older_than_minutes: u64,
+	) -> Result<Vec<TxLogEntry>, ErrorKind> {
+		Owner::cancel_stale_txs(self, (&token.keychain_mask).as_ref(), older_than_minutes)
 			.map_err(|e| e.kind())
 	}
 
@@ -2204,12 +5531,36 @@ where
 			.map_err(|e| e.kind())
 	}
 
-	fn post_tx(&self, token: Token, tx: TransactionV3, fluff: bool) -> Result<(), ErrorKind> {
-		Owner::post_tx(
+	fn get_tx_details(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		tx_id: u32,
+	) -> Result<(bool, TxDetails), ErrorKind> {
+		Owner::get_tx_details(
 			self,
 			(&token.keychain_mask).as_ref(),
-			&Transaction::from(tx),
-			fluff,
+			refresh_from_node,
+			tx_id,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn post_tx(
+		&self,
+		token: Token,
+		tx: TransactionV3,
+		fluff: bool,
+		idempotency_key: Option<String>,
+	) -> Result<(), ErrorKind> {
+		let mask = (&token.keychain_mask).as_ref();
+		Owner::with_idempotency_key(
+			self,
+			mask,
+			"post_tx",
+			idempotency_key.as_ref().map(|k| k.as_str()),
+			&(&tx, fluff),
+			|| Owner::post_tx(self, mask, &Transaction::from(tx.clone()), fluff),
 		)
 		.map_err(|e| e.kind())
 	}
@@ -2238,10 +5589,65 @@ where
 		Owner::node_height(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
 	}
 
+	fn node_status(&self, token: Token) -> Result<NodeStatus, ErrorKind> {
+		Owner::node_status(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn node_ping(&self, token: Token) -> Result<NodePing, ErrorKind> {
+		Owner::node_ping(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn get_kernel_status(&self, token: Token, excess: String) -> Result<KernelStatus, ErrorKind> {
+		let excess = from_hex(excess)
+			.map_err(|e| ErrorKind::GenericError(format!("Invalid kernel excess: {}", e)))?;
+		Owner::get_kernel_status(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&pedersen::Commitment::from_vec(excess),
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn wait_tx_finality(
+		&self,
+		token: Token,
+		tx_id: u32,
+		target_confirmations: u64,
+		timeout: u32,
+	) -> Result<TxFinality, ErrorKind> {
+		Owner::wait_tx_finality(
+			self,
+			(&token.keychain_mask).as_ref(),
+			tx_id,
+			target_confirmations,
+			Duration::from_millis(timeout as u64),
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn watch_tx_finality(
+		&self,
+		token: Token,
+		tx_id: u32,
+		target_confirmations: u64,
+		callback_url: String,
+		timeout: u32,
+	) -> Result<(), ErrorKind> {
+		Owner::watch_tx_finality(
+			self,
+			(&token.keychain_mask).as_ref(),
+			tx_id,
+			target_confirmations,
+			&callback_url,
+			Duration::from_millis(timeout as u64),
+		)
+		.map_err(|e| e.kind())
+	}
+
 	fn init_secure_api(&self, ecdh_pubkey: ECDHPubkey) -> Result<ECDHPubkey, ErrorKind> {
 		let secp_inst = static_secp_instance();
 		let secp = secp_inst.lock();
-		let sec_key = SecretKey::new(&secp, &mut thread_rng());
+		let sec_key = random_secret_key(&secp).map_err(|e| ErrorKind::Rng(e.0))?;
 
 		let mut shared_pubkey = ecdh_pubkey.ecdh_pubkey.clone();
 		shared_pubkey
@@ -2308,18 +5714,57 @@ where
 		})
 	}
 
+	fn init_wallet_from_env(
+		&self,
+		name: Option<String>,
+		provisioning_token: String,
+		mnemonic: Option<String>,
+		password: Option<String>,
+	) -> Result<Token, ErrorKind> {
+		let n = name.as_ref().map(|s| s.as_str());
+		let sec_key = Owner::init_wallet_from_env(
+			self,
+			n,
+			&provisioning_token,
+			mnemonic.map(ZeroingString::from),
+			password.map(ZeroingString::from),
+		)
+		.map_err(|e| e.kind())?;
+		Ok(Token {
+			keychain_mask: sec_key,
+		})
+	}
+
 	fn close_wallet(&self, name: Option<String>) -> Result<(), ErrorKind> {
 		let n = name.as_ref().map(|s| s.as_str());
 		Owner::close_wallet(self, n).map_err(|e| e.kind())
 	}
 
-	fn get_mnemonic(&self, name: Option<String>, password: String) -> Result<String, ErrorKind> {
+	fn get_mnemonic(
+		&self,
+		token: Token,
+		name: Option<String>,
+		password: String,
+	) -> Result<String, ErrorKind> {
 		let n = name.as_ref().map(|s| s.as_str());
-		let res =
-			Owner::get_mnemonic(self, n, ZeroingString::from(password)).map_err(|e| e.kind())?;
+		let res = Owner::reveal_mnemonic(
+			self,
+			(&token.keychain_mask).as_ref(),
+			n,
+			ZeroingString::from(password),
+		)
+		.map_err(|e| e.kind())?;
 		Ok(format!("{}", &*res))
 	}
 
+	fn rotate_keychain_mask(&self, token: Token) -> Result<Token, ErrorKind> {
+		let sec_key = Owner::rotate_keychain_mask(self, (&token.keychain_mask).as_ref())
+			.map_err(|e| e.kind())?;
+		Ok(Token {
+			keychain_mask: sec_key,
+		})
+	}
+
 	fn change_password(
 		&self,
 		name: Option<String>,
@@ -2345,6 +5790,15 @@ where
 		.map_err(|e| e.kind())
 	}
 
+	fn start_tip_updater(&self, token: Token, poll_frequency: u32) -> Result<(), ErrorKind> {
+		Owner::start_tip_updater(
+			self,
+			(&token.keychain_mask).as_ref(),
+			Duration::from_millis(poll_frequency as u64),
+		)
+		.map_err(|e| e.kind())
+	}
+
 	fn stop_updater(&self) -> Result<(), ErrorKind> {
 		Owner::stop_updater(self).map_err(|e| e.kind())
 	}
@@ -2397,8 +5851,95 @@ where
 		Ok(PubAddress { address })
 	}
 
+	fn validate_destination(&self, dest: String) -> Result<DestinationValidation, ErrorKind> {
+		Owner::validate_destination(self, &dest).map_err(|e| e.kind())
+	}
+
+	fn parse_payment_uri(&self, uri: String) -> Result<PaymentUri, ErrorKind> {
+		Owner::parse_payment_uri(self, &uri).map_err(|e| e.kind())
+	}
+
+	fn parse_payment_request(&self, request: String) -> Result<PaymentRequest, ErrorKind> {
+		Owner::parse_payment_request(self, &request).map_err(|e| e.kind())
+	}
+
+	fn create_payment_uri(&self, payment_uri: PaymentUri) -> Result<String, ErrorKind> {
+		Owner::create_payment_uri(self, &payment_uri).map_err(|e| e.kind())
+	}
+
+	fn shard_balances(
+		&self,
+		token: Token,
+		refresh_from_node: bool,
+		shards: Vec<String>,
+		minimum_confirmations: u64,
+	) -> Result<(bool, Vec<ShardBalance>), ErrorKind> {
+		Owner::shard_balances(
+			self,
+			(&token.keychain_mask).as_ref(),
+			refresh_from_node,
+			&shards,
+			minimum_confirmations,
+		)
+		.map_err(|e| e.kind())
+	}
+
+	fn rebalance_shards(
+		&self,
+		token: Token,
+		shards: Vec<String>,
+		minimum_confirmations: u64,
+		min_amount: u64,
+	) -> Result<Option<VersionedSlate>, ErrorKind> {
+		let slate = Owner::rebalance_shards(
+			self,
+			(&token.keychain_mask).as_ref(),
+			&shards,
+			minimum_confirmations,
+			min_amount,
+		)
+		.map_err(|e| e.kind())?;
+		Ok(slate.map(|s| VersionedSlate::into_version(s, SlateVersion::V3)))
+	}
+
+	fn retrieve_post_queue(&self, token: Token) -> Result<Vec<TxLogEntry>, ErrorKind> {
+		Owner::retrieve_post_queue(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn retrieve_send_queue(&self, token: Token) -> Result<Vec<QueuedSend>, ErrorKind> {
+		Owner::retrieve_send_queue(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn cancel_queued_send(&self, token: Token, id: Uuid) -> Result<QueuedSend, ErrorKind> {
+		Owner::cancel_queued_send(self, (&token.keychain_mask).as_ref(), &id).map_err(|e| e.kind())
+	}
+
+	fn process_send_queue(&self, token: Token) -> Result<Vec<QueuedSendResult>, ErrorKind> {
+		Owner::process_send_queue(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn retrieve_jobs(&self, token: Token) -> Result<Vec<Job>, ErrorKind> {
+		Owner::retrieve_jobs(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
+	fn retry_job(&self, token: Token, id: Uuid) -> Result<Job, ErrorKind> {
+		Owner::retry_job(self, (&token.keychain_mask).as_ref(), &id).map_err(|e| e.kind())
+	}
+
+	fn delete_job(&self, token: Token, id: Uuid) -> Result<Job, ErrorKind> {
+		Owner::delete_job(self, (&token.keychain_mask).as_ref(), &id).map_err(|e| e.kind())
+	}
+
+	fn process_jobs(&self, token: Token) -> Result<Vec<JobResult>, ErrorKind> {
+		Owner::process_jobs(self, (&token.keychain_mask).as_ref()).map_err(|e| e.kind())
+	}
+
 	fn set_tor_config(&self, tor_config: Option<TorConfig>) -> Result<(), ErrorKind> {
 		Owner::set_tor_config(self, tor_config);
 		Ok(())
 	}
+
+	fn get_capabilities(&self) -> Result<Capabilities, ErrorKind> {
+		Owner::get_capabilities(self).map_err(|e| e.kind())
+	}
 }
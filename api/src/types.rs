@@ -16,11 +16,12 @@ use crate::libwallet::dalek_ser;
 use crate::libwallet::{Error, ErrorKind};
 use crate::util::secp::key::{PublicKey, SecretKey};
 use crate::util::{from_hex, to_hex};
+use epic_wallet_util::os_random;
 use failure::ResultExt;
 
 use base64;
+use chrono::Utc;
 use ed25519_dalek::PublicKey as DalekPublicKey;
-use rand::{thread_rng, Rng};
 use ring::aead;
 use serde_json::{self, Value};
 use std::collections::HashMap;
@@ -72,7 +73,8 @@ impl EncryptedBody {
 		let sealing_key = aead::SealingKey::new(&aead::AES_256_GCM, &enc_key.0).context(
 			ErrorKind::APIEncryption("EncryptedBody Enc: Unable to create key".to_owned()),
 		)?;
-		let nonce: [u8; 12] = thread_rng().gen();
+		let mut nonce = [0u8; 12];
+		os_random(&mut nonce).map_err(|e| ErrorKind::APIEncryption(format!("{}", e)))?;
 		let suffix_len = aead::AES_256_GCM.tag_len();
 		for _ in 0..suffix_len {
 			to_encrypt.push(0);
@@ -115,6 +117,13 @@ impl EncryptedBody {
 		let nonce = from_hex(self.nonce.clone()).context(ErrorKind::APIEncryption(
 			"EncryptedBody Dec: Invalid Nonce".to_string(),
 		))?;
+		if nonce.len() != aead::AES_256_GCM.nonce_len() {
+			return Err(ErrorKind::APIEncryption(format!(
+				"EncryptedBody Dec: Nonce must be {} bytes",
+				aead::AES_256_GCM.nonce_len()
+			))
+			.into());
+		}
 		aead::open_in_place(&opening_key, &nonce, &[], 0, &mut to_decrypt).context(
 			ErrorKind::APIEncryption(
 				"EncryptedBody Dec: Decryption Failed (is key correct?)".to_string(),
@@ -143,6 +152,21 @@ pub enum RpcId {
 	Integer(u64),
 }
 
+impl RpcId {
+	/// Id of a JSON-RPC request, for answering requests too malformed to
+	/// handle. Defaults to 1 if the request has no readable id
+	pub fn from_request(val: &Value) -> RpcId {
+		match serde_json::from_value(val["id"].clone()) {
+			Ok(RpcId::Null) | Err(_) => RpcId::Integer(1),
+			Ok(id) => id,
+		}
+	}
+}
+
+/// Field of an encrypted request holding the time it was made, in seconds
+/// since the epoch
+pub const REQUEST_TIMESTAMP: &str = "timestamp";
+
 /// Wrapper for secure JSON requests
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EncryptedRequest {
@@ -157,13 +181,22 @@ pub struct EncryptedRequest {
 }
 
 impl EncryptedRequest {
-	/// from json
+	/// from json, with the current time added to the request as its
+	/// `timestamp` before it's encrypted, which the wallet checks against its
+	/// replay window
 	pub fn from_json(id: RpcId, json_in: &Value, enc_key: &SecretKey) -> Result<Self, Error> {
+		let mut json_in = json_in.clone();
+		if let Some(req) = json_in.as_object_mut() {
+			req.insert(
+				REQUEST_TIMESTAMP.to_owned(),
+				Value::from(Utc::now().timestamp()),
+			);
+		}
 		Ok(EncryptedRequest {
 			jsonrpc: "2.0".to_owned(),
 			method: "encrypted_request_v3".to_owned(),
 			id: id,
-			params: EncryptedBody::from_json(json_in, enc_key)?,
+			params: EncryptedBody::from_json(&json_in, enc_key)?,
 		})
 	}
 
@@ -235,7 +268,13 @@ impl EncryptedResponse {
 
 	/// Return decrypted body
 	pub fn decrypt(&self, dec_key: &SecretKey) -> Result<Value, Error> {
-		self.result.get("Ok").unwrap().decrypt(dec_key)
+		match self.result.get("Ok") {
+			Some(body) => body.decrypt(dec_key),
+			None => Err(ErrorKind::APIEncryption(
+				"EncryptedResponse Dec: No encrypted result".to_owned(),
+			)
+			.into()),
+		}
 	}
 }
 
@@ -316,8 +355,10 @@ fn encrypted_request() -> Result<(), Error> {
 	let id = RpcId::Integer(1);
 	let enc_req = EncryptedRequest::from_json(id, &req, &shared_key)?;
 	println!("{:?}", enc_req);
-	let dec_req = enc_req.decrypt(&shared_key)?;
+	let mut dec_req = enc_req.decrypt(&shared_key)?;
 	println!("{:?}", dec_req);
+	assert!(dec_req[REQUEST_TIMESTAMP].is_i64());
+	dec_req.as_object_mut().unwrap().remove(REQUEST_TIMESTAMP);
 	assert_eq!(req, dec_req);
 	let id = RpcId::Integer(1);
 	let enc_res = EncryptedResponse::from_json(id, &req, &shared_key)?;
@@ -328,3 +369,51 @@ fn encrypted_request() -> Result<(), Error> {
 	assert_eq!(req, dec_res);
 	Ok(())
 }
+
+#[test]
+fn encrypted_request_nonce() -> Result<(), Error> {
+	use crate::util::static_secp_instance;
+
+	let shared_key = {
+		let secp_inst = static_secp_instance();
+		let secp = secp_inst.lock();
+		SecretKey::new(&secp, &mut rand::thread_rng())
+	};
+	let req = serde_json::json!({
+		"jsonrpc": "2.0",
+		"method": "accounts",
+		"id": "abc",
+		"params": {}
+	});
+	assert_eq!(
+		serde_json::to_value(RpcId::from_request(&req)).unwrap(),
+		serde_json::json!("abc")
+	);
+	assert_eq!(
+		serde_json::to_value(RpcId::from_request(&serde_json::json!({}))).unwrap(),
+		serde_json::json!(1)
+	);
+
+	// nonces of the wrong length, and tampered bodies, are rejected
+	let enc_req = EncryptedRequest::from_json(RpcId::Integer(1), &req, &shared_key)?;
+	let mut bad = enc_req.clone();
+	bad.params.nonce = "000102".to_owned();
+	assert!(bad.decrypt(&shared_key).is_err());
+	let mut bad = enc_req.clone();
+	let mut body = base64::decode(&bad.params.body_enc).unwrap();
+	body[0] ^= 1;
+	bad.params.body_enc = base64::encode(&body);
+	assert!(bad.decrypt(&shared_key).is_err());
+	let mut dec_req = enc_req.decrypt(&shared_key)?;
+	dec_req.as_object_mut().unwrap().remove(REQUEST_TIMESTAMP);
+	assert_eq!(dec_req, req);
+
+	let res: EncryptedResponse = serde_json::from_value(serde_json::json!({
+		"jsonrpc": "2.0",
+		"id": 1,
+		"result": {}
+	}))
+	.unwrap();
+	assert!(res.decrypt(&shared_key).is_err());
+	Ok(())
+}
@@ -44,6 +44,13 @@ fn comments() -> HashMap<String, String> {
 #tls_certificate_file = \"\"
 #private key for the TLS certificate
 #tls_certificate_key = \"\"
+#alternatively, the domain for which the listener obtains and renews a
+#certificate from Let's Encrypt. The wallet answers the HTTP-01 challenge
+#on port 80 of api_listen_interface, which must be reachable from the
+#internet under this domain
+#tls_acme_domain = \"pay.example.com\"
+#contact email registered with Let's Encrypt
+#tls_acme_contact_email = \"admin@example.com\"
 
 #port for wallet listener
 "
@@ -58,6 +65,18 @@ fn comments() -> HashMap<String, String> {
 		.to_string(),
 	);
 
+	retval.insert(
+		"owner_api_socket_permissions".to_string(),
+		"
+#path of a unix domain socket for the owner api to listen on instead of
+#its port, so local applications can use it without any network exposure
+#owner_api_socket_path = \"/home/user/.epic/main/owner_api.sock\"
+#permissions of the socket file, in octal. The default 600 only lets the
+#wallet's user connect
+"
+		.to_string(),
+	);
+
 	retval.insert(
 		"api_secret_path".to_string(),
 		"
@@ -70,6 +89,35 @@ fn comments() -> HashMap<String, String> {
 		"check_node_api_http_addr".to_string(),
 		"
 #where the wallet should find a running node
+"
+		.to_string(),
+	);
+	retval.insert(
+		"node_retry_max_attempts".to_string(),
+		"
+#calls to the node that fail because it couldn't be reached, timed out or
+#answered with a server error are tried this many times in all, waiting
+#node_retry_base_delay_ms before the first retry and twice as long before each
+#one after, up to node_retry_max_delay_ms. Waits are randomly shortened by up
+#to half. Other errors are never retried
+"
+		.to_string(),
+	);
+	retval.insert(
+		"node_circuit_breaker_threshold".to_string(),
+		"
+#once this many node calls in a row have failed every attempt, further calls
+#fail straight away for node_circuit_breaker_cooldown_secs rather than each
+#waiting out their retries. 0 disables this
+"
+		.to_string(),
+	);
+	retval.insert(
+		"node_request_timeout_secs".to_string(),
+		"
+#seconds each attempt at a node call may take before it's given up on and
+#retried. The wallet waits on the node meanwhile, so this bounds how long a
+#node that stops answering holds it up. 0 for no limit
 "
 		.to_string(),
 	);
@@ -86,6 +134,99 @@ fn comments() -> HashMap<String, String> {
 #include the foreign API endpoints on the same port as the owner
 #API. Useful for networking environments like AWS ECS that make
 #it difficult to access multiple ports on a single service.
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_allowed_origins".to_string(),
+		"
+#origins of browser-based wallet frontends allowed to call the owner API,
+#e.g. [\"http://localhost:8080\"], or [\"*\"] for any origin. The API
+#returns CORS headers to these origins and refuses requests from any
+#other. Disabled when unset
+#owner_api_allowed_origins = [\"http://localhost:8080\"]
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_replay_window_secs".to_string(),
+		"
+#how far, in seconds, from the wallet's time encrypted owner API requests
+#may be made, by the timestamp inside them. Each is remembered meanwhile to
+#refuse it if it's sent again with the same token. Requests from older clients
+#without a timestamp are only remembered for this long after they're
+#received. 0 disables the check
+#owner_api_replay_window_secs = 300
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_read_timeout_secs".to_string(),
+		"
+#seconds an owner API call that only reads the wallet's stored data may
+#run before it's answered with an error, so a call stuck behind another
+#can't hang the API. 0 disables the timeout
+#owner_api_read_timeout_secs = 5
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_node_timeout_secs".to_string(),
+		"
+#seconds an owner API call reading the wallet's data after refreshing it
+#from the node, or only querying the node or another wallet, may run. Calls
+#changing the wallet have no timeout, as they carry on after timing out
+#owner_api_node_timeout_secs = 120
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_method_timeouts".to_string(),
+		"
+#timeouts of particular owner API methods, overriding the above, as
+#\"method:seconds\". A call changing the wallet that times out may still
+#complete
+#owner_api_method_timeouts = [\"retrieve_txs:30\", \"scan:0\"]
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_allow_output_status_override".to_string(),
+		"
+#whether the owner API's set_output_status may be used to override the
+#status of outputs by hand, e.g. to release an output stuck locked. Each
+#override is recorded in the wallet's audit trail
+#owner_api_allow_output_status_override = false
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_allow_webhooks".to_string(),
+		"
+#whether the owner API may queue webhooks, and callbacks of watch_tx_finality,
+#posting to URLs given by its callers. get_capabilities reports webhooks as
+#disabled when they aren't allowed, or the wallet wasn't built with http
+#owner_api_allow_webhooks = true
+"
+		.to_string(),
+	);
+	retval.insert(
+		"owner_api_read_only".to_string(),
+		"
+#whether the owner API opens the wallet database read-only, as a replica
+#of the wallet another process (such as the one listening for payments)
+#writes to. It then serves only queries of the wallet's stored data, so
+#reporting doesn't hold up the wallet itself, and never migrates the
+#database, which the writing wallet must have opened first
+#owner_api_read_only = false
+"
+		.to_string(),
+	);
+	retval.insert(
+		"api_allowed_origins".to_string(),
+		"
+#origins allowed to call the foreign API listener, as above
+#api_allowed_origins = [\"https://shop.example.com\"]
 "
 		.to_string(),
 	);
@@ -99,9 +240,17 @@ fn comments() -> HashMap<String, String> {
 	retval.insert(
 		"no_commit_cache".to_string(),
 		"
-#If true, don't store calculated commits in the database
-#better privacy, but at a performance cost of having to
-#re-calculate commits every time they're used
+#No longer used. Commits are always stored in the database
+#alongside outputs, and are calculated for any outputs stored
+#without one when the wallet is opened
+"
+		.to_string(),
+	);
+	retval.insert(
+		"tls_acme_staging".to_string(),
+		"
+#Request certificates from Let's Encrypt's staging environment, to test
+#a tls_acme_domain setup without running into production rate limits
 "
 		.to_string(),
 	);
@@ -119,6 +268,242 @@ fn comments() -> HashMap<String, String> {
 #Unit: Minute. Default value 1440 minutes for one day.
 #Refer to https://keybase.io/blog/keybase-exploding-messages for detail.
 #To disable this notification, set it as 0.
+"
+		.to_string(),
+	);
+	retval.insert(
+		"min_reorg_safe_confirmations".to_string(),
+		"
+#If set, outputs with fewer than this many confirmations will never be
+#selected for spending, even if a lower minimum confirmation count is
+#requested for a transaction. Protects against spending outputs that
+#could be removed by a shallow chain reorganisation.
+#Can be overridden per transaction with --override_reorg_safety.
+#min_reorg_safe_confirmations = 10
+"
+		.to_string(),
+	);
+	retval.insert(
+		"max_invoice_fee".to_string(),
+		"
+#If set, invoices whose payment would cost more than this many freemans
+#in fees are refused, as when an invoice has been made to cost more
+#than its payer would expect. Applies to the pay command and the owner API.
+#max_invoice_fee = 1000000
+"
+		.to_string(),
+	);
+	retval.insert(
+		"foundation_wallet".to_string(),
+		"
+#Set to true only on the foundation wallet. Enables the build_foundation
+#foreign API call, which all other wallets reject.
+"
+		.to_string(),
+	);
+	retval.insert(
+		"unconfirmed_coinbase_cleanup_window".to_string(),
+		"
+#Number of blocks after which a coinbase output that's still unconfirmed
+#(for instance because its block was orphaned) is removed from the wallet.
+#Removals are recorded in the transaction log as reverted coinbase entries.
+#To never remove them, set it as 0.
+"
+		.to_string(),
+	);
+	retval.insert(
+		"confirm_missing_outputs".to_string(),
+		"
+#Outputs the node no longer has are marked spent when the wallet refreshes,
+#as long as the node reports it's synced. If true, they're only marked spent
+#once two refreshes in a row find them missing, guarding against a node
+#answering oddly for a moment.
+#confirm_missing_outputs = false
+"
+		.to_string(),
+	);
+	retval.insert(
+		"backup_before_migrate".to_string(),
+		"
+#Whether to copy the wallet database aside (next to it, as db_backup_v<version>_<time>)
+#before migrating it to the format of a newer wallet version.
+"
+		.to_string(),
+	);
+	retval.insert(
+		"receive_callback_url".to_string(),
+		"
+#If set, each transaction received through the foreign API listener is
+#reported to this URL with an HTTP POST of its slate id, amount and proof
+#addresses, signed with the receiving account's payment proof address.
+#Lets order systems mark invoices as paid before the transaction confirms.
+#receive_callback_url = \"https://shop.example.com/epic/received\"
+"
+		.to_string(),
+	);
+	retval.insert(
+		"receive_min_amount".to_string(),
+		"
+#Smallest and largest amounts, in freemans (100000000 to an epic), that the
+#foreign API listener will receive in a single transaction. Others are refused
+#receive_min_amount = 1000000
+#receive_max_amount = 100000000000
+"
+		.to_string(),
+	);
+	retval.insert(
+		"receive_sender_allowlist".to_string(),
+		"
+#If set, the foreign API listener only receives transactions that request a
+#payment proof from one of these sender addresses, refusing all others
+#receive_sender_allowlist = [\"<onion v3 address>\"]
+"
+		.to_string(),
+	);
+	retval.insert(
+		"receive_shard_accounts".to_string(),
+		"
+#If set, transactions received by the foreign API listener without a
+#destination account are spread across these accounts, each going to the one
+#holding the least. Sending from the accounts in turn, and evening them out
+#with the rebalance_shards owner API call, keeps a busy wallet's outputs from
+#being tied up by transactions in progress
+#receive_shard_accounts = [\"shard1\", \"shard2\", \"shard3\"]
+"
+		.to_string(),
+	);
+	retval.insert(
+		"receive_fee_contribution".to_string(),
+		"
+#If set, the foreign API listener offers to add one of the wallet's outputs as
+#an input to transactions it receives, paying up to this much of the fee, in
+#freemans, out of it. Senders asking for it get harder to trace transactions,
+#and merchants can pay their customers' fees. 0 contributes an input but none
+#of the fee
+#receive_fee_contribution = 0
+"
+		.to_string(),
+	);
+	retval.insert(
+		"receive_fee_contribution_max_pending".to_string(),
+		"
+#Contributed outputs stay locked until their transactions confirm or are
+#cancelled, and anyone can ask for a contribution, so at most this many are
+#locked at once. Outputs contributed need min_reorg_safe_confirmations, or 10
+#if that isn't set
+#receive_fee_contribution_max_pending = 5
+"
+		.to_string(),
+	);
+	retval.insert(
+		"merchant_api_secret_path".to_string(),
+		"
+#If set, the foreign API listener also serves a merchant API for e-commerce
+#plugins, with POST /invoices to issue an invoice and GET /invoices/<id> for
+#its payment status. Requests must use HTTP basic auth with the user epic
+#and the secret in this file as the password
+#merchant_api_secret_path = \".merchant_api_secret\"
+"
+		.to_string(),
+	);
+	retval.insert(
+		"sign_foreign_responses".to_string(),
+		"
+#If true, responses of the foreign API listener are signed with the payment
+#proof address of the wallet's active account, given in the X-Epic-Proof-Address and
+#X-Epic-Signature headers. Senders expecting that address can then tell they
+#reached this wallet, even through HTTP relays
+"
+		.to_string(),
+	);
+	retval.insert(
+		"accept_legacy_slates".to_string(),
+		"
+#If true, the foreign API listener also receives the V0 and V1 slates still
+#sent by epic wallets older than 1.1.0, which post them to
+#/v1/wallet/foreign/receive_tx, and answers them in the version they were sent
+#in. Only receiving is supported; sends are always made in V2 or newer
+"
+		.to_string(),
+	);
+	retval.insert(
+		"version_advisory_url".to_string(),
+		"
+#If set, the foreign API listener fetches an upgrade advisory, naming the
+#oldest wallet release still recommended, from this URL every 6 hours. It's
+#passed on to counterparties in check_version, so they can warn users running
+#outdated builds, and a warning is logged if this wallet is one. Advisories
+#not signed by version_advisory_address are ignored
+#version_advisory_url = \"https://example.com/epic-wallet-advisory.json\"
+"
+		.to_string(),
+	);
+	retval.insert(
+		"version_advisory_address".to_string(),
+		"
+#Payment proof address, in onion v3 form, upgrade advisories fetched from
+#version_advisory_url have to be signed by
+#version_advisory_address = \"<onion v3 address>\"
+"
+		.to_string(),
+	);
+	retval.insert(
+		"seed_storage".to_string(),
+		"
+#Where the password-encrypted wallet seed is kept. One of
+#\"file\" - wallet.seed in the wallet data directory (default)
+#\"os_keystore\" - macOS Keychain, Windows Credential Manager or the Linux
+#Secret Service (needs the os_keystore build feature)
+#\"pkcs11\" - a data object on a PKCS#11 token such as an HSM, set up with
+#pkcs11_module and pkcs11_slot below. The token PIN, if needed, is read from
+#the EPIC_WALLET_PKCS11_PIN environment variable (needs the pkcs11 build feature)
+#An existing wallet.seed is not moved, recover the wallet into the new storage
+#with 'epic-wallet init -r'.
+"
+		.to_string(),
+	);
+	retval.insert(
+		"pkcs11_module".to_string(),
+		"
+#Path of the PKCS#11 module library when seed_storage is \"pkcs11\"
+#pkcs11_module = \"/usr/lib/softhsm/libsofthsm2.so\"
+"
+		.to_string(),
+	);
+	retval.insert(
+		"pkcs11_slot".to_string(),
+		"
+#PKCS#11 slot holding the seed. If unset, the first slot with a token present is used
+#pkcs11_slot = 0
+"
+		.to_string(),
+	);
+	retval.insert(
+		"seed_kdf_memory_kib".to_string(),
+		"
+#Costs of the argon2id derivation of the key the wallet seed is encrypted
+#with from the password. Higher costs make guessing the password slower, for
+#attackers and when opening the wallet alike. A seed encrypted with other
+#costs, or with the PBKDF2 used by earlier wallets, is encrypted again with
+#these the next time the wallet is opened
+#Memory used, in KiB (default 65536)
+#seed_kdf_memory_kib = 65536
+"
+		.to_string(),
+	);
+	retval.insert(
+		"seed_kdf_iterations".to_string(),
+		"
+#Passes over that memory (default 3)
+#seed_kdf_iterations = 3
+"
+		.to_string(),
+	);
+	retval.insert(
+		"seed_kdf_parallelism".to_string(),
+		"
+#Lanes computed in parallel (default 4)
+#seed_kdf_parallelism = 4
 "
 		.to_string(),
 	);
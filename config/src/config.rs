@@ -28,7 +28,7 @@ use toml;
 use crate::comments::insert_comments;
 use crate::core::global;
 use crate::types::{ConfigError, GlobalWalletConfig, GlobalWalletConfigMembers};
-use crate::types::{TorConfig, WalletConfig};
+use crate::types::{SupervisorConfig, TorConfig, WalletConfig};
 use crate::util::logger::LoggingConfig;
 
 /// Wallet configuration file name
@@ -308,3 +308,50 @@ impl GlobalWalletConfig {
 			.replace("ERROR", "Error")
 	}
 }
+
+impl SupervisorConfig {
+	/// Read the wallets to supervise from a file
+	pub fn new(file_path: &str) -> Result<SupervisorConfig, ConfigError> {
+		let path = PathBuf::from(file_path);
+		if !path.exists() {
+			return Err(ConfigError::FileNotFoundError(file_path.to_owned()));
+		}
+		let contents = fs::read_to_string(&path)?;
+		let config: SupervisorConfig = toml::from_str(&contents)
+			.map_err(|e| ConfigError::ParseError(file_path.to_owned(), format!("{}", e)))?;
+
+		// each wallet needs its own name, directory and ports
+		let mut ports = vec![];
+		for (i, w) in config.wallets.iter().enumerate() {
+			let others = &config.wallets[..i];
+			let reason = if others.iter().any(|o| o.name == w.name) {
+				Some(format!("wallet name {} is used twice", w.name))
+			} else if others.iter().any(|o| o.data_file_dir == w.data_file_dir) {
+				Some(format!("data_file_dir {} is used twice", w.data_file_dir))
+			} else if ports.contains(&w.api_listen_port)
+				|| w.owner_api_listen_port
+					.map_or(false, |p| ports.contains(&p))
+				|| w.owner_api_listen_port == Some(w.api_listen_port)
+			{
+				Some(format!(
+					"wallet {} listens on a port already in use",
+					w.name
+				))
+			} else {
+				None
+			};
+			if let Some(r) = reason {
+				return Err(ConfigError::ParseError(file_path.to_owned(), r));
+			}
+			ports.push(w.api_listen_port);
+			ports.extend(w.owner_api_listen_port);
+		}
+		if config.wallets.is_empty() {
+			return Err(ConfigError::ParseError(
+				file_path.to_owned(),
+				"no wallets to supervise".to_owned(),
+			));
+		}
+		Ok(config)
+	}
+}
@@ -32,5 +32,6 @@ pub mod types;
 
 pub use crate::config::{initial_setup_wallet, EPIC_WALLET_DIR, WALLET_CONFIG_FILE_NAME};
 pub use crate::types::{
-	ConfigError, GlobalWalletConfig, GlobalWalletConfigMembers, TorConfig, WalletConfig,
+	ConfigError, GlobalWalletConfig, GlobalWalletConfigMembers, SeedKdfParams, SeedStorage,
+	SupervisedWalletConfig, SupervisorConfig, TorConfig, WalletConfig,
 };
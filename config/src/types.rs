@@ -33,6 +33,11 @@ pub struct WalletConfig {
 	pub api_listen_port: u16,
 	/// The port this wallet's owner API will run on
 	pub owner_api_listen_port: Option<u16>,
+	/// If set, the owner API listens on a unix domain socket at this path
+	/// instead of the TCP port
+	pub owner_api_socket_path: Option<String>,
+	/// Permissions of the owner API socket file, in octal
+	pub owner_api_socket_permissions: Option<String>,
 	/// Location of the secret for basic auth on the Owner API
 	pub api_secret_path: Option<String>,
 	/// Location of the node api secret for basic auth on the Epic API
@@ -40,22 +45,144 @@ pub struct WalletConfig {
 	/// The api address of a running server node against which transaction inputs
 	/// will be checked during send
 	pub check_node_api_http_addr: String,
+	/// Attempts made at each node call failing with a transient error,
+	/// including the first
+	pub node_retry_max_attempts: Option<u32>,
+	/// Delay before retrying a node call, in milliseconds, doubling with each
+	/// retry
+	pub node_retry_base_delay_ms: Option<u64>,
+	/// Longest delay between node call retries, in milliseconds
+	pub node_retry_max_delay_ms: Option<u64>,
+	/// Node calls failing in a row before they're paused. 0 never pauses them
+	pub node_circuit_breaker_threshold: Option<u32>,
+	/// How long node calls are paused, in seconds
+	pub node_circuit_breaker_cooldown_secs: Option<u64>,
+	/// Seconds each attempt at a node call may take before it's given up on.
+	/// 0 for no limit
+	pub node_request_timeout_secs: Option<u64>,
 	/// Whether to include foreign API endpoints on the Owner API
 	pub owner_api_include_foreign: Option<bool>,
+	/// Origins of browser frontends allowed to call the Owner API, which
+	/// then returns CORS headers to them. Requests from other origins are
+	/// refused. Unset, no CORS headers are sent
+	pub owner_api_allowed_origins: Option<Vec<String>>,
+	/// How far, in seconds, from the wallet's time encrypted owner API
+	/// requests may be made, with their nonces remembered meanwhile to refuse
+	/// replays. 0 checks neither
+	pub owner_api_replay_window_secs: Option<u64>,
+	/// Seconds owner API calls only reading stored data may run before
+	/// they're answered with an error. 0 for no timeout
+	pub owner_api_read_timeout_secs: Option<u64>,
+	/// Seconds owner API calls refreshing from the node before reading, or
+	/// only querying the node, may run. Calls changing the wallet have none
+	pub owner_api_node_timeout_secs: Option<u64>,
+	/// Timeouts of particular owner API methods, as `method:seconds`
+	pub owner_api_method_timeouts: Option<Vec<String>>,
+	/// If true, the owner API's `set_output_status` may be used to override
+	/// the status of outputs by hand
+	pub owner_api_allow_output_status_override: Option<bool>,
+	/// If false, the owner API refuses to queue webhooks or watch for a
+	/// transaction's finality with a callback
+	pub owner_api_allow_webhooks: Option<bool>,
+	/// If true, the owner API opens the wallet database read-only, as a
+	/// replica of the wallet another process writes to, and serves only
+	/// queries of its stored data
+	pub owner_api_read_only: Option<bool>,
+	/// Origins of browser frontends allowed to call the Foreign API
+	/// listener, as above
+	pub api_allowed_origins: Option<Vec<String>>,
 	/// The directory in which wallet files are stored
 	pub data_file_dir: String,
-	/// If Some(true), don't cache commits alongside output data
-	/// speed improvement, but your commits are in the database
+	/// No longer used, commits are always cached alongside output data
+	/// and backfilled for older outputs when the wallet is opened
 	pub no_commit_cache: Option<bool>,
 	/// TLS certificate file
 	pub tls_certificate_file: Option<String>,
 	/// TLS certificate private key file
 	pub tls_certificate_key: Option<String>,
+	/// If set, the foreign listener obtains and renews a TLS certificate for
+	/// this domain from Let's Encrypt, instead of using the files above
+	pub tls_acme_domain: Option<String>,
+	/// Contact email registered with Let's Encrypt for the certificate
+	pub tls_acme_contact_email: Option<String>,
+	/// If true, certificates are requested from Let's Encrypt's staging
+	/// environment, for testing the setup
+	pub tls_acme_staging: Option<bool>,
 	/// Whether to use the black background color scheme for command line
 	/// if enabled, wallet command output color will be suitable for black background terminal
 	pub dark_background_color_scheme: Option<bool>,
 	/// The exploding lifetime (minutes) for keybase notification on coins received
 	pub keybase_notify_ttl: Option<u16>,
+	/// If set, outputs confirmed fewer than this many blocks ago are never
+	/// selected for spending, regardless of the requested minimum confirmations
+	pub min_reorg_safe_confirmations: Option<u64>,
+	/// If set, invoices are only paid if the payer's part of the fee is no more
+	/// than this many freemans
+	pub max_invoice_fee: Option<u64>,
+	/// If true, this is the foundation wallet, which builds the foundation
+	/// reward outputs. Other wallets refuse to build them
+	pub foundation_wallet: Option<bool>,
+	/// Number of blocks after which a coinbase output that's still unconfirmed
+	/// is removed from the wallet. 0 never removes them
+	pub unconfirmed_coinbase_cleanup_window: Option<u64>,
+	/// If true, an output missing from the node is only marked spent once two
+	/// refreshes in a row find it missing
+	pub confirm_missing_outputs: Option<bool>,
+	/// If true, the wallet database is copied aside before it's migrated to
+	/// a newer schema version
+	pub backup_before_migrate: Option<bool>,
+	/// If set, a signed notice of each transaction received by the foreign
+	/// API listener is posted to this URL
+	pub receive_callback_url: Option<String>,
+	/// Smallest amount, in freemans, the foreign API listener receives in a
+	/// single transaction
+	pub receive_min_amount: Option<u64>,
+	/// Largest amount, in freemans, the foreign API listener receives in a
+	/// single transaction
+	pub receive_max_amount: Option<u64>,
+	/// If set, the foreign API listener only receives transactions carrying a
+	/// payment proof from one of these sender addresses
+	pub receive_sender_allowlist: Option<Vec<String>>,
+	/// If set, transactions received by the foreign API listener without a
+	/// destination account go to whichever of these accounts holds the least
+	pub receive_shard_accounts: Option<Vec<String>>,
+	/// If set, the foreign API listener adds an input of the wallet's to
+	/// transactions whose sender asks for one, paying up to this much of the
+	/// fee, in freemans, out of it
+	pub receive_fee_contribution: Option<u64>,
+	/// Most outputs contributed by the foreign API listener that can be
+	/// locked at once, waiting on their transactions to confirm
+	pub receive_fee_contribution_max_pending: Option<usize>,
+	/// If set, the foreign API listener also serves the merchant invoice API,
+	/// authenticated with the secret in this file
+	pub merchant_api_secret_path: Option<String>,
+	/// If true, foreign API listener responses are signed with the wallet's
+	/// payment proof address, so senders can check who answered
+	pub sign_foreign_responses: Option<bool>,
+	/// If true, the foreign API listener also receives the V0 and V1 slates
+	/// of old wallets, answering them in the version they were sent in
+	pub accept_legacy_slates: Option<bool>,
+	/// If set, the foreign API listener regularly fetches an upgrade advisory
+	/// from this URL, passing it on to counterparties checking its version
+	pub version_advisory_url: Option<String>,
+	/// Payment proof address, in onion v3 form, advisories fetched from
+	/// `version_advisory_url` have to be signed by
+	pub version_advisory_address: Option<String>,
+	/// Where the encrypted wallet seed is kept: `file` (the default),
+	/// `os_keystore` or `pkcs11`
+	pub seed_storage: Option<String>,
+	/// Path of the PKCS#11 module library, when the seed is kept on a token
+	pub pkcs11_module: Option<String>,
+	/// PKCS#11 slot holding the seed. Unset, the first slot with a token
+	/// present is used
+	pub pkcs11_slot: Option<u64>,
+	/// Memory, in KiB, used deriving the key the seed is encrypted with from
+	/// the password. Unset, 65536
+	pub seed_kdf_memory_kib: Option<u32>,
+	/// Passes over that memory. Unset, 3
+	pub seed_kdf_iterations: Option<u32>,
+	/// Lanes of the derivation computed in parallel. Unset, 4
+	pub seed_kdf_parallelism: Option<u32>,
 }
 
 impl Default for WalletConfig {
@@ -65,16 +192,60 @@ impl Default for WalletConfig {
 			api_listen_interface: "127.0.0.1".to_string(),
 			api_listen_port: 3415,
 			owner_api_listen_port: Some(WalletConfig::default_owner_api_listen_port()),
+			owner_api_socket_path: None,
+			owner_api_socket_permissions: Some("600".to_owned()),
 			api_secret_path: Some(".owner_api_secret".to_string()),
 			node_api_secret_path: Some(".api_secret".to_string()),
 			check_node_api_http_addr: "http://127.0.0.1:3413".to_string(),
+			node_retry_max_attempts: Some(3),
+			node_retry_base_delay_ms: Some(250),
+			node_retry_max_delay_ms: Some(5000),
+			node_circuit_breaker_threshold: Some(5),
+			node_circuit_breaker_cooldown_secs: Some(30),
+			node_request_timeout_secs: Some(30),
 			owner_api_include_foreign: Some(false),
+			owner_api_allowed_origins: None,
+			owner_api_replay_window_secs: None,
+			owner_api_read_timeout_secs: None,
+			owner_api_node_timeout_secs: None,
+			owner_api_method_timeouts: None,
+			owner_api_allow_output_status_override: None,
+			owner_api_allow_webhooks: None,
+			owner_api_read_only: None,
+			api_allowed_origins: None,
 			data_file_dir: ".".to_string(),
 			no_commit_cache: Some(false),
 			tls_certificate_file: None,
 			tls_certificate_key: None,
+			tls_acme_domain: None,
+			tls_acme_contact_email: None,
+			tls_acme_staging: Some(false),
 			dark_background_color_scheme: Some(true),
 			keybase_notify_ttl: Some(1440),
+			min_reorg_safe_confirmations: None,
+			max_invoice_fee: None,
+			foundation_wallet: Some(false),
+			unconfirmed_coinbase_cleanup_window: Some(50),
+			confirm_missing_outputs: None,
+			backup_before_migrate: Some(true),
+			receive_callback_url: None,
+			receive_min_amount: None,
+			receive_max_amount: None,
+			receive_sender_allowlist: None,
+			receive_shard_accounts: None,
+			receive_fee_contribution: None,
+			receive_fee_contribution_max_pending: None,
+			merchant_api_secret_path: None,
+			sign_foreign_responses: Some(false),
+			accept_legacy_slates: Some(false),
+			version_advisory_url: None,
+			version_advisory_address: None,
+			seed_storage: Some("file".to_owned()),
+			pkcs11_module: None,
+			pkcs11_slot: None,
+			seed_kdf_memory_kib: None,
+			seed_kdf_iterations: None,
+			seed_kdf_parallelism: None,
 		}
 	}
 }
@@ -101,6 +272,53 @@ impl WalletConfig {
 		format!("127.0.0.1:{}", self.owner_api_listen_port())
 	}
 }
+/// Where the lifecycle provider keeps the wallet seed, which is encrypted
+/// with the wallet password in all cases
+#[derive(Clone, Debug, PartialEq)]
+pub enum SeedStorage {
+	/// The `wallet.seed` file in the wallet data directory
+	File,
+	/// The OS keystore: macOS Keychain, Windows Credential Manager (DPAPI
+	/// protected) or the Secret Service on Linux
+	OsKeystore,
+	/// A data object on a PKCS#11 token, such as an HSM
+	Pkcs11 {
+		/// Path of the PKCS#11 module library
+		module: String,
+		/// Slot of the token, or the first one with a token present
+		slot: Option<u64>,
+	},
+}
+
+impl Default for SeedStorage {
+	fn default() -> SeedStorage {
+		SeedStorage::File
+	}
+}
+
+/// Costs of the argon2id derivation of the key the wallet seed is encrypted
+/// with from the wallet password. Seeds encrypted with other costs, or with
+/// the PBKDF2 of older wallets, are encrypted again when the wallet is opened
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeedKdfParams {
+	/// Memory used, in KiB
+	pub memory_kib: u32,
+	/// Passes over the memory
+	pub iterations: u32,
+	/// Lanes computed in parallel
+	pub parallelism: u32,
+}
+
+impl Default for SeedKdfParams {
+	fn default() -> SeedKdfParams {
+		SeedKdfParams {
+			memory_kib: 64 * 1024,
+			iterations: 3,
+			parallelism: 4,
+		}
+	}
+}
+
 /// Error type wrapping config errors.
 #[derive(Debug)]
 pub enum ConfigError {
@@ -167,6 +385,39 @@ impl From<io::Error> for ConfigError {
 	}
 }
 
+/// Wallets served together by a single `epic-wallet supervise` process,
+/// read from one TOML file with a `[[wallets]]` table for each
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SupervisorConfig {
+	/// The wallets to open and serve
+	pub wallets: Vec<SupervisedWalletConfig>,
+}
+
+/// A wallet served by the supervisor. Settings not given here, such as the
+/// node to use and the interface to listen on, are taken from the main
+/// wallet configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SupervisedWalletConfig {
+	/// Name of the wallet, used in logs
+	pub name: String,
+	/// Top level directory of the wallet's data
+	pub data_file_dir: String,
+	/// Port of the wallet's foreign API listener
+	pub api_listen_port: u16,
+	/// Port of the wallet's owner API listener, if it has one
+	#[serde(default)]
+	pub owner_api_listen_port: Option<u16>,
+	/// Location of the secret the owner API listener requires
+	#[serde(default)]
+	pub api_secret_path: Option<String>,
+	/// File holding the wallet password, which is otherwise prompted for
+	#[serde(default)]
+	pub password_file: Option<String>,
+	/// Account receiving funds, if not the default
+	#[serde(default)]
+	pub account: Option<String>,
+}
+
 /// Wallet should be split into a separate configuration file
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct GlobalWalletConfig {
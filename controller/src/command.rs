@@ -14,28 +14,64 @@
 
 //! Epic wallet command-line function implementations
 
-use crate::api::TLSConfig;
-use crate::config::{TorConfig, WalletConfig, WALLET_CONFIG_FILE_NAME};
-use crate::core::{core, global};
+use crate::api::{Owner, TLSConfig};
+use crate::apiwallet::{AmountPolicy, ForeignMiddlewareRegistry, SenderAllowlist};
+use crate::config::{TorConfig, WalletConfig, EPIC_WALLET_DIR, WALLET_CONFIG_FILE_NAME};
+use crate::controller::{ForeignListenerConfig, ListenerService, OwnerListenerConfig};
+use crate::core::core::feijoada::PoWType;
+use crate::core::global;
 use crate::error::{Error, ErrorKind};
+use crate::impls::acme::AcmeConfig;
 use crate::impls::{create_sender, KeybaseAllChannels, SlateGetter as _, SlateReceiver as _};
-use crate::impls::{PathToSlate, SlatePutter};
+use crate::impls::{ExecSlateSender, SlateSender};
+use crate::impls::{PathToSlate, QrImagesToSlate, QrToTerminal, SlatePutter, SEED_FILE};
 use crate::keychain;
 use crate::libwallet::{
-	self, address, InitTxArgs, IssueInvoiceTxArgs, NodeClient, PaymentProof, WalletInst,
-	WalletLCProvider,
+	self, address, Amount, CsvSkippedRow, Denomination, FeeContribution, InitTxArgs,
+	InitTxArgsBuilder, InitTxSendArgs, IssueInvoiceTxArgs, NodeClient, PaymentProof,
+	PaymentRequest, SelectionStrategy, Slate, TxFilter, TxLogEntry, TxLogEntryType, TxState,
+	WalletInst, WalletLCProvider, WalletStateExport, MAX_PENDING_RECEIVER_INPUTS,
+	RECEIVER_INPUT_MIN_CONFIRMATIONS,
 };
 use crate::util::secp::key::SecretKey;
+use crate::util::secp::pedersen;
 use crate::util::{to_hex, Mutex, ZeroingString};
+#[cfg(windows)]
+use crate::win_service;
 use crate::{controller, display};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use failure::ResultExt;
 use serde_json as json;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use uuid::Uuid;
 
+/// Directory, within the wallet data directory, holding certificates
+/// obtained via ACME
+const ACME_CERT_DIR: &'static str = "tls";
+
+/// PID file written in the wallet data directory when listening as a daemon,
+/// unless another path is given
+const PID_FILE: &'static str = "epic-wallet.pid";
+
+/// Environment variable holding the one-shot token for provisioning the
+/// wallet through the Owner API
+const PROVISIONING_TOKEN_ENV: &'static str = "EPIC_WALLET_PROVISIONING_TOKEN";
+
+/// How often a daemon listener retries the node before reporting readiness
+const READINESS_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long the supervisor waits before restarting a listener that failed
+const SUPERVISOR_RESTART_DELAY: Duration = Duration::from_secs(10);
+
 fn show_recovery_phrase(phrase: ZeroingString) {
 	println!("Your recovery phrase is:");
 	println!();
@@ -54,6 +90,10 @@ pub struct GlobalArgs {
 	pub chain_type: global::ChainTypes,
 	pub password: Option<ZeroingString>,
 	pub tls_conf: Option<TLSConfig>,
+	/// Print command results as JSON instead of tables
+	pub json_output: bool,
+	/// Units amounts are given and displayed in
+	pub units: Denomination,
 }
 
 /// Arguments for init command
@@ -119,9 +159,123 @@ where
 	Ok(())
 }
 
+/// Arguments for seed show command
+pub struct SeedShowArgs {
+	pub password: ZeroingString,
+}
+
+/// Show the recovery phrase of the open wallet, after asking for the password
+/// again
+pub fn seed_show<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	args: SeedShowArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let phrase = api.reveal_mnemonic(m, None, args.password)?;
+		show_recovery_phrase(phrase);
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Arguments for migrate command
+pub struct MigrateArgs {
+	pub dry_run: bool,
+}
+
+pub fn migrate<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	args: MigrateArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut w_lock = wallet.lock();
+	let p = w_lock.lc_provider()?;
+	let report = p.migrate_wallet(None, args.dry_run)?;
+	display::migration_report(&report);
+	Ok(())
+}
+
 /// Arguments for listen command
 pub struct ListenArgs {
 	pub method: String,
+	/// Run as a service: write a PID file, report readiness to systemd and
+	/// stop cleanly on SIGTERM
+	pub daemon: bool,
+	pub pid_file: Option<String>,
+	/// Secret of the merchant invoice API, served alongside the foreign API
+	/// when set
+	pub merchant_api_secret: Option<String>,
+}
+
+#[cfg(unix)]
+fn notify_service_manager(state: sd_notify::NotifyState) {
+	// does nothing unless started by systemd with Type=notify
+	if let Err(e) = sd_notify::notify(false, &[state]) {
+		warn!("Unable to notify the service manager: {}", e);
+	}
+}
+
+/// Set up the PID file, SIGTERM handling and readiness notification of a
+/// listener run as a daemon
+fn start_daemon<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+	pid_file: &str,
+) -> Result<ListenerService, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut file = File::create(pid_file).context(ErrorKind::IO)?;
+	write!(file, "{}\n", std::process::id()).context(ErrorKind::IO)?;
+
+	let service = ListenerService::default();
+	let shutdown = service.shutdown.clone();
+	ctrlc::set_handler(move || {
+		warn!("Received termination signal, stopping listener");
+		#[cfg(unix)]
+		notify_service_manager(sd_notify::NotifyState::Stopping);
+		shutdown.store(true, Ordering::SeqCst);
+	})
+	.map_err(|e| ErrorKind::GenericError(format!("Unable to handle SIGTERM: {}", e)))?;
+
+	// ready once the listener is up and the node can be reached
+	let ready = service.ready.clone();
+	let shutdown = service.shutdown.clone();
+	thread::spawn(move || {
+		while !shutdown.load(Ordering::SeqCst) {
+			if !ready.load(Ordering::SeqCst) {
+				thread::sleep(Duration::from_millis(500));
+				continue;
+			}
+			let mask = keychain_mask.lock().clone();
+			let mut reachable = false;
+			let _ = controller::owner_single_use(wallet.clone(), mask.as_ref(), |api, m| {
+				reachable = api.node_height(m)?.updated_from_node;
+				Ok(())
+			});
+			if reachable {
+				warn!("Listener ready");
+				#[cfg(unix)]
+				notify_service_manager(sd_notify::NotifyState::Ready);
+				return;
+			}
+			warn!("Listener started, waiting for the node to become reachable");
+			thread::sleep(READINESS_RETRY_INTERVAL);
+		}
+	});
+	Ok(service)
 }
 
 pub fn listen<L, C, K>(
@@ -137,13 +291,95 @@ where
 	C: NodeClient + 'static,
 	K: keychain::Keychain + 'static,
 {
+	if args.daemon && args.method != "http" {
+		return Err(ErrorKind::ArgumentError(
+			"Only the http listener can be run as a daemon".to_owned(),
+		)
+		.into());
+	}
+	let pid_file = args.pid_file.clone().unwrap_or_else(|| {
+		Path::new(&config.data_file_dir)
+			.join(PID_FILE)
+			.to_string_lossy()
+			.into_owned()
+	});
+	let service = match args.daemon {
+		true => Some(start_daemon(
+			wallet.clone(),
+			keychain_mask.clone(),
+			&pid_file,
+		)?),
+		false => None,
+	};
+	let res = run_listener(
+		wallet,
+		keychain_mask,
+		config,
+		tor_config,
+		args,
+		g_args,
+		service.clone(),
+	);
+
+	if service.is_some() {
+		if let Err(e) = std::fs::remove_file(&pid_file) {
+			warn!("Unable to remove PID file {}: {}", pid_file, e);
+		}
+	}
+	res
+}
+
+/// Run the listener until it fails or, when run as a service, is asked to
+/// stop
+fn run_listener<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+	config: &WalletConfig,
+	tor_config: &TorConfig,
+	args: &ListenArgs,
+	g_args: &GlobalArgs,
+	service: Option<ListenerService>,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let acme_config = match config.tls_acme_domain.clone() {
+		Some(domain) => {
+			let contact_email = config.tls_acme_contact_email.clone().ok_or_else(|| {
+				ErrorKind::ArgumentError(
+					"tls_acme_contact_email must be set along with tls_acme_domain".to_owned(),
+				)
+			})?;
+			Some(AcmeConfig {
+				domain,
+				contact_email,
+				challenge_addr: format!("{}:80", config.api_listen_interface),
+				cert_dir: Path::new(&config.data_file_dir)
+					.join(ACME_CERT_DIR)
+					.to_string_lossy()
+					.into_owned(),
+				staging: config.tls_acme_staging.unwrap_or(false),
+			})
+		}
+		None => None,
+	};
+	// slates and notifications the listener couldn't deliver are retried
+	controller::start_job_queue_thread(Arc::new(Owner::new(wallet.clone())), keychain_mask.clone());
 	let res = match args.method.as_str() {
 		"http" => controller::foreign_listener(
 			wallet.clone(),
 			keychain_mask,
 			&config.api_listen_addr(),
-			g_args.tls_conf.clone(),
-			tor_config.use_tor_listener,
+			ForeignListenerConfig {
+				tls_config: g_args.tls_conf.clone(),
+				acme_config,
+				use_tor: tor_config.use_tor_listener,
+				service,
+				merchant_api_secret: args.merchant_api_secret.clone(),
+				..foreign_listener_config(config)?
+			},
 		),
 		"keybase" => {
 			KeybaseAllChannels::new()?.listen(wallet.clone(), keychain_mask, config.clone())
@@ -163,6 +399,242 @@ where
 	Ok(())
 }
 
+/// Arguments for service command
+pub struct ServiceArgs {
+	/// Name the service is registered under, also its event log source
+	pub name: String,
+	pub action: ServiceAction,
+}
+
+/// What the service command does with the Windows service
+pub enum ServiceAction {
+	/// Register the service to start at boot, run with the given arguments
+	Install(Vec<String>),
+	Uninstall,
+	Start,
+	Stop,
+	/// Run the listener, as the service manager does when starting the
+	/// service
+	Run(ListenArgs),
+}
+
+/// Install, uninstall, start or stop the Windows service running the
+/// foreign listener, or run the listener as that service
+#[cfg(windows)]
+pub fn service<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<SecretKey>,
+	config: &WalletConfig,
+	tor_config: &TorConfig,
+	args: ServiceArgs,
+	g_args: &GlobalArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let name = args.name;
+	match args.action {
+		ServiceAction::Install(launch_arguments) => {
+			win_service::install(&name, launch_arguments)?;
+			println!(
+				"Service {} installed, it starts at boot or with `epic-wallet service start`",
+				name
+			);
+		}
+		ServiceAction::Uninstall => {
+			win_service::uninstall(&name)?;
+			println!("Service {} uninstalled", name);
+		}
+		ServiceAction::Start => {
+			win_service::start(&name)?;
+			println!("Service {} started", name);
+		}
+		ServiceAction::Stop => {
+			win_service::stop(&name)?;
+			println!("Service {} stopped", name);
+		}
+		ServiceAction::Run(listen_args) => {
+			let config = config.clone();
+			let tor_config = tor_config.clone();
+			let g_args = g_args.clone();
+			let keychain_mask = Arc::new(Mutex::new(keychain_mask));
+			win_service::run(&name, move |service| {
+				run_listener(
+					wallet,
+					keychain_mask,
+					&config,
+					&tor_config,
+					&listen_args,
+					&g_args,
+					Some(service),
+				)
+			})?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn service<L, C, K>(
+	_wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	_keychain_mask: Option<SecretKey>,
+	_config: &WalletConfig,
+	_tor_config: &TorConfig,
+	_args: ServiceArgs,
+	_g_args: &GlobalArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	Err(ErrorKind::ArgumentError(
+		"Windows services are only available on Windows, elsewhere run `listen --daemon` \
+		 from the system's service manager"
+			.to_owned(),
+	)
+	.into())
+}
+
+/// Checks run before foreign API listener calls, with the receive policies
+/// set in the config
+fn foreign_middleware(config: &WalletConfig) -> Result<ForeignMiddlewareRegistry, Error> {
+	let mut middleware = controller::default_foreign_middleware();
+	if config.receive_min_amount.is_some() || config.receive_max_amount.is_some() {
+		middleware.register(AmountPolicy {
+			min_amount: config.receive_min_amount,
+			max_amount: config.receive_max_amount,
+		});
+	}
+	if let Some(ref senders) = config.receive_sender_allowlist {
+		let allowlist = SenderAllowlist::new(senders).map_err(|e| {
+			ErrorKind::ArgumentError(format!("Invalid receive_sender_allowlist: {}", e))
+		})?;
+		middleware.register(allowlist);
+	}
+	Ok(middleware)
+}
+
+/// What the foreign API listener offers when asked to contribute an input to a
+/// receive, as configured
+fn fee_contribution(config: &WalletConfig) -> Option<FeeContribution> {
+	config
+		.receive_fee_contribution
+		.map(|max_fee| FeeContribution {
+			max_fee,
+			min_confirmations: config
+				.min_reorg_safe_confirmations
+				.unwrap_or(RECEIVER_INPUT_MIN_CONFIRMATIONS),
+			max_pending: config
+				.receive_fee_contribution_max_pending
+				.unwrap_or(MAX_PENDING_RECEIVER_INPUTS),
+		})
+}
+
+/// Where the foreign API listener fetches upgrade advisories from, as
+/// configured
+fn version_advisory_source(
+	config: &WalletConfig,
+) -> Result<Option<controller::VersionAdvisorySource>, Error> {
+	match (
+		&config.version_advisory_url,
+		&config.version_advisory_address,
+	) {
+		(Some(url), Some(a)) => {
+			let signer = address::pubkey_from_onion_v3(a).map_err(|e| {
+				ErrorKind::ArgumentError(format!("Invalid version_advisory_address: {}", e))
+			})?;
+			Ok(Some(controller::VersionAdvisorySource {
+				url: url.clone(),
+				signer,
+			}))
+		}
+		(None, None) => Ok(None),
+		_ => Err(ErrorKind::ArgumentError(
+			"version_advisory_url and version_advisory_address have to be set together".to_owned(),
+		)
+		.into()),
+	}
+}
+
+/// How long each owner API method may run, as configured
+fn owner_rpc_timeouts(config: &WalletConfig) -> Result<controller::RpcTimeouts, Error> {
+	let secs = |s: u64| match s {
+		0 => None,
+		s => Some(Duration::from_secs(s)),
+	};
+	let mut methods = HashMap::new();
+	for m in config.owner_api_method_timeouts.iter().flatten() {
+		let mut parts = m.splitn(2, ':');
+		let method = parts.next().unwrap_or("").trim();
+		let timeout = parts.next().and_then(|s| s.trim().parse::<u64>().ok());
+		match timeout {
+			Some(t) if !method.is_empty() => {
+				methods.insert(method.to_owned(), secs(t));
+			}
+			_ => {
+				return Err(ErrorKind::ArgumentError(format!(
+					"Invalid owner_api_method_timeouts entry '{}', expected method:seconds",
+					m
+				))
+				.into());
+			}
+		}
+	}
+	Ok(controller::RpcTimeouts::new(
+		secs(
+			config
+				.owner_api_read_timeout_secs
+				.unwrap_or(controller::DEFAULT_READ_TIMEOUT_SECS),
+		),
+		secs(
+			config
+				.owner_api_node_timeout_secs
+				.unwrap_or(controller::DEFAULT_NODE_TIMEOUT_SECS),
+		),
+		methods,
+	))
+}
+
+/// How the foreign API listener serves the wallet, as configured
+fn foreign_listener_config(config: &WalletConfig) -> Result<ForeignListenerConfig, Error> {
+	Ok(ForeignListenerConfig {
+		foundation_wallet: config.foundation_wallet.unwrap_or(false),
+		receive_callback_url: config.receive_callback_url.clone(),
+		allowed_origins: config.api_allowed_origins.clone(),
+		middleware: foreign_middleware(config)?,
+		receive_shards: config.receive_shard_accounts.clone(),
+		sign_responses: config.sign_foreign_responses.unwrap_or(false),
+		fee_contribution: fee_contribution(config),
+		accept_legacy_slates: config.accept_legacy_slates.unwrap_or(false),
+		version_advisory_source: version_advisory_source(config)?,
+		..ForeignListenerConfig::default()
+	})
+}
+
+/// How the owner API listener serves the wallet, as configured
+fn owner_listener_config(config: &WalletConfig) -> Result<OwnerListenerConfig, Error> {
+	Ok(OwnerListenerConfig {
+		include_foreign: config.owner_api_include_foreign.unwrap_or(false),
+		min_reorg_safe_confirmations: config.min_reorg_safe_confirmations,
+		max_invoice_fee: config.max_invoice_fee,
+		foundation_wallet: config.foundation_wallet.unwrap_or(false),
+		receive_callback_url: config.receive_callback_url.clone(),
+		allowed_origins: config.owner_api_allowed_origins.clone(),
+		foreign_middleware: foreign_middleware(config)?,
+		replay_window_secs: config.owner_api_replay_window_secs,
+		rpc_timeouts: owner_rpc_timeouts(config)?,
+		allow_output_status_override: config
+			.owner_api_allow_output_status_override
+			.unwrap_or(false),
+		allow_webhooks: config.owner_api_allow_webhooks.unwrap_or(true),
+		read_only: config.owner_api_read_only.unwrap_or(false),
+		..OwnerListenerConfig::default()
+	})
+}
+
 pub fn owner_api<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<SecretKey>,
@@ -178,14 +650,47 @@ where
 	// keychain mask needs to be a sinlge instance, in case the foreign API is
 	// also being run at the same time
 	let km = Arc::new(Mutex::new(keychain_mask));
+	let socket = match config.owner_api_socket_path.clone() {
+		Some(path) => {
+			let permissions = config
+				.owner_api_socket_permissions
+				.clone()
+				.unwrap_or("600".to_owned());
+			let permissions = u32::from_str_radix(&permissions, 8).map_err(|_| {
+				ErrorKind::ArgumentError(format!(
+					"Invalid owner_api_socket_permissions {}, expected octal",
+					permissions
+				))
+			})?;
+			Some(controller::UnixSocketConfig { path, permissions })
+		}
+		None => None,
+	};
+	// allows a single init_wallet_from_env call, for unattended setup
+	let provisioning_token = std::env::var(PROVISIONING_TOKEN_ENV)
+		.ok()
+		.map(ZeroingString::from);
+	if provisioning_token.is_some() {
+		warn!("Wallet provisioning via init_wallet_from_env is enabled");
+	}
+	if config
+		.owner_api_allow_output_status_override
+		.unwrap_or(false)
+	{
+		warn!("Overriding output statuses via set_output_status is enabled");
+	}
 	let res = controller::owner_listener(
 		wallet,
 		km,
 		config.owner_api_listen_addr().as_str(),
-		g_args.api_secret.clone(),
-		g_args.tls_conf.clone(),
-		config.owner_api_include_foreign.clone(),
-		Some(tor_config.clone()),
+		OwnerListenerConfig {
+			api_secret: g_args.api_secret.clone(),
+			tls_config: g_args.tls_conf.clone(),
+			tor_config: Some(tor_config.clone()),
+			socket,
+			provisioning_token,
+			..owner_listener_config(config)?
+		},
 	);
 	if let Err(e) = res {
 		return Err(ErrorKind::LibWallet(e.kind(), e.cause_string()).into());
@@ -193,9 +698,120 @@ where
 	Ok(())
 }
 
+/// A wallet opened by the supervisor, with the configuration it's served with
+pub struct SupervisedWallet<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	/// Name of the wallet, used in logs
+	pub name: String,
+	/// The open wallet
+	pub wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	/// Mask of the wallet's keychain
+	pub keychain_mask: Option<SecretKey>,
+	/// Configuration of the wallet's listeners
+	pub config: WalletConfig,
+	/// Secret required by the wallet's owner API listener
+	pub api_secret: Option<String>,
+}
+
+/// Serve several open wallets from one process, each with a foreign API
+/// listener and optionally an owner API listener on ports of its own.
+/// Listeners that fail are restarted; tor listeners aren't started.
+pub fn supervise<L, C, K>(
+	wallets: Vec<SupervisedWallet<L, C, K>>,
+	g_args: &GlobalArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + Send + Sync + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut handles = vec![];
+	for w in wallets.into_iter() {
+		let km = Arc::new(Mutex::new(w.keychain_mask));
+		let listener_config = ForeignListenerConfig {
+			tls_config: g_args.tls_conf.clone(),
+			..foreign_listener_config(&w.config)?
+		};
+		let (wallet, mask, addr) = (w.wallet.clone(), km.clone(), w.config.api_listen_addr());
+		handles.push(supervise_listener(&w.name, "Foreign", move || {
+			controller::foreign_listener(
+				wallet.clone(),
+				mask.clone(),
+				&addr,
+				listener_config.clone(),
+			)
+		})?);
+
+		if w.config.owner_api_listen_port.is_none() {
+			continue;
+		}
+		let listener_config = OwnerListenerConfig {
+			api_secret: w.api_secret.clone(),
+			read_only: false,
+			..owner_listener_config(&w.config)?
+		};
+		let (wallet, addr) = (w.wallet, w.config.owner_api_listen_addr());
+		handles.push(supervise_listener(&w.name, "Owner", move || {
+			controller::owner_listener(wallet.clone(), km.clone(), &addr, listener_config.clone())
+		})?);
+	}
+	for h in handles {
+		let _ = h.join();
+	}
+	Ok(())
+}
+
+// run a listener of a supervised wallet in a thread of its own, restarting
+// it whenever it fails
+fn supervise_listener<F>(
+	name: &str,
+	listener: &'static str,
+	run: F,
+) -> Result<thread::JoinHandle<()>, Error>
+where
+	F: Fn() -> Result<(), libwallet::Error> + Send + 'static,
+{
+	let name = name.to_owned();
+	let handle = thread::Builder::new()
+		.name(format!("{}-{}", name, listener.to_lowercase()))
+		.spawn(move || loop {
+			match run() {
+				Ok(()) => {
+					warn!("{} listener of wallet {} stopped", listener, name);
+					return;
+				}
+				Err(e) => {
+					error!(
+						"{} listener of wallet {} failed, restarting in {}s: {}",
+						listener,
+						name,
+						SUPERVISOR_RESTART_DELAY.as_secs(),
+						e
+					);
+					thread::sleep(SUPERVISOR_RESTART_DELAY);
+				}
+			}
+		})
+		.context(ErrorKind::GenericError(format!(
+			"Unable to start listener thread for wallet {}",
+			name
+		)))?;
+	Ok(handle)
+}
+
 /// Arguments for account command
 pub struct AccountArgs {
 	pub create: Option<String>,
+	/// Account to freeze
+	pub freeze: Option<String>,
+	/// Account to unfreeze
+	pub unfreeze: Option<String>,
+	/// Why the account is frozen or unfrozen
+	pub reason: Option<String>,
 }
 
 pub fn account<L, C, K>(
@@ -208,7 +824,31 @@ where
 	C: NodeClient + 'static,
 	K: keychain::Keychain + 'static,
 {
-	if args.create.is_none() {
+	let freeze = match (args.freeze, args.unfreeze) {
+		(Some(label), _) => Some((label, true)),
+		(None, Some(label)) => Some((label, false)),
+		(None, None) => None,
+	};
+	if let Some((label, frozen)) = freeze {
+		let reason = args.reason.unwrap_or_default();
+		let res = controller::owner_single_use(wallet, keychain_mask, |api, m| {
+			match frozen {
+				true => api.freeze_account(m, &label, &reason)?,
+				false => api.unfreeze_account(m, &label, &reason)?,
+			};
+			thread::sleep(Duration::from_millis(200));
+			match frozen {
+				true => info!("Account: '{}' Frozen!", label),
+				false => info!("Account: '{}' Unfrozen!", label),
+			}
+			Ok(())
+		});
+		if let Err(e) = res {
+			thread::sleep(Duration::from_millis(200));
+			error!("Error freezing or unfreezing account '{}': {}", label, e);
+			return Err(ErrorKind::LibWallet(e.kind(), e.cause_string()).into());
+		}
+	} else if args.create.is_none() {
 		let res = controller::owner_single_use(wallet, keychain_mask, |api, m| {
 			let acct_mappings = api.accounts(m)?;
 			// give logging thread a moment to catch up
@@ -246,18 +886,77 @@ pub struct SendArgs {
 	pub estimate_selection_strategies: bool,
 	pub method: String,
 	pub dest: String,
+	pub program: Option<String>,
 	pub change_outputs: usize,
 	pub fluff: bool,
 	pub max_outputs: usize,
 	pub target_slate_version: Option<u16>,
 	pub payment_proof_address: Option<String>,
 	pub ttl_blocks: Option<u64>,
+	pub override_reorg_safety: bool,
+	pub selection_tag: Option<String>,
+	pub reservation_id: Option<Uuid>,
+	pub send_max: bool,
+	pub verify_recipient: bool,
+	pub payjoin: bool,
+	/// Blocks to wait for funds to mature, queueing the send if they fall short
+	pub wait_for_maturity: Option<u64>,
+	/// Send even if it looks like a repeat of an earlier send
+	pub force: bool,
+	/// Payment request the send pays
+	pub payment_request: Option<PaymentRequest>,
+}
+
+/// Select a SlateSender for the method and destination, running the given
+/// transport program for the exec method. Over http, any response signer
+/// given must have signed the response.
+fn slate_sender(
+	method: &str,
+	dest: &str,
+	program: Option<&String>,
+	tor_config: Option<TorConfig>,
+	response_signer: Option<DalekPublicKey>,
+) -> Result<Box<dyn SlateSender>, libwallet::Error> {
+	match (method, program) {
+		("exec", Some(p)) => Ok(Box::new(ExecSlateSender::new(p, dest))),
+		_ => create_sender(method, dest, tor_config, response_signer),
+	}
+}
+
+/// JSON result of the send command
+fn send_result(slate: &Slate, args: &SendArgs, posted: bool) -> json::Value {
+	json!({
+		"tx_slate_id": slate.id,
+		"amount": slate.amount.freemans(),
+		"fee": slate.fee,
+		"method": args.method,
+		"dest": args.dest,
+		"posted": posted,
+	})
+}
+
+/// Transaction arguments sending the amount given, or everything spendable
+fn send_amount_args(amount: u64, send_max: bool) -> InitTxArgsBuilder {
+	match send_max {
+		true => InitTxArgs::builder().send_max(true),
+		false => InitTxArgs::builder().amount(Amount::from_freemans(amount)),
+	}
+}
+
+/// The selection strategy named on the command line, "all" or "smallest"
+fn selection_strategy(name: &str) -> SelectionStrategy {
+	match name {
+		"all" => SelectionStrategy::All,
+		_ => SelectionStrategy::Smallest,
+	}
 }
 
 pub fn send<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
 	tor_config: Option<TorConfig>,
+	min_reorg_safe_confirmations: Option<u64>,
 	args: SendArgs,
 	dark_scheme: bool,
 ) -> Result<(), Error>
@@ -267,50 +966,149 @@ where
 	K: keychain::Keychain + 'static,
 {
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		api.set_min_reorg_safe_confirmations(min_reorg_safe_confirmations);
 		if args.estimate_selection_strategies {
 			let strategies = vec!["smallest", "all"]
 				.into_iter()
 				.map(|strategy| {
-					let init_args = InitTxArgs {
-						src_acct_name: None,
-						amount: args.amount,
-						minimum_confirmations: args.minimum_confirmations,
-						max_outputs: args.max_outputs as u32,
-						num_change_outputs: args.change_outputs as u32,
-						selection_strategy_is_use_all: strategy == "all",
-						estimate_only: Some(true),
-						..Default::default()
-					};
+					let init_args = send_amount_args(args.amount, args.send_max)
+						.minimum_confirmations(args.minimum_confirmations)
+						.max_outputs(args.max_outputs as u32)
+						.num_change_outputs(args.change_outputs as u32)
+						.strategy(selection_strategy(strategy))
+						.estimate_only(true)
+						.override_reorg_safety(args.override_reorg_safety)
+						.selection_tag(args.selection_tag.clone())
+						.build()
+						.unwrap();
 					let slate = api.init_send_tx(m, init_args).unwrap();
-					(strategy, slate.amount, slate.fee)
+					(
+						strategy,
+						slate.amount.freemans(),
+						slate.fee,
+						slate.fee_breakdown,
+					)
 				})
-				.collect();
-			display::estimate(args.amount, strategies, dark_scheme);
+				.collect::<Vec<_>>();
+			if g_args.json_output {
+				let estimates = strategies
+					.iter()
+					.map(|(strategy, amount, fee, fee_breakdown)| {
+						json!({
+							"selection_strategy": strategy,
+							"amount": amount,
+							"fee": fee,
+							"fee_breakdown": fee_breakdown,
+						})
+					})
+					.collect::<Vec<_>>();
+				return display::json(&json!({ "estimates": estimates }));
+			}
+			// when sending max, everything locked but the fee is sent
+			let amount = match args.send_max {
+				true => strategies[0].1 - strategies[0].2,
+				false => args.amount,
+			};
+			display::estimate(amount, strategies, dark_scheme);
 		} else {
 			let payment_proof_recipient_address = match args.payment_proof_address {
 				Some(ref p) => Some(address::ed25519_parse_pubkey(p)?),
 				None => None,
 			};
-			let init_args = InitTxArgs {
-				src_acct_name: None,
-				amount: args.amount,
-				minimum_confirmations: args.minimum_confirmations,
-				max_outputs: args.max_outputs as u32,
-				num_change_outputs: args.change_outputs as u32,
-				selection_strategy_is_use_all: args.selection_strategy == "all",
-				message: args.message.clone(),
-				target_slate_version: args.target_slate_version,
-				payment_proof_recipient_address,
-				ttl_blocks: args.ttl_blocks,
-				send_args: None,
-				..Default::default()
+			// ask the recipient for as much of the fee as it offers to pay
+			let receiver_fee = match args.payjoin {
+				true => {
+					let sender = slate_sender(
+						&args.method,
+						&args.dest,
+						args.program.as_ref(),
+						tor_config.clone(),
+						None,
+					)?;
+					match sender.receiver_fee_contribution()? {
+						Some(f) => Some(f),
+						None => {
+							return Err(libwallet::ErrorKind::ReceiverContribution(
+								"recipient doesn't contribute inputs".to_owned(),
+							)
+							.into());
+						}
+					}
+				}
+				false => None,
+			};
+			// a file, QR code or account of this wallet doesn't say who's paid
+			let destination = match args.method.as_str() {
+				"file" | "qr" | "self" => None,
+				_ => Some(args.dest.clone()),
 			};
-			let result = api.init_send_tx(m, init_args);
+			let init_args = send_amount_args(args.amount, args.send_max)
+				.minimum_confirmations(args.minimum_confirmations)
+				.max_outputs(args.max_outputs as u32)
+				.num_change_outputs(args.change_outputs as u32)
+				.strategy(selection_strategy(&args.selection_strategy))
+				.message(args.message.clone())
+				.target_slate_version(args.target_slate_version)
+				.payment_proof_recipient_address(payment_proof_recipient_address)
+				.ttl_blocks(args.ttl_blocks)
+				.override_reorg_safety(args.override_reorg_safety)
+				.selection_tag(args.selection_tag.clone())
+				.reservation_id(args.reservation_id)
+				.receiver_fee(receiver_fee)
+				.destination(destination)
+				.allow_duplicate(args.force)
+				.payment_request(args.payment_request.clone());
+			// the wallet makes the send itself, now or once funds mature
+			if args.wait_for_maturity.is_some() {
+				let init_args = init_args
+					.send_args(InitTxSendArgs {
+						method: args.method.clone(),
+						dest: args.dest.clone(),
+						finalize: true,
+						post_tx: true,
+						fluff: args.fluff,
+					})
+					.wait_for_maturity(args.wait_for_maturity)
+					.build()?;
+				api.set_tor_config(tor_config.clone());
+				return match api.init_send_tx(m, init_args) {
+					Ok(slate) => {
+						info!("Tx sent ok");
+						if g_args.json_output {
+							return display::json(&send_result(&slate, &args, true));
+						}
+						Ok(())
+					}
+					Err(e) => match e.kind() {
+						libwallet::ErrorKind::SendQueued { id, ready_height } => {
+							if g_args.json_output {
+								return display::json(&json!({
+									"queued": id,
+									"ready_height": ready_height,
+								}));
+							}
+							warn!(
+								"Not enough funds yet, send {} queued until height {}. It's made by the owner API listener, or by 'send_queue --process'",
+								id, ready_height
+							);
+							Ok(())
+						}
+						k => {
+							if !g_args.json_output {
+								display::not_enough_funds(&k);
+								display::duplicate_payment(&k);
+							}
+							Err(e)
+						}
+					},
+				};
+			}
+			let result = api.init_send_tx(m, init_args.build()?);
 			let mut slate = match result {
 				Ok(s) => {
 					info!(
-						"Tx created: {} epic to {} (strategy '{}')",
-						core::amount_to_hr_string(args.amount, false),
+						"Tx created: {} to {} (strategy '{}')",
+						display::hr_amount_with_units(s.amount),
 						args.dest,
 						args.selection_strategy,
 					);
@@ -318,6 +1116,10 @@ where
 				}
 				Err(e) => {
 					info!("Tx not created: {}", e);
+					if !g_args.json_output {
+						display::not_enough_funds(&e.kind());
+						display::duplicate_payment(&e.kind());
+					}
 					return Err(e);
 				}
 			};
@@ -326,6 +1128,17 @@ where
 				"file" => {
 					PathToSlate((&args.dest).into()).put_tx(&slate)?;
 					api.tx_lock_outputs(m, &slate, 0)?;
+					if g_args.json_output {
+						return display::json(&send_result(&slate, &args, false));
+					}
+					return Ok(());
+				}
+				"qr" => {
+					QrToTerminal.put_tx(&slate)?;
+					api.tx_lock_outputs(m, &slate, 0)?;
+					if g_args.json_output {
+						return display::json(&send_result(&slate, &args, false));
+					}
 					return Ok(());
 				}
 				"self" => {
@@ -340,7 +1153,17 @@ where
 					})?;
 				}
 				method => {
-					let sender = create_sender(method, &args.dest, tor_config)?;
+					let response_signer = match args.verify_recipient {
+						true => payment_proof_recipient_address,
+						false => None,
+					};
+					let sender = slate_sender(
+						method,
+						&args.dest,
+						args.program.as_ref(),
+						tor_config,
+						response_signer,
+					)?;
 					slate = sender.send_tx(&slate)?;
 					api.tx_lock_outputs(m, &slate, 0)?;
 				}
@@ -351,10 +1174,17 @@ where
 				e
 			})?;
 			slate = api.finalize_tx(m, &slate)?;
-			let result = api.post_tx(m, &slate.tx, args.fluff);
+			let result = api.post_or_queue_tx(m, &slate.tx, args.fluff);
 			match result {
-				Ok(_) => {
-					info!("Tx sent ok",);
+				Ok(posted) => {
+					if posted {
+						info!("Tx sent ok",);
+					} else {
+						warn!("Node unreachable, tx queued to post once it's back");
+					}
+					if g_args.json_output {
+						return display::json(&send_result(&slate, &args, posted));
+					}
 					return Ok(());
 				}
 				Err(e) => {
@@ -370,10 +1200,19 @@ where
 
 /// Receive command argument
 pub struct ReceiveArgs {
+	pub method: String,
 	pub input: String,
 	pub message: Option<String>,
 }
 
+/// Read an incoming slate from a file, or from images of its QR codes
+fn get_slate(method: &str, input: &str) -> Result<Slate, libwallet::Error> {
+	match method {
+		"qr" => QrImagesToSlate(input.into()).get_tx(),
+		_ => PathToSlate(input.into()).get_tx(),
+	}
+}
+
 pub fn receive<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
@@ -385,7 +1224,7 @@ where
 	C: NodeClient + 'static,
 	K: keychain::Keychain + 'static,
 {
-	let mut slate = PathToSlate((&args.input).into()).get_tx()?;
+	let mut slate = get_slate(&args.method, &args.input)?;
 	let km = match keychain_mask.as_ref() {
 		None => None,
 		Some(&m) => Some(m.to_owned()),
@@ -398,6 +1237,11 @@ where
 		slate = api.receive_tx(&slate, Some(&g_args.account), args.message.clone())?;
 		Ok(())
 	})?;
+	if args.method == "qr" {
+		QrToTerminal.put_tx(&slate)?;
+		info!("Response shown, and can be scanned by the transaction originator.");
+		return Ok(());
+	}
 	PathToSlate(format!("{}.response", args.input).into()).put_tx(&slate)?;
 	info!(
 		"Response file {}.response generated, and can be sent back to the transaction originator.",
@@ -408,6 +1252,7 @@ where
 
 /// Finalize command args
 pub struct FinalizeArgs {
+	pub method: String,
 	pub input: String,
 	pub fluff: bool,
 	pub nopost: bool,
@@ -417,6 +1262,7 @@ pub struct FinalizeArgs {
 pub fn finalize<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
 	args: FinalizeArgs,
 ) -> Result<(), Error>
 where
@@ -424,7 +1270,7 @@ where
 	C: NodeClient + 'static,
 	K: keychain::Keychain + 'static,
 {
-	let mut slate = PathToSlate((&args.input).into()).get_tx()?;
+	let mut slate = get_slate(&args.method, &args.input)?;
 
 	// Rather than duplicating the entire command, we'll just
 	// try to determine what kind of finalization this is
@@ -470,14 +1316,18 @@ where
 
 	if !args.nopost {
 		controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-			let result = api.post_tx(m, &slate.tx, args.fluff);
+			let result = api.post_or_queue_tx(m, &slate.tx, args.fluff);
 			match result {
-				Ok(_) => {
+				Ok(true) => {
 					info!(
 						"Transaction sent successfully, check the wallet again for confirmation."
 					);
 					Ok(())
 				}
+				Ok(false) => {
+					warn!("Node unreachable, transaction queued to post once it's back.");
+					Ok(())
+				}
 				Err(e) => {
 					error!("Tx not sent: {}", e);
 					Err(e)
@@ -486,8 +1336,17 @@ where
 		})?;
 	}
 
-	if args.dest.is_some() {
-		PathToSlate((&args.dest.unwrap()).into()).put_tx(&slate)?;
+	if let Some(ref dest) = args.dest {
+		PathToSlate(dest.into()).put_tx(&slate)?;
+	}
+
+	if g_args.json_output {
+		display::json(&json!({
+			"tx_slate_id": slate.id,
+			"invoice": is_invoice,
+			"posted": !args.nopost,
+			"dest": args.dest,
+		}))?;
 	}
 
 	Ok(())
@@ -519,6 +1378,50 @@ where
 	Ok(())
 }
 
+/// Payment request command args
+pub struct PaymentRequestArgs {
+	/// amount requested, in freemans
+	pub amount: u64,
+	/// what the payment is for
+	pub memo: Option<String>,
+}
+
+/// Create a payment request for the active account, shown armored and as a
+/// QR code for the payer to pay with 'send'
+pub fn payment_request<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: PaymentRequestArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let km = match keychain_mask.as_ref() {
+		None => None,
+		Some(&m) => Some(m.to_owned()),
+	};
+	controller::foreign_single_use(wallet, km, |api| {
+		let r = api.create_payment_request(Amount::from_freemans(args.amount), args.memo)?;
+		if g_args.json_output {
+			return display::json(&r);
+		}
+		println!("{}", r.qr);
+		println!("{}", r.armored);
+		println!();
+		info!(
+			"Request {} for {} created, valid until {}. It's paid with 'send -d <request>'.",
+			r.request.id,
+			display::hr_amount_with_units(r.request.amount),
+			display::hr_time(&r.request.expires_at),
+		);
+		Ok(())
+	})?;
+	Ok(())
+}
+
 /// Arguments for the process_invoice command
 pub struct ProcessInvoiceArgs {
 	pub message: Option<String>,
@@ -526,10 +1429,13 @@ pub struct ProcessInvoiceArgs {
 	pub selection_strategy: String,
 	pub method: String,
 	pub dest: String,
+	pub program: Option<String>,
 	pub max_outputs: usize,
 	pub input: String,
 	pub estimate_selection_strategies: bool,
 	pub ttl_blocks: Option<u64>,
+	pub override_reorg_safety: bool,
+	pub max_fee: Option<u64>,
 }
 
 /// Process invoice
@@ -537,6 +1443,8 @@ pub fn process_invoice<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
 	tor_config: Option<TorConfig>,
+	min_reorg_safe_confirmations: Option<u64>,
+	max_invoice_fee: Option<u64>,
 	args: ProcessInvoiceArgs,
 	dark_scheme: bool,
 ) -> Result<(), Error>
@@ -547,38 +1455,44 @@ where
 {
 	let slate = PathToSlate((&args.input).into()).get_tx()?;
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		api.set_min_reorg_safe_confirmations(min_reorg_safe_confirmations);
+		api.set_max_invoice_fee(max_invoice_fee);
 		if args.estimate_selection_strategies {
 			let strategies = vec!["smallest", "all"]
 				.into_iter()
 				.map(|strategy| {
-					let init_args = InitTxArgs {
-						src_acct_name: None,
-						amount: slate.amount,
-						minimum_confirmations: args.minimum_confirmations,
-						max_outputs: args.max_outputs as u32,
-						num_change_outputs: 1u32,
-						selection_strategy_is_use_all: strategy == "all",
-						estimate_only: Some(true),
-						..Default::default()
-					};
+					let init_args = InitTxArgs::builder()
+						.amount(slate.amount)
+						.minimum_confirmations(args.minimum_confirmations)
+						.max_outputs(args.max_outputs as u32)
+						.num_change_outputs(1)
+						.strategy(selection_strategy(strategy))
+						.estimate_only(true)
+						.override_reorg_safety(args.override_reorg_safety)
+						.build()
+						.unwrap();
 					let slate = api.init_send_tx(m, init_args).unwrap();
-					(strategy, slate.amount, slate.fee)
+					(
+						strategy,
+						slate.amount.freemans(),
+						slate.fee,
+						slate.fee_breakdown,
+					)
 				})
 				.collect();
-			display::estimate(slate.amount, strategies, dark_scheme);
+			display::estimate(slate.amount.freemans(), strategies, dark_scheme);
 		} else {
-			let init_args = InitTxArgs {
-				src_acct_name: None,
-				amount: 0,
-				minimum_confirmations: args.minimum_confirmations,
-				max_outputs: args.max_outputs as u32,
-				num_change_outputs: 1u32,
-				selection_strategy_is_use_all: args.selection_strategy == "all",
-				message: args.message.clone(),
-				ttl_blocks: args.ttl_blocks,
-				send_args: None,
-				..Default::default()
-			};
+			let init_args = InitTxArgs::builder()
+				.amount_from_invoice()
+				.minimum_confirmations(args.minimum_confirmations)
+				.max_outputs(args.max_outputs as u32)
+				.num_change_outputs(1)
+				.strategy(selection_strategy(&args.selection_strategy))
+				.message(args.message.clone())
+				.ttl_blocks(args.ttl_blocks)
+				.override_reorg_safety(args.override_reorg_safety)
+				.max_invoice_fee(args.max_fee)
+				.build()?;
 			if let Err(e) = api.verify_slate_messages(m, &slate) {
 				error!("Error validating participant messages: {}", e);
 				return Err(e);
@@ -587,8 +1501,8 @@ where
 			let mut slate = match result {
 				Ok(s) => {
 					info!(
-						"Invoice processed: {} epic to {} (strategy '{}')",
-						core::amount_to_hr_string(slate.amount, false),
+						"Invoice processed: {} to {} (strategy '{}')",
+						display::hr_amount_with_units(slate.amount),
 						args.dest,
 						args.selection_strategy,
 					);
@@ -596,6 +1510,7 @@ where
 				}
 				Err(e) => {
 					info!("Tx not created: {}", e);
+					display::not_enough_funds(&e.kind());
 					return Err(e);
 				}
 			};
@@ -618,9 +1533,16 @@ where
 					})?;
 				}
 				method => {
-					let sender = create_sender(method, &args.dest, tor_config)?;
+					let sender =
+						slate_sender(method, &args.dest, args.program.as_ref(), tor_config, None)?;
 					slate = sender.send_tx(&slate)?;
 					api.tx_lock_outputs(m, &slate, 0)?;
+					// invoicers listening for payments, such as merchants,
+					// finalize the transaction but leave posting it to the payer
+					if slate.participant_data.iter().all(|p| p.is_complete()) {
+						api.post_tx(m, &slate.tx, false)?;
+						info!("Invoice paid, transaction posted.");
+					}
 				}
 			}
 		}
@@ -631,6 +1553,7 @@ where
 /// Info command args
 pub struct InfoArgs {
 	pub minimum_confirmations: u64,
+	pub detailed: bool,
 }
 
 pub fn info<L, C, K>(
@@ -646,25 +1569,1156 @@ where
 	K: keychain::Keychain + 'static,
 {
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-		let (validated, wallet_info) =
-			api.retrieve_summary_info(m, true, args.minimum_confirmations)?;
-		display::info(&g_args.account, &wallet_info, validated, dark_scheme);
+		let (validated, wallet_info) = match args.detailed {
+			true => api.retrieve_detailed_summary_info(m, true, args.minimum_confirmations)?,
+			false => api.retrieve_summary_info(m, true, args.minimum_confirmations)?,
+		};
+		if g_args.json_output {
+			return display::json(&json!({
+				"account": g_args.account,
+				"refreshed_from_node": validated,
+				"summary": wallet_info,
+			}));
+		}
+		display::info(&g_args.account, &wallet_info, validated, dark_scheme);
+		Ok(())
+	})?;
+	Ok(())
+}
+
+pub fn status<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let status = api.node_status(m)?;
+		display::node_status(&status);
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Doctor command args
+pub struct DoctorArgs {
+	/// Make the repairs that are safe to make unattended
+	pub fix: bool,
+}
+
+/// How one of the doctor's checks turned out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DoctorStatus {
+	/// Nothing to fix
+	Ok,
+	/// A problem the wallet works around
+	Warn,
+	/// A problem keeping the wallet from working properly
+	Fail,
+}
+
+impl DoctorStatus {
+	/// Short label for the status
+	pub fn label(&self) -> &'static str {
+		match self {
+			DoctorStatus::Ok => "OK",
+			DoctorStatus::Warn => "WARN",
+			DoctorStatus::Fail => "FAIL",
+		}
+	}
+}
+
+/// Outcome of one of the doctor's checks
+pub struct DoctorCheck {
+	/// What was checked
+	pub name: &'static str,
+	pub status: DoctorStatus,
+	/// What was found
+	pub detail: String,
+	/// How to fix the problem found, if any
+	pub suggestion: Option<String>,
+	/// Whether `--fix` repaired the problem
+	pub fixed: bool,
+}
+
+impl DoctorCheck {
+	fn ok(name: &'static str, detail: String) -> DoctorCheck {
+		DoctorCheck {
+			name,
+			status: DoctorStatus::Ok,
+			detail,
+			suggestion: None,
+			fixed: false,
+		}
+	}
+
+	fn problem(
+		name: &'static str,
+		status: DoctorStatus,
+		detail: String,
+		suggestion: &str,
+	) -> DoctorCheck {
+		DoctorCheck {
+			name,
+			status,
+			detail,
+			suggestion: Some(suggestion.to_owned()),
+			fixed: false,
+		}
+	}
+}
+
+/// Run the wallet's diagnostics, printing what each found along with a
+/// suggested fix. With `fix`, only repairs that can't lose funds or data are
+/// made: removing a PID file left by a dead listener, releasing stale locks
+/// (which the node is asked about first) and cancelling transactions past
+/// their TTL. Fails if any check failed
+pub fn doctor<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	config: &WalletConfig,
+	g_args: &GlobalArgs,
+	args: DoctorArgs,
+	wallet_open: bool,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut checks = vec![doctor_config(config), doctor_pid_file(config, args.fix)];
+	match wallet_open {
+		true => {
+			checks.push(doctor_seed(config));
+			checks.push(doctor_database(wallet.clone())?);
+			checks.append(&mut doctor_wallet(wallet, keychain_mask, args.fix)?);
+		}
+		false => checks.push(DoctorCheck::problem(
+			"Seed",
+			DoctorStatus::Fail,
+			format!("No wallet found in {}", config.data_file_dir),
+			"Create a wallet with `epic-wallet init`, or restore one with `epic-wallet init -r`",
+		)),
+	}
+
+	if g_args.json_output {
+		let checks: Vec<_> = checks
+			.iter()
+			.map(|c| {
+				json!({
+					"name": c.name,
+					"status": c.status.label(),
+					"detail": c.detail,
+					"suggestion": c.suggestion,
+					"fixed": c.fixed,
+				})
+			})
+			.collect();
+		display::json(&json!({ "checks": checks }))?;
+	} else {
+		display::doctor(&checks);
+	}
+	let failed = checks
+		.iter()
+		.filter(|c| c.status == DoctorStatus::Fail && !c.fixed)
+		.count();
+	if failed > 0 {
+		let msg = format!("{} doctor check(s) failed", failed);
+		return Err(ErrorKind::GenericError(msg).into());
+	}
+	Ok(())
+}
+
+// settings that are wrong without stopping the wallet from opening
+fn doctor_config(config: &WalletConfig) -> DoctorCheck {
+	let mut problems = vec![];
+	match url::Url::parse(&config.check_node_api_http_addr) {
+		Ok(u) if u.scheme() == "http" || u.scheme() == "https" => {}
+		_ => problems.push(format!(
+			"check_node_api_http_addr \"{}\" is not an http(s) URL",
+			config.check_node_api_http_addr
+		)),
+	}
+	if config.owner_api_listen_port() == config.api_listen_port {
+		problems.push(format!(
+			"api_listen_port and owner_api_listen_port are both {}",
+			config.api_listen_port
+		));
+	}
+	if let (Some(min), Some(max)) = (config.receive_min_amount, config.receive_max_amount) {
+		if min > max {
+			problems.push("receive_min_amount is above receive_max_amount".to_owned());
+		}
+	}
+	if config.tls_certificate_file.is_some() != config.tls_certificate_key.is_some() {
+		problems
+			.push("tls_certificate_file and tls_certificate_key must be set together".to_owned());
+	}
+	if config.tls_acme_domain.is_some() && config.tls_acme_contact_email.is_none() {
+		problems.push("tls_acme_domain needs tls_acme_contact_email to be set".to_owned());
+	}
+	let secrets = [
+		("api_secret_path", &config.api_secret_path),
+		("node_api_secret_path", &config.node_api_secret_path),
+		("merchant_api_secret_path", &config.merchant_api_secret_path),
+	];
+	for (key, path) in secrets.iter() {
+		if let Some(p) = path {
+			if !Path::new(p).exists() {
+				problems.push(format!("{} {} does not exist", key, p));
+			}
+		}
+	}
+	match problems.is_empty() {
+		true => DoctorCheck::ok("Config", "No problems found".to_owned()),
+		false => DoctorCheck::problem(
+			"Config",
+			DoctorStatus::Warn,
+			problems.join("; "),
+			&format!("Correct the settings in {}", WALLET_CONFIG_FILE_NAME),
+		),
+	}
+}
+
+// whether a process is running, if that can be told on this platform
+#[cfg(target_os = "linux")]
+fn process_running(pid: u32) -> Option<bool> {
+	Some(Path::new(&format!("/proc/{}", pid)).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_running(_pid: u32) -> Option<bool> {
+	None
+}
+
+// a PID file left by a daemon listener that's no longer running
+fn doctor_pid_file(config: &WalletConfig, fix: bool) -> DoctorCheck {
+	let name = "Lock File";
+	let pid_file = Path::new(&config.data_file_dir).join(PID_FILE);
+	let pid = match std::fs::read_to_string(&pid_file) {
+		Ok(p) => p.trim().parse::<u32>().ok(),
+		Err(_) => return DoctorCheck::ok(name, "No listener PID file".to_owned()),
+	};
+	let pid_file = pid_file.to_string_lossy().into_owned();
+	let running = pid.and_then(process_running);
+	if let (Some(pid), Some(true)) = (pid, running) {
+		return DoctorCheck::ok(name, format!("Listener running as process {}", pid));
+	}
+	if pid.is_some() && running.is_none() {
+		return DoctorCheck::problem(
+			name,
+			DoctorStatus::Warn,
+			format!(
+				"Unable to check whether the listener in {} is running",
+				pid_file
+			),
+			"Remove the PID file if no listener is running",
+		);
+	}
+	let mut check = DoctorCheck::problem(
+		name,
+		DoctorStatus::Warn,
+		format!(
+			"{} was left by a listener that is no longer running",
+			pid_file
+		),
+		"Remove the PID file, or run with --fix",
+	);
+	if fix {
+		match std::fs::remove_file(&pid_file) {
+			Ok(_) => check.fixed = true,
+			Err(e) => warn!("Unable to remove PID file {}: {}", pid_file, e),
+		}
+	}
+	check
+}
+
+// copies of the seed left by an interrupted password change
+fn doctor_seed(config: &WalletConfig) -> DoctorCheck {
+	let name = "Seed";
+	let opened = "Wallet seed opened. Keep its recovery phrase written down, \
+	              `epic-wallet seed show` displays it";
+	match config.seed_storage.as_ref().map(|s| s.as_str()) {
+		None | Some("file") => {}
+		_ => return DoctorCheck::ok(name, opened.to_owned()),
+	}
+	let seed_dir = Path::new(&config.data_file_dir).join(EPIC_WALLET_DIR);
+	let backup_prefix = format!("{}.bak", SEED_FILE);
+	let mut backups: Vec<String> = match std::fs::read_dir(&seed_dir) {
+		Ok(entries) => entries
+			.filter_map(|e| e.ok())
+			.map(|e| e.file_name().to_string_lossy().into_owned())
+			.filter(|f| f.starts_with(&backup_prefix))
+			.collect(),
+		Err(_) => vec![],
+	};
+	if backups.is_empty() {
+		return DoctorCheck::ok(name, opened.to_owned());
+	}
+	backups.sort();
+	DoctorCheck::problem(
+		name,
+		DoctorStatus::Warn,
+		format!(
+			"Seed backups {} in {} were left by an interrupted password change",
+			backups.join(", "),
+			seed_dir.to_string_lossy()
+		),
+		"Once the recovery phrase is written down, remove the backups, which open with an \
+		 old password",
+	)
+}
+
+// records of the wallet database referring to ones that don't exist
+fn doctor_database<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+) -> Result<DoctorCheck, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut w_lock = wallet.lock();
+	let w = w_lock.lc_provider()?.wallet_inst()?;
+	let accounts: HashSet<_> = w.acct_path_iter().map(|a| a.path).collect();
+	let txs: HashSet<_> = w
+		.tx_log_iter()
+		.map(|t| (t.parent_key_id.clone(), t.id))
+		.collect();
+	let mut problems = vec![];
+	for tx in w.tx_log_iter() {
+		if !accounts.contains(&tx.parent_key_id) {
+			problems.push(format!("transaction {} belongs to no account", tx.id));
+		}
+	}
+	let mut outputs = 0;
+	for out in w.iter() {
+		outputs += 1;
+		let label = out
+			.commit
+			.clone()
+			.unwrap_or_else(|| out.key_id.to_bip_32_string());
+		if !accounts.contains(&out.root_key_id) {
+			problems.push(format!("output {} belongs to no account", label));
+		}
+		let refs = [
+			("created by", out.tx_log_entry),
+			("spent by", out.spent_by_tx),
+		];
+		for (what, id) in refs.iter() {
+			if let Some(id) = id {
+				if !txs.contains(&(out.root_key_id.clone(), *id)) {
+					problems.push(format!(
+						"output {} is {} missing transaction {}",
+						label, what, id
+					));
+				}
+			}
+		}
+	}
+	let name = "Database";
+	Ok(match problems.is_empty() {
+		true => DoctorCheck::ok(
+			name,
+			format!(
+				"{} outputs and {} transactions consistent",
+				outputs,
+				txs.len()
+			),
+		),
+		false => DoctorCheck::problem(
+			name,
+			DoctorStatus::Fail,
+			problems.join("; "),
+			"Run `epic-wallet scan` to rebuild the wallet's outputs from the chain",
+		),
+	})
+}
+
+// the node, and the wallet's state relative to it
+fn doctor_wallet<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	fix: bool,
+) -> Result<Vec<DoctorCheck>, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut checks = vec![];
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let status = api.node_status(m)?;
+		let node_check = match (status.node_reachable, status.node_version.as_ref()) {
+			(false, _) => DoctorCheck::problem(
+				"Node",
+				DoctorStatus::Fail,
+				"Unable to contact the node".to_owned(),
+				"Check that the node is running and `check_node_api_http_addr` is correct",
+			),
+			(true, None) => DoctorCheck::problem(
+				"Node",
+				DoctorStatus::Warn,
+				"Node reachable, but it didn't report its version".to_owned(),
+				"Upgrade the node",
+			),
+			(true, Some(v)) if status.clock_skewed => DoctorCheck::problem(
+				"Node",
+				DoctorStatus::Warn,
+				format!(
+					"Node {} reachable, its tip's timestamp is {} seconds from this machine's clock",
+					v.node_version,
+					status.clock_skew_secs.unwrap_or(0)
+				),
+				"Check the system clock, or wait for the node to finish syncing",
+			),
+			(true, Some(v)) => DoctorCheck::ok(
+				"Node",
+				format!(
+					"Node {} at height {}, wallet {} blocks behind",
+					v.node_version,
+					status.node_height.unwrap_or(0),
+					status.sync_lag.unwrap_or(0)
+				),
+			),
+		};
+		checks.push(node_check);
+
+		let chain_check = match status.node_reachable {
+			false => DoctorCheck::problem(
+				"Chain Type",
+				DoctorStatus::Warn,
+				"Not checked, the node can't be reached".to_owned(),
+				"Run the doctor again once the node is reachable",
+			),
+			true => match api.node_ping(m) {
+				Ok(_) => DoctorCheck::ok(
+					"Chain Type",
+					format!(
+						"Node is on the wallet's chain ({:?})",
+						global::CHAIN_TYPE.read().clone()
+					),
+				),
+				Err(e) => match e.kind() {
+					libwallet::ErrorKind::ChainTypeMismatch(..) => DoctorCheck::problem(
+						"Chain Type",
+						DoctorStatus::Fail,
+						format!("{}", e),
+						"Point `check_node_api_http_addr` at a node on the wallet's chain, or \
+						 correct `chain_type`",
+					),
+					_ => DoctorCheck::problem(
+						"Chain Type",
+						DoctorStatus::Warn,
+						format!("Unable to check the node's chain: {}", e),
+						"Run the doctor again once the node is reachable",
+					),
+				},
+			},
+		};
+		checks.push(chain_check);
+
+		let (_, locked) = api.get_locked_outputs(m, false)?;
+		let stale: Vec<String> = locked
+			.iter()
+			.filter(|l| l.stale)
+			.map(|l| to_hex(l.output.commit.as_ref().to_vec()))
+			.collect();
+		let mut lock_check = match stale.is_empty() {
+			true => DoctorCheck::ok(
+				"Stale Locks",
+				format!("{} locked outputs, none stale", locked.len()),
+			),
+			false => DoctorCheck::problem(
+				"Stale Locks",
+				DoctorStatus::Warn,
+				format!(
+					"Outputs {} are held by no pending transaction",
+					stale.join(", ")
+				),
+				"Release them with `epic-wallet unlock <commit>`, or run with --fix",
+			),
+		};
+		if fix && !stale.is_empty() {
+			let mut released = 0;
+			for commit in stale.iter() {
+				match api.force_unlock(m, commit, false) {
+					Ok(_) => released += 1,
+					Err(e) => warn!("Unable to unlock output {}: {}", commit, e),
+				}
+			}
+			lock_check.fixed = released == stale.len();
+		}
+		checks.push(lock_check);
+
+		let height = match status.node_height {
+			Some(h) => h,
+			None => status.last_confirmed_height,
+		};
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		let expired: Vec<u32> = txs
+			.iter()
+			.filter(|t| TxStage::of(t, height) == TxStage::Expired)
+			.filter(|t| match t.tx_type {
+				TxLogEntryType::TxSent | TxLogEntryType::TxReceived => true,
+				_ => false,
+			})
+			.map(|t| t.id)
+			.collect();
+		let ids: Vec<String> = expired.iter().map(|id| id.to_string()).collect();
+		let mut ttl_check = match expired.is_empty() {
+			true => DoctorCheck::ok(
+				"Expired Txs",
+				"No pending transactions past their TTL".to_owned(),
+			),
+			false => DoctorCheck::problem(
+				"Expired Txs",
+				DoctorStatus::Warn,
+				format!(
+					"Transactions {} are past their TTL and still pending",
+					ids.join(", ")
+				),
+				"Cancel them with `epic-wallet cancel -i <id>`, or run with --fix",
+			),
+		};
+		if fix && !expired.is_empty() {
+			let mut cancelled = 0;
+			for id in expired.iter() {
+				match api.cancel_tx(m, Some(*id), None, false) {
+					Ok(_) => cancelled += 1,
+					Err(e) => warn!("Unable to cancel transaction {}: {}", id, e),
+				}
+			}
+			ttl_check.fixed = cancelled == expired.len();
+		}
+		checks.push(ttl_check);
+		Ok(())
+	})?;
+	Ok(checks)
+}
+
+/// Rewards command args
+pub struct RewardsArgs {
+	pub pow_type: Option<PoWType>,
+	pub start_height: Option<u64>,
+	pub end_height: Option<u64>,
+}
+
+pub fn rewards<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: RewardsArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let (validated, rewards) = api.retrieve_mining_rewards(
+			m,
+			true,
+			args.pow_type,
+			args.start_height,
+			args.end_height,
+		)?;
+		display::mining_rewards(&g_args.account, validated, rewards);
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Outputs command args
+pub struct OutputsArgs {
+	pub show_full_history: bool,
+	pub include_history: bool,
+}
+
+pub fn outputs<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: OutputsArgs,
+	dark_scheme: bool,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let res = api.node_height(m)?;
+		if args.include_history {
+			let (validated, lineage) = api.retrieve_output_lineage(m, true, None)?;
+			if g_args.json_output {
+				return display::json(&json!({
+					"account": g_args.account,
+					"height": res.height,
+					"refreshed_from_node": validated,
+					"outputs": lineage,
+				}));
+			}
+			display::output_lineage(&g_args.account, res.height, validated, lineage, dark_scheme)?;
+			return Ok(());
+		}
+		let (validated, outputs) =
+			api.retrieve_outputs(m, g_args.show_spent, true, args.show_full_history, None)?;
+		if g_args.json_output {
+			return display::json(&json!({
+				"account": g_args.account,
+				"height": res.height,
+				"refreshed_from_node": validated,
+				"outputs": outputs,
+			}));
+		}
+		display::outputs(&g_args.account, res.height, validated, outputs, dark_scheme)?;
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Txs command args
+pub struct TxsArgs {
+	pub id: Option<u32>,
+	pub tx_slate_id: Option<Uuid>,
+	pub check_kernel: Option<pedersen::Commitment>,
+	pub since: Option<DateTime<Utc>>,
+	pub until: Option<DateTime<Utc>>,
+	pub filter: Option<TxFilter>,
+}
+
+pub fn txs<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: TxsArgs,
+	dark_scheme: bool,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let kernel_status = match args.check_kernel.as_ref() {
+			Some(excess) => Some(api.get_kernel_status(m, excess)?),
+			None => None,
+		};
+		if let (Some(status), false) = (kernel_status.as_ref(), g_args.json_output) {
+			display::kernel_status(status);
+		}
+		let res = api.node_height(m)?;
+		let (validated, txs) = match args.filter.as_ref() {
+			Some(f) => api.retrieve_txs_by_filter(m, true, f)?,
+			None if args.since.is_some() || args.until.is_some() => {
+				api.retrieve_txs_by_time(m, true, args.since, args.until)?
+			}
+			None => api.retrieve_txs(m, true, args.id, args.tx_slate_id)?,
+		};
+		if g_args.json_output {
+			// a single transaction comes with its outputs
+			let outputs = match (args.id, args.tx_slate_id, txs.first()) {
+				(None, None, _) | (_, _, None) => None,
+				(_, _, Some(tx)) => {
+					Some(api.retrieve_outputs(m, true, false, false, Some(tx.id))?.1)
+				}
+			};
+			return display::json(&json!({
+				"account": g_args.account,
+				"height": res.height,
+				"refreshed_from_node": validated,
+				"kernel_status": kernel_status,
+				"txs": txs,
+				"outputs": outputs,
+			}));
+		}
+		let include_status = !args.id.is_some() && !args.tx_slate_id.is_some();
+		display::txs(
+			&g_args.account,
+			res.height,
+			validated,
+			&txs,
+			include_status,
+			dark_scheme,
+		)?;
+
+		// if given a particular transaction id or uuid, also get and display associated
+		// inputs/outputs and messages
+		let id = if args.id.is_some() {
+			args.id
+		} else if args.tx_slate_id.is_some() {
+			if let Some(tx) = txs.iter().find(|t| t.tx_slate_id == args.tx_slate_id) {
+				Some(tx.id)
+			} else {
+				println!("Could not find a transaction matching given txid.\n");
+				None
+			}
+		} else {
+			None
+		};
+
+		if id.is_some() {
+			let (_, outputs) = api.retrieve_outputs(m, true, false, false, id)?;
+			display::outputs(&g_args.account, res.height, validated, outputs, dark_scheme)?;
+			// should only be one here, but just in case
+			for tx in txs {
+				display::tx_messages(&tx, dark_scheme)?;
+				display::payment_proof(&tx)?;
+			}
+		}
+
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Tx show and tx resume, identifying the transaction by local id or slate id
+pub struct TxArgs {
+	pub id: Option<u32>,
+	pub tx_slate_id: Option<Uuid>,
+}
+
+/// Where a transaction stands in the send/receive/invoice workflow
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TxStage {
+	/// Sent, waiting for the recipient's response slate to finalize
+	AwaitingResponse,
+	/// Received, waiting for the other party to finalize and post
+	AwaitingFinalization,
+	/// Finalized, but not known to have been posted
+	ReadyToPost,
+	/// Posted, waiting to be mined
+	AwaitingConfirmation,
+	/// Unconfirmed past its TTL cutoff height
+	Expired,
+	/// Confirmed on chain
+	Confirmed,
+	/// Cancelled, or a reverted coinbase
+	Cancelled,
+	/// Written by a newer wallet version
+	Unknown,
+}
+
+impl TxStage {
+	/// Work out the stage of a transaction log entry at the given chain height
+	pub fn of(tx: &TxLogEntry, height: u64) -> TxStage {
+		// the wallet only expires a transaction past its TTL when it next refreshes
+		let past_ttl = tx.ttl_cutoff_height.map(|h| height >= h).unwrap_or(false);
+		match tx.state {
+			TxState::Confirmed => TxStage::Confirmed,
+			TxState::Cancelled => TxStage::Cancelled,
+			TxState::Expired => TxStage::Expired,
+			TxState::Unknown => TxStage::Unknown,
+			_ if past_ttl => TxStage::Expired,
+			TxState::Posted => TxStage::AwaitingConfirmation,
+			TxState::Finalized => TxStage::ReadyToPost,
+			TxState::Created | TxState::Sent => match tx.tx_type {
+				TxLogEntryType::TxSent => TxStage::AwaitingResponse,
+				_ => TxStage::AwaitingFinalization,
+			},
+		}
+	}
+
+	/// Short description of the stage
+	pub fn description(&self) -> &'static str {
+		match self {
+			TxStage::AwaitingResponse => "Awaiting the recipient's response",
+			TxStage::AwaitingFinalization => "Awaiting finalization by the other party",
+			TxStage::ReadyToPost => "Finalized, not posted",
+			TxStage::AwaitingConfirmation => "Posted, awaiting confirmation",
+			TxStage::Expired => "Expired",
+			TxStage::Confirmed => "Confirmed",
+			TxStage::Cancelled => "Cancelled",
+			TxStage::Unknown => "Unknown",
+		}
+	}
+
+	/// What the user needs to do next to move the transaction along
+	pub fn next_action(&self, tx: &TxLogEntry) -> String {
+		match self {
+			TxStage::AwaitingResponse => format!(
+				"Wait for the recipient to return the response slate, then finalize it with \
+				 `epic-wallet finalize -i <response file>`. To abandon the transaction and \
+				 unlock its inputs, run `epic-wallet cancel -i {}`.",
+				tx.id
+			),
+			TxStage::AwaitingFinalization => format!(
+				"Wait for the other party to finalize and post the transaction. If this is an \
+				 invoice you issued, finalize the paid invoice with \
+				 `epic-wallet finalize -i <paid invoice file>`. To abandon it, run \
+				 `epic-wallet cancel -i {}`.",
+				tx.id
+			),
+			TxStage::ReadyToPost => format!(
+				"Post the finalized transaction with `epic-wallet repost -i {}`.",
+				tx.id
+			),
+			TxStage::AwaitingConfirmation => format!(
+				"Wait for the transaction to be mined, then run `epic-wallet txs -i {}` to \
+				 refresh it. If it is not confirmed after a while, post it again with \
+				 `epic-wallet repost -i {}`.",
+				tx.id, tx.id
+			),
+			TxStage::Expired => format!(
+				"The transaction passed its TTL cutoff height and can no longer complete. \
+				 Cancel it with `epic-wallet cancel -i {}` to unlock its outputs.",
+				tx.id
+			),
+			TxStage::Confirmed => match tx.confirmations {
+				Some(c) => format!("Nothing to do, confirmed with {} confirmations.", c),
+				None => "Nothing to do, the transaction is confirmed.".to_owned(),
+			},
+			TxStage::Cancelled => "Nothing to do, the transaction was cancelled.".to_owned(),
+			TxStage::Unknown => {
+				"This transaction was written by a newer wallet version, use it to continue."
+					.to_owned()
+			}
+		}
+	}
+}
+
+fn find_tx<L, C, K>(
+	api: &mut Owner<L, C, K>,
+	m: Option<&SecretKey>,
+	args: &TxArgs,
+) -> Result<(bool, TxLogEntry), libwallet::Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let (validated, txs) = api.retrieve_txs(m, true, args.id, args.tx_slate_id)?;
+	match txs.into_iter().next() {
+		Some(tx) => Ok((validated, tx)),
+		None => {
+			let id = match args.tx_slate_id {
+				Some(u) => u.to_string(),
+				None => args.id.map(|i| i.to_string()).unwrap_or_default(),
+			};
+			Err(libwallet::ErrorKind::TransactionDoesntExist(id).into())
+		}
+	}
+}
+
+/// Show everything known about a single transaction: its stage, kernel,
+/// outputs, participant data and payment proof
+pub fn tx_show<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: TxArgs,
+	dark_scheme: bool,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let height = api.node_height(m)?.height;
+		let (validated, tx) = find_tx(api, m, &args)?;
+		let stage = TxStage::of(&tx, height);
+		let (_, outputs) = api.retrieve_outputs(m, true, false, false, Some(tx.id))?;
+		if g_args.json_output {
+			return display::json(&json!({
+				"account": g_args.account,
+				"height": height,
+				"refreshed_from_node": validated,
+				"stage": format!("{:?}", stage),
+				"next_action": stage.next_action(&tx),
+				"tx": tx,
+				"outputs": outputs,
+			}));
+		}
+		display::tx_details(&tx, stage.description());
+		display::outputs(&g_args.account, height, validated, outputs, dark_scheme)?;
+		display::tx_messages(&tx, dark_scheme)?;
+		display::payment_proof(&tx)?;
+		println!("Next: {}\n", stage.next_action(&tx));
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Tell the user what is needed to move a transaction to its next stage
+pub fn tx_resume<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: TxArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let height = api.node_height(m)?.height;
+		let (_, tx) = find_tx(api, m, &args)?;
+		let stage = TxStage::of(&tx, height);
+		if g_args.json_output {
+			return display::json(&json!({
+				"id": tx.id,
+				"tx_slate_id": tx.tx_slate_id,
+				"stage": format!("{:?}", stage),
+				"next_action": stage.next_action(&tx),
+			}));
+		}
+		println!(
+			"\nTransaction {}: {}\n\n{}\n",
+			tx.id,
+			stage.description(),
+			stage.next_action(&tx)
+		);
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// Post
+pub struct PostArgs {
+	pub input: String,
+	pub fluff: bool,
+}
+
+pub fn post<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	args: PostArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let slate = PathToSlate((&args.input).into()).get_tx()?;
+
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		if api.post_or_queue_tx(m, &slate.tx, args.fluff)? {
+			info!("Posted transaction");
+		} else {
+			warn!("Node unreachable, transaction queued to post once it's back");
+		}
+		return Ok(());
+	})?;
+	Ok(())
+}
+
+/// Repost
+pub struct RepostArgs {
+	pub id: u32,
+	pub dump_file: Option<String>,
+	pub fluff: bool,
+}
+
+pub fn repost<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	args: RepostArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, true, Some(args.id), None)?;
+		let stored_tx = api.get_stored_tx(m, &txs[0])?;
+		if stored_tx.is_none() {
+			error!(
+				"Transaction with id {} does not have transaction data. Not reposting.",
+				args.id
+			);
+			return Ok(());
+		}
+		match args.dump_file {
+			None => {
+				if txs[0].confirmed {
+					error!(
+						"Transaction with id {} is confirmed. Not reposting.",
+						args.id
+					);
+					return Ok(());
+				}
+				api.post_tx(m, &stored_tx.unwrap(), args.fluff)?;
+				info!("Reposted transaction at {}", args.id);
+				return Ok(());
+			}
+			Some(f) => {
+				let mut tx_file = File::create(f.clone())?;
+				tx_file.write_all(json::to_string(&stored_tx).unwrap().as_bytes())?;
+				tx_file.sync_all()?;
+				info!("Dumped transaction data for tx {} to {}", args.id, f);
+				return Ok(());
+			}
+		}
+	})?;
+	Ok(())
+}
+
+/// List the locked outputs of the active account
+pub fn locks<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	dark_scheme: bool,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let (validated, locked) = api.get_locked_outputs(m, true)?;
+		if g_args.json_output {
+			return display::json(&json!({
+				"account": g_args.account,
+				"refreshed_from_node": validated,
+				"outputs": locked,
+			}));
+		}
+		display::locked_outputs(&g_args.account, validated, locked, dark_scheme)?;
 		Ok(())
 	})?;
 	Ok(())
 }
 
-/// Outputs command args
-pub struct OutputsArgs {
-	pub show_full_history: bool,
+/// Unlock command args
+pub struct UnlockArgs {
+	pub commit: String,
+	pub include_pending: bool,
+}
+
+pub fn unlock<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: UnlockArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		match api.force_unlock(m, &args.commit, args.include_pending) {
+			Ok(unlocked) => {
+				if g_args.json_output {
+					return display::json(&unlocked);
+				}
+				info!("Output {} unlocked", args.commit);
+				Ok(())
+			}
+			Err(e) => {
+				error!("Unlocking output failed: {}", e);
+				Err(e)
+			}
+		}
+	})?;
+	Ok(())
+}
+
+/// Arguments for the tag command
+pub struct TagArgs {
+	pub commits: Vec<String>,
+	pub tag: String,
+	pub remove: bool,
+}
+
+pub fn tag<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: TagArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let result = match args.remove {
+			true => api.untag_outputs(m, args.commits.clone(), &args.tag),
+			false => api.tag_outputs(m, args.commits.clone(), &args.tag),
+		};
+		match result {
+			Ok(outputs) => {
+				if g_args.json_output {
+					return display::json(&outputs);
+				}
+				match args.remove {
+					true => info!(
+						"Tag '{}' removed from {} output(s)",
+						args.tag,
+						outputs.len()
+					),
+					false => info!("{} output(s) tagged '{}'", outputs.len(), args.tag),
+				}
+				Ok(())
+			}
+			Err(e) => {
+				error!("Tagging outputs failed: {}", e);
+				Err(e)
+			}
+		}
+	})?;
+	Ok(())
+}
+
+/// Arguments for the reserve command
+pub struct ReserveArgs {
+	pub amount: u64,
+	pub label: Option<String>,
+}
+
+pub fn reserve<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	args: ReserveArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		match api.reserve_funds(m, args.amount, args.label.clone()) {
+			Ok(reservation) => {
+				if g_args.json_output {
+					return display::json(&reservation);
+				}
+				info!(
+					"Reserved {} as {}",
+					display::hr_amount_with_units(reservation.amount),
+					reservation.id
+				);
+				Ok(())
+			}
+			Err(e) => {
+				error!("Reserving funds failed: {}", e);
+				Err(e)
+			}
+		}
+	})?;
+	Ok(())
+}
+
+/// Arguments for the reservations command
+pub struct ReservationsArgs {
+	pub release: Option<Uuid>,
 }
 
-pub fn outputs<L, C, K>(
+pub fn reservations<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
 	g_args: &GlobalArgs,
-	args: OutputsArgs,
 	dark_scheme: bool,
+	args: ReservationsArgs,
 ) -> Result<(), Error>
 where
 	L: WalletLCProvider<'static, C, K> + 'static,
@@ -672,27 +2726,41 @@ where
 	K: keychain::Keychain + 'static,
 {
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-		let res = api.node_height(m)?;
-		let (validated, outputs) =
-			api.retrieve_outputs(m, g_args.show_spent, true, args.show_full_history, None)?;
-		display::outputs(&g_args.account, res.height, validated, outputs, dark_scheme)?;
+		if let Some(id) = args.release {
+			let released = api.release_reservation(m, id)?;
+			if g_args.json_output {
+				return display::json(&released);
+			}
+			info!(
+				"Released reservation {} of {}",
+				id,
+				display::hr_amount_with_units(released.amount)
+			);
+			return Ok(());
+		}
+		let reservations = api.retrieve_reservations(m)?;
+		if g_args.json_output {
+			return display::json(&reservations);
+		}
+		display::reservations(&g_args.account, &reservations, dark_scheme);
 		Ok(())
 	})?;
 	Ok(())
 }
 
-/// Txs command args
-pub struct TxsArgs {
-	pub id: Option<u32>,
-	pub tx_slate_id: Option<Uuid>,
+/// Arguments for the send_queue command
+pub struct SendQueueArgs {
+	pub cancel: Option<Uuid>,
+	pub process: bool,
 }
 
-pub fn txs<L, C, K>(
+pub fn send_queue<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
 	g_args: &GlobalArgs,
-	args: TxsArgs,
+	tor_config: Option<TorConfig>,
 	dark_scheme: bool,
+	args: SendQueueArgs,
 ) -> Result<(), Error>
 where
 	L: WalletLCProvider<'static, C, K> + 'static,
@@ -700,122 +2768,139 @@ where
 	K: keychain::Keychain + 'static,
 {
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-		let res = api.node_height(m)?;
-		let (validated, txs) = api.retrieve_txs(m, true, args.id, args.tx_slate_id)?;
-		let include_status = !args.id.is_some() && !args.tx_slate_id.is_some();
-		display::txs(
-			&g_args.account,
-			res.height,
-			validated,
-			&txs,
-			include_status,
-			dark_scheme,
-		)?;
-
-		// if given a particular transaction id or uuid, also get and display associated
-		// inputs/outputs and messages
-		let id = if args.id.is_some() {
-			args.id
-		} else if args.tx_slate_id.is_some() {
-			if let Some(tx) = txs.iter().find(|t| t.tx_slate_id == args.tx_slate_id) {
-				Some(tx.id)
-			} else {
-				println!("Could not find a transaction matching given txid.\n");
-				None
+		if let Some(id) = args.cancel {
+			let cancelled = api.cancel_queued_send(m, &id)?;
+			if g_args.json_output {
+				return display::json(&cancelled);
 			}
-		} else {
-			None
-		};
-
-		if id.is_some() {
-			let (_, outputs) = api.retrieve_outputs(m, true, false, false, id)?;
-			display::outputs(&g_args.account, res.height, validated, outputs, dark_scheme)?;
-			// should only be one here, but just in case
-			for tx in txs {
-				display::tx_messages(&tx, dark_scheme)?;
-				display::payment_proof(&tx)?;
+			info!(
+				"Cancelled queued send {} of {}",
+				id,
+				display::hr_amount_with_units(cancelled.args.amount)
+			);
+			return Ok(());
+		}
+		if args.process {
+			api.set_tor_config(tor_config.clone());
+			let results = api.process_send_queue(m)?;
+			if g_args.json_output {
+				return display::json(&results);
+			}
+			for r in results.iter() {
+				match r.error {
+					Some(ref e) => warn!("Queued send {} dropped: {}", r.queued.id, e),
+					None => info!("Queued send {} made", r.queued.id),
+				}
 			}
 		}
-
+		let queue = api.retrieve_send_queue(m)?;
+		if g_args.json_output {
+			return display::json(&queue);
+		}
+		display::send_queue(&queue, dark_scheme);
 		Ok(())
 	})?;
 	Ok(())
 }
 
-/// Post
-pub struct PostArgs {
-	pub input: String,
-	pub fluff: bool,
+/// Arguments for the jobs command
+pub struct JobsArgs {
+	pub retry: Option<Uuid>,
+	pub delete: Option<Uuid>,
+	pub process: bool,
 }
 
-pub fn post<L, C, K>(
+pub fn jobs<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
-	args: PostArgs,
+	g_args: &GlobalArgs,
+	dark_scheme: bool,
+	args: JobsArgs,
 ) -> Result<(), Error>
 where
 	L: WalletLCProvider<'static, C, K> + 'static,
 	C: NodeClient + 'static,
 	K: keychain::Keychain + 'static,
 {
-	let slate = PathToSlate((&args.input).into()).get_tx()?;
-
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-		api.post_tx(m, &slate.tx, args.fluff)?;
-		info!("Posted transaction");
-		return Ok(());
+		if let Some(id) = args.delete {
+			let deleted = api.delete_job(m, &id)?;
+			if g_args.json_output {
+				return display::json(&deleted);
+			}
+			info!("Deleted job {}", id);
+			return Ok(());
+		}
+		if let Some(id) = args.retry {
+			api.retry_job(m, &id)?;
+		}
+		if args.process || args.retry.is_some() {
+			let results = api.process_jobs(m)?;
+			if g_args.json_output {
+				return display::json(&results);
+			}
+			for r in results.iter() {
+				match r.error {
+					Some(ref e) => warn!("Job {} failed: {}", r.job.id, e),
+					None => info!("Job {} done", r.job.id),
+				}
+			}
+		}
+		let jobs = api.retrieve_jobs(m)?;
+		if g_args.json_output {
+			return display::json(&jobs);
+		}
+		display::jobs(&jobs, dark_scheme);
+		Ok(())
 	})?;
 	Ok(())
 }
 
-/// Repost
-pub struct RepostArgs {
-	pub id: u32,
-	pub dump_file: Option<String>,
-	pub fluff: bool,
+/// Arguments for the export_account_key command
+pub struct ExportAccountKeyArgs {
+	/// Values to derive the account's expected commitments for
+	pub values: Vec<u64>,
+	pub from_index: u32,
+	pub count: u32,
+	pub accept_warning: bool,
 }
 
-pub fn repost<L, C, K>(
+pub fn export_account_key<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
-	args: RepostArgs,
+	g_args: &GlobalArgs,
+	dark_scheme: bool,
+	args: ExportAccountKeyArgs,
 ) -> Result<(), Error>
 where
 	L: WalletLCProvider<'static, C, K> + 'static,
 	C: NodeClient + 'static,
 	K: keychain::Keychain + 'static,
 {
-	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-		let (_, txs) = api.retrieve_txs(m, true, Some(args.id), None)?;
-		let stored_tx = api.get_stored_tx(m, &txs[0])?;
-		if stored_tx.is_none() {
-			error!(
-				"Transaction with id {} does not have transaction data. Not reposting.",
-				args.id
-			);
-			return Ok(());
+	controller::owner_single_use(wallet, keychain_mask, |api, m| {
+		let export = api.export_account_key(m, &g_args.account, args.accept_warning)?;
+		let commits = match args.values.is_empty() {
+			true => vec![],
+			false => api.derive_expected_commitments(
+				m,
+				&g_args.account,
+				args.from_index,
+				args.count,
+				&args.values,
+				args.accept_warning,
+			)?,
+		};
+		if g_args.json_output {
+			return display::json(&json!({
+				"account": export,
+				"expected_commitments": commits,
+			}));
 		}
-		match args.dump_file {
-			None => {
-				if txs[0].confirmed {
-					error!(
-						"Transaction with id {} is confirmed. Not reposting.",
-						args.id
-					);
-					return Ok(());
-				}
-				api.post_tx(m, &stored_tx.unwrap(), args.fluff)?;
-				info!("Reposted transaction at {}", args.id);
-				return Ok(());
-			}
-			Some(f) => {
-				let mut tx_file = File::create(f.clone())?;
-				tx_file.write_all(json::to_string(&stored_tx).unwrap().as_bytes())?;
-				tx_file.sync_all()?;
-				info!("Dumped transaction data for tx {} to {}", args.id, f);
-				return Ok(());
-			}
+		display::account_key_export(&export);
+		if !commits.is_empty() {
+			display::expected_commitments(&commits, dark_scheme);
 		}
+		Ok(())
 	})?;
 	Ok(())
 }
@@ -825,11 +2910,16 @@ pub struct CancelArgs {
 	pub tx_id: Option<u32>,
 	pub tx_slate_id: Option<Uuid>,
 	pub tx_id_string: String,
+	pub stale_minutes: Option<u64>,
+	pub dry_run: bool,
+	pub force: bool,
 }
 
 pub fn cancel<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
+	g_args: &GlobalArgs,
+	dark_scheme: bool,
 	args: CancelArgs,
 ) -> Result<(), Error>
 where
@@ -838,7 +2928,34 @@ where
 	K: keychain::Keychain + 'static,
 {
 	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
-		let result = api.cancel_tx(m, args.tx_id, args.tx_slate_id);
+		if args.dry_run {
+			let preview = api.preview_cancel_tx(m, args.tx_id, args.tx_slate_id)?;
+			if g_args.json_output {
+				return display::json(&preview);
+			}
+			display::cancel_preview(&preview, dark_scheme);
+			return Ok(());
+		}
+		if let Some(minutes) = args.stale_minutes {
+			return match api.cancel_stale_txs(m, minutes) {
+				Ok(cancelled) => {
+					for tx in cancelled.iter() {
+						info!("Transaction {} Cancelled", tx.id);
+					}
+					info!(
+						"{} transaction(s) older than {} minutes cancelled",
+						cancelled.len(),
+						minutes
+					);
+					Ok(())
+				}
+				Err(e) => {
+					error!("TX Cancellation failed: {}", e);
+					Err(e)
+				}
+			};
+		}
+		let result = api.cancel_tx(m, args.tx_id, args.tx_slate_id, args.force);
 		match result {
 			Ok(_) => {
 				info!("Transaction {} Cancelled", args.tx_id_string);
@@ -846,6 +2963,12 @@ where
 			}
 			Err(e) => {
 				error!("TX Cancellation failed: {}", e);
+				if let libwallet::ErrorKind::CancelRefused(_) = e.kind() {
+					warn!(
+						"Check the transaction with `epic-wallet cancel --dry_run`, and only \
+						 use --force if it can't confirm"
+					);
+				}
 				Err(e)
 			}
 		}
@@ -853,6 +2976,35 @@ where
 	Ok(())
 }
 
+/// Ping the node once the wallet is open, refusing to run against a node on a
+/// different chain than the wallet's and finishing any transaction post
+/// interrupted by an earlier crash. An unreachable node only gets a warning,
+/// so the wallet's stored data can still be used. Returns whether the node
+/// answered
+pub fn node_ping<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<bool, Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let mut reachable = false;
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let ping = api.node_ping(m)?;
+		if let Some(e) = ping.error {
+			warn!(
+				"Unable to contact node, wallet data may be out of date: {}",
+				e
+			);
+		}
+		reachable = ping.reachable;
+		Ok(())
+	})?;
+	Ok(reachable)
+}
+
 /// wallet check
 pub struct CheckArgs {
 	pub delete_unconfirmed: bool,
@@ -1025,3 +3177,167 @@ where
 	})?;
 	Ok(())
 }
+
+/// State Export Args
+pub struct StateExportArgs {
+	pub output_file: String,
+}
+
+pub fn state_export<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	args: StateExportArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let result = api.export_wallet_state(m);
+		match result {
+			Ok(export) => {
+				let mut state_file = File::create(args.output_file.clone())?;
+				state_file.write_all(json::to_string_pretty(&export).unwrap().as_bytes())?;
+				state_file.sync_all()?;
+				warn!(
+					"Wallet state at height {} exported to {}, with {} outputs. Keep it private, \
+					 it lists the wallet's outputs and their values.",
+					export.state.height,
+					args.output_file,
+					export.state.outputs.len()
+				);
+				Ok(())
+			}
+			Err(e) => {
+				error!("Wallet state export failed: {}", e);
+				Err(e)
+			}
+		}
+	})?;
+	Ok(())
+}
+
+/// CSV Import Args
+pub struct CsvImportArgs {
+	pub outputs_file: Option<String>,
+	pub txs_file: Option<String>,
+	pub units: Denomination,
+}
+
+pub fn csv_import<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	args: CsvImportArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	let read_csv = |file: &str| -> Result<String, Error> {
+		let mut csv = String::new();
+		match File::open(file).and_then(|mut f| f.read_to_string(&mut csv)) {
+			Ok(_) => Ok(csv),
+			Err(e) => {
+				let msg = format!("unable to read {}: {}", file, e);
+				error!("CSV import failed: {}", msg);
+				Err(libwallet::ErrorKind::CsvImport(msg).into())
+			}
+		}
+	};
+	let print_skipped = |file: &str, skipped: &[CsvSkippedRow]| {
+		for s in skipped {
+			println!("  {} line {} skipped: {}", file, s.line, s.reason);
+		}
+	};
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		// outputs first, so the entries made restoring them are filled in
+		// from the transaction log rather than imported twice
+		if let Some(ref file) = args.outputs_file {
+			let import = api.import_outputs_csv(m, &read_csv(file)?, args.units)?;
+			println!(
+				"Imported {} outputs from {}, skipped {} rows.",
+				import.imported.len(),
+				file,
+				import.skipped.len()
+			);
+			print_skipped(file, &import.skipped);
+		}
+		if let Some(ref file) = args.txs_file {
+			let import = api.import_txs_csv(m, &read_csv(file)?, args.units)?;
+			println!(
+				"Imported {} transactions from {}, skipped {} rows.",
+				import.imported.len(),
+				file,
+				import.skipped.len()
+			);
+			print_skipped(file, &import.skipped);
+		}
+		Ok(())
+	})?;
+	Ok(())
+}
+
+/// State Import Args
+pub struct StateImportArgs {
+	pub input_file: String,
+}
+
+pub fn state_import<L, C, K>(
+	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	args: StateImportArgs,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: keychain::Keychain + 'static,
+{
+	controller::owner_single_use(wallet.clone(), keychain_mask, |api, m| {
+		let mut state_f = match File::open(&args.input_file) {
+			Ok(f) => f,
+			Err(e) => {
+				let msg = format!("unable to open {}: {}", args.input_file, e);
+				error!("Wallet state import failed: {}", msg);
+				return Err(libwallet::ErrorKind::StateImport(msg).into());
+			}
+		};
+		let mut state = String::new();
+		state_f.read_to_string(&mut state)?;
+		let export: WalletStateExport = match json::from_str(&state) {
+			Ok(s) => s,
+			Err(e) => {
+				let msg = format!("unable to parse {}: {}", args.input_file, e);
+				error!("Wallet state import failed: {}", msg);
+				return Err(libwallet::ErrorKind::StateImport(msg).into());
+			}
+		};
+		let result = api.import_wallet_state(m, &export);
+		match result {
+			Ok(import) => {
+				println!(
+					"Wallet state imported at height {}: {} outputs added.",
+					import.height,
+					import.imported.len()
+				);
+				if !import.accounts.is_empty() {
+					println!("Accounts added: {}", import.accounts.join(", "));
+				}
+				if !import.skipped.is_empty() {
+					warn!(
+						"{} exported outputs weren't found unspent on chain and were skipped, \
+						 run a scan if any are still the wallet's.",
+						import.skipped.len()
+					);
+				}
+				Ok(())
+			}
+			Err(e) => {
+				error!("Wallet state import failed: {}", e);
+				Err(e)
+			}
+		}
+	})?;
+	Ok(())
+}
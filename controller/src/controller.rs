@@ -14,15 +14,21 @@
 
 //! Controller for wallet.. instantiates and handles listeners (or single-run
 //! invocations) as needed.
-use crate::api::{self, ApiServer, BasicAuthMiddleware, ResponseFuture, Router, TLSConfig};
+use crate::api::{self, ApiServer, ResponseFuture, Router, TLSConfig};
 use crate::config::TorConfig;
 use crate::keychain::Keychain;
+use crate::libwallet::api_impl::foreign::{
+	RESPONSE_ADDRESS_HEADER, RESPONSE_SIGNATURE_HEADER, WALLET_VERSION,
+};
 use crate::libwallet::{
-	address, Error, ErrorKind, NodeClient, NodeVersionInfo, Slate, WalletInst, WalletLCProvider,
-	EPIC_BLOCK_HEADER_VERSION,
+	address, Error, ErrorKind, FeeContribution, NodeClient, Slate, VersionAdvisory, WalletInst,
+	WalletLCProvider,
 };
 use crate::util::secp::key::SecretKey;
-use crate::util::{from_hex, static_secp_instance, to_base64, Mutex};
+use crate::util::{
+	from_hex, static_secp_instance, to_base64, to_hex, Mutex, RwLock, ZeroingString,
+};
+use epic_wallet_util::{ct_eq, zeroize_secret_key};
 use failure::ResultExt;
 use futures::future::{err, ok};
 use futures::{Future, Stream};
@@ -32,48 +38,136 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
+use crate::impls::acme::AcmeConfig;
+use crate::impls::fetch_version_advisory;
 use crate::impls::tor::config as tor_config;
 use crate::impls::tor::process as tor_process;
 
 use crate::apiwallet::{
 	EncryptedRequest, EncryptedResponse, EncryptionErrorResponse, Foreign,
-	ForeignCheckMiddlewareFn, ForeignRpc, Owner, OwnerRpc, OwnerRpcS, RpcId,
+	ForeignMiddlewareRegistry, ForeignRpc, Owner, OwnerRpc, OwnerRpcS, RpcId, VersionCheck,
+	REQUEST_TIMESTAMP,
 };
+use chrono::Utc;
 use easy_jsonrpc_mw;
 use easy_jsonrpc_mw::{Handler, MaybeReply};
+use ed25519_dalek::PublicKey as DalekPublicKey;
+
+/// How often the expiry of a certificate obtained via ACME is checked
+const ACME_RENEWAL_CHECK_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// How often a listener run as a service checks whether it has been asked
+/// to stop
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the owner listener makes queued sends whose funds have matured
+const SEND_QUEUE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often listeners run the queued jobs that are due
+const JOB_QUEUE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the foreign listener fetches the upgrade advisory, if it does
+const VERSION_ADVISORY_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How far from the wallet's time encrypted owner API requests may be made,
+/// with their nonces remembered meanwhile to refuse replays, unless
+/// configured otherwise
+pub const DEFAULT_REPLAY_WINDOW_SECS: u64 = 300;
+
+/// Timeout of owner API calls only reading the wallet's stored data, unless
+/// configured otherwise
+pub const DEFAULT_READ_TIMEOUT_SECS: u64 = 5;
+
+/// Timeout of owner API calls that may wait on the node or another wallet,
+/// unless configured otherwise
+pub const DEFAULT_NODE_TIMEOUT_SECS: u64 = 120;
+
+/// Path old wallets, sending V0 and V1 slates, post them to for receiving
+pub const LEGACY_RECEIVE_PATH: &str = "/v1/wallet/foreign/receive_tx";
+
+/// JSON-RPC error code of owner API calls that ran past their timeout
+pub const RPC_TIMEOUT_ERROR_CODE: i32 = -32098;
+
+/// Calls still running after timing out before further calls with a timeout
+/// are refused, rather than left to pile up behind them
+const MAX_ABANDONED_CALLS: usize = 16;
+
+/// Owner API methods that only read the wallet's stored data, unless asked to
+/// refresh from the node first
+const READ_METHODS: &[&str] = &[
+	"accounts",
+	"check_duplicate_payment",
+	"create_payment_uri",
+	"derive_expected_commitments",
+	"export_account_key",
+	"export_wallet_state",
+	"get_balance_history",
+	"get_locked_outputs",
+	"get_public_proof_address",
+	"get_stored_tx",
+	"get_top_level_directory",
+	"get_tx_details",
+	"get_updater_messages",
+	"parse_payment_uri",
+	"proof_address_from_onion_v3",
+	"retrieve_cleaned_coinbase",
+	"retrieve_detailed_summary_info",
+	"retrieve_foundation_rewards",
+	"retrieve_jobs",
+	"retrieve_mining_rewards",
+	"retrieve_output_lineage",
+	"retrieve_outputs",
+	"retrieve_outputs_page",
+	"retrieve_payment_proof",
+	"retrieve_post_queue",
+	"retrieve_reservations",
+	"retrieve_send_queue",
+	"retrieve_summary_info",
+	"retrieve_txs",
+	"retrieve_txs_by_filter",
+	"retrieve_txs_by_time",
+	"retrieve_txs_page",
+	"rewind_rangeproof",
+	"shard_balances",
+	"verify_slate_messages",
+];
+
+/// Owner API methods that only ask the node or another wallet, without
+/// changing the wallet
+const NODE_QUERY_METHODS: &[&str] = &[
+	"get_kernel_status",
+	"node_height",
+	"node_ping",
+	"node_status",
+	"validate_destination",
+	"verify_payment_proof",
+];
+
+/// Owner API methods a read-only replica serves besides `READ_METHODS`, to
+/// open and close the wallet and look at the node or other wallets
+const REPLICA_METHODS: &[&str] = &[
+	"close_wallet",
+	"get_mnemonic",
+	"init_secure_api",
+	"node_height",
+	"open_wallet",
+	"retrieve_audit_log",
+	"set_active_account",
+	"validate_destination",
+];
 
 lazy_static! {
 	pub static ref EPIC_OWNER_BASIC_REALM: HeaderValue =
 		HeaderValue::from_str("Basic realm=EpicOwnerAPI").unwrap();
-}
-
-fn check_middleware(
-	name: ForeignCheckMiddlewareFn,
-	node_version_info: Option<NodeVersionInfo>,
-	slate: Option<&Slate>,
-) -> Result<(), Error> {
-	match name {
-		// allow coinbases to be built regardless
-		ForeignCheckMiddlewareFn::BuildCoinbase => Ok(()),
-		_ => {
-			let mut bhv = 2;
-			if let Some(n) = node_version_info {
-				bhv = n.block_header_version;
-			}
-			if let Some(s) = slate {
-				if bhv > 3 && s.version_info.block_header_version < EPIC_BLOCK_HEADER_VERSION {
-					Err(ErrorKind::Compatibility(
-						"Incoming Slate is not compatible with this wallet. \
-						 Please upgrade the node or use a different one."
-							.into(),
-					))?;
-				}
-			}
-			Ok(())
-		}
-	}
+	pub static ref EPIC_MERCHANT_BASIC_REALM: HeaderValue =
+		HeaderValue::from_str("Basic realm=EpicMerchantAPI").unwrap();
 }
 
 /// initiate the tor listener
@@ -147,14 +241,144 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	f(&mut Foreign::new(
-		wallet,
-		keychain_mask,
-		Some(check_middleware),
-	))?;
+	let mut api = Foreign::new(wallet, keychain_mask, None);
+	api.set_middleware(default_foreign_middleware());
+	f(&mut api)?;
 	Ok(())
 }
 
+/// The checks run before foreign API calls unless others are given: slates
+/// must be compatible with the node's chain
+pub fn default_foreign_middleware() -> ForeignMiddlewareRegistry {
+	let mut middleware = ForeignMiddlewareRegistry::new();
+	middleware.register(VersionCheck);
+	middleware
+}
+
+/// Lets a service manager follow a listener's startup and stop it
+#[derive(Clone, Debug, Default)]
+pub struct ListenerService {
+	/// Set by the listener once it is accepting connections
+	pub ready: Arc<AtomicBool>,
+	/// Set to stop the listener, letting requests in progress finish
+	pub shutdown: Arc<AtomicBool>,
+}
+
+impl ListenerService {
+	fn shutdown_requested(service: &Option<ListenerService>) -> bool {
+		match service {
+			Some(s) => s.shutdown.load(Ordering::SeqCst),
+			None => false,
+		}
+	}
+}
+
+/// Unix domain socket the Owner API listens on instead of a TCP port
+#[derive(Clone, Debug)]
+pub struct UnixSocketConfig {
+	/// Path of the socket file, replaced if it already exists
+	pub path: String,
+	/// Permissions of the socket file, e.g. `0o600` to limit access to the
+	/// wallet's user
+	pub permissions: u32,
+}
+
+#[cfg(unix)]
+fn serve_unix_socket(router: Router, socket: &UnixSocketConfig) -> Result<(), Error> {
+	use std::fs;
+	use std::os::unix::fs::PermissionsExt;
+	use tokio_uds::UnixListener;
+
+	// a socket file left by an earlier run would make the bind fail
+	let _ = fs::remove_file(&socket.path);
+	let listener = UnixListener::bind(&socket.path).context(ErrorKind::GenericError(format!(
+		"Unable to listen on unix socket {}",
+		socket.path
+	)))?;
+	fs::set_permissions(&socket.path, fs::Permissions::from_mode(socket.permissions)).context(
+		ErrorKind::GenericError(format!("Unable to set permissions of {}", socket.path)),
+	)?;
+	let server = hyper::Server::builder(listener.incoming())
+		.serve(router)
+		.map_err(|e| error!("Owner API unix socket server error: {}", e));
+	warn!("HTTP Owner listener started.");
+	hyper::rt::run(server);
+	Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_unix_socket(_router: Router, _socket: &UnixSocketConfig) -> Result<(), Error> {
+	Err(
+		ErrorKind::GenericError("Unix sockets are not supported on this platform".to_owned())
+			.into(),
+	)
+}
+
+/// How the Owner API listener serves the wallet
+#[derive(Clone)]
+pub struct OwnerListenerConfig {
+	/// Secret callers give with HTTP basic auth, if one is required
+	pub api_secret: Option<String>,
+	/// Certificate and key to serve the API over https with
+	pub tls_config: Option<TLSConfig>,
+	/// Whether to serve the foreign API on the same port
+	pub include_foreign: bool,
+	/// Configuration of the TOR process sending to onion addresses
+	pub tor_config: Option<TorConfig>,
+	/// Confirmations spent outputs need, unless a call overrides it
+	pub min_reorg_safe_confirmations: Option<u64>,
+	/// Largest part of the fee paid when paying an invoice
+	pub max_invoice_fee: Option<u64>,
+	/// Whether the included foreign API receives for a foundation wallet
+	pub foundation_wallet: bool,
+	/// URL notified of transactions received by the included foreign API
+	pub receive_callback_url: Option<String>,
+	/// Origins of browser frontends allowed to call the API
+	pub allowed_origins: Option<Vec<String>>,
+	/// Unix domain socket to listen on instead of a TCP port
+	pub socket: Option<UnixSocketConfig>,
+	/// One-shot token allowing a single `init_wallet_from_env` call
+	pub provisioning_token: Option<ZeroingString>,
+	/// Checks run before calls of the included foreign API
+	pub foreign_middleware: ForeignMiddlewareRegistry,
+	/// Seconds either side of now encrypted requests may be made,
+	/// `DEFAULT_REPLAY_WINDOW_SECS` if not set
+	pub replay_window_secs: Option<u64>,
+	/// How long each method may run
+	pub rpc_timeouts: RpcTimeouts,
+	/// Whether `set_output_status` may override the status of outputs
+	pub allow_output_status_override: bool,
+	/// Whether webhooks may be queued
+	pub allow_webhooks: bool,
+	/// Whether the wallet database is a replica, of which only queries are
+	/// served
+	pub read_only: bool,
+}
+
+impl Default for OwnerListenerConfig {
+	fn default() -> OwnerListenerConfig {
+		OwnerListenerConfig {
+			api_secret: None,
+			tls_config: None,
+			include_foreign: false,
+			tor_config: None,
+			min_reorg_safe_confirmations: None,
+			max_invoice_fee: None,
+			foundation_wallet: false,
+			receive_callback_url: None,
+			allowed_origins: None,
+			socket: None,
+			provisioning_token: None,
+			foreign_middleware: default_foreign_middleware(),
+			replay_window_secs: None,
+			rpc_timeouts: RpcTimeouts::default(),
+			allow_output_status_override: false,
+			allow_webhooks: true,
+			read_only: false,
+		}
+	}
+}
+
 /// Listener version, providing same API but listening for requests on a
 /// port and wrapping the calls
 /// Note keychain mask is only provided here in case the foreign listener is also being used
@@ -163,40 +387,83 @@ pub fn owner_listener<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
 	keychain_mask: Arc<Mutex<Option<SecretKey>>>,
 	addr: &str,
-	api_secret: Option<String>,
-	tls_config: Option<TLSConfig>,
-	owner_api_include_foreign: Option<bool>,
-	tor_config: Option<TorConfig>,
+	config: OwnerListenerConfig,
 ) -> Result<(), Error>
 where
 	L: WalletLCProvider<'static, C, K> + 'static,
 	C: NodeClient + 'static,
 	K: Keychain + 'static,
 {
+	let OwnerListenerConfig {
+		api_secret,
+		tls_config,
+		include_foreign: running_foreign,
+		tor_config,
+		min_reorg_safe_confirmations,
+		max_invoice_fee,
+		foundation_wallet,
+		receive_callback_url,
+		allowed_origins,
+		socket,
+		provisioning_token,
+		foreign_middleware,
+		replay_window_secs,
+		rpc_timeouts,
+		allow_output_status_override,
+		allow_webhooks,
+		read_only,
+	} = config;
 	let mut router = Router::new();
+	if let Some(origins) = allowed_origins {
+		router.add_middleware(Arc::new(CorsMiddleware::new(origins)));
+	}
 	if api_secret.is_some() {
 		let api_basic_auth =
 			"Basic ".to_string() + &to_base64(&("epic:".to_string() + &api_secret.unwrap()));
-		let basic_auth_middleware = Arc::new(BasicAuthMiddleware::new(
+		let basic_auth_middleware = Arc::new(ApiSecretMiddleware::new(
 			api_basic_auth,
-			&EPIC_OWNER_BASIC_REALM,
+			EPIC_OWNER_BASIC_REALM.clone(),
 			Some("/v2/foreign".into()),
 		));
 		router.add_middleware(basic_auth_middleware);
 	}
-	let mut running_foreign = false;
-	if owner_api_include_foreign.unwrap_or(false) {
-		running_foreign = true;
+	if running_foreign && read_only {
+		return Err(ErrorKind::GenericError(
+			"A read-only owner API can't include the foreign API".to_owned(),
+		)
+		.into());
 	}
 
-	let api_handler_v2 = OwnerAPIHandlerV2::new(wallet.clone());
+	let api_handler_v2 = OwnerAPIHandlerV2::new(
+		wallet.clone(),
+		min_reorg_safe_confirmations,
+		max_invoice_fee,
+		rpc_timeouts.clone(),
+		read_only,
+	);
 	let api_handler_v3 = OwnerAPIHandlerV3::new(
 		wallet.clone(),
 		keychain_mask.clone(),
 		tor_config,
+		min_reorg_safe_confirmations,
+		max_invoice_fee,
 		running_foreign,
+		provisioning_token,
+		Duration::from_secs(replay_window_secs.unwrap_or(DEFAULT_REPLAY_WINDOW_SECS)),
+		rpc_timeouts,
+		allow_output_status_override,
+		allow_webhooks,
+		read_only,
 	);
 
+	// a replica leaves queued sends to the wallet writing to the database
+	if read_only {
+		warn!("Owner API is read-only, serving only queries");
+	} else {
+		start_send_queue_thread(api_handler_v3.owner_api.clone(), keychain_mask.clone());
+		start_job_queue_thread(api_handler_v3.owner_api.clone(), keychain_mask.clone());
+	}
+
 	router
 		.add_route("/v2/owner", Arc::new(api_handler_v2))
 		.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
@@ -208,12 +475,30 @@ where
 	// If so configured, add the foreign API to the same port
 	if running_foreign {
 		warn!("Starting HTTP Foreign API on Owner server at {}.", addr);
-		let foreign_api_handler_v2 = ForeignAPIHandlerV2::new(wallet, keychain_mask);
+		let foreign_api_handler_v2 = ForeignAPIHandlerV2::new(
+			wallet,
+			keychain_mask,
+			foundation_wallet,
+			receive_callback_url,
+			foreign_middleware,
+			None,
+			false,
+			None,
+			Arc::new(RwLock::new(None)),
+		);
 		router
 			.add_route("/v2/foreign", Arc::new(foreign_api_handler_v2))
 			.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
 	}
 
+	if let Some(socket) = socket {
+		warn!(
+			"Starting HTTP Owner API server on unix socket {}.",
+			socket.path
+		);
+		return serve_unix_socket(router, &socket);
+	}
+
 	let mut apis = ApiServer::new();
 	warn!("Starting HTTP Owner API server at {}.", addr);
 	let socket_addr: SocketAddr = addr.parse().expect("unable to parse socket address");
@@ -228,20 +513,196 @@ where
 		.map_err(|e| ErrorKind::GenericError(format!("API thread panicked :{:?}", e)).into())
 }
 
+/// Regularly make the sends queued until funds mature, once they have
+fn start_send_queue_thread<L, C, K>(
+	owner_api: Arc<Owner<L, C, K>>,
+	keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+) where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	let _ = thread::Builder::new()
+		.name("wallet-send-queue".to_string())
+		.spawn(move || loop {
+			thread::sleep(SEND_QUEUE_INTERVAL);
+			let mask = keychain_mask.lock().clone();
+			match owner_api.process_send_queue(mask.as_ref()) {
+				Ok(results) => {
+					for r in results {
+						match r.error {
+							Some(e) => warn!("Queued send {} dropped: {}", r.queued.id, e),
+							None => info!("Queued send {} made", r.queued.id),
+						}
+					}
+				}
+				// usually no wallet is open yet
+				Err(e) => debug!("Unable to process the send queue: {}", e),
+			}
+		});
+}
+
+/// Regularly run the queued jobs that are due, such as posts of finalized
+/// transactions and notifications left over from a crash or that failed
+pub fn start_job_queue_thread<L, C, K>(
+	owner_api: Arc<Owner<L, C, K>>,
+	keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+) where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	let _ = thread::Builder::new()
+		.name("wallet-job-queue".to_string())
+		.spawn(move || loop {
+			thread::sleep(JOB_QUEUE_INTERVAL);
+			let mask = keychain_mask.lock().clone();
+			match owner_api.process_jobs(mask.as_ref()) {
+				Ok(results) => {
+					for r in results {
+						match (r.error, r.job.dead) {
+							(Some(e), true) => {
+								error!("Job {} failed for the last time: {}", r.job.id, e)
+							}
+							(Some(e), false) => {
+								warn!("Job {} failed, attempt {}: {}", r.job.id, r.job.attempts, e)
+							}
+							(None, _) => debug!("Job {} done", r.job.id),
+						}
+					}
+				}
+				// usually no wallet is open yet
+				Err(e) => debug!("Unable to process the job queue: {}", e),
+			}
+		});
+}
+
+/// Where the foreign listener fetches the upgrade advisory it passes on to
+/// counterparties
+#[derive(Clone)]
+pub struct VersionAdvisorySource {
+	/// URL the advisory is published at
+	pub url: String,
+	/// Payment proof address the advisory has to be signed by
+	pub signer: DalekPublicKey,
+}
+
+/// Regularly fetch the upgrade advisory, warning when this release is
+/// outdated. The last advisory fetched is kept while fetching fails
+fn start_version_advisory_thread(
+	source: VersionAdvisorySource,
+	advisory: Arc<RwLock<Option<VersionAdvisory>>>,
+) {
+	let _ = thread::Builder::new()
+		.name("wallet-version-advisory".to_string())
+		.spawn(move || loop {
+			match fetch_version_advisory(&source.url, &source.signer) {
+				Ok(a) => {
+					if a.is_outdated(WALLET_VERSION) {
+						warn!(
+							"Upgrade advisory: epic-wallet {} or newer is recommended, running {}{}",
+							a.min_recommended_version,
+							WALLET_VERSION,
+							a.message
+								.as_ref()
+								.map(|m| format!(": {}", m))
+								.unwrap_or_default()
+						);
+					}
+					*advisory.write() = Some(a);
+				}
+				Err(e) => warn!("Unable to fetch the version advisory: {}", e),
+			}
+			thread::sleep(VERSION_ADVISORY_INTERVAL);
+		});
+}
+
+/// How the Foreign API listener serves the wallet
+#[derive(Clone)]
+pub struct ForeignListenerConfig {
+	/// Certificate and key to serve the API over https with
+	pub tls_config: Option<TLSConfig>,
+	/// Where to obtain the certificate with ACME instead, renewing it while
+	/// the listener runs
+	pub acme_config: Option<AcmeConfig>,
+	/// Whether to listen on an onion address as well, through a TOR process
+	pub use_tor: bool,
+	/// Whether received transactions are for a foundation wallet
+	pub foundation_wallet: bool,
+	/// URL notified of each transaction received
+	pub receive_callback_url: Option<String>,
+	/// Origins of browser frontends allowed to call the API
+	pub allowed_origins: Option<Vec<String>>,
+	/// Lets a service manager follow the listener's startup and stop it
+	pub service: Option<ListenerService>,
+	/// Checks run before calls
+	pub middleware: ForeignMiddlewareRegistry,
+	/// Secret the merchant invoice API requires, which is only served if set
+	pub merchant_api_secret: Option<String>,
+	/// Accounts received transactions are spread over
+	pub receive_shards: Option<Vec<String>>,
+	/// Whether to sign responses with the wallet's payment proof address
+	pub sign_responses: bool,
+	/// Part of the fee offered to senders, by adding an input to the
+	/// transactions received
+	pub fee_contribution: Option<FeeContribution>,
+	/// Whether to receive V0 and V1 slates from old wallets
+	pub accept_legacy_slates: bool,
+	/// Where to fetch the upgrade advisory passed on to counterparties
+	pub version_advisory_source: Option<VersionAdvisorySource>,
+}
+
+impl Default for ForeignListenerConfig {
+	fn default() -> ForeignListenerConfig {
+		ForeignListenerConfig {
+			tls_config: None,
+			acme_config: None,
+			use_tor: false,
+			foundation_wallet: false,
+			receive_callback_url: None,
+			allowed_origins: None,
+			service: None,
+			middleware: default_foreign_middleware(),
+			merchant_api_secret: None,
+			receive_shards: None,
+			sign_responses: false,
+			fee_contribution: None,
+			accept_legacy_slates: false,
+			version_advisory_source: None,
+		}
+	}
+}
+
 /// Listener version, providing same API but listening for requests on a
 /// port and wrapping the calls
 pub fn foreign_listener<L, C, K>(
 	wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
 	keychain_mask: Arc<Mutex<Option<SecretKey>>>,
 	addr: &str,
-	tls_config: Option<TLSConfig>,
-	use_tor: bool,
+	config: ForeignListenerConfig,
 ) -> Result<(), Error>
 where
 	L: WalletLCProvider<'static, C, K> + 'static,
 	C: NodeClient + 'static,
 	K: Keychain + 'static,
 {
+	let ForeignListenerConfig {
+		tls_config,
+		acme_config,
+		use_tor,
+		foundation_wallet,
+		receive_callback_url,
+		allowed_origins,
+		service,
+		middleware,
+		merchant_api_secret,
+		receive_shards,
+		sign_responses,
+		fee_contribution,
+		accept_legacy_slates,
+		version_advisory_source,
+	} = config;
+
 	// need to keep in scope while the main listener is running
 	let _tor_process = match use_tor {
 		true => match init_tor_listener(wallet.clone(), keychain_mask.clone(), addr) {
@@ -256,27 +717,129 @@ where
 		false => None,
 	};
 
-	let api_handler_v2 = ForeignAPIHandlerV2::new(wallet, keychain_mask);
-	let mut router = Router::new();
+	let version_advisory = Arc::new(RwLock::new(None));
+	if let Some(source) = version_advisory_source {
+		start_version_advisory_thread(source, version_advisory.clone());
+	}
 
-	router
-		.add_route("/v2/foreign", Arc::new(api_handler_v2))
-		.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
+	// with a certificate from ACME, the listener is restarted whenever the
+	// certificate has been renewed
+	loop {
+		let tls_config = match acme_config {
+			Some(ref a) => {
+				a.ensure_certificate().map_err(|e| {
+					ErrorKind::GenericError(format!("Unable to obtain TLS certificate: {}", e))
+				})?;
+				Some(TLSConfig::new(a.certificate_file(), a.key_file()))
+			}
+			None => tls_config.clone(),
+		};
 
-	let mut apis = ApiServer::new();
-	warn!("Starting HTTP Foreign listener API server at {}.", addr);
-	let socket_addr: SocketAddr = addr.parse().expect("unable to parse socket address");
-	let api_thread =
-		apis.start(socket_addr, router, tls_config)
-			.context(ErrorKind::GenericError(
-				"API thread failed to start".to_string(),
-			))?;
+		let api_handler_v2 = ForeignAPIHandlerV2::new(
+			wallet.clone(),
+			keychain_mask.clone(),
+			foundation_wallet,
+			receive_callback_url.clone(),
+			middleware.clone(),
+			receive_shards.clone(),
+			sign_responses,
+			fee_contribution.clone(),
+			version_advisory.clone(),
+		);
+		let mut router = Router::new();
+		if let Some(ref origins) = allowed_origins {
+			router.add_middleware(Arc::new(CorsMiddleware::new(origins.clone())));
+		}
 
-	warn!("HTTP Foreign listener started.");
+		let api_handler_v2 = Arc::new(api_handler_v2);
+		router
+			.add_route("/v2/foreign", api_handler_v2.clone())
+			.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
+		// per-user deposit paths, tagging what's received through them
+		router
+			.add_route("/v2/foreign/u/*", api_handler_v2.clone())
+			.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
+		// where wallets sending V0 and V1 slates post them
+		if accept_legacy_slates {
+			router
+				.add_route(LEGACY_RECEIVE_PATH, api_handler_v2)
+				.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
+			warn!("Receiving V0 and V1 slates from old wallets.");
+		}
 
-	api_thread
-		.join()
-		.map_err(|e| ErrorKind::GenericError(format!("API thread panicked :{:?}", e)).into())
+		// merchant mode, where the invoice API needs the merchant secret
+		if let Some(ref secret) = merchant_api_secret {
+			let api_basic_auth = "Basic ".to_string() + &to_base64(&format!("epic:{}", secret));
+			// only the invoice API, so the foreign API paths stay open
+			router.add_middleware(Arc::new(ApiSecretMiddleware::for_path(
+				api_basic_auth,
+				EPIC_MERCHANT_BASIC_REALM.clone(),
+				"/invoices".into(),
+			)));
+			let merchant_handler = Arc::new(MerchantAPIHandler::new(
+				wallet.clone(),
+				keychain_mask.clone(),
+			));
+			router
+				.add_route("/invoices", merchant_handler.clone())
+				.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
+			router
+				.add_route("/invoices/*", merchant_handler)
+				.map_err(|_| ErrorKind::GenericError("Router failed to add route".to_string()))?;
+			warn!("Merchant invoice API enabled on the Foreign listener.");
+		}
+
+		let mut apis = ApiServer::new();
+		warn!("Starting HTTP Foreign listener API server at {}.", addr);
+		let socket_addr: SocketAddr = addr.parse().expect("unable to parse socket address");
+		let api_thread =
+			apis.start(socket_addr, router, tls_config)
+				.context(ErrorKind::GenericError(
+					"API thread failed to start".to_string(),
+				))?;
+
+		warn!("HTTP Foreign listener started.");
+		if let Some(ref s) = service {
+			s.ready.store(true, Ordering::SeqCst);
+		}
+
+		if acme_config.is_none() && service.is_none() {
+			return api_thread.join().map_err(|e| {
+				ErrorKind::GenericError(format!("API thread panicked :{:?}", e)).into()
+			});
+		}
+
+		// wait until asked to stop, or the certificate is due for renewal
+		let mut last_renewal_check = Instant::now();
+		loop {
+			thread::sleep(SHUTDOWN_POLL_INTERVAL);
+			if ListenerService::shutdown_requested(&service) {
+				warn!("Stopping HTTP Foreign listener.");
+				apis.stop();
+				return api_thread.join().map_err(|e| {
+					ErrorKind::GenericError(format!("API thread panicked :{:?}", e)).into()
+				});
+			}
+			let acme = match acme_config {
+				Some(ref a) => a,
+				None => continue,
+			};
+			if last_renewal_check.elapsed() < ACME_RENEWAL_CHECK_INTERVAL {
+				continue;
+			}
+			last_renewal_check = Instant::now();
+			match acme.renewal_due() {
+				Ok(true) => break,
+				Ok(false) => {}
+				Err(e) => warn!("Unable to check TLS certificate expiry: {}", e),
+			}
+		}
+		warn!("Renewing TLS certificate, restarting HTTP Foreign listener.");
+		apis.stop();
+		api_thread
+			.join()
+			.map_err(|e| ErrorKind::GenericError(format!("API thread panicked :{:?}", e)))?;
+	}
 }
 
 type WalletResponseFuture = Box<dyn Future<Item = Response<Body>, Error = Error> + Send>;
@@ -290,6 +853,15 @@ where
 {
 	/// Wallet instance
 	pub wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
+	/// Reorg safety policy applied to each Owner API instance
+	pub min_reorg_safe_confirmations: Option<u64>,
+	/// Invoice fee policy applied to each Owner API instance
+	pub max_invoice_fee: Option<u64>,
+	/// How long each method may run
+	pub timeouts: RpcTimeouts,
+	/// Whether only queries are served, as by a replica of a wallet another
+	/// process writes to
+	pub read_only: bool,
 }
 
 impl<L, C, K> OwnerAPIHandlerV2<L, C, K>
@@ -301,8 +873,18 @@ where
 	/// Create a new owner API handler for GET methods
 	pub fn new(
 		wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
+		min_reorg_safe_confirmations: Option<u64>,
+		max_invoice_fee: Option<u64>,
+		timeouts: RpcTimeouts,
+		read_only: bool,
 	) -> OwnerAPIHandlerV2<L, C, K> {
-		OwnerAPIHandlerV2 { wallet }
+		OwnerAPIHandlerV2 {
+			wallet,
+			min_reorg_safe_confirmations,
+			max_invoice_fee,
+			timeouts,
+			read_only,
+		}
 	}
 
 	fn call_api(
@@ -310,9 +892,15 @@ where
 		req: Request<Body>,
 		api: Owner<L, C, K>,
 	) -> Box<dyn Future<Item = serde_json::Value, Error = Error> + Send> {
+		let timeouts = self.timeouts.clone();
+		let read_only = self.read_only;
 		Box::new(parse_body(req).and_then(move |val: serde_json::Value| {
-			let owner_api = &api as &dyn OwnerRpc;
-			match owner_api.handle_request(val) {
+			if read_only && !replica_serves(&val) {
+				return ok(replica_refusal(&val));
+			}
+			let call = val.clone();
+			let reply = timeouts.call(&call, move || (&api as &dyn OwnerRpc).handle_request(val));
+			match reply {
 				MaybeReply::Reply(r) => ok(r),
 				MaybeReply::DontReply => {
 					// Since it's http, we need to return something. We return [] because jsonrpc
@@ -325,6 +913,8 @@ where
 
 	fn handle_post_request(&self, req: Request<Body>) -> WalletResponseFuture {
 		let api = Owner::new(self.wallet.clone());
+		api.set_min_reorg_safe_confirmations(self.min_reorg_safe_confirmations);
+		api.set_max_invoice_fee(self.max_invoice_fee);
 		Box::new(
 			self.call_api(req, api)
 				.and_then(|resp| ok(json_response_pretty(&resp))),
@@ -377,6 +967,271 @@ where
 	/// Whether we're running the foreign API on the same port, and therefore
 	/// have to store the mask in-process
 	pub running_foreign: bool,
+
+	/// Nonces of recent encrypted requests, to refuse replays
+	pub replay_guard: Arc<RequestReplayGuard>,
+
+	/// How long each method may run
+	pub timeouts: RpcTimeouts,
+
+	/// Whether only queries are served, as by a replica of a wallet another
+	/// process writes to
+	pub read_only: bool,
+}
+
+/// Whether a read-only owner API serves a JSON-RPC request: queries of the
+/// wallet's stored data, and opening and closing the wallet
+fn replica_serves(req: &serde_json::Value) -> bool {
+	let method = req["method"].as_str().unwrap_or("");
+	let refresh = req["params"]["refresh_from_node"]
+		.as_bool()
+		.unwrap_or(false);
+	REPLICA_METHODS.contains(&method) || (READ_METHODS.contains(&method) && !refresh)
+}
+
+/// Answer to a request a read-only owner API doesn't serve
+fn replica_refusal(req: &serde_json::Value) -> serde_json::Value {
+	serde_json::json!({
+		"jsonrpc": "2.0",
+		"id": req["id"],
+		"result": {
+			"Err": ErrorKind::ReadOnlyWallet,
+		}
+	})
+}
+
+/// Refuses encrypted owner API requests made outside a window of time, by the
+/// timestamps they carry inside their encryption, and remembers the nonces of
+/// those made with each token until their timestamps leave the window. So a
+/// recorded request can't be sent again, however long after it was made.
+/// Requests made without a token are tracked together.
+///
+/// Clients predating timestamps send requests without one. Those are only
+/// checked by their nonces, which are remembered for the window from when
+/// they're received, so they can't be replayed within it
+pub struct RequestReplayGuard {
+	window: Duration,
+	/// Nonces seen with each token, and when they can be forgotten, in
+	/// seconds since the epoch
+	seen: Mutex<HashMap<String, HashMap<String, i64>>>,
+}
+
+impl RequestReplayGuard {
+	/// Refuse requests made more than the window from now. A window of 0
+	/// checks none
+	pub fn new(window: Duration) -> RequestReplayGuard {
+		RequestReplayGuard {
+			window,
+			seen: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Record the nonce of a request made with a token at `timestamp`, if
+	/// it has one, failing with the reason it's refused if it was made
+	/// outside the window, or its nonce was already used with that token
+	pub fn check(&self, token: &str, nonce: &str, timestamp: Option<i64>) -> Result<(), String> {
+		self.check_at(token, nonce, timestamp, Utc::now().timestamp())
+	}
+
+	/// [check](#method.check) as of `now`, in seconds since the epoch
+	pub fn check_at(
+		&self,
+		token: &str,
+		nonce: &str,
+		timestamp: Option<i64>,
+		now: i64,
+	) -> Result<(), String> {
+		if self.window == Duration::from_secs(0) {
+			return Ok(());
+		}
+		let window = self.window.as_secs() as i64;
+		// remembered until its timestamp leaves the window, so it's refused
+		// by its timestamp once it's forgotten
+		let expiry = match timestamp {
+			Some(t) if (now - t).abs() > window => {
+				return Err(format!(
+					"Request made at {}, more than {} seconds from the wallet's time of {}",
+					t, window, now
+				));
+			}
+			Some(t) => t + window,
+			None => now + window,
+		};
+		let mut seen = self.seen.lock();
+		for nonces in seen.values_mut() {
+			nonces.retain(|_, expiry| *expiry >= now);
+		}
+		seen.retain(|_, nonces| !nonces.is_empty());
+		let nonces = seen.entry(token.to_owned()).or_insert_with(HashMap::new);
+		if nonces.contains_key(nonce) {
+			return Err("Request replayed: its nonce was already used with this token".to_owned());
+		}
+		nonces.insert(nonce.to_owned(), expiry);
+		Ok(())
+	}
+}
+
+/// How long each owner API method may run. A call running past its timeout
+/// is answered with an error, and left to finish on its own thread, releasing
+/// the wallet and any other locks it holds once it returns. Meanwhile calls
+/// needing the same locks time out in turn rather than hang. Requests to the
+/// node have their own timeout (`node_request_timeout_secs`), so a call
+/// abandoned on a hung node still returns, and releases the wallet.
+///
+/// Only reads and queries of the node have a timeout unless one is configured
+/// for a method, as a call changing the wallet carries on after timing out,
+/// and may still lock outputs or post a transaction.
+#[derive(Clone)]
+pub struct RpcTimeouts {
+	/// Timeout of calls only reading the wallet's stored data
+	pub read: Option<Duration>,
+	/// Timeout of reads refreshing from the node first, and of queries of
+	/// the node or another wallet
+	pub node: Option<Duration>,
+	/// Timeouts of particular methods, overriding the above. `None` for no
+	/// timeout
+	pub methods: HashMap<String, Option<Duration>>,
+	abandoned: Arc<AtomicUsize>,
+}
+
+impl Default for RpcTimeouts {
+	fn default() -> RpcTimeouts {
+		RpcTimeouts::new(
+			Some(Duration::from_secs(DEFAULT_READ_TIMEOUT_SECS)),
+			Some(Duration::from_secs(DEFAULT_NODE_TIMEOUT_SECS)),
+			HashMap::new(),
+		)
+	}
+}
+
+impl RpcTimeouts {
+	/// Timeouts for reads, other calls and particular methods
+	pub fn new(
+		read: Option<Duration>,
+		node: Option<Duration>,
+		methods: HashMap<String, Option<Duration>>,
+	) -> RpcTimeouts {
+		RpcTimeouts {
+			read,
+			node,
+			methods,
+			abandoned: Arc::new(AtomicUsize::new(0)),
+		}
+	}
+
+	/// Timeout of a JSON-RPC request, if it has one
+	pub fn timeout(&self, req: &serde_json::Value) -> Option<Duration> {
+		let method = req["method"].as_str().unwrap_or("");
+		if let Some(t) = self.methods.get(method) {
+			return *t;
+		}
+		let refresh = req["params"]["refresh_from_node"]
+			.as_bool()
+			.unwrap_or(false);
+		if READ_METHODS.contains(&method) {
+			return match refresh {
+				false => self.read,
+				true => self.node,
+			};
+		}
+		match NODE_QUERY_METHODS.contains(&method) {
+			true => self.node,
+			false => None,
+		}
+	}
+
+	/// Number of calls that timed out and are still running
+	pub fn abandoned_calls(&self) -> usize {
+		self.abandoned.load(Ordering::SeqCst)
+	}
+
+	/// Handle a request with `f` on its own thread, answering with an error
+	/// if it runs past its timeout
+	pub fn call<F>(&self, req: &serde_json::Value, f: F) -> MaybeReply
+	where
+		F: FnOnce() -> MaybeReply + Send + 'static,
+	{
+		let timeout = match self.timeout(req) {
+			Some(t) => t,
+			None => return f(),
+		};
+		let method = req["method"].as_str().unwrap_or("").to_owned();
+		if self.abandoned_calls() >= MAX_ABANDONED_CALLS {
+			return timeout_reply(
+				req,
+				&format!(
+					"{} refused: {} earlier calls are still running after timing out",
+					method,
+					self.abandoned_calls()
+				),
+			);
+		}
+
+		// whether the call was abandoned, checked and set under the lock so
+		// a call finishing as it times out is counted exactly once
+		let gave_up = Arc::new(Mutex::new(false));
+		let (tx, rx) = mpsc::channel();
+		let thread_gave_up = gave_up.clone();
+		let abandoned = self.abandoned.clone();
+		let thread_method = method.clone();
+		let spawned = thread::Builder::new()
+			.name(format!("owner-rpc-{}", method))
+			.spawn(move || {
+				let reply = f();
+				let gave_up = thread_gave_up.lock();
+				if *gave_up {
+					abandoned.fetch_sub(1, Ordering::SeqCst);
+					warn!("{} finished after timing out", thread_method);
+				} else {
+					let _ = tx.send(reply);
+				}
+			});
+		if let Err(e) = spawned {
+			return timeout_reply(req, &format!("{} couldn't be started: {}", method, e));
+		}
+
+		match rx.recv_timeout(timeout) {
+			Ok(reply) => reply,
+			Err(mpsc::RecvTimeoutError::Disconnected) => {
+				timeout_reply(req, &format!("{} failed without answering", method))
+			}
+			Err(mpsc::RecvTimeoutError::Timeout) => {
+				let mut gave_up = gave_up.lock();
+				if let Ok(reply) = rx.try_recv() {
+					return reply;
+				}
+				*gave_up = true;
+				self.abandoned.fetch_add(1, Ordering::SeqCst);
+				warn!(
+					"{} timed out after {}s, leaving it to finish",
+					method,
+					timeout.as_secs()
+				);
+				let read = READ_METHODS.contains(&method.as_str())
+					|| NODE_QUERY_METHODS.contains(&method.as_str());
+				let message = match read {
+					true => format!("{} timed out after {}s", method, timeout.as_secs()),
+					false => format!(
+						"{} timed out after {}s, and may still complete",
+						method,
+						timeout.as_secs()
+					),
+				};
+				timeout_reply(req, &message)
+			}
+		}
+	}
+}
+
+fn timeout_reply(req: &serde_json::Value, message: &str) -> MaybeReply {
+	MaybeReply::Reply(serde_json::json!({
+		"jsonrpc": "2.0",
+		"id": req["id"],
+		"error": {
+			"message": message,
+			"code": RPC_TIMEOUT_ERROR_CODE
+		}
+	}))
 }
 
 pub struct OwnerV3Helpers;
@@ -394,11 +1249,12 @@ impl OwnerV3Helpers {
 		}
 	}
 
-	/// Checks whether a request is to open the wallet
+	/// Checks whether a request is to open the wallet, or otherwise hands out a
+	/// new keychain mask
 	pub fn is_open_wallet(val: &serde_json::Value) -> bool {
 		if let Some(m) = val["method"].as_str() {
 			match m {
-				"open_wallet" => true,
+				"open_wallet" | "init_wallet_from_env" | "rotate_keychain_mask" => true,
 				_ => false,
 			}
 		} else {
@@ -428,11 +1284,12 @@ impl OwnerV3Helpers {
 	/// Otherwise return an error value
 	pub fn check_encryption_started(
 		key: Arc<Mutex<Option<SecretKey>>>,
+		id: RpcId,
 	) -> Result<(), serde_json::Value> {
 		match OwnerV3Helpers::encryption_enabled(key) {
 			true => Ok(()),
 			false => Err(EncryptionErrorResponse::new(
-				RpcId::Integer(1),
+				id,
 				-32001,
 				"Encryption must be enabled. Please call 'init_secure_api` first",
 			)
@@ -448,6 +1305,9 @@ impl OwnerV3Helpers {
 	) {
 		if let Some(_) = val["result"]["Ok"].as_str() {
 			let mut share_key_ref = key.lock();
+			if let Some(k) = share_key_ref.as_mut() {
+				zeroize_secret_key(k);
+			}
 			*share_key_ref = new_key;
 		}
 	}
@@ -467,10 +1327,33 @@ impl OwnerV3Helpers {
 			};
 
 			let mut shared_mask_ref = mask.lock();
+			if let Some(m) = shared_mask_ref.as_mut() {
+				zeroize_secret_key(m);
+			}
 			*shared_mask_ref = Some(sk);
 		}
 	}
 
+	/// Refuse a decrypted request made outside the replay window, or if the
+	/// nonce of its encrypted envelope was already used with the same token.
+	/// Its timestamp, if it has one, is taken off the request before it's
+	/// handled
+	pub fn check_replay(
+		guard: &RequestReplayGuard,
+		id: RpcId,
+		nonce: &str,
+		req: &mut serde_json::Value,
+	) -> Result<(), serde_json::Value> {
+		let timestamp = req
+			.as_object_mut()
+			.and_then(|r| r.remove(REQUEST_TIMESTAMP))
+			.and_then(|t| t.as_i64());
+		let token = req["params"]["token"].as_str().unwrap_or("");
+		guard
+			.check(token, nonce, timestamp)
+			.map_err(|e| EncryptionErrorResponse::new(id, -32004, &e).as_json_value())
+	}
+
 	/// Decrypt an encrypted request
 	pub fn decrypt_request(
 		key: Arc<Mutex<Option<SecretKey>>>,
@@ -480,7 +1363,7 @@ impl OwnerV3Helpers {
 		let shared_key = share_key_ref.as_ref().unwrap();
 		let enc_req: EncryptedRequest = serde_json::from_value(req.clone()).map_err(|e| {
 			EncryptionErrorResponse::new(
-				RpcId::Integer(1),
+				RpcId::from_request(req),
 				-32002,
 				&format!("Encrypted request format error: {}", e),
 			)
@@ -489,7 +1372,7 @@ impl OwnerV3Helpers {
 		let id = enc_req.id.clone();
 		let res = enc_req.decrypt(&shared_key).map_err(|e| {
 			EncryptionErrorResponse::new(
-				RpcId::Integer(1),
+				id.clone(),
 				-32002,
 				&format!("Decryption error: {}", e.kind()),
 			)
@@ -506,9 +1389,9 @@ impl OwnerV3Helpers {
 	) -> Result<serde_json::Value, serde_json::Value> {
 		let share_key_ref = key.lock();
 		let shared_key = share_key_ref.as_ref().unwrap();
-		let enc_res = EncryptedResponse::from_json(id, res, &shared_key).map_err(|e| {
+		let enc_res = EncryptedResponse::from_json(id.clone(), res, &shared_key).map_err(|e| {
 			EncryptionErrorResponse::new(
-				RpcId::Integer(1),
+				id.clone(),
 				-32003,
 				&format!("EncryptionError: {}", e.kind()),
 			)
@@ -516,7 +1399,7 @@ impl OwnerV3Helpers {
 		})?;
 		let res = enc_res.as_json_value().map_err(|e| {
 			EncryptionErrorResponse::new(
-				RpcId::Integer(1),
+				id,
 				-32002,
 				&format!("Encrypted response format error: {}", e),
 			)
@@ -605,10 +1488,23 @@ where
 		wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
 		keychain_mask: Arc<Mutex<Option<SecretKey>>>,
 		tor_config: Option<TorConfig>,
+		min_reorg_safe_confirmations: Option<u64>,
+		max_invoice_fee: Option<u64>,
 		running_foreign: bool,
+		provisioning_token: Option<ZeroingString>,
+		replay_window: Duration,
+		timeouts: RpcTimeouts,
+		allow_output_status_override: bool,
+		allow_webhooks: bool,
+		read_only: bool,
 	) -> OwnerAPIHandlerV3<L, C, K> {
 		let owner_api = Owner::new(wallet.clone());
 		owner_api.set_tor_config(tor_config);
+		owner_api.set_min_reorg_safe_confirmations(min_reorg_safe_confirmations);
+		owner_api.set_max_invoice_fee(max_invoice_fee);
+		owner_api.set_provisioning_token(provisioning_token);
+		owner_api.set_allow_output_status_override(allow_output_status_override);
+		owner_api.set_allow_webhooks(allow_webhooks);
 		let owner_api = Arc::new(owner_api);
 		OwnerAPIHandlerV3 {
 			wallet,
@@ -616,6 +1512,9 @@ where
 			shared_key: Arc::new(Mutex::new(None)),
 			keychain_mask: keychain_mask,
 			running_foreign,
+			replay_guard: Arc::new(RequestReplayGuard::new(replay_window)),
+			timeouts,
+			read_only,
 		}
 	}
 
@@ -627,16 +1526,21 @@ where
 		let key = self.shared_key.clone();
 		let mask = self.keychain_mask.clone();
 		let running_foreign = self.running_foreign;
+		let replay_guard = self.replay_guard.clone();
+		let timeouts = self.timeouts.clone();
+		let read_only = self.read_only;
 		Box::new(parse_body(req).and_then(move |val: serde_json::Value| {
 			let mut val = val;
-			let owner_api_s = &*api as &dyn OwnerRpcS;
 			let mut is_init_secure_api = OwnerV3Helpers::is_init_secure_api(&val);
 			let mut was_encrypted = false;
 			let mut encrypted_req_id = RpcId::Integer(0);
 			if !is_init_secure_api {
-				if let Err(v) = OwnerV3Helpers::check_encryption_started(key.clone()) {
+				let id = RpcId::from_request(&val);
+				if let Err(v) = OwnerV3Helpers::check_encryption_started(key.clone(), id) {
 					return ok(v);
 				}
+				// hex nonces differing only in case are the same nonce
+				let nonce = val["params"]["nonce"].as_str().unwrap_or("").to_lowercase();
 				let res = OwnerV3Helpers::decrypt_request(key.clone(), &val);
 				match res {
 					Err(e) => return ok(e),
@@ -645,13 +1549,29 @@ where
 						val = v.1;
 					}
 				}
+				if let Err(e) = OwnerV3Helpers::check_replay(
+					&replay_guard,
+					encrypted_req_id.clone(),
+					&nonce,
+					&mut val,
+				) {
+					return ok(e);
+				}
 				was_encrypted = true;
 			}
 			// check again, in case it was an encrypted call to init_secure_api
 			is_init_secure_api = OwnerV3Helpers::is_init_secure_api(&val);
 			// also need to intercept open/close wallet requests
 			let is_open_wallet = OwnerV3Helpers::is_open_wallet(&val);
-			match owner_api_s.handle_request(val) {
+			let call = val.clone();
+			let call_api = api.clone();
+			let reply = match read_only && !replica_serves(&call) {
+				true => MaybeReply::Reply(replica_refusal(&call)),
+				false => timeouts.call(&call, move || {
+					(&*call_api as &dyn OwnerRpcS).handle_request(val)
+				}),
+			};
+			match reply {
 				MaybeReply::Reply(mut r) => {
 					let (_was_error, unencrypted_intercept) =
 						OwnerV3Helpers::check_error_response(&r.clone());
@@ -729,6 +1649,21 @@ where
 	pub wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
 	/// Keychain mask
 	pub keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+	/// Whether this is the foundation wallet
+	pub foundation_wallet: bool,
+	/// URL notified of each transaction received
+	pub receive_callback_url: Option<String>,
+	/// Checks run before each call
+	pub middleware: ForeignMiddlewareRegistry,
+	/// Accounts receives are spread across, if any
+	pub receive_shards: Option<Vec<String>>,
+	/// Whether responses are signed with the wallet's payment proof address
+	pub sign_responses: bool,
+	/// What's offered when asked to contribute an input to a receive, if
+	/// inputs are contributed
+	pub fee_contribution: Option<FeeContribution>,
+	/// Upgrade advisory passed on to counterparties, as last fetched
+	pub version_advisory: Arc<RwLock<Option<VersionAdvisory>>>,
 }
 
 impl<L, C, K> ForeignAPIHandlerV2<L, C, K>
@@ -741,37 +1676,138 @@ where
 	pub fn new(
 		wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
 		keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+		foundation_wallet: bool,
+		receive_callback_url: Option<String>,
+		middleware: ForeignMiddlewareRegistry,
+		receive_shards: Option<Vec<String>>,
+		sign_responses: bool,
+		fee_contribution: Option<FeeContribution>,
+		version_advisory: Arc<RwLock<Option<VersionAdvisory>>>,
 	) -> ForeignAPIHandlerV2<L, C, K> {
 		ForeignAPIHandlerV2 {
 			wallet,
 			keychain_mask,
+			foundation_wallet,
+			receive_callback_url,
+			middleware,
+			receive_shards,
+			sign_responses,
+			fee_contribution,
+			version_advisory,
 		}
 	}
 
+	/// Answer a request, returning its body along with the reply, and the
+	/// API for signing the reply
 	fn call_api(
 		&self,
 		req: Request<Body>,
 		api: Foreign<'static, L, C, K>,
-	) -> Box<dyn Future<Item = serde_json::Value, Error = Error> + Send> {
-		Box::new(parse_body(req).and_then(move |val: serde_json::Value| {
-			let foreign_api = &api as &dyn ForeignRpc;
-			match foreign_api.handle_request(val) {
-				MaybeReply::Reply(r) => ok({ r }),
+	) -> Box<
+		dyn Future<Item = (Vec<u8>, serde_json::Value, Foreign<'static, L, C, K>), Error = Error>
+			+ Send,
+	> {
+		Box::new(read_body(req).and_then(move |body| {
+			let val: serde_json::Value = match serde_json::from_slice(&body) {
+				Ok(v) => v,
+				Err(e) => {
+					return err(
+						ErrorKind::GenericError(format!("Invalid request body: {}", e)).into(),
+					)
+				}
+			};
+			let reply = match (&api as &dyn ForeignRpc).handle_request(val) {
+				MaybeReply::Reply(r) => r,
 				MaybeReply::DontReply => {
 					// Since it's http, we need to return something. We return [] because jsonrpc
 					// clients will parse it as an empty batch response.
-					ok(serde_json::json!([]))
+					serde_json::json!([])
 				}
+			};
+			ok((body, reply, api))
+		}))
+	}
+
+	/// The foreign API answering a request, set up as configured
+	fn foreign_api(&self, req: &Request<Body>) -> Result<Foreign<'static, L, C, K>, Error> {
+		let mask = self.keychain_mask.lock();
+		let mut api = Foreign::new(self.wallet.clone(), mask.clone(), None);
+		api.set_middleware(self.middleware.clone());
+		api.set_foundation_wallet(self.foundation_wallet);
+		api.set_receive_callback(self.receive_callback_url.clone());
+		api.set_receive_shards(self.receive_shards.clone());
+		api.set_fee_contribution(self.fee_contribution.clone());
+		api.set_version_advisory(self.version_advisory.read().clone());
+		if let Some(tag) = deposit_tag(req.uri().path()) {
+			api.set_deposit_tag(Some(tag.to_owned()))?;
+		}
+		Ok(api)
+	}
+
+	/// Receive a slate posted by an old wallet, which sends V0 and V1
+	/// slates as the body of the request and expects the slate back in the
+	/// same version, rather than calling `receive_tx` over JSON-RPC
+	fn handle_legacy_receive(&self, req: Request<Body>) -> WalletResponseFuture {
+		let api = match self.foreign_api(&req) {
+			Ok(a) => a,
+			Err(e) => return Box::new(err(e)),
+		};
+		Box::new(read_body(req).and_then(move |body| {
+			let res = String::from_utf8(body)
+				.map_err(|_| ErrorKind::SlateDeser.into())
+				.and_then(|s| Slate::deserialize_legacy(&s))
+				.and_then(|slate| api.receive_tx(&slate, None, None))
+				.and_then(|slate| {
+					serde_json::to_string(&slate).map_err(|e| {
+						ErrorKind::GenericError(format!("Unable to serialize slate: {}", e)).into()
+					})
+				});
+			match res {
+				Ok(json) => ok(response(StatusCode::OK, json)),
+				Err(e) => err(e),
 			}
 		}))
 	}
 
 	fn handle_post_request(&self, req: Request<Body>) -> WalletResponseFuture {
-		let mask = self.keychain_mask.lock();
-		let api = Foreign::new(self.wallet.clone(), mask.clone(), Some(check_middleware));
+		let api = match self.foreign_api(&req) {
+			Ok(a) => a,
+			Err(e) => return Box::new(err(e)),
+		};
+		let sign_responses = self.sign_responses;
 		Box::new(
 			self.call_api(req, api)
-				.and_then(|resp| ok(json_response_pretty(&resp))),
+				.and_then(move |(request, reply, api)| {
+					let json = match serde_json::to_string_pretty(&reply) {
+						Ok(j) => j,
+						Err(_) => return ok(response(StatusCode::INTERNAL_SERVER_ERROR, "")),
+					};
+					if !sign_responses {
+						return ok(response(StatusCode::OK, json));
+					}
+					let (proof_address, signature) =
+						match api.sign_response(&request, json.as_bytes()) {
+							Ok(s) => s,
+							Err(e) => return err(e),
+						};
+					let proof_address = match address::onion_v3_from_pubkey(&proof_address) {
+						Ok(a) => a,
+						Err(e) => return err(e),
+					};
+					let mut resp = response(StatusCode::OK, json);
+					let headers = resp.headers_mut();
+					for (name, value) in vec![
+						(RESPONSE_ADDRESS_HEADER, proof_address),
+						(
+							RESPONSE_SIGNATURE_HEADER,
+							to_hex(signature.to_bytes().to_vec()),
+						),
+					] {
+						// both are plain ascii
+						headers.insert(name, HeaderValue::from_str(&value).unwrap());
+					}
+					ok(resp)
+				}),
 		)
 	}
 }
@@ -783,21 +1819,295 @@ where
 	K: Keychain + 'static,
 {
 	fn post(&self, req: Request<Body>) -> ResponseFuture {
+		let res = match req.uri().path() {
+			LEGACY_RECEIVE_PATH => self.handle_legacy_receive(req),
+			_ => self.handle_post_request(req),
+		};
+		Box::new(res.and_then(|r| ok(r)).or_else(|e| {
+			error!("Request Error: {:?}", e);
+			ok(create_error_response(e))
+		}))
+	}
+
+	fn options(&self, _req: Request<Body>) -> ResponseFuture {
+		Box::new(ok(create_ok_response("{}")))
+	}
+}
+
+/// The deposit tag of a per-user foreign API path, `/v2/foreign/u/<tag>`
+fn deposit_tag(path: &str) -> Option<&str> {
+	let tag = path.trim_end_matches('/').splitn(5, '/').nth(4)?;
+	match path.starts_with("/v2/foreign/u/") {
+		true => Some(tag),
+		false => None,
+	}
+}
+
+/// API handler for the merchant invoice API, letting e-commerce plugins
+/// issue invoices with `POST /invoices` and check whether they've been paid
+/// with `GET /invoices/<id>`, without handling slates themselves
+pub struct MerchantAPIHandler<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K>,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	/// Wallet instance
+	pub wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
+	/// Keychain mask
+	pub keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+}
+
+impl<L, C, K> MerchantAPIHandler<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	/// Create a new merchant API handler
+	pub fn new(
+		wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K> + 'static>>>,
+		keychain_mask: Arc<Mutex<Option<SecretKey>>>,
+	) -> MerchantAPIHandler<L, C, K> {
+		MerchantAPIHandler {
+			wallet,
+			keychain_mask,
+		}
+	}
+
+	fn create_invoice(&self, val: serde_json::Value) -> Result<Response<Body>, Error> {
+		// amounts in freemans, as a number or a string
+		let amount = match &val["amount"] {
+			serde_json::Value::Number(n) => n.as_u64(),
+			serde_json::Value::String(a) => a.parse().ok(),
+			_ => None,
+		};
+		let amount = match amount {
+			Some(a) => a,
+			None => {
+				return Ok(response(
+					StatusCode::BAD_REQUEST,
+					"amount in freemans required",
+				))
+			}
+		};
+		let memo = val["memo"].as_str().map(|m| m.to_owned());
+
+		let mask = self.keychain_mask.lock();
+		let api = Owner::new(self.wallet.clone());
+		match api.create_merchant_invoice((*mask).as_ref(), amount, memo) {
+			Ok(invoice) => Ok(json_response_pretty(&invoice)),
+			Err(e) => match e.kind() {
+				ErrorKind::Amount(_) => Ok(response(StatusCode::BAD_REQUEST, format!("{}", e))),
+				_ => Err(e),
+			},
+		}
+	}
+
+	fn get_invoice(&self, req: &Request<Body>) -> Result<Response<Body>, Error> {
+		let id = req.uri().path().trim_end_matches('/').rsplit('/').next();
+		let id = match id.and_then(|i| Uuid::parse_str(i).ok()) {
+			Some(id) => id,
+			None => return Ok(response(StatusCode::BAD_REQUEST, "invalid invoice id")),
+		};
+		let mask = self.keychain_mask.lock();
+		let api = Owner::new(self.wallet.clone());
+		match api.retrieve_merchant_invoice((*mask).as_ref(), true, id) {
+			Ok(invoice) => Ok(json_response_pretty(&invoice)),
+			Err(e) => match e.kind() {
+				ErrorKind::TransactionDoesntExist(_) => {
+					Ok(response(StatusCode::NOT_FOUND, "invoice not found"))
+				}
+				_ => Err(e),
+			},
+		}
+	}
+}
+
+impl<L, C, K> api::Handler for MerchantAPIHandler<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	fn get(&self, req: Request<Body>) -> ResponseFuture {
+		match self.get_invoice(&req) {
+			Ok(r) => Box::new(ok(r)),
+			Err(e) => {
+				error!("Request Error: {:?}", e);
+				Box::new(ok(create_error_response(e)))
+			}
+		}
+	}
+
+	fn post(&self, req: Request<Body>) -> ResponseFuture {
+		let handler = MerchantAPIHandler::new(self.wallet.clone(), self.keychain_mask.clone());
 		Box::new(
-			self.handle_post_request(req)
-				.and_then(|r| ok(r))
+			parse_body(req)
+				.and_then(move |val| handler.create_invoice(val))
 				.or_else(|e| {
 					error!("Request Error: {:?}", e);
 					ok(create_error_response(e))
 				}),
 		)
 	}
+}
 
-	fn options(&self, _req: Request<Body>) -> ResponseFuture {
-		Box::new(ok(create_ok_response("{}")))
+/// Lets browser frontends served from the allowed origins call the API,
+/// adding CORS headers to the responses and answering preflight requests.
+/// Requests from any other origin are refused; requests without an `Origin`
+/// header, which browsers always send cross-origin, are passed through
+pub struct CorsMiddleware {
+	allowed_origins: Vec<String>,
+}
+
+impl CorsMiddleware {
+	/// Create a new middleware allowing the given origins, such as
+	/// `http://localhost:8080`. `*` allows any origin
+	pub fn new(allowed_origins: Vec<String>) -> CorsMiddleware {
+		CorsMiddleware { allowed_origins }
+	}
+
+	fn allows(&self, origin: &HeaderValue) -> bool {
+		match origin.to_str() {
+			Ok(o) => self.allowed_origins.iter().any(|a| a == "*" || a == o),
+			Err(_) => false,
+		}
 	}
 }
 
+impl api::Handler for CorsMiddleware {
+	fn call(
+		&self,
+		req: Request<Body>,
+		mut handlers: Box<dyn Iterator<Item = api::HandlerObj>>,
+	) -> ResponseFuture {
+		let next_handler = match handlers.next() {
+			Some(h) => h,
+			None => {
+				return Box::new(ok(response(
+					StatusCode::INTERNAL_SERVER_ERROR,
+					"no handler found",
+				)))
+			}
+		};
+		let origin = match req.headers().get(hyper::header::ORIGIN) {
+			Some(o) => o.clone(),
+			None => return next_handler.call(req, handlers),
+		};
+		if !self.allows(&origin) {
+			warn!("Refused API request from origin {:?}", origin);
+			return Box::new(ok(response(StatusCode::FORBIDDEN, "Origin not allowed")));
+		}
+		if req.method() == hyper::Method::OPTIONS {
+			return Box::new(ok(add_cors_headers(create_ok_response("{}"), origin)));
+		}
+		Box::new(
+			next_handler
+				.call(req, handlers)
+				.map(move |resp| add_cors_headers(resp, origin)),
+		)
+	}
+}
+
+/// Refuses requests without the API secret, given as basic authorization,
+/// other than preflight requests and those to the paths left open. The
+/// secret is compared in constant time, so timing doesn't give it away
+pub struct ApiSecretMiddleware {
+	api_basic_auth: String,
+	basic_realm: HeaderValue,
+	ignore_uri: Option<String>,
+	path_prefix: Option<String>,
+}
+
+impl ApiSecretMiddleware {
+	/// Create a new middleware expecting the given `Authorization` header
+	pub fn new(
+		api_basic_auth: String,
+		basic_realm: HeaderValue,
+		ignore_uri: Option<String>,
+	) -> ApiSecretMiddleware {
+		ApiSecretMiddleware {
+			api_basic_auth,
+			basic_realm,
+			ignore_uri,
+			path_prefix: None,
+		}
+	}
+
+	/// Create a new middleware expecting the given `Authorization` header
+	/// only on paths starting with `path_prefix`, leaving the others open
+	pub fn for_path(
+		api_basic_auth: String,
+		basic_realm: HeaderValue,
+		path_prefix: String,
+	) -> ApiSecretMiddleware {
+		ApiSecretMiddleware {
+			api_basic_auth,
+			basic_realm,
+			ignore_uri: None,
+			path_prefix: Some(path_prefix),
+		}
+	}
+
+	fn allows(&self, req: &Request<Body>) -> bool {
+		let path = req.uri().path();
+		if req.method() == hyper::Method::OPTIONS
+			|| self.ignore_uri.as_ref().map(|u| u.as_str()) == Some(path)
+			|| self
+				.path_prefix
+				.as_ref()
+				.map_or(false, |p| !path.starts_with(p.as_str()))
+		{
+			return true;
+		}
+		match req.headers().get(hyper::header::AUTHORIZATION) {
+			Some(a) => ct_eq(a.as_bytes(), self.api_basic_auth.as_bytes()),
+			None => false,
+		}
+	}
+}
+
+impl api::Handler for ApiSecretMiddleware {
+	fn call(
+		&self,
+		req: Request<Body>,
+		mut handlers: Box<dyn Iterator<Item = api::HandlerObj>>,
+	) -> ResponseFuture {
+		let next_handler = match handlers.next() {
+			Some(h) => h,
+			None => {
+				return Box::new(ok(response(
+					StatusCode::INTERNAL_SERVER_ERROR,
+					"no handler found",
+				)))
+			}
+		};
+		if self.allows(&req) {
+			return next_handler.call(req, handlers);
+		}
+		let mut resp = response(StatusCode::UNAUTHORIZED, "unauthorized");
+		resp.headers_mut()
+			.insert(hyper::header::WWW_AUTHENTICATE, self.basic_realm.clone());
+		Box::new(ok(resp))
+	}
+}
+
+fn add_cors_headers(mut resp: Response<Body>, origin: HeaderValue) -> Response<Body> {
+	let headers = resp.headers_mut();
+	headers.insert(hyper::header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+	headers.insert(
+		hyper::header::ACCESS_CONTROL_ALLOW_HEADERS,
+		HeaderValue::from_static("Content-Type, Authorization"),
+	);
+	headers.insert(
+		hyper::header::ACCESS_CONTROL_ALLOW_METHODS,
+		HeaderValue::from_static("POST, OPTIONS"),
+	);
+	headers.insert(hyper::header::VARY, HeaderValue::from_static("Origin"));
+	resp
+}
+
 // Utility to serialize a struct into JSON and produce a sensible Response
 // out of it.
 fn _json_response<T>(s: &T) -> Response<Body>
@@ -824,11 +2134,6 @@ where
 fn create_error_response(e: Error) -> Response<Body> {
 	Response::builder()
 		.status(StatusCode::INTERNAL_SERVER_ERROR)
-		.header("access-control-allow-origin", "*")
-		.header(
-			"access-control-allow-headers",
-			"Content-Type, Authorization",
-		)
 		.body(format!("{}", e).into())
 		.unwrap()
 }
@@ -836,11 +2141,6 @@ fn create_error_response(e: Error) -> Response<Body> {
 fn create_ok_response(json: &str) -> Response<Body> {
 	Response::builder()
 		.status(StatusCode::OK)
-		.header("access-control-allow-origin", "*")
-		.header(
-			"access-control-allow-headers",
-			"Content-Type, Authorization",
-		)
 		.header(hyper::header::CONTENT_TYPE, "application/json")
 		.body(json.to_string().into())
 		.unwrap()
@@ -853,13 +2153,7 @@ fn create_ok_response(json: &str) -> Response<Body> {
 fn response<T: Into<Body>>(status: StatusCode, text: T) -> Response<Body> {
 	let mut builder = &mut Response::builder();
 
-	builder = builder
-		.status(status)
-		.header("access-control-allow-origin", "*")
-		.header(
-			"access-control-allow-headers",
-			"Content-Type, Authorization",
-		);
+	builder = builder.status(status);
 
 	if status == StatusCode::OK {
 		builder = builder.header(hyper::header::CONTENT_TYPE, "application/json");
@@ -868,19 +2162,23 @@ fn response<T: Into<Body>>(status: StatusCode, text: T) -> Response<Body> {
 	builder.body(text.into()).unwrap()
 }
 
+fn read_body(req: Request<Body>) -> Box<dyn Future<Item = Vec<u8>, Error = Error> + Send> {
+	Box::new(
+		req.into_body()
+			.concat2()
+			.map_err(|_| ErrorKind::GenericError("Failed to read request".to_owned()).into())
+			.map(|body| body.to_vec()),
+	)
+}
+
 fn parse_body<T>(req: Request<Body>) -> Box<dyn Future<Item = T, Error = Error> + Send>
 where
 	for<'de> T: Deserialize<'de> + Send + 'static,
 {
 	Box::new(
-		req.into_body()
-			.concat2()
-			.map_err(|_| ErrorKind::GenericError("Failed to read request".to_owned()).into())
-			.and_then(|body| match serde_json::from_reader(&body.to_vec()[..]) {
-				Ok(obj) => ok(obj),
-				Err(e) => {
-					err(ErrorKind::GenericError(format!("Invalid request body: {}", e)).into())
-				}
-			}),
+		read_body(req).and_then(|body| match serde_json::from_reader(&body[..]) {
+			Ok(obj) => ok(obj),
+			Err(e) => err(ErrorKind::GenericError(format!("Invalid request body: {}", e)).into()),
+		}),
 	)
 }
@@ -12,16 +12,67 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::core::core::{self, amount_to_hr_string};
+use crate::command::{DoctorCheck, DoctorStatus};
 use crate::core::global;
+use crate::impls;
 use crate::libwallet::{
-	address, AcctPathMapping, Error, OutputCommitMapping, OutputStatus, TxLogEntry, WalletInfo,
+	address, AccountKeyExport, AcctPathMapping, CancelPreview, Denomination, Error, ErrorKind,
+	ExpectedCommitment, FeeBreakdown, Job, JobAction, KernelStatus, LockedOutput, MigrationReport,
+	MiningRewardSummary, NodeStatus, OutputCommitMapping, OutputLineage, OutputStatus, QueuedSend,
+	Reservation, TxLogEntry, WalletInfo, WalletInfoBreakdown, CLOCK_SKEW_WARNING_SECS,
 };
-use crate::util;
+use crate::util::{self, RwLock};
+use chrono::{DateTime, Local, Utc};
 use prettytable;
+use serde::Serialize;
 use std::io::prelude::Write;
 use term;
 
+lazy_static! {
+	/// Units amounts are displayed in, set from the `--units` option
+	static ref DISPLAY_UNITS: RwLock<Denomination> = RwLock::new(Denomination::Epic);
+}
+
+/// Set the units amounts are displayed in
+pub fn set_units(units: Denomination) {
+	*DISPLAY_UNITS.write() = units;
+}
+
+/// Format an amount of freemans in the display units. Amounts in epics are
+/// shown as a bare number, as they always have been, and any other units
+/// are named after the number.
+pub fn hr_amount(amount: impl Into<u64>, truncate: bool) -> String {
+	let amount = amount.into();
+	let units = *DISPLAY_UNITS.read();
+	match units {
+		Denomination::Epic => units.format_amount(amount, truncate),
+		_ => format!("{} {}", units.format_amount(amount, truncate), units),
+	}
+}
+
+/// Format an amount of freemans in the display units, always naming them
+pub fn hr_amount_with_units(amount: impl Into<u64>) -> String {
+	let units = *DISPLAY_UNITS.read();
+	format!("{} {}", units.format_amount(amount.into(), false), units)
+}
+
+/// Format a timestamp in the local time zone, with its offset from UTC
+pub fn hr_time(ts: &DateTime<Utc>) -> String {
+	ts.with_timezone(&Local)
+		.format("%Y-%m-%d %H:%M:%S %:z")
+		.to_string()
+}
+
+/// Print the result of a command as JSON, in place of its tables, for the
+/// `--json` scripting mode
+pub fn json<T: Serialize>(result: &T) -> Result<(), Error> {
+	let out = serde_json::to_string_pretty(result).map_err(|e| {
+		ErrorKind::GenericError(format!("Unable to serialize command output: {}", e))
+	})?;
+	println!("{}", out);
+	Ok(())
+}
+
 /// Display outputs in a pretty way
 pub fn outputs(
 	account: &str,
@@ -55,7 +106,8 @@ pub fn outputs(
 		bMG->"Coinbase?",
 		bMG->"# Confirms",
 		bMG->"Value",
-		bMG->"Tx"
+		bMG->"Tx",
+		bMG->"Tags"
 	]);
 
 	for m in outputs {
@@ -75,11 +127,12 @@ pub fn outputs(
 		};
 
 		let num_confirmations = format!("{}", m.output.num_confirmations(cur_height));
-		let value = format!("{}", core::amount_to_hr_string(m.output.value, false));
+		let value = format!("{}", hr_amount(m.output.value, false));
 		let tx = match m.output.tx_log_entry {
 			None => "".to_owned(),
 			Some(t) => t.to_string(),
 		};
+		let tags = m.output.tags.join(", ");
 
 		if dark_background_color_scheme {
 			table.add_row(row![
@@ -92,6 +145,7 @@ pub fn outputs(
 				bFB->num_confirmations,
 				bFG->value,
 				bFC->tx,
+				bFY->tags,
 			]);
 		} else {
 			table.add_row(row![
@@ -104,6 +158,7 @@ pub fn outputs(
 				bFB->num_confirmations,
 				bFG->value,
 				bFD->tx,
+				bFD->tags,
 			]);
 		}
 	}
@@ -122,6 +177,321 @@ pub fn outputs(
 	Ok(())
 }
 
+/// Display outputs with their spend events and earlier states
+pub fn output_lineage(
+	account: &str,
+	cur_height: u64,
+	validated: bool,
+	lineage: Vec<OutputLineage>,
+	dark_background_color_scheme: bool,
+) -> Result<(), Error> {
+	let title = format!(
+		"Wallet Output History - Account '{}' - Block Height: {}",
+		account, cur_height
+	);
+	println!();
+	if term::stdout().is_none() {
+		println!("Could not open terminal");
+		return Ok(());
+	}
+	let mut t = term::stdout().unwrap();
+	t.fg(term::color::MAGENTA).unwrap();
+	writeln!(t, "{}", title).unwrap();
+	t.reset().unwrap();
+
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Output Commitment",
+		bMG->"Block Height",
+		bMG->"Status",
+		bMG->"Value",
+		bMG->"Tx",
+		bMG->"Spent By Tx",
+		bMG->"Spent Height",
+		bMG->"Previous States"
+	]);
+
+	let opt_to_string = |o: Option<String>| o.unwrap_or_else(|| "".to_owned());
+
+	for l in lineage {
+		let m = l.output;
+		let commit = format!("{}", util::to_hex(m.commit.as_ref().to_vec()));
+		let height = format!("{}", m.output.height);
+		let status = format!("{}", m.output.status);
+		let value = format!("{}", hr_amount(m.output.value, false));
+		let tx = opt_to_string(m.output.tx_log_entry.map(|t| t.to_string()));
+		let spent_by = opt_to_string(l.spent_by.map(|t| format!("{} ({})", t.id, t.tx_type)));
+		let spent_height = opt_to_string(l.spent_height.map(|h| h.to_string()));
+		let states = l
+			.history
+			.iter()
+			.map(|o| match o.tx_log_entry {
+				Some(t) => format!("{} (tx {})", o.status, t),
+				None => format!("{}", o.status),
+			})
+			.collect::<Vec<_>>()
+			.join(" > ");
+
+		if dark_background_color_scheme {
+			table.add_row(row![
+				bFC->commit,
+				bFB->height,
+				bFR->status,
+				bFG->value,
+				bFC->tx,
+				bFY->spent_by,
+				bFB->spent_height,
+				bFB->states,
+			]);
+		} else {
+			table.add_row(row![
+				bFD->commit,
+				bFB->height,
+				bFR->status,
+				bFG->value,
+				bFD->tx,
+				bFD->spent_by,
+				bFB->spent_height,
+				bFB->states,
+			]);
+		}
+	}
+
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+
+	if !validated {
+		println!(
+			"\nWARNING: Wallet failed to verify data. \
+			 The above is from local cache and possibly invalid! \
+			 (is your `epic server` offline or broken?)"
+		);
+	}
+	Ok(())
+}
+
+/// Display locked outputs with the transactions holding their locks
+pub fn locked_outputs(
+	account: &str,
+	validated: bool,
+	locked: Vec<LockedOutput>,
+	dark_background_color_scheme: bool,
+) -> Result<(), Error> {
+	let title = format!("Locked Outputs - Account '{}'", account);
+	println!();
+	if term::stdout().is_none() {
+		println!("Could not open terminal");
+		return Ok(());
+	}
+	let mut t = term::stdout().unwrap();
+	t.fg(term::color::MAGENTA).unwrap();
+	writeln!(t, "{}", title).unwrap();
+	t.reset().unwrap();
+
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Output Commitment",
+		bMG->"Value",
+		bMG->"Locked By Tx",
+		bMG->"Slate ID",
+		bMG->"Locked Since",
+		bMG->"Stale?"
+	]);
+
+	let opt_to_string = |o: Option<String>| o.unwrap_or_else(|| "".to_owned());
+
+	for l in locked {
+		let m = l.output;
+		let commit = format!("{}", util::to_hex(m.commit.as_ref().to_vec()));
+		let value = format!("{}", hr_amount(m.output.value, false));
+		let locked_by = match (l.locked_by.as_ref(), m.output.spent_by_tx) {
+			(Some(t), _) => format!("{} ({})", t.id, t.tx_type),
+			(None, Some(id)) => format!("{} (missing)", id),
+			(None, None) => "".to_owned(),
+		};
+		let slate_id = opt_to_string(
+			l.locked_by
+				.as_ref()
+				.and_then(|t| t.tx_slate_id)
+				.map(|id| id.to_string()),
+		);
+		let since = opt_to_string(l.locked_since.map(|ts| hr_time(&ts)));
+		let stale = format!("{}", l.stale);
+
+		if dark_background_color_scheme {
+			table.add_row(row![
+				bFC->commit,
+				bFG->value,
+				bFY->locked_by,
+				bFB->slate_id,
+				bFB->since,
+				bFR->stale,
+			]);
+		} else {
+			table.add_row(row![
+				bFD->commit,
+				bFG->value,
+				bFD->locked_by,
+				bFB->slate_id,
+				bFB->since,
+				bFR->stale,
+			]);
+		}
+	}
+
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+
+	if !validated {
+		println!(
+			"\nWARNING: Wallet failed to verify data. \
+			 The above is from local cache and possibly invalid! \
+			 (is your `epic server` offline or broken?)"
+		);
+	}
+	Ok(())
+}
+
+/// Explain a failure to find enough funds for a transaction, with where the
+/// wallet's funds are and when enough of them will be spendable
+pub fn not_enough_funds(kind: &ErrorKind) {
+	if let ErrorKind::NotEnoughFunds {
+		needed,
+		spendable,
+		immature,
+		locked,
+		awaiting_confirmation,
+		shortfall,
+		enough_at_height,
+		..
+	} = kind
+	{
+		println!();
+		println!(
+			"Needed {} epic, short by {}. The wallet holds:",
+			hr_amount(*needed, false),
+			hr_amount(*shortfall, false)
+		);
+		for (label, amount) in vec![
+			("Spendable now", spendable),
+			("Awaiting confirmation", awaiting_confirmation),
+			("Immature coinbase", immature),
+			("Locked by transactions", locked),
+		] {
+			println!("  {:<24}{}", label, hr_amount(*amount, false));
+		}
+		match enough_at_height {
+			Some(_) if spendable >= needed => println!(
+				"Enough funds are spendable now, but spread over too many outputs \
+				 for one transaction"
+			),
+			Some(h) => println!("Enough funds will be spendable at block height {}", h),
+			None => println!(
+				"The confirmed funds won't cover it, receive more or check 'locks' for stale locks"
+			),
+		}
+		println!();
+	}
+}
+
+/// On a send refused as a likely repeat, say how to check and make it anyway
+pub fn duplicate_payment(kind: &ErrorKind) {
+	if let ErrorKind::DuplicatePayment { id, .. } = kind {
+		println!();
+		println!(
+			"Check transaction {} with 'txs -i {}', and if it's not the same payment, send again with --force",
+			id, id
+		);
+		println!();
+	}
+}
+
+/// Display what cancelling a transaction would do, warning if the
+/// transaction might still confirm
+pub fn cancel_preview(preview: &CancelPreview, dark_background_color_scheme: bool) {
+	let tx = &preview.tx;
+	let title = format!("Cancelling transaction {} ({})", tx.id, tx.tx_type);
+	println!();
+	if term::stdout().is_none() {
+		println!("Could not open terminal");
+		return;
+	}
+	let mut t = term::stdout().unwrap();
+	t.fg(term::color::MAGENTA).unwrap();
+	writeln!(t, "{}", title).unwrap();
+	t.reset().unwrap();
+
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Output Commitment",
+		bMG->"Value",
+		bMG->"Status",
+		bMG->"After Cancelling",
+	]);
+
+	let effects = preview
+		.unlocked_outputs
+		.iter()
+		.map(|m| (m, "Unlocked"))
+		.chain(preview.deleted_outputs.iter().map(|m| (m, "Deleted")));
+	for (m, effect) in effects {
+		let commit = format!("{}", util::to_hex(m.commit.as_ref().to_vec()));
+		let value = format!("{}", hr_amount(m.output.value, false));
+		let status = format!("{}", m.output.status);
+
+		if dark_background_color_scheme {
+			table.add_row(row![
+				bFC->commit,
+				bFG->value,
+				bFY->status,
+				bFR->effect,
+			]);
+		} else {
+			table.add_row(row![
+				bFD->commit,
+				bFG->value,
+				bFD->status,
+				bFR->effect,
+			]);
+		}
+	}
+
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+
+	if let Some(h) = preview.kernel_height {
+		println!(
+			"WARNING: The transaction kernel is on chain at block height {}, the \
+			 transaction went through. Don't cancel it.",
+			h
+		);
+	} else if preview.in_pool == Some(true) {
+		println!(
+			"WARNING: The transaction is in the node's pool, waiting to be mined. \
+			 Don't cancel it."
+		);
+	} else if preview.posted {
+		println!(
+			"WARNING: The transaction was posted and may still be in other nodes' \
+			 pools. Cancelling it could lead to spending outputs it already spent."
+		);
+	} else if preview.may_confirm {
+		println!(
+			"WARNING: The sender can still post this transaction. Only cancel it \
+			 once they have cancelled it too."
+		);
+	} else {
+		println!("The transaction isn't known to the chain and can be cancelled.");
+	}
+	println!();
+}
+
 /// Display transaction log in a pretty way
 pub fn txs(
 	account: &str,
@@ -173,31 +543,28 @@ pub fn txs(
 			None => "None".to_owned(),
 		};
 		let entry_type = format!("{}", t.tx_type);
-		let creation_ts = format!("{}", t.creation_ts.format("%Y-%m-%d %H:%M:%S"));
+		let creation_ts = hr_time(&t.creation_ts);
 		let ttl_cutoff_height = match t.ttl_cutoff_height {
 			Some(b) => format!("{}", b),
 			None => "None".to_owned(),
 		};
 		let confirmation_ts = match t.confirmation_ts {
-			Some(m) => format!("{}", m.format("%Y-%m-%d %H:%M:%S")),
+			Some(m) => hr_time(&m),
 			None => "None".to_owned(),
 		};
 		let confirmed = format!("{}", t.confirmed);
 		let num_inputs = format!("{}", t.num_inputs);
 		let num_outputs = format!("{}", t.num_outputs);
-		let amount_debited_str = core::amount_to_hr_string(t.amount_debited, true);
-		let amount_credited_str = core::amount_to_hr_string(t.amount_credited, true);
+		let amount_debited_str = hr_amount(t.amount_debited, true);
+		let amount_credited_str = hr_amount(t.amount_credited, true);
 		let fee = match t.fee {
-			Some(f) => format!("{}", core::amount_to_hr_string(f, true)),
+			Some(f) => format!("{}", hr_amount(f, true)),
 			None => "None".to_owned(),
 		};
 		let net_diff = if t.amount_credited >= t.amount_debited {
-			core::amount_to_hr_string(t.amount_credited - t.amount_debited, true)
+			hr_amount(t.amount_credited - t.amount_debited, true)
 		} else {
-			format!(
-				"-{}",
-				core::amount_to_hr_string(t.amount_debited - t.amount_credited, true)
-			)
+			format!("-{}", hr_amount(t.amount_debited - t.amount_credited, true))
 		};
 		let tx_data = match t.stored_tx {
 			Some(_) => "Yes".to_owned(),
@@ -301,27 +668,27 @@ pub fn info(
 	if dark_background_color_scheme {
 		table.add_row(row![
 			bFG->"Confirmed Total",
-			FG->amount_to_hr_string(wallet_info.total, false)
+			FG->hr_amount(wallet_info.total, false)
 		]);
 		// Only dispay "Immature Coinbase" if we have related outputs in the wallet.
 		// This row just introduces confusion if the wallet does not receive coinbase rewards.
 		if wallet_info.amount_immature > 0 {
 			table.add_row(row![
 				bFY->format!("Immature Coinbase (< {})", global::coinbase_maturity()),
-				FY->amount_to_hr_string(wallet_info.amount_immature, false)
+				FY->hr_amount(wallet_info.amount_immature, false)
 			]);
 		}
 		table.add_row(row![
 			bFY->format!("Awaiting Confirmation (< {})", wallet_info.minimum_confirmations),
-			FY->amount_to_hr_string(wallet_info.amount_awaiting_confirmation, false)
+			FY->hr_amount(wallet_info.amount_awaiting_confirmation, false)
 		]);
 		table.add_row(row![
 			bFB->format!("Awaiting Finalization"),
-			FB->amount_to_hr_string(wallet_info.amount_awaiting_finalization, false)
+			FB->hr_amount(wallet_info.amount_awaiting_finalization, false)
 		]);
 		table.add_row(row![
 			Fr->"Locked by previous transaction",
-			Fr->amount_to_hr_string(wallet_info.amount_locked, false)
+			Fr->hr_amount(wallet_info.amount_locked, false)
 		]);
 		table.add_row(row![
 			Fw->"--------------------------------",
@@ -329,28 +696,28 @@ pub fn info(
 		]);
 		table.add_row(row![
 			bFG->"Currently Spendable",
-			FG->amount_to_hr_string(wallet_info.amount_currently_spendable, false)
+			FG->hr_amount(wallet_info.amount_currently_spendable, false)
 		]);
 	} else {
 		table.add_row(row![
 			bFG->"Total",
-			FG->amount_to_hr_string(wallet_info.total, false)
+			FG->hr_amount(wallet_info.total, false)
 		]);
 		// Only dispay "Immature Coinbase" if we have related outputs in the wallet.
 		// This row just introduces confusion if the wallet does not receive coinbase rewards.
 		if wallet_info.amount_immature > 0 {
 			table.add_row(row![
 				bFB->format!("Immature Coinbase (< {})", global::coinbase_maturity()),
-				FB->amount_to_hr_string(wallet_info.amount_immature, false)
+				FB->hr_amount(wallet_info.amount_immature, false)
 			]);
 		}
 		table.add_row(row![
 			bFB->format!("Awaiting Confirmation (< {})", wallet_info.minimum_confirmations),
-			FB->amount_to_hr_string(wallet_info.amount_awaiting_confirmation, false)
+			FB->hr_amount(wallet_info.amount_awaiting_confirmation, false)
 		]);
 		table.add_row(row![
 			Fr->"Locked by previous transaction",
-			Fr->amount_to_hr_string(wallet_info.amount_locked, false)
+			Fr->hr_amount(wallet_info.amount_locked, false)
 		]);
 		table.add_row(row![
 			Fw->"--------------------------------",
@@ -358,12 +725,15 @@ pub fn info(
 		]);
 		table.add_row(row![
 			bFG->"Currently Spendable",
-			FG->amount_to_hr_string(wallet_info.amount_currently_spendable, false)
+			FG->hr_amount(wallet_info.amount_currently_spendable, false)
 		]);
 	};
 	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
 	table.printstd();
 	println!();
+	if let Some(b) = wallet_info.breakdown.as_ref() {
+		info_breakdown(b);
+	}
 	if !validated {
 		println!(
 			"\nWARNING: Wallet failed to verify data against a live chain. \
@@ -373,6 +743,73 @@ pub fn info(
 	}
 }
 
+/// Display the breakdown of summary info by output source
+fn info_breakdown(breakdown: &WalletInfoBreakdown) {
+	println!("\n____ Breakdown by Source ____\n");
+	let mut table = table!();
+	table.set_titles(row![
+		bMG->"Source",
+		bMG->"Total",
+		bMG->"Immature",
+		bMG->"Awaiting Confirmation",
+		bMG->"Locked",
+		bMG->"Currently Spendable",
+	]);
+	for (name, b) in vec![
+		("Coinbase", &breakdown.coinbase),
+		("Foundation", &breakdown.foundation),
+		("Regular", &breakdown.regular),
+	] {
+		table.add_row(row![
+			bFC->name,
+			FG->hr_amount(b.total, false),
+			FY->hr_amount(b.amount_immature, false),
+			FY->hr_amount(b.amount_awaiting_confirmation, false),
+			Fr->hr_amount(b.amount_locked, false),
+			FG->hr_amount(b.amount_currently_spendable, false),
+		]);
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+	table.printstd();
+	println!();
+
+	if breakdown.immature_schedule.is_empty() {
+		return;
+	}
+	println!("\n____ Immature Schedule ____\n");
+	let mut table = table!();
+	table.set_titles(row![
+		bMG->"Spendable At Height",
+		bMG->"Outputs",
+		bMG->"Amount",
+	]);
+	for m in breakdown.immature_schedule.iter() {
+		table.add_row(row![
+			bFB->m.lock_height,
+			FB->m.num_outputs,
+			FY->hr_amount(m.amount, false),
+		]);
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+	table.printstd();
+	println!();
+}
+
+/// How a fee was worked out, in a line
+pub fn fee_breakdown_summary(b: &FeeBreakdown) -> String {
+	format!(
+		"weight {} ({} outputs x {} + {} kernels x {} - {} inputs x {}) x {}",
+		b.weight,
+		b.num_outputs,
+		b.output_weight,
+		b.num_kernels,
+		b.kernel_weight,
+		b.num_inputs,
+		b.input_weight,
+		hr_amount(b.fee_base, false)
+	)
+}
+
 /// Display summary info in a pretty way
 pub fn estimate(
 	amount: u64,
@@ -380,13 +817,11 @@ pub fn estimate(
 		&str, // strategy
 		u64,  // total amount to be locked
 		u64,  // fee
+		Option<FeeBreakdown>,
 	)>,
 	dark_background_color_scheme: bool,
 ) {
-	println!(
-		"\nEstimation for sending {}:\n",
-		amount_to_hr_string(amount, false)
-	);
+	println!("\nEstimation for sending {}:\n", hr_amount(amount, false));
 
 	let mut table = table!();
 
@@ -394,20 +829,27 @@ pub fn estimate(
 		bMG->"Selection strategy",
 		bMG->"Fee",
 		bMG->"Will be locked",
+		bMG->"Fee breakdown",
 	]);
 
-	for (strategy, total, fee) in strategies {
+	for (strategy, total, fee, fee_breakdown) in strategies {
+		let breakdown = fee_breakdown
+			.as_ref()
+			.map(fee_breakdown_summary)
+			.unwrap_or_else(|| "".to_owned());
 		if dark_background_color_scheme {
 			table.add_row(row![
 				bFC->strategy,
-				FR->amount_to_hr_string(fee, false),
-				FY->amount_to_hr_string(total, false),
+				FR->hr_amount(fee, false),
+				FY->hr_amount(total, false),
+				FB->breakdown,
 			]);
 		} else {
 			table.add_row(row![
 				bFD->strategy,
-				FR->amount_to_hr_string(fee, false),
-				FY->amount_to_hr_string(total, false),
+				FR->hr_amount(fee, false),
+				FY->hr_amount(total, false),
+				FB->breakdown,
 			]);
 		}
 	}
@@ -415,6 +857,201 @@ pub fn estimate(
 	println!();
 }
 
+/// Display the on-chain status of a transaction kernel
+pub fn kernel_status(status: &KernelStatus) {
+	println!("\nKernel {}:\n", util::to_hex(status.excess.0.to_vec()));
+	match status.height {
+		Some(h) => println!("Included in block at height {}", h),
+		None => println!("Not found on chain"),
+	}
+	match status.tx_log_entry {
+		Some(id) => println!(
+			"Wallet transaction {} (confirmed: {})",
+			id, status.confirmed
+		),
+		None => println!("No matching transaction in this wallet"),
+	}
+	println!();
+}
+
+/// Display the migrations applied to the wallet database
+pub fn migration_report(report: &MigrationReport) {
+	if report.to_version > impls::SCHEMA_VERSION {
+		println!(
+			"\nWallet database is at schema version {}, written by a newer wallet version. \
+			 It can only be opened read-only\n",
+			report.to_version
+		);
+		return;
+	}
+	if report.steps.is_empty() {
+		println!(
+			"\nWallet database is at the current schema version ({})\n",
+			report.to_version
+		);
+		return;
+	}
+	match report.dry_run {
+		true => println!(
+			"\nWallet database would be migrated from schema version {} to {}:\n",
+			report.from_version, report.to_version
+		),
+		false => println!(
+			"\nWallet database migrated from schema version {} to {}:\n",
+			report.from_version, report.to_version
+		),
+	}
+	let mut table = table!();
+	table.set_titles(row![bMG->"Version", bMG->"Migration", bMG->"Records"]);
+	for step in report.steps.iter() {
+		table.add_row(row![bFB->step.version, step.description, bFG->step.records]);
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	if let Some(p) = report.backup_path.as_ref() {
+		println!("\nThe previous database was backed up to {}", p);
+	}
+	println!();
+}
+
+/// Display the status of the wallet's node relative to the wallet
+pub fn node_status(status: &NodeStatus) {
+	println!("\n____ Node Status ____\n");
+
+	let mut table = table!();
+	let na = "-".to_owned();
+	table.add_row(row![bFG->"Node Reachable", FG->status.node_reachable]);
+	table.add_row(row![
+		bFG->"Node Height",
+		FG->status.node_height.map(|h| h.to_string()).unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"Node Tip Hash",
+		FG->status.header_hash.clone().unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"Node Version",
+		FG->status
+			.node_version
+			.as_ref()
+			.map(|v| v.node_version.clone())
+			.unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"Wallet Last Confirmed Height",
+		FG->status.last_confirmed_height
+	]);
+	table.add_row(row![
+		bFY->"Sync Lag (blocks)",
+		FY->status.sync_lag.map(|l| l.to_string()).unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"Node Tip Time",
+		FG->status.tip_timestamp.as_ref().map(hr_time).unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFY->"Clock Skew (seconds)",
+		FY->status.clock_skew_secs.map(|s| s.to_string()).unwrap_or(na)
+	]);
+	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+	table.printstd();
+	println!();
+	if !status.node_reachable {
+		println!(
+			"WARNING: Unable to contact the configured node. \
+			 Check that your `epic server` is running and `check_node_api_http_addr` is correct."
+		);
+	} else if status.sync_lag.unwrap_or(0) > 0 {
+		println!(
+			"The wallet has not yet been refreshed up to the node's tip. \
+			 Running `info` or `txs` will update it."
+		);
+	}
+	if status.clock_skewed {
+		println!(
+			"WARNING: This machine's clock differs from the timestamp of the node's latest \
+			 block by more than {} minutes. Check the system clock, or whether the node is \
+			 still syncing, as times and lock heights shown by the wallet may be misleading.",
+			CLOCK_SKEW_WARNING_SECS / 60
+		);
+	}
+}
+
+/// Display the outcome of the doctor's checks, followed by suggested fixes
+pub fn doctor(checks: &[DoctorCheck]) {
+	println!("\n____ Wallet Doctor ____\n");
+
+	let mut table = table!();
+	table.set_titles(row![bMG->"Check", bMG->"Status", bMG->"Details"]);
+	for c in checks {
+		let status = match c.fixed {
+			true => "FIXED",
+			false => c.status.label(),
+		};
+		match (c.status, c.fixed) {
+			(DoctorStatus::Ok, _) | (_, true) => {
+				table.add_row(row![bFC->c.name, bFG->status, c.detail])
+			}
+			(DoctorStatus::Warn, false) => table.add_row(row![bFC->c.name, bFY->status, c.detail]),
+			(DoctorStatus::Fail, false) => table.add_row(row![bFC->c.name, bFR->status, c.detail]),
+		};
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+
+	let suggestions: Vec<_> = checks
+		.iter()
+		.filter(|c| !c.fixed)
+		.filter_map(|c| c.suggestion.as_ref().map(|s| (c.name, s)))
+		.collect();
+	if suggestions.is_empty() {
+		println!("Nothing to fix.");
+		return;
+	}
+	println!("Suggested fixes:");
+	for (name, s) in suggestions {
+		println!("* {}: {}", name, s);
+	}
+	println!();
+}
+
+/// Display mining rewards by PoW algorithm
+pub fn mining_rewards(account: &str, validated: bool, rewards: Vec<MiningRewardSummary>) {
+	println!(
+		"\n____ Mining Rewards by Algorithm - Account '{}' ____\n",
+		account
+	);
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"PoW Algorithm",
+		bMG->"Blocks",
+		bMG->"Amount",
+	]);
+	for r in rewards {
+		let pow_type = match r.pow_type {
+			Some(t) => format!("{:?}", t),
+			None => "Unknown".to_owned(),
+		};
+		table.add_row(row![
+			bFC->pow_type,
+			FB->r.num_blocks,
+			FG->hr_amount(r.amount, false),
+		]);
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+	table.printstd();
+	println!();
+	if !validated {
+		println!(
+			"\nWARNING: Wallet failed to verify data against a live chain. \
+			 The above is from local cache and only valid up to the given height! \
+			 (is your `epic server` offline or broken?)"
+		);
+	}
+}
+
 /// Display list of wallet accounts in a pretty way
 pub fn accounts(acct_mappings: Vec<AcctPathMapping>) {
 	println!("\n____ Wallet Accounts ____\n",);
@@ -423,11 +1060,17 @@ pub fn accounts(acct_mappings: Vec<AcctPathMapping>) {
 	table.set_titles(row![
 		mMG->"Name",
 		bMG->"Parent BIP-32 Derivation Path",
+		bMG->"Frozen",
 	]);
 	for m in acct_mappings {
+		let frozen = match m.frozen {
+			true => "yes",
+			false => "",
+		};
 		table.add_row(row![
 			bFC->m.label,
 			bGC->m.path.to_bip_32_string(),
+			bFR->frozen,
 		]);
 	}
 	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
@@ -435,6 +1078,235 @@ pub fn accounts(acct_mappings: Vec<AcctPathMapping>) {
 	println!();
 }
 
+/// Display the reservations of an account
+pub fn reservations(
+	account: &str,
+	reservations: &[Reservation],
+	dark_background_color_scheme: bool,
+) {
+	println!("\n____ Reservations - Account '{}' ____\n", account);
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Id",
+		bMG->"Created",
+		bMG->"Amount",
+		bMG->"Label",
+	]);
+	for r in reservations {
+		let id = r.id.to_string();
+		let created = hr_time(&r.creation_ts);
+		let amount = hr_amount(r.amount, false);
+		let label = r.label.clone().unwrap_or_default();
+		if dark_background_color_scheme {
+			table.add_row(row![bFC->id, bFB->created, bFG->amount, bFY->label]);
+		} else {
+			table.add_row(row![bFD->id, bFB->created, bFG->amount, bFD->label]);
+		}
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+
+	let total: u64 = reservations.iter().map(|r| r.amount).sum();
+	println!("\nTotal reserved: {}\n", hr_amount(total, false));
+}
+
+/// Display the sends queued until funds mature
+pub fn send_queue(queue: &[QueuedSend], dark_background_color_scheme: bool) {
+	println!("\n____ Send Queue ____\n");
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Id",
+		bMG->"Queued",
+		bMG->"Amount",
+		bMG->"Destination",
+		bMG->"Ready At",
+		bMG->"Expires After",
+	]);
+	for q in queue {
+		let id = q.id.to_string();
+		let queued = hr_time(&q.creation_ts);
+		let amount = hr_amount(q.args.amount, false);
+		let dest = q
+			.args
+			.send_args
+			.as_ref()
+			.map(|s| s.dest.clone())
+			.unwrap_or_default();
+		let ready = q.ready_height.to_string();
+		let expiry = q.expiry_height.to_string();
+		if dark_background_color_scheme {
+			table.add_row(
+				row![bFC->id, bFB->queued, bFG->amount, bFY->dest, bFB->ready, bFB->expiry],
+			);
+		} else {
+			table.add_row(
+				row![bFD->id, bFB->queued, bFG->amount, bFD->dest, bFB->ready, bFB->expiry],
+			);
+		}
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+}
+
+/// Display the queued jobs, dead letters included
+pub fn jobs(jobs: &[Job], dark_background_color_scheme: bool) {
+	println!("\n____ Job Queue ____\n");
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Id",
+		bMG->"Queued",
+		bMG->"Action",
+		bMG->"Attempts",
+		bMG->"Next Attempt",
+		bMG->"Last Error",
+	]);
+	for j in jobs {
+		let id = j.id.to_string();
+		let queued = hr_time(&j.creation_ts);
+		let action = match j.action {
+			JobAction::PostTx { tx_slate_id, .. } => format!("post tx {}", tx_slate_id),
+			JobAction::SendResponseSlate {
+				ref method,
+				ref dest,
+				..
+			} => format!("return slate by {} to {}", method, dest),
+			JobAction::Webhook { ref url, .. } => format!("notify {}", url),
+		};
+		let attempts = j.attempts.to_string();
+		let next = match j.dead {
+			true => "dead".to_owned(),
+			false => hr_time(&j.next_attempt_ts),
+		};
+		let error = j.last_error.clone().unwrap_or_default();
+		if dark_background_color_scheme {
+			table.add_row(
+				row![bFC->id, bFB->queued, bFY->action, bFB->attempts, bFB->next, bFR->error],
+			);
+		} else {
+			table.add_row(
+				row![bFD->id, bFB->queued, bFD->action, bFB->attempts, bFB->next, bFR->error],
+			);
+		}
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+}
+
+/// Display the public key material exported for an account
+pub fn account_key_export(export: &AccountKeyExport) {
+	println!("\n____ Account '{}' Key ____\n", export.label);
+	let mut table = table!();
+	table.add_row(row![bFG->"Path", FC->export.path.to_bip_32_string()]);
+	table.add_row(row![bFG->"Key Id", FC->export.path.to_hex()]);
+	table.add_row(row![bFG->"Public Key", FC->export.public_key]);
+	table.add_row(row![bFG->"Output Keys Derived", FC->export.child_count]);
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+}
+
+/// Display the commitments expected of an account's outputs
+pub fn expected_commitments(commits: &[ExpectedCommitment], dark_background_color_scheme: bool) {
+	println!("\n____ Expected Commitments ____\n");
+	let mut table = table!();
+
+	table.set_titles(row![
+		bMG->"Child",
+		bMG->"Value",
+		bMG->"Commitment",
+		bMG->"In Wallet",
+	]);
+	for c in commits {
+		let child = c.child_index.to_string();
+		let value = hr_amount(c.value, false);
+		let commit = util::to_hex(c.commit.as_ref().to_vec());
+		let in_wallet = match c.in_wallet {
+			true => "yes",
+			false => "no",
+		};
+		if dark_background_color_scheme {
+			table.add_row(row![bFC->child, bFG->value, bFY->commit, bFB->in_wallet]);
+		} else {
+			table.add_row(row![bFD->child, bFG->value, bFD->commit, bFB->in_wallet]);
+		}
+	}
+	table.set_format(*prettytable::format::consts::FORMAT_NO_COLSEP);
+	table.printstd();
+	println!();
+}
+
+/// Display a single transaction in full, with where it stands in the slate
+/// workflow
+pub fn tx_details(tx: &TxLogEntry, stage: &str) {
+	println!("\n____ Transaction {} ____\n", tx.id);
+
+	let mut table = table!();
+	let na = "-".to_owned();
+	let fee = tx.fee.unwrap_or(0);
+	let proof_status = match tx.payment_proof.as_ref() {
+		None => "None",
+		Some(p) if p.sender_signature.is_some() => "Complete",
+		Some(p) if p.receiver_signature.is_some() => "Signed by recipient",
+		Some(_) => "Requested",
+	};
+	table.add_row(row![bFG->"Stage", FY->stage]);
+	table.add_row(row![
+		bFG->"Slate Id",
+		FC->tx.tx_slate_id.map(|u| u.to_string()).unwrap_or(na.clone())
+	]);
+	table.add_row(row![bFG->"Type", FC->format!("{:?}", tx.tx_type)]);
+	table.add_row(row![
+		bFG->"Created",
+		FB->hr_time(&tx.creation_ts)
+	]);
+	table.add_row(row![
+		bFG->"Confirmed",
+		FB->match tx.confirmation_ts {
+			Some(t) => hr_time(&t),
+			None => na.clone(),
+		}
+	]);
+	table.add_row(row![
+		bFG->"Confirmations",
+		FB->tx.confirmations.map(|c| c.to_string()).unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"Amount Credited",
+		FG->hr_amount(tx.amount_credited, true)
+	]);
+	table.add_row(row![
+		bFG->"Amount Debited",
+		FR->hr_amount(tx.amount_debited, true)
+	]);
+	table.add_row(row![bFG->"Fee", FR->hr_amount(fee, true)]);
+	table.add_row(row![
+		bFG->"Fee Breakdown",
+		FR->tx.fee_breakdown.as_ref().map(fee_breakdown_summary).unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"TTL Cutoff Height",
+		FB->tx.ttl_cutoff_height.map(|h| h.to_string()).unwrap_or(na.clone())
+	]);
+	table.add_row(row![
+		bFG->"Kernel Excess",
+		FB->tx.kernel_excess.map(|e| util::to_hex(e.0.to_vec())).unwrap_or(na.clone())
+	]);
+	table.add_row(row![bFG->"Transaction Stored", FB->tx.stored_tx.is_some()]);
+	table.add_row(row![
+		bFG->"Post State",
+		FB->tx.post_state.as_ref().map(|s| format!("{:?}", s)).unwrap_or(na)
+	]);
+	table.add_row(row![bFG->"Payment Proof", FB->proof_status]);
+	table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+	table.printstd();
+	println!();
+}
+
 /// Display transaction log messages
 pub fn tx_messages(tx: &TxLogEntry, dark_background_color_scheme: bool) -> Result<(), Error> {
 	let title = format!("Transaction Messages - Transaction '{}'", tx.id,);
@@ -528,7 +1400,20 @@ pub fn payment_proof(tx: &TxLogEntry) -> Result<(), Error> {
 
 	let pp = match &tx.payment_proof {
 		None => {
-			writeln!(t, "{}", "None").unwrap();
+			match tx.sender_proof_address {
+				Some(a) => {
+					t.fg(term::color::WHITE).unwrap();
+					writeln!(t).unwrap();
+					writeln!(t, "Sender Address: {}", util::to_hex(a.to_bytes().to_vec())).unwrap();
+					writeln!(
+						t,
+						"Sender Address (Onion V3): {}",
+						address::onion_v3_from_pubkey(&a)?
+					)
+					.unwrap();
+				}
+				None => writeln!(t, "{}", "None").unwrap(),
+			}
 			t.reset().unwrap();
 			return Ok(());
 		}
@@ -548,11 +1433,14 @@ pub fn payment_proof(tx: &TxLogEntry) -> Result<(), Error> {
 		None => 0,
 	};
 	let amount = if tx.amount_credited >= tx.amount_debited {
-		core::amount_to_hr_string(tx.amount_credited - tx.amount_debited, true)
+		hr_amount(tx.amount_credited - tx.amount_debited, true)
 	} else {
 		format!(
 			"{}",
-			core::amount_to_hr_string(tx.amount_debited - tx.amount_credited - fee, true)
+			hr_amount(
+				(tx.amount_debited - tx.amount_credited).freemans() - fee,
+				true
+			)
 		)
 	};
 
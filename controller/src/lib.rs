@@ -35,5 +35,7 @@ pub mod command;
 pub mod controller;
 pub mod display;
 mod error;
+#[cfg(windows)]
+mod win_service;
 
 pub use crate::error::{Error, ErrorKind};
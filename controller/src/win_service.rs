@@ -0,0 +1,313 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The foreign listener as a Windows service: registered with the service
+//! manager to start at boot, stopped through it, and reporting its starts,
+//! stops and failures to the Application event log
+
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::os::windows::ffi::OsStrExt;
+use std::ptr;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use failure::ResultExt;
+use winapi::shared::minwindef::WORD;
+use winapi::um::winbase::{DeregisterEventSource, RegisterEventSourceW, ReportEventW};
+use winapi::um::winnt::{
+	EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, HANDLE,
+};
+use windows_service::define_windows_service;
+use windows_service::service::{
+	ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+	ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_dispatcher;
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use winreg::enums::HKEY_LOCAL_MACHINE;
+use winreg::RegKey;
+
+use crate::controller::ListenerService;
+use crate::error::{Error, ErrorKind};
+use crate::util::Mutex;
+
+/// Registry key event log sources of the Application log are registered
+/// under
+const EVENT_LOG_KEY: &str = r"SYSTEM\CurrentControlSet\Services\EventLog\Application";
+
+/// Message file passing an event's text through as its description,
+/// installed with the .NET framework
+const EVENT_MESSAGE_FILE: &str = r"Microsoft.NET\Framework\v4.0.30319\EventLogMessages.dll";
+
+/// Error, warning and information events
+const EVENT_TYPES_SUPPORTED: u32 = 7;
+
+/// How often the service checks whether the listener is up yet
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A listener to be run by the service's main function
+type Listener = Box<dyn FnOnce(ListenerService) -> Result<(), Error> + Send>;
+
+lazy_static! {
+	/// The listener handed over by `run` to the service's main function,
+	/// which the service manager calls on a thread of its own
+	static ref LISTENER: Mutex<Option<(String, Listener)>> = Mutex::new(None);
+}
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// A source of events in the Application event log
+struct EventLog(HANDLE);
+
+impl EventLog {
+	fn open(source: &str) -> Option<EventLog> {
+		let source = wide(source);
+		let handle = unsafe { RegisterEventSourceW(ptr::null(), source.as_ptr()) };
+		match handle.is_null() {
+			true => None,
+			false => Some(EventLog(handle)),
+		}
+	}
+
+	fn report(&self, event_type: WORD, message: &str) {
+		let message = wide(message);
+		let mut strings = [message.as_ptr()];
+		unsafe {
+			ReportEventW(
+				self.0,
+				event_type,
+				0,
+				0,
+				ptr::null_mut(),
+				1,
+				0,
+				strings.as_mut_ptr(),
+				ptr::null_mut(),
+			)
+		};
+	}
+}
+
+impl Drop for EventLog {
+	fn drop(&mut self) {
+		unsafe { DeregisterEventSource(self.0) };
+	}
+}
+
+/// Report an event to the Application log as coming from the service, as
+/// well as to the wallet's log
+fn report_event(name: &str, event_type: WORD, message: &str) {
+	match event_type {
+		EVENTLOG_ERROR_TYPE => error!("{}", message),
+		EVENTLOG_WARNING_TYPE => warn!("{}", message),
+		_ => info!("{}", message),
+	}
+	match EventLog::open(name) {
+		Some(log) => log.report(event_type, message),
+		None => warn!("Unable to report to the event log as {}", name),
+	}
+}
+
+fn wide(s: &str) -> Vec<u16> {
+	OsStr::new(s).encode_wide().chain(Some(0)).collect()
+}
+
+fn service_error(e: windows_service::Error) -> Error {
+	ErrorKind::GenericError(format!("Windows service error: {}", e)).into()
+}
+
+fn open_service(
+	name: &str,
+	access: ServiceAccess,
+) -> Result<windows_service::service::Service, Error> {
+	let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+		.map_err(service_error)?;
+	manager.open_service(name, access).map_err(service_error)
+}
+
+/// Register the service, started at boot by running this executable with
+/// `launch_arguments`, along with its event log source
+pub fn install(name: &str, launch_arguments: Vec<String>) -> Result<(), Error> {
+	let manager = ServiceManager::local_computer(
+		None::<&str>,
+		ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+	)
+	.map_err(service_error)?;
+	let info = ServiceInfo {
+		name: OsString::from(name),
+		display_name: OsString::from(format!("Epic Wallet listener ({})", name)),
+		service_type: ServiceType::OWN_PROCESS,
+		start_type: ServiceStartType::AutoStart,
+		error_control: ServiceErrorControl::Normal,
+		executable_path: env::current_exe().context(ErrorKind::IO)?,
+		launch_arguments: launch_arguments.into_iter().map(OsString::from).collect(),
+		dependencies: vec![],
+		account_name: None,
+		account_password: None,
+	};
+	manager
+		.create_service(&info, ServiceAccess::empty())
+		.map_err(service_error)?;
+
+	let system_root = env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_owned());
+	let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+	let (key, _) = hklm
+		.create_subkey(format!(r"{}\{}", EVENT_LOG_KEY, name))
+		.context(ErrorKind::IO)?;
+	key.set_value(
+		"EventMessageFile",
+		&format!(r"{}\{}", system_root, EVENT_MESSAGE_FILE),
+	)
+	.context(ErrorKind::IO)?;
+	key.set_value("TypesSupported", &EVENT_TYPES_SUPPORTED)
+		.context(ErrorKind::IO)?;
+	Ok(())
+}
+
+/// Stop the service if it's running, and remove it and its event log source
+pub fn uninstall(name: &str) -> Result<(), Error> {
+	let access = ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE;
+	let service = open_service(name, access)?;
+	let status = service.query_status().map_err(service_error)?;
+	if status.current_state != ServiceState::Stopped {
+		service.stop().map_err(service_error)?;
+	}
+	service.delete().map_err(service_error)?;
+
+	let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+	if let Err(e) = hklm.delete_subkey(format!(r"{}\{}", EVENT_LOG_KEY, name)) {
+		warn!("Unable to remove event log source {}: {}", name, e);
+	}
+	Ok(())
+}
+
+pub fn start(name: &str) -> Result<(), Error> {
+	let service = open_service(name, ServiceAccess::START)?;
+	service.start(&[] as &[&OsStr]).map_err(service_error)
+}
+
+pub fn stop(name: &str) -> Result<(), Error> {
+	let service = open_service(name, ServiceAccess::STOP)?;
+	service.stop().map_err(service_error)?;
+	Ok(())
+}
+
+/// Run `listener` as the service `name`, until the service manager stops
+/// it. Only works when started by the service manager
+pub fn run<F>(name: &str, listener: F) -> Result<(), Error>
+where
+	F: FnOnce(ListenerService) -> Result<(), Error> + Send + 'static,
+{
+	*LISTENER.lock() = Some((name.to_owned(), Box::new(listener)));
+	// returns once the service has stopped
+	service_dispatcher::start(name, ffi_service_main).map_err(|e| {
+		ErrorKind::GenericError(format!(
+			"Unable to run as a service, `service run` is run by the Windows service \
+			 manager: {}",
+			e
+		))
+	})?;
+	Ok(())
+}
+
+fn service_status(state: ServiceState, exit_code: ServiceExitCode) -> ServiceStatus {
+	let controls_accepted = match state {
+		ServiceState::Running => ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+		_ => ServiceControlAccept::empty(),
+	};
+	ServiceStatus {
+		service_type: ServiceType::OWN_PROCESS,
+		current_state: state,
+		controls_accepted,
+		exit_code,
+		checkpoint: 0,
+		wait_hint: Duration::default(),
+		process_id: None,
+	}
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+	let (name, listener) = match LISTENER.lock().take() {
+		Some(l) => l,
+		None => return,
+	};
+	if let Err(e) = run_service(&name, listener) {
+		let msg = format!("Wallet listener service {} failed: {}", name, e);
+		report_event(&name, EVENTLOG_ERROR_TYPE, &msg);
+	}
+}
+
+fn run_service(name: &str, listener: Listener) -> Result<(), Error> {
+	let service = ListenerService::default();
+	let shutdown = service.shutdown.clone();
+	let status_handle = service_control_handler::register(name, move |control| match control {
+		ServiceControl::Stop | ServiceControl::Shutdown => {
+			shutdown.store(true, Ordering::SeqCst);
+			ServiceControlHandlerResult::NoError
+		}
+		ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+		_ => ServiceControlHandlerResult::NotImplemented,
+	})
+	.map_err(service_error)?;
+	status_handle
+		.set_service_status(service_status(
+			ServiceState::Running,
+			ServiceExitCode::Win32(0),
+		))
+		.map_err(service_error)?;
+	report_event(
+		name,
+		EVENTLOG_INFORMATION_TYPE,
+		&format!("Wallet listener service {} started", name),
+	);
+
+	// the listener binds its port once any TOR listener is up
+	let ready = service.ready.clone();
+	let shutdown = service.shutdown.clone();
+	let source = name.to_owned();
+	thread::spawn(move || {
+		while !shutdown.load(Ordering::SeqCst) {
+			if ready.load(Ordering::SeqCst) {
+				let msg = format!(
+					"Wallet listener service {} is accepting connections",
+					source
+				);
+				report_event(&source, EVENTLOG_INFORMATION_TYPE, &msg);
+				return;
+			}
+			thread::sleep(READY_POLL_INTERVAL);
+		}
+	});
+
+	let res = listener(service);
+	let exit_code = match res {
+		Ok(_) => {
+			let msg = format!("Wallet listener service {} stopped", name);
+			report_event(name, EVENTLOG_INFORMATION_TYPE, &msg);
+			ServiceExitCode::Win32(0)
+		}
+		Err(ref e) => {
+			let msg = format!("Wallet listener service {} stopped: {}", name, e);
+			report_event(name, EVENTLOG_ERROR_TYPE, &msg);
+			ServiceExitCode::ServiceSpecific(1)
+		}
+	};
+	status_handle
+		.set_service_status(service_status(ServiceState::Stopped, exit_code))
+		.map_err(service_error)?;
+	Ok(())
+}
@@ -0,0 +1,148 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test freezing accounts, which blocks spends but not receives
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn account_freeze_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	owner_api.create_account_path(mask1, "listener")?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = |estimate_only: bool| InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		estimate_only: Some(estimate_only),
+		..Default::default()
+	};
+	// send from the default account to the listener account
+	let send = || -> Result<(), libwallet::Error> {
+		let mut slate: Slate = owner_api.init_send_tx(mask1, args(false))?;
+		owner_api.tx_lock_outputs(mask1, &slate, 0)?;
+		wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+			slate = api.receive_tx(&slate, Some("listener"), None)?;
+			Ok(())
+		})?;
+		slate = owner_api.finalize_tx(mask1, &slate)?;
+		owner_api.post_tx(mask1, &slate.tx, false)?;
+		Ok(())
+	};
+	let frozen = |res: Result<Slate, libwallet::Error>| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::AccountFrozen(label) => assert_eq!(label, "default"),
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("spent from a frozen account"),
+	};
+
+	// freezing needs a reason and a known account, and can't be repeated
+	assert!(owner_api.freeze_account(mask1, "default", " ").is_err());
+	assert!(owner_api.freeze_account(mask1, "unknown", "hold").is_err());
+	assert!(owner_api
+		.unfreeze_account(mask1, "default", "hold")
+		.is_err());
+
+	// a frozen account can still receive
+	let account = owner_api.freeze_account(mask1, "listener", "compliance hold")?;
+	assert!(account.frozen);
+	assert!(owner_api
+		.freeze_account(mask1, "listener", "again")
+		.is_err());
+	send()?;
+
+	// and be queried, but not spent from
+	owner_api.freeze_account(mask1, "default", "compliance hold")?;
+	let accounts = owner_api.accounts(mask1)?;
+	assert!(accounts.iter().all(|a| a.frozen));
+	let (_, info) = owner_api.retrieve_summary_info(mask1, true, 1)?;
+	assert!(info.amount_currently_spendable > 0);
+	frozen(owner_api.init_send_tx(mask1, args(false)));
+	frozen(owner_api.init_send_tx(mask1, args(true)));
+
+	// until it's unfrozen
+	let account = owner_api.unfreeze_account(mask1, "default", "hold lifted")?;
+	assert!(!account.frozen);
+	send()?;
+
+	// each change is in the audit trail
+	let log = owner_api.retrieve_audit_log(mask1)?;
+	let changes = log
+		.iter()
+		.map(|e| (e.action.as_str(), e.target.as_str(), e.after.as_str()))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		changes,
+		vec![
+			("freeze_account", "listener", "frozen"),
+			("freeze_account", "default", "frozen"),
+			("unfreeze_account", "default", "unfrozen"),
+		]
+	);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn account_freeze() {
+	let test_dir = "test_output/account_freeze";
+	setup(test_dir);
+	if let Err(e) = account_freeze_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
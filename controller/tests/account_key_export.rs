@@ -0,0 +1,161 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test exporting an account's public key material and the commitments
+//! expected of its outputs
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{ErrorKind, ACCOUNT_KEY_EXPORT_WARNING};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn account_key_export_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let refused = |e: libwallet::Error| match e.kind() {
+		ErrorKind::AccountKeyExport(_) => {}
+		k => panic!("unexpected error {}", k),
+	};
+
+	// the warning must be accepted
+	match owner_api.export_account_key(mask1, "default", false) {
+		Err(e) => match e.kind() {
+			ErrorKind::AccountKeyExport(msg) => assert_eq!(msg, ACCOUNT_KEY_EXPORT_WARNING),
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("key exported without accepting the warning"),
+	}
+	refused(
+		owner_api
+			.derive_expected_commitments(mask1, "default", 0, 1, &[1], false)
+			.unwrap_err(),
+	);
+	assert!(owner_api
+		.export_account_key(mask1, "unknown", true)
+		.is_err());
+
+	// the export is stable, and covers the keys derived so far
+	let export = owner_api.export_account_key(mask1, "default", true)?;
+	assert_eq!(export.label, "default");
+	assert_eq!(export.path.to_bip_32_string(), "m/0/0");
+	assert_eq!(export.public_key.len(), 66);
+	assert_eq!(
+		export.public_key,
+		owner_api
+			.export_account_key(mask1, "default", true)?
+			.public_key
+	);
+	owner_api.create_account_path(mask1, "other")?;
+	let other = owner_api.export_account_key(mask1, "other", true)?;
+	assert_ne!(export.public_key, other.public_key);
+	assert_eq!(other.child_count, 0);
+
+	// each of the wallet's outputs is among the commitments expected of its
+	// children, at its own child index
+	let (_, outputs) = owner_api.retrieve_outputs(mask1, false, true, false, None)?;
+	assert!(export.child_count as usize >= outputs.len());
+	let mut values: Vec<u64> = outputs.iter().map(|o| o.output.value).collect();
+	values.sort();
+	values.dedup();
+	let expected = owner_api.derive_expected_commitments(
+		mask1,
+		"default",
+		0,
+		export.child_count,
+		&values,
+		true,
+	)?;
+	assert_eq!(expected.len(), export.child_count as usize * values.len());
+	for o in outputs.iter() {
+		let e = expected.iter().find(|e| e.commit == o.commit).unwrap();
+		assert!(e.in_wallet);
+		assert_eq!(e.key_id, o.output.key_id);
+		assert_eq!(e.child_index, o.output.n_child);
+		assert_eq!(e.value, o.output.value);
+	}
+	assert_eq!(
+		expected.iter().filter(|e| e.in_wallet).count(),
+		outputs.len()
+	);
+
+	// and none of the children not yet derived are held
+	let ahead = owner_api.derive_expected_commitments(
+		mask1,
+		"default",
+		export.child_count,
+		5,
+		&values,
+		true,
+	)?;
+	assert!(ahead.iter().all(|e| !e.in_wallet));
+
+	// the number derived at once is limited
+	refused(
+		owner_api
+			.derive_expected_commitments(mask1, "default", 0, 501, &[1, 2], true)
+			.unwrap_err(),
+	);
+	refused(
+		owner_api
+			.derive_expected_commitments(mask1, "default", u32::max_value(), 2, &[1], true)
+			.unwrap_err(),
+	);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn account_key_export() {
+	let test_dir = "test_output/account_key_export";
+	setup(test_dir);
+	if let Err(e) = account_key_export_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
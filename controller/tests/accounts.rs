@@ -24,7 +24,7 @@ use self::core::global;
 use self::keychain::{ExtKeychain, Keychain};
 use epic_wallet_libwallet as libwallet;
 use impls::test_framework::{self, LocalWalletClient};
-use libwallet::InitTxArgs;
+use libwallet::{Amount, InitTxArgs};
 use std::thread;
 use std::time::Duration;
 
@@ -189,7 +189,7 @@ fn accounts_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: reward,
+			amount: Amount::from_freemans(reward),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -0,0 +1,103 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test sharing Owner and Foreign API handles between threads
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+extern crate epic_wallet_libwallet as libwallet;
+
+use api::{ForeignApi, OwnerApi};
+use impls::test_framework::LocalWalletClient;
+use libwallet::FeeContribution;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn assert_send_sync<T: Send + Sync + Clone + 'static>(_: &T) {}
+
+fn api_handles_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+
+	create_wallet_and_add!(
+		_client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let owner = OwnerApi::new(wallet1.clone());
+	let foreign = ForeignApi::new(wallet1.clone(), mask1_i.clone(), None);
+	assert_send_sync(&owner);
+	assert_send_sync(&foreign);
+
+	let mut threads = vec![];
+	for i in 0..4 {
+		let owner = owner.clone();
+		let foreign = foreign.clone();
+		let mask = mask1_i.clone();
+		threads.push(thread::spawn(move || -> Result<(), libwallet::Error> {
+			for _ in 0..5 {
+				let accounts = owner.accounts(mask.as_ref())?;
+				assert_eq!(accounts[0].label, "default");
+				let (_, info) = owner.retrieve_summary_info(mask.as_ref(), false, 1)?;
+				assert_eq!(info.total, 0);
+				let version = foreign.call(|api| api.check_version())?;
+				assert!(version.wallet_version.is_some());
+				foreign.configure(|api| api.set_fee_contribution(Some(FeeContribution::new(i))));
+			}
+			Ok(())
+		}));
+	}
+	for t in threads {
+		t.join().unwrap()?;
+	}
+
+	// an invalid setting is refused through any handle
+	let other = foreign.clone();
+	assert!(other
+		.configure(|api| api.set_deposit_tag(Some("not a tag!".to_owned())))
+		.is_err());
+	foreign.configure(|api| api.set_deposit_tag(Some("shared".to_owned())))?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn api_handles() {
+	let test_dir = "test_output/api_handles";
+	setup(test_dir);
+	if let Err(e) = api_handles_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
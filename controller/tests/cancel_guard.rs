@@ -0,0 +1,143 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test refusing to cancel a transaction the node may still mine
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, Slate, TxLogEntryType};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn cancel_guard_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+	let hold_posted = wallet_proxy.hold_posted.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	// posted transactions now wait in the pool
+	hold_posted.store(true, Ordering::Relaxed);
+
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let args = InitTxArgs {
+			src_acct_name: None,
+			amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: false,
+			..Default::default()
+		};
+		slate = api.init_send_tx(m, args)?;
+		slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+
+	// the sender sees the transaction in the pool and can't cancel it
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let preview = api.preview_cancel_tx(m, None, Some(slate.id))?;
+		assert_eq!(preview.kernel_height, None);
+		assert_eq!(preview.in_pool, Some(true));
+		assert!(preview.posted);
+		assert!(preview.may_confirm);
+
+		match api.cancel_tx(m, None, Some(slate.id), false) {
+			Err(e) => match e.kind() {
+				ErrorKind::CancelRefused(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("cancelled a transaction in the pool"),
+		}
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert_eq!(txs[0].tx_type, TxLogEntryType::TxSent);
+		Ok(())
+	})?;
+
+	// and neither can the recipient
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let res = api.cancel_tx(m, None, Some(slate.id), false);
+		assert!(res.is_err());
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert_eq!(txs[0].tx_type, TxLogEntryType::TxReceived);
+		Ok(())
+	})?;
+
+	// unless forced
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.cancel_tx(m, None, Some(slate.id), true)?;
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert_eq!(txs[0].tx_type, TxLogEntryType::TxSentCancelled);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn cancel_guard() {
+	let test_dir = "test_output/cancel_guard";
+	setup(test_dir);
+	if let Err(e) = cancel_guard_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -25,7 +25,7 @@ use self::core::global;
 use epic_wallet_libwallet as libwallet;
 use impls::test_framework::{self, LocalWalletClient};
 use impls::{PathToSlate, SlatePutter as _};
-use libwallet::{InitTxArgs, NodeClient};
+use libwallet::{Amount, InitTxArgs, NodeClient};
 use std::thread;
 use std::time::Duration;
 use util::ZeroingString;
@@ -178,7 +178,7 @@ fn scan_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 		// send to send
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			minimum_confirmations: cm,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -0,0 +1,83 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test detecting skew between the wallet's clock and the node's chain tip
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use chrono::Utc;
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::CLOCK_SKEW_WARNING_SECS;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn clock_skew_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	// test blocks are stamped a minute apart from a genesis long in the past,
+	// so the wallet's clock looks far ahead of the chain tip
+	let tip = chain.head_header().unwrap();
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let status = api.node_status(m)?;
+		assert_eq!(status.tip_timestamp, Some(tip.timestamp));
+		let skew = status.clock_skew_secs.unwrap();
+		assert!(skew > CLOCK_SKEW_WARNING_SECS);
+		assert!(skew <= (Utc::now() - tip.timestamp).num_seconds());
+		assert!(status.clock_skewed);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn clock_skew() {
+	let test_dir = "test_output/clock_skew";
+	setup(test_dir);
+	if let Err(e) = clock_skew_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
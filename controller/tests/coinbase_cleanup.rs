@@ -0,0 +1,125 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the removal of coinbase outputs that never get confirmed
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{BlockFees, OutputStatus, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn coinbase_cleanup_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	{
+		let mut w_lock = wallet1.lock();
+		w_lock.lc_provider()?.set_coinbase_cleanup_window(5)?;
+	}
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 2, false);
+
+	// a coinbase for a block that never makes it to the chain
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		api.build_coinbase(&BlockFees {
+			fees: 0,
+			height: 3,
+			key_id: None,
+		})?;
+		Ok(())
+	})?;
+
+	// still within the cleanup window
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+	let mut orphaned = None;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, cleaned) = api.retrieve_cleaned_coinbase(m, true)?;
+		assert!(cleaned.is_empty());
+		let (_, outputs) = api.retrieve_outputs(m, false, false, false, None)?;
+		let unconfirmed = outputs
+			.into_iter()
+			.filter(|o| o.output.status == OutputStatus::Unconfirmed)
+			.collect::<Vec<_>>();
+		assert_eq!(unconfirmed.len(), 1);
+		orphaned = Some(unconfirmed[0].output.clone());
+		Ok(())
+	})?;
+	let orphaned = orphaned.unwrap();
+
+	// past the window, the output is removed and the removal recorded
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 4, false);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, cleaned) = api.retrieve_cleaned_coinbase(m, true)?;
+		assert!(refreshed);
+		assert_eq!(cleaned.len(), 1);
+		assert_eq!(cleaned[0].tx.tx_type, TxLogEntryType::RevertedCoinbase);
+		assert_eq!(cleaned[0].tx.amount_credited, orphaned.value);
+		assert!(!cleaned[0].tx.confirmed);
+		let output = cleaned[0].output.clone().unwrap();
+		assert_eq!(output.key_id, orphaned.key_id);
+		assert_eq!(output.height, 3);
+
+		let (_, outputs) = api.retrieve_outputs(m, true, false, false, None)?;
+		assert!(outputs.iter().all(|o| o.output.key_id != orphaned.key_id));
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		assert_eq!(
+			txs.iter()
+				.filter(|t| t.tx_type == TxLogEntryType::RevertedCoinbase)
+				.count(),
+			1
+		);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn coinbase_cleanup() {
+	let test_dir = "test_output/coinbase_cleanup";
+	setup(test_dir);
+	if let Err(e) = coinbase_cleanup_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
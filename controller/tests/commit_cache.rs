@@ -0,0 +1,117 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test backfilling of commits for outputs stored without one
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::ErrorKind;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn commit_cache_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	let mut original = None;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, outputs) = api.retrieve_outputs(m, false, true, false, None)?;
+		assert_eq!(outputs.len(), 3);
+		original = Some(outputs[0].clone());
+		Ok(())
+	})?;
+	let original = original.unwrap();
+
+	// store the output the way older wallets did, without its commit
+	{
+		wallet_inst!(wallet1, w);
+		let mut legacy = original.output.clone();
+		legacy.commit = None;
+		let mut batch = w.batch(mask1)?;
+		batch.save(legacy)?;
+		batch.commit()?;
+	}
+
+	// missing commits are no longer silently recalculated
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		match api.retrieve_outputs(m, false, false, false, None) {
+			Err(e) => match e.kind() {
+				ErrorKind::MissingOutputCommit(_) => {}
+				k => panic!("unexpected error {:?}", k),
+			},
+			Ok(_) => panic!("retrieving an output without a commit should fail"),
+		}
+		Ok(())
+	})?;
+
+	// the backfill restores it, and is a no-op once everything has a commit
+	{
+		wallet_inst!(wallet1, w);
+		assert_eq!(w.backfill_output_commits(mask1)?, 1);
+		assert_eq!(w.backfill_output_commits(mask1)?, 0);
+	}
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, outputs) = api.retrieve_outputs(m, false, false, false, None)?;
+		let restored = outputs
+			.iter()
+			.find(|o| o.output.key_id == original.output.key_id)
+			.unwrap();
+		assert_eq!(restored.commit, original.commit);
+		assert_eq!(restored.output.commit, original.output.commit);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn commit_cache() {
+	let test_dir = "test_output/commit_cache";
+	setup(test_dir);
+	if let Err(e) = commit_cache_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
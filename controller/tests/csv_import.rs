@@ -0,0 +1,196 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test importing the outputs and transactions another wallet exported as CSV
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use epic_wallet_util::epic_util::{to_hex, ZeroingString};
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Denomination, OutputStatus, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn csv_import_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let seed_phrase = "affair pistol cancel crush garment candy ancient flag work \
+	                   market crush dry stand focus mutual weapon offer ceiling rival turn team spring \
+	                   where swift";
+	let seed_phrase = Some(ZeroingString::from(seed_phrase));
+
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		seed_phrase,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		seed_phrase,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+	create_wallet_and_add!(
+		client3,
+		wallet3,
+		mask3_i,
+		test_dir,
+		"wallet3",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask3 = (&mask3_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let owner_api1 = api::Owner::new(wallet1.clone());
+	let owner_api2 = api::Owner::new(wallet2.clone());
+	let owner_api3 = api::Owner::new(wallet3.clone());
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+	let (_, info1) = owner_api1.retrieve_summary_info(mask1, true, 1)?;
+	let (_, outputs) = owner_api1.retrieve_outputs(mask1, false, false, false, None)?;
+	let (_, txs) = owner_api1.retrieve_txs(mask1, false, None, None)?;
+	let hr = |v: u64| Denomination::Epic.format_amount(v, true);
+
+	// outputs as shown in the outputs table, with a spent one
+	let mut outputs_csv =
+		"Output Commitment,MMR Index,Block Height,Status,Coinbase?,Value\n".to_owned();
+	for o in outputs.iter().map(|m| &m.output) {
+		outputs_csv += &format!(
+			"{},{},{},{},{},{}\n",
+			o.commit.clone().unwrap(),
+			o.mmr_index.unwrap(),
+			o.height,
+			o.status,
+			o.is_coinbase,
+			hr(o.value)
+		);
+	}
+	outputs_csv += &format!("{},None,1,Spent,true,1.0\n", to_hex(vec![8; 33]));
+
+	// a wallet with a different seed can't import them
+	let import = owner_api3.import_outputs_csv(mask3, &outputs_csv, Denomination::Epic)?;
+	assert!(import.imported.is_empty());
+	assert_eq!(import.skipped.len(), outputs.len() + 1);
+	assert!(import.skipped[0].reason.contains("isn't this wallet's"));
+
+	let import = owner_api2.import_outputs_csv(mask2, &outputs_csv, Denomination::Epic)?;
+	assert_eq!(import.imported.len(), outputs.len());
+	assert_eq!(import.skipped.len(), 1);
+	assert_eq!(import.skipped[0].line, outputs.len() + 2);
+	assert!(import.skipped[0].reason.contains("Spent"));
+	assert!(import
+		.imported
+		.iter()
+		.all(|o| o.status == OutputStatus::Unspent));
+	let (_, info2) = owner_api2.retrieve_summary_info(mask2, false, 1)?;
+	assert_eq!(info2.total, info1.total);
+
+	// outputs given by key id and value, as in the owner API
+	let o = &outputs[0].output;
+	let api_csv = format!(
+		"key_id,value,height\nm/0/0,{},{}\nm/0/0/99,{},{}\n{},{},{}\n",
+		o.value,
+		o.height,
+		o.value,
+		o.height,
+		o.key_id.to_hex(),
+		o.value,
+		o.height
+	);
+	let import = owner_api2.import_outputs_csv(mask2, &api_csv, Denomination::Freeman)?;
+	assert!(import.imported.is_empty());
+	let reasons = import
+		.skipped
+		.iter()
+		.map(|s| (s.line, s.reason.as_str()))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		reasons,
+		vec![
+			(2, "'m/0/0' isn't an output's key path, such as m/0/0/4"),
+			(3, "the output isn't unspent on chain"),
+			(4, "the output is already in the wallet"),
+		]
+	);
+
+	// the transaction log, as shown in the txs table, fills in the entries
+	// made restoring the outputs
+	let mut txs_csv = "Id,Type,Shared Transaction Id,Creation Time,Confirmed?,\
+	                   Amount Credited,Amount Debited,Fee,Kernel\n"
+		.to_owned();
+	for t in txs.iter() {
+		txs_csv += &format!(
+			"{},\"{}\",None,{},{},{},{},None,None\n",
+			t.id,
+			t.tx_type,
+			t.creation_ts.format("%Y-%m-%d %H:%M:%S %:z"),
+			t.confirmed,
+			hr(t.amount_credited.freemans()),
+			hr(t.amount_debited.freemans())
+		);
+	}
+	txs_csv += "9,Sent Tx,None,2020-01-02 03:04:05,false,0.0,1.0,0.008,None\n";
+	let import = owner_api2.import_txs_csv(mask2, &txs_csv, Denomination::Epic)?;
+	assert_eq!(import.imported.len(), txs.len());
+	assert_eq!(import.skipped.len(), 1);
+	assert!(import.skipped[0].reason.contains("unconfirmed"));
+	let (_, txs2) = owner_api2.retrieve_txs(mask2, false, None, None)?;
+	assert_eq!(txs2.len(), txs.len());
+	for (t1, t2) in txs.iter().zip(txs2.iter()) {
+		assert_eq!(t2.tx_type, TxLogEntryType::ConfirmedCoinbase);
+		assert_eq!(t2.amount_credited, t1.amount_credited);
+		assert_eq!(t2.creation_ts.timestamp(), t1.creation_ts.timestamp());
+	}
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn csv_import() {
+	let test_dir = "test_output/csv_import";
+	setup(test_dir);
+	if let Err(e) = csv_import_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
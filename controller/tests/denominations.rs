@@ -0,0 +1,118 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test giving transaction amounts in units other than freemans
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, Denomination, InitTxArgs, IssueInvoiceTxArgs};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn denominations_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = |amount, units| InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(amount),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		units,
+		..Default::default()
+	};
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		// without units, amounts are in freemans
+		let slate = api.init_send_tx(m, args(core::consensus::EPIC_BASE * 3 / 2, None))?;
+		assert_eq!(slate.amount, core::consensus::EPIC_BASE * 3 / 2);
+
+		let slate = api.init_send_tx(m, args(1500, Some(Denomination::Milli)))?;
+		assert_eq!(slate.amount, core::consensus::EPIC_BASE * 3 / 2);
+
+		let slate = api.init_send_tx(m, args(2, Some(Denomination::Epic)))?;
+		assert_eq!(slate.amount, core::consensus::EPIC_BASE * 2);
+
+		// amounts too large to be held in freemans are refused
+		let res = api.init_send_tx(m, args(u64::max_value(), Some(Denomination::Micro)));
+		assert!(res.is_err());
+
+		let invoice = IssueInvoiceTxArgs {
+			amount: Amount::from_freemans(250),
+			units: Some(Denomination::Micro),
+			..Default::default()
+		};
+		let slate = api.issue_invoice_tx(m, invoice)?;
+		assert_eq!(slate.amount, Denomination::Micro.freemans() * 250);
+		Ok(())
+	})?;
+
+	// the units field is named in lowercase in the API
+	let args: InitTxArgs = serde_json::from_str(
+		r#"{"amount": "1500", "units": "milli", "src_acct_name": null,
+		"minimum_confirmations": 2, "max_outputs": 500, "num_change_outputs": 1,
+		"selection_strategy_is_use_all": true, "message": null, "target_slate_version": null,
+		"payment_proof_recipient_address": null, "ttl_blocks": null, "send_args": null,
+		"estimate_only": null}"#,
+	)
+	.unwrap();
+	assert_eq!(args.units, Some(Denomination::Milli));
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn denominations() {
+	let test_dir = "test_output/denominations";
+	setup(test_dir);
+	if let Err(e) = denominations_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
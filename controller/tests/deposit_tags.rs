@@ -0,0 +1,133 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test tagging received transactions with the deposit tag they came in through
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn deposit_tags_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		exchange,
+		mask2_i,
+		test_dir,
+		"exchange",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	// sends to the exchange, received through the given tag if any
+	let deposit = |tag: Option<&str>| -> Result<Slate, libwallet::Error> {
+		let mut slate = Slate::blank(2);
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			let args = InitTxArgs {
+				src_acct_name: None,
+				amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+				minimum_confirmations: 2,
+				max_outputs: 500,
+				num_change_outputs: 1,
+				selection_strategy_is_use_all: false,
+				..Default::default()
+			};
+			slate = api.init_send_tx(m, args)?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			Ok(())
+		})?;
+		wallet::controller::foreign_single_use(exchange.clone(), mask2_i.clone(), |api| {
+			api.set_deposit_tag(tag.map(|t| t.to_owned()))?;
+			slate = api.receive_tx(&slate, None, None)?;
+			Ok(())
+		})?;
+		Ok(slate)
+	};
+
+	let tagged = deposit(Some("user-17"))?;
+	let untagged = deposit(None)?;
+
+	wallet::controller::owner_single_use(exchange.clone(), mask2, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(tagged.id))?;
+		assert_eq!(txs[0].deposit_tag, Some("user-17".to_owned()));
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(untagged.id))?;
+		assert_eq!(txs[0].deposit_tag, None);
+		Ok(())
+	})?;
+
+	// tags have to fit in a listener path
+	let too_long = "x".repeat(65);
+	for tag in vec!["", "user/17", "user 17", too_long.as_str()] {
+		wallet::controller::foreign_single_use(exchange.clone(), mask2_i.clone(), |api| {
+			match api.set_deposit_tag(Some(tag.to_owned())) {
+				Err(e) => match e.kind() {
+					ErrorKind::DepositTag(_) => {}
+					k => panic!("unexpected error {}", k),
+				},
+				Ok(_) => panic!("accepted deposit tag '{}'", tag),
+			}
+			Ok(())
+		})?;
+	}
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn deposit_tags() {
+	let test_dir = "test_output/deposit_tags";
+	setup(test_dir);
+	if let Err(e) = deposit_tags_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,174 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test refusing sends that repeat an unconfirmed or recent payment
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, Slate, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn duplicate_payment_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let _mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 4, false);
+
+	let amount = core::consensus::EPIC_BASE;
+	let args = |amount: u64, destination: Option<&str>| InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(amount),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		destination: destination.map(|d| d.to_owned()),
+		..Default::default()
+	};
+	let is_duplicate = |res: Result<Slate, libwallet::Error>| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::DuplicatePayment { destination, .. } => {
+				assert_eq!(destination, "wallet2");
+				true
+			}
+			k => panic!("expected a duplicate payment, got {}", k),
+		},
+		Ok(_) => false,
+	};
+
+	// a send left unconfirmed, as if the client never saw it go through
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		assert!(api
+			.check_duplicate_payment(m, amount, "wallet2")?
+			.is_empty());
+		slate = api.init_send_tx(m, args(amount, Some("wallet2")))?;
+		slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		Ok(())
+	})?;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let earlier = api.check_duplicate_payment(m, amount, "wallet2")?;
+		assert_eq!(earlier.len(), 1);
+		assert_eq!(earlier[0].tx_slate_id, Some(slate.id));
+		assert_eq!(earlier[0].destination, Some("wallet2".to_owned()));
+
+		// sending it again is refused, unless asked for
+		assert!(is_duplicate(
+			api.init_send_tx(m, args(amount, Some("wallet2")))
+		));
+		let mut again = args(amount, Some("wallet2"));
+		again.allow_duplicate = Some(true);
+		assert!(!is_duplicate(api.init_send_tx(m, again)));
+		// other amounts, destinations and sends without one aren't repeats
+		assert!(!is_duplicate(
+			api.init_send_tx(m, args(amount * 2, Some("wallet2")))
+		));
+		assert!(!is_duplicate(
+			api.init_send_tx(m, args(amount, Some("wallet3")))
+		));
+		assert!(!is_duplicate(api.init_send_tx(m, args(amount, None))));
+		// estimates aren't sends
+		let mut estimate = args(amount, Some("wallet2"));
+		estimate.estimate_only = Some(true);
+		assert!(!is_duplicate(api.init_send_tx(m, estimate)));
+
+		// nor is a cancelled send repeated
+		api.cancel_tx(m, None, Some(slate.id), false)?;
+		assert!(api
+			.check_duplicate_payment(m, amount, "wallet2")?
+			.is_empty());
+		Ok(())
+	})?;
+
+	// a completed send is still repeated by another soon after
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.init_send_tx(m, args(amount, Some("wallet2")))?;
+		slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 1, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert_eq!(txs[0].tx_type, TxLogEntryType::TxSent);
+		assert!(txs[0].confirmed);
+		let earlier = api.check_duplicate_payment(m, amount, "wallet2")?;
+		assert_eq!(earlier.len(), 1);
+		assert_eq!(earlier[0].id, txs[0].id);
+		assert!(is_duplicate(
+			api.init_send_tx(m, args(amount, Some("wallet2")))
+		));
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn duplicate_payment() {
+	let test_dir = "test_output/duplicate_payment";
+	setup(test_dir);
+	if let Err(e) = duplicate_payment_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
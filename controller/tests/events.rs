@@ -0,0 +1,142 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the events a wallet emits as outputs confirm, transactions are
+//! cancelled, the node falls behind and refreshes complete
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use epic_wallet_util::epic_core as core;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, WalletEvent};
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn drain(events: &Receiver<WalletEvent>) -> Vec<WalletEvent> {
+	events.try_iter().collect()
+}
+
+fn events_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		_client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let events = owner_api.subscribe_events()?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+	assert!(drain(&events).is_empty());
+
+	// a refresh reports each coinbase output found, then its completion
+	owner_api.retrieve_summary_info(mask1, true, 1)?;
+	let height = owner_api.node_height(mask1)?.height;
+	let received = drain(&events);
+	let confirmed = received
+		.iter()
+		.filter(|e| match e {
+			WalletEvent::OutputConfirmed {
+				value,
+				tx_log_entry,
+				..
+			} => !value.is_zero() && tx_log_entry.is_some(),
+			_ => false,
+		})
+		.count();
+	assert_eq!(confirmed, 10);
+	match received.last() {
+		Some(WalletEvent::RefreshCompleted { height: h, .. }) => assert_eq!(*h, height),
+		e => panic!("expected the refresh to complete, got {:?}", e),
+	}
+
+	// cancelling a send reports it, to subscribers made since too
+	let late = owner_api.subscribe_events()?;
+	let args = InitTxArgs {
+		amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 1,
+		..Default::default()
+	};
+	let slate = owner_api.init_send_tx(mask1, args)?;
+	owner_api.tx_lock_outputs(mask1, &slate, 0)?;
+	owner_api.cancel_tx(mask1, None, Some(slate.id), false)?;
+	for rx in vec![&events, &late] {
+		let cancelled: Vec<_> = drain(rx)
+			.into_iter()
+			.filter_map(|e| match e {
+				WalletEvent::TxCancelled { tx_slate_id, .. } => Some(tx_slate_id),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(cancelled, vec![Some(slate.id)]);
+	}
+
+	// a wallet ahead of the node reports a reorg and leaves its outputs be
+	{
+		wallet_inst!(wallet1, w);
+		let parent_key_id = w.parent_key_id();
+		let mut batch = w.batch(mask1)?;
+		batch.save_last_confirmed_height(&parent_key_id, height + 10)?;
+		batch.commit()?;
+	}
+	drop(late);
+	owner_api.retrieve_summary_info(mask1, true, 1)?;
+	let reorg = drain(&events).into_iter().any(|e| match e {
+		WalletEvent::ReorgDetected {
+			wallet_height,
+			node_height,
+			..
+		} => wallet_height == height + 10 && node_height == height,
+		_ => false,
+	});
+	assert!(reorg);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn wallet_events() {
+	let test_dir = "test_output/wallet_events";
+	setup(test_dir);
+	if let Err(e) = events_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
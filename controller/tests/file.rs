@@ -25,7 +25,7 @@ use impls::{PathToSlate, SlateGetter as _, SlatePutter as _};
 use std::thread;
 use std::time::Duration;
 
-use epic_wallet_libwallet::InitTxArgs;
+use epic_wallet_libwallet::{Amount, InitTxArgs};
 
 use serde_json;
 
@@ -112,7 +112,7 @@ fn file_exchange_test_impl(test_dir: &'static str) -> Result<(), libwallet::Erro
 		// send to send
 		let args = InitTxArgs {
 			src_acct_name: Some("mining".to_owned()),
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
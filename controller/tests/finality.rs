@@ -0,0 +1,146 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test waiting on a transaction to reach a number of confirmations
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, FinalityState, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn finality_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	// a payment, posted and mined in a single block
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.init_send_tx(m, args.clone())?;
+		slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+
+	// the recipient waits for 3 confirmations, timing out with 1
+	let mut tx_id = 0;
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		tx_id = txs[0].id;
+		let finality = api.wait_tx_finality(m, tx_id, 3, Duration::from_millis(0))?;
+		assert_eq!(finality.state, FinalityState::Pending);
+		assert_eq!(finality.confirmations, 1);
+		assert_eq!(finality.kernel_height, Some(finality.height));
+		assert_eq!(finality.tx_slate_id, Some(slate.id));
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 2, false);
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let finality = api.wait_tx_finality(m, tx_id, 3, Duration::from_secs(60))?;
+		assert_eq!(finality.state, FinalityState::Final);
+		assert_eq!(finality.confirmations, 3);
+		Ok(())
+	})?;
+
+	// a send cancelled before reaching the chain can't become final
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		api.cancel_tx(m, None, Some(slate.id), false)?;
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		let finality = api.wait_tx_finality(m, txs[0].id, 3, Duration::from_secs(60))?;
+		assert_eq!(finality.state, FinalityState::Invalidated);
+		assert_eq!(finality.kernel_height, None);
+
+		// callbacks only go to http(s) URLs
+		let res = api.watch_tx_finality(
+			m,
+			txs[0].id,
+			3,
+			"ftp://localhost/settled",
+			Duration::from_secs(60),
+		);
+		assert!(res.is_err());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn finality() {
+	let test_dir = "test_output/finality";
+	setup(test_dir);
+	if let Err(e) = finality_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,152 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the checks run before foreign API calls
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+extern crate epic_wallet_libwallet as libwallet;
+
+use epic_wallet_util::epic_core as core;
+
+use api::{AmountPolicy, Foreign, ForeignMiddlewareRegistry, SenderAllowlist, VersionCheck};
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{address, Amount, ErrorKind, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn expect_refused(res: Result<Slate, libwallet::Error>) {
+	match res {
+		Err(e) => match e.kind() {
+			ErrorKind::PolicyRefused(_) => {}
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("slate was received"),
+	}
+}
+
+fn foreign_middleware_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let mut sender_address = None;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		sender_address = Some(api.get_public_proof_address(m, 0)?);
+		Ok(())
+	})?;
+	let mut recipient_address = None;
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		recipient_address = Some(api.get_public_proof_address(m, 0)?);
+		Ok(())
+	})?;
+
+	let (mut too_large, mut no_proof, mut allowed) =
+		(Slate::blank(2), Slate::blank(2), Slate::blank(2));
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let args = |amount, proof| InitTxArgs {
+			src_acct_name: None,
+			amount: Amount::from_freemans(amount),
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: false,
+			payment_proof_recipient_address: proof,
+			..Default::default()
+		};
+		too_large = api.init_send_tx(m, args(core::consensus::EPIC_BASE * 2, recipient_address))?;
+		no_proof = api.init_send_tx(m, args(core::consensus::EPIC_BASE / 2, None))?;
+		allowed = api.init_send_tx(m, args(core::consensus::EPIC_BASE / 2, recipient_address))?;
+		Ok(())
+	})?;
+
+	let mut middleware = ForeignMiddlewareRegistry::new();
+	middleware.register(VersionCheck);
+	middleware.register(AmountPolicy {
+		min_amount: None,
+		max_amount: Some(core::consensus::EPIC_BASE),
+	});
+	let sender = address::onion_v3_from_pubkey(&sender_address.unwrap())?;
+	middleware.register(SenderAllowlist::new(&[sender])?);
+	assert!(SenderAllowlist::new(&["not an address".to_owned()]).is_err());
+
+	let mut foreign = Foreign::new(wallet2.clone(), mask2_i.clone(), None);
+	foreign.set_middleware(middleware);
+	expect_refused(foreign.receive_tx(&too_large, None, None));
+	expect_refused(foreign.receive_tx(&no_proof, None, None));
+	foreign.receive_tx(&allowed, None, None)?;
+
+	// refused slates were never stored
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		assert_eq!(txs.len(), 1);
+		assert_eq!(txs[0].tx_slate_id, Some(allowed.id));
+		Ok(())
+	})?;
+
+	// without checks, anything is received
+	let foreign = Foreign::new(wallet2.clone(), mask2_i.clone(), None);
+	foreign.receive_tx(&no_proof, None, None)?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn foreign_middleware() {
+	let test_dir = "test_output/foreign_middleware";
+	setup(test_dir);
+	if let Err(e) = foreign_middleware_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
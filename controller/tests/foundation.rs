@@ -0,0 +1,117 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test foundation wallet mode
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{BlockFees, ErrorKind};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn foundation_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 2, false);
+
+	let block_fees = BlockFees {
+		fees: 0,
+		height: 3,
+		key_id: None,
+	};
+
+	// a regular wallet refuses to build foundation outputs
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		match api.build_foundation(&block_fees) {
+			Err(e) => assert_eq!(e.kind(), ErrorKind::NotFoundationWallet),
+			Ok(_) => panic!("build_foundation should fail outside foundation mode"),
+		}
+		Ok(())
+	})?;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, rewards) = api.retrieve_foundation_rewards(m, false, None, None)?;
+		assert!(rewards.is_empty());
+		Ok(())
+	})?;
+
+	// the foundation wallet builds them, tagged as such
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		api.set_foundation_wallet(true);
+		api.build_foundation(&block_fees)?;
+		Ok(())
+	})?;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, rewards) = api.retrieve_foundation_rewards(m, false, None, None)?;
+		assert_eq!(rewards.len(), 1);
+		assert!(rewards[0].output.is_foundation);
+		assert_eq!(rewards[0].output.height, 3);
+
+		// regular coinbase outputs aren't reported
+		let (_, outputs) = api.retrieve_outputs(m, true, false, false, None)?;
+		assert_eq!(
+			outputs.iter().filter(|m| !m.output.is_foundation).count(),
+			2
+		);
+
+		// and the height range is respected
+		let (_, rewards) = api.retrieve_foundation_rewards(m, false, Some(4), None)?;
+		assert!(rewards.is_empty());
+		let (_, rewards) = api.retrieve_foundation_rewards(m, false, None, Some(2))?;
+		assert!(rewards.is_empty());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn foundation() {
+	let test_dir = "test_output/foundation";
+	setup(test_dir);
+	if let Err(e) = foundation_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,118 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test that retried owner calls carrying an idempotency key are deduplicated
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, Slate, SlateVersion, VersionedSlate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn idempotency_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: true,
+		..Default::default()
+	};
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let init_with = |key: Option<&str>, args: &InitTxArgs| {
+			api.with_idempotency_key(m, "init_send_tx", key, args, || {
+				let slate = api.init_send_tx(m, args.clone())?;
+				Ok(VersionedSlate::into_version(slate, SlateVersion::V3))
+			})
+		};
+		let init = |key: Option<&str>| init_with(key, &args);
+		let first = Slate::from(init(Some("withdrawal-42"))?);
+		let retried = Slate::from(init(Some("withdrawal-42"))?);
+		assert_eq!(first.id, retried.id);
+		assert_eq!(first.tx.kernels(), retried.tx.kernels());
+
+		// a different key, or no key at all, is a new request
+		let other = Slate::from(init(Some("withdrawal-43"))?);
+		assert_ne!(first.id, other.id);
+		let unkeyed = Slate::from(init(None)?);
+		assert_ne!(first.id, unkeyed.id);
+
+		// reusing a key for a call with other arguments is refused
+		let other_args = InitTxArgs {
+			amount: Amount::from_freemans(reward / 2),
+			..args.clone()
+		};
+		match init_with(Some("withdrawal-42"), &other_args) {
+			Err(e) => match e.kind() {
+				ErrorKind::IdempotencyKeyReused(method, key) => {
+					assert_eq!(method, "init_send_tx");
+					assert_eq!(key, "withdrawal-42");
+				}
+				k => panic!("unexpected error: {}", k),
+			},
+			Ok(_) => panic!("key reused with other arguments"),
+		}
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn idempotency() {
+	let test_dir = "test_output/idempotency";
+	setup(test_dir);
+	if let Err(e) = idempotency_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
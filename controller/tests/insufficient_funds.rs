@@ -0,0 +1,128 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the funds breakdown reported when a transaction can't be funded
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn insufficient_funds_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let mut args = InitTxArgs {
+		src_acct_name: None,
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+	let mut height = 0;
+	let mut enough_at = 0;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, info) = api.retrieve_summary_info(m, true, 2)?;
+		height = api.node_height(m)?.height;
+		assert!(info.amount_immature > 0);
+
+		// more than is spendable now, but covered by the next reward to mature
+		args.amount =
+			info.amount_currently_spendable + Amount::from_freemans(core::consensus::EPIC_BASE);
+		match api.init_send_tx(m, args.clone()).map_err(|e| e.kind()) {
+			Err(ErrorKind::NotEnoughFunds {
+				available,
+				needed,
+				spendable,
+				immature,
+				locked,
+				awaiting_confirmation,
+				shortfall,
+				enough_at_height,
+				..
+			}) => {
+				assert_eq!(spendable, info.amount_currently_spendable);
+				assert_eq!(immature, info.amount_immature);
+				assert_eq!(locked, 0);
+				assert_eq!(awaiting_confirmation, info.amount_awaiting_confirmation);
+				assert_eq!(shortfall, needed - available);
+				assert!(needed > spendable);
+				enough_at = enough_at_height.unwrap();
+				assert!(enough_at > height);
+			}
+			r => panic!("expected not enough funds, got {:?}", r.map(|s| s.id)),
+		}
+		Ok(())
+	})?;
+
+	// once that height is reached, the same transaction can be funded
+	let _ = test_framework::award_blocks_to_wallet(
+		&chain,
+		wallet1.clone(),
+		mask1,
+		(enough_at - height) as usize,
+		false,
+	);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		assert!(api.node_height(m)?.height >= enough_at);
+		api.init_send_tx(m, args)?;
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn insufficient_funds() {
+	let test_dir = "test_output/insufficient_funds";
+	setup(test_dir);
+	if let Err(e) = insufficient_funds_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
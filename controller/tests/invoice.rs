@@ -21,7 +21,7 @@ use epic_wallet_libwallet as libwallet;
 use epic_wallet_util::epic_core as core;
 
 use impls::test_framework::{self, LocalWalletClient};
-use libwallet::{InitTxArgs, IssueInvoiceTxArgs, Slate};
+use libwallet::{Amount, ErrorKind, InitTxArgs, IssueInvoiceTxArgs, Slate};
 use std::thread;
 use std::time::Duration;
 
@@ -29,6 +29,16 @@ use std::time::Duration;
 mod common;
 use common::{clean_output_dir, create_wallet_proxy, setup};
 
+fn expect_refused(res: Result<Slate, libwallet::Error>) {
+	match res {
+		Err(e) => match e.kind() {
+			ErrorKind::PolicyRefused(_) => {}
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("invoice was paid"),
+	}
+}
+
 /// self send impl
 fn invoice_tx_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	// Create a new proxy to simulate server and wallet responses
@@ -98,7 +108,7 @@ fn invoice_tx_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
 		// Wallet 2 inititates an invoice transaction, requesting payment
 		let args = IssueInvoiceTxArgs {
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			..Default::default()
 		};
 		slate = api.issue_invoice_tx(m, args)?;
@@ -116,6 +126,34 @@ fn invoice_tx_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 			selection_strategy_is_use_all: true,
 			..Default::default()
 		};
+
+		// invoices for another amount than expected, or costing more in fees
+		// than allowed, aren't paid
+		let other_amount = InitTxArgs {
+			amount: Amount::from_freemans(reward),
+			..args.clone()
+		};
+		expect_refused(api.process_invoice_tx(m, &slate, other_amount));
+		let max_fee = InitTxArgs {
+			max_invoice_fee: Some(1),
+			..args.clone()
+		};
+		expect_refused(api.process_invoice_tx(m, &slate, max_fee));
+		api.set_max_invoice_fee(Some(1));
+		expect_refused(api.process_invoice_tx(m, &slate, args.clone()));
+		api.set_max_invoice_fee(Some(reward));
+
+		// nor are invoices already setting a fee
+		let mut with_fee = slate.clone();
+		with_fee.fee = reward;
+		match api.process_invoice_tx(m, &with_fee, args.clone()) {
+			Err(e) => match e.kind() {
+				ErrorKind::InvalidSlate(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("invoice was paid"),
+		}
+
 		slate = api.process_invoice_tx(m, &slate, args)?;
 		api.tx_lock_outputs(m, &slate, 0)?;
 		Ok(())
@@ -171,7 +209,7 @@ fn invoice_tx_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
 		// Wallet 1 inititates an invoice transaction, requesting payment
 		let args = IssueInvoiceTxArgs {
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			..Default::default()
 		};
 		slate = api.issue_invoice_tx(m, args)?;
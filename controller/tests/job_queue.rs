@@ -0,0 +1,195 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test queued jobs: posts left over from a crash, failed jobs being retried
+//! and set aside as dead letters
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use chrono::Utc;
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, JobAction, Slate, TxPostState, JOB_MAX_ATTEMPTS};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn job_queue_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	// builds, receives and finalizes a send without posting it
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+			slate = api.receive_tx(&slate, Some("listener"), None)?;
+			Ok(())
+		})?;
+		slate = api.finalize_tx(m, &slate)?;
+		Ok(())
+	})?;
+
+	// a post queued before a crash is made by the next run of the queue
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let action = JobAction::PostTx {
+			tx_slate_id: slate.id,
+			fluff: false,
+		};
+		let job = api.queue_job(m, action.clone())?;
+		let results = api.process_jobs(m)?;
+		assert_eq!(results.len(), 1);
+		assert_eq!(results[0].job.id, job.id);
+		assert!(results[0].error.is_none());
+		assert!(api.retrieve_jobs(m)?.is_empty());
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		assert!(txs
+			.iter()
+			.any(|t| t.post_state == Some(TxPostState::Posted)));
+
+		// and isn't made twice
+		api.queue_job(m, action)?;
+		let results = api.process_jobs(m)?;
+		assert_eq!(results.len(), 1);
+		assert!(results[0].error.is_none());
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert!(refreshed);
+		assert!(txs.iter().all(|t| t.confirmed));
+		Ok(())
+	})?;
+
+	// a failed job waits before it's tried again, and is given up on after
+	// failing too many times
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let action = JobAction::Webhook {
+			url: "http://127.0.0.1:1/received".to_owned(),
+			body: serde_json::json!({ "tx_slate_id": slate.id }),
+		};
+		let job = api.queue_job(m, action)?;
+		let results = api.process_jobs(m)?;
+		assert_eq!(results.len(), 1);
+		assert!(results[0].error.is_some());
+		assert_eq!(results[0].job.attempts, 1);
+		assert!(!results[0].job.dead);
+		assert!(api.process_jobs(m)?.is_empty());
+
+		for attempt in 2..=JOB_MAX_ATTEMPTS {
+			// as if the wait was over
+			{
+				wallet_inst!(wallet1, w);
+				let mut job = w.job_iter().next().unwrap();
+				assert!(!job.dead);
+				job.next_attempt_ts = Utc::now();
+				let mut batch = w.batch(m)?;
+				batch.save_job(&job)?;
+				batch.commit()?;
+			}
+			let results = api.process_jobs(m)?;
+			assert_eq!(results.len(), 1);
+			assert_eq!(results[0].job.attempts, attempt);
+		}
+		let jobs = api.retrieve_jobs(m)?;
+		assert_eq!(jobs.len(), 1);
+		assert!(jobs[0].dead);
+		assert!(jobs[0].last_error.is_some());
+		assert!(api.process_jobs(m)?.is_empty());
+
+		// dead letters can be retried by hand, or deleted
+		let retried = api.retry_job(m, &job.id)?;
+		assert_eq!(retried.attempts, 0);
+		assert!(!retried.dead);
+		api.delete_job(m, &job.id)?;
+		assert!(api.retrieve_jobs(m)?.is_empty());
+		assert!(api.delete_job(m, &job.id).is_err());
+
+		// webhooks can be disallowed, as get_capabilities then reports
+		assert!(api.get_capabilities()?.webhooks.enabled);
+		api.set_allow_webhooks(false);
+		let webhooks = api.get_capabilities()?.webhooks;
+		assert!(webhooks.compiled && !webhooks.enabled);
+		let action = JobAction::Webhook {
+			url: "http://127.0.0.1:1/received".to_owned(),
+			body: serde_json::json!({ "tx_slate_id": slate.id }),
+		};
+		assert!(api.queue_job(m, action).is_err());
+		assert!(api.retrieve_jobs(m)?.is_empty());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn job_queue() {
+	let test_dir = "test_output/job_queue";
+	setup(test_dir);
+	if let Err(e) = job_queue_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,147 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test listing and force unlocking locked outputs
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, OutputStatus, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn locked_outputs_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	let refused = |res: Result<_, libwallet::Error>| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::UnlockRefused(_) => true,
+			_ => false,
+		},
+		Ok(_) => false,
+	};
+
+	// a lock held by a transaction awaiting its response
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		assert!(api.get_locked_outputs(m, true)?.1.is_empty());
+		slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		let (_, locked) = api.get_locked_outputs(m, true)?;
+		assert_eq!(locked.len(), 1);
+		let l = &locked[0];
+		let tx = l.locked_by.as_ref().unwrap();
+		assert_eq!(tx.tx_slate_id, Some(slate.id));
+		assert_eq!(l.locked_since, Some(tx.creation_ts));
+		assert!(!l.stale);
+		let commit = l.output.output.commit.clone().unwrap();
+
+		// pending, so only released when asked to
+		assert!(refused(api.force_unlock(m, &commit, false)));
+		let unlocked = api.force_unlock(m, &commit, true)?;
+		assert_eq!(unlocked.locked_by.unwrap().id, tx.id);
+		assert!(api.get_locked_outputs(m, true)?.1.is_empty());
+
+		// not locked any more
+		assert!(refused(api.force_unlock(m, &commit, true)));
+		Ok(())
+	})?;
+
+	// a lock left behind by a transaction that's gone from the log
+	let commit = {
+		wallet_inst!(wallet1, w);
+		let mut o = w
+			.iter()
+			.find(|o| o.status == OutputStatus::Unspent)
+			.unwrap();
+		o.status = OutputStatus::Locked;
+		o.spent_by_tx = Some(1000);
+		let commit = o.commit.clone().unwrap();
+		let mut batch = w.batch(mask1)?;
+		batch.save(o)?;
+		batch.commit()?;
+		commit
+	};
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, locked) = api.get_locked_outputs(m, false)?;
+		let l = locked
+			.iter()
+			.find(|l| l.output.output.commit.as_ref() == Some(&commit))
+			.unwrap();
+		assert!(l.stale);
+		assert!(l.locked_by.is_none());
+		assert!(l.locked_since.is_none());
+		api.force_unlock(m, &commit, false)?;
+		let (_, locked) = api.get_locked_outputs(m, false)?;
+		assert!(locked
+			.iter()
+			.all(|l| l.output.output.commit.as_ref() != Some(&commit)));
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn locked_outputs() {
+	let test_dir = "test_output/locked_outputs";
+	setup(test_dir);
+	if let Err(e) = locked_outputs_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
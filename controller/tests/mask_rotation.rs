@@ -0,0 +1,104 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test rotating the keychain mask of an open wallet
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+extern crate epic_wallet_libwallet as libwallet;
+
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::ErrorKind;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn mask_rotation_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let bh = 5u64;
+	let _ =
+		test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, bh as usize, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let (_, info) = owner_api.retrieve_summary_info(mask1, true, 1)?;
+	let total = info.total;
+	assert!(total > 0);
+
+	// not while the updater still holds the old mask
+	owner_api.start_updater(mask1, Duration::from_secs(60))?;
+	assert!(owner_api.rotate_keychain_mask(mask1).is_err());
+	owner_api.stop_updater()?;
+	thread::sleep(Duration::from_secs(2));
+
+	let new_mask_i = owner_api.rotate_keychain_mask(mask1)?;
+	let new_mask = (&new_mask_i).as_ref();
+	assert!(new_mask.is_some());
+	assert_ne!(new_mask, mask1);
+
+	// the old mask is no longer accepted
+	match owner_api.retrieve_summary_info(mask1, false, 1) {
+		Err(e) => match e.kind() {
+			ErrorKind::InvalidKeychainMask => {}
+			k => panic!("unexpected error: {}", k),
+		},
+		Ok(_) => panic!("old keychain mask still accepted"),
+	}
+	assert!(owner_api.rotate_keychain_mask(mask1).is_err());
+
+	// while the new one unlocks the same wallet
+	let (_, info) = owner_api.retrieve_summary_info(new_mask, false, 1)?;
+	assert_eq!(info.total, total);
+
+	// without a mask there is nothing to rotate
+	assert!(owner_api.rotate_keychain_mask(None).is_err());
+
+	// let logs catch up
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn mask_rotation() {
+	let test_dir = "test_output/mask_rotation";
+	setup(test_dir);
+	if let Err(e) = mask_rotation_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,108 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test no copy of the mnemonic is left in memory once the wallet is closed.
+//! Reads the process memory, so it's only built with the memory_scan feature,
+//! on Linux
+#![cfg(all(feature = "memory_scan", target_os = "linux"))]
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use epic_wallet_util::epic_util::ZeroingString;
+use impls::test_framework::LocalWalletClient;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_local_wallet, create_wallet_proxy, setup};
+
+// the mnemonic written backwards, so only the wallet ever holds it as it is
+const REVERSED_MNEMONIC: &str = "hcnul ecaep ecarbme iwik yad yroeht kniw nus ekib blub \
+	yraurbef gnirud txen emalf lufwa esnemmi ydnac kcehc llehs tegrof eerged naem ytnewt taf";
+
+// addresses in the heap, stacks and other anonymous memory holding the mnemonic
+fn find_mnemonic() -> Vec<usize> {
+	let reversed = REVERSED_MNEMONIC.as_bytes();
+	let maps = fs::read_to_string("/proc/self/maps").unwrap();
+	let mut mem = File::open("/proc/self/mem").unwrap();
+	let mut found = vec![];
+	for line in maps.lines() {
+		let fields: Vec<&str> = line.split_whitespace().collect();
+		let anonymous =
+			fields.len() < 6 || fields[5] == "[heap]" || fields[5].starts_with("[stack");
+		if !fields[1].starts_with('r') || !anonymous {
+			continue;
+		}
+		let mut range = fields[0]
+			.split('-')
+			.map(|a| usize::from_str_radix(a, 16).unwrap());
+		let (start, end) = (range.next().unwrap(), range.next().unwrap());
+		let mut region = vec![0; end - start];
+		if mem.seek(SeekFrom::Start(start as u64)).is_err() || mem.read_exact(&mut region).is_err()
+		{
+			continue;
+		}
+		found.extend(
+			region
+				.windows(reversed.len())
+				.enumerate()
+				.filter(|(_, w)| w.iter().eq(reversed.iter().rev()))
+				.map(|(i, _)| start + i),
+		);
+	}
+	found
+}
+
+fn memory_hygiene_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let wallet_proxy = create_wallet_proxy(test_dir);
+	let client1 = LocalWalletClient::new("wallet1", wallet_proxy.tx.clone());
+
+	// sized up front, so it's never copied while growing
+	let mut mnemonic = String::with_capacity(REVERSED_MNEMONIC.len());
+	mnemonic.extend(REVERSED_MNEMONIC.chars().rev());
+	let (wallet1, mask1_i) = create_local_wallet(
+		test_dir,
+		"wallet1",
+		Some(ZeroingString::from(mnemonic)),
+		client1,
+		true,
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, info) = api.retrieve_summary_info(m, false, 1)?;
+		assert_eq!(info.total, 0);
+		let recovered = api.get_mnemonic(None, ZeroingString::from(""))?;
+		assert!(recovered.bytes().eq(REVERSED_MNEMONIC.bytes().rev()));
+		api.close_wallet(None)?;
+		Ok(())
+	})?;
+	drop(wallet1);
+	drop(wallet_proxy);
+
+	assert_eq!(find_mnemonic(), Vec::<usize>::new());
+	Ok(())
+}
+
+#[test]
+fn memory_hygiene() {
+	let test_dir = "test_output/memory_hygiene";
+	setup(test_dir);
+	if let Err(e) = memory_hygiene_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,151 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test issuing merchant invoices and following their payment
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{ErrorKind, InitTxArgs, MerchantInvoiceStatus, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn merchant_invoices_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		merchant,
+		mask2_i,
+		test_dir,
+		"merchant",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+	let amount = core::consensus::EPIC_BASE * 2;
+
+	// the merchant issues an invoice, which starts out unpaid
+	let mut slate = Slate::blank(2);
+	let mut id = slate.id;
+	wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+		match api.create_merchant_invoice(m, 0, None) {
+			Err(e) => match e.kind() {
+				ErrorKind::Amount(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("issued an invoice for nothing"),
+		}
+		let invoice = api.create_merchant_invoice(m, amount, Some("order #42".to_owned()))?;
+		assert_eq!(invoice.amount, amount);
+		assert_eq!(invoice.memo, Some("order #42".to_owned()));
+		assert_eq!(invoice.status, MerchantInvoiceStatus::Unpaid);
+		id = invoice.id;
+		slate = Slate::from(invoice.slate.unwrap());
+		assert_eq!(slate.id, id);
+
+		// only the slate is left out when the invoice is looked up
+		let invoice = api.retrieve_merchant_invoice(m, false, id)?;
+		assert_eq!(invoice.status, MerchantInvoiceStatus::Unpaid);
+		assert!(invoice.slate.is_none());
+		assert!(api
+			.retrieve_merchant_invoice(m, false, Slate::blank(2).id)
+			.is_err());
+		Ok(())
+	})?;
+
+	// the customer pays
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let args = InitTxArgs {
+			src_acct_name: None,
+			amount: slate.amount,
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: true,
+			..Default::default()
+		};
+		slate = api.process_invoice_tx(m, &slate, args)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		Ok(())
+	})?;
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		slate = api.finalize_invoice_tx(&slate)?;
+		Ok(())
+	})?;
+	wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+		let invoice = api.retrieve_merchant_invoice(m, false, id)?;
+		assert_eq!(invoice.status, MerchantInvoiceStatus::Paid);
+		assert!(invoice.confirmed_at.is_none());
+		Ok(())
+	})?;
+
+	// and the payment is confirmed once mined
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+	wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+		let invoice = api.retrieve_merchant_invoice(m, true, id)?;
+		assert_eq!(invoice.status, MerchantInvoiceStatus::Confirmed);
+		assert!(invoice.confirmed_at.is_some());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn merchant_invoices() {
+	let test_dir = "test_output/merchant_invoices";
+	setup(test_dir);
+	if let Err(e) = merchant_invoices_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
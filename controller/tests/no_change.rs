@@ -21,7 +21,7 @@ use epic_wallet_util::epic_core as core;
 
 use epic_wallet_libwallet as libwallet;
 use impls::test_framework::{self, LocalWalletClient};
-use libwallet::{InitTxArgs, IssueInvoiceTxArgs, Slate};
+use libwallet::{Amount, InitTxArgs, IssueInvoiceTxArgs, Slate};
 use std::thread;
 use std::time::Duration;
 
@@ -78,7 +78,7 @@ fn no_change_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: reward - fee,
+			amount: Amount::from_freemans(reward - fee),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -107,7 +107,7 @@ fn no_change_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
 		// Wallet 2 inititates an invoice transaction, requesting payment
 		let args = IssueInvoiceTxArgs {
-			amount: reward - fee,
+			amount: Amount::from_freemans(reward - fee),
 			..Default::default()
 		};
 		slate = api.issue_invoice_tx(m, args)?;
@@ -0,0 +1,123 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test opening a wallet without its node, and pinging the node once it's open
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+use epic_wallet_util::epic_util::ZeroingString;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn node_ping_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let ping = owner_api.node_ping(mask1)?;
+	assert!(ping.reachable);
+	assert_eq!(ping.height, Some(owner_api.node_height(mask1)?.height));
+	assert!(ping.header_hash.is_some());
+	assert!(ping.error.is_none());
+
+	// the wallet opens, and shows its history, while the node is away
+	client1.set_node_offline(true);
+	owner_api.close_wallet(None)?;
+	let mask = owner_api.open_wallet(None, ZeroingString::from(""), false)?;
+	let m = mask.as_ref();
+	let (refreshed, txs) = owner_api.retrieve_txs(m, true, None, None)?;
+	assert!(!refreshed);
+	assert!(!txs.is_empty());
+
+	let ping = owner_api.node_ping(m)?;
+	assert!(!ping.reachable);
+	assert_eq!(ping.height, None);
+	assert!(ping.error.is_some());
+
+	// a post queued meanwhile is made by the first ping to reach the node
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+	let mut slate: Slate = owner_api.init_send_tx(m, args)?;
+	owner_api.tx_lock_outputs(m, &slate, 0)?;
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		slate = api.receive_tx(&slate, Some("listener"), None)?;
+		Ok(())
+	})?;
+	slate = owner_api.finalize_tx(m, &slate)?;
+	assert!(!owner_api.post_or_queue_tx(m, &slate.tx, false)?);
+	assert_eq!(owner_api.retrieve_post_queue(m)?.len(), 1);
+
+	client1.set_node_offline(false);
+	let ping = owner_api.node_ping(m)?;
+	assert!(ping.reachable);
+	assert!(owner_api.retrieve_post_queue(m)?.is_empty());
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn node_ping() {
+	let test_dir = "test_output/node_ping";
+	setup(test_dir);
+	if let Err(e) = node_ping_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,124 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test owner API calls abandoned on a node that never answers
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+extern crate epic_wallet_libwallet as libwallet;
+
+use epic_wallet_util::epic_core as core;
+use epic_wallet_util::epic_keychain as keychain;
+use epic_wallet_util::epic_util as util;
+
+use self::core::global;
+use self::core::global::ChainTypes;
+use self::keychain::ExtKeychain;
+use api::{Owner, OwnerRpc};
+use easy_jsonrpc_mw::{Handler, MaybeReply};
+use impls::{DefaultLCProvider, DefaultWalletImpl, HTTPNodeClient, NodeRetryPolicy};
+use libwallet::WalletInst;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use util::{Mutex, ZeroingString};
+use wallet::controller::{RpcTimeouts, RPC_TIMEOUT_ERROR_CODE};
+
+fn reply(r: MaybeReply) -> serde_json::Value {
+	match r {
+		MaybeReply::Reply(v) => v,
+		MaybeReply::DontReply => panic!("no reply"),
+	}
+}
+
+fn node_stall_test_impl(test_dir: &'static str) {
+	// a node accepting connections, and never answering on them
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let node_url = format!("http://{}", listener.local_addr().unwrap());
+	thread::spawn(move || {
+		let mut held = vec![];
+		for stream in listener.incoming() {
+			held.push(stream);
+		}
+	});
+
+	let mut client = HTTPNodeClient::new(&node_url, None);
+	client.set_retry_policy(NodeRetryPolicy {
+		max_attempts: 1,
+		breaker_threshold: 0,
+		request_timeout: Some(Duration::from_millis(500)),
+		..Default::default()
+	});
+	let mut wallet = Box::new(DefaultWalletImpl::<HTTPNodeClient>::new(client).unwrap())
+		as Box<
+			dyn WalletInst<
+				DefaultLCProvider<'static, HTTPNodeClient, ExtKeychain>,
+				HTTPNodeClient,
+				ExtKeychain,
+			>,
+		>;
+	let lc = wallet.lc_provider().unwrap();
+	let _ = lc.set_top_level_directory(&format!("{}/wallet1", test_dir));
+	lc.create_wallet(None, None, 32, ZeroingString::from(""), false)
+		.unwrap();
+	lc.open_wallet(None, ZeroingString::from(""), false, false)
+		.unwrap();
+	let wallet1 = Arc::new(Mutex::new(wallet));
+
+	let timeouts = RpcTimeouts::new(
+		Some(Duration::from_secs(5)),
+		Some(Duration::from_millis(100)),
+		HashMap::new(),
+	);
+	let call = |method: &str| {
+		let req = json!({"jsonrpc": "2.0", "method": method, "params": [], "id": 1});
+		let api = Owner::new(wallet1.clone());
+		let r = req.clone();
+		reply(timeouts.call(&req, move || (&api as &dyn OwnerRpc).handle_request(r)))
+	};
+
+	// asking the stalled node for its height is abandoned, while still
+	// holding the wallet
+	let res = call("node_height");
+	assert_eq!(res["error"]["code"], RPC_TIMEOUT_ERROR_CODE);
+	assert_eq!(timeouts.abandoned_calls(), 1);
+
+	// the node request gives up on its own, so a later call gets the wallet
+	let start = Instant::now();
+	let res = call("accounts");
+	assert!(res["error"].is_null(), "{}", res);
+	assert!(res["result"]["Ok"].is_array());
+	assert!(start.elapsed() < Duration::from_secs(5));
+	for _ in 0..50 {
+		if timeouts.abandoned_calls() == 0 {
+			break;
+		}
+		thread::sleep(Duration::from_millis(100));
+	}
+	assert_eq!(timeouts.abandoned_calls(), 0);
+}
+
+#[test]
+fn node_stall() {
+	let test_dir = "test_output/node_stall";
+	util::init_test_logger();
+	let _ = fs::remove_dir_all(test_dir);
+	global::set_mining_mode(ChainTypes::AutomatedTesting);
+	node_stall_test_impl(test_dir);
+	let _ = fs::remove_dir_all(test_dir);
+}
@@ -0,0 +1,143 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test building transactions without a node, and queuing their posts
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate, TxPostState};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn offline_queue_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+	let mut known_height = 0;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, _) = api.retrieve_summary_info(m, true, 1)?;
+		assert!(refreshed);
+		known_height = api.node_height(m)?.height;
+		Ok(())
+	})?;
+
+	client1.set_node_offline(true);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	// local operations carry on without the node, and a transaction can be
+	// built from the outputs already known
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, txs) = api.retrieve_txs(m, true, None, None)?;
+		assert!(!refreshed);
+		assert!(!txs.is_empty());
+
+		slate = api.init_send_tx(m, args.clone())?;
+		assert_eq!(slate.height, known_height);
+		api.tx_lock_outputs(m, &slate, 0)?;
+		wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+			slate = api.receive_tx(&slate, Some("listener"), None)?;
+			Ok(())
+		})?;
+		slate = api.finalize_tx(m, &slate)?;
+
+		// its post is queued, and stays queued while the node is away
+		assert!(api.post_tx(m, &slate.tx, false).is_err());
+		assert!(!api.post_or_queue_tx(m, &slate.tx, false)?);
+		let queue = api.retrieve_post_queue(m)?;
+		assert_eq!(queue.len(), 1);
+		assert_eq!(queue[0].tx_slate_id, Some(slate.id));
+		assert_eq!(queue[0].post_state, Some(TxPostState::Queued));
+		assert!(api.recover_pending_posts(m)?.is_empty());
+		assert_eq!(api.retrieve_post_queue(m)?.len(), 1);
+		Ok(())
+	})?;
+
+	// once the node is back, the queue is posted
+	client1.set_node_offline(false);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let resolved = api.recover_pending_posts(m)?;
+		assert_eq!(resolved.len(), 1);
+		assert_eq!(resolved[0].post_state, Some(TxPostState::Posted));
+		assert!(api.retrieve_post_queue(m)?.is_empty());
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert!(refreshed);
+		assert!(txs[0].confirmed);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn offline_queue() {
+	let test_dir = "test_output/offline_queue";
+	setup(test_dir);
+	if let Err(e) = offline_queue_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
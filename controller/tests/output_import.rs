@@ -0,0 +1,124 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test importing a single output the wallet has lost, by key path and value
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{ErrorKind, OutputData, OutputStatus};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn output_import_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let (_, info_before) = owner_api.retrieve_summary_info(mask1, true, 1)?;
+	let (_, outputs) = owner_api.retrieve_outputs(mask1, false, true, false, None)?;
+	let kept = outputs[0].output.clone();
+	let lost = outputs[1].output.clone();
+
+	// lose an output from the wallet's database
+	{
+		wallet_inst!(wallet1, w);
+		let mut batch = w.batch(mask1)?;
+		batch.delete(&lost.key_id, &lost.mmr_index, &None)?;
+		batch.commit()?;
+	}
+	let (_, info) = owner_api.retrieve_summary_info(mask1, false, 1)?;
+	assert_eq!(info.total, info_before.total.freemans() - lost.value);
+
+	let refused = |res: Result<OutputData, libwallet::Error>| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::ImportOutput(_) => {}
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("output imported"),
+	};
+	let path = lost.key_id.to_bip_32_string();
+
+	// the key path must be an output's, and the output must be unspent on
+	// chain at the height given and not already in the wallet
+	refused(owner_api.import_output(mask1, "m/0/0", lost.value, lost.height));
+	refused(owner_api.import_output(mask1, "0/0/1", lost.value, lost.height));
+	refused(owner_api.import_output(mask1, "m/0/0/x", lost.value, lost.height));
+	refused(owner_api.import_output(mask1, &path, lost.value + 1, lost.height));
+	refused(owner_api.import_output(mask1, &path, lost.value, lost.height + 1));
+	refused(owner_api.import_output(
+		mask1,
+		&kept.key_id.to_bip_32_string(),
+		kept.value,
+		kept.height,
+	));
+
+	let imported = owner_api.import_output(mask1, &path, lost.value, lost.height)?;
+	assert_eq!(imported.commit, lost.commit);
+	assert_eq!(imported.key_id, lost.key_id);
+	assert_eq!(imported.n_child, lost.n_child);
+	assert_eq!(imported.mmr_index, lost.mmr_index);
+	assert_eq!(imported.status, OutputStatus::Unspent);
+	assert_eq!(imported.is_coinbase, lost.is_coinbase);
+	assert_eq!(imported.lock_height, lost.lock_height);
+	assert!(imported.tx_log_entry.is_some());
+
+	// the wallet's balance is whole again, and the output can't be imported twice
+	let (_, info) = owner_api.retrieve_summary_info(mask1, true, 1)?;
+	assert_eq!(info.total, info_before.total);
+	refused(owner_api.import_output(mask1, &path, lost.value, lost.height));
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn output_import() {
+	let test_dir = "test_output/output_import";
+	setup(test_dir);
+	if let Err(e) = output_import_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
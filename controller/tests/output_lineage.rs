@@ -0,0 +1,166 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test output lineage: spend events and history of spent and deleted outputs
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, OutputStatus, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn output_lineage_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+
+	let mut bh = 10u64;
+	let _ =
+		test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, bh as usize, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward * 2),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: true,
+		..Default::default()
+	};
+
+	// a cancelled send unlocks its inputs and deletes its change output
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.retrieve_summary_info(m, true, 1)?;
+		let slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+
+		let (_, lineage) = api.retrieve_output_lineage(m, false, None)?;
+		let locked = lineage
+			.iter()
+			.filter(|l| l.output.output.status == OutputStatus::Locked)
+			.collect::<Vec<_>>();
+		assert!(!locked.is_empty());
+		for l in locked.iter() {
+			let spent_by = l.spent_by.as_ref().unwrap();
+			assert_eq!(spent_by.tx_type, TxLogEntryType::TxSent);
+			assert_eq!(spent_by.tx_slate_id, Some(slate.id));
+			assert_eq!(l.spent_height, None);
+			assert_eq!(l.history.last().unwrap().status, OutputStatus::Unspent);
+		}
+
+		api.cancel_tx(m, None, Some(slate.id), false)?;
+		let (_, lineage) = api.retrieve_output_lineage(m, false, None)?;
+		assert!(lineage
+			.iter()
+			.filter(|l| l.output.output.status != OutputStatus::Deleted)
+			.all(|l| l.spent_by.is_none()));
+		let deleted = lineage
+			.iter()
+			.filter(|l| l.output.output.status == OutputStatus::Deleted)
+			.collect::<Vec<_>>();
+		assert_eq!(deleted.len(), 1);
+		let spent_by = deleted[0].spent_by.as_ref().unwrap();
+		assert_eq!(spent_by.tx_type, TxLogEntryType::TxSentCancelled);
+		assert_eq!(deleted[0].history[0].status, OutputStatus::Unconfirmed);
+
+		// the same lineage can be retrieved for the cancelled transaction only
+		let (_, by_tx) = api.retrieve_output_lineage(m, false, Some(spent_by.id))?;
+		assert_eq!(by_tx.len(), locked.len() + 1);
+		Ok(())
+	})?;
+
+	// a confirmed send records the height its inputs were spent at
+	let mut spend_tx_id = None;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let mut slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+			slate = api.receive_tx(&slate, Some("listener"), None)?;
+			Ok(())
+		})?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?; // mines a block
+		bh += 1;
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		spend_tx_id = Some(txs[0].id);
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, lineage) = api.retrieve_output_lineage(m, true, None)?;
+		assert!(refreshed);
+		let spent = lineage
+			.iter()
+			.filter(|l| l.output.output.status == OutputStatus::Spent)
+			.collect::<Vec<_>>();
+		assert!(!spent.is_empty());
+		for l in spent {
+			assert_eq!(l.output.output.spent_by_tx, spend_tx_id);
+			assert_eq!(l.spent_by.as_ref().map(|t| t.id), spend_tx_id);
+			assert_eq!(l.spent_height, Some(bh));
+			assert!(l.history.iter().any(|o| o.status == OutputStatus::Locked));
+		}
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn output_lineage() {
+	let test_dir = "test_output/output_lineage";
+	setup(test_dir);
+	if let Err(e) = output_lineage_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
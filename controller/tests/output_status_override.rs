@@ -0,0 +1,164 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test overriding the status of outputs by hand, and its audit trail
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use api::OwnerRpcS;
+use easy_jsonrpc_mw::{Handler, MaybeReply};
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{ErrorKind, OutputStatus};
+use serde_json::json;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn output_status_override_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let (_, outputs) = owner_api.retrieve_outputs(mask1, false, true, false, None)?;
+	let output = outputs
+		.into_iter()
+		.find(|o| o.output.status == OutputStatus::Unspent)
+		.unwrap()
+		.output;
+	let commit = output.commit.clone().unwrap();
+	let reason = "ticket 1234: output stuck locked";
+
+	let refused = |res: Result<libwallet::OutputData, libwallet::Error>| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::OutputStatusOverride(_) => {}
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("output status overridden"),
+	};
+
+	// overrides must be enabled
+	refused(owner_api.set_output_status(mask1, &commit, OutputStatus::Locked, reason));
+	owner_api.set_allow_output_status_override(true);
+
+	// and need a reason, a settable status, an output of the account and a change
+	refused(owner_api.set_output_status(mask1, &commit, OutputStatus::Locked, " "));
+	refused(owner_api.set_output_status(mask1, &commit, OutputStatus::Deleted, reason));
+	refused(owner_api.set_output_status(mask1, "08ab", OutputStatus::Locked, reason));
+	refused(owner_api.set_output_status(mask1, &commit, OutputStatus::Unspent, reason));
+	assert!(owner_api.retrieve_audit_log(mask1)?.is_empty());
+
+	let locked = owner_api.set_output_status(mask1, &commit, OutputStatus::Locked, reason)?;
+	assert_eq!(locked.status, OutputStatus::Locked);
+	let (_, info) = owner_api.retrieve_summary_info(mask1, false, 1)?;
+	assert_eq!(info.amount_locked, output.value);
+
+	let unspent = owner_api.set_output_status(mask1, &commit, OutputStatus::Unspent, "fixed")?;
+	assert_eq!(unspent.status, OutputStatus::Unspent);
+	assert_eq!(unspent.spent_by_tx, None);
+	let (_, info) = owner_api.retrieve_summary_info(mask1, false, 1)?;
+	assert_eq!(info.amount_locked, 0);
+
+	// both changes are in the audit trail, oldest first
+	let log = owner_api.retrieve_audit_log(mask1)?;
+	assert_eq!(log.len(), 2);
+	assert!(log
+		.iter()
+		.all(|e| e.action == "set_output_status" && e.target == commit));
+	assert_eq!(
+		(
+			log[0].before.as_str(),
+			log[0].after.as_str(),
+			log[0].reason.as_str()
+		),
+		("Unspent", "Locked", reason)
+	);
+	assert_eq!(
+		(
+			log[1].before.as_str(),
+			log[1].after.as_str(),
+			log[1].reason.as_str()
+		),
+		("Locked", "Unspent", "fixed")
+	);
+	assert!(log[0].timestamp <= log[1].timestamp);
+
+	// statuses given to the API are read strictly: one this version doesn't
+	// know of, or the placeholder for those, is refused rather than stored
+	for status in vec!["Frozen", "Unknown"] {
+		let req = json!({
+			"jsonrpc": "2.0",
+			"method": "set_output_status",
+			"params": {
+				"token": {"keychain_mask": null},
+				"commit": commit,
+				"status": status,
+				"reason": reason,
+			},
+			"id": 1
+		});
+		let res = match (&owner_api as &dyn OwnerRpcS).handle_request(req) {
+			MaybeReply::Reply(v) => v,
+			MaybeReply::DontReply => panic!("no reply"),
+		};
+		assert_eq!(res["error"]["code"], -32602, "{}", res);
+	}
+	assert_eq!(owner_api.retrieve_audit_log(mask1)?.len(), 2);
+	let (_, outputs) = owner_api.retrieve_outputs(mask1, false, false, false, None)?;
+	assert!(outputs
+		.iter()
+		.any(|o| o.output.commit.as_ref() == Some(&commit)
+			&& o.output.status == OutputStatus::Unspent));
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn output_status_override() {
+	let test_dir = "test_output/output_status_override";
+	setup(test_dir);
+	if let Err(e) = output_status_override_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
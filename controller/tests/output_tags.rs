@@ -0,0 +1,145 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test tagging outputs and restricting sends to a tag
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, OutputStatus};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn output_tags_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = |tag: &str| InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: true,
+		selection_tag: Some(tag.to_owned()),
+		..Default::default()
+	};
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, outputs) = api.retrieve_outputs(m, false, true, false, None)?;
+		let cold = outputs
+			.iter()
+			.find(|o| o.output.height == 1)
+			.unwrap()
+			.output
+			.commit
+			.clone()
+			.unwrap();
+
+		// tags are validated, and only outputs of the account can be tagged
+		match api.tag_outputs(m, vec![cold.clone()], "two words") {
+			Err(e) => match e.kind() {
+				ErrorKind::OutputTag(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("tagged outputs with an invalid tag"),
+		}
+		let res = api.tag_outputs(m, vec![cold.clone(), "00".repeat(33)], "cold");
+		assert!(res.is_err());
+		let (_, outputs) = api.retrieve_outputs(m, false, false, false, None)?;
+		assert!(outputs.iter().all(|o| o.output.tags.is_empty()));
+
+		// tagging twice leaves a single tag
+		api.tag_outputs(m, vec![cold.clone()], "cold")?;
+		let tagged = api.tag_outputs(m, vec![cold.clone()], "cold")?;
+		assert_eq!(tagged[0].output.tags, vec!["cold".to_owned()]);
+
+		// nothing carries the tag, so nothing can be spent
+		match api.init_send_tx(m, args("mining")) {
+			Err(e) => match e.kind() {
+				ErrorKind::NotEnoughFunds { spendable, .. } => assert_eq!(spendable, 0),
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("spent outputs not carrying the tag"),
+		}
+
+		// only the tagged output is spent, even when using all outputs,
+		// and the change stays in the group
+		let slate = api.init_send_tx(m, args("cold"))?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		assert_eq!(txs[0].num_inputs, 1);
+		let (_, outputs) = api.retrieve_outputs(m, false, false, false, Some(txs[0].id))?;
+		let input = outputs
+			.iter()
+			.find(|o| o.output.status == OutputStatus::Locked)
+			.unwrap();
+		assert_eq!(input.output.commit, Some(cold.clone()));
+		let change = outputs
+			.iter()
+			.find(|o| o.output.status == OutputStatus::Unconfirmed)
+			.unwrap();
+		assert_eq!(change.output.tags, vec!["cold".to_owned()]);
+
+		// untagging the change takes it out of the group
+		let change_commit = change.output.commit.clone().unwrap();
+		let untagged = api.untag_outputs(m, vec![change_commit], "cold")?;
+		assert!(untagged[0].output.tags.is_empty());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn output_tags() {
+	let test_dir = "test_output/output_tags";
+	setup(test_dir);
+	if let Err(e) = output_tags_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
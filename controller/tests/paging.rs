@@ -0,0 +1,127 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test paged retrieval of transactions and outputs
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::ErrorKind;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn paging_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 7, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, all_txs) = api.retrieve_txs(m, true, None, None)?;
+		assert_eq!(all_txs.len(), 7);
+
+		// walk through the tx log in pages of 3
+		let mut ids = vec![];
+		let mut page_sizes = vec![];
+		let mut cursor = None;
+		loop {
+			let (_, page) = api.retrieve_txs_page(m, false, cursor, 3)?;
+			page_sizes.push(page.txs.len());
+			ids.extend(page.txs.iter().map(|t| t.id));
+			match page.next_cursor {
+				Some(c) => cursor = Some(c),
+				None => break,
+			}
+		}
+		assert_eq!(page_sizes, vec![3, 3, 1]);
+		assert_eq!(ids, all_txs.iter().map(|t| t.id).collect::<Vec<_>>());
+
+		// same for outputs
+		let (_, all_outputs) = api.retrieve_outputs(m, true, false, false, None)?;
+		let mut commits = vec![];
+		let mut cursor = None;
+		loop {
+			let (_, page) = api.retrieve_outputs_page(m, true, false, cursor, 2)?;
+			assert!(page.outputs.len() <= 2);
+			commits.extend(page.outputs.iter().map(|o| o.commit));
+			match page.next_cursor {
+				Some(c) => cursor = Some(c),
+				None => break,
+			}
+		}
+		assert_eq!(commits.len(), all_outputs.len());
+		for o in all_outputs.iter() {
+			assert!(commits.contains(&o.commit));
+		}
+
+		// a page that exactly fits the remaining entries has no next cursor
+		let (_, page) = api.retrieve_txs_page(m, false, None, 7)?;
+		assert_eq!(page.txs.len(), 7);
+		assert!(page.next_cursor.is_none());
+
+		// cursors must have been issued by the wallet
+		match api.retrieve_txs_page(m, false, Some("not a cursor".to_owned()), 3) {
+			Err(e) => assert_eq!(
+				e.kind(),
+				ErrorKind::InvalidCursor("not a cursor".to_owned())
+			),
+			Ok(_) => panic!("retrieve_txs_page should reject an invalid cursor"),
+		}
+		let (_, page) = api.retrieve_outputs_page(m, true, false, None, 1)?;
+		assert!(api
+			.retrieve_txs_page(m, false, page.next_cursor, 3)
+			.is_err());
+		assert!(api.retrieve_txs_page(m, false, None, 0).is_err());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn paging() {
+	let test_dir = "test_output/paging";
+	setup(test_dir);
+	if let Err(e) = paging_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,285 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test receivers contributing an input, and part of the fee, to transactions
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, FeeContribution, InitTxArgs, OutputStatus, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn payjoin_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		merchant,
+		mask2_i,
+		test_dir,
+		"merchant",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	// the merchant's outputs are old enough to contribute by the time it's paid
+	let _ = test_framework::award_blocks_to_wallet(&chain, merchant.clone(), mask2, 10, false);
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+	let mut merchant_total = Amount::ZERO;
+	wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+		let (_, info) = api.retrieve_summary_info(m, true, 1)?;
+		merchant_total = info.total;
+		Ok(())
+	})?;
+
+	// the offer is reported to senders
+	let offer = core::consensus::EPIC_BASE;
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		assert_eq!(api.check_version()?.receiver_fee_contribution, None);
+		api.set_fee_contribution(Some(FeeContribution::new(offer)));
+		assert_eq!(api.check_version()?.receiver_fee_contribution, Some(offer));
+		Ok(())
+	})?;
+
+	let amount = core::consensus::EPIC_BASE * 2;
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(amount),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		receiver_fee: Some(offer),
+		..Default::default()
+	};
+	let send = |args: InitTxArgs| -> Result<Slate, libwallet::Error> {
+		let mut slate = Slate::blank(2);
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			slate = api.init_send_tx(m, args)?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			Ok(())
+		})?;
+		Ok(slate)
+	};
+
+	// the part of the fee asked for is capped at the whole fee
+	let mut slate = send(args.clone())?;
+	assert_eq!(slate.receiver_fee, Some(slate.fee));
+	let fee = slate.fee;
+	let sender_inputs = slate.tx.inputs().len();
+
+	// receivers not offering as much as asked refuse the transaction
+	for max in vec![None, Some(fee - 1)] {
+		wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+			api.set_fee_contribution(max.map(FeeContribution::new));
+			match api.receive_tx(&slate, None, None) {
+				Err(e) => match e.kind() {
+					ErrorKind::ReceiverContribution(_) => {}
+					k => panic!("unexpected error {}", k),
+				},
+				Ok(_) => panic!("receive contributing more than offered"),
+			}
+			Ok(())
+		})?;
+	}
+
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		api.set_fee_contribution(Some(FeeContribution::new(offer)));
+		slate = api.receive_tx(&slate, None, None)?;
+		Ok(())
+	})?;
+	assert_eq!(slate.tx.inputs().len(), sender_inputs + 1);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	// the sender paid none of the fee, and the merchant all of it
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert!(txs[0].confirmed);
+		assert_eq!(txs[0].amount_debited - txs[0].amount_credited, amount);
+		Ok(())
+	})?;
+	wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert!(txs[0].confirmed);
+		assert_eq!(txs[0].num_inputs, 1);
+		assert_eq!(txs[0].fee, Some(fee));
+		assert_eq!(txs[0].amount_credited - txs[0].amount_debited, amount - fee);
+		let (_, info) = api.retrieve_summary_info(m, true, 1)?;
+		assert_eq!(
+			info.total,
+			merchant_total + Amount::from_freemans(amount - fee)
+		);
+		Ok(())
+	})?;
+
+	let locked = || -> Result<usize, libwallet::Error> {
+		let mut locked = 0;
+		wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+			let (_, outputs) = api.retrieve_outputs(m, false, false, false, None)?;
+			locked = outputs
+				.iter()
+				.filter(|o| o.output.status == OutputStatus::Locked)
+				.count();
+			Ok(())
+		})?;
+		Ok(locked)
+	};
+
+	// asking for no part of the fee still gets an input
+	slate = send(InitTxArgs {
+		receiver_fee: Some(0),
+		..args.clone()
+	})?;
+	let sender_inputs = slate.tx.inputs().len();
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		api.set_fee_contribution(Some(FeeContribution::new(0)));
+		slate = api.receive_tx(&slate, None, None)?;
+		Ok(())
+	})?;
+	assert_eq!(slate.tx.inputs().len(), sender_inputs + 1);
+
+	// that input stays locked until the sender finalizes, so once as many are
+	// pending as allowed, none are given, and receives asking for part of the
+	// fee are refused
+	let locked_before = locked()?;
+	let capped = FeeContribution {
+		max_pending: 1,
+		..FeeContribution::new(offer)
+	};
+	slate = send(InitTxArgs {
+		receiver_fee: Some(0),
+		..args.clone()
+	})?;
+	let sender_inputs = slate.tx.inputs().len();
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		api.set_fee_contribution(Some(capped.clone()));
+		slate = api.receive_tx(&slate, None, None)?;
+		Ok(())
+	})?;
+	assert_eq!(slate.tx.inputs().len(), sender_inputs);
+	slate = send(args.clone())?;
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		api.set_fee_contribution(Some(capped.clone()));
+		match api.receive_tx(&slate, None, None) {
+			Err(e) => match e.kind() {
+				ErrorKind::ReceiverContribution(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("contributed more inputs than allowed pending"),
+		}
+		Ok(())
+	})?;
+	assert_eq!(locked()?, locked_before);
+
+	// outputs without the confirmations asked for aren't given either
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		api.set_fee_contribution(Some(FeeContribution {
+			min_confirmations: 1000,
+			..FeeContribution::new(offer)
+		}));
+		match api.receive_tx(&slate, None, None) {
+			Err(e) => match e.kind() {
+				ErrorKind::ReceiverContribution(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("contributed an output without enough confirmations"),
+		}
+		Ok(())
+	})?;
+	assert_eq!(locked()?, locked_before);
+
+	// a frozen account receives without giving an input, and refuses to pay
+	// part of the fee
+	wallet::controller::owner_single_use(merchant.clone(), mask2, |api, m| {
+		api.freeze_account(m, "default", "compliance hold")?;
+		Ok(())
+	})?;
+	let locked_before = locked()?;
+	slate = send(InitTxArgs {
+		receiver_fee: Some(0),
+		..args.clone()
+	})?;
+	let sender_inputs = slate.tx.inputs().len();
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		api.set_fee_contribution(Some(FeeContribution::new(offer)));
+		slate = api.receive_tx(&slate, None, None)?;
+		Ok(())
+	})?;
+	assert_eq!(slate.tx.inputs().len(), sender_inputs);
+	assert_eq!(locked()?, locked_before);
+
+	slate = send(args)?;
+	wallet::controller::foreign_single_use(merchant.clone(), mask2_i.clone(), |api| {
+		match api.receive_tx(&slate, None, None) {
+			Err(e) => match e.kind() {
+				ErrorKind::AccountFrozen(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("frozen account contributed an input"),
+		}
+		Ok(())
+	})?;
+	assert_eq!(locked()?, locked_before);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn payjoin() {
+	let test_dir = "test_output/payjoin";
+	setup(test_dir);
+	if let Err(e) = payjoin_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
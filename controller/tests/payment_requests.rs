@@ -0,0 +1,175 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test paying payment requests created through the foreign API, and
+//! refusing ones that were already paid or are for another amount
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use epic_wallet_util::epic_core as core;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{address, Amount, EncodedPaymentRequest, ErrorKind, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn payment_requests_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		_client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	// wallet 2 asks to be paid
+	let mut request: Option<EncodedPaymentRequest> = None;
+	wallet::controller::foreign_single_use(wallet2.clone(), mask2_i.clone(), |api| {
+		assert!(api
+			.create_payment_request(Amount::from_freemans(0), None)
+			.is_err());
+		let memo = Some("order #42".to_owned());
+		request = Some(api.create_payment_request(Amount::from_freemans(reward), memo)?);
+		Ok(())
+	})?;
+	let request = request.unwrap();
+	assert!(!request.qr.is_empty());
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	// wallet 1 pays it, the transaction taking the request's id and proving
+	// payment to its address
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let parsed = api.parse_payment_request(&request.armored)?;
+		assert_eq!(parsed, request.request);
+
+		// a request isn't paid for another amount than requested
+		let other_amount = InitTxArgs {
+			amount: Amount::from_freemans(reward * 2),
+			..args.clone()
+		};
+		match api.pay_request(m, &request.armored, other_amount) {
+			Err(e) => match e.kind() {
+				ErrorKind::PolicyRefused(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("request was paid"),
+		}
+
+		let slate_i = api.pay_request(m, &request.armored, args.clone())?;
+		assert_eq!(slate_i.id, request.request.id);
+		assert_eq!(slate_i.amount, request.request.amount);
+		assert_eq!(
+			slate_i.payment_proof.as_ref().unwrap().receiver_address,
+			request.request.proof_address
+		);
+		assert_eq!(
+			slate_i.participant_data[0].message,
+			Some("order #42".to_owned())
+		);
+		slate = client1.send_tx_slate_direct("wallet2", &slate_i)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+
+		// and isn't paid twice
+		match api.pay_request(m, &request.armored, args.clone()) {
+			Err(e) => match e.kind() {
+				ErrorKind::PaymentRequest(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("request was paid twice"),
+		}
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	// wallet 2 finds the payment under the request's id
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let (refreshed, txs) = api.retrieve_txs(m, true, None, Some(request.request.id))?;
+		assert!(refreshed);
+		assert_eq!(txs.len(), 1);
+		assert!(txs[0].confirmed);
+		assert_eq!(txs[0].amount_credited, Amount::from_freemans(reward));
+		Ok(())
+	})?;
+
+	// wallet 1 holds a proof of the payment
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let pp = api.retrieve_payment_proof(m, true, None, Some(request.request.id))?;
+		assert_eq!(
+			pp.recipient_address,
+			address::onion_v3_from_pubkey(&request.request.proof_address)?
+		);
+		assert_eq!(api.verify_payment_proof(m, &pp)?, (true, false));
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn payment_requests() {
+	let test_dir = "test_output/payment_requests";
+	setup(test_dir);
+	if let Err(e) = payment_requests_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
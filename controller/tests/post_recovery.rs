@@ -0,0 +1,164 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test recording and recovery of transaction posts
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate, TxLogEntryType, TxPostState};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn post_recovery_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	// builds, receives and finalizes a send without posting it
+	let finalized_send = || -> Result<Slate, libwallet::Error> {
+		let mut slate = Slate::blank(2);
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			slate = api.init_send_tx(m, args.clone())?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+				slate = api.receive_tx(&slate, Some("listener"), None)?;
+				Ok(())
+			})?;
+			slate = api.finalize_tx(m, &slate)?;
+			Ok(())
+		})?;
+		Ok(slate)
+	};
+
+	// leaves the send as a crash between saving and posting it would
+	let interrupt_post = |slate: &Slate, lose_stored_tx: bool| -> Result<(), libwallet::Error> {
+		wallet_inst!(wallet1, w);
+		let mut t = w
+			.tx_log_iter()
+			.find(|t| t.tx_slate_id == Some(slate.id) && t.tx_type == TxLogEntryType::TxSent)
+			.unwrap();
+		t.post_state = Some(TxPostState::ReadyToPost);
+		if lose_stored_tx {
+			t.stored_tx = None;
+		}
+		let parent_key_id = t.parent_key_id.clone();
+		let mut batch = w.batch(mask1)?;
+		batch.save_tx_log_entry(t, &parent_key_id)?;
+		batch.commit()?;
+		Ok(())
+	};
+
+	// a normal post is recorded once the node accepts it
+	let slate = finalized_send()?;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.post_tx(m, &slate.tx, false)?;
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		assert_eq!(txs[0].post_state, Some(TxPostState::Posted));
+		assert!(api.recover_pending_posts(m)?.is_empty());
+		Ok(())
+	})?;
+
+	// an interrupted post is posted again
+	let slate = finalized_send()?;
+	interrupt_post(&slate, false)?;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let resolved = api.recover_pending_posts(m)?;
+		assert_eq!(resolved.len(), 1);
+		assert_eq!(resolved[0].tx_slate_id, Some(slate.id));
+		assert_eq!(resolved[0].post_state, Some(TxPostState::Posted));
+		assert!(api.recover_pending_posts(m)?.is_empty());
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		assert!(refreshed);
+		assert!(txs[0].confirmed);
+		Ok(())
+	})?;
+
+	// one that can't be posted again is rolled back
+	let slate = finalized_send()?;
+	interrupt_post(&slate, true)?;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let resolved = api.recover_pending_posts(m)?;
+		assert_eq!(resolved.len(), 1);
+		assert_eq!(resolved[0].tx_type, TxLogEntryType::TxSentCancelled);
+		assert_eq!(resolved[0].post_state, None);
+		assert!(api.recover_pending_posts(m)?.is_empty());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn post_recovery() {
+	let test_dir = "test_output/post_recovery";
+	setup(test_dir);
+	if let Err(e) = post_recovery_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,118 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test one-shot wallet provisioning through the owner API
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_api::Owner;
+use epic_wallet_libwallet as libwallet;
+use epic_wallet_util::epic_keychain::ExtKeychain;
+use epic_wallet_util::epic_util::{Mutex, ZeroingString};
+use impls::test_framework::LocalWalletClient;
+use impls::{DefaultLCProvider, DefaultWalletImpl};
+use libwallet::{ErrorKind, WalletInst};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn provisioning_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+
+	// a wallet instance that hasn't been initialized yet
+	let client1 = LocalWalletClient::new("wallet1", wallet_proxy.tx.clone());
+	let mut wallet1 = Box::new(DefaultWalletImpl::<LocalWalletClient>::new(
+		client1.clone(),
+	)?)
+		as Box<
+			dyn WalletInst<
+				DefaultLCProvider<'static, LocalWalletClient, ExtKeychain>,
+				LocalWalletClient,
+				ExtKeychain,
+			>,
+		>;
+	let lc = wallet1.lc_provider()?;
+	let _ = lc.set_top_level_directory(&format!("{}/wallet1", test_dir));
+	let wallet1 = Arc::new(Mutex::new(wallet1));
+	wallet_proxy.add_wallet(
+		"wallet1",
+		client1.get_send_instance(),
+		wallet1.clone(),
+		None,
+	);
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let phrase = "fat twenty mean degree forget shell check candy immense awful \
+	              flame next during february bulb bike sun wink theory day kiwi embrace peace lunch";
+	let provision = |api: &Owner<_, _, _>, token: &str| {
+		api.init_wallet_from_env(
+			None,
+			token,
+			Some(ZeroingString::from(phrase)),
+			Some(ZeroingString::from("secret")),
+		)
+	};
+	let denied = |res: Result<_, libwallet::Error>| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::ProvisioningDenied(_) => true,
+			_ => false,
+		},
+		Ok(_) => false,
+	};
+
+	let api = Owner::new(wallet1.clone());
+
+	// disabled until a token is set
+	assert!(denied(provision(&api, "bootstrap-token")));
+
+	api.set_provisioning_token(Some(ZeroingString::from("bootstrap-token")));
+	assert!(denied(provision(&api, "wrong-token")));
+	assert!(denied(provision(&api, "bootstrap")));
+
+	// the right token creates and opens the wallet from the phrase
+	let mask = provision(&api, "bootstrap-token")?;
+	assert!(mask.is_some());
+	let (_, info) = api.retrieve_summary_info(mask.as_ref(), true, 1)?;
+	assert_eq!(info.total, 0);
+	let mnemonic = api.get_mnemonic(None, ZeroingString::from("secret"))?;
+	assert_eq!(&*mnemonic, phrase);
+
+	// and can't be used again
+	assert!(denied(provision(&api, "bootstrap-token")));
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn provisioning() {
+	let test_dir = "test_output/provisioning";
+	setup(test_dir);
+	if let Err(e) = provisioning_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
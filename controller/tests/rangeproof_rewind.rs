@@ -0,0 +1,139 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test rewinding the range proofs of individual outputs with a wallet's keys
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_util as util;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{ErrorKind, NodeClient};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn rangeproof_rewind_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet2.clone(), mask2, 3, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let (_, outputs) = owner_api.retrieve_outputs(mask1, false, true, false, None)?;
+	let (_, _, chain_outputs) = client1.get_outputs_by_pmmr_index(1, None, 1000)?;
+
+	// each of the wallet's outputs on chain is recovered, and no others
+	let mut ours = 0;
+	let mut theirs = 0;
+	for (commit, proof, _, _, _) in chain_outputs.iter() {
+		let rewound = owner_api.rewind_rangeproof(
+			mask1,
+			&util::to_hex(commit.0.to_vec()),
+			&util::to_hex(proof.proof[..proof.plen].to_vec()),
+		)?;
+		assert_eq!(rewound.commit, *commit);
+		let output = outputs.iter().find(|o| o.commit == *commit);
+		match output {
+			Some(o) => {
+				ours += 1;
+				assert!(rewound.is_ours);
+				assert_eq!(rewound.value, Some(o.output.value));
+				assert_eq!(rewound.key_id, Some(o.output.key_id.clone()));
+				assert_eq!(rewound.key_path, Some(o.output.key_id.to_bip_32_string()));
+				assert_eq!(rewound.account, Some("default".to_owned()));
+				assert_eq!(rewound.output.unwrap().key_id, o.output.key_id);
+			}
+			None => {
+				theirs += 1;
+				assert!(!rewound.is_ours);
+				assert_eq!(rewound.value, None);
+				assert_eq!(rewound.key_id, None);
+				assert_eq!(rewound.account, None);
+				assert!(rewound.output.is_none());
+			}
+		}
+	}
+	assert_eq!(ours, outputs.len());
+	assert!(theirs >= 3);
+
+	// the commitment and proof must be hex of the right size
+	let refused =
+		|commit: &str, proof: &str| match owner_api.rewind_rangeproof(mask1, commit, proof) {
+			Err(e) => match e.kind() {
+				ErrorKind::RangeProofRewind(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("rewound an invalid proof"),
+		};
+	let commit = util::to_hex(outputs[0].commit.0.to_vec());
+	refused("zz", "00");
+	refused("0800", "00");
+	refused(&commit, "zz");
+	refused(&commit, "");
+	refused(&commit, &"00".repeat(10000));
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn rangeproof_rewind() {
+	let test_dir = "test_output/rangeproof_rewind";
+	setup(test_dir);
+	if let Err(e) = rangeproof_rewind_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,151 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test that the receiver's transaction log keeps the messages and proof
+//! address a transaction was received with, and the receive notification
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn receive_notes_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let mut address = None;
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		address = Some(api.get_public_proof_address(m, 0)?);
+		Ok(())
+	})?;
+
+	let mut slate = Slate::blank(1);
+	let mut sender_address = None;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let args = InitTxArgs {
+			src_acct_name: None,
+			amount: Amount::from_freemans(60_000_000_000),
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: true,
+			message: Some("order 1234".to_owned()),
+			payment_proof_recipient_address: address,
+			..Default::default()
+		};
+		let slate_i = api.init_send_tx(m, args)?;
+		sender_address = Some(slate_i.payment_proof.as_ref().unwrap().sender_address);
+		wallet::controller::foreign_single_use(wallet2.clone(), mask2_i.clone(), |api| {
+			slate = api.receive_tx(&slate_i, None, Some("thanks".to_owned()))?;
+			Ok(())
+		})?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		Ok(())
+	})?;
+
+	// the receiver can reconcile the payment from its own tx log
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		assert_eq!(txs.len(), 1);
+		assert_eq!(txs[0].tx_type, TxLogEntryType::TxReceived);
+		assert_eq!(txs[0].sender_proof_address, sender_address);
+		let messages = txs[0]
+			.messages
+			.as_ref()
+			.unwrap()
+			.messages
+			.iter()
+			.filter_map(|m| m.message.clone())
+			.collect::<Vec<_>>();
+		assert!(messages.contains(&"order 1234".to_owned()));
+		assert!(messages.contains(&"thanks".to_owned()));
+		Ok(())
+	})?;
+
+	// the notice sent to a receive callback is signed by the receiving account
+	let notification = {
+		wallet_inst!(wallet2, w);
+		libwallet::api_impl::foreign::receive_notification(&mut **w, mask2, &slate, None)?
+	};
+	assert_eq!(notification.slate_id, slate.id);
+	assert_eq!(notification.amount, 60_000_000_000);
+	assert_eq!(Some(notification.proof_address), address);
+	assert_eq!(notification.sender_proof_address, sender_address);
+	notification.verify()?;
+	let mut tampered = notification.clone();
+	tampered.amount += 1;
+	assert!(tampered.verify().is_err());
+
+	// the sender's entry has no counterparty proof address of its own
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		assert_eq!(txs[0].sender_proof_address, None);
+		assert!(txs[0].payment_proof.is_some());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn receive_notes() {
+	let test_dir = "test_output/receive_notes";
+	setup(test_dir);
+	if let Err(e) = receive_notes_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
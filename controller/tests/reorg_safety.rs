@@ -0,0 +1,116 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the reorg safety policy on output selection
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn reorg_safety_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let cm = core::global::coinbase_maturity();
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+
+	// Mine enough blocks that several outputs are mature but still shallow
+	let _ = test_framework::award_blocks_to_wallet(
+		&chain,
+		wallet1.clone(),
+		mask1,
+		cm as usize + 10,
+		false,
+	);
+
+	let estimate_args = |override_reorg_safety| InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward),
+		minimum_confirmations: 1,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: true,
+		estimate_only: Some(true),
+		override_reorg_safety,
+		..Default::default()
+	};
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.retrieve_summary_info(m, true, 1)?;
+
+		// without a policy, all mature outputs are selected
+		let unrestricted = api.init_send_tx(m, estimate_args(None))?.amount;
+
+		// with a policy, the most recent outputs are left alone
+		api.set_min_reorg_safe_confirmations(Some(cm + 5));
+		let restricted = api.init_send_tx(m, estimate_args(None))?.amount;
+		assert!(restricted < unrestricted);
+
+		// a policy lower than the requested confirmations has no effect
+		api.set_min_reorg_safe_confirmations(Some(1));
+		let lower = api.init_send_tx(m, estimate_args(None))?.amount;
+		assert_eq!(lower, unrestricted);
+
+		// and the policy can be overridden per transaction
+		api.set_min_reorg_safe_confirmations(Some(cm + 5));
+		let overridden = api.init_send_tx(m, estimate_args(Some(true)))?.amount;
+		assert_eq!(overridden, unrestricted);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn reorg_safety() {
+	let test_dir = "test_output/reorg_safety";
+	setup(test_dir);
+	if let Err(e) = reorg_safety_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,56 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test encrypted owner API requests can't be replayed
+extern crate epic_wallet_controller as wallet;
+
+use std::time::Duration;
+use wallet::controller::RequestReplayGuard;
+
+#[test]
+fn replay_guard() {
+	let guard = RequestReplayGuard::new(Duration::from_secs(300));
+	let made = 1_600_000_000;
+
+	// a request is accepted once, and refused when replayed with its token
+	assert!(guard.check_at("t", "n1", Some(made), made).is_ok());
+	assert!(guard.check_at("t", "n1", Some(made), made + 10).is_err());
+	assert!(guard.check_at("u", "n1", Some(made), made + 10).is_ok());
+
+	// replayed after the window, once its nonce is forgotten, it's refused
+	// by its timestamp
+	assert!(guard
+		.check_at("t", "n2", Some(made + 10), made + 20)
+		.is_ok());
+	assert!(guard.check_at("t", "n1", Some(made), made + 301).is_err());
+	assert!(guard.check_at("t", "n1", Some(made), made + 3600).is_err());
+
+	// as are requests made too far ahead of the wallet
+	assert!(guard.check_at("t", "n4", Some(made + 301), made).is_err());
+	assert!(guard.check_at("t", "n5", Some(made + 300), made).is_ok());
+	assert!(guard
+		.check_at("t", "n5", Some(made + 300), made + 599)
+		.is_err());
+
+	// requests from clients without timestamps are accepted, and refused
+	// when replayed within the window of when they were received
+	assert!(guard.check_at("t", "n6", None, made).is_ok());
+	assert!(guard.check_at("t", "n6", None, made + 300).is_err());
+	assert!(guard.check_at("u", "n6", None, made + 300).is_ok());
+
+	// a window of 0 checks nothing
+	let guard = RequestReplayGuard::new(Duration::from_secs(0));
+	assert!(guard.check_at("t", "n1", None, made).is_ok());
+	assert!(guard.check_at("t", "n1", None, made).is_ok());
+}
@@ -20,7 +20,7 @@ extern crate epic_wallet_libwallet as libwallet;
 
 use epic_wallet_util::epic_core as core;
 
-use self::libwallet::{InitTxArgs, Slate};
+use self::libwallet::{Amount, InitTxArgs, Slate};
 use impls::test_framework::{self, LocalWalletClient};
 use impls::{PathToSlate, SlateGetter as _, SlatePutter as _};
 use std::thread;
@@ -108,7 +108,7 @@ fn file_repost_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error>
 		// send to send
 		let args = InitTxArgs {
 			src_acct_name: Some("mining".to_owned()),
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -201,7 +201,7 @@ fn file_repost_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error>
 		// note this will increment the block count as part of the transaction "Posting"
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
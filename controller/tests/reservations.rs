@@ -0,0 +1,141 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test reserving account balance ahead of building transactions
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, OutputStatus};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn expect_refused<T>(res: Result<T, libwallet::Error>) {
+	match res {
+		Err(e) => match e.kind() {
+			ErrorKind::Reservation(_) => {}
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("reserved funds were spent"),
+	}
+}
+
+fn reservations_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = |amount| InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(amount),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		// everything but immature coinbase can be reserved
+		let height = api.node_height(m)?.height;
+		let (_, outputs) = api.retrieve_outputs(m, false, true, false, None)?;
+		let balance: u64 = outputs
+			.iter()
+			.filter(|o| o.output.status == OutputStatus::Unspent)
+			.filter(|o| !o.output.is_coinbase || o.output.lock_height <= height)
+			.map(|o| o.output.value)
+			.sum();
+		assert!(api.reserve_funds(m, 0, None).is_err());
+		assert!(api.reserve_funds(m, balance + 1, None).is_err());
+
+		// keep a single epic unreserved
+		let withdrawal = api.reserve_funds(
+			m,
+			balance - core::consensus::EPIC_BASE,
+			Some("withdrawal 1".to_owned()),
+		)?;
+		expect_refused(api.reserve_funds(m, core::consensus::EPIC_BASE + 1, None));
+		assert_eq!(api.retrieve_reservations(m)?, vec![withdrawal.clone()]);
+
+		// other payments can't eat into the reservation, estimated or not
+		expect_refused(api.init_send_tx(m, args(core::consensus::EPIC_BASE * 2)));
+		let mut estimate = args(core::consensus::EPIC_BASE * 2);
+		estimate.estimate_only = Some(true);
+		expect_refused(api.init_send_tx(m, estimate));
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		let num_txs = txs.len();
+
+		// but can use what's left
+		api.init_send_tx(m, args(core::consensus::EPIC_BASE / 10))?;
+
+		// paying out the reservation releases it
+		let mut payout = args(core::consensus::EPIC_BASE * 2);
+		payout.reservation_id = Some(withdrawal.id);
+		api.init_send_tx(m, payout.clone())?;
+		assert!(api.retrieve_reservations(m)?.is_empty());
+		expect_refused(api.init_send_tx(m, payout));
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		assert_eq!(txs.len(), num_txs);
+
+		// a reservation can be released without being paid out
+		let r = api.reserve_funds(m, core::consensus::EPIC_BASE, None)?;
+		assert_eq!(api.release_reservation(m, r.id)?.amount, r.amount);
+		assert!(api.retrieve_reservations(m)?.is_empty());
+		expect_refused(api.release_reservation(m, r.id));
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn reservations() {
+	let test_dir = "test_output/reservations";
+	setup(test_dir);
+	if let Err(e) = reservations_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,117 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test signing foreign API responses with the wallet's payment proof address
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::LocalWalletClient;
+use libwallet::api_impl::foreign::verify_response_signature;
+use libwallet::ErrorKind;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn response_signatures_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let mut addresses = vec![];
+	for (w, m) in vec![(wallet1.clone(), mask1), (wallet2.clone(), mask2)] {
+		wallet::controller::owner_single_use(w, m, |api, m| {
+			addresses.push(api.get_public_proof_address(m, 0)?);
+			Ok(())
+		})?;
+	}
+
+	let request = br#"{"jsonrpc":"2.0","method":"receive_tx","id":1,"params":[]}"#;
+	let response = br#"{"id":1,"jsonrpc":"2.0","result":{"Ok":{}}}"#;
+	let mut signed = None;
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		signed = Some(api.sign_response(request, response)?);
+		Ok(())
+	})?;
+	let (address, signature) = signed.unwrap();
+
+	// responses are signed with the wallet's payment proof address
+	assert_eq!(address, addresses[0]);
+	verify_response_signature(&addresses[0], request, response, &signature)?;
+
+	// and don't verify against another wallet, a changed response, or as
+	// the answer to another request
+	let other_request = br#"{"jsonrpc":"2.0","method":"check_version","id":1,"params":[]}"#;
+	let changed_response = br#"{"id":1,"jsonrpc":"2.0","result":{"Err":{}}}"#;
+	for (addr, req, resp) in vec![
+		(&addresses[1], &request[..], &response[..]),
+		(&addresses[0], &request[..], &changed_response[..]),
+		(&addresses[0], &other_request[..], &response[..]),
+	] {
+		match verify_response_signature(addr, req, resp, &signature) {
+			Err(e) => match e.kind() {
+				ErrorKind::ResponseSignature(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("response verified"),
+		}
+	}
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn response_signatures() {
+	let test_dir = "test_output/response_signatures";
+	setup(test_dir);
+	if let Err(e) = response_signatures_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,157 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test owner API calls timing out
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+extern crate epic_wallet_libwallet as libwallet;
+
+use api::{Owner, OwnerRpc};
+use easy_jsonrpc_mw::{Handler, MaybeReply};
+use impls::test_framework::LocalWalletClient;
+use serde_json::json;
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+use wallet::controller::{RpcTimeouts, RPC_TIMEOUT_ERROR_CODE};
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn reply(r: MaybeReply) -> serde_json::Value {
+	match r {
+		MaybeReply::Reply(v) => v,
+		MaybeReply::DontReply => panic!("no reply"),
+	}
+}
+
+fn rpc_timeouts_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+
+	create_wallet_and_add!(
+		_client1,
+		wallet1,
+		_mask1,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	// reads get the short timeout, unless they refresh from the node first,
+	// and calls changing the wallet get none
+	let secs = |s| Some(Duration::from_secs(s));
+	let mut methods = HashMap::new();
+	methods.insert("retrieve_outputs".to_owned(), None);
+	let timeouts = RpcTimeouts::new(secs(5), secs(120), methods);
+	for (req, timeout) in vec![
+		(json!({"method": "retrieve_txs", "params": {}}), secs(5)),
+		(
+			json!({"method": "retrieve_txs", "params": {"refresh_from_node": true}}),
+			secs(120),
+		),
+		(json!({"method": "node_status", "params": {}}), secs(120)),
+		(json!({"method": "post_tx", "params": {}}), None),
+		(json!({"method": "init_send_tx", "params": {}}), None),
+		(json!({"method": "get_mnemonic", "params": {}}), None),
+		(json!({"method": "scan", "params": {}}), None),
+		(json!({"method": "retrieve_outputs", "params": {}}), None),
+	] {
+		assert_eq!(timeouts.timeout(&req), timeout, "{}", req);
+	}
+
+	// a call stuck behind the wallet lock is answered once its timeout
+	// passes, and finishes on its own once the lock is released
+	let short = Some(Duration::from_millis(100));
+	let timeouts = RpcTimeouts::new(short, short, HashMap::new());
+	let req = json!({"jsonrpc": "2.0", "method": "accounts", "params": [], "id": 1});
+	let call = |timeouts: &RpcTimeouts| {
+		let api = Owner::new(wallet1.clone());
+		let r = req.clone();
+		reply(timeouts.call(&req, move || (&api as &dyn OwnerRpc).handle_request(r)))
+	};
+	{
+		let _lock = wallet1.lock();
+		let res = call(&timeouts);
+		assert_eq!(res["error"]["code"], RPC_TIMEOUT_ERROR_CODE);
+		assert_eq!(res["id"], 1);
+		assert_eq!(timeouts.abandoned_calls(), 1);
+	}
+	for _ in 0..50 {
+		if timeouts.abandoned_calls() == 0 {
+			break;
+		}
+		thread::sleep(Duration::from_millis(100));
+	}
+	assert_eq!(timeouts.abandoned_calls(), 0);
+	let res = call(&timeouts);
+	assert!(res["error"].is_null());
+	assert!(res["result"]["Ok"].is_array());
+
+	// a call changing the wallet times out only if configured to, and says
+	// it may still complete
+	let mut methods = HashMap::new();
+	methods.insert("create_account_path".to_owned(), short);
+	let timeouts = RpcTimeouts::new(short, short, methods);
+	let req = json!({
+		"jsonrpc": "2.0",
+		"method": "create_account_path",
+		"params": {"label": "late"},
+		"id": 2
+	});
+	{
+		let _lock = wallet1.lock();
+		let api = Owner::new(wallet1.clone());
+		let r = req.clone();
+		let res = reply(timeouts.call(&req, move || (&api as &dyn OwnerRpc).handle_request(r)));
+		assert_eq!(res["error"]["code"], RPC_TIMEOUT_ERROR_CODE);
+		assert!(res["error"]["message"]
+			.as_str()
+			.unwrap()
+			.contains("may still complete"));
+	}
+	for _ in 0..50 {
+		if timeouts.abandoned_calls() == 0 {
+			break;
+		}
+		thread::sleep(Duration::from_millis(100));
+	}
+	assert_eq!(timeouts.abandoned_calls(), 0);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn rpc_timeouts() {
+	let test_dir = "test_output/rpc_timeouts";
+	setup(test_dir);
+	if let Err(e) = rpc_timeouts_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -21,7 +21,7 @@ use epic_wallet_util::epic_core as core;
 
 use epic_wallet_libwallet as libwallet;
 use impls::test_framework::{self, LocalWalletClient};
-use libwallet::InitTxArgs;
+use libwallet::{Amount, InitTxArgs};
 use std::thread;
 use std::time::Duration;
 
@@ -84,7 +84,7 @@ fn self_send_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
 		// send to send
 		let args = InitTxArgs {
 			src_acct_name: Some("mining".to_owned()),
-			amount: reward * 2,
+			amount: Amount::from_freemans(reward * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -0,0 +1,138 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test sending everything an account can spend
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn send_max_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	// the amount is ignored, as are the strategy and the soft limit on inputs
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(1),
+		minimum_confirmations: 2,
+		max_outputs: 1,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		send_max: Some(true),
+		..Default::default()
+	};
+
+	let mut slate = Slate::blank(2);
+	let mut spendable = Amount::ZERO;
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, info) = api.retrieve_summary_info(m, true, 2)?;
+		spendable = info.amount_currently_spendable;
+
+		// restricted to a tag, only the tagged output is swept
+		let (_, outputs) = api.retrieve_outputs(m, false, false, false, None)?;
+		let first = outputs.iter().find(|o| o.output.height == 1).unwrap();
+		api.tag_outputs(m, vec![first.output.commit.clone().unwrap()], "sweep")?;
+		let mut tagged = args.clone();
+		tagged.selection_tag = Some("sweep".to_owned());
+		tagged.estimate_only = Some(true);
+		let est = api.init_send_tx(m, tagged)?;
+		assert_eq!(est.amount, first.output.value);
+
+		// the estimate locks everything spendable, and the fee matches the
+		// final transaction's
+		let mut estimate = args.clone();
+		estimate.estimate_only = Some(true);
+		let est = api.init_send_tx(m, estimate)?;
+		assert_eq!(est.amount, spendable);
+
+		slate = api.init_send_tx(m, args.clone())?;
+		assert_eq!(slate.fee, est.fee);
+		assert_eq!(slate.amount + Amount::from_freemans(slate.fee), spendable);
+		slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+
+		// no change was left
+		let (_, txs) = api.retrieve_txs(m, false, None, Some(slate.id))?;
+		assert_eq!(txs[0].num_outputs, 0);
+		assert_eq!(txs[0].amount_credited, 0);
+		assert_eq!(txs[0].amount_debited, spendable);
+		Ok(())
+	})?;
+
+	// the recipient gets everything but the fee
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let (_, info) = api.retrieve_summary_info(m, true, 1)?;
+		assert_eq!(info.total, slate.amount);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn send_max() {
+	let test_dir = "test_output/send_max";
+	setup(test_dir);
+	if let Err(e) = send_max_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,159 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test queueing sends until the coinbase outputs funding them mature
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, InitTxSendArgs};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn send_queue_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	let (_, info) = owner_api.retrieve_summary_info(mask1, true, 2)?;
+	assert!(info.amount_immature > 0);
+
+	// more than is spendable now, but covered by the next reward to mature,
+	// sent to a listener that isn't there
+	let args = |wait_for_maturity: Option<u64>| InitTxArgs {
+		src_acct_name: None,
+		amount: info.amount_currently_spendable + Amount::from_freemans(core::consensus::EPIC_BASE),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		send_args: Some(InitTxSendArgs {
+			method: "http".to_owned(),
+			dest: "http://127.0.0.1:1".to_owned(),
+			finalize: true,
+			post_tx: true,
+			fluff: false,
+		}),
+		wait_for_maturity,
+		..Default::default()
+	};
+	let queue = |wait_for_maturity: Option<u64>| -> (String, u64) {
+		match owner_api.init_send_tx(mask1, args(wait_for_maturity)) {
+			Err(e) => match e.kind() {
+				ErrorKind::SendQueued { id, ready_height } => (id, ready_height),
+				k => panic!("expected the send to be queued, got {}", k),
+			},
+			Ok(_) => panic!("send made without funds"),
+		}
+	};
+
+	// the funds must mature within the blocks waited for
+	match owner_api.init_send_tx(mask1, args(Some(0))) {
+		Err(e) => match e.kind() {
+			ErrorKind::NotEnoughFunds { .. } => {}
+			k => panic!("expected not enough funds, got {}", k),
+		},
+		Ok(_) => panic!("send made without funds"),
+	}
+	// and a queued send must be deliverable by the wallet alone
+	let mut no_send_args = args(Some(100));
+	no_send_args.send_args = None;
+	match owner_api.init_send_tx(mask1, no_send_args) {
+		Err(e) => match e.kind() {
+			ErrorKind::SendQueue(_) => {}
+			k => panic!("expected a send queue error, got {}", k),
+		},
+		Ok(_) => panic!("send made without funds"),
+	}
+	assert!(owner_api.retrieve_send_queue(mask1)?.is_empty());
+
+	// queued sends are listed, left alone until due and can be cancelled
+	let height = owner_api.node_height(mask1)?.height;
+	let (id, ready_height) = queue(Some(100));
+	assert!(ready_height > height);
+	let queued = owner_api.retrieve_send_queue(mask1)?;
+	assert_eq!(queued.len(), 1);
+	assert_eq!(queued[0].id.to_string(), id);
+	assert_eq!(queued[0].ready_height, ready_height);
+	assert_eq!(queued[0].expiry_height, height + 100);
+	assert!(owner_api.process_send_queue(mask1)?.is_empty());
+	assert_eq!(owner_api.retrieve_send_queue(mask1)?.len(), 1);
+
+	let cancelled = owner_api.cancel_queued_send(mask1, &queued[0].id)?;
+	assert_eq!(cancelled.id, queued[0].id);
+	assert!(owner_api.retrieve_send_queue(mask1)?.is_empty());
+	assert!(owner_api.cancel_queued_send(mask1, &queued[0].id).is_err());
+
+	// once the funds mature the send is made, and dropped from the queue as
+	// the recipient can't be reached
+	let (id, ready_height) = queue(Some(100));
+	let _ = test_framework::award_blocks_to_wallet(
+		&chain,
+		wallet1.clone(),
+		mask1,
+		(ready_height - height) as usize,
+		false,
+	);
+	let results = owner_api.process_send_queue(mask1)?;
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].queued.id.to_string(), id);
+	assert_eq!(results[0].tx_slate_id, None);
+	assert!(results[0].error.is_some());
+	assert!(owner_api.retrieve_send_queue(mask1)?.is_empty());
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn send_queue() {
+	let test_dir = "test_output/send_queue";
+	setup(test_dir);
+	if let Err(e) = send_queue_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,167 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test spreading a wallet's receives and sends across shard accounts
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn sharding_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		hot,
+		mask2_i,
+		test_dir,
+		"hot",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+	let shards = vec!["shard1".to_owned(), "shard2".to_owned()];
+	wallet::controller::owner_single_use(hot.clone(), mask2, |api, m| {
+		api.create_account_path(m, "shard1")?;
+		api.create_account_path(m, "shard2")?;
+		Ok(())
+	})?;
+
+	// receives go to whichever shard holds the least
+	let epic = core::consensus::EPIC_BASE;
+	for amount in vec![3 * epic, epic] {
+		let mut slate = Slate::blank(2);
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			let args = InitTxArgs {
+				src_acct_name: None,
+				amount: Amount::from_freemans(amount),
+				minimum_confirmations: 2,
+				max_outputs: 500,
+				num_change_outputs: 1,
+				selection_strategy_is_use_all: false,
+				..Default::default()
+			};
+			slate = api.init_send_tx(m, args)?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			Ok(())
+		})?;
+		wallet::controller::foreign_single_use(hot.clone(), mask2_i.clone(), |api| {
+			api.set_receive_shards(Some(shards.clone()));
+			slate = api.receive_tx(&slate, None, None)?;
+			Ok(())
+		})?;
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			slate = api.finalize_tx(m, &slate)?;
+			api.post_tx(m, &slate.tx, false)?;
+			Ok(())
+		})?;
+	}
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(hot.clone(), mask2, |api, m| {
+		let (_, balances) = api.shard_balances(m, true, &shards, 1)?;
+		assert_eq!(balances[0].account, "shard1");
+		assert_eq!(balances[0].amount_currently_spendable, 3 * epic);
+		assert_eq!(balances[1].amount_currently_spendable, epic);
+
+		match api.shard_balances(m, false, &["shard3".to_owned()], 1) {
+			Err(e) => match e.kind() {
+				ErrorKind::UnknownAccountLabel(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("returned the balance of a missing account"),
+		}
+
+		// nothing is moved for less than the minimum
+		assert!(api.rebalance_shards(m, &shards, 1, 2 * epic)?.is_none());
+		let slate = api.rebalance_shards(m, &shards, 1, epic / 2)?.unwrap();
+		assert_eq!(slate.amount, epic);
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(hot.clone(), mask2, |api, m| {
+		let (_, balances) = api.shard_balances(m, true, &shards, 1)?;
+		let fee = 2 * epic - balances[0].amount_currently_spendable.freemans();
+		assert!(fee > 0);
+		assert_eq!(balances[1].amount_currently_spendable, 2 * epic);
+		// within a fee of even, so there's nothing worth moving
+		assert!(api.rebalance_shards(m, &shards, 1, fee)?.is_none());
+
+		// sends come from whichever shard has the most to spend
+		let args = InitTxArgs {
+			src_acct_name: Some("shard1".to_owned()),
+			amount: Amount::from_freemans(epic / 2),
+			minimum_confirmations: 1,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: false,
+			..Default::default()
+		};
+		let slate = api.init_sharded_send_tx(m, &shards, args)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		let (_, balances) = api.shard_balances(m, false, &shards, 1)?;
+		assert_eq!(balances[0].amount_locked, 0);
+		assert!(balances[1].amount_locked > 0);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn sharding() {
+	let test_dir = "test_output/sharding";
+	setup(test_dir);
+	if let Err(e) = sharding_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
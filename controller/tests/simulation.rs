@@ -0,0 +1,87 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test wallet invariants over random sequences of sends, cancellations,
+//! reorgs and refreshes between several wallets
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::simulation::{SimWallet, Simulation};
+use impls::test_framework::LocalWalletClient;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+/// Seeds run on every test run. A seed that breaks an invariant replays the
+/// same steps
+const SEEDS: [u64; 4] = [1, 2, 3, 4];
+
+/// Steps taken with each seed, after funding the wallets
+const STEPS: usize = 40;
+
+fn simulation_test_impl(test_dir: &str, seed: u64) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let mut sim = Simulation::new(wallet_proxy.chain.clone(), seed);
+
+	for name in vec!["wallet1", "wallet2", "wallet3"] {
+		create_wallet_and_add!(
+			client,
+			wallet,
+			mask,
+			test_dir,
+			name,
+			None,
+			&mut wallet_proxy,
+			false
+		);
+		sim.add_wallet(SimWallet {
+			name: name.to_owned(),
+			wallet,
+			mask,
+			client,
+		});
+	}
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	sim.run(STEPS)?;
+	debug!("Simulation {} took steps {:?}", seed, sim.history());
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn simulation() {
+	for seed in SEEDS.iter() {
+		let test_dir = format!("test_output/simulation_{}", seed);
+		setup(&test_dir);
+		if let Err(e) = simulation_test_impl(&test_dir, *seed) {
+			panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+		}
+		clean_output_dir(&test_dir);
+	}
+}
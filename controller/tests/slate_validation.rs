@@ -0,0 +1,134 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test validating slates without acting on them
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn slate_validation_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let args = InitTxArgs {
+			src_acct_name: None,
+			amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: false,
+			message: Some("for the escrow".to_owned()),
+			..Default::default()
+		};
+		slate = api.init_send_tx(m, args)?;
+		Ok(())
+	})?;
+
+	// a slate fresh from the sender, and once the recipient has signed it
+	let received = client1.send_tx_slate_direct("wallet2", &slate)?;
+	wallet::controller::foreign_single_use(wallet2.clone(), mask2_i.clone(), |api| {
+		for s in &[&slate, &received] {
+			let v = api.validate_slate(s)?;
+			assert!(v.valid, "{:?}", v.errors);
+			assert_eq!(v.slate_id, slate.id);
+		}
+
+		let mut zero_amount = slate.clone();
+		zero_amount.amount = Amount::ZERO;
+		let v = api.validate_slate(&zero_amount)?;
+		assert!(!v.valid);
+		assert!(!v.structure_ok);
+		assert!(v.version_supported);
+		assert_eq!(v.errors.len(), 1);
+
+		// the kernel no longer matches, and the recipient's signature breaks
+		let mut fee_changed = received.clone();
+		fee_changed.fee += 1;
+		let v = api.validate_slate(&fee_changed)?;
+		assert!(!v.structure_ok);
+		assert!(!v.signatures_ok);
+
+		let mut old_header = slate.clone();
+		old_header.version_info.block_header_version = 1;
+		let v = api.validate_slate(&old_header)?;
+		assert!(!v.version_supported);
+		assert!(v.structure_ok && v.fee_ok && v.signatures_ok);
+
+		let mut message_changed = slate.clone();
+		message_changed.participant_data[0].message = Some("for someone else".to_owned());
+		let v = api.validate_slate(&message_changed)?;
+		assert!(!v.signatures_ok);
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn slate_validation() {
+	let test_dir = "test_output/slate_validation";
+	setup(test_dir);
+	if let Err(e) = slate_validation_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,130 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test recording the slate version a transaction was exchanged in
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn slate_versions_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 4, false);
+
+	// one send in the current version and one kept to V2
+	let mut slate_ids = vec![];
+	for target_slate_version in vec![None, Some(2)] {
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			let args = InitTxArgs {
+				src_acct_name: None,
+				amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+				minimum_confirmations: 2,
+				max_outputs: 500,
+				num_change_outputs: 1,
+				selection_strategy_is_use_all: false,
+				target_slate_version,
+				..Default::default()
+			};
+			let mut slate = api.init_send_tx(m, args)?;
+			slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			slate_ids.push(slate.id);
+			Ok(())
+		})?;
+	}
+
+	let version = |txs: &Vec<libwallet::TxLogEntry>, slate_id: Uuid| {
+		txs.iter()
+			.find(|t| t.tx_slate_id == Some(slate_id))
+			.and_then(|t| t.slate_version)
+	};
+
+	// both sides record the version
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		assert!(txs
+			.iter()
+			.filter(|t| t.tx_type == TxLogEntryType::ConfirmedCoinbase)
+			.all(|t| t.slate_version.is_none()));
+		assert_eq!(version(&txs, slate_ids[0]), Some(3));
+		assert_eq!(version(&txs, slate_ids[1]), Some(2));
+		Ok(())
+	})?;
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		assert!(txs.iter().all(|t| t.tx_type == TxLogEntryType::TxReceived));
+		assert_eq!(version(&txs, slate_ids[0]), Some(3));
+		assert_eq!(version(&txs, slate_ids[1]), Some(2));
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn slate_versions() {
+	let test_dir = "test_output/slate_versions";
+	setup(test_dir);
+	if let Err(e) = slate_versions_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
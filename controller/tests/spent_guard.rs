@@ -0,0 +1,143 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test outputs missing from the node are only marked spent once it's synced,
+//! and optionally once two refreshes find them missing
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, OutputStatus, Slate, NODE_SYNCED_STATUS};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn spent_guard_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	// send to the wallet's other account, returning the number of outputs
+	// locked by the send
+	let send = || -> Result<usize, libwallet::Error> {
+		let args = InitTxArgs {
+			src_acct_name: None,
+			amount: Amount::from_freemans(core::consensus::EPIC_BASE),
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: false,
+			..Default::default()
+		};
+		let mut slate = Slate::blank(2);
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			slate = api.init_send_tx(m, args)?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+				slate = api.receive_tx(&slate, Some("listener"), None)?;
+				Ok(())
+			})?;
+			slate = api.finalize_tx(m, &slate)?;
+			api.post_tx(m, &slate.tx, false)?;
+			Ok(())
+		})?;
+		let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+		Ok(slate.tx.inputs().len())
+	};
+	// refresh, returning the number of locked outputs and those waiting for
+	// a second refresh to confirm they're spent
+	let refresh = || -> Result<(usize, usize), libwallet::Error> {
+		let mut counts = (0, 0);
+		wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+			let (refreshed, outputs) = api.retrieve_outputs(m, true, true, false, None)?;
+			assert!(refreshed);
+			let locked: Vec<_> = outputs
+				.into_iter()
+				.filter(|o| o.output.status == OutputStatus::Locked)
+				.collect();
+			let missing = locked
+				.iter()
+				.filter(|o| o.output.missing_since.is_some())
+				.count();
+			counts = (locked.len(), missing);
+			Ok(())
+		})?;
+		Ok(counts)
+	};
+
+	// the inputs of a confirmed send stay locked while the node syncs
+	client1.set_node_sync_status("body_sync");
+	let inputs = send()?;
+	assert!(inputs > 0);
+	assert_eq!(refresh()?, (inputs, 0));
+
+	// and are spent once it's synced
+	client1.set_node_sync_status(NODE_SYNCED_STATUS);
+	assert_eq!(refresh()?, (0, 0));
+
+	// when asked to, two refreshes must find them missing
+	{
+		let mut w_lock = wallet1.lock();
+		w_lock.lc_provider()?.set_confirm_missing_outputs(true)?;
+	}
+	let inputs = send()?;
+	assert_eq!(refresh()?, (inputs, inputs));
+	assert_eq!(refresh()?, (0, 0));
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn spent_guard() {
+	let test_dir = "test_output/spent_guard";
+	setup(test_dir);
+	if let Err(e) = spent_guard_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -22,7 +22,7 @@ use epic_wallet_util::epic_core as core;
 
 use self::core::core::transaction;
 use self::core::global;
-use self::libwallet::{InitTxArgs, OutputStatus, Slate};
+use self::libwallet::{Amount, InitTxArgs, OutputStatus, Slate, TxLogEntryType};
 use impls::test_framework::{self, LocalWalletClient};
 use std::thread;
 use std::time::Duration;
@@ -89,6 +89,26 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 			(wallet1_info.last_confirmed_height - cm) * reward
 		);
 		assert_eq!(wallet1_info.amount_immature, cm * reward);
+		assert!(wallet1_info.breakdown.is_none());
+
+		// the detailed summary attributes everything to coinbase outputs
+		let (_, detailed_info) = api.retrieve_detailed_summary_info(m, true, 1)?;
+		let breakdown = detailed_info.breakdown.unwrap();
+		assert_eq!(breakdown.coinbase.total, wallet1_info.total);
+		assert_eq!(
+			breakdown.coinbase.amount_currently_spendable,
+			wallet1_info.amount_currently_spendable
+		);
+		assert_eq!(breakdown.coinbase.amount_immature, cm * reward);
+		assert_eq!(breakdown.foundation.total, 0);
+		assert_eq!(breakdown.regular.total, 0);
+		assert_eq!(breakdown.immature_schedule.len() as u64, cm);
+		let last_height = wallet1_info.last_confirmed_height;
+		for (i, bucket) in breakdown.immature_schedule.iter().enumerate() {
+			assert_eq!(bucket.lock_height, last_height + 1 + i as u64);
+			assert_eq!(bucket.num_outputs, 1);
+			assert_eq!(bucket.amount, reward);
+		}
 		Ok(())
 	})?;
 
@@ -100,7 +120,7 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 		// note this will increment the block count as part of the transaction "Posting"
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount,
+			amount: Amount::from_freemans(amount),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -147,6 +167,12 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 		assert_eq!(tx.amount_debited - tx.amount_credited, fee + amount);
 		println!("tx: {:?}", tx);
 		assert_eq!(Some(fee), tx.fee);
+		// with how the fee was worked out
+		let breakdown = tx.fee_breakdown.clone().unwrap();
+		assert_eq!(breakdown.fee, fee);
+		assert_eq!(breakdown.weight * breakdown.fee_base, fee);
+		assert_eq!(breakdown.num_inputs, tx.num_inputs);
+		assert_eq!((breakdown.num_outputs, breakdown.num_kernels), (2, 1));
 		Ok(())
 	})?;
 
@@ -160,9 +186,11 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 		let tx = tx.unwrap();
 		assert!(!tx.confirmed);
 		assert!(tx.confirmation_ts.is_none());
+		assert!(tx.confirmations.is_none());
 		assert_eq!(amount, tx.amount_credited);
 		assert_eq!(0, tx.amount_debited);
 		assert_eq!(None, tx.fee);
+		assert_eq!(None, tx.fee_breakdown);
 		Ok(())
 	})?;
 
@@ -241,6 +269,50 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 		let tx = tx.unwrap();
 		assert!(tx.confirmed);
 		assert!(tx.confirmation_ts.is_some());
+
+		// and carries the confirming block and its depth
+		let confirmation_height = tx.confirmation_height.unwrap();
+		assert!(tx.confirmation_hash.is_some());
+		assert_eq!(
+			tx.confirmations,
+			Some(wallet2_info.last_confirmed_height - confirmation_height + 1)
+		);
+		assert!(tx.confirmations.unwrap() > 1);
+		Ok(())
+	})?;
+
+	// mining rewards are broken down by the algorithm of each rewarded block
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, true, None, None)?;
+		let coinbase: Vec<_> = txs
+			.iter()
+			.filter(|t| t.tx_type == TxLogEntryType::ConfirmedCoinbase)
+			.collect();
+		assert!(coinbase.iter().all(|t| t.pow_type.is_some()));
+
+		let (refreshed, rewards) = api.retrieve_mining_rewards(m, true, None, None, None)?;
+		assert!(refreshed);
+		assert!(rewards.iter().all(|r| r.pow_type.is_some()));
+		let num_blocks: u64 = rewards.iter().map(|r| r.num_blocks).sum();
+		assert_eq!(num_blocks, coinbase.len() as u64);
+		let amount: u64 = rewards.iter().map(|r| r.amount).sum();
+		assert_eq!(
+			amount,
+			coinbase
+				.iter()
+				.map(|t| t.amount_credited.freemans())
+				.sum::<u64>()
+		);
+
+		// filtering by algorithm only returns that algorithm's rewards
+		let pow_type = rewards[0].pow_type;
+		let (_, filtered) = api.retrieve_mining_rewards(m, false, pow_type, None, None)?;
+		assert_eq!(filtered, vec![rewards[0].clone()]);
+
+		// and by height range
+		let (_, filtered) = api.retrieve_mining_rewards(m, false, None, Some(1), Some(1))?;
+		let num_blocks: u64 = filtered.iter().map(|r| r.num_blocks).sum();
+		assert_eq!(num_blocks, 1);
 		Ok(())
 	})?;
 
@@ -248,7 +320,7 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 	wallet::controller::owner_single_use(wallet1.clone(), mask1, |sender_api, m| {
 		let init_args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount * 2,
+			amount: Amount::from_freemans(amount * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -262,7 +334,7 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 
 		let init_args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount * 2,
+			amount: Amount::from_freemans(amount * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -283,7 +355,7 @@ fn basic_transaction_api(test_dir: &'static str) -> Result<(), libwallet::Error>
 		// note this will increment the block count as part of the transaction "Posting"
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount * 2,
+			amount: Amount::from_freemans(amount * 2),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -382,7 +454,7 @@ fn tx_rollback(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	// few values to keep things shorter
 	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
 	let cm = global::coinbase_maturity(); // assume all testing precedes soft fork height
-									  // mine a few blocks
+									   // mine a few blocks
 	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
 
 	let amount = 30_000_000_000;
@@ -391,7 +463,7 @@ fn tx_rollback(test_dir: &'static str) -> Result<(), libwallet::Error> {
 		// note this will increment the block count as part of the transaction "Posting"
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount,
+			amount: Amount::from_freemans(amount),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -467,14 +539,29 @@ fn tx_rollback(test_dir: &'static str) -> Result<(), libwallet::Error> {
 	// Wallet 1 decides to roll back instead
 	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
 		// can't roll back coinbase
-		let res = api.cancel_tx(m, Some(1), None);
+		let res = api.cancel_tx(m, Some(1), None, false);
 		assert!(res.is_err());
 		let (_, txs) = api.retrieve_txs(m, true, None, None)?;
 		let tx = txs
 			.iter()
 			.find(|t| t.tx_slate_id == Some(slate.id))
 			.unwrap();
-		api.cancel_tx(m, Some(tx.id), None)?;
+		// the preview changes nothing, and the unposted tx can't confirm
+		let preview = api.preview_cancel_tx(m, Some(tx.id), None)?;
+		assert_eq!(preview.unlocked_outputs.len(), 2);
+		assert_eq!(preview.deleted_outputs.len(), 1);
+		assert!(preview.kernel_height.is_none());
+		assert!(!preview.posted);
+		assert!(!preview.may_confirm);
+		let (_, outputs) = api.retrieve_outputs(m, true, false, Some(tx.id))?;
+		assert_eq!(
+			outputs
+				.iter()
+				.filter(|o| o.output.status == OutputStatus::Locked)
+				.count(),
+			2
+		);
+		api.cancel_tx(m, Some(tx.id), None, false)?;
 		let (refreshed, wallet1_info) = api.retrieve_summary_info(m, true, 1)?;
 		assert!(refreshed);
 		println!(
@@ -488,7 +575,7 @@ fn tx_rollback(test_dir: &'static str) -> Result<(), libwallet::Error> {
 			(wallet1_info.last_confirmed_height - cm) * reward
 		);
 		// can't roll back again
-		let res = api.cancel_tx(m, Some(tx.id), None);
+		let res = api.cancel_tx(m, Some(tx.id), None, false);
 		assert!(res.is_err());
 
 		Ok(())
@@ -501,19 +588,61 @@ fn tx_rollback(test_dir: &'static str) -> Result<(), libwallet::Error> {
 			.iter()
 			.find(|t| t.tx_slate_id == Some(slate.id))
 			.unwrap();
-		api.cancel_tx(m, Some(tx.id), None)?;
+		// the sender could still post what the recipient signed
+		let preview = api.preview_cancel_tx(m, Some(tx.id), None)?;
+		assert!(preview.unlocked_outputs.is_empty());
+		assert_eq!(preview.deleted_outputs.len(), 1);
+		assert!(preview.may_confirm);
+		api.cancel_tx(m, Some(tx.id), None, false)?;
 		let (refreshed, wallet2_info) = api.retrieve_summary_info(m, true, 1)?;
 		assert!(refreshed);
 		// check all eligible inputs should be now be spendable
 		assert_eq!(wallet2_info.amount_currently_spendable, 0,);
 		assert_eq!(wallet2_info.total, 0,);
 		// can't roll back again
-		let res = api.cancel_tx(m, Some(tx.id), None);
+		let res = api.cancel_tx(m, Some(tx.id), None, false);
 		assert!(res.is_err());
 
 		Ok(())
 	})?;
 
+	// Wallet 1 abandons a couple of sends and bulk cancels them
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, info_before) = api.retrieve_summary_info(m, true, 1)?;
+		let mut slate_ids = vec![];
+		for _ in 0..2 {
+			let args = InitTxArgs {
+				src_acct_name: None,
+				amount: Amount::from_freemans(reward),
+				minimum_confirmations: 2,
+				max_outputs: 500,
+				num_change_outputs: 1,
+				selection_strategy_is_use_all: false,
+				..Default::default()
+			};
+			let slate = api.init_send_tx(m, args)?;
+			api.tx_lock_outputs(m, &slate, 0)?;
+			slate_ids.push(slate.id);
+		}
+		// nothing is old enough yet
+		assert!(api.cancel_stale_txs(m, 60)?.is_empty());
+
+		let cancelled = api.cancel_stale_txs(m, 0)?;
+		assert_eq!(cancelled.len(), 2);
+		for tx in cancelled.iter() {
+			assert!(slate_ids.contains(&tx.tx_slate_id.unwrap()));
+			assert_eq!(tx.tx_type, TxLogEntryType::TxSentCancelled);
+		}
+		let (_, info_after) = api.retrieve_summary_info(m, true, 1)?;
+		assert_eq!(
+			info_after.amount_currently_spendable,
+			info_before.amount_currently_spendable
+		);
+		assert_eq!(info_after.amount_locked, 0);
+		assert!(api.cancel_stale_txs(m, 0)?.is_empty());
+		Ok(())
+	})?;
+
 	// let logging finish
 	thread::sleep(Duration::from_millis(200));
 	Ok(())
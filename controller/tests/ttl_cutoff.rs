@@ -20,7 +20,7 @@ extern crate epic_wallet_util;
 
 use epic_wallet_libwallet as libwallet;
 use impls::test_framework::{self, LocalWalletClient};
-use libwallet::{InitTxArgs, Slate, TxLogEntryType};
+use libwallet::{Amount, InitTxArgs, Slate, TxLogEntryType};
 use std::thread;
 use std::time::Duration;
 
@@ -80,7 +80,7 @@ fn ttl_cutoff_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error>
 		// note this will increment the block count as part of the transaction "Posting"
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount,
+			amount: Amount::from_freemans(amount),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -130,7 +130,7 @@ fn ttl_cutoff_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error>
 		// note this will increment the block count as part of the transaction "Posting"
 		let args = InitTxArgs {
 			src_acct_name: None,
-			amount: amount,
+			amount: Amount::from_freemans(amount),
 			minimum_confirmations: 2,
 			max_outputs: 500,
 			num_change_outputs: 1,
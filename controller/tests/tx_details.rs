@@ -0,0 +1,137 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test retrieving a transaction with everything recorded about it
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, ErrorKind, InitTxArgs, OutputStatus, Slate};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn tx_details_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let amount = core::consensus::EPIC_BASE;
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(amount),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.init_send_tx(m, args)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+			slate = api.receive_tx(&slate, Some("listener"), None)?;
+			Ok(())
+		})?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 3, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (_, txs) = api.retrieve_txs(m, true, None, Some(slate.id))?;
+		let entry = txs[0].clone();
+		let (_, details) = api.get_tx_details(m, true, entry.id)?;
+		assert_eq!(details.tx.id, entry.id);
+		assert!(details.tx.confirmed);
+
+		// the spent inputs and change, as recorded against the transaction
+		assert_eq!(details.inputs.len(), entry.num_inputs);
+		assert_eq!(details.outputs.len(), entry.num_outputs);
+		for i in details.inputs.iter() {
+			assert_eq!(i.output.status, OutputStatus::Spent);
+		}
+		let inputs: u64 = details.inputs.iter().map(|i| i.output.value).sum();
+		let change: u64 = details.outputs.iter().map(|o| o.output.value).sum();
+		assert_eq!(inputs, entry.amount_debited);
+		assert_eq!(change, entry.amount_credited);
+
+		let stored = details.stored_tx.unwrap();
+		assert_eq!(details.kernel_excess, entry.kernel_excess);
+		assert_eq!(details.kernel_excess, Some(stored.body.kernels[0].excess));
+		assert!(details.payment_proof.is_none());
+
+		// the receiving side of the self-send shares the slate
+		assert_eq!(details.slate_history.len(), 1);
+		assert_eq!(details.slate_history[0].tx_slate_id, Some(slate.id));
+		assert_eq!(details.slate_history[0].amount_credited, amount);
+
+		match api.get_tx_details(m, false, 1000) {
+			Err(e) => match e.kind() {
+				ErrorKind::TransactionDoesntExist(_) => {}
+				k => panic!("unexpected error {}", k),
+			},
+			Ok(_) => panic!("details of a missing transaction"),
+		}
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn tx_details() {
+	let test_dir = "test_output/tx_details";
+	setup(test_dir);
+	if let Err(e) = tx_details_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,167 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test retrieving tx log entries matching a filter
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate, TxFilter, TxLogEntryType};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn tx_filter_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 4, false);
+
+	let amount = core::consensus::EPIC_BASE;
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let args = InitTxArgs {
+			src_acct_name: None,
+			amount: Amount::from_freemans(amount),
+			minimum_confirmations: 2,
+			max_outputs: 500,
+			num_change_outputs: 1,
+			selection_strategy_is_use_all: false,
+			message: Some("March payroll".to_owned()),
+			..Default::default()
+		};
+		slate = api.init_send_tx(m, args)?;
+		slate = client1.send_tx_slate_direct("wallet2", &slate)?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		slate = api.finalize_tx(m, &slate)?;
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 1, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		let (refreshed, txs) = api.retrieve_txs(m, true, None, None)?;
+		assert!(refreshed);
+		assert_eq!(txs.len(), 6);
+		let ids = |txs: Vec<libwallet::TxLogEntry>| txs.iter().map(|t| t.id).collect::<Vec<_>>();
+
+		let payroll: TxFilter =
+			r#"{"and": [{"type": "sent"}, {"amount_gt": "100000000"}, {"message": "payroll"}]}"#
+				.parse()
+				.unwrap();
+		let (_, found) = api.retrieve_txs_by_filter(m, false, &payroll)?;
+		assert_eq!(found.len(), 1);
+		assert_eq!(found[0].tx_slate_id, Some(slate.id));
+		assert!(found[0].confirmed);
+
+		let early_rewards = TxFilter::And(vec![
+			TxFilter::Type(TxLogEntryType::ConfirmedCoinbase),
+			TxFilter::MaxHeight(2),
+		]);
+		let (_, found) = api.retrieve_txs_by_filter(m, false, &early_rewards)?;
+		assert_eq!(ids(found), vec![0, 1]);
+
+		// entries come in the order they were created, whichever index
+		// they're found in
+		let either = TxFilter::Or(vec![
+			TxFilter::SlateId(slate.id),
+			TxFilter::And(vec![
+				TxFilter::Type(TxLogEntryType::ConfirmedCoinbase),
+				TxFilter::MinHeight(4),
+			]),
+		]);
+		let (_, found) = api.retrieve_txs_by_filter(m, false, &either)?;
+		let sent_id = txs
+			.iter()
+			.find(|t| t.tx_slate_id == Some(slate.id))
+			.unwrap()
+			.id;
+		assert_eq!(ids(found), vec![3, sent_id, 5]);
+
+		let not_coinbase =
+			TxFilter::Not(Box::new(TxFilter::Type(TxLogEntryType::ConfirmedCoinbase)));
+		let (_, found) = api.retrieve_txs_by_filter(m, false, &not_coinbase)?;
+		assert_eq!(ids(found), vec![sent_id]);
+
+		let (_, found) = api.retrieve_txs_by_filter(m, false, &TxFilter::And(vec![]))?;
+		assert_eq!(found.len(), 6);
+		let (_, found) = api.retrieve_txs_by_filter(m, false, &TxFilter::Confirmed(false))?;
+		assert!(found.is_empty());
+		Ok(())
+	})?;
+
+	// the receiving wallet's entry is its own
+	wallet::controller::owner_single_use(wallet2.clone(), mask2, |api, m| {
+		let received = TxFilter::Type(TxLogEntryType::TxReceived);
+		let (_, found) = api.retrieve_txs_by_filter(m, true, &received)?;
+		assert_eq!(found.len(), 1);
+		assert_eq!(found[0].amount_credited, amount);
+		let (_, found) =
+			api.retrieve_txs_by_filter(m, false, &TxFilter::Type(TxLogEntryType::TxSent))?;
+		assert!(found.is_empty());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn tx_filter() {
+	let test_dir = "test_output/tx_filter";
+	setup(test_dir);
+	if let Err(e) = tx_filter_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,157 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the workflow stages reported by `tx show` and `tx resume`
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_util::epic_core as core;
+
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{Amount, InitTxArgs, Slate};
+use std::thread;
+use std::time::Duration;
+use wallet::command::TxStage;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn tx_stage_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		true
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let reward = core::consensus::BLOCK_TIME_SEC * core::consensus::EPIC_BASE;
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.create_account_path(m, "listener")?;
+		Ok(())
+	})?;
+
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 10, false);
+
+	let args = InitTxArgs {
+		src_acct_name: None,
+		amount: Amount::from_freemans(reward),
+		minimum_confirmations: 2,
+		max_outputs: 500,
+		num_change_outputs: 1,
+		selection_strategy_is_use_all: false,
+		..Default::default()
+	};
+
+	let stage_of =
+		|slate: &Slate, account: &str, refresh: bool| -> Result<TxStage, libwallet::Error> {
+			let mut stage = TxStage::Unknown;
+			wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+				api.set_active_account(m, account)?;
+				let height = api.node_height(m)?.height;
+				let (_, txs) = api.retrieve_txs(m, refresh, None, Some(slate.id))?;
+				stage = TxStage::of(&txs[0], height);
+				api.set_active_account(m, "default")?;
+				Ok(())
+			})?;
+			Ok(stage)
+		};
+
+	// sent, waiting for the response
+	let mut slate = Slate::blank(2);
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		Ok(())
+	})?;
+	assert_eq!(
+		stage_of(&slate, "default", true)?,
+		TxStage::AwaitingResponse
+	);
+
+	// received, waiting for the sender
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		slate = api.receive_tx(&slate, Some("listener"), None)?;
+		Ok(())
+	})?;
+	assert_eq!(
+		stage_of(&slate, "listener", true)?,
+		TxStage::AwaitingFinalization
+	);
+	assert_eq!(
+		stage_of(&slate, "default", true)?,
+		TxStage::AwaitingResponse
+	);
+
+	// finalized without posting
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.finalize_tx(m, &slate)?;
+		Ok(())
+	})?;
+	assert_eq!(stage_of(&slate, "default", true)?, TxStage::ReadyToPost);
+
+	// posted and mined
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		api.post_tx(m, &slate.tx, false)?;
+		Ok(())
+	})?;
+	// the test node mines posted transactions straight away, so only the
+	// stored state is still awaiting confirmation
+	assert_eq!(
+		stage_of(&slate, "default", false)?,
+		TxStage::AwaitingConfirmation
+	);
+	assert_eq!(stage_of(&slate, "default", true)?, TxStage::Confirmed);
+
+	// cancelled
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		slate = api.init_send_tx(m, args.clone())?;
+		api.tx_lock_outputs(m, &slate, 0)?;
+		api.cancel_tx(m, None, Some(slate.id), false)?;
+		Ok(())
+	})?;
+	assert_eq!(stage_of(&slate, "default", true)?, TxStage::Cancelled);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn tx_stage() {
+	let test_dir = "test_output/tx_stage";
+	setup(test_dir);
+	if let Err(e) = tx_stage_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,93 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test the order of tx log entries and retrieving them by time
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::{self, LocalWalletClient};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn tx_times_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let start = Utc::now();
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+
+	wallet::controller::owner_single_use(wallet1.clone(), mask1, |api, m| {
+		// entries are listed in the order they were created
+		let (_, txs) = api.retrieve_txs(m, false, None, None)?;
+		assert_eq!(txs.len(), 5);
+		let seqs = txs.iter().map(|t| t.seq).collect::<Vec<_>>();
+		assert_eq!(seqs, vec![1, 2, 3, 4, 5]);
+
+		let ids = txs.iter().map(|t| t.id).collect::<Vec<_>>();
+		let (_, in_range) = api.retrieve_txs_by_time(m, false, Some(start), None)?;
+		assert_eq!(in_range.iter().map(|t| t.id).collect::<Vec<_>>(), ids);
+		let (_, in_range) = api.retrieve_txs_by_time(m, false, None, Some(start))?;
+		assert!(in_range.is_empty());
+		let later = Utc::now() + ChronoDuration::days(1);
+		let (_, in_range) = api.retrieve_txs_by_time(m, false, Some(later), None)?;
+		assert!(in_range.is_empty());
+
+		// the start of a range can't be after its end
+		assert!(api
+			.retrieve_txs_by_time(m, false, Some(later), Some(start))
+			.is_err());
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn tx_times() {
+	let test_dir = "test_output/tx_times";
+	setup(test_dir);
+	if let Err(e) = tx_times_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -105,6 +105,59 @@ fn updater_thread_test_impl(test_dir: &'static str) -> Result<(), libwallet::Err
 	Ok(())
 }
 
+/// tip updater thread test impl
+fn tip_updater_thread_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	// Create a new proxy to simulate server and wallet responses
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let bh = 10u64;
+	let _ =
+		test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, bh as usize, false);
+
+	let owner_api = api::Owner::new(wallet1.clone());
+	owner_api.start_tip_updater(mask1, Duration::from_millis(500))?;
+
+	// first poll should always update
+	thread::sleep(Duration::from_secs(5));
+	let messages = owner_api.get_updater_messages(1000)?;
+	assert!(messages.len() > 0);
+
+	// tip hasn't moved, so no further updates should have been performed
+	thread::sleep(Duration::from_secs(3));
+	let messages = owner_api.get_updater_messages(1000)?;
+	assert_eq!(messages.len(), 0);
+
+	// mine a block, which should trigger an update
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 1, false);
+	thread::sleep(Duration::from_secs(5));
+	let messages = owner_api.get_updater_messages(1000)?;
+	assert!(messages.len() > 0);
+
+	owner_api.stop_updater()?;
+	thread::sleep(Duration::from_secs(2));
+	Ok(())
+}
+
 #[test]
 fn updater_thread() {
 	let test_dir = "test_output/updater_thread";
@@ -114,3 +167,13 @@ fn updater_thread() {
 	}
 	clean_output_dir(test_dir);
 }
+
+#[test]
+fn tip_updater_thread() {
+	let test_dir = "test_output/tip_updater_thread";
+	setup(test_dir);
+	if let Err(e) = tip_updater_thread_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
@@ -0,0 +1,108 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test passing upgrade advisories on to counterparties
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use ed25519_dalek::Keypair as DalekKeypair;
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use ed25519_dalek::SecretKey as DalekSecretKey;
+use epic_wallet_libwallet as libwallet;
+use impls::test_framework::LocalWalletClient;
+use libwallet::api_impl::foreign::WALLET_VERSION;
+use libwallet::{VersionAdvisory, VersionInfo};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn version_advisory_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+
+	create_wallet_and_add!(
+		_client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let secret = DalekSecretKey::from_bytes(&[1; 32]).unwrap();
+	let public = DalekPublicKey::from(&secret);
+	let keypair = DalekKeypair { secret, public };
+	let message = Some("fixes a fee rounding bug".to_owned());
+	let advisory = VersionAdvisory {
+		min_recommended_version: "3.0.1".to_owned(),
+		signature: keypair.sign(&VersionAdvisory::message("3.0.1", &message)),
+		message,
+		signer: public,
+	};
+	advisory.verify()?;
+	assert!(advisory.is_outdated("3.0.0"));
+	assert!(advisory.is_outdated("v2.9.12-beta"));
+	assert!(!advisory.is_outdated("3.0.1"));
+	assert!(!advisory.is_outdated("3.1.0"));
+	assert!(!advisory.is_outdated("unknown"));
+
+	// an advisory changed after signing doesn't verify
+	let mut changed = advisory.clone();
+	changed.min_recommended_version = "9.0.0".to_owned();
+	assert!(changed.verify().is_err());
+
+	wallet::controller::foreign_single_use(wallet1.clone(), mask1_i.clone(), |api| {
+		let info = api.check_version()?;
+		assert_eq!(info.wallet_version, Some(WALLET_VERSION.to_owned()));
+		assert!(info.version_advisory.is_none());
+
+		api.set_version_advisory(Some(advisory.clone()));
+		let info = api.check_version()?;
+		// as counterparties read it
+		let json = serde_json::to_string(&info).unwrap();
+		let info: VersionInfo = serde_json::from_str(&json).unwrap();
+		let passed_on = info.version_advisory.unwrap();
+		passed_on.verify()?;
+		assert_eq!(passed_on.signer, public);
+		assert_eq!(passed_on.min_recommended_version, "3.0.1");
+		Ok(())
+	})?;
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn version_advisory() {
+	let test_dir = "test_output/version_advisory";
+	setup(test_dir);
+	if let Err(e) = version_advisory_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
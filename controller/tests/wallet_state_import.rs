@@ -0,0 +1,176 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test bootstrapping a wallet from a state export of another instance
+#[macro_use]
+extern crate log;
+extern crate epic_wallet_api as api;
+extern crate epic_wallet_controller as wallet;
+extern crate epic_wallet_impls as impls;
+
+use epic_wallet_libwallet as libwallet;
+use epic_wallet_util::epic_util::ZeroingString;
+use impls::test_framework::{self, LocalWalletClient};
+use libwallet::{ErrorKind, WalletStateImport};
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+use common::{clean_output_dir, create_wallet_proxy, setup};
+
+fn wallet_state_import_test_impl(test_dir: &'static str) -> Result<(), libwallet::Error> {
+	let seed_phrase = "affair pistol cancel crush garment candy ancient flag work \
+	                   market crush dry stand focus mutual weapon offer ceiling rival turn team spring \
+	                   where swift";
+	let seed_phrase = Some(ZeroingString::from(seed_phrase));
+
+	let mut wallet_proxy = create_wallet_proxy(test_dir);
+	let chain = wallet_proxy.chain.clone();
+
+	create_wallet_and_add!(
+		client1,
+		wallet1,
+		mask1_i,
+		test_dir,
+		"wallet1",
+		seed_phrase,
+		&mut wallet_proxy,
+		false
+	);
+	let mask1 = (&mask1_i).as_ref();
+	create_wallet_and_add!(
+		client2,
+		wallet2,
+		mask2_i,
+		test_dir,
+		"wallet2",
+		seed_phrase,
+		&mut wallet_proxy,
+		false
+	);
+	let mask2 = (&mask2_i).as_ref();
+	create_wallet_and_add!(
+		client3,
+		wallet3,
+		mask3_i,
+		test_dir,
+		"wallet3",
+		None,
+		&mut wallet_proxy,
+		false
+	);
+	let mask3 = (&mask3_i).as_ref();
+
+	// Set the wallet proxy listener running
+	thread::spawn(move || {
+		if let Err(e) = wallet_proxy.run() {
+			error!("Wallet Proxy error: {}", e);
+		}
+	});
+
+	let refused = |res: Result<WalletStateImport, libwallet::Error>, msg: &str| match res {
+		Err(e) => match e.kind() {
+			ErrorKind::StateImport(m) => assert!(m.contains(msg), "{}", m),
+			k => panic!("unexpected error {}", k),
+		},
+		Ok(_) => panic!("wallet state imported"),
+	};
+
+	// a wallet that hasn't been scanned has no state to export
+	let owner_api1 = api::Owner::new(wallet1.clone());
+	assert!(owner_api1.export_wallet_state(mask1).is_err());
+
+	owner_api1.create_account_path(mask1, "savings")?;
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 5, false);
+	let (_, info1) = owner_api1.retrieve_summary_info(mask1, true, 1)?;
+	let export = owner_api1.export_wallet_state(mask1)?;
+	assert_eq!(export.state.height, info1.last_confirmed_height);
+	assert_eq!(export.state.accounts.len(), 2);
+	assert_eq!(export.state.outputs.len(), 5);
+	export.verify()?;
+
+	// only a wallet with the same seed can import the export, unaltered
+	let owner_api2 = api::Owner::new(wallet2.clone());
+	let owner_api3 = api::Owner::new(wallet3.clone());
+	refused(
+		owner_api3.import_wallet_state(mask3, &export),
+		"different seed",
+	);
+	let mut tampered = export.clone();
+	tampered.state.outputs[0].value += 1;
+	refused(
+		owner_api2.import_wallet_state(mask2, &tampered),
+		"signature doesn't verify",
+	);
+	let mut tampered = export.clone();
+	tampered.state.version += 1;
+	refused(owner_api2.import_wallet_state(mask2, &tampered), "version");
+
+	let import = owner_api2.import_wallet_state(mask2, &export)?;
+	assert_eq!(import.height, export.state.height);
+	assert_eq!(import.accounts, vec!["savings".to_owned()]);
+	assert_eq!(import.imported.len(), 5);
+	assert!(import.skipped.is_empty());
+
+	// the new wallet has the same balance and accounts without a scan, and
+	// carries on from the export's height
+	let (_, info2) = owner_api2.retrieve_summary_info(mask2, false, 1)?;
+	assert_eq!(info2.total, info1.total);
+	assert_eq!(
+		info2.amount_currently_spendable,
+		info1.amount_currently_spendable
+	);
+	assert_eq!(info2.amount_immature, info1.amount_immature);
+	let accounts = owner_api2.accounts(mask2)?;
+	assert!(accounts.iter().any(|a| a.label == "savings"));
+	{
+		wallet_inst!(wallet2, w);
+		assert_eq!(w.last_scanned_block()?.height, export.state.height);
+	}
+
+	// new keys don't reuse those of the imported outputs
+	let (_, outputs) = owner_api2.retrieve_outputs(mask2, false, false, false, None)?;
+	let next = {
+		wallet_inst!(wallet2, w);
+		w.next_child(mask2)?
+	};
+	assert!(outputs.iter().all(|o| o.output.key_id != next));
+
+	// state can only be imported once, into a wallet without outputs
+	refused(
+		owner_api2.import_wallet_state(mask2, &export),
+		"already has outputs",
+	);
+
+	// and keeps up with the chain from there
+	let _ = test_framework::award_blocks_to_wallet(&chain, wallet1.clone(), mask1, 1, false);
+	let (_, info1) = owner_api1.retrieve_summary_info(mask1, true, 1)?;
+	let (_, info2) = owner_api2.retrieve_summary_info(mask2, true, 1)?;
+	assert_eq!(info2.total, info1.total);
+
+	// let logging finish
+	thread::sleep(Duration::from_millis(200));
+	Ok(())
+}
+
+#[test]
+fn wallet_state_import() {
+	let test_dir = "test_output/wallet_state_import";
+	setup(test_dir);
+	if let Err(e) = wallet_state_import_test_impl(test_dir) {
+		panic!("Libwallet Error: {} - {}", e, e.backtrace().unwrap());
+	}
+	clean_output_dir(test_dir);
+}
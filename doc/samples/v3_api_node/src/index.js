@@ -58,6 +58,8 @@ class JSONRequestEncrypted {
 	}
 
 	async send(key){
+		// checked by the wallet against its replay window
+		this.timestamp = Math.floor(Date.now() / 1000)
 		const aesCipher = aes256gcm(key);
 		const nonce = new Buffer.from(crypto.randomBytes(12));
 		let enc = aesCipher.encrypt(JSON.stringify(this), nonce);
@@ -0,0 +1,227 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Obtaining and renewing the foreign listener's TLS certificate from an ACME
+//! certificate authority (Let's Encrypt), answering HTTP-01 challenges itself
+use crate::util::RwLock;
+use crate::{Error, ErrorKind};
+
+use acme_lib::persist::FilePersist;
+use acme_lib::{create_p384_key, Account, Certificate, Directory, DirectoryUrl};
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use failure::ResultExt;
+
+const CERT_FILE: &'static str = "fullchain.pem";
+const KEY_FILE: &'static str = "privkey.pem";
+const CHALLENGE_PATH: &'static str = "/.well-known/acme-challenge/";
+
+/// Certificates are renewed once they have fewer days left than this
+pub const RENEW_DAYS_LEFT: i64 = 30;
+
+/// Milliseconds to wait between polls of the certificate authority
+const POLL_DELAY_MS: u64 = 5000;
+
+fn acme_error(e: acme_lib::Error) -> Error {
+	ErrorKind::Acme(format!("{}", e)).into()
+}
+
+/// Where and how to obtain the listener's certificate
+#[derive(Clone, Debug)]
+pub struct AcmeConfig {
+	/// Domain name the certificate is issued for
+	pub domain: String,
+	/// Contact address registered with the certificate authority
+	pub contact_email: String,
+	/// Address the HTTP-01 challenge responder listens on, which the
+	/// certificate authority reaches on port 80 of the domain
+	pub challenge_addr: String,
+	/// Directory holding the account key and issued certificates
+	pub cert_dir: String,
+	/// Use the certificate authority's staging environment, for testing
+	pub staging: bool,
+}
+
+impl AcmeConfig {
+	/// Path of the certificate chain file served by the listener
+	pub fn certificate_file(&self) -> String {
+		self.domain_dir()
+			.join(CERT_FILE)
+			.to_string_lossy()
+			.into_owned()
+	}
+
+	/// Path of the certificate's private key file
+	pub fn key_file(&self) -> String {
+		self.domain_dir()
+			.join(KEY_FILE)
+			.to_string_lossy()
+			.into_owned()
+	}
+
+	fn domain_dir(&self) -> std::path::PathBuf {
+		Path::new(&self.cert_dir).join(&self.domain)
+	}
+
+	fn account(&self) -> Result<Account<FilePersist>, Error> {
+		fs::create_dir_all(&self.cert_dir).context(ErrorKind::IO)?;
+		let url = match self.staging {
+			true => DirectoryUrl::LetsEncryptStaging,
+			false => DirectoryUrl::LetsEncrypt,
+		};
+		let dir = Directory::from_url(FilePersist::new(&self.cert_dir), url).map_err(acme_error)?;
+		dir.account(&self.contact_email).map_err(acme_error)
+	}
+
+	/// Whether the stored certificate is missing or has fewer than
+	/// [RENEW_DAYS_LEFT](constant.RENEW_DAYS_LEFT.html) days left
+	pub fn renewal_due(&self) -> Result<bool, Error> {
+		let cert = self
+			.account()?
+			.certificate(&self.domain)
+			.map_err(acme_error)?;
+		Ok(match cert {
+			Some(c) => c.valid_days_left() < RENEW_DAYS_LEFT,
+			None => true,
+		})
+	}
+
+	/// Make sure a current certificate and its key are written to
+	/// [certificate_file](#method.certificate_file) and
+	/// [key_file](#method.key_file), ordering a new one if needed
+	pub fn ensure_certificate(&self) -> Result<(), Error> {
+		let acc = self.account()?;
+		let cert = match acc.certificate(&self.domain).map_err(acme_error)? {
+			Some(c) if c.valid_days_left() >= RENEW_DAYS_LEFT => c,
+			_ => self.order_certificate(&acc)?,
+		};
+		fs::create_dir_all(self.domain_dir()).context(ErrorKind::IO)?;
+		let mut file = File::create(self.certificate_file()).context(ErrorKind::IO)?;
+		file.write_all(cert.certificate().as_bytes())
+			.context(ErrorKind::IO)?;
+		let mut file = File::create(self.key_file()).context(ErrorKind::IO)?;
+		file.write_all(cert.private_key().as_bytes())
+			.context(ErrorKind::IO)?;
+		Ok(())
+	}
+
+	fn order_certificate(&self, acc: &Account<FilePersist>) -> Result<Certificate, Error> {
+		warn!("Ordering TLS certificate for {}", self.domain);
+		let responder = ChallengeResponder::start(&self.challenge_addr)?;
+		let mut order = acc.new_order(&self.domain, &[]).map_err(acme_error)?;
+		let csr = loop {
+			if let Some(csr) = order.confirm_validations() {
+				break csr;
+			}
+			for auth in order.authorizations().map_err(acme_error)? {
+				let challenge = auth.http_challenge();
+				responder.add(challenge.http_token(), &challenge.http_proof());
+				challenge.validate(POLL_DELAY_MS).map_err(acme_error)?;
+			}
+			order.refresh().map_err(acme_error)?;
+		};
+		let cert = csr
+			.finalize_pkey(create_p384_key(), POLL_DELAY_MS)
+			.map_err(acme_error)?
+			.download_and_save_cert()
+			.map_err(acme_error)?;
+		warn!(
+			"TLS certificate for {} issued, valid for {} days",
+			self.domain,
+			cert.valid_days_left()
+		);
+		Ok(cert)
+	}
+}
+
+/// Answers HTTP-01 challenges while a certificate is being ordered, until
+/// dropped
+struct ChallengeResponder {
+	proofs: Arc<RwLock<HashMap<String, String>>>,
+	stopped: Arc<AtomicBool>,
+}
+
+impl ChallengeResponder {
+	fn start(addr: &str) -> Result<Self, Error> {
+		let listener = TcpListener::bind(addr).context(ErrorKind::Acme(format!(
+			"Unable to listen for HTTP-01 challenges on {}",
+			addr
+		)))?;
+		listener.set_nonblocking(true).context(ErrorKind::IO)?;
+		let responder = ChallengeResponder {
+			proofs: Arc::new(RwLock::new(HashMap::new())),
+			stopped: Arc::new(AtomicBool::new(false)),
+		};
+		let proofs = responder.proofs.clone();
+		let stopped = responder.stopped.clone();
+		thread::spawn(move || {
+			while !stopped.load(Ordering::SeqCst) {
+				match listener.accept() {
+					Ok((stream, _)) => {
+						if let Err(e) = respond(stream, &proofs) {
+							debug!("Error answering ACME challenge request: {}", e);
+						}
+					}
+					Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+						thread::sleep(Duration::from_millis(100));
+					}
+					Err(e) => warn!("Error accepting ACME challenge request: {}", e),
+				}
+			}
+		});
+		Ok(responder)
+	}
+
+	fn add(&self, token: &str, proof: &str) {
+		self.proofs
+			.write()
+			.insert(token.to_owned(), proof.to_owned());
+	}
+}
+
+impl Drop for ChallengeResponder {
+	fn drop(&mut self) {
+		self.stopped.store(true, Ordering::SeqCst);
+	}
+}
+
+fn respond(mut stream: TcpStream, proofs: &RwLock<HashMap<String, String>>) -> io::Result<()> {
+	stream.set_nonblocking(false)?;
+	stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+	let mut request_line = String::new();
+	BufReader::new(&stream).read_line(&mut request_line)?;
+	let proof = request_line
+		.split_whitespace()
+		.nth(1)
+		.filter(|path| path.starts_with(CHALLENGE_PATH))
+		.and_then(|path| proofs.read().get(&path[CHALLENGE_PATH.len()..]).cloned());
+	let response = match proof {
+		Some(p) => format!(
+			"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+			p.len(),
+			p
+		),
+		None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+	};
+	stream.write_all(response.as_bytes())
+}
@@ -0,0 +1,156 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Delivers slates through an external transport program, so new transports
+//! can be added without rebuilding the wallet.
+//!
+//! The program is run once per slate, without a shell. It's sent a single
+//! JSON request on stdin, which is then closed:
+//!
+//! ```json
+//! {"protocol_version": 1, "method": "send_tx", "dest": "<as given>", "slate": {...}}
+//! ```
+//!
+//! The program delivers the slate to `dest` however it sees fit, waits for
+//! the other party's response and writes a single JSON reply to stdout,
+//! either `{"slate": {...}}` with the response slate or `{"error": "..."}`.
+//! Slates are sent as V3, and any slate version the wallet reads is accepted
+//! back. A non-zero exit status fails the send, with the program's stderr
+//! reported.
+
+use crate::libwallet::{Error, ErrorKind, Slate, SlateVersion, VersionedSlate};
+use crate::SlateSender;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Version of the request and reply format
+pub const EXEC_PROTOCOL_VERSION: u16 = 1;
+
+/// Sends slates by running an external transport program
+#[derive(Clone)]
+pub struct ExecSlateSender {
+	program: String,
+	dest: String,
+}
+
+impl ExecSlateSender {
+	/// Deliver slates to `dest` by running `program`
+	pub fn new(program: &str, dest: &str) -> ExecSlateSender {
+		ExecSlateSender {
+			program: program.to_owned(),
+			dest: dest.to_owned(),
+		}
+	}
+
+	fn comms_error(&self, reason: String) -> Error {
+		ErrorKind::WalletComms(format!("Transport program {}: {}", self.program, reason)).into()
+	}
+}
+
+impl SlateSender for ExecSlateSender {
+	fn send_tx(&self, slate: &Slate) -> Result<Slate, Error> {
+		let request = json!({
+			"protocol_version": EXEC_PROTOCOL_VERSION,
+			"method": "send_tx",
+			"dest": self.dest,
+			"slate": VersionedSlate::into_version(slate.clone(), SlateVersion::V3),
+		});
+
+		let mut child = Command::new(&self.program)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(|e| self.comms_error(format!("unable to start: {}", e)))?;
+		{
+			// stdin is closed once the request is written
+			let mut stdin = child.stdin.take().unwrap();
+			stdin
+				.write_all(format!("{}\n", request).as_bytes())
+				.map_err(|e| self.comms_error(format!("unable to write request: {}", e)))?;
+		}
+		let output = child
+			.wait_with_output()
+			.map_err(|e| self.comms_error(format!("{}", e)))?;
+		if !output.status.success() {
+			return Err(self.comms_error(format!(
+				"exited with {}: {}",
+				output.status,
+				String::from_utf8_lossy(&output.stderr).trim()
+			)));
+		}
+
+		let reply: Value = serde_json::from_slice(&output.stdout)
+			.map_err(|e| self.comms_error(format!("invalid reply: {}", e)))?;
+		if let Some(e) = reply.get("error") {
+			let reason = match e.as_str() {
+				Some(s) => s.to_owned(),
+				None => e.to_string(),
+			};
+			return Err(self.comms_error(reason));
+		}
+		match reply.get("slate") {
+			Some(s) => Slate::deserialize_upgrade(&s.to_string()),
+			None => Err(self.comms_error("reply has neither a slate nor an error".to_owned())),
+		}
+	}
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+	use std::fs;
+	use std::os::unix::fs::PermissionsExt;
+
+	fn write_program(test_dir: &str, name: &str, script: &str) -> String {
+		let path = format!("{}/{}", test_dir, name);
+		fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+		fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+		path
+	}
+
+	#[test]
+	fn exec_transport() {
+		let test_dir = "test_output/exec_transport";
+		let _ = fs::remove_dir_all(test_dir);
+		fs::create_dir_all(test_dir).unwrap();
+		let slate = Slate::blank(2);
+
+		// the request carries the slate, so echoing it back is a valid reply
+		let echo = write_program(test_dir, "echo.sh", "cat");
+		let res = ExecSlateSender::new(&echo, "somewhere")
+			.send_tx(&slate)
+			.unwrap();
+		assert_eq!(res.id, slate.id);
+
+		// errors reported by the program, and failed runs, fail the send
+		let refuse = write_program(test_dir, "refuse.sh", "echo '{\"error\": \"no route\"}'");
+		let crash = write_program(test_dir, "crash.sh", "echo 'out of cheese' >&2; exit 3");
+		for (program, reason) in vec![(refuse, "no route"), (crash, "out of cheese")] {
+			match ExecSlateSender::new(&program, "").send_tx(&slate) {
+				Err(e) => match e.kind() {
+					ErrorKind::WalletComms(m) => assert!(m.contains(reason)),
+					k => panic!("unexpected error {}", k),
+				},
+				Ok(_) => panic!("{} didn't fail the send", program),
+			}
+		}
+		assert!(ExecSlateSender::new("./no-such-program", "")
+			.send_tx(&slate)
+			.is_err());
+
+		let _ = fs::remove_dir_all(test_dir);
+	}
+}
@@ -12,19 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::adapters::negotiate_slate_version;
 /// HTTP Wallet 'plugin' implementation
 use crate::client_utils::{Client, ClientError};
-use crate::libwallet::slate_versions::{SlateVersion, VersionedSlate};
-use crate::libwallet::{Error, ErrorKind, Slate};
+use crate::libwallet::api_impl::foreign::{
+	verify_response_signature, RESPONSE_ADDRESS_HEADER, RESPONSE_SIGNATURE_HEADER, WALLET_VERSION,
+};
+use crate::libwallet::slate_versions::{SlateVersion, VersionedSlate, CURRENT_SLATE_VERSION};
+use crate::libwallet::{address, Error, ErrorKind, Slate, VersionAdvisory, VersionInfo};
+use crate::util::from_hex;
 use crate::SlateSender;
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use ed25519_dalek::Signature as DalekSignature;
+use hyper::header::HeaderMap;
 use serde::Serialize;
 use serde_json::{json, Value};
 use std::net::SocketAddr;
+#[cfg(feature = "tor")]
 use std::path::MAIN_SEPARATOR;
 
+#[cfg(feature = "tor")]
 use crate::tor::config as tor_config;
+#[cfg(feature = "tor")]
 use crate::tor::process as tor_process;
 
+#[cfg(feature = "tor")]
 const TOR_CONFIG_PATH: &'static str = "tor/sender";
 
 #[derive(Clone)]
@@ -32,7 +44,9 @@ pub struct HttpSlateSender {
 	base_url: String,
 	use_socks: bool,
 	socks_proxy_addr: Option<SocketAddr>,
+	#[cfg_attr(not(feature = "tor"), allow(dead_code))]
 	tor_config_dir: String,
+	response_signer: Option<DalekPublicKey>,
 }
 
 impl HttpSlateSender {
@@ -46,10 +60,53 @@ impl HttpSlateSender {
 				use_socks: false,
 				socks_proxy_addr: None,
 				tor_config_dir: String::from(""),
+				response_signer: None,
 			})
 		}
 	}
 
+	/// Require the response slate to be signed by the given payment proof
+	/// address, so it's known to come from the intended recipient even if it
+	/// passed through relays
+	pub fn set_response_signer(&mut self, signer: Option<DalekPublicKey>) {
+		self.response_signer = signer;
+	}
+
+	/// Check a response was signed by the expected recipient, if there is one
+	fn check_response_signature(
+		&self,
+		request: &str,
+		headers: &HeaderMap,
+		response: &str,
+	) -> Result<(), Error> {
+		let signer = match self.response_signer {
+			Some(ref s) => s,
+			None => return Ok(()),
+		};
+		let header = |name: &str| -> Result<&str, Error> {
+			headers
+				.get(name)
+				.and_then(|v| v.to_str().ok())
+				.ok_or_else(|| {
+					ErrorKind::ResponseSignature(format!("response has no {} header", name)).into()
+				})
+		};
+		let address = address::pubkey_from_onion_v3(header(RESPONSE_ADDRESS_HEADER)?)?;
+		if &address != signer {
+			return Err(ErrorKind::ResponseSignature(format!(
+				"response signed by {}, expected {}",
+				address::onion_v3_from_pubkey(&address)?,
+				address::onion_v3_from_pubkey(signer)?
+			))
+			.into());
+		}
+		let signature = from_hex(header(RESPONSE_SIGNATURE_HEADER)?.to_owned())
+			.ok()
+			.and_then(|s| DalekSignature::from_bytes(&s).ok())
+			.ok_or_else(|| ErrorKind::ResponseSignature("invalid signature".to_owned()))?;
+		verify_response_signature(signer, request.as_bytes(), response.as_bytes(), &signature)
+	}
+
 	/// Switch to using socks proxy
 	pub fn with_socks_proxy(
 		base_url: &str,
@@ -64,8 +121,60 @@ impl HttpSlateSender {
 		Ok(ret)
 	}
 
-	/// Check version of the listening wallet
-	fn check_other_version(&self, url: &str) -> Result<SlateVersion, Error> {
+	/// URL of the listening wallet's foreign API
+	fn foreign_url(&self) -> String {
+		let trailing = match self.base_url.ends_with('/') {
+			true => "",
+			false => "/",
+		};
+		format!("{}{}v2/foreign", self.base_url, trailing)
+	}
+
+	/// Start a TOR process for sending, if sending through a socks proxy. It
+	/// runs until the returned process is dropped
+	#[cfg(feature = "tor")]
+	fn start_tor(&self) -> Result<tor_process::TorProcess, Error> {
+		let mut tor = tor_process::TorProcess::new();
+		if self.use_socks {
+			let tor_dir = format!(
+				"{}{}{}",
+				&self.tor_config_dir, MAIN_SEPARATOR, TOR_CONFIG_PATH
+			);
+			warn!(
+				"Starting TOR Process for send at {:?}",
+				self.socks_proxy_addr
+			);
+			tor_config::output_tor_sender_config(
+				&tor_dir,
+				&self.socks_proxy_addr.unwrap().to_string(),
+			)
+			.map_err(|e| ErrorKind::TorConfig(format!("{:?}", e).into()))?;
+			// Start TOR process
+			tor.torrc_path(&format!("{}/torrc", &tor_dir))
+				.working_dir(&tor_dir)
+				.timeout(20)
+				.completion_percent(100)
+				.launch()
+				.map_err(|e| ErrorKind::TorProcess(format!("{:?}", e).into()))?;
+		}
+		Ok(tor)
+	}
+
+	/// Without the tor feature nothing can be sent through a socks proxy
+	#[cfg(not(feature = "tor"))]
+	fn start_tor(&self) -> Result<(), Error> {
+		if self.use_socks {
+			return Err(ErrorKind::TorProcess(
+				"this wallet was built without TOR support".to_owned(),
+			)
+			.into());
+		}
+		Ok(())
+	}
+
+	/// Ask the listening wallet for its version. Slate versions this wallet
+	/// doesn't know are left out
+	fn other_version_info(&self, url: &str) -> Result<VersionInfo, Error> {
 		let req = json!({
 			"jsonrpc": "2.0",
 			"method": "check_version",
@@ -104,24 +213,58 @@ impl HttpSlateSender {
 			serde_json::from_value(resp_value["foreign_api_version"].clone()).unwrap();
 		let supported_slate_versions: Vec<String> =
 			serde_json::from_value(resp_value["supported_slate_versions"].clone()).unwrap();
-
-		// trivial tests for now, but will be expanded later
-		if foreign_api_version < 2 {
-			let report = format!("Other wallet reports unrecognized API format.");
-			error!("{}", report);
-			return Err(ErrorKind::ClientCallback(report).into());
-		}
-
-		if supported_slate_versions.contains(&"V3".to_owned()) {
-			return Ok(SlateVersion::V3);
-		}
-		if supported_slate_versions.contains(&"V2".to_owned()) {
-			return Ok(SlateVersion::V2);
+		let receiver_fee_contribution = match resp_value["receiver_fee_contribution"] {
+			Value::String(ref s) => s.parse::<u64>().ok(),
+			ref v => v.as_u64(),
+		};
+		let wallet_version = resp_value["wallet_version"].as_str().map(|v| v.to_owned());
+		// an advisory that doesn't verify is left out
+		let version_advisory =
+			serde_json::from_value::<VersionAdvisory>(resp_value["version_advisory"].clone())
+				.ok()
+				.filter(|a| a.verify().is_ok());
+		if let Some(ref a) = version_advisory {
+			if a.is_outdated(WALLET_VERSION) {
+				warn!(
+					"The recipient passed on an advisory, signed by {}, recommending \
+					 upgrading from epic-wallet {} to {} or newer{}",
+					address::onion_v3_from_pubkey(&a.signer)?,
+					WALLET_VERSION,
+					a.min_recommended_version,
+					a.message
+						.as_ref()
+						.map(|m| format!(": {}", m))
+						.unwrap_or_default()
+				);
+			}
 		}
+		Ok(VersionInfo {
+			foreign_api_version,
+			supported_slate_versions: supported_slate_versions
+				.into_iter()
+				.filter_map(|v| serde_json::from_value(Value::String(v)).ok())
+				.collect(),
+			receiver_fee_contribution,
+			wallet_version,
+			version_advisory,
+		})
+	}
 
-		let report = format!("Unable to negotiate slate format with other wallet.");
-		error!("{}", report);
-		Err(ErrorKind::ClientCallback(report).into())
+	/// Check version of the listening wallet, returning the slate version to
+	/// use, the newest both wallets support up to `max_version`, and the
+	/// largest part of the fee it offers to pay by contributing an input, if
+	/// it does
+	fn check_other_version(
+		&self,
+		url: &str,
+		max_version: u16,
+	) -> Result<(SlateVersion, Option<u64>), Error> {
+		let info = self.other_version_info(url)?;
+		let version = negotiate_slate_version(&info, max_version).map_err(|e| {
+			error!("{}", e);
+			e
+		})?;
+		Ok((version, info.receiver_fee_contribution))
 	}
 
 	fn post<IN>(
@@ -145,39 +288,40 @@ impl HttpSlateSender {
 }
 
 impl SlateSender for HttpSlateSender {
+	fn receiver_fee_contribution(&self) -> Result<Option<u64>, Error> {
+		let _tor = self.start_tor()?;
+		let url = self.foreign_url();
+		Ok(self.check_other_version(&url, CURRENT_SLATE_VERSION)?.1)
+	}
+
+	fn check_version(&self) -> Result<Option<VersionInfo>, Error> {
+		let _tor = self.start_tor()?;
+		Ok(Some(self.other_version_info(&self.foreign_url())?))
+	}
+
 	fn send_tx(&self, slate: &Slate) -> Result<Slate, Error> {
-		let trailing = match self.base_url.ends_with('/') {
-			true => "",
-			false => "/",
-		};
-		let url_str = format!("{}{}v2/foreign", self.base_url, trailing);
+		let url_str = self.foreign_url();
 
 		// set up tor send process if needed
-		let mut tor = tor_process::TorProcess::new();
-		if self.use_socks {
-			let tor_dir = format!(
-				"{}{}{}",
-				&self.tor_config_dir, MAIN_SEPARATOR, TOR_CONFIG_PATH
-			);
-			warn!(
-				"Starting TOR Process for send at {:?}",
-				self.socks_proxy_addr
-			);
-			tor_config::output_tor_sender_config(
-				&tor_dir,
-				&self.socks_proxy_addr.unwrap().to_string(),
-			)
-			.map_err(|e| ErrorKind::TorConfig(format!("{:?}", e).into()))?;
-			// Start TOR process
-			tor.torrc_path(&format!("{}/torrc", &tor_dir))
-				.working_dir(&tor_dir)
-				.timeout(20)
-				.completion_percent(100)
-				.launch()
-				.map_err(|e| ErrorKind::TorProcess(format!("{:?}", e).into()))?;
-		}
+		let _tor = self.start_tor()?;
 
-		let slate_send = match self.check_other_version(&url_str)? {
+		// a slate kept to an older version by its sender is sent as one
+		let (version, receiver_fee_contribution) =
+			self.check_other_version(&url_str, slate.version_info.orig_version)?;
+		debug!("Sending slate {} as {:?}", slate.id, version);
+		if let Some(f) = slate.receiver_fee {
+			if receiver_fee_contribution.map(|max| f > max).unwrap_or(true) {
+				return Err(ErrorKind::ReceiverContribution(format!(
+					"other wallet offers to pay {} of the fee, {} asked",
+					receiver_fee_contribution
+						.map(|max| max.to_string())
+						.unwrap_or("none".to_owned()),
+					f
+				))
+				.into());
+			}
+		}
+		let slate_send = match version {
 			SlateVersion::V3 => VersionedSlate::into_version(slate.clone(), SlateVersion::V3),
 			SlateVersion::V2 => {
 				let mut slate = slate.clone();
@@ -192,24 +336,55 @@ impl SlateSender for HttpSlateSender {
 				VersionedSlate::into_version(slate, SlateVersion::V2)
 			}
 		};
+		// a paid invoice goes back to the invoicer to finalize, which it can
+		// tell from the invoicer (participant 1) not having signed yet
+		let is_invoice = slate
+			.participant_with_id(1)
+			.map(|p| !p.is_complete())
+			.unwrap_or(false);
+
 		// Note: not using easy-jsonrpc as don't want the dependencies in this crate
-		let req = json!({
-			"jsonrpc": "2.0",
-			"method": "receive_tx",
-			"id": 1,
-			"params": [
-						slate_send,
-						null,
-						null
-					]
-		});
-		trace!("Sending receive_tx request: {}", req);
+		let req = match is_invoice {
+			true => json!({
+				"jsonrpc": "2.0",
+				"method": "finalize_invoice_tx",
+				"id": 1,
+				"params": [slate_send]
+			}),
+			false => json!({
+				"jsonrpc": "2.0",
+				"method": "receive_tx",
+				"id": 1,
+				"params": [
+							slate_send,
+							null,
+							null
+						]
+			}),
+		};
+		trace!("Sending slate request: {}", req);
 
-		let res: String = self.post(&url_str, None, req).map_err(|e| {
-			let report = format!("Posting transaction slate (is recipient listening?): {}", e);
-			error!("{}", report);
-			ErrorKind::ClientCallback(report)
-		})?;
+		let mut client = Client::new();
+		if self.use_socks {
+			client.use_socks = true;
+			client.socks_proxy_addr = self.socks_proxy_addr.clone();
+		}
+		// the request goes out serialized just as it's shown here, which the
+		// response signature covers
+		let req_body = req.to_string();
+		let (headers, res) = client
+			.create_post_request(&url_str, None, &req)
+			.and_then(|r| client.send_request_with_headers(r))
+			.map_err(|e| {
+				let report = format!("Posting transaction slate (is recipient listening?): {}", e);
+				error!("{}", report);
+				ErrorKind::ClientCallback(report)
+			})?;
+		self.check_response_signature(&req_body, &headers, &res)
+			.map_err(|e| {
+				error!("Recipient not verified: {}", e);
+				e
+			})?;
 
 		let res: Value = serde_json::from_str(&res).unwrap();
 		trace!("Response: {}", res);
@@ -224,13 +399,51 @@ impl SlateSender for HttpSlateSender {
 
 		let slate_value = res["result"]["Ok"].clone();
 		trace!("slate_value: {}", slate_value);
-		let slate = Slate::deserialize_upgrade(&serde_json::to_string(&slate_value).unwrap())
+		let mut slate = Slate::deserialize_upgrade(&serde_json::to_string(&slate_value).unwrap())
 			.map_err(|_| ErrorKind::SlateDeser)?;
+		// the slate stays in the version the other wallet was sent, and is
+		// recorded with it
+		slate.version_info.orig_version = version.number();
 
 		Ok(slate)
 	}
 }
 
+/// Post a JSON notification queued as a job to its URL
+pub fn post_webhook(url: &str, body: &Value) -> Result<(), Error> {
+	if !url.starts_with("http") {
+		return Err(SchemeNotHttp.into());
+	}
+	Client::new()
+		.post_no_ret(url, None, body)
+		.map_err(|e| ErrorKind::ClientCallback(format!("Posting to {}: {}", url, e)))?;
+	Ok(())
+}
+
+/// Fetch the upgrade advisory published at a URL, checking it's signed by
+/// the expected payment proof address
+pub fn fetch_version_advisory(
+	url: &str,
+	signer: &DalekPublicKey,
+) -> Result<VersionAdvisory, Error> {
+	if !url.starts_with("http") {
+		return Err(SchemeNotHttp.into());
+	}
+	let advisory: VersionAdvisory = Client::new().get(url, None).map_err(|e| {
+		ErrorKind::ClientCallback(format!("Fetching version advisory from {}: {}", url, e))
+	})?;
+	if &advisory.signer != signer {
+		return Err(ErrorKind::VersionAdvisory(format!(
+			"advisory signed by {}, expected {}",
+			address::onion_v3_from_pubkey(&advisory.signer)?,
+			address::onion_v3_from_pubkey(signer)?
+		))
+		.into());
+	}
+	advisory.verify()?;
+	Ok(advisory)
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct SchemeNotHttp;
 
@@ -17,8 +17,11 @@
 use crate::adapters::{SlateReceiver, SlateSender};
 use crate::config::WalletConfig;
 use crate::keychain::ExtKeychain;
-use crate::libwallet::api_impl::foreign;
-use crate::libwallet::{Error, ErrorKind, NodeClient, Slate, WalletInst, WalletLCProvider};
+use crate::libwallet::api_impl::{foreign, owner};
+use crate::libwallet::{
+	Error, ErrorKind, JobAction, NodeClient, Slate, SlateVersion, VersionedSlate, WalletInst,
+	WalletLCProvider,
+};
 use crate::util::{Mutex, ZeroingString};
 use crate::{DefaultLCProvider, DefaultWalletImpl, HTTPNodeClient};
 use serde::Serialize;
@@ -272,6 +275,17 @@ fn notify(message: &str, channel: &str, ttl: u16) -> bool {
 	}
 }
 
+/// Return a received slate to a keybase channel, as the listener replies
+pub fn send_response(slate: &Slate, channel: &str) -> Result<(), Error> {
+	match send(slate, channel, SLATE_SIGNED, TTL) {
+		true => Ok(()),
+		false => Err(ErrorKind::WalletComms(format!(
+			"Failed to return slate to @{} via keybase",
+			channel
+		)))?,
+	}
+}
+
 /// Listen for a message from a specific channel with topic SLATE_SIGNED for nseconds and return the first valid slate.
 fn poll(nseconds: u64, channel: &str) -> Option<Slate> {
 	let start = Instant::now();
@@ -362,12 +376,6 @@ impl SlateReceiver for KeybaseAllChannels {
 		C: NodeClient + 'static,
 		K: Keychain + 'static,
 	{
-		let mask = keychain_mask.lock();
-		// eventually want to read a list of service config keys
-		let mut w_lock = wallet.lock();
-		let lc = w_lock.lc_provider()?;
-		let w_inst = lc.wallet_inst()?;
-
 		info!("Listening for transactions on keybase ...");
 		loop {
 			// listen for messages from all channels with topic SLATE_NEW
@@ -389,7 +397,7 @@ impl SlateReceiver for KeybaseAllChannels {
 								error!(
 									"Incoming tx initiated on channel \"{}\" is rejected, multiple recipients channel! amount: {}(g), tx uuid: {}",
 									channel,
-									slate.amount as f64 / 1000000000.0,
+									slate.amount.freemans() as f64 / 1000000000.0,
 									tx_uuid,
 								);
 								continue;
@@ -399,13 +407,19 @@ impl SlateReceiver for KeybaseAllChannels {
 						info!(
 							"tx initiated on channel \"{}\", to send you {}(g). tx uuid: {}",
 							channel,
-							slate.amount as f64 / 1000000000.0,
+							slate.amount.freemans() as f64 / 1000000000.0,
 							tx_uuid,
 						);
 						if let Err(e) = slate.verify_messages() {
 							error!("Error validating participant messages: {}", e);
 							return Err(e);
 						}
+						// the wallet is only held while a slate is handled,
+						// leaving it to the job queue in between
+						let mask = keychain_mask.lock();
+						let mut w_lock = wallet.lock();
+						let lc = w_lock.lc_provider()?;
+						let w_inst = lc.wallet_inst()?;
 						let res = {
 							let r = foreign::receive_tx(
 								&mut **w_inst,
@@ -413,6 +427,7 @@ impl SlateReceiver for KeybaseAllChannels {
 								&slate,
 								None,
 								None,
+								None,
 								false,
 							);
 							r
@@ -420,7 +435,7 @@ impl SlateReceiver for KeybaseAllChannels {
 						match res {
 							// Reply to the same channel with topic SLATE_SIGNED
 							Ok(s) => {
-								let success = send(s, channel, SLATE_SIGNED, TTL);
+								let success = send(&s, channel, SLATE_SIGNED, TTL);
 
 								if success {
 									notify_on_receive(
@@ -430,7 +445,23 @@ impl SlateReceiver for KeybaseAllChannels {
 									);
 									debug!("Returned slate to @{} via keybase", channel);
 								} else {
-									error!("Failed to return slate to @{} via keybase. Incoming tx failed", channel);
+									error!("Failed to return slate to @{} via keybase, queued to try again", channel);
+									let action = JobAction::SendResponseSlate {
+										method: "keybase".to_owned(),
+										dest: channel.to_string(),
+										slate: VersionedSlate::into_version(s, SlateVersion::V3),
+									};
+									if let Err(e) = owner::queue_job(
+										&mut **w_inst,
+										(mask).as_ref(),
+										action,
+										false,
+									) {
+										error!(
+											"Unable to queue slate for @{}: {}. Incoming tx failed",
+											channel, e
+										);
+									}
 								}
 							}
 
@@ -12,29 +12,84 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod exec;
 mod file;
+#[cfg(feature = "http")]
 pub mod http;
+#[cfg(feature = "http")]
 mod keybase;
+#[cfg(feature = "qr")]
+mod qr;
 
+pub use self::exec::ExecSlateSender;
 pub use self::file::PathToSlate;
-pub use self::http::{HttpSlateSender, SchemeNotHttp};
+#[cfg(feature = "http")]
+pub use self::http::{fetch_version_advisory, post_webhook, HttpSlateSender, SchemeNotHttp};
+#[cfg(feature = "http")]
 pub use self::keybase::{KeybaseAllChannels, KeybaseChannel};
+#[cfg(feature = "qr")]
+pub use self::qr::{
+	qr_parts_from_image, render_qr_part, slate_to_qr_parts, QrImagesToSlate, QrSlateDecoder,
+	QrToTerminal, QR_PART_MAX_CHARS,
+};
 
 use crate::config::{TorConfig, WalletConfig};
-use crate::libwallet::{Error, ErrorKind, NodeClient, Slate, WalletInst, WalletLCProvider};
+use crate::libwallet::slate_versions::SlateVersion;
+use crate::libwallet::{
+	DestinationTransport, DestinationValidation, Error, ErrorKind, NodeClient, Slate, VersionInfo,
+	WalletInst, WalletLCProvider, CURRENT_SLATE_VERSION,
+};
 use crate::tor::config::complete_tor_address;
 use crate::util::ZeroingString;
+use ed25519_dalek::PublicKey as DalekPublicKey;
 
 use crate::keychain::Keychain;
 use crate::util::secp::key::SecretKey;
 use crate::util::Mutex;
 use std::sync::Arc;
 
+/// Whether slates and payment requests can be shown and read as QR codes,
+/// i.e. the wallet was built with the qr feature
+pub const QR_SUPPORT: bool = cfg!(feature = "qr");
+
+/// Whether the wallet can reach other wallets, and post webhooks, over HTTP,
+/// i.e. it was built with the http feature
+pub const HTTP_SUPPORT: bool = cfg!(feature = "http");
+
+/// The error of a transport this build of the wallet can't use
+#[cfg(not(feature = "http"))]
+fn no_http(method: &str) -> Error {
+	ErrorKind::WalletComms(format!(
+		"Wallet comm method \"{}\" needs a wallet built with the http feature.",
+		method
+	))
+	.into()
+}
+
+/// Post a JSON notification queued as a job to its URL, which needs the
+/// http feature
+#[cfg(not(feature = "http"))]
+pub fn post_webhook(_url: &str, _body: &serde_json::Value) -> Result<(), Error> {
+	Err(no_http("webhook"))
+}
+
 /// Sends transactions to a corresponding SlateReceiver
 pub trait SlateSender {
 	/// Send a transaction slate to another listening wallet and return result
 	/// TODO: Probably need a slate wrapper type
 	fn send_tx(&self, slate: &Slate) -> Result<Slate, Error>;
+
+	/// The largest part of the fee the other wallet offers to pay, by adding
+	/// an input of its own to transactions it receives, if it does
+	fn receiver_fee_contribution(&self) -> Result<Option<u64>, Error> {
+		Ok(None)
+	}
+
+	/// Ask the other wallet for its version, which also shows it can be
+	/// reached, if that can be done before sending
+	fn check_version(&self) -> Result<Option<VersionInfo>, Error> {
+		Ok(None)
+	}
 }
 
 pub trait SlateReceiver {
@@ -70,6 +125,7 @@ pub fn create_sender(
 	method: &str,
 	dest: &str,
 	tor_config: Option<TorConfig>,
+	response_signer: Option<DalekPublicKey>,
 ) -> Result<Box<dyn SlateSender>, Error> {
 	let invalid = || {
 		ErrorKind::WalletComms(format!(
@@ -91,19 +147,37 @@ pub fn create_sender(
 	};
 
 	Ok(match method {
-		"http" => Box::new(HttpSlateSender::new(&dest).map_err(|_| invalid())?),
+		#[cfg(feature = "http")]
+		"http" => {
+			let mut sender = HttpSlateSender::new(&dest).map_err(|_| invalid())?;
+			sender.set_response_signer(response_signer);
+			Box::new(sender)
+		}
+		#[cfg(feature = "http")]
 		"tor" => match tor_config {
 			None => {
 				return Err(
 					ErrorKind::WalletComms("Tor Configuration required".to_string()).into(),
 				);
 			}
-			Some(tc) => Box::new(
-				HttpSlateSender::with_socks_proxy(&dest, &tc.socks_proxy_addr, &tc.send_config_dir)
-					.map_err(|_| invalid())?,
-			),
+			Some(tc) => {
+				let mut sender = HttpSlateSender::with_socks_proxy(
+					&dest,
+					&tc.socks_proxy_addr,
+					&tc.send_config_dir,
+				)
+				.map_err(|_| invalid())?;
+				sender.set_response_signer(response_signer);
+				Box::new(sender)
+			}
 		},
+		#[cfg(feature = "http")]
 		"keybase" => Box::new(KeybaseChannel::new(dest.to_owned())?),
+		#[cfg(not(feature = "http"))]
+		"http" | "tor" | "keybase" => {
+			let _ = (tor_config, response_signer, invalid);
+			return Err(no_http(method));
+		}
 		"self" => {
 			return Err(ErrorKind::WalletComms(
 				"No sender implementation for \"self\".".to_string(),
@@ -116,6 +190,18 @@ pub fn create_sender(
 			)
 			.into());
 		}
+		"qr" => {
+			return Err(ErrorKind::WalletComms(
+				"QR code transactions must be performed asynchronously.".to_string(),
+			)
+			.into());
+		}
+		"exec" => {
+			return Err(ErrorKind::WalletComms(
+				"A transport program is required for \"exec\".".to_string(),
+			)
+			.into());
+		}
 		_ => {
 			return Err(ErrorKind::WalletComms(format!(
 				"Wallet comm method \"{}\" does not exist.",
@@ -125,3 +211,200 @@ pub fn create_sender(
 		}
 	})
 }
+
+/// Return a received slate to its sender, as a `SendResponseSlate` job does:
+/// to a keybase channel, as the keybase listener replies, or to a file
+pub fn send_response_slate(method: &str, dest: &str, slate: &Slate) -> Result<(), Error> {
+	match method {
+		#[cfg(feature = "http")]
+		"keybase" => keybase::send_response(slate, dest),
+		#[cfg(not(feature = "http"))]
+		"keybase" => Err(no_http(method)),
+		"file" => PathToSlate(dest.into()).put_tx(slate),
+		_ => Err(ErrorKind::WalletComms(format!(
+			"Slates can't be returned by \"{}\".",
+			method
+		)))?,
+	}
+}
+
+/// Characters of the data part of a bech32 string, as in slatepack addresses
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Whether the destination has the form of a slatepack address, the bech32
+/// encoding of an ed25519 public key with an `epic` prefix
+fn is_slatepack_address(dest: &str) -> bool {
+	let dest = dest.to_lowercase();
+	dest.len() == 63
+		&& dest.starts_with("epic1")
+		&& dest[5..].chars().all(|c| BECH32_CHARSET.contains(c))
+}
+
+/// The slate version to send another wallet, given the version it reported:
+/// the newest both support that isn't newer than `max_version`
+pub fn negotiate_slate_version(
+	info: &VersionInfo,
+	max_version: u16,
+) -> Result<SlateVersion, Error> {
+	// trivial tests for now, but will be expanded later
+	if info.foreign_api_version < 2 {
+		let report = format!("Other wallet reports unrecognized API format.");
+		return Err(ErrorKind::ClientCallback(report).into());
+	}
+
+	match SlateVersion::highest_common(&info.supported_slate_versions, max_version) {
+		Some(v) => Ok(v),
+		None => {
+			let report = format!("Unable to negotiate slate format with other wallet.");
+			Err(ErrorKind::ClientCallback(report).into())
+		}
+	}
+}
+
+/// Whether the destination is an http(s) URL with a host
+#[cfg(feature = "http")]
+fn is_http_url(dest: &str) -> bool {
+	let uri = dest.parse::<hyper::Uri>().ok();
+	let scheme = uri.as_ref().and_then(|u| u.scheme_str());
+	let host = uri.as_ref().and_then(|u| u.host());
+	match (scheme, host) {
+		(Some("http"), Some(_)) | (Some("https"), Some(_)) => true,
+		_ => false,
+	}
+}
+
+/// Whether the destination is an http(s) URL with a host
+#[cfg(not(feature = "http"))]
+fn is_http_url(dest: &str) -> bool {
+	["http://", "https://"]
+		.iter()
+		.any(|s| dest.starts_with(s) && dest.len() > s.len())
+}
+
+/// Check a destination before sending to it: work out how it's sent to and,
+/// for a wallet listening at an http(s) URL or onion address, that it answers
+/// and shares a slate version with this one. Destinations that aren't any of
+/// these are an error, while the result says why a recognised one can't be
+/// sent to, if it can't
+pub fn validate_destination(
+	dest: &str,
+	tor_config: Option<TorConfig>,
+) -> Result<DestinationValidation, Error> {
+	let dest = dest.trim();
+	let (transport, dest) = if is_slatepack_address(dest) {
+		(DestinationTransport::Slatepack, dest.to_owned())
+	} else if let Ok(d) = complete_tor_address(dest) {
+		(DestinationTransport::Tor, d)
+	} else if is_http_url(dest) {
+		(DestinationTransport::Http, dest.to_owned())
+	} else {
+		return Err(ErrorKind::WalletComms(format!(
+			"{} is not an http(s) URL, onion address or slatepack address",
+			dest
+		))
+		.into());
+	};
+
+	let mut result = DestinationValidation {
+		dest,
+		transport,
+		valid: false,
+		reachable: false,
+		version_info: None,
+		slate_version: None,
+		error: None,
+	};
+	let method = match result.transport {
+		DestinationTransport::Http => "http",
+		DestinationTransport::Tor => "tor",
+		DestinationTransport::Slatepack => {
+			result.error = Some("Sending to slatepack addresses isn't supported yet".to_owned());
+			return Ok(result);
+		}
+	};
+	let info = match create_sender(method, &result.dest, tor_config, None)
+		.and_then(|s| s.check_version())
+	{
+		Ok(Some(info)) => info,
+		Ok(None) => return Ok(result),
+		Err(e) => {
+			result.error = Some(e.to_string());
+			return Ok(result);
+		}
+	};
+	result.reachable = true;
+	match negotiate_slate_version(&info, CURRENT_SLATE_VERSION) {
+		Ok(v) => {
+			result.slate_version = Some(v);
+			result.valid = true;
+		}
+		Err(e) => result.error = Some(e.to_string()),
+	}
+	result.version_info = Some(info);
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn destinations_recognised() {
+		let onion = "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid";
+		let v = validate_destination(onion, None).unwrap();
+		assert_eq!(v.transport, DestinationTransport::Tor);
+		assert_eq!(v.dest, format!("http://{}.onion", onion));
+		// reaching it needs tor
+		assert!(!v.valid && !v.reachable);
+		assert!(v.error.is_some());
+
+		// nothing listens on port 1
+		let v = validate_destination(" http://127.0.0.1:1 ", None).unwrap();
+		assert_eq!(v.transport, DestinationTransport::Http);
+		assert_eq!(v.dest, "http://127.0.0.1:1");
+		assert!(!v.valid && !v.reachable);
+		assert!(v.version_info.is_none() && v.slate_version.is_none());
+
+		let slatepack = format!("epic1{}", "q".repeat(58));
+		let v = validate_destination(&slatepack, None).unwrap();
+		assert_eq!(v.transport, DestinationTransport::Slatepack);
+		assert!(!v.valid);
+
+		for dest in &[
+			"",
+			"ftp://127.0.0.1",
+			"127.0.0.1:3415",
+			"epic1qqqq",
+			"http://",
+		] {
+			assert!(validate_destination(dest, None).is_err(), "{}", dest);
+		}
+	}
+
+	#[test]
+	fn slate_version_negotiation() {
+		let info = |versions: Vec<SlateVersion>| VersionInfo {
+			foreign_api_version: 2,
+			supported_slate_versions: versions,
+			receiver_fee_contribution: None,
+			wallet_version: None,
+			version_advisory: None,
+		};
+		let both = info(vec![SlateVersion::V2, SlateVersion::V3]);
+		assert_eq!(negotiate_slate_version(&both, 3).unwrap(), SlateVersion::V3);
+		// kept to the version its sender asked for
+		assert_eq!(negotiate_slate_version(&both, 2).unwrap(), SlateVersion::V2);
+		// a V2-only receiver gets V2
+		let v2_only = info(vec![SlateVersion::V2]);
+		assert_eq!(
+			negotiate_slate_version(&v2_only, 3).unwrap(),
+			SlateVersion::V2
+		);
+
+		assert!(negotiate_slate_version(&info(vec![]), 3).is_err());
+		assert!(negotiate_slate_version(&info(vec![SlateVersion::V3]), 2).is_err());
+		let mut old_api = both.clone();
+		old_api.foreign_api_version = 1;
+		assert!(negotiate_slate_version(&old_api, 3).is_err());
+	}
+}
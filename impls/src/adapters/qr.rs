@@ -0,0 +1,306 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! QR code 'plugin' implementation, for exchanging slates with air-gapped
+//! wallets.
+//!
+//! A slate is serialized as V3 JSON, base32 encoded so it fits the compact
+//! alphanumeric QR mode, and split into parts of at most
+//! [`QR_PART_MAX_CHARS`](constant.QR_PART_MAX_CHARS.html) characters. Each part
+//! reads `EPICSLATE:<n>/<total>:<checksum>:<data>`, where the checksum covers
+//! all of the data and ties the parts of one slate together, so they can be
+//! scanned in any order. Slates needing more than one part are shown as an
+//! animation cycling through them.
+
+use crate::blake2::blake2b::blake2b;
+use crate::libwallet::{Error, ErrorKind, Slate, SlateVersion, VersionedSlate};
+use crate::util::to_hex;
+use crate::{SlateGetter, SlatePutter};
+use data_encoding::BASE32_NOPAD;
+use qrcode::render::unicode;
+use qrcode::{EcLevel, QrCode};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Prefix of every QR code part
+pub const QR_PART_PREFIX: &str = "EPICSLATE";
+
+/// Largest part shown by default, small enough for the code to fit a
+/// terminal and be read by a phone camera
+pub const QR_PART_MAX_CHARS: usize = 600;
+
+/// Characters set aside in each part for its header
+const QR_HEADER_CHARS: usize = 40;
+
+/// How long each part of an animated slate is shown
+const QR_FRAME_INTERVAL_MS: u64 = 800;
+
+fn qr_error(reason: String) -> Error {
+	ErrorKind::QrCode(reason).into()
+}
+
+fn checksum(data: &str) -> String {
+	to_hex(blake2b(4, &[], data.as_bytes()).as_bytes().to_vec()).to_uppercase()
+}
+
+/// Split a slate into the contents of QR codes of at most `max_chars`
+/// characters each
+pub fn slate_to_qr_parts(slate: &Slate, max_chars: usize) -> Result<Vec<String>, Error> {
+	if max_chars <= QR_HEADER_CHARS {
+		return Err(qr_error(format!(
+			"parts must be longer than {} characters",
+			QR_HEADER_CHARS
+		)));
+	}
+	let out_slate = VersionedSlate::into_version(slate.clone(), SlateVersion::V3);
+	let json = serde_json::to_string(&out_slate).map_err(|_| ErrorKind::SlateSer)?;
+	let data = BASE32_NOPAD.encode(json.as_bytes());
+	let sum = checksum(&data);
+
+	let chunks = data
+		.as_bytes()
+		.chunks(max_chars - QR_HEADER_CHARS)
+		.map(|c| String::from_utf8_lossy(c).into_owned())
+		.collect::<Vec<_>>();
+	let total = chunks.len();
+	Ok(chunks
+		.into_iter()
+		.enumerate()
+		.map(|(i, c)| format!("{}:{}/{}:{}:{}", QR_PART_PREFIX, i + 1, total, sum, c))
+		.collect())
+}
+
+/// Reassembles a slate from the contents of its QR codes, read in any order
+#[derive(Clone, Debug, Default)]
+pub struct QrSlateDecoder {
+	checksum: Option<String>,
+	parts: Vec<Option<String>>,
+}
+
+impl QrSlateDecoder {
+	/// A decoder yet to be given any parts
+	pub fn new() -> QrSlateDecoder {
+		QrSlateDecoder::default()
+	}
+
+	/// Add the contents of a QR code, returning whether every part of the
+	/// slate has now been read. Parts read more than once are ignored.
+	pub fn add_part(&mut self, part: &str) -> Result<bool, Error> {
+		let fields = part.trim().splitn(4, ':').collect::<Vec<_>>();
+		if fields.len() != 4 || fields[0] != QR_PART_PREFIX {
+			return Err(qr_error("not part of a slate".to_owned()));
+		}
+		let (index, total) = {
+			let mut n = fields[1].splitn(2, '/').map(|v| v.parse::<usize>());
+			match (n.next(), n.next()) {
+				(Some(Ok(i)), Some(Ok(t))) if i >= 1 && i <= t => (i, t),
+				_ => return Err(qr_error(format!("invalid part number {}", fields[1]))),
+			}
+		};
+
+		match self.checksum {
+			None => {
+				self.checksum = Some(fields[2].to_owned());
+				self.parts = vec![None; total];
+			}
+			Some(ref c) if c != fields[2] || self.parts.len() != total => {
+				return Err(qr_error("part belongs to a different slate".to_owned()));
+			}
+			Some(_) => {}
+		}
+		if self.parts[index - 1].is_none() {
+			self.parts[index - 1] = Some(fields[3].to_owned());
+		}
+		Ok(self.is_complete())
+	}
+
+	/// Number of parts read, and the number making up the slate
+	pub fn progress(&self) -> (usize, usize) {
+		let read = self.parts.iter().filter(|p| p.is_some()).count();
+		(read, self.parts.len())
+	}
+
+	/// Whether every part of the slate has been read
+	pub fn is_complete(&self) -> bool {
+		!self.parts.is_empty() && self.parts.iter().all(|p| p.is_some())
+	}
+
+	/// The slate, once every part has been read
+	pub fn slate(&self) -> Result<Slate, Error> {
+		if !self.is_complete() {
+			let (read, total) = self.progress();
+			return Err(qr_error(format!("only {} of {} parts read", read, total)));
+		}
+		let data = self
+			.parts
+			.iter()
+			.map(|p| p.as_ref().unwrap().as_str())
+			.collect::<String>();
+		if Some(checksum(&data)) != self.checksum {
+			return Err(qr_error("checksum mismatch".to_owned()));
+		}
+		let json = BASE32_NOPAD
+			.decode(data.as_bytes())
+			.map_err(|e| qr_error(format!("{}", e)))?;
+		let json = String::from_utf8(json).map_err(|_| ErrorKind::SlateDeser)?;
+		Slate::deserialize_upgrade(&json)
+	}
+}
+
+/// Render the contents of a QR code for display on a terminal
+pub fn render_qr_part(part: &str) -> Result<String, Error> {
+	let code = QrCode::with_error_correction_level(part.as_bytes(), EcLevel::L)
+		.map_err(|e| qr_error(format!("{}", e)))?;
+	Ok(code
+		.render::<unicode::Dense1x2>()
+		.dark_color(unicode::Dense1x2::Light)
+		.light_color(unicode::Dense1x2::Dark)
+		.build())
+}
+
+/// Read the contents of every QR code found in an image
+pub fn qr_parts_from_image(path: &Path) -> Result<Vec<String>, Error> {
+	let img = image::open(path)
+		.map_err(|e| qr_error(format!("unable to read image {}: {}", path.display(), e)))?;
+	let mut img = rqrr::PreparedImage::prepare(img.to_luma());
+	let mut parts = vec![];
+	for grid in img.detect_grids() {
+		match grid.decode() {
+			Ok((_, content)) => parts.push(content),
+			Err(e) => debug!("Unreadable QR code in {}: {}", path.display(), e),
+		}
+	}
+	Ok(parts)
+}
+
+/// Shows slates as QR codes on the terminal
+#[derive(Clone)]
+pub struct QrToTerminal;
+
+impl SlatePutter for QrToTerminal {
+	fn put_tx(&self, slate: &Slate) -> Result<(), Error> {
+		let frames = slate_to_qr_parts(slate, QR_PART_MAX_CHARS)?
+			.iter()
+			.map(|p| render_qr_part(p))
+			.collect::<Result<Vec<_>, _>>()?;
+		if frames.len() == 1 {
+			println!("{}", frames[0]);
+			return Ok(());
+		}
+
+		// cycle through the parts until told they've all been scanned
+		let (tx, rx) = mpsc::channel();
+		thread::spawn(move || {
+			let mut line = String::new();
+			let _ = io::stdin().read_line(&mut line);
+			let _ = tx.send(());
+		});
+		for (i, frame) in frames.iter().enumerate().cycle() {
+			print!(
+				"\x1b[2J\x1b[H{}\nPart {} of {}, press Enter once every part has been scanned\n",
+				frame,
+				i + 1,
+				frames.len()
+			);
+			io::stdout().flush()?;
+			match rx.recv_timeout(Duration::from_millis(QR_FRAME_INTERVAL_MS)) {
+				Err(RecvTimeoutError::Timeout) => continue,
+				_ => break,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Reads slates from images of their QR codes, either a single image or a
+/// directory of captured frames
+#[derive(Clone)]
+pub struct QrImagesToSlate(pub PathBuf);
+
+impl SlateGetter for QrImagesToSlate {
+	fn get_tx(&self) -> Result<Slate, Error> {
+		let images = if self.0.is_dir() {
+			let mut paths = fs::read_dir(&self.0)?
+				.filter_map(|e| e.ok().map(|e| e.path()))
+				.filter(|p| p.is_file())
+				.collect::<Vec<_>>();
+			paths.sort();
+			paths
+		} else {
+			vec![self.0.clone()]
+		};
+
+		let mut decoder = QrSlateDecoder::new();
+		for path in images.iter() {
+			let parts = match qr_parts_from_image(path) {
+				Ok(p) => p,
+				Err(e) if images.len() > 1 => {
+					warn!("Skipping {}: {}", path.display(), e);
+					continue;
+				}
+				Err(e) => return Err(e),
+			};
+			for part in parts.iter() {
+				if let Err(e) = decoder.add_part(part) {
+					warn!("Ignoring QR code in {}: {}", path.display(), e);
+				}
+			}
+		}
+		decoder.slate()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn qr_parts_round_trip() {
+		let slate = Slate::blank(2);
+		let parts = slate_to_qr_parts(&slate, 100).unwrap();
+		assert!(parts.len() > 1);
+		assert!(parts.iter().all(|p| p.len() <= 100));
+		assert!(render_qr_part(&parts[0]).is_ok());
+
+		// parts can be read in any order, and more than once
+		let mut decoder = QrSlateDecoder::new();
+		for part in parts.iter().rev().skip(1) {
+			assert!(!decoder.add_part(part).unwrap());
+		}
+		assert!(!decoder.add_part(&parts[1]).unwrap());
+		assert!(decoder.slate().is_err());
+		assert!(decoder.add_part(&parts[0]).unwrap());
+		assert_eq!(decoder.slate().unwrap().id, slate.id);
+
+		// parts of another slate aren't mixed in
+		let other = slate_to_qr_parts(&Slate::blank(2), 100).unwrap();
+		assert!(decoder.add_part(&other[0]).is_err());
+		assert!(decoder.add_part("not a slate").is_err());
+
+		// corrupted data is caught
+		let mut decoder = QrSlateDecoder::new();
+		for part in parts.iter().skip(1) {
+			decoder.add_part(part).unwrap();
+		}
+		let mut bad = parts[0].clone();
+		bad.pop();
+		bad.push(if parts[0].ends_with('A') { 'B' } else { 'A' });
+		decoder.add_part(&bad).unwrap();
+		assert!(decoder.slate().is_err());
+	}
+}
@@ -31,32 +31,54 @@ use crate::store::{self, option_to_not_found, to_key, to_key_u64};
 use crate::core::core::Transaction;
 use crate::core::ser;
 use crate::libwallet::{
-	AcctPathMapping, Context, Error, ErrorKind, NodeClient, OutputData, OutputStatus,
-	ScannedBlockInfo, TxLogEntry, WalletBackend, WalletInitStatus, WalletOutputBatch,
+	AcctPathMapping, AuditEntry, Context, Error, ErrorKind, EventBus, IdempotentResult, Job,
+	NodeClient, OutputData, OutputStatus, QueuedSend, Reservation, ScannedBlockInfo, TxLogEntry,
+	TxLogEntryType, WalletBackend, WalletInitStatus, WalletOutputBatch,
+	DEFAULT_COINBASE_CLEANUP_WINDOW,
 };
 use crate::util::secp::constants::SECRET_KEY_SIZE;
 use crate::util::secp::key::SecretKey;
 use crate::util::{self, secp};
 
+use super::migrations::{self, MigrationOptions};
+use super::platform;
+
+use epic_wallet_util::random_secret_key;
 use rand::rngs::mock::StepRng;
-use rand::thread_rng;
 
 pub const DB_DIR: &'static str = "db";
 pub const TX_SAVE_DIR: &'static str = "saved_txs";
 
 const OUTPUT_HISTORY_PREFIX: u8 = 'h' as u8;
 const OUTPUT_HISTORY_ID_PREFIX: u8 = 'j' as u8;
-const OUTPUT_PREFIX: u8 = 'o' as u8;
+pub(super) const OUTPUT_PREFIX: u8 = 'o' as u8;
 const DERIV_PREFIX: u8 = 'd' as u8;
 const CONFIRMED_HEIGHT_PREFIX: u8 = 'c' as u8;
 const PRIVATE_TX_CONTEXT_PREFIX: u8 = 'p' as u8;
-const TX_LOG_ENTRY_PREFIX: u8 = 't' as u8;
+pub(super) const TX_LOG_ENTRY_PREFIX: u8 = 't' as u8;
 const TX_LOG_ID_PREFIX: u8 = 'i' as u8;
-const ACCOUNT_PATH_MAPPING_PREFIX: u8 = 'a' as u8;
+pub(super) const ACCOUNT_PATH_MAPPING_PREFIX: u8 = 'a' as u8;
 const LAST_SCANNED_BLOCK: u8 = 'l' as u8;
 const LAST_SCANNED_KEY: &str = "LAST_SCANNED_KEY";
 const WALLET_INIT_STATUS: u8 = 'w' as u8;
 const WALLET_INIT_STATUS_KEY: &str = "WALLET_INIT_STATUS";
+const TX_LOG_SLATE_INDEX_PREFIX: u8 = 's' as u8;
+const OUTPUT_STATUS_INDEX_PREFIX: u8 = 'u' as u8;
+const OUTPUT_HEIGHT_INDEX_PREFIX: u8 = 'e' as u8;
+const TX_LOG_TYPE_INDEX_PREFIX: u8 = 'y' as u8;
+const INDEX_VERSION_PREFIX: u8 = 'x' as u8;
+const INDEX_VERSION_KEY: &str = "INDEX_VERSION";
+const IDEMPOTENT_RESULT_PREFIX: u8 = 'r' as u8;
+const RESERVATION_PREFIX: u8 = 'f' as u8;
+const TX_LOG_SEQ_PREFIX: u8 = 'q' as u8;
+const TX_LOG_SEQ_KEY: &str = "TX_LOG_SEQ";
+const AUDIT_PREFIX: u8 = 'z' as u8;
+const SEND_QUEUE_PREFIX: u8 = 'm' as u8;
+const JOB_PREFIX: u8 = 'b' as u8;
+
+/// Version of the secondary indexes. Bump when the layout of the index keys
+/// changes, so they are rebuilt the next time the wallet is opened
+const INDEX_VERSION: u32 = 2;
 
 /// test to see if database files exist in the current directory. If so,
 /// use a DB backend for all operations
@@ -98,6 +120,169 @@ where
 	Ok((ret_blind, ret_nonce))
 }
 
+fn index_version_key() -> Vec<u8> {
+	to_key(
+		INDEX_VERSION_PREFIX,
+		&mut INDEX_VERSION_KEY.as_bytes().to_vec(),
+	)
+}
+
+/// Rebuild the secondary indexes over outputs and tx log entries from
+/// scratch in the given batch. Returns the number of records indexed
+pub(super) fn index_records(db: &store::Store, batch: &store::Batch<'_>) -> Result<usize, Error> {
+	let mut stale = vec![];
+	for prefix in &[
+		TX_LOG_SLATE_INDEX_PREFIX,
+		TX_LOG_TYPE_INDEX_PREFIX,
+		OUTPUT_STATUS_INDEX_PREFIX,
+		OUTPUT_HEIGHT_INDEX_PREFIX,
+	] {
+		stale.extend(db.iter::<IndexEntry>(&[*prefix])?.map(|(k, _)| k));
+	}
+	let outputs = db.iter::<OutputData>(&[OUTPUT_PREFIX])?.collect::<Vec<_>>();
+	let txs = db
+		.iter::<TxLogEntry>(&[TX_LOG_ENTRY_PREFIX])?
+		.collect::<Vec<_>>();
+
+	for k in stale.iter() {
+		batch.delete(k)?;
+	}
+	for (key, out) in outputs.iter() {
+		for index_key in output_index_keys(out, key) {
+			batch.put_ser(&index_key, &IndexEntry(key.clone()))?;
+		}
+	}
+	for (key, tx) in txs.iter() {
+		for index_key in tx_log_index_keys(tx, key) {
+			batch.put_ser(&index_key, &IndexEntry(key.clone()))?;
+		}
+	}
+	batch.put_ser(&index_version_key(), &INDEX_VERSION)?;
+	Ok(outputs.len() + txs.len())
+}
+
+/// Key of an output in the output table
+fn output_key(id: &Identifier, mmr_index: &Option<u64>) -> Vec<u8> {
+	match mmr_index {
+		Some(i) => to_key_u64(OUTPUT_PREFIX, &mut id.to_bytes().to_vec(), *i),
+		None => to_key(OUTPUT_PREFIX, &mut id.to_bytes().to_vec()),
+	}
+}
+
+/// Key of a tx log entry in the tx log table
+/// Key of the stored result of a call to `method` with the given idempotency key
+fn idempotent_result_key(method: &str, key: &str) -> Vec<u8> {
+	let mut k = method.as_bytes().to_vec();
+	k.push(b':');
+	k.extend_from_slice(key.as_bytes());
+	to_key(IDEMPOTENT_RESULT_PREFIX, &mut k)
+}
+
+/// Key of a balance reservation, grouped by account
+fn reservation_key(parent_key_id: &Identifier, id: &Uuid) -> Vec<u8> {
+	let mut k = parent_key_id.to_bytes().to_vec();
+	k.extend_from_slice(id.as_bytes());
+	to_key(RESERVATION_PREFIX, &mut k)
+}
+
+/// Key of an audit trail entry, ordered by the time of the change
+fn audit_key(entry: &AuditEntry) -> Vec<u8> {
+	let mut k = (entry.timestamp.timestamp_nanos() as u64)
+		.to_be_bytes()
+		.to_vec();
+	k.extend_from_slice(entry.id.as_bytes());
+	to_key(AUDIT_PREFIX, &mut k)
+}
+
+pub(super) fn tx_log_key(parent_key_id: &Identifier, id: u32) -> Vec<u8> {
+	to_key_u64(
+		TX_LOG_ENTRY_PREFIX,
+		&mut parent_key_id.to_bytes().to_vec(),
+		id as u64,
+	)
+}
+
+/// Id of an output history entry, read back from its key in the history table
+fn output_history_id(key: &[u8]) -> u32 {
+	let mut id = [0u8; 4];
+	for (i, b) in key.iter().skip(2).take(4).enumerate() {
+		id[i] = *b;
+	}
+	u32::from_le_bytes(id)
+}
+
+fn output_status_byte(status: &OutputStatus) -> u8 {
+	match status {
+		OutputStatus::Unconfirmed => 0,
+		OutputStatus::Unspent => 1,
+		OutputStatus::Locked => 2,
+		OutputStatus::Spent => 3,
+		OutputStatus::Deleted => 4,
+		OutputStatus::Unknown => 0xff,
+	}
+}
+
+/// Secondary index keys of an output, stored under `key`
+fn output_index_keys(out: &OutputData, key: &[u8]) -> Vec<Vec<u8>> {
+	let mut by_status = vec![output_status_byte(&out.status)];
+	by_status.extend_from_slice(key);
+	let mut by_height = out.height.to_be_bytes().to_vec();
+	by_height.extend_from_slice(key);
+	vec![
+		to_key(OUTPUT_STATUS_INDEX_PREFIX, &mut by_status),
+		to_key(OUTPUT_HEIGHT_INDEX_PREFIX, &mut by_height),
+	]
+}
+
+fn tx_log_type_byte(tx_type: &TxLogEntryType) -> u8 {
+	match tx_type {
+		TxLogEntryType::ConfirmedCoinbase => 0,
+		TxLogEntryType::TxReceived => 1,
+		TxLogEntryType::TxSent => 2,
+		TxLogEntryType::TxReceivedCancelled => 3,
+		TxLogEntryType::TxSentCancelled => 4,
+		TxLogEntryType::RevertedCoinbase => 5,
+		TxLogEntryType::Unknown => 0xff,
+	}
+}
+
+/// Prefix of the type index entries of an account's tx log entries
+fn tx_log_type_prefix(parent_key_id: &Identifier, tx_type: &TxLogEntryType) -> Vec<u8> {
+	let mut by_type = parent_key_id.to_bytes().to_vec();
+	by_type.push(tx_log_type_byte(tx_type));
+	to_key(TX_LOG_TYPE_INDEX_PREFIX, &mut by_type)
+}
+
+/// Secondary index keys of a tx log entry, stored under `key`
+fn tx_log_index_keys(tx: &TxLogEntry, key: &[u8]) -> Vec<Vec<u8>> {
+	let mut by_type = tx_log_type_prefix(&tx.parent_key_id, &tx.tx_type);
+	by_type.extend_from_slice(key);
+	let mut keys = vec![by_type];
+	if let Some(s) = tx.tx_slate_id {
+		let mut by_slate = s.as_bytes().to_vec();
+		by_slate.extend_from_slice(key);
+		keys.push(to_key(TX_LOG_SLATE_INDEX_PREFIX, &mut by_slate));
+	}
+	keys
+}
+
+/// A secondary index entry, holding the key of the record it points to.
+/// Index keys end with the same key, so a record can be indexed more than
+/// once under the same value
+struct IndexEntry(Vec<u8>);
+
+impl ser::Writeable for IndexEntry {
+	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+		writer.write_bytes(&self.0)
+	}
+}
+
+impl ser::Readable for IndexEntry {
+	fn read(reader: &mut dyn ser::Reader) -> Result<IndexEntry, ser::Error> {
+		Ok(IndexEntry(reader.read_bytes_len_prefix()?))
+	}
+}
+
 pub struct LMDBBackend<'ck, C, K>
 where
 	C: NodeClient + 'ck,
@@ -113,6 +298,17 @@ where
 	parent_key_id: Identifier,
 	/// wallet to node client
 	w2n_client: C,
+	/// Blocks after which unconfirmed coinbase outputs are removed
+	coinbase_cleanup_window: u64,
+	/// Whether outputs missing from the node are only marked spent once two
+	/// refreshes in a row find them missing
+	confirm_missing_outputs: bool,
+	/// Where the wallet's events are emitted
+	events: EventBus,
+	/// Whether the database was written by a newer wallet version, or is
+	/// opened as a replica of a wallet another process writes to, and so
+	/// mustn't be written to
+	read_only: bool,
 	///phantom
 	_phantom: &'ck PhantomData<C>,
 }
@@ -123,28 +319,66 @@ where
 	K: Keychain + 'ck,
 {
 	pub fn new(data_file_dir: &str, n_client: C) -> Result<Self, Error> {
+		LMDBBackend::open(data_file_dir, n_client, false)
+	}
+
+	/// Open an existing database read-only, as a replica of a wallet another
+	/// process writes to, such as to serve reporting queries without holding
+	/// up the wallet's own. Nothing is created, migrated or indexed, so the
+	/// database must already be at this version's schema
+	pub fn new_read_only(data_file_dir: &str, n_client: C) -> Result<Self, Error> {
+		LMDBBackend::open(data_file_dir, n_client, true)
+	}
+
+	fn open(data_file_dir: &str, n_client: C, replica: bool) -> Result<Self, Error> {
 		let db_path = path::Path::new(data_file_dir).join(DB_DIR);
+		if replica && !db_path.exists() {
+			let msg = format!("no wallet database to replicate in {}", data_file_dir);
+			return Err(ErrorKind::Lifecycle(msg).into());
+		}
 		fs::create_dir_all(&db_path).expect("Couldn't create wallet backend directory!");
 
 		let stored_tx_path = path::Path::new(data_file_dir).join(TX_SAVE_DIR);
 		fs::create_dir_all(&stored_tx_path)
 			.expect("Couldn't create wallet backend tx storage directory!");
 
-		let store = store::Store::new(db_path.to_str().unwrap(), None, Some(DB_DIR), None)?;
-
-		// Make sure default wallet derivation path always exists
-		// as well as path (so it can be retrieved by batches to know where to store
-		// completed transactions, for reference
-		let default_account = AcctPathMapping {
-			label: "default".to_owned(),
-			path: LMDBBackend::<C, K>::default_path(),
-		};
-		let acct_key = to_key(
-			ACCOUNT_PATH_MAPPING_PREFIX,
-			&mut default_account.label.as_bytes().to_vec(),
-		);
+		let store = platform::open_store(&db_path)?;
+		let read_only = migrations::is_read_only(&store)? || replica;
+		if replica {
+			let indexed = store.get_ser::<u32>(&index_version_key())? == Some(INDEX_VERSION);
+			if migrations::needs_migration(&store)? || !indexed {
+				let msg = format!(
+					"wallet database in {} must be opened by the wallet writing to it first, \
+					 to bring it up to date",
+					data_file_dir
+				);
+				return Err(ErrorKind::Lifecycle(msg).into());
+			}
+			info!(
+				"Opening wallet database at {} read-only, as a replica",
+				data_file_dir
+			);
+		} else if read_only {
+			warn!(
+				"Wallet database at {} was written by a newer wallet version, opening it read-only",
+				data_file_dir
+			);
+		} else {
+			migrations::migrate_store(&store, data_file_dir, &MigrationOptions::default())?;
+
+			// Make sure default wallet derivation path always exists
+			// as well as path (so it can be retrieved by batches to know where to store
+			// completed transactions, for reference
+			let default_account = AcctPathMapping {
+				label: "default".to_owned(),
+				path: LMDBBackend::<C, K>::default_path(),
+				frozen: false,
+			};
+			let acct_key = to_key(
+				ACCOUNT_PATH_MAPPING_PREFIX,
+				&mut default_account.label.as_bytes().to_vec(),
+			);
 
-		{
 			let batch = store.batch()?;
 			batch.put_ser(&acct_key, &default_account)?;
 			batch.commit()?;
@@ -157,11 +391,86 @@ where
 			master_checksum: Box::new(None),
 			parent_key_id: LMDBBackend::<C, K>::default_path(),
 			w2n_client: n_client,
+			coinbase_cleanup_window: DEFAULT_COINBASE_CLEANUP_WINDOW,
+			confirm_missing_outputs: false,
+			events: EventBus::new(),
+			read_only,
 			_phantom: &PhantomData,
 		};
+		if !read_only {
+			res.build_indexes()?;
+		}
 		Ok(res)
 	}
 
+	/// Build the secondary indexes over outputs and tx log entries, if they
+	/// were built by an older version or not at all. Returns whether they
+	/// had to be (re)built
+	pub fn build_indexes(&self) -> Result<bool, Error> {
+		if self.db.get_ser::<u32>(&index_version_key())? == Some(INDEX_VERSION) {
+			return Ok(false);
+		}
+		let batch = self.db.batch()?;
+		let count = index_records(&self.db, &batch)?;
+		batch.commit()?;
+		info!("Built wallet indexes over {} records", count);
+		Ok(true)
+	}
+
+	/// Iterate over the records under `prefix` along with their keys as
+	/// cursors, starting after the record with key `cursor` if given
+	fn iter_from_cursor<'a, T: ser::Readable + 'a>(
+		&'a self,
+		prefix: Vec<u8>,
+		cursor: Option<&str>,
+	) -> Result<Box<dyn Iterator<Item = (String, T)> + 'a>, Error> {
+		let after = match cursor {
+			Some(c) => match util::from_hex(c.to_owned()) {
+				Ok(k) if k.starts_with(&prefix) => Some(k),
+				_ => return Err(ErrorKind::InvalidCursor(c.to_owned()).into()),
+			},
+			None => None,
+		};
+		Ok(Box::new(
+			self.db
+				.iter::<T>(&prefix)?
+				.skip_while(move |(k, _)| match after.as_ref() {
+					Some(a) => k <= a,
+					None => false,
+				})
+				.map(|(k, r)| (util::to_hex(k), r)),
+		))
+	}
+
+	/// Start a write batch, unless the database is open read-only
+	fn write_batch(&self) -> Result<store::Batch<'_>, Error> {
+		if self.read_only {
+			return Err(ErrorKind::ReadOnlyWallet.into());
+		}
+		Ok(self.db.batch()?)
+	}
+
+	/// Look up the records referenced by the index entries under `prefix`
+	fn index_lookup<T: ser::Readable>(&self, prefix: Vec<u8>) -> Result<Vec<T>, Error> {
+		self.db
+			.iter::<IndexEntry>(&prefix)?
+			.map(|(_, e)| self.index_record(&e))
+			.collect()
+	}
+
+	/// The record an index entry references, which an entry left behind by
+	/// a record that's gone doesn't
+	fn index_record<T: ser::Readable>(&self, entry: &IndexEntry) -> Result<T, Error> {
+		match self.db.get_ser(&entry.0)? {
+			Some(r) => Ok(r),
+			None => Err(ErrorKind::Backend(format!(
+				"index entry refers to missing record {}",
+				util::to_hex(entry.0.clone())
+			))
+			.into()),
+		}
+	}
+
 	fn default_path() -> Identifier {
 		// return the default parent wallet path, corresponding to the default account
 		// in the BIP32 spec. Parent is account 0 at level 2, child output identifiers
@@ -205,7 +514,7 @@ where
 							let mut test_rng = StepRng::new(1234567890u64, 1);
 							secp::key::SecretKey::new(&k.secp(), &mut test_rng)
 						}
-						false => secp::key::SecretKey::new(&k.secp(), &mut thread_rng()),
+						false => random_secret_key(&k.secp())?,
 					};
 					k.mask_master_key(&mask_value)?;
 					Some(mask_value)
@@ -253,25 +562,49 @@ where
 		&mut self.w2n_client
 	}
 
-	/// return the version of the commit for caching
+	/// return the hex encoded commit of an output
 	fn calc_commit_for_cache(
 		&mut self,
 		keychain_mask: Option<&SecretKey>,
 		amount: u64,
 		id: &Identifier,
-	) -> Result<Option<String>, Error> {
-		//TODO: Check if this is really necessary, it's the only thing
-		//preventing removing the need for config in the wallet backend
-		/*if self.config.no_commit_cache == Some(true) {
-			Ok(None)
-		} else {*/
-		Ok(Some(util::to_hex(
+	) -> Result<String, Error> {
+		Ok(util::to_hex(
 			self.keychain(keychain_mask)?
 				.commit(amount, &id, &SwitchCommitmentType::Regular)?
 				.0
 				.to_vec(), // TODO: proper support for different switch commitment schemes
-		)))
-		/*}*/
+		))
+	}
+
+	/// Backfill the commits of outputs stored before commits were always
+	/// cached. Outputs are rewritten in place, so no output history is recorded
+	fn backfill_output_commits(
+		&mut self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<usize, Error> {
+		let keychain = self.keychain(keychain_mask)?;
+		let mut missing = vec![];
+		for prefix in &[OUTPUT_PREFIX, OUTPUT_HISTORY_PREFIX] {
+			missing.extend(
+				self.db
+					.iter::<OutputData>(&[*prefix])?
+					.filter(|(_, o)| o.commit.is_none()),
+			);
+		}
+		// a newer wallet always stores commits
+		if missing.is_empty() || self.read_only {
+			return Ok(0);
+		}
+		let batch = self.write_batch()?;
+		for (key, mut out) in missing.iter().cloned() {
+			let commit = keychain.commit(out.value, &out.key_id, &SwitchCommitmentType::Regular)?;
+			out.commit = Some(util::to_hex(commit.0.to_vec()));
+			batch.put_ser(&key, &out)?;
+		}
+		batch.commit()?;
+		info!("Backfilled commits for {} stored outputs", missing.len());
+		Ok(missing.len())
 	}
 
 	/// Set parent path by account name
@@ -295,21 +628,85 @@ where
 		self.parent_key_id.clone()
 	}
 
+	fn set_coinbase_cleanup_window(&mut self, blocks: u64) {
+		self.coinbase_cleanup_window = blocks;
+	}
+
+	fn coinbase_cleanup_window(&self) -> u64 {
+		self.coinbase_cleanup_window
+	}
+
+	fn set_confirm_missing_outputs(&mut self, confirm: bool) {
+		self.confirm_missing_outputs = confirm;
+	}
+
+	fn confirm_missing_outputs(&self) -> bool {
+		self.confirm_missing_outputs
+	}
+
+	fn set_events(&mut self, events: EventBus) {
+		self.events = events;
+	}
+
+	fn events(&self) -> &EventBus {
+		&self.events
+	}
+
 	fn get(&self, id: &Identifier, mmr_index: &Option<u64>) -> Result<OutputData, Error> {
-		let key = match mmr_index {
-			Some(i) => to_key_u64(OUTPUT_PREFIX, &mut id.to_bytes().to_vec(), *i),
-			None => to_key(OUTPUT_PREFIX, &mut id.to_bytes().to_vec()),
-		};
+		let key = output_key(id, mmr_index);
 		option_to_not_found(self.db.get_ser(&key), || format!("Key Id: {}", id))
 			.map_err(|e| e.into())
 	}
 
+	fn iter_from<'a>(
+		&'a self,
+		cursor: Option<&str>,
+	) -> Result<Box<dyn Iterator<Item = (String, OutputData)> + 'a>, Error> {
+		self.iter_from_cursor(vec![OUTPUT_PREFIX], cursor)
+	}
+
+	fn outputs_by_status(&self, status: OutputStatus) -> Result<Vec<OutputData>, Error> {
+		let prefix = to_key(
+			OUTPUT_STATUS_INDEX_PREFIX,
+			&mut vec![output_status_byte(&status)],
+		);
+		self.index_lookup(prefix)
+	}
+
+	fn outputs_by_height(
+		&self,
+		start_height: u64,
+		end_height: u64,
+	) -> Result<Vec<OutputData>, Error> {
+		let prefix = to_key(OUTPUT_HEIGHT_INDEX_PREFIX, &mut vec![]);
+		let height_at = prefix.len();
+		self.db
+			.iter::<IndexEntry>(&prefix)?
+			.map(|(k, e)| {
+				let mut height = [0u8; 8];
+				height.copy_from_slice(&k[height_at..height_at + 8]);
+				(u64::from_be_bytes(height), e)
+			})
+			.skip_while(|(h, _)| *h < start_height)
+			.take_while(|(h, _)| *h <= end_height)
+			.map(|(_, e)| self.index_record(&e))
+			.collect()
+	}
+
 	fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = OutputData> + 'a> {
 		Box::new(self.db.iter(&[OUTPUT_PREFIX]).unwrap().map(|o| o.1))
 	}
 
 	fn history_iter<'a>(&'a self) -> Box<dyn Iterator<Item = OutputData> + 'a> {
-		Box::new(self.db.iter(&[OUTPUT_HISTORY_PREFIX]).unwrap().map(|o| o.1))
+		// history ids are stored little-endian, so key order isn't recording order
+		let mut entries = self
+			.db
+			.iter(&[OUTPUT_HISTORY_PREFIX])
+			.unwrap()
+			.map(|(k, o)| (output_history_id(&k), o))
+			.collect::<Vec<_>>();
+		entries.sort_by_key(|(id, _)| *id);
+		Box::new(entries.into_iter().map(|(_, o)| o))
 	}
 
 	fn get_tx_log_entry(&self, u: &Uuid) -> Result<Option<TxLogEntry>, Error> {
@@ -321,6 +718,68 @@ where
 		Box::new(self.db.iter(&[TX_LOG_ENTRY_PREFIX]).unwrap().map(|o| o.1))
 	}
 
+	fn get_idempotent_result(
+		&self,
+		method: &str,
+		key: &str,
+	) -> Result<Option<IdempotentResult>, Error> {
+		self.db
+			.get_ser(&idempotent_result_key(method, key))
+			.map_err(|e| e.into())
+	}
+
+	fn reservation_iter<'a>(
+		&'a self,
+		parent_key_id: &Identifier,
+	) -> Box<dyn Iterator<Item = Reservation> + 'a> {
+		let prefix = to_key(RESERVATION_PREFIX, &mut parent_key_id.to_bytes().to_vec());
+		Box::new(self.db.iter(&prefix).unwrap().map(|o| o.1))
+	}
+
+	fn audit_iter<'a>(&'a self) -> Box<dyn Iterator<Item = AuditEntry> + 'a> {
+		Box::new(self.db.iter(&[AUDIT_PREFIX]).unwrap().map(|o| o.1))
+	}
+
+	fn send_queue_iter<'a>(&'a self) -> Box<dyn Iterator<Item = QueuedSend> + 'a> {
+		Box::new(self.db.iter(&[SEND_QUEUE_PREFIX]).unwrap().map(|o| o.1))
+	}
+
+	fn job_iter<'a>(&'a self) -> Box<dyn Iterator<Item = Job> + 'a> {
+		Box::new(self.db.iter(&[JOB_PREFIX]).unwrap().map(|o| o.1))
+	}
+
+	fn tx_log_iter_from<'a>(
+		&'a self,
+		parent_key_id: &Identifier,
+		cursor: Option<&str>,
+	) -> Result<Box<dyn Iterator<Item = (String, TxLogEntry)> + 'a>, Error> {
+		let prefix = to_key(TX_LOG_ENTRY_PREFIX, &mut parent_key_id.to_bytes().to_vec());
+		self.iter_from_cursor(prefix, cursor)
+	}
+
+	fn tx_log_entry(
+		&self,
+		parent_key_id: &Identifier,
+		id: u32,
+	) -> Result<Option<TxLogEntry>, Error> {
+		self.db
+			.get_ser(&tx_log_key(parent_key_id, id))
+			.map_err(|e| e.into())
+	}
+
+	fn tx_log_entries_by_slate_id(&self, slate_id: &Uuid) -> Result<Vec<TxLogEntry>, Error> {
+		let prefix = to_key(TX_LOG_SLATE_INDEX_PREFIX, &mut slate_id.as_bytes().to_vec());
+		self.index_lookup(prefix)
+	}
+
+	fn tx_log_entries_by_type(
+		&self,
+		parent_key_id: &Identifier,
+		tx_type: &TxLogEntryType,
+	) -> Result<Vec<TxLogEntry>, Error> {
+		self.index_lookup(tx_log_type_prefix(parent_key_id, tx_type))
+	}
+
 	fn get_private_context(
 		&mut self,
 		keychain_mask: Option<&SecretKey>,
@@ -398,7 +857,7 @@ where
 	) -> Result<Box<dyn WalletOutputBatch<K> + 'a>, Error> {
 		Ok(Box::new(Batch {
 			_store: self,
-			db: RefCell::new(Some(self.db.batch()?)),
+			db: RefCell::new(Some(self.write_batch()?)),
 			keychain: Some(self.keychain(keychain_mask)?),
 		}))
 	}
@@ -406,7 +865,7 @@ where
 	fn batch_no_mask<'a>(&'a mut self) -> Result<Box<dyn WalletOutputBatch<K> + 'a>, Error> {
 		Ok(Box::new(Batch {
 			_store: self,
-			db: RefCell::new(Some(self.db.batch()?)),
+			db: RefCell::new(Some(self.write_batch()?)),
 			keychain: None,
 		}))
 	}
@@ -501,6 +960,46 @@ where
 	keychain: Option<K>,
 }
 
+impl<'a, C, K> Batch<'a, C, K>
+where
+	C: NodeClient,
+	K: Keychain,
+{
+	fn put_index_keys(&self, index_keys: Vec<Vec<u8>>, key: &[u8]) -> Result<(), Error> {
+		for index_key in index_keys {
+			self.db
+				.borrow()
+				.as_ref()
+				.unwrap()
+				.put_ser(&index_key, &IndexEntry(key.to_vec()))?;
+		}
+		Ok(())
+	}
+
+	fn delete_index_keys(&self, index_keys: Vec<Vec<u8>>) -> Result<(), Error> {
+		for index_key in index_keys {
+			let _ = self.db.borrow().as_ref().unwrap().delete(&index_key);
+		}
+		Ok(())
+	}
+
+	/// Next position in the wallet-wide order tx log entries are created in,
+	/// starting from 1
+	fn next_tx_log_seq(&self) -> Result<u64, Error> {
+		let seq_key = to_key(TX_LOG_SEQ_PREFIX, &mut TX_LOG_SEQ_KEY.as_bytes().to_vec());
+		let last_seq: u64 = match self.db.borrow().as_ref().unwrap().get_ser(&seq_key)? {
+			Some(s) => s,
+			None => 0,
+		};
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.put_ser(&seq_key, &(last_seq + 1))?;
+		Ok(last_seq + 1)
+	}
+}
+
 #[allow(missing_docs)]
 impl<'a, C, K> WalletOutputBatch<K> for Batch<'a, C, K>
 where
@@ -512,19 +1011,18 @@ where
 	}
 
 	fn save(&mut self, out: OutputData) -> Result<(), Error> {
+		let key = output_key(&out.key_id, &out.mmr_index);
 		// Save the previous output data to the db.
 		if let Ok(previous_output) = self.get(&out.key_id, &out.mmr_index) {
+			self.delete_index_keys(output_index_keys(&previous_output, &key))?;
 			if previous_output != out {
 				self.save_output_history(previous_output);
 			}
 		}
 		// Save the updated output data to the db.
 		{
-			let key = match out.mmr_index {
-				Some(i) => to_key_u64(OUTPUT_PREFIX, &mut out.key_id.to_bytes().to_vec(), i),
-				None => to_key(OUTPUT_PREFIX, &mut out.key_id.to_bytes().to_vec()),
-			};
 			self.db.borrow().as_ref().unwrap().put_ser(&key, &out)?;
+			self.put_index_keys(output_index_keys(&out, &key), &key)?;
 		}
 
 		Ok(())
@@ -562,10 +1060,7 @@ where
 	}
 
 	fn get(&self, id: &Identifier, mmr_index: &Option<u64>) -> Result<OutputData, Error> {
-		let key = match mmr_index {
-			Some(i) => to_key_u64(OUTPUT_PREFIX, &mut id.to_bytes().to_vec(), *i),
-			None => to_key(OUTPUT_PREFIX, &mut id.to_bytes().to_vec()),
-		};
+		let key = output_key(id, mmr_index);
 		option_to_not_found(self.db.borrow().as_ref().unwrap().get_ser(&key), || {
 			format!("Key ID: {}", id)
 		})
@@ -613,10 +1108,10 @@ where
 
 		// Delete the output data.
 		{
-			let key = match mmr_index {
-				Some(i) => to_key_u64(OUTPUT_PREFIX, &mut id.to_bytes().to_vec(), *i),
-				None => to_key(OUTPUT_PREFIX, &mut id.to_bytes().to_vec()),
-			};
+			let key = output_key(id, mmr_index);
+			if let Ok(previous_output) = self.get(&id, &mmr_index) {
+				self.delete_index_keys(output_index_keys(&previous_output, &key))?;
+			}
 			let _ = self.db.borrow().as_ref().unwrap().delete(&key);
 		}
 
@@ -728,19 +1223,44 @@ where
 		tx_in: TxLogEntry,
 		parent_id: &Identifier,
 	) -> Result<(), Error> {
-		let tx_log_key = to_key_u64(
-			TX_LOG_ENTRY_PREFIX,
-			&mut parent_id.to_bytes().to_vec(),
-			tx_in.id as u64,
-		);
+		let mut tx_in = tx_in;
+		let tx_log_key = tx_log_key(parent_id, tx_in.id);
+		match self.get_tx_log_entry(parent_id, tx_in.id)? {
+			Some(previous) => {
+				// keep the position of the entry, which the caller may not have
+				if tx_in.seq == 0 {
+					tx_in.seq = previous.seq;
+				}
+				self.delete_index_keys(tx_log_index_keys(&previous, &tx_log_key))?;
+			}
+			None => {
+				if tx_in.seq == 0 {
+					tx_in.seq = self.next_tx_log_seq()?;
+				}
+			}
+		}
 		self.db
 			.borrow()
 			.as_ref()
 			.unwrap()
 			.put_ser(&tx_log_key, &tx_in)?;
+		self.put_index_keys(tx_log_index_keys(&tx_in, &tx_log_key), &tx_log_key)?;
 		Ok(())
 	}
 
+	fn get_tx_log_entry(
+		&self,
+		parent_key_id: &Identifier,
+		id: u32,
+	) -> Result<Option<TxLogEntry>, Error> {
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.get_ser(&tx_log_key(parent_key_id, id))
+			.map_err(|e| e.into())
+	}
+
 	fn save_acct_path(&mut self, mapping: AcctPathMapping) -> Result<(), Error> {
 		let acct_key = to_key(
 			ACCOUNT_PATH_MAPPING_PREFIX,
@@ -816,9 +1336,525 @@ where
 			.map_err(|e| e.into())
 	}
 
+	fn save_idempotent_result(&mut self, result: IdempotentResult) -> Result<(), Error> {
+		let key = idempotent_result_key(&result.method, &result.key);
+		self.db.borrow().as_ref().unwrap().put_ser(&key, &result)?;
+		Ok(())
+	}
+
+	fn save_reservation(&mut self, reservation: &Reservation) -> Result<(), Error> {
+		let key = reservation_key(&reservation.parent_key_id, &reservation.id);
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.put_ser(&key, reservation)?;
+		Ok(())
+	}
+
+	fn delete_reservation(&mut self, reservation: &Reservation) -> Result<(), Error> {
+		let key = reservation_key(&reservation.parent_key_id, &reservation.id);
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.delete(&key)
+			.map_err(|e| e.into())
+	}
+
+	fn save_audit_entry(&mut self, entry: &AuditEntry) -> Result<(), Error> {
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.put_ser(&audit_key(entry), entry)?;
+		Ok(())
+	}
+
+	fn save_queued_send(&mut self, queued: &QueuedSend) -> Result<(), Error> {
+		let key = to_key(SEND_QUEUE_PREFIX, &mut queued.id.as_bytes().to_vec());
+		self.db.borrow().as_ref().unwrap().put_ser(&key, queued)?;
+		Ok(())
+	}
+
+	fn delete_queued_send(&mut self, id: &Uuid) -> Result<(), Error> {
+		let key = to_key(SEND_QUEUE_PREFIX, &mut id.as_bytes().to_vec());
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.delete(&key)
+			.map_err(|e| e.into())
+	}
+
+	fn save_job(&mut self, job: &Job) -> Result<(), Error> {
+		let key = to_key(JOB_PREFIX, &mut job.id.as_bytes().to_vec());
+		self.db.borrow().as_ref().unwrap().put_ser(&key, job)?;
+		Ok(())
+	}
+
+	fn delete_job(&mut self, id: &Uuid) -> Result<(), Error> {
+		let key = to_key(JOB_PREFIX, &mut id.as_bytes().to_vec());
+		self.db
+			.borrow()
+			.as_ref()
+			.unwrap()
+			.delete(&key)
+			.map_err(|e| e.into())
+	}
+
 	fn commit(&self) -> Result<(), Error> {
 		let db = self.db.replace(None);
 		db.unwrap().commit()?;
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::libwallet::TxLogEntryType;
+	use crate::test_framework::LocalWalletClient;
+	use std::sync::mpsc::channel;
+	use std::time::Instant;
+
+	fn clean_output_dir(test_dir: &str) {
+		let _ = fs::remove_dir_all(test_dir);
+	}
+
+	fn open_backend(test_dir: &str) -> LMDBBackend<'static, LocalWalletClient, ExtKeychain> {
+		let (tx, _) = channel();
+		let client = LocalWalletClient::new("wallet1", tx);
+		LMDBBackend::new(test_dir, client).unwrap()
+	}
+
+	fn test_output(parent_key_id: &Identifier, n: u32) -> OutputData {
+		OutputData {
+			root_key_id: parent_key_id.clone(),
+			key_id: ExtKeychain::derive_key_id(3, 0, 0, n, 0),
+			n_child: n,
+			commit: None,
+			mmr_index: None,
+			value: 1_000,
+			status: OutputStatus::Unconfirmed,
+			height: n as u64,
+			lock_height: 0,
+			is_coinbase: false,
+			tx_log_entry: Some(n),
+			is_foundation: false,
+			spent_by_tx: None,
+			spent_height: None,
+			tags: vec![],
+			missing_since: None,
+		}
+	}
+
+	fn test_tx(parent_key_id: &Identifier, n: u32) -> TxLogEntry {
+		let mut t = TxLogEntry::new(parent_key_id.clone(), TxLogEntryType::TxReceived, n);
+		t.tx_slate_id = Some(Uuid::new_v4());
+		t
+	}
+
+	/// Store `count` outputs and tx log entries, in batches small enough
+	/// for the store to resize between them
+	fn populate(
+		backend: &mut LMDBBackend<'static, LocalWalletClient, ExtKeychain>,
+		count: u32,
+	) -> Vec<Uuid> {
+		let parent_key_id = LMDBBackend::<LocalWalletClient, ExtKeychain>::default_path();
+		let mut slate_ids = vec![];
+		let mut n = 0;
+		while n < count {
+			let mut batch = backend.batch_no_mask().unwrap();
+			for i in n..std::cmp::min(n + 10_000, count) {
+				let t = test_tx(&parent_key_id, i);
+				slate_ids.push(t.tx_slate_id.unwrap());
+				batch.save_tx_log_entry(t, &parent_key_id).unwrap();
+				batch.save(test_output(&parent_key_id, i)).unwrap();
+			}
+			batch.commit().unwrap();
+			n += 10_000;
+		}
+		slate_ids
+	}
+
+	#[test]
+	fn indexes_follow_updates() {
+		let test_dir = "test_output/lmdb_indexes";
+		clean_output_dir(test_dir);
+		let mut backend = open_backend(test_dir);
+		let parent_key_id = LMDBBackend::<LocalWalletClient, ExtKeychain>::default_path();
+		let slate_ids = populate(&mut backend, 10);
+
+		let txs = backend.tx_log_entries_by_slate_id(&slate_ids[3]).unwrap();
+		assert_eq!(txs.len(), 1);
+		assert_eq!(txs[0].id, 3);
+		assert_eq!(
+			backend.tx_log_entry(&parent_key_id, 4).unwrap().unwrap().id,
+			4
+		);
+		assert!(backend.tx_log_entry(&parent_key_id, 10).unwrap().is_none());
+		assert!(backend
+			.tx_log_entries_by_slate_id(&Uuid::new_v4())
+			.unwrap()
+			.is_empty());
+		assert_eq!(
+			backend
+				.tx_log_entries_by_type(&parent_key_id, &TxLogEntryType::TxReceived)
+				.unwrap()
+				.len(),
+			10
+		);
+
+		// type changes move the entry between index entries
+		{
+			let mut batch = backend.batch_no_mask().unwrap();
+			let mut tx = test_tx(&parent_key_id, 5);
+			tx.tx_type = TxLogEntryType::TxReceivedCancelled;
+			batch.save_tx_log_entry(tx, &parent_key_id).unwrap();
+			batch.commit().unwrap();
+		}
+		let cancelled = backend
+			.tx_log_entries_by_type(&parent_key_id, &TxLogEntryType::TxReceivedCancelled)
+			.unwrap()
+			.iter()
+			.map(|t| t.id)
+			.collect::<Vec<_>>();
+		assert_eq!(cancelled, vec![5]);
+		assert_eq!(
+			backend
+				.tx_log_entries_by_type(&parent_key_id, &TxLogEntryType::TxReceived)
+				.unwrap()
+				.len(),
+			9
+		);
+		assert_eq!(
+			backend
+				.tx_log_entries_by_type(
+					&ExtKeychain::derive_key_id(2, 1, 0, 0, 0),
+					&TxLogEntryType::TxReceivedCancelled
+				)
+				.unwrap()
+				.len(),
+			0
+		);
+
+		// status changes move the output between index entries
+		{
+			let mut batch = backend.batch_no_mask().unwrap();
+			let mut out = batch
+				.get(&test_output(&parent_key_id, 2).key_id, &None)
+				.unwrap();
+			out.status = OutputStatus::Unspent;
+			batch.save(out).unwrap();
+			batch
+				.delete(&test_output(&parent_key_id, 5).key_id, &None, &None)
+				.unwrap();
+			batch.commit().unwrap();
+		}
+		assert_eq!(
+			backend
+				.outputs_by_status(OutputStatus::Unconfirmed)
+				.unwrap()
+				.len(),
+			8
+		);
+		let unspent = backend.outputs_by_status(OutputStatus::Unspent).unwrap();
+		assert_eq!(unspent.len(), 1);
+		assert_eq!(unspent[0].n_child, 2);
+
+		let heights = backend
+			.outputs_by_height(3, 7)
+			.unwrap()
+			.iter()
+			.map(|o| o.height)
+			.collect::<Vec<_>>();
+		assert_eq!(heights, vec![3, 4, 6, 7]);
+
+		// indexes are rebuilt from scratch when missing or outdated
+		assert!(!backend.build_indexes().unwrap());
+		{
+			let batch = backend.db.batch().unwrap();
+			let version_key = to_key(
+				INDEX_VERSION_PREFIX,
+				&mut INDEX_VERSION_KEY.as_bytes().to_vec(),
+			);
+			batch.put_ser(&version_key, &0u32).unwrap();
+			batch.commit().unwrap();
+		}
+		assert!(backend.build_indexes().unwrap());
+		assert_eq!(
+			backend
+				.outputs_by_status(OutputStatus::Unconfirmed)
+				.unwrap()
+				.len(),
+			8
+		);
+		assert_eq!(
+			backend.outputs_by_height(0, std::u64::MAX).unwrap().len(),
+			9
+		);
+		assert_eq!(
+			backend.tx_log_entries_by_slate_id(&slate_ids[3]).unwrap()[0].id,
+			3
+		);
+		assert_eq!(
+			backend
+				.tx_log_entries_by_type(&parent_key_id, &TxLogEntryType::TxReceived)
+				.unwrap()
+				.len(),
+			9
+		);
+
+		// an index entry left behind by a record that's gone is an error
+		{
+			let batch = backend.db.batch().unwrap();
+			let missing = vec![OUTPUT_PREFIX, 0xff];
+			let mut by_status = vec![output_status_byte(&OutputStatus::Spent)];
+			by_status.extend_from_slice(&missing);
+			batch
+				.put_ser(
+					&to_key(OUTPUT_STATUS_INDEX_PREFIX, &mut by_status),
+					&IndexEntry(missing),
+				)
+				.unwrap();
+			batch.commit().unwrap();
+		}
+		assert!(backend.outputs_by_status(OutputStatus::Spent).is_err());
+
+		clean_output_dir(test_dir);
+	}
+
+	#[test]
+	#[ignore]
+	fn index_benchmark() {
+		let test_dir = "test_output/lmdb_index_benchmark";
+		clean_output_dir(test_dir);
+		let mut backend = open_backend(test_dir);
+		let parent_key_id = LMDBBackend::<LocalWalletClient, ExtKeychain>::default_path();
+		let count = 1_000_000;
+
+		let start = Instant::now();
+		let slate_ids = populate(&mut backend, count);
+		println!("Stored {} outputs and txs in {:?}", count, start.elapsed());
+
+		let slate_id = slate_ids[count as usize / 2];
+		let start = Instant::now();
+		let found = backend
+			.tx_log_iter()
+			.filter(|t| t.tx_slate_id == Some(slate_id))
+			.count();
+		println!("Slate id lookup by iteration: {:?}", start.elapsed());
+		assert_eq!(found, 1);
+
+		let start = Instant::now();
+		let found = backend.tx_log_entries_by_slate_id(&slate_id).unwrap();
+		println!("Slate id lookup by index: {:?}", start.elapsed());
+		assert_eq!(found.len(), 1);
+
+		let start = Instant::now();
+		assert!(backend
+			.tx_log_entry(&parent_key_id, count / 2)
+			.unwrap()
+			.is_some());
+		println!("Tx log id lookup: {:?}", start.elapsed());
+
+		let start = Instant::now();
+		let found = backend.outputs_by_height(1_000, 1_099).unwrap();
+		println!("Height range lookup by index: {:?}", start.elapsed());
+		assert_eq!(found.len(), 100);
+
+		let start = Instant::now();
+		let found = backend.outputs_by_status(OutputStatus::Unspent).unwrap();
+		println!("Status lookup by index: {:?}", start.elapsed());
+		assert!(found.is_empty());
+
+		clean_output_dir(test_dir);
+	}
+
+	#[test]
+	fn tx_log_seq_follows_creation() {
+		let test_dir = "test_output/lmdb_tx_log_seq";
+		clean_output_dir(test_dir);
+		let mut backend = open_backend(test_dir);
+		let parent_key_id = LMDBBackend::<LocalWalletClient, ExtKeychain>::default_path();
+		let other_key_id = ExtKeychain::derive_key_id(2, 1, 0, 0, 0);
+		populate(&mut backend, 3);
+
+		// entries of every account share the order, whatever their timestamps
+		{
+			let mut batch = backend.batch_no_mask().unwrap();
+			let mut t = test_tx(&other_key_id, 0);
+			t.creation_ts = t.creation_ts - chrono::Duration::days(1);
+			batch.save_tx_log_entry(t, &other_key_id).unwrap();
+			batch.commit().unwrap();
+		}
+		let other = backend.tx_log_entry(&other_key_id, 0).unwrap().unwrap();
+		assert_eq!(other.seq, 4);
+
+		// updates keep the position, even when the caller didn't read it
+		{
+			let mut batch = backend.batch_no_mask().unwrap();
+			let mut t = test_tx(&parent_key_id, 1);
+			t.confirmed = true;
+			batch.save_tx_log_entry(t, &parent_key_id).unwrap();
+			batch.commit().unwrap();
+		}
+		let seqs = backend.tx_log_iter().map(|t| t.seq).collect::<Vec<_>>();
+		assert_eq!(seqs.len(), 4);
+		assert_eq!(
+			backend
+				.tx_log_entry(&parent_key_id, 1)
+				.unwrap()
+				.unwrap()
+				.seq,
+			2
+		);
+		let mut sorted = seqs.clone();
+		sorted.sort();
+		assert_eq!(sorted, vec![1, 2, 3, 4]);
+
+		clean_output_dir(test_dir);
+	}
+
+	/// A record as a newer wallet version would write it
+	struct FutureRecord(serde_json::Value);
+
+	impl ser::Writeable for FutureRecord {
+		fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+			writer.write_bytes(&serde_json::to_vec(&self.0).unwrap())
+		}
+	}
+
+	#[test]
+	fn newer_database_opens_read_only() {
+		let test_dir = "test_output/lmdb_read_only";
+		clean_output_dir(test_dir);
+		let parent_key_id = LMDBBackend::<LocalWalletClient, ExtKeychain>::default_path();
+		{
+			let mut backend = open_backend(test_dir);
+			populate(&mut backend, 2);
+
+			// a newer wallet adds fields and values this version doesn't know
+			let mut out = serde_json::to_value(test_output(&parent_key_id, 2)).unwrap();
+			out["status"] = "Frozen".into();
+			out["label"] = "cold storage".into();
+			let mut tx = serde_json::to_value(test_tx(&parent_key_id, 2)).unwrap();
+			tx["tx_type"] = "TxSwapped".into();
+			tx["counterparty"] = "someone".into();
+
+			let batch = backend.db.batch().unwrap();
+			let key_id = test_output(&parent_key_id, 2).key_id;
+			batch
+				.put_ser(
+					&to_key(OUTPUT_PREFIX, &mut key_id.to_bytes().to_vec()),
+					&FutureRecord(out),
+				)
+				.unwrap();
+			batch
+				.put_ser(&tx_log_key(&parent_key_id, 2), &FutureRecord(tx))
+				.unwrap();
+			migrations::put_schema_version(&batch, migrations::SCHEMA_VERSION + 1).unwrap();
+			batch.commit().unwrap();
+		}
+
+		let mut backend = open_backend(test_dir);
+		assert!(backend.read_only);
+		let outputs = backend.iter().collect::<Vec<_>>();
+		assert_eq!(outputs.len(), 3);
+		assert!(outputs
+			.iter()
+			.any(|o| o.n_child == 2 && o.status == OutputStatus::Unknown));
+		let tx = backend.tx_log_entry(&parent_key_id, 2).unwrap().unwrap();
+		assert_eq!(tx.tx_type, TxLogEntryType::Unknown);
+		match backend.batch_no_mask() {
+			Err(e) => assert_eq!(e.kind(), ErrorKind::ReadOnlyWallet),
+			Ok(_) => panic!("newer database opened for writing"),
+		}
+		drop(backend);
+
+		// a database this version can't read at all is refused
+		{
+			let store = store::Store::new(
+				Path::new(test_dir).join(DB_DIR).to_str().unwrap(),
+				None,
+				Some(DB_DIR),
+				None,
+			)
+			.unwrap();
+			let batch = store.batch().unwrap();
+			batch
+				.put_ser(
+					&migrations::read_compat_version_key(),
+					&(migrations::SCHEMA_VERSION + 1),
+				)
+				.unwrap();
+			batch.commit().unwrap();
+		}
+		let (tx, _) = channel();
+		let client = LocalWalletClient::new("wallet1", tx);
+		match LMDBBackend::<LocalWalletClient, ExtKeychain>::new(test_dir, client) {
+			Err(e) => assert_eq!(
+				e.kind(),
+				ErrorKind::UnsupportedSchemaVersion(
+					migrations::SCHEMA_VERSION + 1,
+					migrations::SCHEMA_VERSION
+				)
+			),
+			Ok(_) => panic!("unreadable database was opened"),
+		}
+
+		clean_output_dir(test_dir);
+	}
+
+	#[test]
+	fn replica_opens_read_only() {
+		let test_dir = "test_output/lmdb_replica";
+		clean_output_dir(test_dir);
+		let parent_key_id = LMDBBackend::<LocalWalletClient, ExtKeychain>::default_path();
+		let open_replica = || {
+			let (tx, _) = channel();
+			let client = LocalWalletClient::new("wallet1", tx);
+			LMDBBackend::<LocalWalletClient, ExtKeychain>::new_read_only(test_dir, client)
+		};
+
+		// there's nothing to replicate until the wallet has been created
+		match open_replica() {
+			Err(e) => match e.kind() {
+				ErrorKind::Lifecycle(_) => {}
+				k => panic!("unexpected error {:?}", k),
+			},
+			Ok(_) => panic!("missing database was opened"),
+		}
+		assert!(!Path::new(test_dir).join(DB_DIR).exists());
+
+		{
+			let mut backend = open_backend(test_dir);
+			populate(&mut backend, 3);
+		}
+
+		let mut replica = open_replica().unwrap();
+		assert!(replica.read_only);
+		assert_eq!(replica.iter().count(), 3);
+		assert!(replica.tx_log_entry(&parent_key_id, 1).unwrap().is_some());
+		match replica.batch_no_mask() {
+			Err(e) => assert_eq!(e.kind(), ErrorKind::ReadOnlyWallet),
+			Ok(_) => panic!("replica opened for writing"),
+		}
+		drop(replica);
+
+		// the writing wallet keeps working, and the replica sees its changes
+		{
+			let mut backend = open_backend(test_dir);
+			assert!(!backend.read_only);
+			let mut batch = backend.batch_no_mask().unwrap();
+			batch.save(test_output(&parent_key_id, 3)).unwrap();
+			batch.commit().unwrap();
+		}
+		let replica = open_replica().unwrap();
+		assert_eq!(replica.iter().count(), 4);
+		drop(replica);
+
+		clean_output_dir(test_dir);
+	}
+}
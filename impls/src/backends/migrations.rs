@@ -0,0 +1,447 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Schema versions of the wallet database and the migrations between them
+
+use std::fs;
+use std::path::Path;
+
+use chrono::Utc;
+
+use crate::libwallet::{
+	AcctPathMapping, Error, ErrorKind, MigrationReport, MigrationStep, OutputData, OutputStatus,
+	TxLogEntry, TxState,
+};
+use crate::store::{self, to_key};
+
+use super::lmdb::{
+	index_records, ACCOUNT_PATH_MAPPING_PREFIX, DB_DIR, OUTPUT_PREFIX, TX_LOG_ENTRY_PREFIX,
+};
+use super::platform;
+
+const SCHEMA_VERSION_PREFIX: u8 = 'v' as u8;
+const SCHEMA_VERSION_KEY: &str = "SCHEMA_VERSION";
+const READ_COMPAT_VERSION_KEY: &str = "READ_COMPAT_VERSION";
+
+/// Schema version of the wallet database written by this version of the
+/// wallet. Databases written before schema versions were tracked are at 0
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Oldest schema version whose wallets can still read a database written at
+/// `SCHEMA_VERSION`, opening it read-only. Records are self-describing, so
+/// changes older wallets can ignore (new fields, new enum values) leave this
+/// alone, while changes they would misread (removed or retyped fields, a new
+/// index layout) raise it to `SCHEMA_VERSION`
+pub const READ_COMPAT_VERSION: u32 = 2;
+
+/// A migration bringing the database from the previous schema version to
+/// `version`. It reads the database as it was before the migration, writes
+/// to the given batch and returns the number of records it wrote
+struct Migration {
+	version: u32,
+	description: &'static str,
+	run: fn(&store::Store, &store::Batch<'_>) -> Result<usize, Error>,
+}
+
+/// All migrations, in the order they're applied. A format change ships as a
+/// new migration at the end of this list, along with a bump of
+/// `SCHEMA_VERSION` to its version
+const MIGRATIONS: &[Migration] = &[
+	Migration {
+		version: 1,
+		description: "Build secondary indexes over outputs and tx log entries",
+		run: index_records,
+	},
+	Migration {
+		version: 2,
+		description: "Record the spending transaction of locked outputs",
+		run: record_locked_spenders,
+	},
+	Migration {
+		version: 3,
+		description: "Record the lifecycle state of tx log entries",
+		run: record_tx_states,
+	},
+];
+
+/// Options for bringing the wallet database up to the current schema version
+#[derive(Clone, Debug, Default)]
+pub struct MigrationOptions {
+	/// Report the pending migrations without writing anything
+	pub dry_run: bool,
+	/// Copy the database aside before applying any migration
+	pub backup: bool,
+}
+
+fn schema_version_key() -> Vec<u8> {
+	to_key(
+		SCHEMA_VERSION_PREFIX,
+		&mut SCHEMA_VERSION_KEY.as_bytes().to_vec(),
+	)
+}
+
+pub(super) fn read_compat_version_key() -> Vec<u8> {
+	to_key(
+		SCHEMA_VERSION_PREFIX,
+		&mut READ_COMPAT_VERSION_KEY.as_bytes().to_vec(),
+	)
+}
+
+/// Record the schema version of the database, along with the oldest schema
+/// version that can still read it
+pub(super) fn put_schema_version(batch: &store::Batch<'_>, version: u32) -> Result<(), Error> {
+	batch.put_ser(&schema_version_key(), &version)?;
+	batch.put_ser(
+		&read_compat_version_key(),
+		&READ_COMPAT_VERSION.min(version),
+	)?;
+	Ok(())
+}
+
+/// Whether the store was written by a newer wallet this one can still read,
+/// in which case it must only be read. Fails if it can't be read at all.
+/// Databases without a recorded compatible version are assumed readable only
+/// at their own schema version
+pub(super) fn is_read_only(store: &store::Store) -> Result<bool, Error> {
+	let version = store.get_ser::<u32>(&schema_version_key())?.unwrap_or(0);
+	if version <= SCHEMA_VERSION {
+		return Ok(false);
+	}
+	let compat = store
+		.get_ser::<u32>(&read_compat_version_key())?
+		.unwrap_or(version);
+	if compat > SCHEMA_VERSION {
+		return Err(ErrorKind::UnsupportedSchemaVersion(version, SCHEMA_VERSION).into());
+	}
+	Ok(true)
+}
+
+/// Whether the store is older than this version's schema, and so has to be
+/// migrated before it can be read
+pub(super) fn needs_migration(store: &store::Store) -> Result<bool, Error> {
+	let version = store.get_ser::<u32>(&schema_version_key())?.unwrap_or(0);
+	Ok(version < SCHEMA_VERSION)
+}
+
+/// Open the wallet database in `data_file_dir` and bring it up to the
+/// current schema version
+pub fn migrate(data_file_dir: &str, options: &MigrationOptions) -> Result<MigrationReport, Error> {
+	let db_path = Path::new(data_file_dir).join(DB_DIR);
+	fs::create_dir_all(&db_path).expect("Couldn't create wallet backend directory!");
+	let store = platform::open_store(&db_path)?;
+	migrate_store(&store, data_file_dir, options)
+}
+
+/// Apply the migrations the given store is missing, each in its own batch
+/// along with the new schema version, so an interrupted migration resumes
+/// from the last one applied
+pub(super) fn migrate_store(
+	store: &store::Store,
+	data_file_dir: &str,
+	options: &MigrationOptions,
+) -> Result<MigrationReport, Error> {
+	let from_version = store.get_ser::<u32>(&schema_version_key())?.unwrap_or(0);
+	let read_only = is_read_only(store)?;
+	let mut report = MigrationReport {
+		from_version,
+		to_version: from_version,
+		steps: vec![],
+		backup_path: None,
+		dry_run: options.dry_run,
+	};
+	// a newer wallet's database is left as it is
+	if read_only {
+		return Ok(report);
+	}
+
+	// a database without any account was just created, and is already current
+	let is_new = store
+		.iter::<AcctPathMapping>(&[ACCOUNT_PATH_MAPPING_PREFIX])?
+		.next()
+		.is_none();
+	if is_new {
+		if !options.dry_run {
+			let batch = store.batch()?;
+			put_schema_version(&batch, SCHEMA_VERSION)?;
+			batch.commit()?;
+		}
+		report.to_version = SCHEMA_VERSION;
+		return Ok(report);
+	}
+
+	let pending = MIGRATIONS
+		.iter()
+		.filter(|m| m.version > from_version)
+		.collect::<Vec<_>>();
+	if pending.is_empty() {
+		return Ok(report);
+	}
+	if options.backup && !options.dry_run {
+		report.backup_path = Some(backup(data_file_dir, from_version)?);
+	}
+
+	for m in pending {
+		// in a dry run the batch is dropped without being committed
+		let batch = store.batch()?;
+		let records = (m.run)(store, &batch)?;
+		if !options.dry_run {
+			put_schema_version(&batch, m.version)?;
+			batch.commit()?;
+			info!(
+				"Migrated wallet database to schema version {}: {} ({} records)",
+				m.version, m.description, records
+			);
+		}
+		report.steps.push(MigrationStep {
+			version: m.version,
+			description: m.description.to_owned(),
+			records,
+		});
+		report.to_version = m.version;
+	}
+	Ok(report)
+}
+
+/// Copy the database directory next to it, returning the path of the copy
+fn backup(data_file_dir: &str, version: u32) -> Result<String, Error> {
+	let db_path = Path::new(data_file_dir).join(DB_DIR);
+	let backup_path = Path::new(data_file_dir).join(format!(
+		"{}_backup_v{}_{}",
+		DB_DIR,
+		version,
+		Utc::now().format("%Y%m%d%H%M%S")
+	));
+	copy_dir(&db_path, &backup_path).map_err(|e| {
+		ErrorKind::Lifecycle(format!(
+			"Error backing up wallet database to {}: {}",
+			backup_path.display(),
+			e
+		))
+	})?;
+	info!("Backed up wallet database to {}", backup_path.display());
+	Ok(backup_path.to_string_lossy().into_owned())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+	fs::create_dir_all(to)?;
+	for entry in fs::read_dir(from)? {
+		let entry = entry?;
+		let target = to.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			copy_dir(&entry.path(), &target)?;
+		} else {
+			fs::copy(entry.path(), target)?;
+		}
+	}
+	Ok(())
+}
+
+/// Outputs locked by a transaction carry that transaction's id as their tx
+/// log entry. Record it as their spending transaction, as locking now does
+fn record_locked_spenders(db: &store::Store, batch: &store::Batch<'_>) -> Result<usize, Error> {
+	let locked = db
+		.iter::<OutputData>(&[OUTPUT_PREFIX])?
+		.filter(|(_, o)| o.status == OutputStatus::Locked && o.spent_by_tx.is_none())
+		.collect::<Vec<_>>();
+	let mut count = 0;
+	for (key, mut out) in locked {
+		if out.tx_log_entry.is_some() {
+			out.spent_by_tx = out.tx_log_entry;
+			batch.put_ser(&key, &out)?;
+			count += 1;
+		}
+	}
+	Ok(count)
+}
+
+/// Tx log entries stored before their state was recorded read as `Created`.
+/// Record the state their type and flags show they're in
+fn record_tx_states(db: &store::Store, batch: &store::Batch<'_>) -> Result<usize, Error> {
+	let txs = db
+		.iter::<TxLogEntry>(&[TX_LOG_ENTRY_PREFIX])?
+		.collect::<Vec<_>>();
+	let mut count = 0;
+	for (key, mut tx) in txs {
+		let state = tx.infer_state();
+		if state != TxState::Created {
+			tx.state = state;
+			batch.put_ser(&key, &tx)?;
+			count += 1;
+		}
+	}
+	Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+	use super::super::lmdb::tx_log_key;
+	use super::*;
+	use crate::keychain::{ExtKeychain, Keychain};
+	use crate::libwallet::TxLogEntryType;
+
+	fn clean_output_dir(test_dir: &str) {
+		let _ = fs::remove_dir_all(test_dir);
+	}
+
+	fn open_store(test_dir: &str) -> store::Store {
+		let db_path = Path::new(test_dir).join(DB_DIR);
+		fs::create_dir_all(&db_path).unwrap();
+		store::Store::new(db_path.to_str().unwrap(), None, Some(DB_DIR), None).unwrap()
+	}
+
+	/// Write a database as an unversioned wallet would have, with one
+	/// locked output and the tx log entry of a confirmed send
+	fn legacy_store(test_dir: &str) -> (store::Store, Vec<u8>, Vec<u8>) {
+		let store = open_store(test_dir);
+		let parent_key_id = ExtKeychain::derive_key_id(2, 0, 0, 0, 0);
+		let account = AcctPathMapping {
+			label: "default".to_owned(),
+			path: parent_key_id.clone(),
+			frozen: false,
+		};
+		let key_id = ExtKeychain::derive_key_id(3, 0, 0, 1, 0);
+		let out = OutputData {
+			root_key_id: parent_key_id,
+			key_id: key_id.clone(),
+			n_child: 1,
+			commit: None,
+			mmr_index: None,
+			value: 1_000,
+			status: OutputStatus::Locked,
+			height: 1,
+			lock_height: 0,
+			is_coinbase: false,
+			tx_log_entry: Some(4),
+			is_foundation: false,
+			spent_by_tx: None,
+			spent_height: None,
+			tags: vec![],
+			missing_since: None,
+		};
+		let out_key = to_key(OUTPUT_PREFIX, &mut key_id.to_bytes().to_vec());
+		let mut tx = TxLogEntry::new(parent_key_id.clone(), TxLogEntryType::TxSent, 3);
+		tx.confirmed = true;
+		let tx_key = tx_log_key(&parent_key_id, 3);
+		{
+			let batch = store.batch().unwrap();
+			batch
+				.put_ser(
+					&to_key(ACCOUNT_PATH_MAPPING_PREFIX, &mut b"default".to_vec()),
+					&account,
+				)
+				.unwrap();
+			batch.put_ser(&out_key, &out).unwrap();
+			batch.put_ser(&tx_key, &tx).unwrap();
+			batch.commit().unwrap();
+		}
+		(store, out_key, tx_key)
+	}
+
+	#[test]
+	fn migrations_run_in_order() {
+		let test_dir = "test_output/migrations";
+		clean_output_dir(test_dir);
+		let (store, out_key, tx_key) = legacy_store(test_dir);
+		let spent_by = |store: &store::Store| {
+			store
+				.get_ser::<OutputData>(&out_key)
+				.unwrap()
+				.unwrap()
+				.spent_by_tx
+		};
+
+		// a dry run reports every migration but leaves the database alone
+		let options = MigrationOptions {
+			dry_run: true,
+			backup: true,
+		};
+		let report = migrate_store(&store, test_dir, &options).unwrap();
+		assert_eq!(report.from_version, 0);
+		assert_eq!(report.to_version, SCHEMA_VERSION);
+		assert_eq!(
+			report.steps.iter().map(|s| s.version).collect::<Vec<_>>(),
+			vec![1, 2, 3]
+		);
+		assert_eq!(report.steps[1].records, 1);
+		assert_eq!(report.steps[2].records, 1);
+		assert!(report.backup_path.is_none());
+		assert_eq!(store.get_ser::<u32>(&schema_version_key()).unwrap(), None);
+		assert_eq!(spent_by(&store), None);
+
+		// the database is backed up before being migrated
+		let options = MigrationOptions {
+			dry_run: false,
+			backup: true,
+		};
+		let report = migrate_store(&store, test_dir, &options).unwrap();
+		assert_eq!(report.steps.len(), 3);
+		assert!(Path::new(&report.backup_path.unwrap()).exists());
+		assert_eq!(
+			store.get_ser::<u32>(&schema_version_key()).unwrap(),
+			Some(SCHEMA_VERSION)
+		);
+		assert_eq!(spent_by(&store), Some(4));
+		let tx = store.get_ser::<TxLogEntry>(&tx_key).unwrap().unwrap();
+		assert_eq!(tx.state, TxState::Confirmed);
+
+		// once current, there's nothing left to do
+		let report = migrate_store(&store, test_dir, &options).unwrap();
+		assert!(report.steps.is_empty());
+		assert!(report.backup_path.is_none());
+
+		// a database from a newer wallet that can still be read is left alone
+		{
+			let batch = store.batch().unwrap();
+			batch
+				.put_ser(&schema_version_key(), &(SCHEMA_VERSION + 1))
+				.unwrap();
+			batch.commit().unwrap();
+		}
+		assert!(is_read_only(&store).unwrap());
+		let report = migrate_store(&store, test_dir, &options).unwrap();
+		assert!(report.steps.is_empty());
+		assert_eq!(report.from_version, SCHEMA_VERSION + 1);
+
+		// and one that can't is refused
+		{
+			let batch = store.batch().unwrap();
+			batch
+				.put_ser(&read_compat_version_key(), &(SCHEMA_VERSION + 1))
+				.unwrap();
+			batch.commit().unwrap();
+		}
+		match migrate_store(&store, test_dir, &options) {
+			Err(e) => assert_eq!(
+				e.kind(),
+				ErrorKind::UnsupportedSchemaVersion(SCHEMA_VERSION + 1, SCHEMA_VERSION)
+			),
+			Ok(_) => panic!("newer schema versions should be refused"),
+		}
+		clean_output_dir(test_dir);
+	}
+
+	#[test]
+	fn new_database_is_current() {
+		let test_dir = "test_output/migrations_new";
+		clean_output_dir(test_dir);
+		let store = open_store(test_dir);
+		let report = migrate_store(&store, test_dir, &MigrationOptions::default()).unwrap();
+		assert!(report.steps.is_empty());
+		assert_eq!(
+			store.get_ser::<u32>(&schema_version_key()).unwrap(),
+			Some(SCHEMA_VERSION)
+		);
+		clean_output_dir(test_dir);
+	}
+}
@@ -13,5 +13,8 @@
 // limitations under the License.
 
 mod lmdb;
+mod migrations;
+mod platform;
 
 pub use self::lmdb::{wallet_db_exists, LMDBBackend};
+pub use self::migrations::{migrate, MigrationOptions, SCHEMA_VERSION};
@@ -0,0 +1,351 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opening the LMDB store behind the wallet database, on every platform the
+//! wallet runs on. LMDB data files aren't portable between 32 and 64-bit
+//! builds, need a filesystem whose file locks work, and on 32-bit targets
+//! such as armv7 phones share a small address space with everything else.
+//! These are checked before the store maps the file, since LMDB itself
+//! reports them as crashes or corruption rather than errors
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::mem;
+use std::path::Path;
+
+use crate::libwallet::{Error, ErrorKind};
+use crate::store;
+
+use super::lmdb::DB_DIR;
+
+/// Directory the epic store keeps its LMDB environment in, inside the
+/// wallet's database directory
+const LMDB_ENV_DIR: &str = "lmdb";
+
+/// LMDB's data file in its environment directory
+const LMDB_DATA_FILE: &str = "data.mdb";
+
+/// File briefly locked to find out whether locks work in a directory
+const LOCK_PROBE_FILE: &str = "lock.probe";
+
+/// Magic number at the start of LMDB's meta pages
+const LMDB_MAGIC: u32 = 0xBEEF_C0DE;
+
+/// Smallest and largest page sizes LMDB creates data files with
+const LMDB_MIN_PAGE_SIZE: u32 = 512;
+const LMDB_MAX_PAGE_SIZE: u32 = 0x8000;
+
+/// Largest memory map a 32-bit build opens a database with. A 32-bit
+/// process rarely finds a larger contiguous range free in its address space
+const MAX_MAP_SIZE_32BIT: u64 = 1 << 30;
+
+/// What an LMDB data file's header says about the build that created it
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DbHeader {
+	/// Word size of the build that created the file, in bits
+	word_bits: usize,
+	/// Size of the file's pages
+	page_size: u32,
+	/// Size of the memory map the file was last opened with
+	map_size: u64,
+}
+
+/// Open the wallet's LMDB store in `db_path`, after checking the database
+/// there can be used by this build on this filesystem
+pub(super) fn open_store(db_path: &Path) -> Result<store::Store, Error> {
+	let env_path = db_path.join(LMDB_ENV_DIR);
+	fs::create_dir_all(&env_path)
+		.map_err(|e| ErrorKind::Backend(format!("can't create {}: {}", env_path.display(), e)))?;
+	check_file_locking(&env_path)?;
+	if let Some(header) = read_header(&env_path.join(LMDB_DATA_FILE))? {
+		check_header(&header, mem::size_of::<usize>() * 8, db_path)?;
+		let os_page_size = os_page_size();
+		if header.page_size != os_page_size {
+			info!(
+				"Wallet database at {} has {} byte pages, on a system with {} byte pages",
+				db_path.display(),
+				header.page_size,
+				os_page_size
+			);
+		}
+	}
+	Ok(store::Store::new(
+		db_path.to_str().unwrap(),
+		None,
+		Some(DB_DIR),
+		None,
+	)?)
+}
+
+/// Read the header of an LMDB data file, if there is one yet
+fn read_header(path: &Path) -> Result<Option<DbHeader>, Error> {
+	let not_lmdb = || ErrorKind::Backend(format!("{} isn't an LMDB data file", path.display()));
+	let mut file = match File::open(path) {
+		Ok(f) => f,
+		Err(_) => return Ok(None),
+	};
+	let mut bytes = vec![];
+	file.by_ref()
+		.take(64)
+		.read_to_end(&mut bytes)
+		.map_err(|_| not_lmdb())?;
+	if bytes.is_empty() {
+		return Ok(None);
+	}
+	// this build's layout first, as it's all but certain to be that
+	let word = mem::size_of::<usize>();
+	let other = if word == 8 { 4 } else { 8 };
+	match parse_header(&bytes, word).or_else(|| parse_header(&bytes, other)) {
+		Some(h) => Ok(Some(h)),
+		None => Err(not_lmdb().into()),
+	}
+}
+
+/// Parse the first meta page of an LMDB data file, as laid out by a build
+/// whose words are `word` bytes long. None if it isn't laid out that way
+fn parse_header(bytes: &[u8], word: usize) -> Option<DbHeader> {
+	let read_u32 = |at: usize| {
+		let mut b = [0u8; 4];
+		b.copy_from_slice(bytes.get(at..at + 4)?);
+		Some(u32::from_ne_bytes(b))
+	};
+	let read_word = |at: usize| {
+		if word == 4 {
+			return read_u32(at).map(|v| v as u64);
+		}
+		let mut b = [0u8; 8];
+		b.copy_from_slice(bytes.get(at..at + 8)?);
+		Some(u64::from_ne_bytes(b))
+	};
+	// the page header is the page number, then 2 byte padding and flags
+	// and a 4 byte bound or overflow count
+	let meta = word + 8;
+	if read_u32(meta)? != LMDB_MAGIC {
+		return None;
+	}
+	// the magic is followed by the format version, the fixed map address
+	// and the map size, then the free page db whose padding is the page size
+	Some(DbHeader {
+		word_bits: word * 8,
+		map_size: read_word(meta + 8 + word)?,
+		page_size: read_u32(meta + 8 + 2 * word)?,
+	})
+}
+
+/// Check a database created by the build described in `header` can be
+/// opened by one with `word_bits` long words
+fn check_header(header: &DbHeader, word_bits: usize, db_path: &Path) -> Result<(), Error> {
+	if header.word_bits != word_bits {
+		return Err(ErrorKind::Backend(format!(
+			"wallet database at {} was created by a {}-bit wallet and can't be opened by \
+			 this {}-bit one, restore the wallet from its recovery phrase instead",
+			db_path.display(),
+			header.word_bits,
+			word_bits
+		))
+		.into());
+	}
+	if !header.page_size.is_power_of_two()
+		|| header.page_size < LMDB_MIN_PAGE_SIZE
+		|| header.page_size > LMDB_MAX_PAGE_SIZE
+	{
+		return Err(ErrorKind::Backend(format!(
+			"wallet database at {} has an unsupported page size of {} bytes",
+			db_path.display(),
+			header.page_size
+		))
+		.into());
+	}
+	if word_bits == 32 && header.map_size > MAX_MAP_SIZE_32BIT {
+		return Err(ErrorKind::Backend(format!(
+			"wallet database at {} needs a {} byte memory map, more than a 32-bit wallet \
+			 can map",
+			db_path.display(),
+			header.map_size
+		))
+		.into());
+	}
+	Ok(())
+}
+
+/// Check file locks work in `dir`, as LMDB relies on them to keep readers
+/// and writers in different processes apart. They don't on some network
+/// filesystems and on shared storage on phones
+#[cfg(unix)]
+fn check_file_locking(dir: &Path) -> Result<(), Error> {
+	use std::fs::OpenOptions;
+	use std::os::unix::io::AsRawFd;
+
+	let probe = dir.join(LOCK_PROBE_FILE);
+	let file = OpenOptions::new()
+		.read(true)
+		.write(true)
+		.create(true)
+		.open(&probe)
+		.map_err(|e| ErrorKind::Backend(format!("can't create {}: {}", probe.display(), e)))?;
+	let mut lock: libc::flock = unsafe { mem::zeroed() };
+	lock.l_type = libc::F_WRLCK as libc::c_short;
+	lock.l_whence = libc::SEEK_SET as libc::c_short;
+	let res = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETLK, &lock as *const libc::flock) };
+	let err = std::io::Error::last_os_error();
+	if res == 0 {
+		lock.l_type = libc::F_UNLCK as libc::c_short;
+		unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETLK, &lock as *const libc::flock) };
+	}
+	drop(file);
+	let _ = fs::remove_file(&probe);
+
+	// another wallet holding the lock while probing shows locks work
+	let held = match err.raw_os_error() {
+		Some(code) => code == libc::EAGAIN || code == libc::EACCES,
+		None => false,
+	};
+	if res != 0 && !held {
+		return Err(ErrorKind::Backend(format!(
+			"file locks don't work in {} ({}), keep the wallet data directory on local \
+			 or internal storage",
+			dir.display(),
+			err
+		))
+		.into());
+	}
+	Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_file_locking(_dir: &Path) -> Result<(), Error> {
+	Ok(())
+}
+
+/// The system's memory page size, which LMDB creates new data files with
+#[cfg(unix)]
+fn os_page_size() -> u32 {
+	match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+		size if size > 0 => size as u32,
+		_ => 4096,
+	}
+}
+
+#[cfg(not(unix))]
+fn os_page_size() -> u32 {
+	4096
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn clean_output_dir(test_dir: &str) {
+		let _ = fs::remove_dir_all(test_dir);
+	}
+
+	/// The start of a data file as created by a build with `word` byte words
+	fn header_bytes(word: usize, page_size: u32, map_size: u64) -> Vec<u8> {
+		let mut bytes = vec![0u8; word + 8];
+		bytes.extend_from_slice(&LMDB_MAGIC.to_ne_bytes());
+		bytes.extend_from_slice(&1u32.to_ne_bytes());
+		bytes.resize(bytes.len() + word, 0);
+		match word {
+			4 => bytes.extend_from_slice(&(map_size as u32).to_ne_bytes()),
+			_ => bytes.extend_from_slice(&map_size.to_ne_bytes()),
+		}
+		bytes.extend_from_slice(&page_size.to_ne_bytes());
+		bytes.resize(64, 0);
+		bytes
+	}
+
+	#[test]
+	fn headers_parsed_for_either_word_size() {
+		for &word in &[4, 8] {
+			let bytes = header_bytes(word, 4096, 1 << 27);
+			let header = parse_header(&bytes, word).unwrap();
+			assert_eq!(
+				header,
+				DbHeader {
+					word_bits: word * 8,
+					page_size: 4096,
+					map_size: 1 << 27,
+				}
+			);
+			let other = if word == 8 { 4 } else { 8 };
+			assert!(parse_header(&bytes, other).is_none());
+		}
+		assert!(parse_header(&[0u8; 64], 8).is_none());
+		assert!(parse_header(&[0u8; 10], 8).is_none());
+	}
+
+	#[test]
+	fn headers_checked() {
+		let path = Path::new("wallet_data/db");
+		let header = |word_bits, page_size, map_size| DbHeader {
+			word_bits,
+			page_size,
+			map_size,
+		};
+		assert!(check_header(&header(64, 4096, 1 << 32), 64, path).is_ok());
+		assert!(check_header(&header(64, 16384, 1 << 27), 64, path).is_ok());
+		assert!(check_header(&header(32, 4096, 1 << 27), 32, path).is_ok());
+
+		// files don't move between word sizes
+		assert!(check_header(&header(64, 4096, 1 << 27), 32, path).is_err());
+		assert!(check_header(&header(32, 4096, 1 << 27), 64, path).is_err());
+
+		// nor are pages of a size LMDB doesn't use
+		assert!(check_header(&header(64, 3000, 1 << 27), 64, path).is_err());
+		assert!(check_header(&header(64, 0x10000, 1 << 27), 64, path).is_err());
+
+		// a 32-bit build can't map what a 64-bit one can
+		assert!(check_header(&header(32, 4096, 1 << 31), 32, path).is_err());
+	}
+
+	#[test]
+	fn store_opened_after_checks() {
+		let test_dir = "test_output/platform_open_store";
+		clean_output_dir(test_dir);
+		let db_path = Path::new(test_dir).join(DB_DIR);
+		let env_path = db_path.join(LMDB_ENV_DIR);
+
+		fs::create_dir_all(&env_path).unwrap();
+		check_file_locking(&env_path).unwrap();
+		assert!(!env_path.join(LOCK_PROBE_FILE).exists());
+		assert!(read_header(&env_path.join(LMDB_DATA_FILE))
+			.unwrap()
+			.is_none());
+
+		// the file LMDB creates is one this build reads back and accepts
+		{
+			let store = open_store(&db_path).unwrap();
+			let batch = store.batch().unwrap();
+			batch.put_ser(&[1u8], &42u32).unwrap();
+			batch.commit().unwrap();
+		}
+		let header = read_header(&env_path.join(LMDB_DATA_FILE))
+			.unwrap()
+			.unwrap();
+		assert_eq!(header.word_bits, mem::size_of::<usize>() * 8);
+		assert!(header.page_size.is_power_of_two());
+		assert_eq!(
+			header.page_size,
+			std::cmp::min(os_page_size(), LMDB_MAX_PAGE_SIZE)
+		);
+		let store = open_store(&db_path).unwrap();
+		assert_eq!(store.get_ser::<u32>(&[1u8]).unwrap(), Some(42));
+
+		// a file that isn't LMDB's is refused rather than mapped
+		drop(store);
+		fs::write(env_path.join(LMDB_DATA_FILE), vec![7u8; 64]).unwrap();
+		assert!(open_store(&db_path).is_err());
+		clean_output_dir(test_dir);
+	}
+}
@@ -20,10 +20,9 @@ use failure::{Backtrace, Context, Fail, ResultExt};
 use futures::future::result;
 use futures::future::{err, ok, Either};
 use futures::stream::Stream;
-use http::uri::{InvalidUri, Uri};
-use hyper::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use hyper::header::{HeaderMap, ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
 use hyper::rt::Future;
-use hyper::{self, Body, Request};
+use hyper::{self, Body, Request, Uri};
 use hyper_rustls;
 use hyper_timeout::TimeoutConnector;
 use serde::{Deserialize, Serialize};
@@ -32,6 +31,7 @@ use std::fmt::{self, Display};
 use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::runtime::Runtime;
+use tokio::timer::Timeout;
 
 /// Errors that can be returned by an ApiEndpoint implementation.
 #[derive(Debug)]
@@ -51,6 +51,12 @@ pub enum ErrorKind {
 	RequestError(String),
 	#[fail(display = "ResponseError error: {}", _0)]
 	ResponseError(String),
+	/// The server couldn't be reached, or the connection dropped
+	#[fail(display = "Request error: {}", _0)]
+	Connection(String),
+	/// The server answered with an error status code
+	#[fail(display = "Request error: {}", _1)]
+	ResponseStatus(u16, String),
 }
 
 impl Fail for Error {
@@ -73,6 +79,17 @@ impl Error {
 	pub fn _kind(&self) -> &ErrorKind {
 		self.inner.get_context()
 	}
+
+	/// Whether the request may succeed if simply made again: the server
+	/// couldn't be reached, timed out, or was briefly unable to answer.
+	/// Bad requests, refusals and unreadable responses won't get any better.
+	pub fn is_transient(&self) -> bool {
+		match self._kind() {
+			ErrorKind::Connection(_) => true,
+			ErrorKind::ResponseStatus(code, _) => *code == 408 || *code == 429 || *code >= 500,
+			_ => false,
+		}
+	}
 }
 
 impl From<ErrorKind> for Error {
@@ -96,6 +113,11 @@ pub struct Client {
 	pub use_socks: bool,
 	/// Proxy url/port
 	pub socks_proxy_addr: Option<SocketAddr>,
+	/// Longest a request may take, from connecting to reading the whole
+	/// response, if it's limited. The connection's own timeouts only limit
+	/// each read and write, so a server answering slowly, a little at a
+	/// time, can otherwise hold a request up indefinitely
+	pub timeout: Option<Duration>,
 }
 
 impl Client {
@@ -104,6 +126,15 @@ impl Client {
 		Client {
 			use_socks: false,
 			socks_proxy_addr: None,
+			timeout: None,
+		}
+	}
+
+	/// New client giving up on requests taking longer than `timeout`
+	pub fn with_timeout(timeout: Option<Duration>) -> Self {
+		Client {
+			timeout,
+			..Client::new()
 		}
 	}
 
@@ -147,7 +178,7 @@ impl Client {
 	/// object as body on a given URL that returns a JSON object. Handles request
 	/// building, JSON serialization and deserialization, and response code
 	/// checking.
-	pub fn _post<IN, OUT>(
+	pub fn post<IN, OUT>(
 		&self,
 		url: &str,
 		api_secret: Option<String>,
@@ -226,7 +257,7 @@ impl Client {
 		api_secret: Option<String>,
 		body: Option<String>,
 	) -> Result<Request<Body>, Error> {
-		let uri = url.parse::<Uri>().map_err::<Error, _>(|e: InvalidUri| {
+		let uri = url.parse::<Uri>().map_err::<Error, _>(|e| {
 			e.context(ErrorKind::Argument(format!("Invalid url {}", url)))
 				.into()
 		})?;
@@ -293,6 +324,33 @@ impl Client {
 		&self,
 		req: Request<Body>,
 	) -> Box<dyn Future<Item = String, Error = Error> + Send> {
+		Box::new(
+			self.send_request_with_headers_async(req)
+				.map(|(_, body)| body),
+		)
+	}
+
+	fn send_request_with_headers_async(
+		&self,
+		req: Request<Body>,
+	) -> Box<dyn Future<Item = (HeaderMap, String), Error = Error> + Send> {
+		let task = self.send_request_untimed_async(req);
+		let timeout = match self.timeout {
+			Some(t) => t,
+			None => return task,
+		};
+		Box::new(
+			Timeout::new(task, timeout).map_err(move |e| match e.into_inner() {
+				Some(e) => e,
+				None => ErrorKind::Connection(format!("No response within {:?}", timeout)).into(),
+			}),
+		)
+	}
+
+	fn send_request_untimed_async(
+		&self,
+		req: Request<Body>,
+	) -> Box<dyn Future<Item = (HeaderMap, String), Error = Error> + Send> {
 		//TODO: redundant code, enjoy figuring out type params for dynamic dispatch of client
 		match self.use_socks {
 			false => {
@@ -306,29 +364,35 @@ impl Client {
 					client
 						.request(req)
 						.map_err(|e| {
-							ErrorKind::RequestError(format!("Cannot make request: {}", e)).into()
+							ErrorKind::Connection(format!("Cannot make request: {}", e)).into()
 						})
 						.and_then(|resp| {
 							if !resp.status().is_success() {
-								Either::A(err(ErrorKind::RequestError(format!(
-									"Wrong response code: {} with data {:?}",
-									resp.status(),
-									resp.body()
-								))
+								Either::A(err(ErrorKind::ResponseStatus(
+									resp.status().as_u16(),
+									format!(
+										"Wrong response code: {} with data {:?}",
+										resp.status(),
+										resp.body()
+									),
+								)
 								.into()))
 							} else {
+								let headers = resp.headers().clone();
 								Either::B(
 									resp.into_body()
 										.map_err(|e| {
-											ErrorKind::RequestError(format!(
+											ErrorKind::Connection(format!(
 												"Cannot read response body: {}",
 												e
 											))
 											.into()
 										})
 										.concat2()
-										.and_then(|ch| {
-											ok(String::from_utf8_lossy(&ch.to_vec()).to_string())
+										.and_then(move |ch| {
+											let body =
+												String::from_utf8_lossy(&ch.to_vec()).to_string();
+											ok((headers, body))
 										}),
 								)
 							}
@@ -355,29 +419,35 @@ impl Client {
 					client
 						.request(req)
 						.map_err(|e| {
-							ErrorKind::RequestError(format!("Cannot make request: {}", e)).into()
+							ErrorKind::Connection(format!("Cannot make request: {}", e)).into()
 						})
 						.and_then(|resp| {
 							if !resp.status().is_success() {
-								Either::A(err(ErrorKind::RequestError(format!(
-									"Wrong response code: {} with data {:?}",
-									resp.status(),
-									resp.body()
-								))
+								Either::A(err(ErrorKind::ResponseStatus(
+									resp.status().as_u16(),
+									format!(
+										"Wrong response code: {} with data {:?}",
+										resp.status(),
+										resp.body()
+									),
+								)
 								.into()))
 							} else {
+								let headers = resp.headers().clone();
 								Either::B(
 									resp.into_body()
 										.map_err(|e| {
-											ErrorKind::RequestError(format!(
+											ErrorKind::Connection(format!(
 												"Cannot read response body: {}",
 												e
 											))
 											.into()
 										})
 										.concat2()
-										.and_then(|ch| {
-											ok(String::from_utf8_lossy(&ch.to_vec()).to_string())
+										.and_then(move |ch| {
+											let body =
+												String::from_utf8_lossy(&ch.to_vec()).to_string();
+											ok((headers, body))
 										}),
 								)
 							}
@@ -393,4 +463,15 @@ impl Client {
 			Runtime::new().context(ErrorKind::Internal("can't create Tokio runtime".to_owned()))?;
 		Ok(rt.block_on(task)?)
 	}
+
+	/// Send a request, returning the response headers along with its body
+	pub fn send_request_with_headers(
+		&self,
+		req: Request<Body>,
+	) -> Result<(HeaderMap, String), Error> {
+		let task = self.send_request_with_headers_async(req);
+		let mut rt =
+			Runtime::new().context(ErrorKind::Internal("can't create Tokio runtime".to_owned()))?;
+		Ok(rt.block_on(task)?)
+	}
 }
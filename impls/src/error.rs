@@ -17,6 +17,7 @@ use crate::core::libtx;
 use crate::keychain;
 use crate::libwallet;
 use crate::util::secp;
+use epic_wallet_util::RngError;
 use failure::{Backtrace, Context, Fail};
 use std::env;
 use std::fmt::{self, Display};
@@ -86,6 +87,14 @@ pub enum ErrorKind {
 	#[fail(display = "Address is not an Onion v3 Address: {}", _0)]
 	NotOnion(String),
 
+	/// Obtaining a certificate from an ACME certificate authority
+	#[fail(display = "ACME certificate error: {}", _0)]
+	Acme(String),
+
+	/// Storing or loading the wallet seed in its configured storage
+	#[fail(display = "Seed storage error: {}", _0)]
+	SeedStorage(String),
+
 	/// Other
 	#[fail(display = "Generic error: {}", _0)]
 	GenericError(String),
@@ -180,6 +189,12 @@ impl From<libwallet::Error> for Error {
 	}
 }
 
+impl From<RngError> for Error {
+	fn from(error: RngError) -> Error {
+		Error::from(libwallet::Error::from(error))
+	}
+}
+
 impl From<libtx::Error> for Error {
 	fn from(error: libtx::Error) -> Error {
 		Error {
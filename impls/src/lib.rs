@@ -23,7 +23,9 @@ extern crate serde_derive;
 #[macro_use]
 extern crate log;
 use epic_wallet_libwallet as libwallet;
+#[cfg(any(test, feature = "http", feature = "test_framework"))]
 use epic_wallet_util::epic_api as api;
+#[cfg(any(test, feature = "test_framework"))]
 use epic_wallet_util::epic_chain as chain;
 use epic_wallet_util::epic_core as core;
 use epic_wallet_util::epic_keychain as keychain;
@@ -32,23 +34,48 @@ use epic_wallet_util::epic_util as util;
 
 use epic_wallet_config as config;
 
+#[cfg(feature = "acme")]
+pub mod acme;
 mod adapters;
 mod backends;
+#[cfg(feature = "http")]
 mod client_utils;
 mod error;
 mod lifecycle;
+#[cfg(feature = "http")]
 mod node_clients;
+#[cfg(any(test, feature = "test_framework"))]
 pub mod test_framework;
 pub mod tor;
 
 pub use crate::adapters::{
-	create_sender, HttpSlateSender, KeybaseAllChannels, KeybaseChannel, PathToSlate, SlateGetter,
-	SlatePutter, SlateReceiver, SlateSender,
+	create_sender, post_webhook, send_response_slate, validate_destination, ExecSlateSender,
+	PathToSlate, SlateGetter, SlatePutter, SlateReceiver, SlateSender, HTTP_SUPPORT, QR_SUPPORT,
+};
+#[cfg(feature = "http")]
+pub use crate::adapters::{
+	fetch_version_advisory, HttpSlateSender, KeybaseAllChannels, KeybaseChannel,
+};
+#[cfg(feature = "qr")]
+pub use crate::adapters::{
+	qr_parts_from_image, render_qr_part, slate_to_qr_parts, QrImagesToSlate, QrSlateDecoder,
+	QrToTerminal, QR_PART_MAX_CHARS,
+};
+pub use crate::backends::{
+	migrate, wallet_db_exists, LMDBBackend, MigrationOptions, SCHEMA_VERSION,
 };
-pub use crate::backends::{wallet_db_exists, LMDBBackend};
 pub use crate::error::{Error, ErrorKind};
-pub use crate::lifecycle::DefaultLCProvider;
-pub use crate::node_clients::HTTPNodeClient;
+pub use crate::lifecycle::{
+	seed_backend, DefaultLCProvider, EncryptedWalletSeed, FileSeedBackend, SeedBackend,
+	OS_KEYSTORE_SUPPORT, PKCS11_PIN_ENV, PKCS11_SUPPORT, SEED_FILE,
+};
+#[cfg(feature = "http")]
+pub use crate::node_clients::{HTTPNodeClient, NodeRetryPolicy};
+pub use crate::tor::TOR_SUPPORT;
+
+/// Whether the foreign listener can obtain its TLS certificate with ACME,
+/// i.e. the wallet was built with the acme feature
+pub const ACME_SUPPORT: bool = cfg!(feature = "acme");
 
 use crate::keychain::{ExtKeychain, Keychain};
 
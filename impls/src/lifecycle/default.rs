@@ -15,17 +15,20 @@
 //! Default wallet lifecycle provider
 
 use crate::config::{
-	config, GlobalWalletConfig, GlobalWalletConfigMembers, TorConfig, WalletConfig, EPIC_WALLET_DIR,
+	config, GlobalWalletConfig, GlobalWalletConfigMembers, SeedKdfParams, SeedStorage, TorConfig,
+	WalletConfig, EPIC_WALLET_DIR,
 };
 use crate::core::global;
 use crate::keychain::Keychain;
 use crate::libwallet::{
-	Error, ErrorKind, NodeClient, WalletBackend, WalletInitStatus, WalletLCProvider,
+	Error, ErrorKind, EventBus, MigrationReport, NodeClient, WalletBackend, WalletInitStatus,
+	WalletLCProvider, DEFAULT_COINBASE_CLEANUP_WINDOW,
 };
 use crate::lifecycle::seed::WalletSeed;
+use crate::lifecycle::seed_backend::{seed_backend, SeedBackend};
 use crate::util::secp::key::SecretKey;
 use crate::util::ZeroingString;
-use crate::LMDBBackend;
+use crate::{migrate, LMDBBackend, MigrationOptions};
 use epic_wallet_util::epic_util::logger::LoggingConfig;
 use failure::ResultExt;
 use std::fs;
@@ -38,6 +41,13 @@ where
 {
 	data_dir: String,
 	node_client: C,
+	coinbase_cleanup_window: u64,
+	confirm_missing_outputs: bool,
+	backup_before_migrate: bool,
+	seed_storage: SeedStorage,
+	seed_kdf: SeedKdfParams,
+	read_only: bool,
+	events: EventBus,
 	backend: Option<Box<dyn WalletBackend<'a, C, K> + 'a>>,
 }
 
@@ -51,9 +61,21 @@ where
 		DefaultLCProvider {
 			node_client,
 			data_dir: "default".to_owned(),
+			coinbase_cleanup_window: DEFAULT_COINBASE_CLEANUP_WINDOW,
+			confirm_missing_outputs: false,
+			backup_before_migrate: true,
+			seed_storage: SeedStorage::File,
+			seed_kdf: SeedKdfParams::default(),
+			read_only: false,
+			events: EventBus::new(),
 			backend: None,
 		}
 	}
+
+	fn seed_backend(&self, data_dir_name: &str) -> Result<Box<dyn SeedBackend>, Error> {
+		seed_backend(&self.seed_storage, data_dir_name)
+			.map_err(|e| ErrorKind::Lifecycle(format!("{}", e)).into())
+	}
 }
 
 impl<'a, C, K> WalletLCProvider<'a, C, K> for DefaultLCProvider<'a, C, K>
@@ -70,6 +92,65 @@ where
 		Ok(self.data_dir.to_owned())
 	}
 
+	fn set_coinbase_cleanup_window(&mut self, blocks: u64) -> Result<(), Error> {
+		self.coinbase_cleanup_window = blocks;
+		if let Some(b) = self.backend.as_mut() {
+			b.set_coinbase_cleanup_window(blocks);
+		}
+		Ok(())
+	}
+
+	fn set_confirm_missing_outputs(&mut self, confirm: bool) -> Result<(), Error> {
+		self.confirm_missing_outputs = confirm;
+		if let Some(b) = self.backend.as_mut() {
+			b.set_confirm_missing_outputs(confirm);
+		}
+		Ok(())
+	}
+
+	fn set_backup_before_migrate(&mut self, backup: bool) -> Result<(), Error> {
+		self.backup_before_migrate = backup;
+		Ok(())
+	}
+
+	fn set_seed_storage(&mut self, storage: SeedStorage) -> Result<(), Error> {
+		self.seed_storage = storage;
+		Ok(())
+	}
+
+	fn set_seed_kdf(&mut self, params: SeedKdfParams) -> Result<(), Error> {
+		self.seed_kdf = params;
+		Ok(())
+	}
+
+	fn set_read_only(&mut self, read_only: bool) -> Result<(), Error> {
+		self.read_only = read_only;
+		Ok(())
+	}
+
+	fn events(&self) -> EventBus {
+		self.events.clone()
+	}
+
+	fn migrate_wallet(
+		&mut self,
+		_name: Option<&str>,
+		dry_run: bool,
+	) -> Result<MigrationReport, Error> {
+		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
+		data_dir_name.push(EPIC_WALLET_DIR);
+		let data_dir_name = data_dir_name.to_str().unwrap();
+		if !LMDBBackend::<C, K>::exists(data_dir_name) {
+			let msg = format!("No wallet database found, Data Dir: {}", data_dir_name);
+			return Err(ErrorKind::Lifecycle(msg).into());
+		}
+		let options = MigrationOptions {
+			dry_run,
+			backup: self.backup_before_migrate,
+		};
+		migrate(data_dir_name, &options)
+	}
+
 	fn create_config(
 		&self,
 		chain_type: &global::ChainTypes,
@@ -176,15 +257,23 @@ where
 		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
 		data_dir_name.push(EPIC_WALLET_DIR);
 		let data_dir_name = data_dir_name.to_str().unwrap();
-		let exists = WalletSeed::seed_file_exists(&data_dir_name);
+		let seed_backend = self.seed_backend(data_dir_name)?;
+		let exists = WalletSeed::seed_exists(&*seed_backend);
 		if !test_mode {
 			if let Ok(true) = exists {
 				let msg = format!("Wallet seed already exists at: {}", data_dir_name);
 				return Err(ErrorKind::WalletSeedExists(msg))?;
 			}
 		}
-		let _ = WalletSeed::init_file(&data_dir_name, mnemonic_length, mnemonic.clone(), password);
-		info!("Wallet seed file created");
+		let _ = WalletSeed::init_seed(
+			&*seed_backend,
+			&data_dir_name,
+			mnemonic_length,
+			mnemonic.clone(),
+			password,
+			&self.seed_kdf,
+		);
+		info!("Wallet seed created");
 		let mut wallet: LMDBBackend<'a, C, K> =
 			match LMDBBackend::new(&data_dir_name, self.node_client.clone()) {
 				Err(e) => {
@@ -215,22 +304,45 @@ where
 		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
 		data_dir_name.push(EPIC_WALLET_DIR);
 		let data_dir_name = data_dir_name.to_str().unwrap();
-		let mut wallet: LMDBBackend<'a, C, K> =
-			match LMDBBackend::new(&data_dir_name, self.node_client.clone()) {
-				Err(e) => {
-					let msg = format!("Error opening wallet: {}, Data Dir: {}", e, &data_dir_name);
-					return Err(ErrorKind::Lifecycle(msg).into());
-				}
-				Ok(d) => d,
+		// bring the database up to date first, so it can be backed up beforehand.
+		// A replica leaves that to the wallet writing to the database
+		if !self.read_only && LMDBBackend::<C, K>::exists(data_dir_name) {
+			let options = MigrationOptions {
+				dry_run: false,
+				backup: self.backup_before_migrate,
 			};
-		let wallet_seed = WalletSeed::from_file(&data_dir_name, password).context(
-			ErrorKind::Lifecycle("Error opening wallet (is password correct?)".into()),
-		)?;
+			migrate(data_dir_name, &options)?;
+		}
+		let backend = match self.read_only {
+			true => LMDBBackend::new_read_only(&data_dir_name, self.node_client.clone()),
+			false => LMDBBackend::new(&data_dir_name, self.node_client.clone()),
+		};
+		let mut wallet: LMDBBackend<'a, C, K> = match backend {
+			Err(e) => {
+				let msg = format!("Error opening wallet: {}, Data Dir: {}", e, &data_dir_name);
+				return Err(ErrorKind::Lifecycle(msg).into());
+			}
+			Ok(d) => d,
+		};
+		let seed_backend = self.seed_backend(data_dir_name)?;
+		// a read-only wallet leaves the seed as it is
+		let wallet_seed = match self.read_only {
+			true => WalletSeed::from_backend(&*seed_backend, password),
+			false => WalletSeed::open(&*seed_backend, password, &self.seed_kdf),
+		}
+		.context(ErrorKind::Lifecycle(
+			"Error opening wallet (is password correct?)".into(),
+		))?;
 		let keychain = wallet_seed
 			.derive_keychain(global::is_floonet())
 			.context(ErrorKind::Lifecycle("Error deriving keychain".into()))?;
 
 		let mask = wallet.set_keychain(Box::new(keychain), create_mask, use_test_rng)?;
+		// outputs stored by older versions may lack a commit
+		wallet.backfill_output_commits(mask.as_ref())?;
+		wallet.set_coinbase_cleanup_window(self.coinbase_cleanup_window);
+		wallet.set_confirm_missing_outputs(self.confirm_missing_outputs);
+		wallet.set_events(self.events.clone());
 		self.backend = Some(Box::new(wallet));
 		Ok(mask)
 	}
@@ -248,7 +360,8 @@ where
 		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
 		data_dir_name.push(EPIC_WALLET_DIR);
 		let data_dir_name = data_dir_name.to_str().unwrap();
-		let res = WalletSeed::seed_file_exists(&data_dir_name).context(ErrorKind::CallbackImpl(
+		let seed_backend = self.seed_backend(data_dir_name)?;
+		let res = WalletSeed::seed_exists(&*seed_backend).context(ErrorKind::CallbackImpl(
 			"Error checking for wallet existence",
 		))?;
 		Ok(res)
@@ -262,13 +375,13 @@ where
 		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
 		data_dir_name.push(EPIC_WALLET_DIR);
 		let data_dir_name = data_dir_name.to_str().unwrap();
-		let wallet_seed = WalletSeed::from_file(&data_dir_name, password).context(
-			ErrorKind::Lifecycle("Error opening wallet seed file".into()),
-		)?;
+		let seed_backend = self.seed_backend(data_dir_name)?;
+		let wallet_seed = WalletSeed::from_backend(&*seed_backend, password)
+			.context(ErrorKind::Lifecycle("Error opening wallet seed".into()))?;
 		let res = wallet_seed
 			.to_mnemonic()
 			.context(ErrorKind::Lifecycle("Error recovering wallet seed".into()))?;
-		Ok(ZeroingString::from(res))
+		Ok(res)
 	}
 
 	fn validate_mnemonic(&self, mnemonic: ZeroingString) -> Result<(), Error> {
@@ -286,9 +399,17 @@ where
 		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
 		data_dir_name.push(EPIC_WALLET_DIR);
 		let data_dir_name = data_dir_name.to_str().unwrap();
-		WalletSeed::recover_from_phrase(data_dir_name, mnemonic, password).context(
-			ErrorKind::Lifecycle("Error recovering from mnemonic".into()),
-		)?;
+		let seed_backend = self.seed_backend(data_dir_name)?;
+		WalletSeed::recover_from_phrase(
+			&*seed_backend,
+			data_dir_name,
+			mnemonic,
+			password,
+			&self.seed_kdf,
+		)
+		.context(ErrorKind::Lifecycle(
+			"Error recovering from mnemonic".into(),
+		))?;
 		Ok(())
 	}
 
@@ -301,38 +422,39 @@ where
 		let mut data_dir_name = PathBuf::from(self.data_dir.clone());
 		data_dir_name.push(EPIC_WALLET_DIR);
 		let data_dir_name = data_dir_name.to_str().unwrap();
+		let seed_backend = self.seed_backend(data_dir_name)?;
 		// get seed for later check
 
-		let orig_wallet_seed = WalletSeed::from_file(&data_dir_name, old).context(
-			ErrorKind::Lifecycle("Error opening wallet seed file".into()),
-		)?;
+		let orig_wallet_seed = WalletSeed::from_backend(&*seed_backend, old)
+			.context(ErrorKind::Lifecycle("Error opening wallet seed".into()))?;
 		let orig_mnemonic = orig_wallet_seed
 			.to_mnemonic()
 			.context(ErrorKind::Lifecycle("Error recovering mnemonic".into()))?;
 
-		// Back up existing seed, and keep track of filename as we're deleting it
+		// Back up existing seed, and keep track of its name as we're deleting it
 		// once the password change is confirmed
-		let backup_name = WalletSeed::backup_seed(data_dir_name).context(ErrorKind::Lifecycle(
+		let backup_name = WalletSeed::backup_seed(&*seed_backend).context(ErrorKind::Lifecycle(
 			"Error temporarily backing up existing seed".into(),
 		))?;
 
-		// Delete seed file
-		WalletSeed::delete_seed_file(data_dir_name).context(ErrorKind::Lifecycle(
-			"Unable to delete seed file for password change".into(),
+		// Delete seed
+		WalletSeed::delete_seed(&*seed_backend).context(ErrorKind::Lifecycle(
+			"Unable to delete seed for password change".into(),
 		))?;
 
-		// Init a new file
-		let _ = WalletSeed::init_file(
+		// Init a new one
+		let _ = WalletSeed::init_seed(
+			&*seed_backend,
 			data_dir_name,
 			0,
-			Some(ZeroingString::from(orig_mnemonic)),
+			Some(orig_mnemonic),
 			new.clone(),
+			&self.seed_kdf,
 		);
-		info!("Wallet seed file created");
+		info!("Wallet seed created");
 
-		let new_wallet_seed = WalletSeed::from_file(&data_dir_name, new).context(
-			ErrorKind::Lifecycle("Error opening wallet seed file".into()),
-		)?;
+		let new_wallet_seed = WalletSeed::from_backend(&*seed_backend, new)
+			.context(ErrorKind::Lifecycle("Error opening wallet seed".into()))?;
 
 		if orig_wallet_seed != new_wallet_seed {
 			let msg = format!(
@@ -341,13 +463,21 @@ where
 			return Err(ErrorKind::Lifecycle(msg).into());
 		}
 		// Removin
-		info!("Password change confirmed, removing old seed file.");
-		fs::remove_file(backup_name).context(ErrorKind::IO)?;
+		info!("Password change confirmed, removing old seed.");
+		seed_backend
+			.remove_backup(&backup_name)
+			.context(ErrorKind::Lifecycle("Unable to remove seed backup".into()))?;
 
 		Ok(())
 	}
 
 	fn delete_wallet(&self, _name: Option<&str>) -> Result<(), Error> {
+		let mut seed_dir_name = PathBuf::from(self.data_dir.clone());
+		seed_dir_name.push(EPIC_WALLET_DIR);
+		// the seed may be stored outside of the data directory
+		let seed_backend = self.seed_backend(seed_dir_name.to_str().unwrap())?;
+		WalletSeed::delete_seed(&*seed_backend)
+			.context(ErrorKind::Lifecycle("Unable to delete wallet seed".into()))?;
 		let data_dir_name = PathBuf::from(self.data_dir.clone());
 		warn!(
 			"Removing all wallet data from: {}",
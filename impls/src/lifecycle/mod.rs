@@ -14,5 +14,11 @@
 
 mod default;
 mod seed;
+mod seed_backend;
 
 pub use self::default::DefaultLCProvider;
+pub use self::seed::EncryptedWalletSeed;
+pub use self::seed_backend::{
+	seed_backend, FileSeedBackend, SeedBackend, OS_KEYSTORE_SUPPORT, PKCS11_PIN_ENV,
+	PKCS11_SUPPORT, SEED_FILE,
+};
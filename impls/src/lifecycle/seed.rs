@@ -12,37 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::fs;
 use std::path::Path;
-use std::path::MAIN_SEPARATOR;
 
 use crate::blake2;
-use rand::{thread_rng, Rng};
-use serde_json;
-
 use ring::aead;
 use ring::{digest, pbkdf2};
 
+use crate::config::SeedKdfParams;
 use crate::keychain::{mnemonic, Keychain};
+use crate::lifecycle::seed_backend::SeedBackend;
 use crate::util;
 use crate::{Error, ErrorKind};
+use epic_wallet_util::{os_random, SecretBytes};
 use failure::ResultExt;
+use zeroize::Zeroize;
 
-pub const SEED_FILE: &'static str = "wallet.seed";
-
+/// The wallet seed, in memory locked out of swap and zeroed when dropped
 #[derive(Clone, Debug, PartialEq)]
-pub struct WalletSeed(Vec<u8>);
+pub struct WalletSeed(SecretBytes);
 
 impl WalletSeed {
 	pub fn from_bytes(bytes: &[u8]) -> WalletSeed {
-		WalletSeed(bytes.to_vec())
+		WalletSeed(SecretBytes::from_slice(bytes))
 	}
 
 	pub fn from_mnemonic(word_list: util::ZeroingString) -> Result<WalletSeed, Error> {
 		let res = mnemonic::to_entropy(&word_list);
 		match res {
-			Ok(s) => Ok(WalletSeed::from_bytes(&s)),
+			Ok(s) => Ok(WalletSeed(SecretBytes::from_vec(s))),
 			Err(_) => Err(ErrorKind::Mnemonic.into()),
 		}
 	}
@@ -57,10 +55,10 @@ impl WalletSeed {
 		util::to_hex(self.0.to_vec())
 	}
 
-	pub fn to_mnemonic(&self) -> Result<String, Error> {
+	pub fn to_mnemonic(&self) -> Result<util::ZeroingString, Error> {
 		let result = mnemonic::from_entropy(&self.0);
 		match result {
-			Ok(r) => Ok(r),
+			Ok(r) => Ok(util::ZeroingString::from(r)),
 			Err(_) => Err(ErrorKind::Mnemonic.into()),
 		}
 	}
@@ -75,58 +73,31 @@ impl WalletSeed {
 		Ok(result)
 	}
 
-	pub fn init_new(seed_length: usize) -> WalletSeed {
-		let mut seed: Vec<u8> = vec![];
-		let mut rng = thread_rng();
-		for _ in 0..seed_length {
-			seed.push(rng.gen());
-		}
-		WalletSeed(seed)
+	pub fn init_new(seed_length: usize) -> Result<WalletSeed, Error> {
+		let mut seed = SecretBytes::new(seed_length);
+		os_random(&mut seed[..])?;
+		Ok(WalletSeed(seed))
 	}
 
-	pub fn seed_file_exists(data_file_dir: &str) -> Result<bool, Error> {
-		let seed_file_path = &format!("{}{}{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE,);
-		debug!("Seed file path: {}", seed_file_path);
-		if Path::new(seed_file_path).exists() {
-			Ok(true)
-		} else {
-			Ok(false)
-		}
+	pub fn seed_exists(backend: &dyn SeedBackend) -> Result<bool, Error> {
+		backend.exists()
 	}
 
-	pub fn backup_seed(data_file_dir: &str) -> Result<String, Error> {
-		let seed_file_name = &format!("{}{}{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE,);
-
-		let mut path = Path::new(seed_file_name).to_path_buf();
-		path.pop();
-		let mut backup_seed_file_name =
-			format!("{}{}{}.bak", data_file_dir, MAIN_SEPARATOR, SEED_FILE);
-		let mut i = 1;
-		while Path::new(&backup_seed_file_name).exists() {
-			backup_seed_file_name =
-				format!("{}{}{}.bak.{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE, i);
-			i += 1;
-		}
-		path.push(backup_seed_file_name.clone());
-		if let Err(_) = fs::rename(seed_file_name, backup_seed_file_name.as_str()) {
-			return Err(ErrorKind::GenericError(
-				"Can't rename wallet seed file".to_owned(),
-			))?;
-		}
-		warn!("{} backed up as {}", seed_file_name, backup_seed_file_name);
-		Ok(backup_seed_file_name)
+	pub fn backup_seed(backend: &dyn SeedBackend) -> Result<String, Error> {
+		backend.backup()
 	}
 
 	pub fn recover_from_phrase(
+		backend: &dyn SeedBackend,
 		data_file_dir: &str,
 		word_list: util::ZeroingString,
 		password: util::ZeroingString,
+		kdf: &SeedKdfParams,
 	) -> Result<(), Error> {
-		let seed_file_path = &format!("{}{}{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE,);
 		debug!("data file dir: {}", data_file_dir);
-		if let Ok(true) = WalletSeed::seed_file_exists(data_file_dir) {
-			debug!("seed file exists");
-			WalletSeed::backup_seed(data_file_dir)?;
+		if let Ok(true) = WalletSeed::seed_exists(backend) {
+			debug!("seed exists");
+			WalletSeed::backup_seed(backend)?;
 		}
 		if !Path::new(&data_file_dir).exists() {
 			return Err(ErrorKind::WalletDoesntExist(
@@ -136,28 +107,25 @@ impl WalletSeed {
 			))?;
 		}
 		let seed = WalletSeed::from_mnemonic(word_list)?;
-		let enc_seed = EncryptedWalletSeed::from_seed(&seed, password)?;
-		let enc_seed_json = serde_json::to_string_pretty(&enc_seed).context(ErrorKind::Format)?;
-		let mut file = File::create(seed_file_path).context(ErrorKind::IO)?;
-		file.write_all(&enc_seed_json.as_bytes())
-			.context(ErrorKind::IO)?;
+		let enc_seed = EncryptedWalletSeed::from_seed(&seed, password, kdf)?;
+		backend.store(&enc_seed)?;
 		warn!("Seed created from word list");
 		Ok(())
 	}
 
-	pub fn init_file(
+	pub fn init_seed(
+		backend: &dyn SeedBackend,
 		data_file_dir: &str,
 		seed_length: usize,
 		recovery_phrase: Option<util::ZeroingString>,
 		password: util::ZeroingString,
+		kdf: &SeedKdfParams,
 	) -> Result<WalletSeed, Error> {
 		// create directory if it doesn't exist
 		fs::create_dir_all(data_file_dir).context(ErrorKind::IO)?;
 
-		let seed_file_path = &format!("{}{}{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE,);
-
-		warn!("Generating wallet seed file at: {}", seed_file_path);
-		let exists = WalletSeed::seed_file_exists(data_file_dir)?;
+		warn!("Generating wallet seed for: {}", data_file_dir);
+		let exists = WalletSeed::seed_exists(backend)?;
 		if exists {
 			let msg = format!("Wallet seed already exists at: {}", data_file_dir);
 			return Err(ErrorKind::WalletSeedExists(msg))?;
@@ -165,53 +133,133 @@ impl WalletSeed {
 
 		let seed = match recovery_phrase {
 			Some(p) => WalletSeed::from_mnemonic(p)?,
-			None => WalletSeed::init_new(seed_length),
+			None => WalletSeed::init_new(seed_length)?,
 		};
 
-		let enc_seed = EncryptedWalletSeed::from_seed(&seed, password)?;
-		let enc_seed_json = serde_json::to_string_pretty(&enc_seed).context(ErrorKind::Format)?;
-		let mut file = File::create(seed_file_path).context(ErrorKind::IO)?;
-		file.write_all(&enc_seed_json.as_bytes())
-			.context(ErrorKind::IO)?;
+		let enc_seed = EncryptedWalletSeed::from_seed(&seed, password, kdf)?;
+		backend.store(&enc_seed)?;
 		Ok(seed)
 	}
 
-	pub fn from_file(
-		data_file_dir: &str,
+	pub fn from_backend(
+		backend: &dyn SeedBackend,
 		password: util::ZeroingString,
 	) -> Result<WalletSeed, Error> {
-		// create directory if it doesn't exist
-		fs::create_dir_all(data_file_dir).context(ErrorKind::IO)?;
+		let enc_seed = backend.load()?;
+		let wallet_seed = enc_seed.decrypt(&password)?;
+		Ok(wallet_seed)
+	}
 
-		let seed_file_path = &format!("{}{}{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE,);
-
-		debug!("Using wallet seed file at: {}", seed_file_path);
-
-		if Path::new(seed_file_path).exists() {
-			let mut file = File::open(seed_file_path).context(ErrorKind::IO)?;
-			let mut buffer = String::new();
-			file.read_to_string(&mut buffer).context(ErrorKind::IO)?;
-			let enc_seed: EncryptedWalletSeed =
-				serde_json::from_str(&buffer).context(ErrorKind::Format)?;
-			let wallet_seed = enc_seed.decrypt(&password)?;
-			Ok(wallet_seed)
-		} else {
-			error!(
-				"wallet seed file {} could not be opened (epic wallet init). \
-				 Run \"epic wallet init\" to initialize a new wallet.",
-				seed_file_path
-			);
-			Err(ErrorKind::WalletSeedDoesntExist)?
+	/// Decrypt the stored seed, as [from_backend](#method.from_backend), and
+	/// if it was encrypted with another key derivation than the one given,
+	/// such as the PBKDF2 of older wallets, store it again encrypted with it
+	pub fn open(
+		backend: &dyn SeedBackend,
+		password: util::ZeroingString,
+		kdf: &SeedKdfParams,
+	) -> Result<WalletSeed, Error> {
+		let enc_seed = backend.load()?;
+		let wallet_seed = enc_seed.decrypt(&password)?;
+		if enc_seed.kdf != SeedKdf::from(kdf) {
+			// the wallet opens with the seed either way
+			match WalletSeed::reencrypt(backend, &wallet_seed, password, kdf) {
+				Ok(()) => info!("Wallet seed encrypted again with {:?}", kdf),
+				Err(e) => warn!("Unable to encrypt the wallet seed again: {}", e),
+			}
 		}
+		Ok(wallet_seed)
 	}
 
-	pub fn delete_seed_file(data_file_dir: &str) -> Result<(), Error> {
-		let seed_file_path = &format!("{}{}{}", data_file_dir, MAIN_SEPARATOR, SEED_FILE,);
-		if Path::new(seed_file_path).exists() {
-			debug!("Deleting wallet seed file at: {}", seed_file_path);
-			fs::remove_file(seed_file_path).context(ErrorKind::IO)?;
+	/// Store the seed again, encrypted with the given key derivation. The
+	/// stored seed is moved aside until the new one is known to decrypt to
+	/// the same seed, so it's never lost on the way
+	fn reencrypt(
+		backend: &dyn SeedBackend,
+		seed: &WalletSeed,
+		password: util::ZeroingString,
+		kdf: &SeedKdfParams,
+	) -> Result<(), Error> {
+		let enc_seed = EncryptedWalletSeed::from_seed(seed, password.clone(), kdf)?;
+		let backup_name = backend.backup()?;
+		let stored = backend
+			.store(&enc_seed)
+			.and_then(|_| WalletSeed::from_backend(backend, password));
+		match stored {
+			Ok(ref s) if s == seed => backend.remove_backup(&backup_name),
+			_ => Err(ErrorKind::GenericError(format!(
+				"Seed encrypted again doesn't match, previous one kept in {}",
+				backup_name
+			)))?,
+		}
+	}
+
+	pub fn delete_seed(backend: &dyn SeedBackend) -> Result<(), Error> {
+		backend.delete()
+	}
+}
+
+/// How the key a seed is encrypted with is derived from the wallet password
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum SeedKdf {
+	/// 100 rounds of PBKDF2-HMAC-SHA512, as used by older wallets, which
+	/// didn't record it. Only read
+	Pbkdf2,
+	/// Argon2id with the given costs
+	Argon2id {
+		/// Memory used, in KiB
+		memory_kib: u32,
+		/// Passes over the memory
+		iterations: u32,
+		/// Lanes computed in parallel
+		parallelism: u32,
+	},
+}
+
+impl SeedKdf {
+	// seeds stored before the derivation was recorded
+	fn legacy() -> SeedKdf {
+		SeedKdf::Pbkdf2
+	}
+
+	/// Derive the key from the password and salt
+	fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<SecretBytes, Error> {
+		let mut key = SecretBytes::new(32);
+		match *self {
+			SeedKdf::Pbkdf2 => pbkdf2::derive(&digest::SHA512, 100, salt, password, &mut key[..]),
+			SeedKdf::Argon2id {
+				memory_kib,
+				iterations,
+				parallelism,
+			} => {
+				let config = argon2::Config {
+					variant: argon2::Variant::Argon2id,
+					version: argon2::Version::Version13,
+					mem_cost: memory_kib,
+					time_cost: iterations,
+					lanes: parallelism,
+					thread_mode: argon2::ThreadMode::from_threads(parallelism),
+					secret: &[],
+					ad: &[],
+					hash_length: key.len() as u32,
+				};
+				let hash = argon2::hash_raw(password, salt, &config).map_err(|e| {
+					ErrorKind::GenericError(format!("Deriving the seed key: {}", e))
+				})?;
+				key = SecretBytes::from_vec(hash);
+			}
+		}
+		Ok(key)
+	}
+}
+
+impl From<&SeedKdfParams> for SeedKdf {
+	fn from(params: &SeedKdfParams) -> SeedKdf {
+		SeedKdf::Argon2id {
+			memory_kib: params.memory_kib,
+			iterations: params.iterations,
+			parallelism: params.parallelism,
 		}
-		Ok(())
 	}
 }
 
@@ -226,33 +274,43 @@ pub struct EncryptedWalletSeed {
 	pub salt: String,
 	/// Nonce
 	pub nonce: String,
+	/// How the key is derived from the password
+	#[serde(default = "SeedKdf::legacy")]
+	pub kdf: SeedKdf,
 }
 
 impl EncryptedWalletSeed {
-	/// Create a new encrypted seed from the given seed + password
+	/// Create a new encrypted seed from the given seed + password, deriving
+	/// the key with argon2id at the given costs
 	pub fn from_seed(
 		seed: &WalletSeed,
 		password: util::ZeroingString,
+		kdf: &SeedKdfParams,
 	) -> Result<EncryptedWalletSeed, Error> {
-		let salt: [u8; 8] = thread_rng().gen();
-		let nonce: [u8; 12] = thread_rng().gen();
-		let password = password.as_bytes();
-		let mut key = [0; 32];
-		pbkdf2::derive(&digest::SHA512, 100, &salt, password, &mut key);
-		let content = seed.0.to_vec();
-		let mut enc_bytes = content.clone();
+		let mut salt = [0u8; 16];
+		let mut nonce = [0u8; 12];
+		os_random(&mut salt)?;
+		os_random(&mut nonce)?;
+		let kdf = SeedKdf::from(kdf);
+		let key = kdf.derive_key(password.as_bytes(), &salt)?;
+		// sized up front, so growing it leaves no copy of the seed behind
 		let suffix_len = aead::CHACHA20_POLY1305.tag_len();
-		for _ in 0..suffix_len {
-			enc_bytes.push(0);
+		let mut enc_bytes = Vec::with_capacity(seed.0.len() + suffix_len);
+		enc_bytes.extend_from_slice(&seed.0);
+		enc_bytes.resize(seed.0.len() + suffix_len, 0);
+		let sealed =
+			aead::SealingKey::new(&aead::CHACHA20_POLY1305, &key).and_then(|sealing_key| {
+				aead::seal_in_place(&sealing_key, &nonce, &[], &mut enc_bytes, suffix_len)
+			});
+		if sealed.is_err() {
+			enc_bytes.zeroize();
 		}
-		let sealing_key =
-			aead::SealingKey::new(&aead::CHACHA20_POLY1305, &key).context(ErrorKind::Encryption)?;
-		aead::seal_in_place(&sealing_key, &nonce, &[], &mut enc_bytes, suffix_len)
-			.context(ErrorKind::Encryption)?;
+		sealed.context(ErrorKind::Encryption)?;
 		Ok(EncryptedWalletSeed {
 			encrypted_seed: util::to_hex(enc_bytes.to_vec()),
 			salt: util::to_hex(salt.to_vec()),
 			nonce: util::to_hex(nonce.to_vec()),
+			kdf,
 		})
 	}
 
@@ -270,29 +328,40 @@ impl EncryptedWalletSeed {
 			Ok(s) => s,
 			Err(_) => return Err(ErrorKind::Encryption)?,
 		};
-		let password = password.as_bytes();
-		let mut key = [0; 32];
-		pbkdf2::derive(&digest::SHA512, 100, &salt, password, &mut key);
+		let key = self.kdf.derive_key(password.as_bytes(), &salt)?;
 
 		let opening_key =
 			aead::OpeningKey::new(&aead::CHACHA20_POLY1305, &key).context(ErrorKind::Encryption)?;
-		let decrypted_data = aead::open_in_place(&opening_key, &nonce, &[], 0, &mut encrypted_seed)
-			.context(ErrorKind::Encryption)?;
-
-		Ok(WalletSeed::from_bytes(&decrypted_data))
+		// decrypted in place, so the buffer holds the seed until zeroed
+		let seed = aead::open_in_place(&opening_key, &nonce, &[], 0, &mut encrypted_seed)
+			.map(|decrypted_data| WalletSeed::from_bytes(decrypted_data));
+		encrypted_seed.zeroize();
+		Ok(seed.context(ErrorKind::Encryption)?)
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::lifecycle::seed_backend::FileSeedBackend;
 	use crate::util::ZeroingString;
+	use rand::{thread_rng, Rng};
+
+	// cheap costs, to keep the tests quick
+	fn test_kdf() -> SeedKdfParams {
+		SeedKdfParams {
+			memory_kib: 64,
+			iterations: 1,
+			parallelism: 1,
+		}
+	}
+
 	#[test]
 	fn wallet_seed_encrypt() {
 		let password = ZeroingString::from("passwoid");
-		let wallet_seed = WalletSeed::init_new(32);
+		let wallet_seed = WalletSeed::init_new(32).unwrap();
 		let mut enc_wallet_seed =
-			EncryptedWalletSeed::from_seed(&wallet_seed, password.clone()).unwrap();
+			EncryptedWalletSeed::from_seed(&wallet_seed, password.clone(), &test_kdf()).unwrap();
 		println!("EWS: {:?}", enc_wallet_seed);
 		let decrypted_wallet_seed = enc_wallet_seed.decrypt(&password).unwrap();
 		assert_eq!(wallet_seed, decrypted_wallet_seed);
@@ -306,4 +375,59 @@ mod tests {
 		let decrypted_wallet_seed = enc_wallet_seed.decrypt(&password);
 		assert!(decrypted_wallet_seed.is_err());
 	}
+
+	#[test]
+	fn wallet_seed_upgrade_kdf() {
+		let dir = "test_output/wallet_seed_upgrade_kdf";
+		let _ = fs::remove_dir_all(dir);
+		fs::create_dir_all(dir).unwrap();
+		let backend = FileSeedBackend::new(dir);
+		let password = ZeroingString::from("passwoid");
+
+		// as stored by older wallets, without the derivation
+		let wallet_seed = WalletSeed::init_new(32).unwrap();
+		let salt: [u8; 8] = thread_rng().gen();
+		let nonce: [u8; 12] = thread_rng().gen();
+		let key = SeedKdf::Pbkdf2
+			.derive_key(password.as_bytes(), &salt)
+			.unwrap();
+		let mut content = wallet_seed.0.to_vec();
+		content.extend_from_slice(&[0; 16]);
+		let sealing_key = aead::SealingKey::new(&aead::CHACHA20_POLY1305, &key).unwrap();
+		let out_len = aead::seal_in_place(&sealing_key, &nonce, &[], &mut content, 16).unwrap();
+		let legacy = format!(
+			"{{\"encrypted_seed\": \"{}\", \"salt\": \"{}\", \"nonce\": \"{}\"}}",
+			util::to_hex(content[..out_len].to_vec()),
+			util::to_hex(salt.to_vec()),
+			util::to_hex(nonce.to_vec())
+		);
+		let enc_wallet_seed: EncryptedWalletSeed = serde_json::from_str(&legacy).unwrap();
+		assert_eq!(enc_wallet_seed.kdf, SeedKdf::Pbkdf2);
+		backend.store(&enc_wallet_seed).unwrap();
+
+		// read as it is, then encrypted again with argon2id on opening
+		assert_eq!(
+			WalletSeed::from_backend(&backend, password.clone()).unwrap(),
+			wallet_seed
+		);
+		let opened = WalletSeed::open(&backend, password.clone(), &test_kdf()).unwrap();
+		assert_eq!(opened, wallet_seed);
+		let stored = backend.load().unwrap();
+		assert_eq!(stored.kdf, SeedKdf::from(&test_kdf()));
+		assert_eq!(util::from_hex(stored.salt.clone()).unwrap().len(), 16);
+		assert_eq!(stored.decrypt(&password).unwrap(), wallet_seed);
+		assert!(stored.decrypt("").is_err());
+
+		// the backup is gone, and costs that don't change leave the seed be
+		assert_eq!(fs::read_dir(dir).unwrap().count(), 1);
+		WalletSeed::open(&backend, password.clone(), &test_kdf()).unwrap();
+		assert_eq!(backend.load().unwrap(), stored);
+
+		// nor does a wrong password touch it
+		assert!(
+			WalletSeed::open(&backend, ZeroingString::from(""), &SeedKdfParams::default()).is_err()
+		);
+		assert_eq!(backend.load().unwrap(), stored);
+		let _ = fs::remove_dir_all(dir);
+	}
 }
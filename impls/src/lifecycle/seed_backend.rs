@@ -0,0 +1,487 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage of the password-encrypted wallet seed, in a file, the OS keystore
+//! or on a PKCS#11 token
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::path::MAIN_SEPARATOR;
+
+use crate::config::SeedStorage;
+use crate::lifecycle::seed::EncryptedWalletSeed;
+use crate::{Error, ErrorKind};
+use failure::ResultExt;
+
+/// Name of the file holding the encrypted seed, in the wallet data directory
+pub const SEED_FILE: &'static str = "wallet.seed";
+
+/// Name the seed is stored under in the OS keystore and on PKCS#11 tokens
+#[cfg_attr(not(any(feature = "keyring", feature = "pkcs11")), allow(dead_code))]
+const SEED_SERVICE: &'static str = "epic-wallet-seed";
+
+/// Environment variable holding the PKCS#11 token PIN
+pub const PKCS11_PIN_ENV: &'static str = "EPIC_WALLET_PKCS11_PIN";
+
+/// Whether seeds can be kept in the OS keystore, i.e. the wallet was built
+/// with the keyring feature
+pub const OS_KEYSTORE_SUPPORT: bool = cfg!(feature = "keyring");
+
+/// Whether seeds can be kept on a PKCS#11 token, i.e. the wallet was built
+/// with the pkcs11 feature
+pub const PKCS11_SUPPORT: bool = cfg!(feature = "pkcs11");
+
+/// Somewhere to keep the encrypted seed of the wallet in one data directory
+pub trait SeedBackend {
+	/// Whether a seed is stored
+	fn exists(&self) -> Result<bool, Error>;
+
+	/// Read the stored seed
+	fn load(&self) -> Result<EncryptedWalletSeed, Error>;
+
+	/// Store the seed, replacing any stored before
+	fn store(&self, seed: &EncryptedWalletSeed) -> Result<(), Error>;
+
+	/// Move the stored seed aside, returning a name for the backup
+	fn backup(&self) -> Result<String, Error>;
+
+	/// Remove a backup made by [backup](#tymethod.backup)
+	fn remove_backup(&self, name: &str) -> Result<(), Error>;
+
+	/// Remove the stored seed, if any
+	fn delete(&self) -> Result<(), Error>;
+}
+
+/// The seed backend for the configured storage and data directory
+pub fn seed_backend(
+	storage: &SeedStorage,
+	data_file_dir: &str,
+) -> Result<Box<dyn SeedBackend>, Error> {
+	match storage {
+		SeedStorage::File => Ok(Box::new(FileSeedBackend::new(data_file_dir))),
+		SeedStorage::OsKeystore => keystore_backend(data_file_dir),
+		SeedStorage::Pkcs11 { module, slot } => pkcs11_backend(module, *slot, data_file_dir),
+	}
+}
+
+fn from_json(json: &str) -> Result<EncryptedWalletSeed, Error> {
+	Ok(serde_json::from_str(json).context(ErrorKind::Format)?)
+}
+
+fn to_json(seed: &EncryptedWalletSeed) -> Result<String, Error> {
+	Ok(serde_json::to_string_pretty(seed).context(ErrorKind::Format)?)
+}
+
+/// The `wallet.seed` file
+pub struct FileSeedBackend {
+	data_file_dir: String,
+}
+
+impl FileSeedBackend {
+	pub fn new(data_file_dir: &str) -> Self {
+		FileSeedBackend {
+			data_file_dir: data_file_dir.to_owned(),
+		}
+	}
+
+	fn path(&self) -> String {
+		format!("{}{}{}", self.data_file_dir, MAIN_SEPARATOR, SEED_FILE)
+	}
+}
+
+impl SeedBackend for FileSeedBackend {
+	fn exists(&self) -> Result<bool, Error> {
+		let seed_file_path = self.path();
+		debug!("Seed file path: {}", seed_file_path);
+		Ok(Path::new(&seed_file_path).exists())
+	}
+
+	fn load(&self) -> Result<EncryptedWalletSeed, Error> {
+		let seed_file_path = self.path();
+		debug!("Using wallet seed file at: {}", seed_file_path);
+		if !Path::new(&seed_file_path).exists() {
+			error!(
+				"wallet seed file {} could not be opened (epic wallet init). \
+				 Run \"epic wallet init\" to initialize a new wallet.",
+				seed_file_path
+			);
+			return Err(ErrorKind::WalletSeedDoesntExist)?;
+		}
+		let mut file = File::open(seed_file_path).context(ErrorKind::IO)?;
+		let mut buffer = String::new();
+		file.read_to_string(&mut buffer).context(ErrorKind::IO)?;
+		from_json(&buffer)
+	}
+
+	fn store(&self, seed: &EncryptedWalletSeed) -> Result<(), Error> {
+		let enc_seed_json = to_json(seed)?;
+		let mut file = File::create(self.path()).context(ErrorKind::IO)?;
+		file.write_all(&enc_seed_json.as_bytes())
+			.context(ErrorKind::IO)?;
+		Ok(())
+	}
+
+	fn backup(&self) -> Result<String, Error> {
+		let seed_file_name = self.path();
+		let mut backup_seed_file_name = format!("{}.bak", seed_file_name);
+		let mut i = 1;
+		while Path::new(&backup_seed_file_name).exists() {
+			backup_seed_file_name = format!("{}.bak.{}", seed_file_name, i);
+			i += 1;
+		}
+		if let Err(_) = fs::rename(&seed_file_name, backup_seed_file_name.as_str()) {
+			return Err(ErrorKind::GenericError(
+				"Can't rename wallet seed file".to_owned(),
+			))?;
+		}
+		warn!("{} backed up as {}", seed_file_name, backup_seed_file_name);
+		Ok(backup_seed_file_name)
+	}
+
+	fn remove_backup(&self, name: &str) -> Result<(), Error> {
+		fs::remove_file(name).context(ErrorKind::IO)?;
+		Ok(())
+	}
+
+	fn delete(&self) -> Result<(), Error> {
+		let seed_file_path = self.path();
+		if Path::new(&seed_file_path).exists() {
+			debug!("Deleting wallet seed file at: {}", seed_file_path);
+			fs::remove_file(seed_file_path).context(ErrorKind::IO)?;
+		}
+		Ok(())
+	}
+}
+
+/// An entry in the OS keystore, named after the data directory
+#[cfg(feature = "keyring")]
+pub struct KeystoreSeedBackend {
+	user: String,
+}
+
+#[cfg(feature = "keyring")]
+impl KeystoreSeedBackend {
+	pub fn new(data_file_dir: &str) -> Self {
+		KeystoreSeedBackend {
+			user: data_file_dir.to_owned(),
+		}
+	}
+
+	fn get(user: &str) -> Result<Option<String>, Error> {
+		match keyring::Keyring::new(SEED_SERVICE, user).get_password() {
+			Ok(s) => Ok(Some(s)),
+			Err(keyring::KeyringError::NoPasswordFound) => Ok(None),
+			Err(e) => Err(ErrorKind::SeedStorage(format!("OS keystore: {}", e)))?,
+		}
+	}
+
+	fn set(user: &str, value: &str) -> Result<(), Error> {
+		keyring::Keyring::new(SEED_SERVICE, user)
+			.set_password(value)
+			.map_err(|e| ErrorKind::SeedStorage(format!("OS keystore: {}", e)))?;
+		Ok(())
+	}
+
+	fn remove(user: &str) -> Result<(), Error> {
+		match keyring::Keyring::new(SEED_SERVICE, user).delete_password() {
+			Ok(_) | Err(keyring::KeyringError::NoPasswordFound) => Ok(()),
+			Err(e) => Err(ErrorKind::SeedStorage(format!("OS keystore: {}", e)))?,
+		}
+	}
+}
+
+#[cfg(feature = "keyring")]
+impl SeedBackend for KeystoreSeedBackend {
+	fn exists(&self) -> Result<bool, Error> {
+		Ok(Self::get(&self.user)?.is_some())
+	}
+
+	fn load(&self) -> Result<EncryptedWalletSeed, Error> {
+		match Self::get(&self.user)? {
+			Some(s) => from_json(&s),
+			None => Err(ErrorKind::WalletSeedDoesntExist)?,
+		}
+	}
+
+	fn store(&self, seed: &EncryptedWalletSeed) -> Result<(), Error> {
+		Self::set(&self.user, &to_json(seed)?)
+	}
+
+	fn backup(&self) -> Result<String, Error> {
+		let backup_user = format!("{}.bak", self.user);
+		match Self::get(&self.user)? {
+			Some(s) => Self::set(&backup_user, &s)?,
+			None => return Err(ErrorKind::WalletSeedDoesntExist)?,
+		}
+		Self::remove(&self.user)?;
+		warn!(
+			"Wallet seed in the OS keystore backed up as {}",
+			backup_user
+		);
+		Ok(backup_user)
+	}
+
+	fn remove_backup(&self, name: &str) -> Result<(), Error> {
+		Self::remove(name)
+	}
+
+	fn delete(&self) -> Result<(), Error> {
+		Self::remove(&self.user)
+	}
+}
+
+#[cfg(feature = "keyring")]
+fn keystore_backend(data_file_dir: &str) -> Result<Box<dyn SeedBackend>, Error> {
+	Ok(Box::new(KeystoreSeedBackend::new(data_file_dir)))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keystore_backend(_data_file_dir: &str) -> Result<Box<dyn SeedBackend>, Error> {
+	Err(ErrorKind::SeedStorage(
+		"This wallet was built without OS keystore support (the os_keystore feature)".to_owned(),
+	))?
+}
+
+/// A private data object on a PKCS#11 token, labelled after the data
+/// directory. The token is logged into with the PIN in `EPIC_WALLET_PKCS11_PIN`,
+/// if set
+#[cfg(feature = "pkcs11")]
+pub struct Pkcs11SeedBackend {
+	ctx: pkcs11::Ctx,
+	session: pkcs11::types::CK_SESSION_HANDLE,
+	label: String,
+}
+
+#[cfg(feature = "pkcs11")]
+fn pkcs11_error(e: pkcs11::errors::Error) -> Error {
+	ErrorKind::SeedStorage(format!("PKCS#11: {}", e)).into()
+}
+
+#[cfg(feature = "pkcs11")]
+impl Pkcs11SeedBackend {
+	pub fn new(module: &str, slot: Option<u64>, data_file_dir: &str) -> Result<Self, Error> {
+		use pkcs11::types::{CKF_RW_SESSION, CKF_SERIAL_SESSION, CKU_USER, CK_SLOT_ID};
+		let ctx = pkcs11::Ctx::new_and_initialize(module).map_err(pkcs11_error)?;
+		let slot = match slot {
+			Some(s) => s as CK_SLOT_ID,
+			None => match ctx.get_slot_list(true).map_err(pkcs11_error)?.first() {
+				Some(s) => *s,
+				None => {
+					return Err(ErrorKind::SeedStorage(format!(
+						"No PKCS#11 token present for module {}",
+						module
+					)))?
+				}
+			},
+		};
+		let session = ctx
+			.open_session(slot, CKF_SERIAL_SESSION | CKF_RW_SESSION, None, None)
+			.map_err(pkcs11_error)?;
+		if let Ok(pin) = std::env::var(PKCS11_PIN_ENV) {
+			ctx.login(session, CKU_USER, Some(&pin))
+				.map_err(pkcs11_error)?;
+		}
+		Ok(Pkcs11SeedBackend {
+			ctx,
+			session,
+			label: format!("{}:{}", SEED_SERVICE, data_file_dir),
+		})
+	}
+
+	fn find(&self, label: &str) -> Result<Option<pkcs11::types::CK_OBJECT_HANDLE>, Error> {
+		use pkcs11::types::{CKA_CLASS, CKA_LABEL, CKO_DATA, CK_ATTRIBUTE};
+		let template = vec![
+			CK_ATTRIBUTE::new(CKA_CLASS).with_ck_ulong(&CKO_DATA),
+			CK_ATTRIBUTE::new(CKA_LABEL).with_string(label),
+		];
+		self.ctx
+			.find_objects_init(self.session, &template)
+			.map_err(pkcs11_error)?;
+		let found = self.ctx.find_objects(self.session, 1).map_err(pkcs11_error);
+		self.ctx
+			.find_objects_final(self.session)
+			.map_err(pkcs11_error)?;
+		Ok(found?.first().cloned())
+	}
+
+	fn get(&self, label: &str) -> Result<Option<String>, Error> {
+		use pkcs11::types::{CKA_VALUE, CK_ATTRIBUTE};
+		let object = match self.find(label)? {
+			Some(o) => o,
+			None => return Ok(None),
+		};
+		// ask for the length first, then for the value
+		let mut template = vec![CK_ATTRIBUTE::new(CKA_VALUE)];
+		let (_, attrs) = self
+			.ctx
+			.get_attribute_value(self.session, object, &mut template)
+			.map_err(pkcs11_error)?;
+		let value = vec![0u8; attrs[0].ulValueLen as usize];
+		let mut template = vec![CK_ATTRIBUTE::new(CKA_VALUE).with_bytes(&value)];
+		self.ctx
+			.get_attribute_value(self.session, object, &mut template)
+			.map_err(pkcs11_error)?;
+		let value = String::from_utf8(value)
+			.map_err(|_| ErrorKind::SeedStorage("PKCS#11: invalid seed object".to_owned()))?;
+		Ok(Some(value))
+	}
+
+	fn set(&self, label: &str, value: &str) -> Result<(), Error> {
+		use pkcs11::types::{
+			CKA_APPLICATION, CKA_CLASS, CKA_LABEL, CKA_PRIVATE, CKA_TOKEN, CKA_VALUE, CKO_DATA,
+			CK_ATTRIBUTE, CK_TRUE,
+		};
+		self.remove(label)?;
+		let template = vec![
+			CK_ATTRIBUTE::new(CKA_CLASS).with_ck_ulong(&CKO_DATA),
+			CK_ATTRIBUTE::new(CKA_TOKEN).with_bool(&CK_TRUE),
+			CK_ATTRIBUTE::new(CKA_PRIVATE).with_bool(&CK_TRUE),
+			CK_ATTRIBUTE::new(CKA_APPLICATION).with_string(SEED_SERVICE),
+			CK_ATTRIBUTE::new(CKA_LABEL).with_string(label),
+			CK_ATTRIBUTE::new(CKA_VALUE).with_bytes(value.as_bytes()),
+		];
+		self.ctx
+			.create_object(self.session, &template)
+			.map_err(pkcs11_error)?;
+		Ok(())
+	}
+
+	fn remove(&self, label: &str) -> Result<(), Error> {
+		if let Some(object) = self.find(label)? {
+			self.ctx
+				.destroy_object(self.session, object)
+				.map_err(pkcs11_error)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(feature = "pkcs11")]
+impl Drop for Pkcs11SeedBackend {
+	fn drop(&mut self) {
+		let _ = self.ctx.close_session(self.session);
+	}
+}
+
+#[cfg(feature = "pkcs11")]
+impl SeedBackend for Pkcs11SeedBackend {
+	fn exists(&self) -> Result<bool, Error> {
+		Ok(self.find(&self.label)?.is_some())
+	}
+
+	fn load(&self) -> Result<EncryptedWalletSeed, Error> {
+		match self.get(&self.label)? {
+			Some(s) => from_json(&s),
+			None => Err(ErrorKind::WalletSeedDoesntExist)?,
+		}
+	}
+
+	fn store(&self, seed: &EncryptedWalletSeed) -> Result<(), Error> {
+		self.set(&self.label, &to_json(seed)?)
+	}
+
+	fn backup(&self) -> Result<String, Error> {
+		let backup_label = format!("{}.bak", self.label);
+		match self.get(&self.label)? {
+			Some(s) => self.set(&backup_label, &s)?,
+			None => return Err(ErrorKind::WalletSeedDoesntExist)?,
+		}
+		self.remove(&self.label)?;
+		warn!(
+			"Wallet seed on the PKCS#11 token backed up as {}",
+			backup_label
+		);
+		Ok(backup_label)
+	}
+
+	fn remove_backup(&self, name: &str) -> Result<(), Error> {
+		self.remove(name)
+	}
+
+	fn delete(&self) -> Result<(), Error> {
+		self.remove(&self.label)
+	}
+}
+
+#[cfg(feature = "pkcs11")]
+fn pkcs11_backend(
+	module: &str,
+	slot: Option<u64>,
+	data_file_dir: &str,
+) -> Result<Box<dyn SeedBackend>, Error> {
+	Ok(Box::new(Pkcs11SeedBackend::new(
+		module,
+		slot,
+		data_file_dir,
+	)?))
+}
+
+#[cfg(not(feature = "pkcs11"))]
+fn pkcs11_backend(
+	_module: &str,
+	_slot: Option<u64>,
+	_data_file_dir: &str,
+) -> Result<Box<dyn SeedBackend>, Error> {
+	Err(ErrorKind::SeedStorage(
+		"This wallet was built without PKCS#11 support (the pkcs11 feature)".to_owned(),
+	))?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::SeedKdfParams;
+	use crate::lifecycle::seed::WalletSeed;
+	use crate::util::ZeroingString;
+
+	#[test]
+	fn file_seed_backend() {
+		let dir = "test_output/file_seed_backend";
+		let _ = fs::remove_dir_all(dir);
+		fs::create_dir_all(dir).unwrap();
+		let backend = seed_backend(&SeedStorage::File, dir).unwrap();
+		assert!(!backend.exists().unwrap());
+		assert!(backend.load().is_err());
+
+		let password = ZeroingString::from("passwoid");
+		let kdf = SeedKdfParams {
+			memory_kib: 64,
+			iterations: 1,
+			parallelism: 1,
+		};
+		let seed = WalletSeed::init_seed(&*backend, dir, 32, None, password.clone(), &kdf).unwrap();
+		assert!(backend.exists().unwrap());
+		assert!(Path::new(&format!("{}{}{}", dir, MAIN_SEPARATOR, SEED_FILE)).exists());
+		assert_eq!(
+			WalletSeed::from_backend(&*backend, password.clone()).unwrap(),
+			seed
+		);
+		// never overwritten on init
+		assert!(WalletSeed::init_seed(&*backend, dir, 32, None, password.clone(), &kdf).is_err());
+
+		let backup = backend.backup().unwrap();
+		assert!(!backend.exists().unwrap());
+		assert!(Path::new(&backup).exists());
+		backend.remove_backup(&backup).unwrap();
+		assert!(!Path::new(&backup).exists());
+
+		backend
+			.store(&EncryptedWalletSeed::from_seed(&seed, password.clone(), &kdf).unwrap())
+			.unwrap();
+		backend.delete().unwrap();
+		assert!(!backend.exists().unwrap());
+		let _ = fs::remove_dir_all(dir);
+	}
+}
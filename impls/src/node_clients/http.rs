@@ -18,14 +18,18 @@
 use futures::{stream, Stream};
 
 use crate::api::{self, LocatedTxKernel};
+use crate::core::core::feijoada::PoWType;
 use crate::core::core::TxKernel;
 use crate::libwallet::{NodeClient, NodeVersionInfo, TxWrapper};
+use chrono::{DateTime, Utc};
 use semver::Version;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use tokio::runtime::Runtime;
 
 use crate::client_utils::Client;
 use crate::libwallet;
+use crate::node_clients::retry::{NodeRetry, NodeRetryPolicy};
 use crate::util::secp::pedersen;
 use crate::util::{self, to_hex};
 
@@ -34,6 +38,7 @@ pub struct HTTPNodeClient {
 	node_url: String,
 	node_api_secret: Option<String>,
 	node_version_info: Option<NodeVersionInfo>,
+	retry: NodeRetry,
 }
 
 impl HTTPNodeClient {
@@ -43,13 +48,25 @@ impl HTTPNodeClient {
 			node_url: node_url.to_owned(),
 			node_api_secret: node_api_secret,
 			node_version_info: None,
+			retry: NodeRetry::default(),
 		}
 	}
 
+	/// Retry calls to the node according to the given policy, instead of
+	/// the default one
+	pub fn set_retry_policy(&mut self, policy: NodeRetryPolicy) {
+		self.retry = NodeRetry::new(policy);
+	}
+
 	/// Allow returning the chain height without needing a wallet instantiated
 	pub fn chain_height(&self) -> Result<(u64, String), libwallet::Error> {
 		self.get_chain_tip()
 	}
+
+	/// An HTTP client giving up on the node as the retry policy says
+	fn client(&self) -> Client {
+		Client::with_timeout(self.retry.policy().request_timeout)
+	}
 }
 
 impl NodeClient for HTTPNodeClient {
@@ -73,7 +90,7 @@ impl NodeClient for HTTPNodeClient {
 			return Some(v.clone());
 		}
 		let url = format!("{}/v1/version", self.node_url());
-		let client = Client::new();
+		let client = self.client();
 		let mut retval = match client.get::<NodeVersionInfo>(url.as_str(), self.node_api_secret()) {
 			Ok(n) => n,
 			Err(e) => {
@@ -106,8 +123,10 @@ impl NodeClient for HTTPNodeClient {
 		} else {
 			url = format!("{}/v1/pool/push_tx", dest);
 		}
-		let client = Client::new();
-		let res = client.post_no_ret(url.as_str(), self.node_api_secret(), tx);
+		let client = self.client();
+		let res = self.retry.call("Post TX", || {
+			client.post_no_ret(url.as_str(), self.node_api_secret(), tx)
+		});
 		if let Err(e) = res {
 			let report = format!("Posting transaction to node: {}", e);
 			error!("Post TX Error: {}", e);
@@ -120,8 +139,10 @@ impl NodeClient for HTTPNodeClient {
 	fn get_chain_tip(&self) -> Result<(u64, String), libwallet::Error> {
 		let addr = self.node_url();
 		let url = format!("{}/v1/chain", addr);
-		let client = Client::new();
-		let res = client.get::<api::Tip>(url.as_str(), self.node_api_secret());
+		let client = self.client();
+		let res = self.retry.call("Get chain height", || {
+			client.get::<api::Tip>(url.as_str(), self.node_api_secret())
+		});
 		match res {
 			Err(e) => {
 				let report = format!("Getting chain height from node: {}", e);
@@ -132,6 +153,86 @@ impl NodeClient for HTTPNodeClient {
 		}
 	}
 
+	/// Return the sync status of the node
+	fn get_sync_status(&self) -> Result<String, libwallet::Error> {
+		let url = format!("{}/v1/status", self.node_url());
+		let client = self.client();
+		let res = self.retry.call("Get sync status", || {
+			client.get::<serde_json::Value>(url.as_str(), self.node_api_secret())
+		});
+		let status = match res {
+			Err(e) => {
+				let report = format!("Getting sync status from node: {}", e);
+				error!("Get sync status error: {}", e);
+				return Err(libwallet::ErrorKind::ClientCallback(report).into());
+			}
+			Ok(s) => s,
+		};
+		match status["sync_status"].as_str() {
+			Some(s) => Ok(s.to_owned()),
+			None => Err(libwallet::ErrorKind::ClientCallback(
+				"Node didn't report a sync status".to_owned(),
+			)
+			.into()),
+		}
+	}
+
+	/// Return the hash of the block header at the given height
+	fn get_header_hash(&self, height: u64) -> Result<String, libwallet::Error> {
+		let addr = self.node_url();
+		let url = format!("{}/v1/headers/{}", addr, height);
+		let client = self.client();
+		let res = client.get::<api::BlockHeaderPrintable>(url.as_str(), self.node_api_secret());
+		match res {
+			Err(e) => {
+				let report = format!("Getting header at height {} from node: {}", height, e);
+				error!("Get header hash error: {}", e);
+				Err(libwallet::ErrorKind::ClientCallback(report).into())
+			}
+			Ok(r) => Ok(r.hash),
+		}
+	}
+
+	/// Return the proof of work algorithm of the block header at the given height
+	fn get_header_pow_type(&self, height: u64) -> Result<PoWType, libwallet::Error> {
+		let addr = self.node_url();
+		let url = format!("{}/v1/headers/{}", addr, height);
+		let client = self.client();
+		let res = client.get::<serde_json::Value>(url.as_str(), self.node_api_secret());
+		let header = match res {
+			Err(e) => {
+				let report = format!("Getting header at height {} from node: {}", height, e);
+				error!("Get header PoW type error: {}", e);
+				return Err(libwallet::ErrorKind::ClientCallback(report).into());
+			}
+			Ok(h) => h,
+		};
+		serde_json::from_value::<PoWType>(header["pow_type"].clone()).map_err(|e| {
+			let report = format!("Node didn't report a PoW type at height {}: {}", height, e);
+			libwallet::ErrorKind::ClientCallback(report).into()
+		})
+	}
+
+	/// Return the timestamp of the block header at the given height
+	fn get_header_timestamp(&self, height: u64) -> Result<DateTime<Utc>, libwallet::Error> {
+		let addr = self.node_url();
+		let url = format!("{}/v1/headers/{}", addr, height);
+		let client = self.client();
+		let res = client.get::<serde_json::Value>(url.as_str(), self.node_api_secret());
+		let header = match res {
+			Err(e) => {
+				let report = format!("Getting header at height {} from node: {}", height, e);
+				error!("Get header timestamp error: {}", e);
+				return Err(libwallet::ErrorKind::ClientCallback(report).into());
+			}
+			Ok(h) => h,
+		};
+		serde_json::from_value::<DateTime<Utc>>(header["timestamp"].clone()).map_err(|e| {
+			let report = format!("Node didn't report a timestamp at height {}: {}", height, e);
+			libwallet::ErrorKind::ClientCallback(report).into()
+		})
+	}
+
 	/// Get kernel implementation
 	fn get_kernel(
 		&mut self,
@@ -173,7 +274,7 @@ impl NodeClient for HTTPNodeClient {
 			to_hex(excess.0.to_vec()),
 			query
 		);
-		let client = Client::new();
+		let client = self.client();
 		let res: Option<LocatedTxKernel> = client
 			.get(url.as_str(), self.node_api_secret())
 			.map_err(|e| libwallet::ErrorKind::ClientCallback(format!("Kernel lookup: {}", e)))?;
@@ -181,6 +282,35 @@ impl NodeClient for HTTPNodeClient {
 		Ok(res.map(|k| (k.tx_kernel, k.height, k.mmr_index)))
 	}
 
+	/// Look for the kernel among the unconfirmed transactions the node holds
+	fn kernel_in_pool(&self, excess: &pedersen::Commitment) -> Result<bool, libwallet::Error> {
+		let url = format!("{}/v2/foreign", self.node_url());
+		let req = json!({
+			"jsonrpc": "2.0",
+			"method": "get_unconfirmed_transactions",
+			"id": 1,
+			"params": []
+		});
+		let client = self.client();
+		let res: Value = client
+			.post(url.as_str(), self.node_api_secret(), &req)
+			.map_err(|e| libwallet::ErrorKind::ClientCallback(format!("Pool lookup: {}", e)))?;
+		let entries = match res["result"]["Ok"].as_array() {
+			Some(entries) => entries.clone(),
+			None => {
+				let report = format!("Pool lookup: node answered {}", res["error"]);
+				return Err(libwallet::ErrorKind::ClientCallback(report).into());
+			}
+		};
+		let excess = json!(to_hex(excess.0.to_vec()));
+		Ok(entries.iter().any(|entry| {
+			entry["tx"]["body"]["kernels"]
+				.as_array()
+				.map(|kernels| kernels.iter().any(|k| k["excess"] == excess))
+				.unwrap_or(false)
+		}))
+	}
+
 	/// Retrieve outputs from node
 	fn get_outputs_from_node(
 		&self,
@@ -196,19 +326,22 @@ impl NodeClient for HTTPNodeClient {
 
 		// build a map of api outputs by commit so we can look them up efficiently
 		let mut api_outputs: HashMap<pedersen::Commitment, (String, u64, u64)> = HashMap::new();
-		let mut tasks = Vec::new();
 
-		let client = Client::new();
+		let client = self.client();
 
-		for query_chunk in query_params.chunks(200) {
-			let url = format!("{}/v1/chain/outputs/byids?{}", addr, query_chunk.join("&"),);
-			tasks.push(client.get_async::<Vec<api::Output>>(url.as_str(), self.node_api_secret()));
-		}
-
-		let task = stream::futures_unordered(tasks).collect();
-
-		let mut rt = Runtime::new().unwrap();
-		let results = match rt.block_on(task) {
+		let res = self.retry.call("Outputs by id", || {
+			let mut tasks = Vec::new();
+			for query_chunk in query_params.chunks(200) {
+				let url = format!("{}/v1/chain/outputs/byids?{}", addr, query_chunk.join("&"),);
+				tasks.push(
+					client.get_async::<Vec<api::Output>>(url.as_str(), self.node_api_secret()),
+				);
+			}
+			let task = stream::futures_unordered(tasks).collect();
+			let mut rt = Runtime::new().unwrap();
+			rt.block_on(task)
+		});
+		let results = match res {
 			Ok(outputs) => outputs,
 			Err(e) => {
 				let report = format!("Getting outputs by id: {}", e);
@@ -253,7 +386,7 @@ impl NodeClient for HTTPNodeClient {
 		let mut api_outputs: Vec<(pedersen::Commitment, pedersen::RangeProof, bool, u64, u64)> =
 			Vec::new();
 
-		let client = Client::new();
+		let client = self.client();
 
 		match client.get::<api::OutputListing>(url.as_str(), self.node_api_secret()) {
 			Ok(o) => {
@@ -319,7 +452,7 @@ impl NodeClient for HTTPNodeClient {
 
 		let url = format!("{}/v1/txhashset/heightstopmmr?{}", addr, query_param,);
 
-		let client = Client::new();
+		let client = self.client();
 
 		match client.get::<api::OutputListing>(url.as_str(), self.node_api_secret()) {
 			Ok(o) => Ok((o.last_retrieved_index, o.highest_index)),
@@ -13,5 +13,7 @@
 // limitations under the License.
 
 mod http;
+mod retry;
 
 pub use self::http::HTTPNodeClient;
+pub use self::retry::NodeRetryPolicy;
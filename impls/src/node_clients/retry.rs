@@ -0,0 +1,327 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retrying node calls through brief outages.
+//!
+//! A call failing with a transient error (the node couldn't be reached, timed
+//! out or answered with a 5xx) is made again after an exponentially growing,
+//! jittered delay, up to a number of attempts. Permanent errors are returned
+//! straight away.
+//!
+//! Calls still failing once their attempts run out count towards a circuit
+//! breaker shared by every clone of the client. Once enough fail in a row,
+//! calls fail immediately for a cooldown period instead of each waiting
+//! through its own retries, after which the next call is let through to try
+//! the node again.
+
+use crate::client_utils::{ClientError, ClientErrorKind};
+use crate::config::WalletConfig;
+use rand::{thread_rng, Rng};
+use std::cmp;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How node calls are retried, and when the node is given a rest
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeRetryPolicy {
+	/// Attempts made at each call, including the first. 1 never retries
+	pub max_attempts: u32,
+	/// Delay before the first retry, doubling with each one after
+	pub base_delay: Duration,
+	/// Longest delay between attempts
+	pub max_delay: Duration,
+	/// Calls failing in a row before the circuit breaker opens. 0 never opens it
+	pub breaker_threshold: u32,
+	/// How long calls fail immediately once the circuit breaker is open
+	pub breaker_cooldown: Duration,
+	/// Longest each attempt may take before it's given up on as timed out,
+	/// if limited. The wallet is held by a call to the node meanwhile, so a
+	/// node that stops answering would hold it up indefinitely otherwise
+	pub request_timeout: Option<Duration>,
+}
+
+impl Default for NodeRetryPolicy {
+	fn default() -> NodeRetryPolicy {
+		NodeRetryPolicy {
+			max_attempts: 3,
+			base_delay: Duration::from_millis(250),
+			max_delay: Duration::from_secs(5),
+			breaker_threshold: 5,
+			breaker_cooldown: Duration::from_secs(30),
+			request_timeout: Some(Duration::from_secs(30)),
+		}
+	}
+}
+
+impl NodeRetryPolicy {
+	/// The policy set in the wallet configuration, with defaults for
+	/// anything left unset
+	pub fn from_config(config: &WalletConfig) -> NodeRetryPolicy {
+		let default = NodeRetryPolicy::default();
+		NodeRetryPolicy {
+			max_attempts: config
+				.node_retry_max_attempts
+				.unwrap_or(default.max_attempts),
+			base_delay: config
+				.node_retry_base_delay_ms
+				.map(Duration::from_millis)
+				.unwrap_or(default.base_delay),
+			max_delay: config
+				.node_retry_max_delay_ms
+				.map(Duration::from_millis)
+				.unwrap_or(default.max_delay),
+			breaker_threshold: config
+				.node_circuit_breaker_threshold
+				.unwrap_or(default.breaker_threshold),
+			breaker_cooldown: config
+				.node_circuit_breaker_cooldown_secs
+				.map(Duration::from_secs)
+				.unwrap_or(default.breaker_cooldown),
+			request_timeout: match config.node_request_timeout_secs {
+				Some(0) => None,
+				Some(s) => Some(Duration::from_secs(s)),
+				None => default.request_timeout,
+			},
+		}
+	}
+
+	/// Delay before the given retry, counting from 1: the base delay doubled
+	/// for each retry before it, capped at the maximum, then jittered down by
+	/// up to half so clients knocked off together don't return together
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let exp = cmp::min(retry.saturating_sub(1), 16);
+		let delay = cmp::min(self.base_delay * (1 << exp), self.max_delay);
+		let ms = delay.as_millis() as u64;
+		if ms < 2 {
+			return delay;
+		}
+		Duration::from_millis(thread_rng().gen_range(ms / 2, ms + 1))
+	}
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+	consecutive_failures: u32,
+	open_until: Option<Instant>,
+}
+
+/// Makes node calls according to a retry policy, sharing the circuit
+/// breaker's state between clones
+#[derive(Clone, Debug)]
+pub struct NodeRetry {
+	policy: NodeRetryPolicy,
+	breaker: Arc<Mutex<BreakerState>>,
+}
+
+impl Default for NodeRetry {
+	fn default() -> NodeRetry {
+		NodeRetry::new(NodeRetryPolicy::default())
+	}
+}
+
+impl NodeRetry {
+	/// Retry calls according to the given policy
+	pub fn new(policy: NodeRetryPolicy) -> NodeRetry {
+		NodeRetry {
+			policy,
+			breaker: Arc::new(Mutex::new(BreakerState::default())),
+		}
+	}
+
+	/// The policy calls are retried by
+	pub fn policy(&self) -> &NodeRetryPolicy {
+		&self.policy
+	}
+
+	/// Make a call, described by `call` in the logs, retrying it while it
+	/// fails with transient errors
+	pub fn call<T, F>(&self, call: &str, mut f: F) -> Result<T, ClientError>
+	where
+		F: FnMut() -> Result<T, ClientError>,
+	{
+		if let Some(remaining) = self.open_for() {
+			return Err(ClientErrorKind::Connection(format!(
+				"node unavailable, not trying again for {}s",
+				remaining.as_secs() + 1
+			))
+			.into());
+		}
+
+		let mut attempt = 1;
+		loop {
+			match f() {
+				Ok(r) => {
+					self.record(true);
+					return Ok(r);
+				}
+				Err(e) => {
+					if !e.is_transient() {
+						// the node answered, so it's up
+						self.record(true);
+						return Err(e);
+					}
+					if attempt >= self.policy.max_attempts {
+						self.record(false);
+						return Err(e);
+					}
+					let delay = self.policy.backoff(attempt);
+					warn!(
+						"{}: attempt {} of {} failed, retrying in {}ms: {}",
+						call,
+						attempt,
+						self.policy.max_attempts,
+						delay.as_millis(),
+						e
+					);
+					thread::sleep(delay);
+					attempt += 1;
+				}
+			}
+		}
+	}
+
+	/// How much longer the circuit breaker stays open, if it's open
+	fn open_for(&self) -> Option<Duration> {
+		let state = self.breaker.lock().unwrap();
+		let now = Instant::now();
+		match state.open_until {
+			Some(t) if t > now => Some(t - now),
+			_ => None,
+		}
+	}
+
+	fn record(&self, success: bool) {
+		let mut state = self.breaker.lock().unwrap();
+		if success {
+			state.consecutive_failures = 0;
+			state.open_until = None;
+			return;
+		}
+		state.consecutive_failures += 1;
+		let threshold = self.policy.breaker_threshold;
+		if threshold > 0 && state.consecutive_failures >= threshold {
+			warn!(
+				"Node calls failed {} times in a row, pausing them for {}s",
+				state.consecutive_failures,
+				self.policy.breaker_cooldown.as_secs()
+			);
+			state.open_until = Some(Instant::now() + self.policy.breaker_cooldown);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::Cell;
+
+	fn policy() -> NodeRetryPolicy {
+		NodeRetryPolicy {
+			max_attempts: 3,
+			base_delay: Duration::from_millis(1),
+			max_delay: Duration::from_millis(4),
+			breaker_threshold: 2,
+			breaker_cooldown: Duration::from_secs(60),
+			request_timeout: None,
+		}
+	}
+
+	fn transient() -> ClientError {
+		ClientErrorKind::ResponseStatus(503, "unavailable".to_owned()).into()
+	}
+
+	#[test]
+	fn node_retry_backoff() {
+		let p = NodeRetryPolicy {
+			base_delay: Duration::from_millis(100),
+			max_delay: Duration::from_millis(1000),
+			..policy()
+		};
+		for (retry, full) in vec![
+			(1, 100),
+			(2, 200),
+			(3, 400),
+			(4, 800),
+			(5, 1000),
+			(40, 1000),
+		] {
+			let d = p.backoff(retry).as_millis() as u64;
+			assert!(d >= full / 2 && d <= full, "retry {} waited {}ms", retry, d);
+		}
+	}
+
+	#[test]
+	fn node_retry_classification() {
+		assert!(transient().is_transient());
+		assert!(
+			ClientError::from(ClientErrorKind::Connection("refused".to_owned())).is_transient()
+		);
+		assert!(
+			!ClientError::from(ClientErrorKind::ResponseStatus(404, "".to_owned())).is_transient()
+		);
+		assert!(
+			!ClientError::from(ClientErrorKind::ResponseError("bad json".to_owned()))
+				.is_transient()
+		);
+
+		let retry = NodeRetry::new(policy());
+
+		// transient errors are retried until the call succeeds
+		let calls = Cell::new(0);
+		let res = retry.call("test", || {
+			calls.set(calls.get() + 1);
+			if calls.get() < 3 {
+				Err(transient())
+			} else {
+				Ok(calls.get())
+			}
+		});
+		assert_eq!(res.unwrap(), 3);
+
+		// permanent errors aren't
+		calls.set(0);
+		let res: Result<(), _> = retry.call("test", || {
+			calls.set(calls.get() + 1);
+			Err(ClientErrorKind::ResponseStatus(400, "bad".to_owned()).into())
+		});
+		assert!(res.is_err());
+		assert_eq!(calls.get(), 1);
+	}
+
+	#[test]
+	fn node_retry_circuit_breaker() {
+		let retry = NodeRetry::new(policy());
+		let calls = Cell::new(0);
+		let failing = || -> Result<(), ClientError> {
+			calls.set(calls.get() + 1);
+			Err(transient())
+		};
+
+		// the breaker opens after two calls run out of attempts, and is
+		// shared with clones
+		assert!(retry.call("test", failing).is_err());
+		assert!(retry.open_for().is_none());
+		assert!(retry.clone().call("test", failing).is_err());
+		assert_eq!(calls.get(), 6);
+		assert!(retry.open_for().is_some());
+		assert!(retry.call("test", failing).is_err());
+		assert_eq!(calls.get(), 6);
+
+		// once the cooldown is over, a success closes it again
+		retry.breaker.lock().unwrap().open_until = Some(Instant::now());
+		assert!(retry.call("test", || Ok(())).is_ok());
+		assert_eq!(retry.breaker.lock().unwrap().consecutive_failures, 0);
+	}
+}
@@ -25,7 +25,7 @@ use crate::keychain;
 use crate::libwallet;
 use crate::libwallet::api_impl::{foreign, owner};
 use crate::libwallet::{
-	BlockFees, InitTxArgs, NodeClient, WalletInfo, WalletInst, WalletLCProvider,
+	Amount, BlockFees, InitTxArgs, NodeClient, WalletInfo, WalletInst, WalletLCProvider,
 };
 use crate::util::secp::key::SecretKey;
 use crate::util::secp::pedersen;
@@ -34,6 +34,7 @@ use chrono::Duration;
 use std::sync::Arc;
 use std::thread;
 
+pub mod simulation;
 mod testclient;
 
 pub use self::{testclient::LocalWalletClient, testclient::WalletProxy};
@@ -249,15 +250,10 @@ where
 	let slate = {
 		let mut w_lock = wallet.lock();
 		let w = w_lock.lc_provider()?.wallet_inst()?;
-		let args = InitTxArgs {
-			src_acct_name: None,
-			amount,
-			minimum_confirmations: 2,
-			max_outputs: 500,
-			num_change_outputs: 1,
-			selection_strategy_is_use_all: true,
-			..Default::default()
-		};
+		let args = InitTxArgs::builder()
+			.amount(Amount::from_freemans(amount))
+			.minimum_confirmations(2)
+			.build()?;
 		let slate_i = owner::init_send_tx(&mut **w, keychain_mask, args, test_mode)?;
 		let slate = client.send_tx_slate_direct(dest, &slate_i)?;
 		owner::tx_lock_outputs(&mut **w, keychain_mask, &slate, 0)?;
@@ -284,7 +280,7 @@ where
 	K: keychain::Keychain + 'a,
 {
 	let (wallet_refreshed, wallet_info) =
-		owner::retrieve_summary_info(wallet, keychain_mask, &None, true, 1)?;
+		owner::retrieve_summary_info(wallet, keychain_mask, &None, true, 1, false)?;
 	assert!(wallet_refreshed);
 	Ok(wallet_info)
 }
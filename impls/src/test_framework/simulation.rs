@@ -0,0 +1,454 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic simulation of wallets transacting with each other against a
+//! local chain, for finding sequences of operations that leave a wallet in
+//! an inconsistent state.
+//!
+//! A random number generator seeded with the simulation's seed picks each
+//! step: mining, sends, sends left in progress then finished or cancelled,
+//! the node falling behind the wallets as after a reorg, and refreshes.
+//! After every step each wallet's stored outputs and transaction log are
+//! checked against invariants that hold whatever came before, and the seed
+//! of a simulation that breaks one replays the same steps.
+
+use super::{award_blocks_to_wallet, send_to_dest, LocalWalletClient};
+use crate::chain::Chain;
+use crate::core::{consensus, global};
+use crate::keychain::Keychain;
+use crate::libwallet::api_impl::owner;
+use crate::libwallet::{
+	self, Amount, ErrorKind, InitTxArgs, NodeClient, OutputData, OutputStatus, Slate, TxLogEntry,
+	TxLogEntryType, WalletInst, WalletLCProvider,
+};
+use crate::util::secp::key::SecretKey;
+use crate::util::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A step of a simulation. Wallets and transactions in progress are referred
+/// to by their index
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+	/// Mine blocks rewarding the wallet
+	Mine {
+		/// Wallet rewarded
+		wallet: usize,
+		/// Number of blocks
+		blocks: usize,
+	},
+	/// Send to another wallet and post the transaction, which mines it
+	Send {
+		/// Sending wallet
+		from: usize,
+		/// Receiving wallet
+		to: usize,
+		/// Amount sent
+		amount: Amount,
+	},
+	/// Send to another wallet, which receives it, and lock the inputs,
+	/// leaving the transaction in progress
+	Start {
+		/// Sending wallet
+		from: usize,
+		/// Receiving wallet
+		to: usize,
+		/// Amount sent
+		amount: Amount,
+	},
+	/// Finalize and post a transaction in progress
+	Finish {
+		/// Transaction in progress
+		pending: usize,
+	},
+	/// Cancel a transaction in progress at both ends
+	Cancel {
+		/// Transaction in progress
+		pending: usize,
+	},
+	/// Leave the node's chain behind the height the wallets were last
+	/// refreshed to, as after a reorg to a shorter chain, until it's mined
+	/// past it
+	Reorg {
+		/// Number of blocks the node is behind
+		blocks: u64,
+	},
+	/// Refresh the wallet from the node
+	Refresh {
+		/// Wallet refreshed
+		wallet: usize,
+	},
+}
+
+/// A wallet taking part in a simulation
+pub struct SimWallet<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	/// Name the wallet was added to the wallet proxy with
+	pub name: String,
+	/// The wallet instance
+	pub wallet: Arc<Mutex<Box<dyn WalletInst<'static, L, C, K>>>>,
+	/// Mask of the wallet's keychain, if any
+	pub mask: Option<SecretKey>,
+	/// Client the wallet was added to the wallet proxy with
+	pub client: LocalWalletClient,
+}
+
+/// A transaction in progress, as received by the other wallet
+struct Pending {
+	from: usize,
+	to: usize,
+	slate: Slate,
+}
+
+/// Runs a simulation over the wallets added to it, which must all have been
+/// added to the wallet proxy running the chain
+pub struct Simulation<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	seed: u64,
+	rng: StdRng,
+	chain: Arc<Chain>,
+	wallets: Vec<SimWallet<L, C, K>>,
+	pending: Vec<Pending>,
+	/// Height the node has to reach before transactions are posted again,
+	/// as the wallets may take outputs still immature on its chain as mature
+	behind_until: u64,
+	history: Vec<Action>,
+}
+
+impl<L, C, K> Simulation<L, C, K>
+where
+	L: WalletLCProvider<'static, C, K> + 'static,
+	C: NodeClient + 'static,
+	K: Keychain + 'static,
+{
+	/// A simulation on the chain, its steps picked by the seed
+	pub fn new(chain: Arc<Chain>, seed: u64) -> Self {
+		let mut rng_seed = [0u8; 32];
+		rng_seed[..8].copy_from_slice(&seed.to_le_bytes());
+		Simulation {
+			seed,
+			rng: StdRng::from_seed(rng_seed),
+			chain,
+			wallets: vec![],
+			pending: vec![],
+			behind_until: 0,
+			history: vec![],
+		}
+	}
+
+	/// Add a wallet to take part
+	pub fn add_wallet(&mut self, wallet: SimWallet<L, C, K>) {
+		self.wallets.push(wallet);
+	}
+
+	/// The steps taken so far
+	pub fn history(&self) -> &[Action] {
+		&self.history
+	}
+
+	/// Fund each wallet with mature coinbase outputs, then take the given
+	/// number of steps, checking the invariants after each
+	pub fn run(&mut self, steps: usize) -> Result<(), libwallet::Error> {
+		if self.wallets.len() < 2 {
+			return Err(ErrorKind::GenericError(
+				"A simulation needs at least two wallets".to_owned(),
+			))?;
+		}
+		let blocks = global::coinbase_maturity() as usize + 2;
+		for wallet in 0..self.wallets.len() {
+			self.apply(Action::Mine { wallet, blocks })?;
+		}
+		for _ in 0..steps {
+			let action = self.next_action();
+			self.apply(action)?;
+		}
+		Ok(())
+	}
+
+	/// Take the step, then check the invariants of every wallet
+	pub fn apply(&mut self, action: Action) -> Result<(), libwallet::Error> {
+		debug!("Simulation {}: {:?}", self.seed, action);
+		self.history.push(action.clone());
+		if let Err(e) = self.take(action) {
+			// wallets may not have the funds the step picked
+			match e.kind() {
+				ErrorKind::NotEnoughFunds { .. } => {}
+				_ => return Err(e),
+			}
+		}
+		for wallet in 0..self.wallets.len() {
+			self.check_invariants(wallet)?;
+		}
+		Ok(())
+	}
+
+	fn height(&self) -> u64 {
+		self.chain.head().unwrap().height
+	}
+
+	fn next_action(&mut self) -> Action {
+		let n = self.wallets.len();
+		let wallet = self.rng.gen_range(0, n);
+		let other = (wallet + self.rng.gen_range(1, n)) % n;
+		// up to two block rewards, so sends take several inputs or fail
+		let max = 2 * consensus::BLOCK_TIME_SEC * consensus::EPIC_BASE;
+		let amount = Amount::from_freemans(self.rng.gen_range(1, max));
+		let behind = self.height() < self.behind_until;
+		let pending = self.pending.len();
+		match self.rng.gen_range(0, 10) {
+			2 | 3 if !behind => Action::Send {
+				from: wallet,
+				to: other,
+				amount,
+			},
+			4 | 5 => Action::Start {
+				from: wallet,
+				to: other,
+				amount,
+			},
+			6 if pending > 0 && !behind => Action::Finish {
+				pending: self.rng.gen_range(0, pending),
+			},
+			7 if pending > 0 => Action::Cancel {
+				pending: self.rng.gen_range(0, pending),
+			},
+			8 => Action::Reorg {
+				blocks: self.rng.gen_range(1, 4),
+			},
+			9 => Action::Refresh { wallet },
+			_ => Action::Mine {
+				wallet,
+				blocks: self.rng.gen_range(1, 4),
+			},
+		}
+	}
+
+	fn take(&mut self, action: Action) -> Result<(), libwallet::Error> {
+		match action {
+			Action::Mine { wallet, blocks } => {
+				let sw = &self.wallets[wallet];
+				award_blocks_to_wallet(
+					&self.chain,
+					sw.wallet.clone(),
+					sw.mask.as_ref(),
+					blocks,
+					false,
+				)
+			}
+			Action::Send { from, to, amount } => {
+				let (sw, dest) = (&self.wallets[from], &self.wallets[to]);
+				owner::update_wallet_state(sw.wallet.clone(), sw.mask.as_ref(), &None, false)?;
+				send_to_dest(
+					sw.wallet.clone(),
+					sw.mask.as_ref(),
+					sw.client.clone(),
+					&dest.name,
+					amount.freemans(),
+					false,
+				)
+			}
+			Action::Start { from, to, amount } => {
+				let (sw, dest) = (&self.wallets[from], &self.wallets[to]);
+				owner::update_wallet_state(sw.wallet.clone(), sw.mask.as_ref(), &None, false)?;
+				let slate = {
+					let mut w_lock = sw.wallet.lock();
+					let w = w_lock.lc_provider()?.wallet_inst()?;
+					let args = InitTxArgs::builder()
+						.amount(amount)
+						.minimum_confirmations(1)
+						.build()?;
+					let slate = owner::init_send_tx(&mut **w, sw.mask.as_ref(), args, false)?;
+					let slate = sw.client.send_tx_slate_direct(&dest.name, &slate)?;
+					owner::tx_lock_outputs(&mut **w, sw.mask.as_ref(), &slate, 0)?;
+					slate
+				};
+				self.pending.push(Pending { from, to, slate });
+				Ok(())
+			}
+			Action::Finish { pending } => {
+				let p = self.pending.remove(pending);
+				let sw = &self.wallets[p.from];
+				let (slate, client) = {
+					let mut w_lock = sw.wallet.lock();
+					let w = w_lock.lc_provider()?.wallet_inst()?;
+					let slate = owner::finalize_tx(&mut **w, sw.mask.as_ref(), &p.slate)?;
+					(slate, w.w2n_client().clone())
+				};
+				owner::post_tx(&client, &slate.tx, false)
+			}
+			Action::Cancel { pending } => {
+				let p = self.pending.remove(pending);
+				for sw in vec![&self.wallets[p.from], &self.wallets[p.to]] {
+					owner::cancel_tx(
+						sw.wallet.clone(),
+						sw.mask.as_ref(),
+						&None,
+						None,
+						Some(p.slate.id),
+						false,
+					)?;
+				}
+				Ok(())
+			}
+			Action::Reorg { blocks } => {
+				let height = self.height() + blocks;
+				for sw in self.wallets.iter() {
+					let mut w_lock = sw.wallet.lock();
+					let w = w_lock.lc_provider()?.wallet_inst()?;
+					let parent_key_id = w.parent_key_id();
+					let mut batch = w.batch(sw.mask.as_ref())?;
+					batch.save_last_confirmed_height(&parent_key_id, height)?;
+					batch.commit()?;
+				}
+				self.behind_until = self.behind_until.max(height);
+				Ok(())
+			}
+			Action::Refresh { wallet } => {
+				let sw = &self.wallets[wallet];
+				owner::update_wallet_state(sw.wallet.clone(), sw.mask.as_ref(), &None, false)?;
+				Ok(())
+			}
+		}
+	}
+
+	/// Check the stored state of the wallet:
+	/// * its balances add up to the outputs it holds, without overflowing
+	/// * its locked outputs are all inputs of sends in progress
+	/// * its outputs belong to logged transactions, and none are waiting on a
+	/// cancelled one
+	/// * the amounts of its confirmed transactions add up to the outputs it
+	/// holds, without ever going below nothing
+	pub fn check_invariants(&self, wallet: usize) -> Result<(), libwallet::Error> {
+		let sw = &self.wallets[wallet];
+		let (_, info) = owner::retrieve_summary_info(
+			sw.wallet.clone(),
+			sw.mask.as_ref(),
+			&None,
+			false,
+			1,
+			false,
+		)?;
+		let mut w_lock = sw.wallet.lock();
+		let w = w_lock.lc_provider()?.wallet_inst()?;
+		let parent_key_id = w.parent_key_id();
+		let outputs: Vec<OutputData> = w
+			.iter()
+			.filter(|o| o.root_key_id == parent_key_id)
+			.collect();
+		let txs: HashMap<u32, TxLogEntry> = w
+			.tx_log_iter()
+			.filter(|t| t.parent_key_id == parent_key_id)
+			.map(|t| (t.id, t))
+			.collect();
+		let held = |status: OutputStatus| {
+			Amount::checked_sum(
+				outputs
+					.iter()
+					.filter(|o| o.status == status)
+					.map(|o| Amount::from_freemans(o.value)),
+			)
+		};
+		let unspent = held(OutputStatus::Unspent)?;
+		let locked = held(OutputStatus::Locked)?;
+		let is_cancelled = |t: &TxLogEntry| {
+			t.tx_type == TxLogEntryType::TxSentCancelled
+				|| t.tx_type == TxLogEntryType::TxReceivedCancelled
+		};
+
+		let parts = Amount::checked_sum(vec![
+			info.amount_currently_spendable,
+			info.amount_awaiting_confirmation,
+			info.amount_immature,
+		])?;
+		if parts != info.total || info.total != unspent || info.amount_locked != locked {
+			return Err(self.violation(
+				&sw.name,
+				format!(
+					"balances {:?} don't add up to the unspent outputs {} and locked outputs {}",
+					info, unspent, locked
+				),
+			));
+		}
+
+		for o in outputs.iter() {
+			if o.status == OutputStatus::Locked {
+				match o.spent_by_tx.and_then(|id| txs.get(&id)) {
+					Some(t) if t.tx_type == TxLogEntryType::TxSent => {}
+					t => {
+						return Err(self.violation(
+							&sw.name,
+							format!("output {} is locked by {:?}", o.key_id, t),
+						))
+					}
+				}
+			}
+			if let Some(id) = o.tx_log_entry {
+				match txs.get(&id) {
+					None => {
+						return Err(self.violation(
+							&sw.name,
+							format!("output {} belongs to missing tx {}", o.key_id, id),
+						))
+					}
+					Some(t) if o.status == OutputStatus::Unconfirmed && is_cancelled(t) => {
+						return Err(self.violation(
+							&sw.name,
+							format!("output {} is waiting on cancelled tx {}", o.key_id, id),
+						))
+					}
+					_ => {}
+				}
+			}
+		}
+
+		let confirmed: Vec<&TxLogEntry> = txs
+			.values()
+			.filter(|t| t.confirmed && !is_cancelled(t))
+			.collect();
+		let credited = Amount::checked_sum(confirmed.iter().map(|t| t.amount_credited))?;
+		let debited = Amount::checked_sum(confirmed.iter().map(|t| t.amount_debited))?;
+		match credited.checked_sub(debited) {
+			Some(net) if unspent.checked_add(locked) == Some(net) => Ok(()),
+			net => Err(self.violation(
+				&sw.name,
+				format!(
+					"confirmed txs net {:?}, while unspent and locked outputs hold {} and {}",
+					net, unspent, locked
+				),
+			)),
+		}
+	}
+
+	fn violation(&self, wallet: &str, what: String) -> libwallet::Error {
+		ErrorKind::GenericError(format!(
+			"Simulation with seed {} broke an invariant of {} at step {} ({:?}): {}",
+			self.seed,
+			wallet,
+			self.history.len(),
+			self.history.last(),
+			what
+		))
+		.into()
+	}
+}
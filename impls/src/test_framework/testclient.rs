@@ -20,8 +20,11 @@ use crate::api::{self, LocatedTxKernel};
 use crate::chain::types::NoopAdapter;
 use crate::chain::Chain;
 
+use crate::core::core::feijoada::PoWType;
+use crate::core::core::hash::Hashed;
 use crate::core::core::{Transaction, TxKernel};
 use crate::core::global::{set_mining_mode, ChainTypes};
+use crate::core::pow::Proof;
 use crate::core::{pow, ser};
 use crate::keychain::Keychain;
 use crate::libwallet;
@@ -35,6 +38,7 @@ use crate::util::secp::key::SecretKey;
 use crate::util::secp::pedersen;
 use crate::util::secp::pedersen::Commitment;
 use crate::util::{Mutex, RwLock};
+use chrono::{DateTime, Utc};
 use failure::ResultExt;
 use serde_json;
 use std::collections::HashMap;
@@ -85,6 +89,11 @@ where
 	pub rx: Receiver<WalletProxyMessage>,
 	/// queue control
 	pub running: Arc<AtomicBool>,
+	/// keep posted transactions in the pool instead of mining them, to test
+	/// what happens while they wait
+	pub hold_posted: Arc<AtomicBool>,
+	/// transactions held while hold_posted is set
+	pub pool: Vec<Transaction>,
 }
 
 impl<'a, L, C, K> WalletProxy<'a, L, C, K>
@@ -114,6 +123,8 @@ where
 			rx: rx,
 			wallets: HashMap::new(),
 			running: Arc::new(AtomicBool::new(false)),
+			hold_posted: Arc::new(AtomicBool::new(false)),
+			pool: vec![],
 		};
 		retval
 	}
@@ -145,12 +156,16 @@ where
 			trace!("Wallet Client Proxy Received: {:?}", m);
 			let resp = match m.method.as_ref() {
 				"get_chain_tip" => self.get_chain_tip(m)?,
+				"get_header_hash" => self.get_header_hash(m)?,
+				"get_header_pow_type" => self.get_header_pow_type(m)?,
+				"get_header_timestamp" => self.get_header_timestamp(m)?,
 				"get_outputs_from_node" => self.get_outputs_from_node(m)?,
 				"get_outputs_by_pmmr_index" => self.get_outputs_by_pmmr_index(m)?,
 				"height_range_to_pmmr_indices" => self.height_range_to_pmmr_indices(m)?,
 				"send_tx_slate" => self.send_tx_slate(m)?,
 				"post_tx" => self.post_tx(m)?,
 				"get_kernel" => self.get_kernel(m)?,
+				"kernel_in_pool" => self.kernel_in_pool(m)?,
 				_ => panic!("Unknown Wallet Proxy Message"),
 			};
 
@@ -188,6 +203,16 @@ where
 			libwallet::ErrorKind::ClientCallback("Error parsing TxWrapper: tx".to_owned()),
 		)?;
 
+		if self.hold_posted.load(Ordering::Relaxed) {
+			self.pool.push(tx);
+			return Ok(WalletProxyMessage {
+				sender_id: "node".to_owned(),
+				dest: m.sender_id,
+				method: m.method,
+				body: "".to_owned(),
+			});
+		}
+
 		super::award_block_to_wallet(
 			&self.chain,
 			vec![&tx],
@@ -229,6 +254,7 @@ where
 				&Slate::from(slate),
 				None,
 				None,
+				None,
 				false,
 			) {
 				Err(e) => {
@@ -267,6 +293,70 @@ where
 		})
 	}
 
+	/// get hash of header at height
+	fn get_header_hash(
+		&mut self,
+		m: WalletProxyMessage,
+	) -> Result<WalletProxyMessage, libwallet::Error> {
+		let height = m.body.parse::<u64>().unwrap();
+		let hash = match self.chain.get_header_by_height(height) {
+			Ok(h) => util::to_hex(h.hash().to_vec()),
+			Err(_) => "".to_owned(),
+		};
+
+		Ok(WalletProxyMessage {
+			sender_id: "node".to_owned(),
+			dest: m.sender_id,
+			method: m.method,
+			body: hash,
+		})
+	}
+
+	/// get the PoW type of the header at height
+	fn get_header_pow_type(
+		&mut self,
+		m: WalletProxyMessage,
+	) -> Result<WalletProxyMessage, libwallet::Error> {
+		let height = m.body.parse::<u64>().unwrap();
+		let pow_type = match self.chain.get_header_by_height(height) {
+			Ok(h) => {
+				let pow_type = match h.pow.proof {
+					Proof::RandomXProof { .. } => PoWType::RandomX,
+					Proof::ProgPowProof { .. } => PoWType::ProgPow,
+					_ => PoWType::Cuckatoo,
+				};
+				serde_json::to_string(&pow_type).unwrap()
+			}
+			Err(_) => "".to_owned(),
+		};
+
+		Ok(WalletProxyMessage {
+			sender_id: "node".to_owned(),
+			dest: m.sender_id,
+			method: m.method,
+			body: pow_type,
+		})
+	}
+
+	/// get the timestamp of the header at height
+	fn get_header_timestamp(
+		&mut self,
+		m: WalletProxyMessage,
+	) -> Result<WalletProxyMessage, libwallet::Error> {
+		let height = m.body.parse::<u64>().unwrap();
+		let timestamp = match self.chain.get_header_by_height(height) {
+			Ok(h) => h.timestamp.to_rfc3339(),
+			Err(_) => "".to_owned(),
+		};
+
+		Ok(WalletProxyMessage {
+			sender_id: "node".to_owned(),
+			dest: m.sender_id,
+			method: m.method,
+			body: timestamp,
+		})
+	}
+
 	/// get api outputs
 	fn get_outputs_from_node(
 		&mut self,
@@ -367,6 +457,24 @@ where
 			body: serde_json::to_string(&k).unwrap(),
 		})
 	}
+
+	/// whether a held transaction has the given kernel
+	fn kernel_in_pool(
+		&mut self,
+		m: WalletProxyMessage,
+	) -> Result<WalletProxyMessage, libwallet::Error> {
+		let commit = pedersen::Commitment::from_vec(util::from_hex(m.body.clone()).unwrap());
+		let found = self
+			.pool
+			.iter()
+			.any(|tx| tx.kernels().iter().any(|k| k.excess == commit));
+		Ok(WalletProxyMessage {
+			sender_id: "node".to_owned(),
+			dest: m.sender_id,
+			method: m.method,
+			body: serde_json::to_string(&found).unwrap(),
+		})
+	}
 }
 
 #[derive(Clone)]
@@ -379,6 +487,10 @@ pub struct LocalWalletClient {
 	pub rx: Arc<Mutex<Receiver<WalletProxyMessage>>>,
 	/// my tx queue
 	pub tx: Arc<Mutex<Sender<WalletProxyMessage>>>,
+	/// whether node calls fail, as if the node couldn't be reached
+	pub node_offline: Arc<AtomicBool>,
+	/// sync status the node reports
+	pub sync_status: Arc<RwLock<String>>,
 }
 
 impl LocalWalletClient {
@@ -390,9 +502,29 @@ impl LocalWalletClient {
 			proxy_tx: Arc::new(Mutex::new(proxy_rx)),
 			rx: Arc::new(Mutex::new(rx)),
 			tx: Arc::new(Mutex::new(tx)),
+			node_offline: Arc::new(AtomicBool::new(false)),
+			sync_status: Arc::new(RwLock::new(libwallet::NODE_SYNCED_STATUS.to_owned())),
 		}
 	}
 
+	/// Make node calls of this client and its clones fail, as if the node
+	/// couldn't be reached, or succeed again
+	pub fn set_node_offline(&self, offline: bool) {
+		self.node_offline.store(offline, Ordering::Relaxed);
+	}
+
+	/// Set the sync status the node reports to this client and its clones
+	pub fn set_node_sync_status(&self, status: &str) {
+		*self.sync_status.write() = status.to_owned();
+	}
+
+	fn check_online(&self) -> Result<(), libwallet::Error> {
+		if self.node_offline.load(Ordering::Relaxed) {
+			return Err(libwallet::ErrorKind::ClientCallback("Node unreachable".to_owned()).into());
+		}
+		Ok(())
+	}
+
 	/// get an instance of the send queue for other senders
 	pub fn get_send_instance(&self) -> Sender<WalletProxyMessage> {
 		self.tx.lock().clone()
@@ -441,6 +573,7 @@ impl NodeClient for LocalWalletClient {
 	/// Posts a transaction to a epic node
 	/// In this case it will create a new block with award rewarded to
 	fn post_tx(&self, tx: &TxWrapper, _fluff: bool) -> Result<(), libwallet::Error> {
+		self.check_online()?;
 		let m = WalletProxyMessage {
 			sender_id: self.id.clone(),
 			dest: self.node_url().to_owned(),
@@ -459,8 +592,15 @@ impl NodeClient for LocalWalletClient {
 		Ok(())
 	}
 
+	/// Return the sync status of the node
+	fn get_sync_status(&self) -> Result<String, libwallet::Error> {
+		self.check_online()?;
+		Ok(self.sync_status.read().clone())
+	}
+
 	/// Return the chain tip from a given node
 	fn get_chain_tip(&self) -> Result<(u64, String), libwallet::Error> {
+		self.check_online()?;
 		let m = WalletProxyMessage {
 			sender_id: self.id.clone(),
 			dest: self.node_url().to_owned(),
@@ -486,6 +626,97 @@ impl NodeClient for LocalWalletClient {
 		Ok((split[0].parse::<u64>().unwrap(), split[1].to_owned()))
 	}
 
+	/// Return the hash of the header at the given height
+	fn get_header_hash(&self, height: u64) -> Result<String, libwallet::Error> {
+		self.check_online()?;
+		let m = WalletProxyMessage {
+			sender_id: self.id.clone(),
+			dest: self.node_url().to_owned(),
+			method: "get_header_hash".to_owned(),
+			body: format!("{}", height),
+		};
+		{
+			let p = self.proxy_tx.lock();
+			p.send(m).context(libwallet::ErrorKind::ClientCallback(
+				"Get header hash send".to_owned(),
+			))?;
+		}
+		let r = self.rx.lock();
+		let m = r.recv().unwrap();
+		trace!("Received get_header_hash response: {:?}", m.clone());
+		if m.body.is_empty() {
+			return Err(libwallet::ErrorKind::ClientCallback(format!(
+				"No header at height {}",
+				height
+			))
+			.into());
+		}
+		Ok(m.body)
+	}
+
+	/// Return the PoW type of the header at the given height
+	fn get_header_pow_type(&self, height: u64) -> Result<PoWType, libwallet::Error> {
+		self.check_online()?;
+		let m = WalletProxyMessage {
+			sender_id: self.id.clone(),
+			dest: self.node_url().to_owned(),
+			method: "get_header_pow_type".to_owned(),
+			body: format!("{}", height),
+		};
+		{
+			let p = self.proxy_tx.lock();
+			p.send(m).context(libwallet::ErrorKind::ClientCallback(
+				"Get header PoW type send".to_owned(),
+			))?;
+		}
+		let r = self.rx.lock();
+		let m = r.recv().unwrap();
+		trace!("Received get_header_pow_type response: {:?}", m.clone());
+		if m.body.is_empty() {
+			return Err(libwallet::ErrorKind::ClientCallback(format!(
+				"No header at height {}",
+				height
+			))
+			.into());
+		}
+		let pow_type = serde_json::from_str(&m.body).context(
+			libwallet::ErrorKind::ClientCallback("Parsing get_header_pow_type response".to_owned()),
+		)?;
+		Ok(pow_type)
+	}
+
+	/// Return the timestamp of the header at the given height
+	fn get_header_timestamp(&self, height: u64) -> Result<DateTime<Utc>, libwallet::Error> {
+		self.check_online()?;
+		let m = WalletProxyMessage {
+			sender_id: self.id.clone(),
+			dest: self.node_url().to_owned(),
+			method: "get_header_timestamp".to_owned(),
+			body: format!("{}", height),
+		};
+		{
+			let p = self.proxy_tx.lock();
+			p.send(m).context(libwallet::ErrorKind::ClientCallback(
+				"Get header timestamp send".to_owned(),
+			))?;
+		}
+		let r = self.rx.lock();
+		let m = r.recv().unwrap();
+		trace!("Received get_header_timestamp response: {:?}", m.clone());
+		if m.body.is_empty() {
+			return Err(libwallet::ErrorKind::ClientCallback(format!(
+				"No header at height {}",
+				height
+			))
+			.into());
+		}
+		let timestamp =
+			DateTime::parse_from_rfc3339(&m.body).context(libwallet::ErrorKind::ClientCallback(
+				"Parsing get_header_timestamp response".to_owned(),
+			))?;
+		Ok(timestamp.with_timezone(&Utc))
+	}
+
 	/// Retrieve outputs from node
 	fn get_outputs_from_node(
 		&self,
@@ -496,6 +727,7 @@ impl NodeClient for LocalWalletClient {
 			.map(|commit| format!("{}", util::to_hex(commit.as_ref().to_vec())))
 			.collect();
 		let query_str = query_params.join(",");
+		self.check_online()?;
 		let m = WalletProxyMessage {
 			sender_id: self.id.clone(),
 			dest: self.node_url().to_owned(),
@@ -539,6 +771,7 @@ impl NodeClient for LocalWalletClient {
 			query += "0"
 		}
 
+		self.check_online()?;
 		let m = WalletProxyMessage {
 			sender_id: self.id.clone(),
 			dest: self.node_url().to_owned(),
@@ -562,6 +795,28 @@ impl NodeClient for LocalWalletClient {
 		}
 	}
 
+	fn kernel_in_pool(&self, excess: &pedersen::Commitment) -> Result<bool, libwallet::Error> {
+		self.check_online()?;
+		let m = WalletProxyMessage {
+			sender_id: self.id.clone(),
+			dest: self.node_url().to_owned(),
+			method: "kernel_in_pool".to_owned(),
+			body: util::to_hex(excess.0.to_vec()),
+		};
+		{
+			let p = self.proxy_tx.lock();
+			p.send(m).context(libwallet::ErrorKind::ClientCallback(
+				"Kernel pool lookup send".to_owned(),
+			))?;
+		}
+		let r = self.rx.lock();
+		let m = r.recv().unwrap();
+		let found: bool = serde_json::from_str(&m.body).context(
+			libwallet::ErrorKind::ClientCallback("Kernel pool lookup".to_owned()),
+		)?;
+		Ok(found)
+	}
+
 	fn get_outputs_by_pmmr_index(
 		&self,
 		start_index: u64,
@@ -581,6 +836,7 @@ impl NodeClient for LocalWalletClient {
 			Some(e) => query_str = format!("{},{}", query_str, e),
 			None => query_str = format!("{},0", query_str),
 		};
+		self.check_online()?;
 		let m = WalletProxyMessage {
 			sender_id: self.id.clone(),
 			dest: self.node_url().to_owned(),
@@ -628,6 +884,7 @@ impl NodeClient for LocalWalletClient {
 			Some(e) => query_str = format!("{},{}", query_str, e),
 			None => query_str = format!("{},0", query_str),
 		};
+		self.check_online()?;
 		let m = WalletProxyMessage {
 			sender_id: self.id.clone(),
 			dest: self.node_url().to_owned(),
@@ -13,4 +13,9 @@
 // limitations under the License.
 
 pub mod config;
+#[cfg(feature = "tor")]
 pub mod process;
+
+/// Whether a TOR process can be run to send to and listen on onion
+/// addresses, i.e. the wallet was built with the tor feature
+pub const TOR_SUPPORT: bool = cfg!(feature = "tor");
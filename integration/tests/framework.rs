@@ -360,7 +360,7 @@ impl LocalServerContainer {
 		wallet.keychain = Some(keychain);
 		let parent_id = keychain::ExtKeychain::derive_key_id(2, 0, 0, 0, 0);
 		let _ = libwallet::internal::updater::refresh_outputs(&mut wallet, &parent_id, false);
-		libwallet::internal::updater::retrieve_info(&mut wallet, &parent_id, 1).unwrap()
+		libwallet::internal::updater::retrieve_info(&mut wallet, &parent_id, 1, false).unwrap()
 	}
 
 	#[allow(dead_code)]
@@ -0,0 +1,244 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Amounts of epic, held as a number of freemans, so an amount can't be
+//! passed where a count of epics, a height or a fee rate is expected, or
+//! the other way around, without saying which unit is meant.
+//!
+//! Amounts serialize as the number of freemans in a string, as the `u64`
+//! fields they replace did, and read it as a string or a number.
+
+use crate::denomination::{self, Denomination};
+use crate::error::{Error, ErrorKind};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+/// An amount of epic, in freemans (`1 EPIC = 100_000_000 freemans`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(u64);
+
+impl Amount {
+	/// No epic at all
+	pub const ZERO: Amount = Amount(0);
+
+	/// The amount of this many freemans
+	pub const fn from_freemans(freemans: u64) -> Amount {
+		Amount(freemans)
+	}
+
+	/// The amount of a whole number of units, unless it's too large
+	pub fn from_units(amount: u64, units: Denomination) -> Result<Amount, Error> {
+		units.to_freemans(amount).map(Amount)
+	}
+
+	/// The number of freemans in the amount
+	pub const fn freemans(self) -> u64 {
+		self.0
+	}
+
+	/// Whether the amount is nothing
+	pub fn is_zero(self) -> bool {
+		self.0 == 0
+	}
+
+	/// The sum of the amounts, unless it overflows
+	pub fn checked_add(self, other: Amount) -> Option<Amount> {
+		self.0.checked_add(other.0).map(Amount)
+	}
+
+	/// The difference of the amounts, unless the other is larger
+	pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+		self.0.checked_sub(other.0).map(Amount)
+	}
+
+	/// The amount this many times over, unless it overflows
+	pub fn checked_mul(self, times: u64) -> Option<Amount> {
+		self.0.checked_mul(times).map(Amount)
+	}
+
+	/// The difference of the amounts, or nothing if the other is larger
+	pub fn saturating_sub(self, other: Amount) -> Amount {
+		Amount(self.0.saturating_sub(other.0))
+	}
+
+	/// The sum of the amounts, or an error if it overflows
+	pub fn checked_sum<I>(amounts: I) -> Result<Amount, Error>
+	where
+		I: IntoIterator<Item = Amount>,
+	{
+		amounts
+			.into_iter()
+			.try_fold(Amount::ZERO, |sum, a| sum.checked_add(a))
+			.ok_or_else(|| ErrorKind::Amount("sum of amounts is too large".to_owned()).into())
+	}
+
+	/// The amount in the given units, as a decimal. With `truncate`, trailing
+	/// zeros are dropped, leaving at least one decimal place
+	pub fn format(self, units: Denomination, truncate: bool) -> String {
+		units.format_amount(self.0, truncate)
+	}
+}
+
+/// Panics if the sum overflows, see [checked_add](#method.checked_add)
+impl Add for Amount {
+	type Output = Amount;
+
+	fn add(self, other: Amount) -> Amount {
+		self.checked_add(other).expect("amount overflow")
+	}
+}
+
+impl AddAssign for Amount {
+	fn add_assign(&mut self, other: Amount) {
+		*self = *self + other;
+	}
+}
+
+/// Panics if the other amount is larger, see [checked_sub](#method.checked_sub)
+impl Sub for Amount {
+	type Output = Amount;
+
+	fn sub(self, other: Amount) -> Amount {
+		self.checked_sub(other).expect("amount underflow")
+	}
+}
+
+impl SubAssign for Amount {
+	fn sub_assign(&mut self, other: Amount) {
+		*self = *self - other;
+	}
+}
+
+impl From<Amount> for u64 {
+	fn from(amount: Amount) -> u64 {
+		amount.0
+	}
+}
+
+/// Comparing with a number of freemans, as amounts are mostly checked against
+/// literals
+impl PartialEq<u64> for Amount {
+	fn eq(&self, other: &u64) -> bool {
+		self.0 == *other
+	}
+}
+
+impl PartialOrd<u64> for Amount {
+	fn partial_cmp(&self, other: &u64) -> Option<Ordering> {
+		self.0.partial_cmp(other)
+	}
+}
+
+impl PartialEq<Amount> for u64 {
+	fn eq(&self, other: &Amount) -> bool {
+		*self == other.0
+	}
+}
+
+impl PartialOrd<Amount> for u64 {
+	fn partial_cmp(&self, other: &Amount) -> Option<Ordering> {
+		self.partial_cmp(&other.0)
+	}
+}
+
+/// In epics, e.g. `1.5`
+impl fmt::Display for Amount {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.format(Denomination::Epic, true))
+	}
+}
+
+/// Amounts with optional units after them, e.g. `1.5`, `1500milli` or
+/// `250 freemans`, in epics if no units are given
+impl FromStr for Amount {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Amount, Error> {
+		denomination::parse_amount(s, Denomination::Epic).map(Amount)
+	}
+}
+
+impl Serialize for Amount {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.collect_str(&self.0)
+	}
+}
+
+impl<'de> Deserialize<'de> for Amount {
+	fn deserialize<D>(deserializer: D) -> Result<Amount, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		struct FreemansVisitor;
+
+		impl<'de> Visitor<'de> for FreemansVisitor {
+			type Value = Amount;
+
+			fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "a number of freemans, as a string or an integer")
+			}
+
+			fn visit_u64<E: de::Error>(self, v: u64) -> Result<Amount, E> {
+				Ok(Amount(v))
+			}
+
+			fn visit_str<E: de::Error>(self, v: &str) -> Result<Amount, E> {
+				v.parse::<u64>().map(Amount).map_err(E::custom)
+			}
+		}
+
+		deserializer.deserialize_any(FreemansVisitor)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_core::consensus::EPIC_BASE;
+
+	#[test]
+	fn amounts() {
+		let a = Amount::from_units(3, Denomination::Epic).unwrap();
+		assert_eq!(a, 3 * EPIC_BASE);
+		assert_eq!(a.to_string(), "3.0");
+		assert_eq!("1.5".parse::<Amount>().unwrap(), EPIC_BASE * 3 / 2);
+		assert_eq!(
+			"250 freemans".parse::<Amount>().unwrap(),
+			Amount::from_freemans(250)
+		);
+		assert_eq!(a.format(Denomination::Milli, true), "3000.0");
+
+		let max = Amount::from_freemans(u64::max_value());
+		assert!(max.checked_add(a).is_none());
+		assert!(Amount::ZERO.checked_sub(a).is_none());
+		assert_eq!(Amount::ZERO.saturating_sub(a), Amount::ZERO);
+		assert_eq!(a - Amount::from_freemans(EPIC_BASE), 2 * EPIC_BASE);
+		assert!(Amount::checked_sum(vec![max, a]).is_err());
+		assert_eq!(Amount::checked_sum(vec![a, a]).unwrap(), 6 * EPIC_BASE);
+
+		// as the u64 fields amounts replace
+		let json = serde_json::to_string(&a).unwrap();
+		assert_eq!(json, format!("\"{}\"", 3 * EPIC_BASE));
+		assert_eq!(serde_json::from_str::<Amount>(&json).unwrap(), a);
+		assert_eq!(serde_json::from_str::<Amount>("300000000").unwrap(), a);
+		assert!(serde_json::from_str::<Amount>("\"1.5\"").is_err());
+	}
+}
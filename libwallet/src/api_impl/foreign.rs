@@ -16,23 +16,54 @@
 use strum::IntoEnumIterator;
 
 use crate::api_impl::owner::check_ttl;
+use crate::blake2::blake2b::blake2b;
 use crate::epic_keychain::Keychain;
+use crate::epic_util::secp;
 use crate::epic_util::secp::key::SecretKey;
 use crate::internal::{tx, updater};
-use crate::slate_versions::SlateVersion;
+use crate::payment_request::{PaymentRequest, PAYMENT_REQUEST_TTL_SECS};
+use crate::slate_versions::{SlateVersion, CURRENT_SLATE_VERSION, EPIC_BLOCK_HEADER_VERSION};
 use crate::{
-	address, BlockFees, CbData, Error, ErrorKind, NodeClient, Slate, TxLogEntryType, VersionInfo,
+	address, Amount, BlockFees, CbData, Error, ErrorKind, FeeContribution, NodeClient,
+	ReceiveNotification, Slate, SlateValidation, TxLogEntryType, VersionAdvisory, VersionInfo,
 	WalletBackend,
 };
+use chrono::{Duration, Utc};
+use ed25519_dalek::Keypair as DalekKeypair;
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use ed25519_dalek::Signature as DalekSignature;
+use uuid::Uuid;
 
 const FOREIGN_API_VERSION: u16 = 2;
+/// Release of the wallet, as given in its version info
+pub const WALLET_VERSION: &str = env!("CARGO_PKG_VERSION");
 const USER_MESSAGE_MAX_LEN: usize = 256;
+const DEPOSIT_TAG_MAX_LEN: usize = 64;
 
-/// Return the version info
-pub fn check_version() -> VersionInfo {
+/// Prefix of the message signed for a foreign API response, so the
+/// signature can't be passed off as any other
+const RESPONSE_SIGNATURE_DOMAIN: &[u8] = b"epic-wallet-foreign-response";
+
+/// Header of a signed foreign API response giving the signing payment proof
+/// address, in onion v3 form
+pub const RESPONSE_ADDRESS_HEADER: &str = "X-Epic-Proof-Address";
+
+/// Header of a signed foreign API response giving the hex encoded signature
+pub const RESPONSE_SIGNATURE_HEADER: &str = "X-Epic-Signature";
+
+/// Return the version info, with the largest part of the fee this wallet pays
+/// when it contributes an input to transactions it receives, if it does, and
+/// the upgrade advisory it last fetched, if any
+pub fn check_version(
+	max_fee_contribution: Option<u64>,
+	version_advisory: Option<VersionAdvisory>,
+) -> VersionInfo {
 	VersionInfo {
 		foreign_api_version: FOREIGN_API_VERSION,
 		supported_slate_versions: SlateVersion::iter().collect(),
+		receiver_fee_contribution: max_fee_contribution,
+		wallet_version: Some(WALLET_VERSION.to_owned()),
+		version_advisory,
 	}
 }
 
@@ -71,6 +102,55 @@ pub fn verify_slate_messages(slate: &Slate) -> Result<(), Error> {
 	slate.verify_messages()
 }
 
+/// Check a slate's structure, version, fee and signatures without acting on it
+pub fn validate_slate(slate: &Slate) -> SlateValidation {
+	let mut errors = vec![];
+	let mut check = |res: Result<(), Error>| match res {
+		Ok(_) => true,
+		Err(e) => {
+			errors.push(format!("{}", e));
+			false
+		}
+	};
+	let structure_ok = check(slate.verify_structure());
+	let version_supported = check(check_slate_version(slate));
+	let fee_ok = check(slate.check_fees());
+	let secp = secp::Secp256k1::with_caps(secp::ContextFlag::VerifyOnly);
+	let signatures_ok = check(
+		slate
+			.verify_part_sigs(&secp)
+			.and_then(|_| slate.verify_messages()),
+	);
+	SlateValidation {
+		slate_id: slate.id,
+		valid: structure_ok && version_supported && fee_ok && signatures_ok,
+		structure_ok,
+		version_supported,
+		fee_ok,
+		signatures_ok,
+		errors,
+	}
+}
+
+/// Whether the slate's versions are ones this wallet can process
+fn check_slate_version(slate: &Slate) -> Result<(), Error> {
+	// V2 is the oldest slate version still read
+	let v = &slate.version_info;
+	for version in &[v.version, v.orig_version] {
+		if *version < 2 || *version > CURRENT_SLATE_VERSION {
+			return Err(ErrorKind::SlateVersion(*version).into());
+		}
+	}
+	if v.block_header_version < EPIC_BLOCK_HEADER_VERSION {
+		return Err(ErrorKind::Compatibility(format!(
+			"slate is for block header version {}, expected at least {}",
+			v.block_header_version, EPIC_BLOCK_HEADER_VERSION
+		))
+		.into());
+	}
+	Ok(())
+}
+
 /// Receive a tx as recipient
 pub fn receive_tx<'a, T: ?Sized, C, K>(
 	w: &mut T,
@@ -78,6 +158,7 @@ pub fn receive_tx<'a, T: ?Sized, C, K>(
 	slate: &Slate,
 	dest_acct_name: Option<&str>,
 	message: Option<String>,
+	fee_contribution: Option<&FeeContribution>,
 	use_test_rng: bool,
 ) -> Result<Slate, Error>
 where
@@ -127,6 +208,7 @@ where
 		1,
 		message,
 		false,
+		fee_contribution,
 		use_test_rng,
 	)?;
 	tx::update_message(&mut *w, keychain_mask, &mut ret_slate)?;
@@ -136,7 +218,7 @@ where
 
 	if let Some(ref mut p) = ret_slate.payment_proof {
 		let sig = tx::create_payment_proof_signature(
-			ret_slate.amount,
+			ret_slate.amount.freemans(),
 			&excess,
 			p.sender_address,
 			address::address_from_derivation_path(&keychain, &parent_key_id, 0)?,
@@ -148,6 +230,169 @@ where
 	Ok(ret_slate)
 }
 
+/// Build the signed notice of a transaction received into the given account
+pub fn receive_notification<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	slate: &Slate,
+	dest_acct_name: Option<&str>,
+) -> Result<ReceiveNotification, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let parent_key_id = match dest_acct_name {
+		Some(d) => match w.get_acct_path(d.to_owned())? {
+			Some(p) => p.path,
+			None => w.parent_key_id(),
+		},
+		None => w.parent_key_id(),
+	};
+	let keychain = w.keychain(keychain_mask)?;
+	let sec_key = address::address_from_derivation_path(&keychain, &parent_key_id, 0)?;
+	let (d_skey, proof_address) = address::ed25519_keypair(&sec_key)?;
+	let keypair = DalekKeypair {
+		public: proof_address,
+		secret: d_skey,
+	};
+	let sender_proof_address = slate.payment_proof.as_ref().map(|p| p.sender_address);
+	let msg =
+		ReceiveNotification::message(&slate.id, slate.amount.freemans(), &sender_proof_address);
+	Ok(ReceiveNotification {
+		slate_id: slate.id,
+		amount: slate.amount.freemans(),
+		proof_address,
+		sender_proof_address,
+		signature: keypair.sign(&msg),
+	})
+}
+
+/// Create a payment request for the amount, signed with the payment proof
+/// address of the wallet's active account
+pub fn create_payment_request<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	amount: Amount,
+	memo: Option<String>,
+) -> Result<PaymentRequest, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let parent_key_id = w.parent_key_id();
+	let keychain = w.keychain(keychain_mask)?;
+	let sec_key = address::address_from_derivation_path(&keychain, &parent_key_id, 0)?;
+	let (d_skey, d_pkey) = address::ed25519_keypair(&sec_key)?;
+	let keypair = DalekKeypair {
+		public: d_pkey,
+		secret: d_skey,
+	};
+	let expires_at = Utc::now() + Duration::seconds(PAYMENT_REQUEST_TTL_SECS);
+	PaymentRequest::new(Uuid::new_v4(), amount, memo, expires_at, &keypair)
+}
+
+/// The message signed for a foreign API response: a hash of the request
+/// body, tying the response to the request it answers, followed by the
+/// response body
+pub fn response_signature_message(request: &[u8], response: &[u8]) -> Vec<u8> {
+	let mut msg = RESPONSE_SIGNATURE_DOMAIN.to_vec();
+	msg.extend_from_slice(blake2b(32, &[], request).as_bytes());
+	msg.extend_from_slice(response);
+	msg
+}
+
+/// Sign a foreign API response with the payment proof address of the
+/// wallet's active account, returning the address and signature
+pub fn sign_response<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	request: &[u8],
+	response: &[u8],
+) -> Result<(DalekPublicKey, DalekSignature), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let parent_key_id = w.parent_key_id();
+	let keychain = w.keychain(keychain_mask)?;
+	let sec_key = address::address_from_derivation_path(&keychain, &parent_key_id, 0)?;
+	let (d_skey, d_pkey) = address::ed25519_keypair(&sec_key)?;
+	let keypair = DalekKeypair {
+		public: d_pkey,
+		secret: d_skey,
+	};
+	let msg = response_signature_message(request, response);
+	Ok((d_pkey, keypair.sign(&msg)))
+}
+
+/// Check a foreign API response was signed by the given payment proof
+/// address
+pub fn verify_response_signature(
+	address: &DalekPublicKey,
+	request: &[u8],
+	response: &[u8],
+	signature: &DalekSignature,
+) -> Result<(), Error> {
+	let msg = response_signature_message(request, response);
+	if address.verify(&msg, signature).is_err() {
+		return Err(ErrorKind::ResponseSignature(format!(
+			"response not signed by {}",
+			address::onion_v3_from_pubkey(address)?
+		)))?;
+	}
+	Ok(())
+}
+
+/// Check a deposit tag can be used as a listener path segment
+pub fn validate_deposit_tag(tag: &str) -> Result<(), Error> {
+	if tag.is_empty() || tag.len() > DEPOSIT_TAG_MAX_LEN {
+		return Err(ErrorKind::DepositTag(format!(
+			"tags must be between 1 and {} characters",
+			DEPOSIT_TAG_MAX_LEN
+		)))?;
+	}
+	if !tag
+		.chars()
+		.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+	{
+		return Err(ErrorKind::DepositTag(format!(
+			"tag '{}' may only contain letters, digits, '-', '_' and '.'",
+			tag
+		)))?;
+	}
+	Ok(())
+}
+
+/// Record the deposit tag a transaction was received through
+pub fn tag_deposit<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	slate: &Slate,
+	tag: &str,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	validate_deposit_tag(tag)?;
+	let tx_vec = updater::retrieve_txs(&mut *w, None, Some(slate.id), None, false)?;
+	let mut batch = w.batch(keychain_mask)?;
+	for mut tx in tx_vec.into_iter() {
+		if tx.tx_type != TxLogEntryType::TxReceived {
+			continue;
+		}
+		tx.deposit_tag = Some(tag.to_owned());
+		let parent_key = tx.parent_key_id.clone();
+		batch.save_tx_log_entry(tx, &parent_key)?;
+	}
+	batch.commit()?;
+	Ok(())
+}
+
 /// Receive an tx that this wallet has issued
 pub fn finalize_invoice_tx<'a, T: ?Sized, C, K>(
 	w: &mut T,
@@ -14,32 +14,59 @@
 
 //! Generic implementation of owner API functions
 
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
+use crate::epic_core::core::amount_to_hr_string;
+use crate::epic_core::core::feijoada::PoWType;
 use crate::epic_core::core::hash::Hashed;
 use crate::epic_core::core::Transaction;
+use crate::epic_core::genesis;
+use crate::epic_core::global::{self, ChainTypes};
 use crate::epic_core::ser;
 use crate::epic_util;
-use crate::epic_util::secp::key::SecretKey;
+use crate::epic_util::secp::key::{PublicKey, SecretKey};
+use crate::epic_util::secp::{constants, pedersen};
 use crate::epic_util::Mutex;
 
+use crate::api_impl::foreign;
 use crate::api_impl::owner_updater::StatusMessage;
-use crate::epic_keychain::{Identifier, Keychain};
+use crate::epic_keychain::{ChildNumber, ExtKeychain, Identifier, Keychain, SwitchCommitmentType};
+use crate::fork_import;
 use crate::internal::{keys, scan, selection, tx, updater};
+use crate::payment_request::PaymentRequest;
 use crate::slate::{PaymentInfo, Slate};
-use crate::types::{AcctPathMapping, NodeClient, TxLogEntry, TxWrapper, WalletBackend, WalletInfo};
+use crate::slate_versions::v3::TransactionV3;
+use crate::types::{
+	AcctPathMapping, AuditEntry, IdempotentResult, Job, JobAction, NodeClient, OutputData,
+	OutputStatus, QueuedSend, Reservation, TxLogEntry, TxPostState, TxState, TxWrapper,
+	WalletBackend, WalletInfo, CLOCK_SKEW_WARNING_SECS, JOB_MAX_ATTEMPTS, JOB_RETRY_SECS,
+};
 use crate::{
-	address, wallet_lock, InitTxArgs, IssueInvoiceTxArgs, NodeHeightResult, OutputCommitMapping,
-	PaymentProof, ScannedBlockInfo, TxLogEntryType, WalletInitStatus, WalletInst, WalletLCProvider,
+	address, wallet_lock, AccountKeyExport, Amount, BalanceHistoryEntry, CancelPreview,
+	Capabilities, Capability, CleanedCoinbase, CsvImport, CsvSkippedRow, Denomination,
+	ExpectedCommitment, ExportedAccount, ExportedOutput, FinalityState, InitTxArgs,
+	IssueInvoiceTxArgs, JobResult, KernelStatus, LockedOutput, MerchantInvoice,
+	MiningRewardSummary, NodeHeightResult, NodePing, NodeStatus, OutputCommitMapping,
+	OutputLineage, OutputPage, PaymentProof, QueuedSendResult, RewoundOutput, ScannedBlockInfo,
+	SelectionStrategy, ShardBalance, SlateVersion, TxDetails, TxFilter, TxFinality, TxLogEntryType,
+	TxLogPage, TxTemplate, VersionedSlate, WalletEvent, WalletInitStatus, WalletInst,
+	WalletLCProvider, WalletState, WalletStateExport, WalletStateImport,
 };
 use crate::{Error, ErrorKind};
+use ed25519_dalek::Keypair as DalekKeypair;
 use ed25519_dalek::PublicKey as DalekPublicKey;
 use ed25519_dalek::SecretKey as DalekSecretKey;
 
+use std::collections::HashMap;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
+use std::time::Instant;
 
 const USER_MESSAGE_MAX_LEN: usize = 256;
+const OUTPUT_TAG_MAX_LEN: usize = 32;
+const MAX_EXPECTED_COMMITMENTS: usize = 1000;
+const WALLET_STATE_VERSION: u16 = 1;
 
 /// List of accounts
 pub fn accounts<'a, T: ?Sized, C, K>(w: &mut T) -> Result<Vec<AcctPathMapping>, Error>
@@ -65,6 +92,65 @@ where
 	keys::new_acct_path(&mut *w, keychain_mask, label)
 }
 
+/// Freeze or unfreeze an account. Spends from a frozen account are refused
+/// when selecting its coins, while it can still receive and be queried. The
+/// change, along with the reason given for it, is recorded in the audit trail
+pub fn set_account_frozen<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	label: &str,
+	frozen: bool,
+	reason: &str,
+) -> Result<AcctPathMapping, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if reason.trim().is_empty() {
+		return Err(ErrorKind::AccountFreeze("a reason must be given".to_owned()).into());
+	}
+	let state = |frozen: bool| match frozen {
+		true => "frozen",
+		false => "unfrozen",
+	};
+
+	wallet_lock!(wallet_inst, w);
+	let mut mapping = w
+		.acct_path_iter()
+		.find(|m| m.label == label)
+		.ok_or_else(|| ErrorKind::UnknownAccountLabel(label.to_owned()))?;
+	if mapping.frozen == frozen {
+		return Err(ErrorKind::AccountFreeze(format!(
+			"account '{}' is already {}",
+			label,
+			state(frozen)
+		))
+		.into());
+	}
+	mapping.frozen = frozen;
+
+	let entry = AuditEntry {
+		id: Uuid::new_v4(),
+		timestamp: Utc::now(),
+		action: match frozen {
+			true => "freeze_account",
+			false => "unfreeze_account",
+		}
+		.to_owned(),
+		target: label.to_owned(),
+		before: state(!frozen).to_owned(),
+		after: state(frozen).to_owned(),
+		reason: reason.to_owned(),
+	};
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save_acct_path(mapping.clone())?;
+	batch.save_audit_entry(&entry)?;
+	batch.commit()?;
+	warn!("Account '{}' {}: {}", label, state(frozen), reason);
+	Ok(mapping)
+}
+
 /// set active account
 pub fn set_active_account<'a, T: ?Sized, C, K>(w: &mut T, label: &str) -> Result<(), Error>
 where
@@ -95,6 +181,584 @@ where
 	Ok(address::ed25519_keypair(&sec_addr_key)?.1)
 }
 
+/// Look up an account by label, for exporting its key material
+fn export_account<'a, T: ?Sized, C, K>(w: &mut T, label: &str) -> Result<AcctPathMapping, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mapping = w
+		.get_acct_path(label.to_owned())?
+		.ok_or_else(|| ErrorKind::UnknownAccountLabel(label.to_owned()))?;
+	warn!("Exporting key material of account '{}'", label);
+	Ok(mapping)
+}
+
+/// Export the public key of an account's derivation key, along with its path
+/// and the number of output keys derived under it
+pub fn export_account_key<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	label: &str,
+) -> Result<AccountKeyExport, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	let mapping = export_account(&mut **w, label)?;
+	let k = w.keychain(keychain_mask)?;
+	let sec_key = k.derive_key(0, &mapping.path, &SwitchCommitmentType::None)?;
+	let public_key = PublicKey::from_secret_key(k.secp(), &sec_key)?;
+	Ok(AccountKeyExport {
+		label: mapping.label,
+		child_count: w.current_child_index(&mapping.path)?,
+		path: mapping.path,
+		public_key: epic_util::to_hex(public_key.serialize_vec(k.secp(), true).to_vec()),
+	})
+}
+
+/// Derive the commitments the outputs of an account at the given child
+/// indices would have for each of the given values, so they can be matched
+/// against those on chain
+pub fn derive_expected_commitments<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	label: &str,
+	from_index: u32,
+	count: u32,
+	values: &[u64],
+) -> Result<Vec<ExpectedCommitment>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if count as usize * values.len() > MAX_EXPECTED_COMMITMENTS {
+		return Err(ErrorKind::AccountKeyExport(format!(
+			"at most {} commitments can be derived at once",
+			MAX_EXPECTED_COMMITMENTS
+		))
+		.into());
+	}
+	let end_index = from_index
+		.checked_add(count)
+		.ok_or_else(|| ErrorKind::AccountKeyExport("child indices out of range".to_owned()))?;
+
+	wallet_lock!(wallet_inst, w);
+	let mapping = export_account(&mut **w, label)?;
+	let held: Vec<String> = w.iter().filter_map(|o| o.commit).collect();
+	let k = w.keychain(keychain_mask)?;
+	let mut res = vec![];
+	for child_index in from_index..end_index {
+		let mut path = mapping.path.to_path();
+		path.depth = path.depth + 1;
+		path.path[path.depth as usize - 1] = ChildNumber::from(child_index);
+		let key_id = Identifier::from_path(&path);
+		for value in values {
+			let commit = k.commit(*value, &key_id, &SwitchCommitmentType::Regular)?;
+			let in_wallet = held.contains(&epic_util::to_hex(commit.0.to_vec()));
+			res.push(ExpectedCommitment {
+				key_id: key_id.clone(),
+				child_index,
+				value: *value,
+				commit,
+				in_wallet,
+			});
+		}
+	}
+	Ok(res)
+}
+
+/// Parse the key path of an output, such as `m/0/0/4`, into its key id
+pub(crate) fn parse_output_key_path(key_path: &str) -> Result<Identifier, Error> {
+	let refused = || -> Error {
+		ErrorKind::ImportOutput(format!(
+			"key path {} isn't an output's, such as m/0/0/4",
+			key_path
+		))
+		.into()
+	};
+	let mut parts = key_path.trim().split('/');
+	if parts.next() != Some("m") {
+		return Err(refused());
+	}
+	let indices = parts
+		.map(|p| p.parse::<u32>())
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|_| refused())?;
+	if indices.len() != 3 {
+		return Err(refused());
+	}
+	Ok(ExtKeychain::derive_key_id(
+		3, indices[0], indices[1], indices[2], 0,
+	))
+}
+
+/// Import an output the wallet is missing, given its key path and value,
+/// once it's been found unspent on chain at the given height
+pub fn import_output<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	key_path: &str,
+	value: u64,
+	height: u64,
+) -> Result<OutputData, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let key_id = parse_output_key_path(key_path)?;
+	scan::import_output(wallet_inst, keychain_mask, &key_id, value, height)
+}
+
+/// The key wallet state exports are signed with: the payment proof address
+/// of the default account, the same for every wallet with the same seed
+fn wallet_state_keypair<K>(keychain: &K) -> Result<DalekKeypair, Error>
+where
+	K: Keychain,
+{
+	let parent_key_id = ExtKeychain::derive_key_id(2, 0, 0, 0, 0);
+	let sec_key = address::address_from_derivation_path(keychain, &parent_key_id, 0)?;
+	let (d_skey, d_pkey) = address::ed25519_keypair(&sec_key)?;
+	Ok(DalekKeypair {
+		public: d_pkey,
+		secret: d_skey,
+	})
+}
+
+/// Export the wallet's accounts and confirmed unspent outputs, as of the
+/// last block it scanned, signed for another instance with the same seed to
+/// import
+pub fn export_wallet_state<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<WalletStateExport, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	let last_scanned_block = w.last_scanned_block()?;
+	if last_scanned_block.height == 0 {
+		return Err(ErrorKind::StateImport(
+			"the wallet hasn't been scanned against the chain yet".to_owned(),
+		)
+		.into());
+	}
+	let mappings: Vec<AcctPathMapping> = w.acct_path_iter().collect();
+	let mut accounts = vec![];
+	for m in mappings {
+		accounts.push(ExportedAccount {
+			child_index: w.current_child_index(&m.path)?,
+			label: m.label,
+			path: m.path,
+		});
+	}
+	let outputs = w
+		.iter()
+		.filter(|o| o.status == OutputStatus::Unspent || o.status == OutputStatus::Locked)
+		.map(|o| ExportedOutput {
+			key_id: o.key_id,
+			value: o.value,
+			height: o.height,
+			is_coinbase: o.is_coinbase,
+		})
+		.collect();
+	let state = WalletState {
+		version: WALLET_STATE_VERSION,
+		created_at: Utc::now(),
+		height: last_scanned_block.height,
+		header_hash: last_scanned_block.hash,
+		accounts,
+		outputs,
+	};
+
+	let keypair = wallet_state_keypair(&w.keychain(keychain_mask)?)?;
+	let msg = WalletStateExport::message(&state)?;
+	Ok(WalletStateExport {
+		state,
+		proof_address: keypair.public,
+		signature: keypair.sign(&msg),
+	})
+}
+
+/// Bootstrap a new wallet from a state export of another instance with the
+/// same seed. The export's outputs are only added once found unspent on
+/// chain, and the wallet carries on scanning from the export's height
+/// rather than scanning the whole chain
+pub fn import_wallet_state<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	export: &WalletStateExport,
+) -> Result<WalletStateImport, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let refused = |msg: &str| -> Error { ErrorKind::StateImport(msg.to_owned()).into() };
+	let state = &export.state;
+	if state.version != WALLET_STATE_VERSION {
+		return Err(refused("the export's version isn't supported"));
+	}
+	export.verify()?;
+
+	let mut added = vec![];
+	{
+		wallet_lock!(wallet_inst, w);
+		let keypair = wallet_state_keypair(&w.keychain(keychain_mask)?)?;
+		if keypair.public != export.proof_address {
+			return Err(refused("the export is from a wallet with a different seed"));
+		}
+		if w.iter().next().is_some() {
+			return Err(refused(
+				"the wallet already has outputs, state can only be imported into a new wallet",
+			));
+		}
+		let existing: Vec<AcctPathMapping> = w.acct_path_iter().collect();
+		for a in state.accounts.iter() {
+			if existing.iter().any(|m| m.path == a.path) {
+				continue;
+			}
+			if existing.iter().any(|m| m.label == a.label) {
+				return Err(refused(&format!(
+					"the wallet already has an account labelled '{}'",
+					a.label
+				)));
+			}
+			keys::set_acct_path(&mut **w, keychain_mask, &a.label, &a.path)?;
+			added.push(a.label.clone());
+		}
+	}
+
+	let (imported, skipped) =
+		scan::import_exported_outputs(wallet_inst.clone(), keychain_mask, &state.outputs)?;
+
+	wallet_lock!(wallet_inst, w);
+	let mut child_indices = vec![];
+	for a in state.accounts.iter() {
+		if a.child_index > w.current_child_index(&a.path)? {
+			child_indices.push((a.path.clone(), a.child_index));
+		}
+	}
+	let mut batch = w.batch(keychain_mask)?;
+	for (path, child_index) in child_indices {
+		batch.save_child_index(&path, child_index)?;
+	}
+	batch.save_last_scanned_block(ScannedBlockInfo {
+		height: state.height,
+		hash: state.header_hash.clone(),
+		start_pmmr_index: 0,
+		last_pmmr_index: 0,
+	})?;
+	batch.save_init_status(WalletInitStatus::InitComplete)?;
+	batch.commit()?;
+	info!(
+		"Imported wallet state at height {}: {} outputs added, {} not found on chain",
+		state.height,
+		imported.len(),
+		skipped.len()
+	);
+
+	Ok(WalletStateImport {
+		height: state.height,
+		accounts: added,
+		imported,
+		skipped,
+	})
+}
+
+/// Import the unspent outputs another wallet with the same seed, such as
+/// grin-wallet or another fork, exported as CSV. Each output is found on
+/// chain by its commitment, worked out from its key id and value if not
+/// given, and only added if its range proof rewinds with the wallet's keys
+pub fn import_outputs_csv<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	csv: &str,
+	units: Denomination,
+) -> Result<CsvImport<OutputData>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (rows, mut skipped) = fork_import::parse_outputs(csv, units)?;
+	let mut skip = |line: usize, reason: String| skipped.push(CsvSkippedRow { line, reason });
+
+	let (client, rows) = {
+		wallet_lock!(wallet_inst, w);
+		let k = w.keychain(keychain_mask)?;
+		let in_wallet: Vec<String> = w.iter().filter_map(|o| o.commit).collect();
+		let mut found = vec![];
+		for r in rows {
+			match r.status {
+				None | Some(OutputStatus::Unspent) | Some(OutputStatus::Locked) => {}
+				Some(s) => {
+					skip(
+						r.line,
+						format!("only unspent outputs are imported, not {}", s),
+					);
+					continue;
+				}
+			}
+			let commit = match (r.commit, r.key_id.as_ref(), r.value) {
+				(Some(c), _, _) => c,
+				(None, Some(key_id), Some(value)) => {
+					k.commit(value, key_id, &SwitchCommitmentType::Regular)?
+				}
+				_ => {
+					skip(
+						r.line,
+						"neither a commitment nor a key id and value are given".to_owned(),
+					);
+					continue;
+				}
+			};
+			if in_wallet.contains(&epic_util::to_hex(commit.0.to_vec())) {
+				skip(r.line, "the output is already in the wallet".to_owned());
+				continue;
+			}
+			found.push((r, commit));
+		}
+		(w.w2n_client().clone(), found)
+	};
+
+	let on_chain = match rows.is_empty() {
+		true => HashMap::new(),
+		false => client.get_outputs_from_node(rows.iter().map(|(_, c)| *c).collect())?,
+	};
+	let mut proofs = vec![];
+	for (r, commit) in rows {
+		let mmr_index = match on_chain.get(&commit) {
+			Some((_, _, mmr_index)) => *mmr_index,
+			None => {
+				skip(r.line, "the output isn't unspent on chain".to_owned());
+				continue;
+			}
+		};
+		let (_, _, chain_outputs) =
+			client.get_outputs_by_pmmr_index(mmr_index, Some(mmr_index), 1)?;
+		match chain_outputs.into_iter().find(|o| o.0 == commit) {
+			Some((_, proof, is_coinbase, height, _)) => {
+				proofs.push((r, commit, proof, is_coinbase, height))
+			}
+			None => skip(
+				r.line,
+				"the output is missing from the node's UTXO set".to_owned(),
+			),
+		}
+	}
+
+	let mut outputs = vec![];
+	{
+		wallet_lock!(wallet_inst, w);
+		let k = w.keychain(keychain_mask)?;
+		for (r, commit, proof, is_coinbase, height) in proofs {
+			let (value, key_id, _) = match scan::rewind_single_output(&k, commit, proof)? {
+				Some(info) => info,
+				None => {
+					skip(
+						r.line,
+						"the output isn't this wallet's, it may be from a different seed"
+							.to_owned(),
+					);
+					continue;
+				}
+			};
+			let mismatch = match (r.key_id.as_ref(), r.value, r.height) {
+				(Some(id), _, _) if *id != key_id => Some(format!("its key id is {}", key_id)),
+				(_, Some(v), _) if v != value => Some(format!("its value is {}", value)),
+				(_, _, Some(h)) if h != height => Some(format!("its height is {}", height)),
+				_ => None,
+			};
+			if let Some(m) = mismatch {
+				skip(r.line, format!("the output on chain doesn't match, {}", m));
+				continue;
+			}
+			outputs.push((
+				r.line,
+				ExportedOutput {
+					key_id,
+					value,
+					height,
+					is_coinbase,
+				},
+			));
+		}
+	}
+
+	let exported: Vec<ExportedOutput> = outputs.iter().map(|(_, o)| o.clone()).collect();
+	let (imported, not_found) =
+		scan::import_exported_outputs(wallet_inst.clone(), keychain_mask, &exported)?;
+	for o in not_found {
+		if let Some((line, _)) = outputs.iter().find(|(_, e)| e.key_id == o.key_id) {
+			skip(*line, "the output isn't unspent on chain".to_owned());
+		}
+	}
+	skipped.sort_by_key(|s| s.line);
+	info!(
+		"Imported {} outputs from CSV, skipped {} rows",
+		imported.len(),
+		skipped.len()
+	);
+	Ok(CsvImport { imported, skipped })
+}
+
+/// Import the transaction log another wallet with the same seed, such as
+/// grin-wallet or another fork, exported as CSV, into the active account.
+/// Entries the wallet made when restoring outputs are filled in from
+/// matching rows rather than added again, so outputs are best imported first
+pub fn import_txs_csv<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	csv: &str,
+	units: Denomination,
+) -> Result<CsvImport<TxLogEntry>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (rows, mut skipped) = fork_import::parse_txs(csv, units)?;
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut existing: Vec<TxLogEntry> = w
+		.tx_log_iter()
+		.filter(|t| t.parent_key_id == parent_key_id)
+		.collect();
+	let mut imported = vec![];
+	let mut batch = w.batch(keychain_mask)?;
+	for r in rows {
+		let cancelled = r.tx_type == TxLogEntryType::TxReceivedCancelled
+			|| r.tx_type == TxLogEntryType::TxSentCancelled;
+		if !r.confirmed && !cancelled {
+			skipped.push(CsvSkippedRow {
+				line: r.line,
+				reason: "unconfirmed transactions aren't imported, finish or cancel it first"
+					.to_owned(),
+			});
+			continue;
+		}
+		let known = existing.iter().any(|t| {
+			(r.tx_slate_id.is_some() && t.tx_slate_id == r.tx_slate_id)
+				|| (r.kernel_excess.is_some() && t.kernel_excess == r.kernel_excess)
+		});
+		if known {
+			skipped.push(CsvSkippedRow {
+				line: r.line,
+				reason: "the transaction is already in the wallet".to_owned(),
+			});
+			continue;
+		}
+
+		// an entry made when restoring an output, which the row fills in
+		let restored = existing.iter().position(|t| {
+			t.tx_slate_id.is_none()
+				&& t.kernel_excess.is_none()
+				&& t.tx_type == r.tx_type
+				&& t.confirmed == r.confirmed
+				&& t.amount_credited == r.amount_credited
+				&& t.amount_debited == r.amount_debited
+		});
+		let mut t = match restored {
+			Some(i) => existing.remove(i),
+			None => {
+				let id = batch.next_tx_log_id(&parent_key_id)?;
+				TxLogEntry::new(parent_key_id.clone(), r.tx_type.clone(), id)
+			}
+		};
+		t.tx_slate_id = r.tx_slate_id;
+		t.creation_ts = r.creation_ts;
+		t.confirmed = r.confirmed;
+		t.confirmation_ts = r.confirmation_ts.or(t.confirmation_ts);
+		t.confirmation_height = r.confirmation_height.or(t.confirmation_height);
+		t.num_inputs = r.num_inputs;
+		t.num_outputs = r.num_outputs;
+		t.amount_credited = Amount::from_freemans(r.amount_credited);
+		t.amount_debited = Amount::from_freemans(r.amount_debited);
+		t.fee = r.fee;
+		t.kernel_excess = r.kernel_excess;
+		let state = t.infer_state();
+		t.transition(state)?;
+		batch.save_tx_log_entry(t.clone(), &parent_key_id)?;
+		existing.push(t.clone());
+		imported.push(t);
+	}
+	batch.commit()?;
+	skipped.sort_by_key(|s| s.line);
+	info!(
+		"Imported {} transactions from CSV, skipped {} rows",
+		imported.len(),
+		skipped.len()
+	);
+	Ok(CsvImport { imported, skipped })
+}
+
+/// Attempt to rewind the range proof of an output with the wallet's keys,
+/// recovering its value and key if it belongs to the wallet
+pub fn rewind_rangeproof<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	commit: &str,
+	proof: &str,
+) -> Result<RewoundOutput, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let refused = |msg: &str| -> Error { ErrorKind::RangeProofRewind(msg.to_owned()).into() };
+	let commit_bytes = epic_util::from_hex(commit.to_owned())
+		.map_err(|_| refused("commitment isn't valid hex"))?;
+	if commit_bytes.len() != constants::PEDERSEN_COMMITMENT_SIZE {
+		return Err(refused("commitment isn't the size of a commitment"));
+	}
+	let proof_bytes =
+		epic_util::from_hex(proof.to_owned()).map_err(|_| refused("proof isn't valid hex"))?;
+	if proof_bytes.is_empty() || proof_bytes.len() > constants::MAX_PROOF_SIZE {
+		return Err(refused("proof isn't the size of a range proof"));
+	}
+	let commit = pedersen::Commitment::from_vec(commit_bytes);
+	let mut range_proof = pedersen::RangeProof {
+		proof: [0; constants::MAX_PROOF_SIZE],
+		plen: proof_bytes.len(),
+	};
+	range_proof.proof[..proof_bytes.len()].copy_from_slice(&proof_bytes);
+
+	wallet_lock!(wallet_inst, w);
+	let k = w.keychain(keychain_mask)?;
+	let info = scan::rewind_single_output(&k, commit, range_proof)?;
+	let commit_hex = epic_util::to_hex(commit.0.to_vec());
+	let output = w.iter().find(|o| o.commit.as_ref() == Some(&commit_hex));
+	let mut res = RewoundOutput {
+		commit,
+		is_ours: info.is_some(),
+		value: None,
+		key_id: None,
+		key_path: None,
+		account: None,
+		output,
+	};
+	if let Some((value, key_id, _)) = info {
+		let parent_key_id = key_id.parent_path();
+		res.account = w
+			.acct_path_iter()
+			.find(|m| m.path == parent_key_id)
+			.map(|m| m.label);
+		res.value = Some(value);
+		res.key_path = Some(key_id.to_bip_32_string());
+		res.key_id = Some(key_id);
+	}
+	Ok(res)
+}
+
 /// retrieve outputs
 pub fn retrieve_outputs<'a, L, C, K>(
 	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
@@ -127,7 +791,6 @@ where
 		validated,
 		updater::retrieve_outputs(
 			&mut **w,
-			keychain_mask,
 			include_spent,
 			show_full_history,
 			tx_id,
@@ -136,15 +799,16 @@ where
 	))
 }
 
-/// Retrieve txs
-pub fn retrieve_txs<'a, L, C, K>(
+/// retrieve a page of outputs
+pub fn retrieve_outputs_page<'a, L, C, K>(
 	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
 	status_send_channel: &Option<Sender<StatusMessage>>,
+	include_spent: bool,
 	refresh_from_node: bool,
-	tx_id: Option<u32>,
-	tx_slate_id: Option<Uuid>,
-) -> Result<(bool, Vec<TxLogEntry>), Error>
+	cursor: Option<String>,
+	limit: usize,
+) -> Result<(bool, OutputPage), Error>
 where
 	L: WalletLCProvider<'a, C, K>,
 	C: NodeClient + 'a,
@@ -162,19 +826,66 @@ where
 
 	wallet_lock!(wallet_inst, w);
 	let parent_key_id = w.parent_key_id();
-	let txs = updater::retrieve_txs(&mut **w, tx_id, tx_slate_id, Some(&parent_key_id), false)?;
 
-	Ok((validated, txs))
+	Ok((
+		validated,
+		updater::retrieve_outputs_page(
+			&mut **w,
+			include_spent,
+			&parent_key_id,
+			cursor.as_ref().map(|c| c.as_str()),
+			limit,
+		)?,
+	))
 }
 
-/// Retrieve summary info
-pub fn retrieve_summary_info<'a, L, C, K>(
+/// Retrieve foundation reward outputs built by this wallet, optionally
+/// limited to those included in blocks within the given height range
+pub fn retrieve_foundation_rewards<'a, L, C, K>(
 	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
 	status_send_channel: &Option<Sender<StatusMessage>>,
 	refresh_from_node: bool,
-	minimum_confirmations: u64,
-) -> Result<(bool, WalletInfo), Error>
+	start_height: Option<u64>,
+	end_height: Option<u64>,
+) -> Result<(bool, Vec<OutputCommitMapping>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (validated, outputs) = retrieve_outputs(
+		wallet_inst,
+		keychain_mask,
+		status_send_channel,
+		true,
+		refresh_from_node,
+		false,
+		None,
+	)?;
+	let start_height = start_height.unwrap_or(0);
+	let end_height = end_height.unwrap_or(std::u64::MAX);
+	let mut rewards: Vec<OutputCommitMapping> = outputs
+		.into_iter()
+		.filter(|m| {
+			m.output.is_foundation
+				&& m.output.height >= start_height
+				&& m.output.height <= end_height
+		})
+		.collect();
+	rewards.sort_by_key(|m| m.output.height);
+	Ok((validated, rewards))
+}
+
+/// Retrieve txs
+pub fn retrieve_txs<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	tx_id: Option<u32>,
+	tx_slate_id: Option<Uuid>,
+) -> Result<(bool, Vec<TxLogEntry>), Error>
 where
 	L: WalletLCProvider<'a, C, K>,
 	C: NodeClient + 'a,
@@ -192,453 +903,2351 @@ where
 
 	wallet_lock!(wallet_inst, w);
 	let parent_key_id = w.parent_key_id();
-	let wallet_info = updater::retrieve_info(&mut **w, &parent_key_id, minimum_confirmations)?;
-	Ok((validated, wallet_info))
+	let mut txs = updater::retrieve_txs(&mut **w, tx_id, tx_slate_id, Some(&parent_key_id), false)?;
+	updater::apply_confirmation_info(&mut **w, keychain_mask, &mut txs, validated)?;
+
+	Ok((validated, txs))
 }
-/// Retrieve payment proof
-pub fn retrieve_payment_proof<'a, L, C, K>(
+
+/// Retrieve a page of txs
+pub fn retrieve_txs_page<'a, L, C, K>(
 	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
 	status_send_channel: &Option<Sender<StatusMessage>>,
 	refresh_from_node: bool,
-	tx_id: Option<u32>,
-	tx_slate_id: Option<Uuid>,
-) -> Result<PaymentProof, Error>
+	cursor: Option<String>,
+	limit: usize,
+) -> Result<(bool, TxLogPage), Error>
 where
 	L: WalletLCProvider<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	if tx_id.is_none() && tx_slate_id.is_none() {
-		return Err(ErrorKind::PaymentProofRetrieval(
-			"Transaction ID or Slate UUID must be specified".into(),
-		)
-		.into());
-	}
-	let mut _validated = false;
+	let mut validated = false;
 	if refresh_from_node {
-		_validated = update_wallet_state(
+		validated = update_wallet_state(
 			wallet_inst.clone(),
 			keychain_mask,
 			status_send_channel,
 			false,
 		)?;
 	}
-	let txs = retrieve_txs(
-		wallet_inst.clone(),
-		keychain_mask,
-		status_send_channel,
-		refresh_from_node,
-		tx_id,
-		tx_slate_id,
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut page = updater::retrieve_txs_page(
+		&mut **w,
+		&parent_key_id,
+		cursor.as_ref().map(|c| c.as_str()),
+		limit,
 	)?;
-	if txs.1.len() != 1 {
-		return Err(ErrorKind::PaymentProofRetrieval("Transaction doesn't exist".into()).into());
+	updater::apply_confirmation_info(&mut **w, keychain_mask, &mut page.txs, validated)?;
+
+	Ok((validated, page))
+}
+
+/// Retrieve the txs created within a time range
+pub fn retrieve_txs_by_time<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	from: Option<DateTime<Utc>>,
+	until: Option<DateTime<Utc>>,
+) -> Result<(bool, Vec<TxLogEntry>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if let (Some(f), Some(u)) = (from, until) {
+		if f > u {
+			return Err(ErrorKind::GenericError(format!(
+				"Start of time range {} is after its end {}",
+				f.to_rfc3339(),
+				u.to_rfc3339()
+			)))?;
+		}
 	}
-	// Pull out all needed fields, returning an error if they're not present
-	let tx = txs.1[0].clone();
-	let proof = match tx.payment_proof {
-		Some(p) => p,
-		None => {
-			return Err(ErrorKind::PaymentProofRetrieval(
-				"Transaction does not contain a payment proof".into(),
-			)
-			.into());
+
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut txs: Vec<TxLogEntry> =
+		updater::retrieve_txs(&mut **w, None, None, Some(&parent_key_id), false)?
+			.into_iter()
+			.filter(|t| from.map(|f| t.creation_ts >= f).unwrap_or(true))
+			.filter(|t| until.map(|u| t.creation_ts < u).unwrap_or(true))
+			.collect();
+	updater::apply_confirmation_info(&mut **w, keychain_mask, &mut txs, validated)?;
+
+	Ok((validated, txs))
+}
+
+/// Retrieve the txs of the active account matching a filter
+pub fn retrieve_txs_by_filter<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	filter: &TxFilter,
+) -> Result<(bool, Vec<TxLogEntry>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut txs = updater::retrieve_txs_by_filter(&mut **w, &parent_key_id, filter)?;
+	updater::apply_confirmation_info(&mut **w, keychain_mask, &mut txs, validated)?;
+
+	Ok((validated, txs))
+}
+
+/// Sent txs of the active account that a send of `amount` to `destination`
+/// would likely repeat
+pub fn check_duplicate_payment<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	amount: u64,
+	destination: &str,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut txs = tx::find_duplicate_payments(&mut **w, &parent_key_id, amount, destination)?;
+	updater::apply_confirmation_info(&mut **w, keychain_mask, &mut txs, false)?;
+	Ok(txs)
+}
+
+/// Retrieve the outputs of the active account with their spend events and history
+pub fn retrieve_output_lineage<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	tx_id: Option<u32>,
+) -> Result<(bool, Vec<OutputLineage>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	Ok((
+		validated,
+		updater::retrieve_output_lineage(&mut **w, &parent_key_id, tx_id)?,
+	))
+}
+
+/// Retrieve the locked outputs of the active account with the transactions
+/// holding their locks
+pub fn get_locked_outputs<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+) -> Result<(bool, Vec<LockedOutput>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	Ok((
+		validated,
+		updater::retrieve_locked_outputs(&mut **w, &parent_key_id)?,
+	))
+}
+
+/// Release the lock on an output of the active account, after checking with
+/// the node that it's still unspent. Locks held by a transaction that was
+/// posted are never released, and locks held by any other pending
+/// transaction only with `include_pending`
+pub fn force_unlock_output<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	commit: &str,
+	include_pending: bool,
+) -> Result<LockedOutput, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut locked = updater::retrieve_locked_outputs(&mut **w, &parent_key_id)?
+		.into_iter()
+		.find(|l| l.output.output.commit.as_ref().map(|c| c.as_str()) == Some(commit))
+		.ok_or_else(|| {
+			ErrorKind::UnlockRefused(format!("no locked output {} in this account", commit))
+		})?;
+
+	if let Some(tx) = locked.locked_by.as_ref() {
+		if !locked.stale && !tx.confirmed {
+			if tx.post_state.is_some() {
+				return Err(ErrorKind::UnlockRefused(format!(
+					"transaction {} holding the lock was posted, wait for it to confirm or expire",
+					tx.id
+				))
+				.into());
+			}
+			if !include_pending {
+				return Err(ErrorKind::UnlockRefused(format!(
+					"transaction {} holding the lock is still pending, cancel it instead",
+					tx.id
+				))
+				.into());
+			}
 		}
-	};
-	let amount = if tx.amount_credited >= tx.amount_debited {
-		tx.amount_credited - tx.amount_debited
-	} else {
-		let fee = match tx.fee {
-			Some(f) => f,
-			None => 0,
-		};
-		tx.amount_debited - tx.amount_credited - fee
-	};
-	let excess = match tx.kernel_excess {
-		Some(e) => e,
-		None => {
-			return Err(ErrorKind::PaymentProofRetrieval(
-				"Transaction does not contain kernel excess".into(),
-			)
-			.into());
+	}
+
+	// an output spent on chain is only waiting for the next refresh
+	let on_chain = w
+		.w2n_client()
+		.get_outputs_from_node(vec![locked.output.commit])?;
+	if !on_chain.contains_key(&locked.output.commit) {
+		return Err(ErrorKind::UnlockRefused(format!(
+			"output {} is not unspent on chain, refresh the wallet instead",
+			commit
+		))
+		.into());
+	}
+
+	let mut batch = w.batch(keychain_mask)?;
+	locked.output.output.status = OutputStatus::Unspent;
+	locked.output.output.spent_by_tx = None;
+	batch.save(locked.output.output.clone())?;
+	batch.commit()?;
+	warn!(
+		"Force unlocked output {}, locked by transaction {:?}",
+		commit,
+		locked.locked_by.as_ref().map(|t| t.id)
+	);
+	Ok(locked)
+}
+
+/// Override the status of an output of the active account, to fix by hand a
+/// rare divergence between the wallet and the chain, such as an output stuck
+/// locked. The change, along with the reason given for it, is recorded in the
+/// audit trail
+pub fn set_output_status<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	commit: &str,
+	status: OutputStatus,
+	reason: &str,
+) -> Result<OutputData, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let refused = |msg: String| -> Error { ErrorKind::OutputStatusOverride(msg).into() };
+	if reason.trim().is_empty() {
+		return Err(refused("a reason must be given".to_owned()));
+	}
+	match status {
+		OutputStatus::Unconfirmed
+		| OutputStatus::Unspent
+		| OutputStatus::Locked
+		| OutputStatus::Spent => {}
+		s => return Err(refused(format!("outputs can't be set {}", s))),
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut output = w
+		.iter()
+		.find(|o| {
+			o.root_key_id == parent_key_id && o.commit.as_ref().map(|c| c.as_str()) == Some(commit)
+		})
+		.ok_or_else(|| refused(format!("no output {} in this account", commit)))?;
+	let before = output.status.clone();
+	if before == status {
+		return Err(refused(format!("output {} is already {}", commit, status)));
+	}
+
+	output.status = status.clone();
+	output.missing_since = None;
+	match status {
+		OutputStatus::Spent => {
+			if output.spent_height.is_none() {
+				output.spent_height = Some(w.last_confirmed_height()?);
+			}
 		}
-	};
-	let r_sig = match proof.receiver_signature {
-		Some(e) => e,
-		None => {
-			return Err(ErrorKind::PaymentProofRetrieval(
-				"Proof does not contain receiver signature ".into(),
-			)
-			.into());
+		OutputStatus::Locked => output.spent_height = None,
+		_ => {
+			output.spent_height = None;
+			output.spent_by_tx = None;
 		}
+	}
+
+	let entry = AuditEntry {
+		id: Uuid::new_v4(),
+		timestamp: Utc::now(),
+		action: "set_output_status".to_owned(),
+		target: commit.to_owned(),
+		before: before.to_string(),
+		after: status.to_string(),
+		reason: reason.to_owned(),
 	};
-	let s_sig = match proof.sender_signature {
-		Some(e) => e,
-		None => {
-			return Err(ErrorKind::PaymentProofRetrieval(
-				"Proof does not contain sender signature ".into(),
-			)
-			.into());
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save(output.clone())?;
+	batch.save_audit_entry(&entry)?;
+	batch.commit()?;
+	warn!(
+		"Status of output {} set from {} to {} by hand: {}",
+		commit, before, status, reason
+	);
+	Ok(output)
+}
+
+/// The audit trail of changes made to the wallet by hand, oldest first
+pub fn retrieve_audit_log<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<Vec<AuditEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	// Test keychain mask, to keep API consistent
+	let _ = w.keychain(keychain_mask)?;
+	Ok(w.audit_iter().collect())
+}
+
+/// Add a tag to, or remove it from, outputs of the active account given by
+/// their commitments, returning the updated outputs. Spent outputs can't be
+/// tagged
+pub fn update_output_tags<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	commits: &[String],
+	tag: &str,
+	add: bool,
+) -> Result<Vec<OutputCommitMapping>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if tag.is_empty() || tag.len() > OUTPUT_TAG_MAX_LEN {
+		return Err(ErrorKind::OutputTag(format!(
+			"tags must be between 1 and {} characters",
+			OUTPUT_TAG_MAX_LEN
+		)))?;
+	}
+	if tag.chars().any(|c| c.is_whitespace() || c.is_control()) {
+		return Err(ErrorKind::OutputTag(format!(
+			"tag '{}' contains whitespace or control characters",
+			tag
+		)))?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let outputs = updater::retrieve_outputs(&mut **w, false, false, None, Some(&parent_key_id))?;
+	let mut updated = vec![];
+	for commit in commits {
+		let mut m = outputs
+			.iter()
+			.find(|m| m.output.commit.as_ref() == Some(commit))
+			.cloned()
+			.ok_or_else(|| {
+				ErrorKind::OutputTag(format!("no unspent output {} in this account", commit))
+			})?;
+		match add {
+			true if !m.output.has_tag(tag) => m.output.tags.push(tag.to_owned()),
+			false => m.output.tags.retain(|t| t != tag),
+			_ => {}
+		}
+		updated.push(m);
+	}
+
+	let mut batch = w.batch(keychain_mask)?;
+	for m in updated.iter() {
+		batch.save(m.output.clone())?;
+	}
+	batch.commit()?;
+	Ok(updated)
+}
+
+/// Reserve an amount of the active account's balance. The account's unspent
+/// and unconfirmed change outputs, less what's already reserved, must cover it
+pub fn reserve_funds<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	amount: u64,
+	label: Option<String>,
+) -> Result<Reservation, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if amount == 0 {
+		return Err(ErrorKind::Reservation(
+			"amount to reserve must be greater than 0".to_owned(),
+		))?;
+	}
+	let label = label.map(|mut l| {
+		l.truncate(USER_MESSAGE_MAX_LEN);
+		l
+	});
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let current_height = w.last_confirmed_height()?;
+	let balance = tx::reservable_balance(&mut **w, current_height, &parent_key_id);
+	let reserved: u64 = w.reservation_iter(&parent_key_id).map(|r| r.amount).sum();
+	let unreserved = balance.saturating_sub(reserved);
+	if unreserved < amount {
+		return Err(ErrorKind::Reservation(format!(
+			"only {} of the account's {} is unreserved",
+			amount_to_hr_string(unreserved, false),
+			amount_to_hr_string(balance, false),
+		)))?;
+	}
+
+	let reservation = Reservation {
+		id: Uuid::new_v4(),
+		parent_key_id,
+		amount,
+		label,
+		creation_ts: Utc::now(),
+	};
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save_reservation(&reservation)?;
+	batch.commit()?;
+	Ok(reservation)
+}
+
+/// Release a reservation of the active account
+pub fn release_reservation<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	id: &Uuid,
+) -> Result<Reservation, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let reservation = w
+		.reservation_iter(&parent_key_id)
+		.find(|r| r.id == *id)
+		.ok_or_else(|| ErrorKind::Reservation(format!("no reservation {} in this account", id)))?;
+	let mut batch = w.batch(keychain_mask)?;
+	batch.delete_reservation(&reservation)?;
+	batch.commit()?;
+	Ok(reservation)
+}
+
+/// The reservations of the active account, oldest first
+pub fn retrieve_reservations<'a, T: ?Sized, C, K>(w: &mut T) -> Result<Vec<Reservation>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let parent_key_id = w.parent_key_id();
+	let mut reservations = w.reservation_iter(&parent_key_id).collect::<Vec<_>>();
+	reservations.sort_by_key(|r| r.creation_ts);
+	Ok(reservations)
+}
+
+/// Retrieve the unconfirmed coinbase outputs removed by the wallet
+pub fn retrieve_cleaned_coinbase<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+) -> Result<(bool, Vec<CleanedCoinbase>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	Ok((
+		validated,
+		updater::retrieve_cleaned_coinbase(&mut **w, &parent_key_id)?,
+	))
+}
+
+/// Retrieve mining rewards confirmed in blocks within the given height range,
+/// totalled by the proof of work algorithm of the rewarded blocks and
+/// optionally limited to a single algorithm
+pub fn retrieve_mining_rewards<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	pow_type: Option<PoWType>,
+	start_height: Option<u64>,
+	end_height: Option<u64>,
+) -> Result<(bool, Vec<MiningRewardSummary>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (validated, txs) = retrieve_txs(
+		wallet_inst,
+		keychain_mask,
+		status_send_channel,
+		refresh_from_node,
+		None,
+		None,
+	)?;
+	let start_height = start_height.unwrap_or(0);
+	let end_height = end_height.unwrap_or(std::u64::MAX);
+	let mut rewards: Vec<MiningRewardSummary> = vec![];
+	for tx in txs {
+		if tx.tx_type != TxLogEntryType::ConfirmedCoinbase {
+			continue;
+		}
+		match tx.confirmation_height {
+			Some(h) if h >= start_height && h <= end_height => {}
+			_ => continue,
+		}
+		if pow_type.is_some() && tx.pow_type != pow_type {
+			continue;
+		}
+		match rewards.iter_mut().find(|r| r.pow_type == tx.pow_type) {
+			Some(r) => {
+				r.num_blocks += 1;
+				r.amount += tx.amount_credited.freemans();
+			}
+			None => rewards.push(MiningRewardSummary {
+				pow_type: tx.pow_type,
+				num_blocks: 1,
+				amount: tx.amount_credited.freemans(),
+			}),
+		}
+	}
+	Ok((validated, rewards))
+}
+
+/// Retrieve summary info, optionally with a breakdown by output source
+pub fn retrieve_summary_info<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	minimum_confirmations: u64,
+	include_breakdown: bool,
+) -> Result<(bool, WalletInfo), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let wallet_info = updater::retrieve_info(
+		&mut **w,
+		&parent_key_id,
+		minimum_confirmations,
+		include_breakdown,
+	)?;
+	Ok((validated, wallet_info))
+}
+
+/// Retrieve balance history
+pub fn get_balance_history<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	from_height: u64,
+	to_height: Option<u64>,
+	step: u64,
+) -> Result<(bool, Vec<BalanceHistoryEntry>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut validated = false;
+	if refresh_from_node {
+		validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let to_height = match to_height {
+		Some(h) => h,
+		None => w.last_confirmed_height()?,
+	};
+	let history =
+		updater::retrieve_balance_history(&mut **w, &parent_key_id, from_height, to_height, step)?;
+	Ok((validated, history))
+}
+
+/// Retrieve payment proof
+pub fn retrieve_payment_proof<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	tx_id: Option<u32>,
+	tx_slate_id: Option<Uuid>,
+) -> Result<PaymentProof, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if tx_id.is_none() && tx_slate_id.is_none() {
+		return Err(ErrorKind::PaymentProofRetrieval(
+			"Transaction ID or Slate UUID must be specified".into(),
+		)
+		.into());
+	}
+	let mut _validated = false;
+	if refresh_from_node {
+		_validated = update_wallet_state(
+			wallet_inst.clone(),
+			keychain_mask,
+			status_send_channel,
+			false,
+		)?;
+	}
+	let txs = retrieve_txs(
+		wallet_inst.clone(),
+		keychain_mask,
+		status_send_channel,
+		refresh_from_node,
+		tx_id,
+		tx_slate_id,
+	)?;
+	if txs.1.len() != 1 {
+		return Err(ErrorKind::PaymentProofRetrieval("Transaction doesn't exist".into()).into());
+	}
+	payment_proof_from_entry(&txs.1[0])
+}
+
+/// Package the payment proof stored with a tx log entry, returning an error if
+/// any of the needed fields isn't present
+fn payment_proof_from_entry(tx: &TxLogEntry) -> Result<PaymentProof, Error> {
+	let tx = tx.clone();
+	let proof = match tx.payment_proof {
+		Some(p) => p,
+		None => {
+			return Err(ErrorKind::PaymentProofRetrieval(
+				"Transaction does not contain a payment proof".into(),
+			)
+			.into());
+		}
+	};
+	let amount = if tx.amount_credited >= tx.amount_debited {
+		(tx.amount_credited - tx.amount_debited).freemans()
+	} else {
+		let fee = match tx.fee {
+			Some(f) => f,
+			None => 0,
+		};
+		(tx.amount_debited - tx.amount_credited).freemans() - fee
+	};
+	let excess = match tx.kernel_excess {
+		Some(e) => e,
+		None => {
+			return Err(ErrorKind::PaymentProofRetrieval(
+				"Transaction does not contain kernel excess".into(),
+			)
+			.into());
+		}
+	};
+	let r_sig = match proof.receiver_signature {
+		Some(e) => e,
+		None => {
+			return Err(ErrorKind::PaymentProofRetrieval(
+				"Proof does not contain receiver signature ".into(),
+			)
+			.into());
+		}
+	};
+	let s_sig = match proof.sender_signature {
+		Some(e) => e,
+		None => {
+			return Err(ErrorKind::PaymentProofRetrieval(
+				"Proof does not contain sender signature ".into(),
+			)
+			.into());
+		}
+	};
+	Ok(PaymentProof {
+		amount: amount,
+		excess: excess,
+		recipient_address: address::onion_v3_from_pubkey(&proof.receiver_address)?,
+		recipient_sig: r_sig,
+		sender_address: address::onion_v3_from_pubkey(&proof.sender_address)?,
+		sender_sig: s_sig,
+	})
+}
+
+/// Retrieve a transaction of the active account with its inputs and outputs,
+/// stored body, kernel excess, payment proof and the other entries recorded
+/// for its slate
+pub fn get_tx_details<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	refresh_from_node: bool,
+	tx_id: u32,
+) -> Result<(bool, TxDetails), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (validated, txs) = retrieve_txs(
+		wallet_inst.clone(),
+		keychain_mask,
+		status_send_channel,
+		refresh_from_node,
+		Some(tx_id),
+		None,
+	)?;
+	let tx = match txs.into_iter().next() {
+		Some(t) => t,
+		None => return Err(ErrorKind::TransactionDoesntExist(tx_id.to_string()).into()),
+	};
+
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	let mut inputs = vec![];
+	let mut outputs = vec![];
+	// the spending transaction takes over an output's tx log entry, so outputs
+	// are matched on any of their recorded states
+	for l in updater::retrieve_output_lineage(&mut **w, &parent_key_id, Some(tx_id))? {
+		let spent = l.output.output.spent_by_tx == Some(tx_id)
+			|| l.history.iter().any(|o| o.spent_by_tx == Some(tx_id));
+		match spent {
+			true => inputs.push(l.output),
+			false => outputs.push(l.output),
+		}
+	}
+
+	let stored_tx = w.get_stored_tx(&tx)?;
+	let kernel_excess = tx.kernel_excess.or_else(|| {
+		stored_tx
+			.as_ref()
+			.and_then(|t| t.kernels().first().map(|k| k.excess))
+	});
+	let slate_history = match tx.tx_slate_id {
+		Some(id) => w
+			.tx_log_entries_by_slate_id(&id)?
+			.into_iter()
+			.filter(|t| !(t.id == tx.id && t.parent_key_id == tx.parent_key_id))
+			.collect(),
+		None => vec![],
+	};
+
+	Ok((
+		validated,
+		TxDetails {
+			payment_proof: payment_proof_from_entry(&tx).ok(),
+			tx,
+			inputs,
+			outputs,
+			stored_tx: stored_tx.map(TransactionV3::from),
+			kernel_excess,
+			slate_history,
+		},
+	))
+}
+
+/// Initiate tx as sender
+pub fn init_send_tx<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	args: InitTxArgs,
+	use_test_rng: bool,
+) -> Result<Slate, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut args = args;
+	if let Some(units) = args.units.take() {
+		args.amount = Amount::from_units(args.amount.freemans(), units)?;
+	}
+	let template = args.template.take();
+	if let Some(t) = template.as_ref() {
+		args.src_acct_name = t.src_acct_name.clone();
+		args.amount = Amount::from_freemans(t.amount);
+		args.minimum_confirmations = t.minimum_confirmations;
+		args.max_outputs = t.max_outputs;
+		args.num_change_outputs = t.num_change_outputs;
+		args.selection_strategy_is_use_all = t.selection_strategy_is_use_all;
+		args.selection_tag = t.selection_tag.clone();
+		args.message = t.message.clone();
+		args.ttl_blocks = t.ttl_blocks;
+		args.payment_proof_recipient_address = t.payment_proof_recipient_address;
+		args.estimate_only = Some(false);
+		args.send_max = Some(false);
+	}
+	let request = args.payment_request.take();
+	if let Some(r) = request.as_ref() {
+		if template.is_some() {
+			return Err(ErrorKind::PaymentRequest(
+				"a request can't be paid with a template".to_owned(),
+			))?;
+		}
+		check_payment_request(&mut *w, r, args.amount)?;
+		args.amount = r.amount;
+		args.message = args.message.take().or_else(|| r.memo.clone());
+		args.payment_proof_recipient_address = Some(r.proof_address);
+		args.send_max = Some(false);
+	}
+
+	let parent_key_id = match args.src_acct_name {
+		Some(d) => {
+			let pm = w.get_acct_path(d)?;
+			match pm {
+				Some(p) => p.path,
+				None => w.parent_key_id(),
+			}
+		}
+		None => w.parent_key_id(),
+	};
+
+	if let Some(t) = template {
+		tx::verify_tx_template(&mut *w, keychain_mask, &t, &parent_key_id)?;
+	}
+
+	let send_max = args.send_max.unwrap_or(false);
+	if send_max {
+		tx::prepare_send_max(&mut *w, keychain_mask, &mut args, &parent_key_id)?;
+	}
+
+	let destination = args
+		.destination
+		.take()
+		.or_else(|| args.send_args.as_ref().map(|s| s.dest.clone()));
+	// refuse what's likely a retry of a send that went through
+	let checked = !args.estimate_only.unwrap_or(false) && !args.allow_duplicate.unwrap_or(false);
+	if let (true, Some(d)) = (checked, destination.as_ref()) {
+		let earlier =
+			tx::find_duplicate_payments(&mut *w, &parent_key_id, args.amount.freemans(), d)?;
+		if let Some(t) = earlier.last() {
+			return Err(ErrorKind::DuplicatePayment {
+				id: t.id,
+				destination: d.clone(),
+			})?;
+		}
+	}
+
+	let message = match args.message {
+		Some(mut m) => {
+			m.truncate(USER_MESSAGE_MAX_LEN);
+			Some(m)
+		}
+		None => None,
+	};
+
+	let mut slate = tx::new_tx_slate(&mut *w, args.amount, 2, use_test_rng, args.ttl_blocks)?;
+	if let Some(r) = request.as_ref() {
+		slate.id = r.id;
+	}
+
+	// if we just want to estimate, don't save a context, just send the results
+	// back
+	if let Some(true) = args.estimate_only {
+		let (total, fee, fee_breakdown) = tx::estimate_send_tx(
+			&mut *w,
+			keychain_mask,
+			args.amount.freemans(),
+			args.minimum_confirmations,
+			args.max_outputs as usize,
+			args.num_change_outputs as usize,
+			args.selection_strategy_is_use_all,
+			args.selection_tag.as_ref().map(|t| t.as_str()),
+			&parent_key_id,
+		)?;
+		tx::check_reservations(
+			&mut *w,
+			slate.height,
+			args.amount.freemans() + fee,
+			args.reservation_id,
+			&parent_key_id,
+		)?;
+		slate.amount = Amount::from_freemans(total);
+		slate.fee = fee;
+		slate.fee_breakdown = Some(fee_breakdown);
+		return Ok(slate);
+	}
+
+	// any part of the fee asked of the receiver is left in the change
+	slate.receiver_fee = args.receiver_fee;
+	let mut context = tx::add_inputs_to_slate(
+		&mut *w,
+		keychain_mask,
+		&mut slate,
+		args.minimum_confirmations,
+		args.max_outputs as usize,
+		args.num_change_outputs as usize,
+		args.selection_strategy_is_use_all,
+		args.selection_tag.as_ref().map(|t| t.as_str()),
+		&parent_key_id,
+		0,
+		message,
+		true,
+		use_test_rng,
+	)?;
+	// outputs confirmed since the amount was worked out would be change
+	if send_max && !context.get_outputs().is_empty() {
+		return Err(ErrorKind::GenericError(
+			"Spendable outputs changed while sending max, please try again".to_owned(),
+		))?;
+	}
+	let drawn = tx::check_reservations(
+		&mut *w,
+		slate.height,
+		slate.amount.freemans() + slate.fee,
+		args.reservation_id,
+		&parent_key_id,
+	)?;
+
+	// Payment Proof, add addresses to slate and save address
+	// TODO: Note we only use single derivation path for now,
+	// probably want to allow sender to specify which one
+	let deriv_path = 0u32;
+
+	if let Some(a) = args.payment_proof_recipient_address {
+		let k = w.keychain(keychain_mask)?;
+
+		let sec_addr_key = address::address_from_derivation_path(&k, &parent_key_id, deriv_path)?;
+		let sender_address = address::ed25519_keypair(&sec_addr_key)?.1;
+
+		slate.payment_proof = Some(PaymentInfo {
+			sender_address,
+			receiver_address: a,
+			receiver_signature: None,
+		});
+
+		context.payment_proof_derivation_index = Some(deriv_path);
+	}
+	context.destination = destination;
+
+	// Save the aggsig context in our DB for when we
+	// recieve the transaction back
+	{
+		let mut batch = w.batch(keychain_mask)?;
+		batch.save_private_context(slate.id.as_bytes(), 0, &context)?;
+		if let Some(r) = drawn {
+			batch.delete_reservation(&r)?;
+		}
+		batch.commit()?;
+	}
+	if let Some(v) = args.target_slate_version {
+		slate.version_info.orig_version = v;
+	}
+
+	Ok(slate)
+}
+
+/// Create a template of a transaction for review, without locking any outputs
+pub fn create_tx_template<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	args: InitTxArgs,
+) -> Result<TxTemplate, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut args = args;
+	if let Some(units) = args.units.take() {
+		args.amount = Amount::from_units(args.amount.freemans(), units)?;
+	}
+	let parent_key_id = match args.src_acct_name.clone() {
+		Some(d) => {
+			let pm = w.get_acct_path(d)?;
+			match pm {
+				Some(p) => p.path,
+				None => w.parent_key_id(),
+			}
+		}
+		None => w.parent_key_id(),
+	};
+
+	if let Some(m) = args.message.as_mut() {
+		m.truncate(USER_MESSAGE_MAX_LEN);
+	}
+
+	if args.send_max.unwrap_or(false) {
+		tx::prepare_send_max(&mut *w, keychain_mask, &mut args, &parent_key_id)?;
+	}
+
+	tx::create_tx_template(&mut *w, keychain_mask, &args, &parent_key_id)
+}
+
+/// Initiate a transaction as the recipient (invoicing)
+pub fn issue_invoice_tx<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	args: IssueInvoiceTxArgs,
+	use_test_rng: bool,
+) -> Result<Slate, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let parent_key_id = match args.dest_acct_name {
+		Some(d) => {
+			let pm = w.get_acct_path(d)?;
+			match pm {
+				Some(p) => p.path,
+				None => w.parent_key_id(),
+			}
+		}
+		None => w.parent_key_id(),
+	};
+
+	let message = match args.message {
+		Some(mut m) => {
+			m.truncate(USER_MESSAGE_MAX_LEN);
+			Some(m)
+		}
+		None => None,
+	};
+
+	let amount = match args.units {
+		Some(units) => Amount::from_units(args.amount.freemans(), units)?,
+		None => args.amount,
+	};
+	let mut slate = tx::new_tx_slate(&mut *w, amount, 2, use_test_rng, None)?;
+	let context = tx::add_output_to_slate(
+		&mut *w,
+		keychain_mask,
+		&mut slate,
+		&parent_key_id,
+		1,
+		message,
+		true,
+		None,
+		use_test_rng,
+	)?;
+
+	// Save the aggsig context in our DB for when we
+	// recieve the transaction back
+	{
+		let mut batch = w.batch(keychain_mask)?;
+		batch.save_private_context(slate.id.as_bytes(), 1, &context)?;
+		batch.commit()?;
+	}
+
+	if let Some(v) = args.target_slate_version {
+		slate.version_info.orig_version = v;
+	}
+
+	Ok(slate)
+}
+
+/// Issue an invoice through the merchant API, recording its memo with the
+/// transaction so it can be returned whenever the invoice is looked up
+pub fn create_merchant_invoice<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	amount: u64,
+	memo: Option<String>,
+	use_test_rng: bool,
+) -> Result<MerchantInvoice, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if amount == 0 {
+		return Err(ErrorKind::Amount("an invoice must be for more than zero".to_owned()).into());
+	}
+	let args = IssueInvoiceTxArgs::builder()
+		.amount(Amount::from_freemans(amount))
+		.message(memo)
+		.build()?;
+	let slate = issue_invoice_tx(&mut *w, keychain_mask, args, use_test_rng)?;
+	tx::update_message(&mut *w, keychain_mask, &slate)?;
+
+	let parent_key_id = w.parent_key_id();
+	let txs = updater::retrieve_txs(&mut *w, None, Some(slate.id), Some(&parent_key_id), false)?;
+	let mut invoice = match txs.first() {
+		Some(t) => MerchantInvoice::from_tx_log(t)?,
+		None => return Err(ErrorKind::TransactionDoesntExist(slate.id.to_string()).into()),
+	};
+	invoice.slate = Some(VersionedSlate::into_version(slate, SlateVersion::V3));
+	Ok(invoice)
+}
+
+/// Look up an invoice issued through the merchant API, refreshing its
+/// payment status from the node if asked
+pub fn retrieve_merchant_invoice<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	refresh_from_node: bool,
+	id: Uuid,
+) -> Result<MerchantInvoice, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (_, txs) = retrieve_txs(
+		wallet_inst,
+		keychain_mask,
+		&None,
+		refresh_from_node,
+		None,
+		Some(id),
+	)?;
+	match txs.first() {
+		Some(t) => MerchantInvoice::from_tx_log(t),
+		None => Err(ErrorKind::TransactionDoesntExist(id.to_string()).into()),
+	}
+}
+
+/// Balances of the accounts a wallet's load is sharded across, in the
+/// order given
+pub fn shard_balances<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	shards: &[String],
+	minimum_confirmations: u64,
+) -> Result<Vec<ShardBalance>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if shards.is_empty() {
+		return Err(ErrorKind::Sharding("no shard accounts given".to_owned()).into());
+	}
+	let mut balances = vec![];
+	for account in shards {
+		let parent_key_id = match w.get_acct_path(account.clone())? {
+			Some(p) => p.path,
+			None => return Err(ErrorKind::UnknownAccountLabel(account.clone()).into()),
+		};
+		let info = updater::retrieve_info(&mut *w, &parent_key_id, minimum_confirmations, false)?;
+		balances.push(ShardBalance {
+			account: account.clone(),
+			amount_currently_spendable: info.amount_currently_spendable,
+			amount_awaiting_confirmation: info.amount_awaiting_confirmation,
+			amount_locked: info.amount_locked,
+		});
+	}
+	Ok(balances)
+}
+
+/// The shard account to receive into: the one holding the least, so that
+/// incoming funds spread evenly
+pub fn select_receive_shard<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	shards: &[String],
+	minimum_confirmations: u64,
+) -> Result<String, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let balances = shard_balances(&mut *w, shards, minimum_confirmations)?;
+	let shard = balances
+		.into_iter()
+		.min_by_key(|b| b.amount_currently_spendable + b.amount_awaiting_confirmation)
+		.unwrap();
+	Ok(shard.account)
+}
+
+/// The shard account to send from: the one with the most to spend, whose
+/// outputs are the least likely to be tied up by other sends in progress
+pub fn select_send_shard<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	shards: &[String],
+	minimum_confirmations: u64,
+) -> Result<String, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let balances = shard_balances(&mut *w, shards, minimum_confirmations)?;
+	let shard = balances
+		.into_iter()
+		.max_by_key(|b| b.amount_currently_spendable)
+		.unwrap();
+	Ok(shard.account)
+}
+
+// the move evening out the richest and poorest shards, half the difference
+// between what they can spend, if that's at least the given amount
+fn plan_shard_rebalance(
+	balances: &[ShardBalance],
+	min_amount: u64,
+) -> Option<(String, String, u64)> {
+	let richest = balances
+		.iter()
+		.max_by_key(|b| b.amount_currently_spendable)?;
+	let poorest = balances
+		.iter()
+		.min_by_key(|b| b.amount_currently_spendable)?;
+	let amount =
+		(richest.amount_currently_spendable - poorest.amount_currently_spendable).freemans() / 2;
+	if amount == 0 || amount < min_amount {
+		return None;
+	}
+	Some((richest.account.clone(), poorest.account.clone(), amount))
+}
+
+/// Even out the richest and poorest of the shard accounts with a self-send
+/// of half the difference between what they can spend, which is then
+/// posted. Nothing is sent if that would move less than `min_amount`.
+pub fn rebalance_shards<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	shards: &[String],
+	minimum_confirmations: u64,
+	min_amount: u64,
+	use_test_rng: bool,
+) -> Result<Option<Slate>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let slate = {
+		wallet_lock!(wallet_inst, w);
+		let balances = shard_balances(&mut **w, shards, minimum_confirmations)?;
+		let (from, to, amount) = match plan_shard_rebalance(&balances, min_amount) {
+			Some(p) => p,
+			None => return Ok(None),
+		};
+		let args = InitTxArgs::builder()
+			.src_acct_name(from.clone())
+			.amount(Amount::from_freemans(amount))
+			.minimum_confirmations(minimum_confirmations)
+			.strategy(SelectionStrategy::Smallest)
+			.build()?;
+		let slate = init_send_tx(&mut **w, keychain_mask, args, use_test_rng)?;
+		tx_lock_outputs(&mut **w, keychain_mask, &slate, 0)?;
+		let slate = foreign::receive_tx(
+			&mut **w,
+			keychain_mask,
+			&slate,
+			Some(&to),
+			None,
+			None,
+			use_test_rng,
+		)?;
+		info!(
+			"Rebalancing {} from shard {} to {}",
+			amount_to_hr_string(amount, false),
+			from,
+			to
+		);
+		finalize_tx(&mut **w, keychain_mask, &slate)?
+	};
+	post_and_record_tx(wallet_inst, keychain_mask, &slate.tx, false)?;
+	Ok(Some(slate))
+}
+
+/// Receive an invoice tx, essentially adding inputs to whatever
+/// output was specified
+pub fn process_invoice_tx<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	slate: &Slate,
+	args: InitTxArgs,
+	use_test_rng: bool,
+) -> Result<Slate, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut ret_slate = slate.clone();
+	check_ttl(w, &ret_slate)?;
+	// the amount is optional here, as the invoice gives it
+	let expected_amount = match args.units {
+		Some(units) => Amount::from_units(args.amount.freemans(), units)?,
+		None => args.amount,
+	};
+	check_invoice(&ret_slate, expected_amount)?;
+	let parent_key_id = match args.src_acct_name {
+		Some(d) => {
+			let pm = w.get_acct_path(d.to_owned())?;
+			match pm {
+				Some(p) => p.path,
+				None => w.parent_key_id(),
+			}
+		}
+		None => w.parent_key_id(),
+	};
+	// Don't do this multiple times
+	let tx = updater::retrieve_txs(
+		&mut *w,
+		None,
+		Some(ret_slate.id),
+		Some(&parent_key_id),
+		use_test_rng,
+	)?;
+	for t in &tx {
+		if t.tx_type == TxLogEntryType::TxSent {
+			return Err(ErrorKind::TransactionAlreadyReceived(ret_slate.id.to_string()).into());
+		}
+	}
+
+	let message = match args.message {
+		Some(mut m) => {
+			m.truncate(USER_MESSAGE_MAX_LEN);
+			Some(m)
+		}
+		None => None,
+	};
+
+	// update slate current height
+	ret_slate.height = updater::current_height(&mut *w)?.0;
+
+	// update ttl if desired
+	if let Some(b) = args.ttl_blocks {
+		ret_slate.ttl_cutoff_height = Some(ret_slate.height + b);
+	}
+
+	let context = tx::add_inputs_to_slate(
+		&mut *w,
+		keychain_mask,
+		&mut ret_slate,
+		args.minimum_confirmations,
+		args.max_outputs as usize,
+		args.num_change_outputs as usize,
+		args.selection_strategy_is_use_all,
+		args.selection_tag.as_ref().map(|t| t.as_str()),
+		&parent_key_id,
+		0,
+		message,
+		false,
+		use_test_rng,
+	)?;
+	// the fee depends on our own inputs and change, so is only known now
+	let payer_fee = ret_slate.fee - ret_slate.receiver_fee.unwrap_or(0);
+	if let Some(max) = args.max_invoice_fee {
+		if payer_fee > max {
+			return Err(ErrorKind::PolicyRefused(format!(
+				"paying the invoice costs a fee of {}, more than the {} allowed",
+				amount_to_hr_string(payer_fee, false),
+				amount_to_hr_string(max, false)
+			))
+			.into());
+		}
+	}
+	let drawn = tx::check_reservations(
+		&mut *w,
+		ret_slate.height,
+		ret_slate.amount.freemans() + ret_slate.fee,
+		args.reservation_id,
+		&parent_key_id,
+	)?;
+
+	// Save the aggsig context in our DB for when we
+	// recieve the transaction back
+	{
+		let mut batch = w.batch(keychain_mask)?;
+		batch.save_private_context(slate.id.as_bytes(), 0, &context)?;
+		if let Some(r) = drawn {
+			batch.delete_reservation(&r)?;
+		}
+		batch.commit()?;
+	}
+
+	if let Some(v) = args.target_slate_version {
+		ret_slate.version_info.orig_version = v;
+	}
+
+	Ok(ret_slate)
+}
+
+/// Checks a payment request can be paid: that it's signed by its proof
+/// address, hasn't expired, is for the amount expected, if one is, and
+/// hasn't been paid already, unless that payment was cancelled
+fn check_payment_request<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	request: &PaymentRequest,
+	expected_amount: Amount,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	request.verify()?;
+	if request.is_expired() {
+		return Err(ErrorKind::PaymentRequest(format!(
+			"request {} expired at {}",
+			request.id, request.expires_at
+		)))?;
+	}
+	if !expected_amount.is_zero() && request.amount != expected_amount {
+		return Err(ErrorKind::PolicyRefused(format!(
+			"the request is for {}, not the {} expected",
+			amount_to_hr_string(request.amount.freemans(), false),
+			amount_to_hr_string(expected_amount.freemans(), false)
+		)))?;
+	}
+	let paid = w
+		.tx_log_iter()
+		.any(|t| t.tx_slate_id == Some(request.id) && t.tx_type != TxLogEntryType::TxSentCancelled);
+	if paid {
+		return Err(ErrorKind::PaymentRequest(format!(
+			"request {} has already been paid",
+			request.id
+		)))?;
+	}
+	Ok(())
+}
+
+/// Checks an invoice is as another wallet issues them, holding the invoicer's
+/// output alone with the fee left for the payer to set, and that it's for the
+/// amount expected, if one is
+fn check_invoice(slate: &Slate, expected_amount: Amount) -> Result<(), Error> {
+	slate.verify_structure()?;
+	let invalid = |reason: String| -> Error { ErrorKind::InvalidSlate(reason).into() };
+	if !slate.tx.inputs().is_empty() {
+		return Err(invalid(format!(
+			"{} inputs in the invoice, expected none",
+			slate.tx.inputs().len()
+		)));
+	}
+	// the fee is worked out for the invoicer's output alone
+	if slate.tx.outputs().len() != 1 {
+		return Err(invalid(format!(
+			"{} outputs in the invoice, expected the invoicer's one",
+			slate.tx.outputs().len()
+		)));
+	}
+	if slate.fee != 0 {
+		return Err(invalid(format!(
+			"the invoice sets a fee of {}, which is the payer's to set",
+			amount_to_hr_string(slate.fee, false)
+		)));
+	}
+	if !expected_amount.is_zero() && slate.amount != expected_amount {
+		return Err(ErrorKind::PolicyRefused(format!(
+			"the invoice is for {}, not the {} expected",
+			amount_to_hr_string(slate.amount.freemans(), false),
+			amount_to_hr_string(expected_amount.freemans(), false)
+		))
+		.into());
+	}
+	Ok(())
+}
+
+/// Lock sender outputs
+pub fn tx_lock_outputs<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	slate: &Slate,
+	participant_id: usize,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let context = w.get_private_context(keychain_mask, slate.id.as_bytes(), participant_id)?;
+	selection::lock_tx_context(&mut *w, keychain_mask, slate, &context)
+}
+
+/// Finalize slate
+pub fn finalize_tx<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	slate: &Slate,
+) -> Result<Slate, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut sl = slate.clone();
+	check_ttl(w, &sl)?;
+	let context = w.get_private_context(keychain_mask, sl.id.as_bytes(), 0)?;
+	let parent_key_id = w.parent_key_id();
+	tx::complete_tx(&mut *w, keychain_mask, &mut sl, 0, &context)?;
+	tx::verify_slate_payment_proof(&mut *w, keychain_mask, &parent_key_id, &context, &sl)?;
+	tx::update_stored_tx(&mut *w, keychain_mask, &context, &mut sl, false)?;
+	tx::update_message(&mut *w, keychain_mask, &mut sl)?;
+	{
+		let mut batch = w.batch(keychain_mask)?;
+		batch.delete_private_context(sl.id.as_bytes(), 0)?;
+		batch.commit()?;
+	}
+	Ok(sl)
+}
+
+/// cancel tx
+pub fn cancel_tx<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	tx_id: Option<u32>,
+	tx_slate_id: Option<Uuid>,
+	force: bool,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if !update_wallet_state(
+		wallet_inst.clone(),
+		keychain_mask,
+		status_send_channel,
+		false,
+	)? {
+		return Err(ErrorKind::TransactionCancellationError(
+			"Can't contact running Epic node. Not Cancelling.",
+		))?;
+	}
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	if !force {
+		// cancelling a transaction that went through unlocks inputs that are
+		// already spent, and deletes outputs that exist
+		let preview = tx::preview_cancel_tx(&mut **w, &parent_key_id, tx_id, tx_slate_id)?;
+		if let Some(seen) = tx::seen_by_node(&preview) {
+			return Err(ErrorKind::CancelRefused(format!(
+				"transaction {} may have gone through, {}",
+				preview.tx.id, seen
+			)))?;
+		}
+	}
+	tx::cancel_tx(&mut **w, keychain_mask, &parent_key_id, tx_id, tx_slate_id)
+}
+
+/// what cancelling a tx would do, without cancelling it
+pub fn preview_cancel_tx<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	tx_id: Option<u32>,
+	tx_slate_id: Option<Uuid>,
+) -> Result<CancelPreview, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if !update_wallet_state(
+		wallet_inst.clone(),
+		keychain_mask,
+		status_send_channel,
+		false,
+	)? {
+		return Err(ErrorKind::TransactionCancellationError(
+			"Can't contact running Epic node. Not Previewing.",
+		))?;
+	}
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	tx::preview_cancel_tx(&mut **w, &parent_key_id, tx_id, tx_slate_id)
+}
+
+/// cancel unconfirmed transactions created more than the given number of
+/// minutes ago
+pub fn cancel_stale_txs<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+	older_than_minutes: u64,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if !update_wallet_state(
+		wallet_inst.clone(),
+		keychain_mask,
+		status_send_channel,
+		false,
+	)? {
+		return Err(ErrorKind::TransactionCancellationError(
+			"Can't contact running Epic node. Not Cancelling.",
+		))?;
+	}
+	let cutoff = Utc::now() - Duration::minutes(older_than_minutes as i64);
+	wallet_lock!(wallet_inst, w);
+	let parent_key_id = w.parent_key_id();
+	tx::cancel_stale_txs(&mut **w, keychain_mask, &parent_key_id, cutoff)
+}
+
+/// get the stored result of an earlier call made with an idempotency key
+pub fn get_idempotent_result<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	method: &str,
+	key: &str,
+) -> Result<Option<IdempotentResult>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	w.get_idempotent_result(method, key)
+}
+
+/// store the result of a call made with an idempotency key
+pub fn save_idempotent_result<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	method: &str,
+	key: &str,
+	args_hash: String,
+	result: serde_json::Value,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save_idempotent_result(IdempotentResult {
+		method: method.to_owned(),
+		key: key.to_owned(),
+		args_hash: Some(args_hash),
+		result,
+		creation_ts: Utc::now(),
+	})?;
+	batch.commit()?;
+	Ok(())
+}
+
+/// get stored tx
+pub fn get_stored_tx<'a, T: ?Sized, C, K>(
+	w: &T,
+	entry: &TxLogEntry,
+) -> Result<Option<Transaction>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	w.get_stored_tx(entry)
+}
+
+/// Posts a transaction to the chain
+/// take a client impl instead of wallet so as not to have to lock the wallet
+pub fn post_tx<'a, C>(client: &C, tx: &Transaction, fluff: bool) -> Result<(), Error>
+where
+	C: NodeClient + 'a,
+{
+	let tx_hex = epic_util::to_hex(ser::ser_vec(tx, ser::ProtocolVersion(1)).unwrap());
+	let res = client.post_tx(&TxWrapper { tx_hex: tx_hex }, fluff);
+	if let Err(e) = res {
+		error!("api: post_tx: failed with error: {}", e);
+		Err(e)
+	} else {
+		debug!(
+			"api: post_tx: successfully posted tx: {}, fluff? {}",
+			tx.hash(),
+			fluff
+		);
+		Ok(())
+	}
+}
+
+/// Chain type of the node, identified by the hash of its genesis block.
+/// `None` if the genesis block isn't that of mainnet or floonet
+pub fn node_chain_type<'a, C>(client: &C) -> Result<Option<ChainTypes>, Error>
+where
+	C: NodeClient + 'a,
+{
+	let node_genesis = client.get_header_hash(0)?;
+	let known = vec![
+		(ChainTypes::Mainnet, genesis::genesis_main()),
+		(ChainTypes::Floonet, genesis::genesis_floo()),
+	];
+	Ok(known
+		.into_iter()
+		.find(|(_, b)| b.header.hash().to_hex() == node_genesis)
+		.map(|(c, _)| c))
+}
+
+/// Refuse to use a node running a different chain than the wallet is
+/// configured for, which would corrupt the wallet's view of confirmed
+/// heights. Only mainnet and floonet wallets are checked, and the check is
+/// skipped if the node can't be reached
+pub fn check_node_chain_type<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+) -> Result<(), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let wallet_chain = global::CHAIN_TYPE.read().clone();
+	match wallet_chain {
+		ChainTypes::Mainnet | ChainTypes::Floonet => {}
+		_ => return Ok(()),
+	}
+	let client = {
+		wallet_lock!(wallet_inst, w);
+		w.w2n_client().clone()
+	};
+	let node_chain = match node_chain_type(&client) {
+		Ok(c) => c,
+		Err(e) => {
+			warn!("Unable to check the chain type of the node: {}", e);
+			return Ok(());
 		}
 	};
-	Ok(PaymentProof {
-		amount: amount,
-		excess: excess,
-		recipient_address: address::onion_v3_from_pubkey(&proof.receiver_address)?,
-		recipient_sig: r_sig,
-		sender_address: address::onion_v3_from_pubkey(&proof.sender_address)?,
-		sender_sig: s_sig,
-	})
+	if node_chain.as_ref() != Some(&wallet_chain) {
+		let node_chain = match node_chain {
+			Some(c) => format!("{:?}", c),
+			None => "an unknown chain".to_owned(),
+		};
+		return Err(ErrorKind::ChainTypeMismatch(
+			format!("{:?}", wallet_chain),
+			node_chain,
+		))?;
+	}
+	Ok(())
 }
 
-/// Initiate tx as sender
-pub fn init_send_tx<'a, T: ?Sized, C, K>(
-	w: &mut T,
+/// Post a finalized transaction, first recording the wallet's matching
+/// transaction log entry as ready to post and then as posted once the node
+/// acknowledges it. An interrupted post is finished by
+/// [recover_pending_posts](fn.recover_pending_posts.html)
+pub fn post_and_record_tx<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
 	keychain_mask: Option<&SecretKey>,
-	args: InitTxArgs,
-	use_test_rng: bool,
-) -> Result<Slate, Error>
+	tx: &Transaction,
+	fluff: bool,
+) -> Result<(), Error>
 where
-	T: WalletBackend<'a, C, K>,
+	L: WalletLCProvider<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let parent_key_id = match args.src_acct_name {
-		Some(d) => {
-			let pm = w.get_acct_path(d)?;
-			match pm {
-				Some(p) => p.path,
-				None => w.parent_key_id(),
-			}
-		}
-		None => w.parent_key_id(),
+	let (client, mut entry) = {
+		wallet_lock!(wallet_inst, w);
+		// Test keychain mask, to keep API consistent
+		let _ = w.keychain(keychain_mask)?;
+		let entry = match tx.kernels().first() {
+			Some(k) => tx::tx_log_entry_by_kernel(&mut **w, &k.excess)?,
+			None => None,
+		};
+		(w.w2n_client().clone(), entry)
 	};
+	let mut prev_state = None;
+	if let Some(ref mut t) = entry {
+		wallet_lock!(wallet_inst, w);
+		prev_state =
+			tx::update_tx_post_state(&mut **w, keychain_mask, t, Some(TxPostState::ReadyToPost))?;
+	}
+	let res = post_tx(&client, tx, fluff);
+	if let Some(ref mut t) = entry {
+		let post_state = match res {
+			Ok(_) => Some(TxPostState::Posted),
+			Err(_) => prev_state,
+		};
+		wallet_lock!(wallet_inst, w);
+		tx::update_tx_post_state(&mut **w, keychain_mask, t, post_state)?;
+	}
+	res
+}
 
-	let message = match args.message {
-		Some(mut m) => {
-			m.truncate(USER_MESSAGE_MAX_LEN);
-			Some(m)
-		}
-		None => None,
+/// Post a finalized transaction as [post_and_record_tx](fn.post_and_record_tx.html)
+/// does, unless the node can't be reached. The transaction is then queued,
+/// and posted by [recover_pending_posts](fn.recover_pending_posts.html) once
+/// the node is back. Returns whether the transaction was posted
+pub fn post_or_queue_tx<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	tx: &Transaction,
+	fluff: bool,
+) -> Result<bool, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let e = match post_and_record_tx(wallet_inst.clone(), keychain_mask, tx, fluff) {
+		Ok(_) => return Ok(true),
+		Err(e) => e,
 	};
-
-	let mut slate = tx::new_tx_slate(&mut *w, args.amount, 2, use_test_rng, args.ttl_blocks)?;
-
-	// if we just want to estimate, don't save a context, just send the results
-	// back
-	if let Some(true) = args.estimate_only {
-		let (total, fee) = tx::estimate_send_tx(
-			&mut *w,
-			keychain_mask,
-			args.amount,
-			args.minimum_confirmations,
-			args.max_outputs as usize,
-			args.num_change_outputs as usize,
-			args.selection_strategy_is_use_all,
-			&parent_key_id,
-		)?;
-		slate.amount = total;
-		slate.fee = fee;
-		return Ok(slate);
+	let client = {
+		wallet_lock!(wallet_inst, w);
+		w.w2n_client().clone()
+	};
+	if client.get_chain_tip().is_ok() {
+		// the node is up, so it refused the transaction
+		return Err(e);
 	}
-
-	let mut context = tx::add_inputs_to_slate(
-		&mut *w,
+	wallet_lock!(wallet_inst, w);
+	let entry = match tx.kernels().first() {
+		Some(k) => tx::tx_log_entry_by_kernel(&mut **w, &k.excess)?,
+		None => None,
+	};
+	let mut entry = match entry {
+		Some(t) if w.get_stored_tx(&t)?.is_some() => t,
+		// nothing to post later from
+		_ => return Err(e),
+	};
+	warn!(
+		"Unable to contact node, transaction {} queued to post once it's back: {}",
+		entry.id, e
+	);
+	tx::update_tx_post_state(
+		&mut **w,
 		keychain_mask,
-		&mut slate,
-		args.minimum_confirmations,
-		args.max_outputs as usize,
-		args.num_change_outputs as usize,
-		args.selection_strategy_is_use_all,
-		&parent_key_id,
-		0,
-		message,
-		true,
-		use_test_rng,
+		&mut entry,
+		Some(TxPostState::Queued),
 	)?;
+	Ok(false)
+}
 
-	// Payment Proof, add addresses to slate and save address
-	// TODO: Note we only use single derivation path for now,
-	// probably want to allow sender to specify which one
-	let deriv_path = 0u32;
-
-	if let Some(a) = args.payment_proof_recipient_address {
-		let k = w.keychain(keychain_mask)?;
-
-		let sec_addr_key = address::address_from_derivation_path(&k, &parent_key_id, deriv_path)?;
-		let sender_address = address::ed25519_keypair(&sec_addr_key)?.1;
-
-		slate.payment_proof = Some(PaymentInfo {
-			sender_address,
-			receiver_address: a,
-			receiver_signature: None,
-		});
+/// Transactions waiting to be posted, either queued while the node couldn't
+/// be reached or interrupted before the node's acknowledgment was recorded
+pub fn retrieve_post_queue<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet_lock!(wallet_inst, w);
+	// Test keychain mask, to keep API consistent
+	let _ = w.keychain(keychain_mask)?;
+	tx::pending_posts(&mut **w)
+}
 
-		context.payment_proof_derivation_index = Some(deriv_path);
+/// Finish transaction posts queued while the node couldn't be reached, or
+/// interrupted before the node's acknowledgment was recorded. Transactions
+/// the node already knows of are marked as posted and the others are posted
+/// again; any the node refuses are cancelled, unlocking their inputs.
+/// Nothing is changed if the node can't be reached. Returns the entries that
+/// were resolved
+pub fn recover_pending_posts<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (pending, mut client) = {
+		wallet_lock!(wallet_inst, w);
+		(tx::pending_posts(&mut **w)?, w.w2n_client().clone())
+	};
+	if pending.is_empty() {
+		return Ok(vec![]);
+	}
+	if let Err(e) = client.get_chain_tip() {
+		warn!(
+			"Unable to contact node, leaving {} pending post(s) for later: {}",
+			pending.len(),
+			e
+		);
+		return Ok(vec![]);
+	}
+	let mut resolved = vec![];
+	for mut t in pending {
+		let on_chain = t.confirmed
+			|| match t.kernel_excess {
+				Some(ref e) => client
+					.get_kernel(e, t.kernel_lookup_min_height, None)?
+					.is_some(),
+				None => false,
+			};
+		let stored_tx = {
+			wallet_lock!(wallet_inst, w);
+			w.get_stored_tx(&t)?
+		};
+		let posted = on_chain
+			|| match stored_tx {
+				Some(ref stored) => post_tx(&client, stored, false).is_ok(),
+				None => false,
+			};
+		wallet_lock!(wallet_inst, w);
+		if posted {
+			info!("Pending post of transaction {} completed", t.id);
+			tx::update_tx_post_state(&mut **w, keychain_mask, &mut t, Some(TxPostState::Posted))?;
+		} else {
+			warn!(
+				"Transaction {} was not accepted by the node, cancelling it",
+				t.id
+			);
+			tx::update_tx_post_state(&mut **w, keychain_mask, &mut t, None)?;
+			tx::cancel_tx(&mut **w, keychain_mask, &t.parent_key_id, Some(t.id), None)?;
+			let tx_vec =
+				updater::retrieve_txs(&mut **w, Some(t.id), None, Some(&t.parent_key_id), false)?;
+			if let Some(cancelled) = tx_vec.into_iter().next() {
+				t = cancelled;
+			}
+		}
+		resolved.push(t);
 	}
+	Ok(resolved)
+}
 
-	// Save the aggsig context in our DB for when we
-	// recieve the transaction back
-	{
-		let mut batch = w.batch(keychain_mask)?;
-		batch.save_private_context(slate.id.as_bytes(), 0, &context)?;
-		batch.commit()?;
+/// Queue a send the account can't fund yet, as asked by its
+/// `wait_for_maturity` argument, if enough of its coinbase outputs mature
+/// within that many blocks. Otherwise the error the send was refused with is
+/// returned
+pub fn queue_send_tx<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	mut args: InitTxArgs,
+	error: Error,
+) -> Result<QueuedSend, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let blocks = match args.wait_for_maturity {
+		Some(b) if !args.estimate_only.unwrap_or(false) => b,
+		_ => return Err(error),
+	};
+	let ready_height = match error.kind() {
+		ErrorKind::NotEnoughFunds {
+			enough_at_height: Some(h),
+			..
+		} => h,
+		_ => return Err(error),
+	};
+	let expiry_height = w.last_confirmed_height()? + blocks;
+	if ready_height > expiry_height {
+		return Err(error);
 	}
-	if let Some(v) = args.target_slate_version {
-		slate.version_info.orig_version = v;
+	if let Some(units) = args.units.take() {
+		args.amount = Amount::from_units(args.amount.freemans(), units)?;
 	}
+	let queued = QueuedSend {
+		id: Uuid::new_v4(),
+		args,
+		ready_height,
+		expiry_height,
+		creation_ts: Utc::now(),
+	};
+	info!(
+		"Not enough funds yet, send {} queued until height {}",
+		queued.id, ready_height
+	);
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save_queued_send(&queued)?;
+	batch.commit()?;
+	Ok(queued)
+}
 
-	Ok(slate)
+/// Sends queued until funds mature, oldest first
+pub fn retrieve_send_queue<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<Vec<QueuedSend>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	// Test keychain mask, to keep API consistent
+	let _ = w.keychain(keychain_mask)?;
+	let mut queue = w.send_queue_iter().collect::<Vec<_>>();
+	queue.sort_by_key(|q| q.creation_ts);
+	Ok(queue)
 }
 
-/// Initiate a transaction as the recipient (invoicing)
-pub fn issue_invoice_tx<'a, T: ?Sized, C, K>(
+/// Remove a send from the queue, so it's never made
+pub fn cancel_queued_send<'a, T: ?Sized, C, K>(
 	w: &mut T,
 	keychain_mask: Option<&SecretKey>,
-	args: IssueInvoiceTxArgs,
-	use_test_rng: bool,
-) -> Result<Slate, Error>
+	id: &Uuid,
+) -> Result<QueuedSend, Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let parent_key_id = match args.dest_acct_name {
-		Some(d) => {
-			let pm = w.get_acct_path(d)?;
-			match pm {
-				Some(p) => p.path,
-				None => w.parent_key_id(),
-			}
-		}
-		None => w.parent_key_id(),
-	};
+	let queued = w
+		.send_queue_iter()
+		.find(|q| q.id == *id)
+		.ok_or_else(|| ErrorKind::SendQueue(format!("no queued send {}", id)))?;
+	let mut batch = w.batch(keychain_mask)?;
+	batch.delete_queued_send(id)?;
+	batch.commit()?;
+	Ok(queued)
+}
 
-	let message = match args.message {
-		Some(mut m) => {
-			m.truncate(USER_MESSAGE_MAX_LEN);
-			Some(m)
+/// Queued sends the chain has reached the ready height of, after refreshing
+/// the wallet's outputs. Nothing is due while the node can't be reached
+pub fn due_queued_sends<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<Vec<QueuedSend>, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (queue, client) = {
+		wallet_lock!(wallet_inst, w);
+		(
+			retrieve_send_queue(&mut **w, keychain_mask)?,
+			w.w2n_client().clone(),
+		)
+	};
+	if queue.is_empty() {
+		return Ok(vec![]);
+	}
+	let height = match client.get_chain_tip() {
+		Ok(tip) => tip.0,
+		Err(e) => {
+			warn!(
+				"Unable to contact node, leaving {} queued send(s) for later: {}",
+				queue.len(),
+				e
+			);
+			return Ok(vec![]);
 		}
-		None => None,
 	};
-
-	let mut slate = tx::new_tx_slate(&mut *w, args.amount, 2, use_test_rng, None)?;
-	let context = tx::add_output_to_slate(
-		&mut *w,
-		keychain_mask,
-		&mut slate,
-		&parent_key_id,
-		1,
-		message,
-		true,
-		use_test_rng,
-	)?;
-
-	// Save the aggsig context in our DB for when we
-	// recieve the transaction back
-	{
-		let mut batch = w.batch(keychain_mask)?;
-		batch.save_private_context(slate.id.as_bytes(), 1, &context)?;
-		batch.commit()?;
+	let due = queue
+		.into_iter()
+		.filter(|q| q.ready_height <= height)
+		.collect::<Vec<_>>();
+	if !due.is_empty() {
+		update_wallet_state(wallet_inst, keychain_mask, &None, false)?;
 	}
+	Ok(due)
+}
 
-	if let Some(v) = args.target_slate_version {
-		slate.version_info.orig_version = v;
-	}
+/// Record the outcome of making a queued send. The send stays queued, with a
+/// later ready height, if funds still fall short but are expected to mature
+/// before it expires, and `None` is returned. Otherwise it's removed from the
+/// queue and its outcome returned
+pub fn resolve_queued_send<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	mut queued: QueuedSend,
+	res: Result<Slate, Error>,
+) -> Result<Option<QueuedSendResult>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (tx_slate_id, error) = match res {
+		Ok(slate) => {
+			info!("Queued send {} made as transaction {}", queued.id, slate.id);
+			(Some(slate.id), None)
+		}
+		Err(e) => {
+			let current_height = w.last_confirmed_height()?;
+			if let ErrorKind::NotEnoughFunds {
+				enough_at_height: Some(h),
+				..
+			} = e.kind()
+			{
+				if h <= queued.expiry_height && current_height < queued.expiry_height {
+					queued.ready_height = h.max(current_height + 1);
+					debug!(
+						"Queued send {} still short of funds, waiting until height {}",
+						queued.id, queued.ready_height
+					);
+					let mut batch = w.batch(keychain_mask)?;
+					batch.save_queued_send(&queued)?;
+					batch.commit()?;
+					return Ok(None);
+				}
+			}
+			warn!("Queued send {} failed, dropping it: {}", queued.id, e);
+			(None, Some(format!("{}", e)))
+		}
+	};
+	let mut batch = w.batch(keychain_mask)?;
+	batch.delete_queued_send(&queued.id)?;
+	batch.commit()?;
+	Ok(Some(QueuedSendResult {
+		queued,
+		tx_slate_id,
+		error,
+	}))
+}
 
-	Ok(slate)
+/// Queue a job, kept in the wallet until it's been done or given up on. A
+/// `claimed` job is run straight away by the caller, and only due to be run
+/// by others once `JOB_RETRY_SECS` have passed, should the caller never
+/// record how it went
+pub fn queue_job<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	action: JobAction,
+	claimed: bool,
+) -> Result<Job, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let now = Utc::now();
+	let job = Job {
+		id: Uuid::new_v4(),
+		action,
+		attempts: 0,
+		next_attempt_ts: match claimed {
+			true => now + Duration::seconds(JOB_RETRY_SECS),
+			false => now,
+		},
+		last_error: None,
+		dead: false,
+		creation_ts: now,
+	};
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save_job(&job)?;
+	batch.commit()?;
+	Ok(job)
 }
 
-/// Receive an invoice tx, essentially adding inputs to whatever
-/// output was specified
-pub fn process_invoice_tx<'a, T: ?Sized, C, K>(
+/// Queued jobs, dead letters included, oldest first
+pub fn retrieve_jobs<'a, T: ?Sized, C, K>(
 	w: &mut T,
 	keychain_mask: Option<&SecretKey>,
-	slate: &Slate,
-	args: InitTxArgs,
-	use_test_rng: bool,
-) -> Result<Slate, Error>
+) -> Result<Vec<Job>, Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let mut ret_slate = slate.clone();
-	check_ttl(w, &ret_slate)?;
-	let parent_key_id = match args.src_acct_name {
-		Some(d) => {
-			let pm = w.get_acct_path(d.to_owned())?;
-			match pm {
-				Some(p) => p.path,
-				None => w.parent_key_id(),
-			}
-		}
-		None => w.parent_key_id(),
-	};
-	// Don't do this multiple times
-	let tx = updater::retrieve_txs(
-		&mut *w,
-		None,
-		Some(ret_slate.id),
-		Some(&parent_key_id),
-		use_test_rng,
-	)?;
-	for t in &tx {
-		if t.tx_type == TxLogEntryType::TxSent {
-			return Err(ErrorKind::TransactionAlreadyReceived(ret_slate.id.to_string()).into());
-		}
-	}
-
-	let message = match args.message {
-		Some(mut m) => {
-			m.truncate(USER_MESSAGE_MAX_LEN);
-			Some(m)
-		}
-		None => None,
-	};
-
-	// update slate current height
-	ret_slate.height = w.w2n_client().get_chain_tip()?.0;
-
-	// update ttl if desired
-	if let Some(b) = args.ttl_blocks {
-		ret_slate.ttl_cutoff_height = Some(ret_slate.height + b);
-	}
-
-	let context = tx::add_inputs_to_slate(
-		&mut *w,
-		keychain_mask,
-		&mut ret_slate,
-		args.minimum_confirmations,
-		args.max_outputs as usize,
-		args.num_change_outputs as usize,
-		args.selection_strategy_is_use_all,
-		&parent_key_id,
-		0,
-		message,
-		false,
-		use_test_rng,
-	)?;
-
-	// Save the aggsig context in our DB for when we
-	// recieve the transaction back
-	{
-		let mut batch = w.batch(keychain_mask)?;
-		batch.save_private_context(slate.id.as_bytes(), 0, &context)?;
-		batch.commit()?;
-	}
-
-	if let Some(v) = args.target_slate_version {
-		ret_slate.version_info.orig_version = v;
-	}
-
-	Ok(ret_slate)
+	// Test keychain mask, to keep API consistent
+	let _ = w.keychain(keychain_mask)?;
+	let mut jobs = w.job_iter().collect::<Vec<_>>();
+	jobs.sort_by_key(|j| j.creation_ts);
+	Ok(jobs)
 }
 
-/// Lock sender outputs
-pub fn tx_lock_outputs<'a, T: ?Sized, C, K>(
+/// Queued jobs due to be tried, oldest first
+pub fn due_jobs<'a, T: ?Sized, C, K>(
 	w: &mut T,
 	keychain_mask: Option<&SecretKey>,
-	slate: &Slate,
-	participant_id: usize,
-) -> Result<(), Error>
+) -> Result<Vec<Job>, Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let context = w.get_private_context(keychain_mask, slate.id.as_bytes(), participant_id)?;
-	selection::lock_tx_context(&mut *w, keychain_mask, slate, &context)
+	let now = Utc::now();
+	Ok(retrieve_jobs(w, keychain_mask)?
+		.into_iter()
+		.filter(|j| !j.dead && j.next_attempt_ts <= now)
+		.collect())
 }
 
-/// Finalize slate
-pub fn finalize_tx<'a, T: ?Sized, C, K>(
+/// Record the outcome of running a queued job. A job that's done is removed
+/// from the queue. One that failed is tried again later, waiting twice as
+/// long after each failure, until it's failed `JOB_MAX_ATTEMPTS` times and
+/// is kept as a dead letter
+pub fn resolve_job<'a, T: ?Sized, C, K>(
 	w: &mut T,
 	keychain_mask: Option<&SecretKey>,
-	slate: &Slate,
-) -> Result<Slate, Error>
+	mut job: Job,
+	res: &Result<(), Error>,
+) -> Result<JobResult, Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let mut sl = slate.clone();
-	check_ttl(w, &sl)?;
-	let context = w.get_private_context(keychain_mask, sl.id.as_bytes(), 0)?;
-	let parent_key_id = w.parent_key_id();
-	tx::complete_tx(&mut *w, keychain_mask, &mut sl, 0, &context)?;
-	tx::verify_slate_payment_proof(&mut *w, keychain_mask, &parent_key_id, &context, &sl)?;
-	tx::update_stored_tx(&mut *w, keychain_mask, &context, &mut sl, false)?;
-	tx::update_message(&mut *w, keychain_mask, &mut sl)?;
-	{
-		let mut batch = w.batch(keychain_mask)?;
-		batch.delete_private_context(sl.id.as_bytes(), 0)?;
-		batch.commit()?;
-	}
-	Ok(sl)
+	let mut batch = w.batch(keychain_mask)?;
+	let error = match res {
+		Ok(_) => {
+			debug!("Job {} done", job.id);
+			batch.delete_job(&job.id)?;
+			None
+		}
+		Err(e) => {
+			job.attempts += 1;
+			job.last_error = Some(format!("{}", e));
+			if job.attempts >= JOB_MAX_ATTEMPTS {
+				warn!(
+					"Job {} failed {} times, giving up on it: {}",
+					job.id, job.attempts, e
+				);
+				job.dead = true;
+			} else {
+				let wait = (JOB_RETRY_SECS << (job.attempts - 1).min(7)).min(60 * 60);
+				job.next_attempt_ts = Utc::now() + Duration::seconds(wait);
+				debug!("Job {} failed, trying again in {}s: {}", job.id, wait, e);
+			}
+			batch.save_job(&job)?;
+			job.last_error.clone()
+		}
+	};
+	batch.commit()?;
+	Ok(JobResult { job, error })
 }
 
-/// cancel tx
-pub fn cancel_tx<'a, L, C, K>(
-	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+/// Try a queued job again straight away, even a dead one, with its count of
+/// attempts reset
+pub fn retry_job<'a, T: ?Sized, C, K>(
+	w: &mut T,
 	keychain_mask: Option<&SecretKey>,
-	status_send_channel: &Option<Sender<StatusMessage>>,
-	tx_id: Option<u32>,
-	tx_slate_id: Option<Uuid>,
-) -> Result<(), Error>
+	id: &Uuid,
+) -> Result<Job, Error>
 where
-	L: WalletLCProvider<'a, C, K>,
+	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	if !update_wallet_state(
-		wallet_inst.clone(),
-		keychain_mask,
-		status_send_channel,
-		false,
-	)? {
-		return Err(ErrorKind::TransactionCancellationError(
-			"Can't contact running Epic node. Not Cancelling.",
-		))?;
-	}
-	wallet_lock!(wallet_inst, w);
-	let parent_key_id = w.parent_key_id();
-	tx::cancel_tx(&mut **w, keychain_mask, &parent_key_id, tx_id, tx_slate_id)
+	let mut job = w
+		.job_iter()
+		.find(|j| j.id == *id)
+		.ok_or_else(|| ErrorKind::JobQueue(format!("no job {}", id)))?;
+	job.attempts = 0;
+	job.dead = false;
+	job.next_attempt_ts = Utc::now();
+	let mut batch = w.batch(keychain_mask)?;
+	batch.save_job(&job)?;
+	batch.commit()?;
+	Ok(job)
 }
 
-/// get stored tx
-pub fn get_stored_tx<'a, T: ?Sized, C, K>(
-	w: &T,
-	entry: &TxLogEntry,
-) -> Result<Option<Transaction>, Error>
+/// Remove a job from the queue, so it's never run
+pub fn delete_job<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	id: &Uuid,
+) -> Result<Job, Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	w.get_stored_tx(entry)
+	let job = w
+		.job_iter()
+		.find(|j| j.id == *id)
+		.ok_or_else(|| ErrorKind::JobQueue(format!("no job {}", id)))?;
+	let mut batch = w.batch(keychain_mask)?;
+	batch.delete_job(id)?;
+	batch.commit()?;
+	Ok(job)
 }
 
-/// Posts a transaction to the chain
-/// take a client impl instead of wallet so as not to have to lock the wallet
-pub fn post_tx<'a, C>(client: &C, tx: &Transaction, fluff: bool) -> Result<(), Error>
+/// The transaction a `PostTx` job is to post, or `None` if there's nothing
+/// left to post, as when it's been posted, confirmed or cancelled since the
+/// job was queued
+pub fn job_tx_to_post<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	tx_slate_id: &Uuid,
+) -> Result<Option<Transaction>, Error>
 where
+	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
+	K: Keychain + 'a,
 {
-	let tx_hex = epic_util::to_hex(ser::ser_vec(tx, ser::ProtocolVersion(1)).unwrap());
-	let res = client.post_tx(&TxWrapper { tx_hex: tx_hex }, fluff);
-	if let Err(e) = res {
-		error!("api: post_tx: failed with error: {}", e);
-		Err(e)
-	} else {
-		debug!(
-			"api: post_tx: successfully posted tx: {}, fluff? {}",
-			tx.hash(),
-			fluff
-		);
-		Ok(())
+	// Test keychain mask, to keep API consistent
+	let _ = w.keychain(keychain_mask)?;
+	let entries = w.tx_log_entries_by_slate_id(tx_slate_id)?;
+	if entries
+		.iter()
+		.any(|t| t.state.is_final() || t.state == TxState::Posted)
+	{
+		return Ok(None);
 	}
+	for t in entries.iter() {
+		if let Some(tx) = w.get_stored_tx(t)? {
+			return Ok(Some(tx));
+		}
+	}
+	Err(ErrorKind::JobQueue(format!(
+		"no stored transaction for slate {}",
+		tx_slate_id
+	)))?
 }
 
 /// verify slate messages
@@ -646,6 +3255,43 @@ pub fn verify_slate_messages(slate: &Slate) -> Result<(), Error> {
 	slate.verify_messages()
 }
 
+/// The optional subsystems of this build, given whether the impls crate was
+/// built with tor, http (for webhooks), QR codes, ACME and which seed storage,
+/// whether a tor configuration is set and whether webhooks are allowed
+pub fn get_capabilities(
+	tor: bool,
+	tor_enabled: bool,
+	webhooks: bool,
+	webhooks_enabled: bool,
+	qr: bool,
+	acme: bool,
+	os_keystore: bool,
+	pkcs11: bool,
+) -> Capabilities {
+	let feature = |built: bool| Capability {
+		compiled: built,
+		enabled: built,
+	};
+	Capabilities {
+		tor: Capability {
+			compiled: tor,
+			enabled: tor && tor_enabled,
+		},
+		qr: feature(qr),
+		acme: feature(acme),
+		swaps: feature(false),
+		webhooks: Capability {
+			compiled: webhooks,
+			enabled: webhooks && webhooks_enabled,
+		},
+		grpc: feature(false),
+		hardware_signing: feature(false),
+		os_keystore: feature(os_keystore),
+		pkcs11: feature(pkcs11),
+		deterministic_nonces: feature(cfg!(feature = "deterministic_nonces")),
+	}
+}
+
 /// check repair
 /// Accepts a wallet inst instead of a raw wallet so it can
 /// lock as little as possible
@@ -725,6 +3371,115 @@ where
 		}
 	}
 }
+
+/// Check whether the configured node can be reached. Opening a wallet leaves
+/// the node alone, so once the node answers this also makes the checks
+/// deferred from opening: refusing a node on another chain, and finishing
+/// any transaction post interrupted by an earlier crash
+pub fn node_ping<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<NodePing, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let client = {
+		wallet_lock!(wallet_inst, w);
+		// Test keychain mask, to keep API consistent
+		let _ = w.keychain(keychain_mask)?;
+		w.w2n_client().clone()
+	};
+	let start = Instant::now();
+	let tip = client.get_chain_tip();
+	let latency_ms = start.elapsed().as_millis() as u64;
+	let tip = match tip {
+		Ok(t) => t,
+		Err(e) => {
+			return Ok(NodePing {
+				reachable: false,
+				height: None,
+				header_hash: None,
+				latency_ms,
+				error: Some(format!("{}", e)),
+			});
+		}
+	};
+	check_node_chain_type(wallet_inst.clone())?;
+	let recovered = match recover_pending_posts(wallet_inst, keychain_mask) {
+		Ok(r) => r,
+		Err(e) => {
+			warn!("Unable to recover interrupted transaction posts: {}", e);
+			vec![]
+		}
+	};
+	for tx in recovered {
+		match tx.post_state {
+			Some(_) => warn!("Interrupted post of transaction {} was completed", tx.id),
+			None => warn!(
+				"Transaction {} from an interrupted post was refused by the node and cancelled",
+				tx.id
+			),
+		}
+	}
+	Ok(NodePing {
+		reachable: true,
+		height: Some(tip.0),
+		header_hash: Some(tip.1),
+		latency_ms,
+		error: None,
+	})
+}
+
+/// Report the status of the configured node relative to the wallet
+pub fn node_status<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+) -> Result<NodeStatus, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	// Test keychain mask, to keep API consistent
+	let _ = w.keychain(keychain_mask)?;
+	let last_confirmed_height = w.last_confirmed_height()?;
+	let mut client = w.w2n_client().clone();
+	let tip = client.get_chain_tip().ok();
+	let node_version = match tip {
+		Some(_) => client.get_version_info(),
+		None => None,
+	};
+	let tip_timestamp = match tip {
+		Some((height, _)) => client.get_header_timestamp(height).ok(),
+		None => None,
+	};
+	let clock_skew_secs = tip_timestamp.map(|t| (Utc::now() - t).num_seconds());
+	let clock_skewed = clock_skew_secs
+		.map(|s| s.abs() > CLOCK_SKEW_WARNING_SECS)
+		.unwrap_or(false);
+	if clock_skewed {
+		warn!(
+			"Wallet clock differs from the node's chain tip timestamp by {} seconds",
+			clock_skew_secs.unwrap_or(0)
+		);
+	}
+	Ok(NodeStatus {
+		node_reachable: tip.is_some(),
+		node_height: tip.as_ref().map(|t| t.0),
+		header_hash: tip.as_ref().map(|t| t.1.clone()),
+		last_confirmed_height,
+		sync_lag: tip
+			.as_ref()
+			.map(|t| t.0.saturating_sub(last_confirmed_height)),
+		node_version,
+		tip_timestamp,
+		clock_skew_secs,
+		clock_skewed,
+	})
+}
+
 /// Experimental, wrap the entire definition of how a wallet's state is updated
 pub fn update_wallet_state<'a, L, C, K>(
 	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
@@ -854,11 +3609,18 @@ where
 			if tip.0 >= e {
 				wallet_lock!(wallet_inst, w);
 				let parent_key_id = w.parent_key_id();
-				tx::cancel_tx(&mut **w, keychain_mask, &parent_key_id, Some(tx.id), None)?;
+				tx::expire_tx(&mut **w, keychain_mask, &parent_key_id, tx.id)?;
 			}
 		}
 	}
 
+	{
+		wallet_lock!(wallet_inst, w);
+		w.events().emit(WalletEvent::RefreshCompleted {
+			parent_key_id,
+			height: tip.0,
+		});
+	}
 	Ok(result)
 }
 
@@ -878,6 +3640,118 @@ where
 	}
 	Ok(())
 }
+/// Look up a transaction kernel on the chain, marking the corresponding
+/// transaction log entry as confirmed if the kernel is found
+pub fn get_kernel_status<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	excess: &pedersen::Commitment,
+) -> Result<KernelStatus, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut tx = w.tx_log_iter().find(|t| t.kernel_excess == Some(*excess));
+	let min_height = match tx.as_ref() {
+		Some(t) => t.kernel_lookup_min_height,
+		None => None,
+	};
+
+	let mut client = w.w2n_client().clone();
+	let height = client.get_chain_tip()?.0;
+	let kernel = client.get_kernel(excess, min_height, Some(height))?;
+
+	if let (Some(t), Some(k)) = (tx.as_mut(), kernel.as_ref()) {
+		if !t.state.is_final() {
+			t.confirm(k.1)?;
+			let mut batch = w.batch(keychain_mask)?;
+			batch.save_tx_log_entry(t.clone(), &t.parent_key_id)?;
+			batch.commit()?;
+		}
+	}
+
+	Ok(KernelStatus {
+		excess: *excess,
+		height: kernel.as_ref().map(|k| k.1),
+		mmr_index: kernel.as_ref().map(|k| k.2),
+		tx_log_entry: tx.as_ref().map(|t| t.id),
+		confirmed: tx.as_ref().map(|t| t.confirmed).unwrap_or(false),
+	})
+}
+
+/// Check how far a transaction has got towards the target number of
+/// confirmations by looking its kernel up on the chain, marking the
+/// transaction confirmed if the kernel is found. `seen_height` is where an
+/// earlier check found the kernel, if one did, so a kernel that has left the
+/// chain since shows the transaction was invalidated by a reorg
+pub fn tx_finality<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	tx_id: u32,
+	target_confirmations: u64,
+	seen_height: Option<u64>,
+) -> Result<TxFinality, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let parent_key_id = w.parent_key_id();
+	let mut tx = match updater::retrieve_txs(w, Some(tx_id), None, Some(&parent_key_id), false)?
+		.into_iter()
+		.next()
+	{
+		Some(t) => t,
+		None => return Err(ErrorKind::TransactionDoesntExist(tx_id.to_string()))?,
+	};
+	let excess = match tx.kernel_excess {
+		Some(e) => e,
+		None => {
+			return Err(ErrorKind::FinalityWatch(format!(
+				"transaction {} has no kernel excess to look up",
+				tx_id
+			)))?
+		}
+	};
+
+	let mut client = w.w2n_client().clone();
+	let height = client.get_chain_tip()?.0;
+	let kernel = client.get_kernel(&excess, tx.kernel_lookup_min_height, Some(height))?;
+	let kernel_height = kernel.map(|(_, h, _)| h);
+
+	if let Some(h) = kernel_height {
+		if !tx.state.is_final() {
+			tx.confirm(h)?;
+			let mut batch = w.batch(keychain_mask)?;
+			batch.save_tx_log_entry(tx.clone(), &parent_key_id)?;
+			batch.commit()?;
+		}
+	}
+
+	let confirmations = kernel_height
+		.map(|h| height.saturating_sub(h) + 1)
+		.unwrap_or(0);
+	let cancelled = tx.tx_type == TxLogEntryType::TxSentCancelled
+		|| tx.tx_type == TxLogEntryType::TxReceivedCancelled;
+	// the chain has the last word, even on a transaction cancelled here
+	let state = match kernel_height {
+		Some(_) if confirmations >= target_confirmations => FinalityState::Final,
+		Some(_) => FinalityState::Pending,
+		None if cancelled || seen_height.is_some() => FinalityState::Invalidated,
+		None => FinalityState::Pending,
+	};
+	Ok(TxFinality {
+		tx_id,
+		tx_slate_id: tx.tx_slate_id,
+		target_confirmations,
+		kernel_height,
+		confirmations,
+		height,
+		state,
+	})
+}
+
 /// Verify/validate arbitrary payment proof
 /// Returns (whether this wallet is the sender, whether this wallet is the recipient)
 pub fn verify_payment_proof<'a, L, C, K>(
@@ -1004,7 +3878,7 @@ where
 	};
 
 	for tx in txs.iter_mut() {
-		if tx.confirmed {
+		if tx.state.is_final() {
 			continue;
 		}
 		if tx.amount_debited != 0 && tx.amount_credited != 0 {
@@ -1020,8 +3894,7 @@ where
 				debug!("Kernel Retrieved: {:?}", k);
 				wallet_lock!(wallet_inst, w);
 				let mut batch = w.batch(keychain_mask)?;
-				tx.confirmed = true;
-				tx.update_confirmation_ts();
+				tx.confirm(k.1)?;
 				batch.save_tx_log_entry(tx.clone(), &parent_key_id)?;
 				batch.commit()?;
 			}
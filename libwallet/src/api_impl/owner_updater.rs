@@ -26,7 +26,7 @@ use crate::epic_util::Mutex;
 use crate::api_impl::owner;
 use crate::types::NodeClient;
 use crate::Error;
-use crate::{WalletInst, WalletLCProvider};
+use crate::{wallet_lock, WalletInst, WalletLCProvider};
 
 const MESSAGE_QUEUE_MAX_LEN: usize = 10_000;
 
@@ -137,4 +137,51 @@ where
 		}
 		Ok(())
 	}
+
+	/// Start the updater, polling the node's chain tip at the given frequency and
+	/// only performing an update when the tip has changed since the last successful
+	/// update
+	pub fn run_on_tip_change(
+		&self,
+		poll_frequency: Duration,
+		keychain_mask: Option<SecretKey>,
+		status_send_channel: &Option<Sender<StatusMessage>>,
+	) -> Result<(), Error> {
+		self.is_running.store(true, Ordering::Relaxed);
+		let mut last_tip: Option<(u64, String)> = None;
+		loop {
+			let mut client = {
+				wallet_lock!(self.wallet_inst, w);
+				w.w2n_client().clone()
+			};
+			match client.get_chain_tip() {
+				Ok(tip) => {
+					if last_tip.as_ref() != Some(&tip) {
+						let updated = owner::update_wallet_state(
+							self.wallet_inst.clone(),
+							(&keychain_mask).as_ref(),
+							status_send_channel,
+							false,
+						)?;
+						if updated {
+							last_tip = Some(tip);
+						}
+					}
+				}
+				Err(e) => {
+					if let Some(ref s) = status_send_channel {
+						let _ = s.send(StatusMessage::UpdateWarning(format!(
+							"Unable to retrieve chain tip from node: {}",
+							e
+						)));
+					}
+				}
+			}
+			if !self.is_running.load(Ordering::Relaxed) {
+				break;
+			}
+			thread::sleep(poll_frequency);
+		}
+		Ok(())
+	}
 }
@@ -14,17 +14,33 @@
 
 //! Types specific to the wallet api, mostly argument serialization
 
+use crate::amount::Amount;
+use crate::blake2::blake2b::blake2b;
+use crate::denomination::Denomination;
+use crate::epic_core::core::feijoada::PoWType;
 use crate::epic_core::libtx::secp_ser;
 use crate::epic_keychain::Identifier;
 use crate::epic_util::secp::pedersen;
+use crate::payment_request::PaymentRequest;
 use crate::slate_versions::ser as dalek_ser;
-use crate::slate_versions::SlateVersion;
-use crate::types::OutputData;
+use crate::slate_versions::v3::TransactionV3;
+use crate::slate_versions::{SlateVersion, VersionedSlate};
+use crate::types::{Job, NodeVersionInfo, OutputData, QueuedSend, TxLogEntry, TxLogEntryType};
+use crate::{Error, ErrorKind};
 
+use chrono::{DateTime, Utc};
 use ed25519_dalek::PublicKey as DalekPublicKey;
 use ed25519_dalek::Signature as DalekSignature;
+use uuid::Uuid;
 
 pub use crate::epic_core::core::block_fees::BlockFees;
+
+/// Domain tag of the message signed by wallet state exports
+const WALLET_STATE_SIGNATURE_DOMAIN: &[u8] = b"epic-wallet-state-export";
+
+/// Domain tag of the message signed by version advisories
+const VERSION_ADVISORY_SIGNATURE_DOMAIN: &[u8] = b"epic-wallet-version-advisory";
+
 /// Send TX API Args
 // TODO: This is here to ensure the legacy V1 API remains intact
 // remove this when v1 api is removed
@@ -57,9 +73,9 @@ pub struct InitTxArgs {
 	/// for the transaction, overriding whatever the active account is as set via the
 	/// [`set_active_account`](../epic_wallet_api/owner/struct.Owner.html#method.set_active_account) method.
 	pub src_acct_name: Option<String>,
-	#[serde(with = "secp_ser::string_or_u64")]
-	/// The amount to send, in nanoepics. (`1 G = 1_000_000_000nG`)
-	pub amount: u64,
+	/// The amount to send. If `units` are given, its number of freemans is read as a whole
+	/// number of those units instead, as JSON-RPC callers give it
+	pub amount: Amount,
 	#[serde(with = "secp_ser::string_or_u64")]
 	/// The minimum number of confirmations an output
 	/// should have in order to be included in the transaction.
@@ -89,7 +105,8 @@ pub struct InitTxArgs {
 	pub message: Option<String>,
 	/// Optionally set the output target slate version (acceptable
 	/// down to the minimum slate version compatible with the current. If `None` the slate
-	/// is generated with the latest version.
+	/// is generated with the latest version. Sends over http use the newest version the
+	/// recipient supports, up to this one.
 	pub target_slate_version: Option<u16>,
 	/// Number of blocks from current after which TX should be ignored
 	#[serde(with = "secp_ser::opt_string_or_u64")]
@@ -105,6 +122,81 @@ pub struct InitTxArgs {
 	/// Sender arguments. If present, the underlying function will also attempt to send the
 	/// transaction to a destination and optionally finalize the result
 	pub send_args: Option<InitTxSendArgs>,
+	/// A transaction template previously created via
+	/// [`create_tx_template`](../epic_wallet_api/owner/struct.Owner.html#method.create_tx_template).
+	/// If present, the amount, selection parameters, message, ttl and payment proof recipient
+	/// are all taken from the template, and the transaction will only be created if the wallet
+	/// still selects exactly the inputs and fee recorded in the template.
+	#[serde(default)]
+	pub template: Option<TxTemplate>,
+	/// If `true`, any `min_reorg_safe_confirmations` policy configured on the wallet is
+	/// ignored and outputs are selected using `minimum_confirmations` alone
+	#[serde(default)]
+	pub override_reorg_safety: Option<bool>,
+	/// If set, only outputs carrying this tag (see
+	/// [`tag_outputs`](../epic_wallet_api/owner/struct.Owner.html#method.tag_outputs))
+	/// are selected as inputs, and change outputs are given the same tag
+	#[serde(default)]
+	pub selection_tag: Option<String>,
+	/// A reservation made via
+	/// [`reserve_funds`](../epic_wallet_api/owner/struct.Owner.html#method.reserve_funds)
+	/// that this transaction pays out. Other reservations of the account must still be covered
+	/// by what is left after the transaction, while this one is released once the transaction
+	/// is created
+	#[serde(default)]
+	pub reservation_id: Option<Uuid>,
+	/// If `true`, `amount` is ignored and the transaction sends everything the account can spend
+	/// under the selection parameters, with the fee deducted from the amount sent and no change
+	/// output. All eligible outputs are spent regardless of `max_outputs` and the selection
+	/// strategy. Not used when processing an invoice, whose amount is fixed.
+	#[serde(default)]
+	pub send_max: Option<bool>,
+	/// If set, the receiver is asked to add an input of its own to the transaction, paying
+	/// this much of the fee out of it. The sender's change is larger by the same amount. Only
+	/// for receivers offering to contribute, as reported by their
+	/// [`check_version`](../epic_wallet_api/foreign/struct.Foreign.html#method.check_version),
+	/// and up to the part of the fee they offer. `Some(0)` asks for an input alone, which makes
+	/// the transaction harder to trace. Capped at the whole fee, and only an input is asked for
+	/// when the transaction has no change.
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	pub receiver_fee: Option<u64>,
+	/// If set, `amount` is a whole number of these units rather than of freemans, e.g.
+	/// `"amount": "1500", "units": "milli"` sends 1.5 EPIC
+	#[serde(default)]
+	pub units: Option<Denomination>,
+	/// If set and the account can't fund the transaction yet, but enough of its coinbase
+	/// outputs mature within this many blocks, the send is queued rather than refused, and
+	/// made once they have by
+	/// [`process_send_queue`](../epic_wallet_api/owner/struct.Owner.html#method.process_send_queue).
+	/// Needs `send_args`, so the queued send can be delivered without the caller.
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	pub wait_for_maturity: Option<u64>,
+	/// Where the transaction is being sent, e.g. a listener's address, recorded with it. If
+	/// not set, the `dest` of `send_args` is used. A send of the same amount to the same
+	/// destination as a sent transaction that is unconfirmed, or was created in the last
+	/// `DUPLICATE_PAYMENT_WINDOW_SECS`, is refused as a likely repeat unless `allow_duplicate` is set. Sends without a
+	/// destination aren't checked.
+	#[serde(default)]
+	pub destination: Option<String>,
+	/// If `true`, make the send even if it looks like a repeat of an earlier one
+	#[serde(default)]
+	pub allow_duplicate: Option<bool>,
+	/// The largest part of the fee, in freemans, the payer will cover when paying an invoice
+	/// with [`process_invoice_tx`](../epic_wallet_api/owner/struct.Owner.html#method.process_invoice_tx).
+	/// An invoice that would cost more is refused. Any `max_invoice_fee` configured on the
+	/// wallet applies as well. Not used when sending.
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	pub max_invoice_fee: Option<u64>,
+	/// A payment request to pay, as set by
+	/// [`pay_request`](../epic_wallet_api/owner/struct.Owner.html#method.pay_request). The
+	/// amount and payment proof recipient are taken from the request, as is the message
+	/// unless one is given, and the transaction takes the request's id as its slate id. Any
+	/// amount given must match the request's.
+	#[serde(default)]
+	pub payment_request: Option<PaymentRequest>,
 }
 
 /// Send TX API Args, for convenience functionality that inits the transaction and sends
@@ -127,7 +219,7 @@ impl Default for InitTxArgs {
 	fn default() -> InitTxArgs {
 		InitTxArgs {
 			src_acct_name: None,
-			amount: 0,
+			amount: Amount::ZERO,
 			minimum_confirmations: 10,
 			max_outputs: 500,
 			num_change_outputs: 1,
@@ -138,10 +230,87 @@ impl Default for InitTxArgs {
 			estimate_only: Some(false),
 			payment_proof_recipient_address: None,
 			send_args: None,
+			template: None,
+			override_reorg_safety: None,
+			selection_tag: None,
+			reservation_id: None,
+			send_max: None,
+			receiver_fee: None,
+			units: None,
+			wait_for_maturity: None,
+			destination: None,
+			allow_duplicate: None,
+			max_invoice_fee: None,
+			payment_request: None,
 		}
 	}
 }
 
+/// An input selected by a transaction template
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TxTemplateInput {
+	/// The commitment of the output being spent
+	pub commit: String,
+	/// The value of the output being spent
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub value: u64,
+}
+
+/// An output that will be created by the transaction described in a template
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TxTemplateOutput {
+	/// The value of the output
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub value: u64,
+	/// Whether this is a change output returning to the sender. If `false`, the output
+	/// will be created by the recipient
+	pub is_change: bool,
+}
+
+/// A fully specified but unsigned description of a transaction, containing
+/// the exact inputs, outputs and fee the wallet would use. Templates can be
+/// reviewed by other parties before being passed to `init_send_tx` via
+/// [`InitTxArgs::template`](struct.InitTxArgs.html#structfield.template), at which point
+/// the transaction is created only if it still matches the template.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TxTemplate {
+	/// The account the inputs are drawn from
+	pub src_acct_name: Option<String>,
+	/// The amount to send, in freemans
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub amount: u64,
+	/// The fee the transaction will pay
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub fee: u64,
+	/// The chain height at which the template was created
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub height: u64,
+	/// Minimum number of confirmations used when selecting inputs
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub minimum_confirmations: u64,
+	/// Soft limit on the number of inputs used when selecting inputs
+	pub max_outputs: u32,
+	/// Number of change outputs
+	pub num_change_outputs: u32,
+	/// Selection strategy used when selecting inputs
+	pub selection_strategy_is_use_all: bool,
+	/// Tag inputs were restricted to, if any
+	#[serde(default)]
+	pub selection_tag: Option<String>,
+	/// Optional participant message
+	pub message: Option<String>,
+	/// Number of blocks from the current height after which the transaction should be ignored
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub ttl_blocks: Option<u64>,
+	/// Payment proof recipient, if a payment proof is required
+	#[serde(with = "dalek_ser::option_dalek_pubkey_serde")]
+	pub payment_proof_recipient_address: Option<DalekPublicKey>,
+	/// The inputs the transaction will spend
+	pub inputs: Vec<TxTemplateInput>,
+	/// The outputs the transaction will create, including the recipient's output
+	pub outputs: Vec<TxTemplateOutput>,
+}
+
 /// V2 Issue Invoice Tx Args
 #[derive(Clone, Serialize, Deserialize)]
 pub struct IssueInvoiceTxArgs {
@@ -149,28 +318,323 @@ pub struct IssueInvoiceTxArgs {
 	/// overriding whatever the active account is as set via the
 	/// [`set_active_account`](../epic_wallet_api/owner/struct.Owner.html#method.set_active_account) method.
 	pub dest_acct_name: Option<String>,
-	/// The invoice amount in nanoepics. (`1 G = 1_000_000_000nG`)
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount: u64,
+	/// The invoice amount. If `units` are given, its number of freemans is read as a whole
+	/// number of those units instead, as JSON-RPC callers give it
+	pub amount: Amount,
 	/// Optional message, that will be signed
 	pub message: Option<String>,
 	/// Optionally set the output target slate version (acceptable
 	/// down to the minimum slate version compatible with the current. If `None` the slate
 	/// is generated with the latest version.
 	pub target_slate_version: Option<u16>,
+	/// If set, `amount` is a whole number of these units rather than of freemans
+	#[serde(default)]
+	pub units: Option<Denomination>,
 }
 
 impl Default for IssueInvoiceTxArgs {
 	fn default() -> IssueInvoiceTxArgs {
 		IssueInvoiceTxArgs {
 			dest_acct_name: None,
-			amount: 0,
+			amount: Amount::ZERO,
 			message: None,
 			target_slate_version: None,
+			units: None,
 		}
 	}
 }
 
+/// How inputs are selected for a transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionStrategy {
+	/// Spend all outputs below the amount, combining them into one
+	All,
+	/// Spend as few outputs as will cover the amount
+	Smallest,
+}
+
+impl InitTxArgs {
+	/// A builder for transaction arguments, which checks on
+	/// [`build`](struct.InitTxArgsBuilder.html#method.build) that the amount
+	/// to send is given, one way or another, and that the rest make sense
+	/// together. Anything not set keeps its value from `InitTxArgs::default()`
+	pub fn builder() -> InitTxArgsBuilder {
+		InitTxArgsBuilder {
+			args: InitTxArgs::default(),
+			amount: None,
+			amount_from_invoice: false,
+		}
+	}
+}
+
+/// Builds [`InitTxArgs`](struct.InitTxArgs.html), see
+/// [`InitTxArgs::builder`](struct.InitTxArgs.html#method.builder)
+#[derive(Clone)]
+pub struct InitTxArgsBuilder {
+	args: InitTxArgs,
+	amount: Option<Amount>,
+	amount_from_invoice: bool,
+}
+
+impl InitTxArgsBuilder {
+	/// The amount to send
+	pub fn amount(mut self, amount: Amount) -> Self {
+		self.amount = Some(amount);
+		self
+	}
+
+	/// Send the whole spendable balance less the fee instead of an amount
+	pub fn send_max(mut self, send_max: bool) -> Self {
+		self.args.send_max = Some(send_max);
+		self
+	}
+
+	/// Take the amount from the invoice or payment request being paid, for
+	/// `process_invoice_tx` or `pay_request`, instead of giving one
+	pub fn amount_from_invoice(mut self) -> Self {
+		self.amount_from_invoice = true;
+		self
+	}
+
+	/// Build the transaction exactly as the template describes, taking the
+	/// amount and the selection settings from it
+	pub fn template(mut self, template: TxTemplate) -> Self {
+		self.args.template = Some(template);
+		self
+	}
+
+	/// Account the inputs are drawn from, rather than the active one
+	pub fn src_acct_name(mut self, name: impl Into<Option<String>>) -> Self {
+		self.args.src_acct_name = name.into();
+		self
+	}
+
+	/// Confirmations outputs need before they're spent
+	pub fn minimum_confirmations(mut self, confirmations: u64) -> Self {
+		self.args.minimum_confirmations = confirmations;
+		self
+	}
+
+	/// Soft limit on the number of inputs
+	pub fn max_outputs(mut self, max_outputs: u32) -> Self {
+		self.args.max_outputs = max_outputs;
+		self
+	}
+
+	/// Number of change outputs to create
+	pub fn num_change_outputs(mut self, num_change_outputs: u32) -> Self {
+		self.args.num_change_outputs = num_change_outputs;
+		self
+	}
+
+	/// How inputs are selected
+	pub fn strategy(mut self, strategy: SelectionStrategy) -> Self {
+		self.args.selection_strategy_is_use_all = strategy == SelectionStrategy::All;
+		self
+	}
+
+	/// Only spend outputs with this tag
+	pub fn selection_tag(mut self, tag: impl Into<Option<String>>) -> Self {
+		self.args.selection_tag = tag.into();
+		self
+	}
+
+	/// Spend the outputs held by this reservation
+	pub fn reservation_id(mut self, id: impl Into<Option<Uuid>>) -> Self {
+		self.args.reservation_id = id.into();
+		self
+	}
+
+	/// Participant message, which will be signed
+	pub fn message(mut self, message: impl Into<Option<String>>) -> Self {
+		self.args.message = message.into();
+		self
+	}
+
+	/// Slate version to create
+	pub fn target_slate_version(mut self, version: impl Into<Option<u16>>) -> Self {
+		self.args.target_slate_version = version.into();
+		self
+	}
+
+	/// Number of blocks after which the transaction is dropped if it isn't
+	/// complete
+	pub fn ttl_blocks(mut self, ttl_blocks: impl Into<Option<u64>>) -> Self {
+		self.args.ttl_blocks = ttl_blocks.into();
+		self
+	}
+
+	/// Recipient of a payment proof, which is then required
+	pub fn payment_proof_recipient_address(
+		mut self,
+		address: impl Into<Option<DalekPublicKey>>,
+	) -> Self {
+		self.args.payment_proof_recipient_address = address.into();
+		self
+	}
+
+	/// Only estimate the fee, without locking any outputs
+	pub fn estimate_only(mut self, estimate_only: bool) -> Self {
+		self.args.estimate_only = Some(estimate_only);
+		self
+	}
+
+	/// Send the transaction on once it's created
+	pub fn send_args(mut self, send_args: impl Into<Option<InitTxSendArgs>>) -> Self {
+		self.args.send_args = send_args.into();
+		self
+	}
+
+	/// Spend outputs with fewer confirmations than reorgs reach
+	pub fn override_reorg_safety(mut self, override_reorg_safety: bool) -> Self {
+		self.args.override_reorg_safety = Some(override_reorg_safety);
+		self
+	}
+
+	/// Part of the fee asked of the receiver
+	pub fn receiver_fee(mut self, receiver_fee: impl Into<Option<u64>>) -> Self {
+		self.args.receiver_fee = receiver_fee.into();
+		self
+	}
+
+	/// Queue the send for up to this many blocks if funds are still maturing
+	pub fn wait_for_maturity(mut self, blocks: impl Into<Option<u64>>) -> Self {
+		self.args.wait_for_maturity = blocks.into();
+		self
+	}
+
+	/// Destination the transaction is sent to, as recorded against it
+	pub fn destination(mut self, destination: impl Into<Option<String>>) -> Self {
+		self.args.destination = destination.into();
+		self
+	}
+
+	/// Send even if an identical payment was made recently
+	pub fn allow_duplicate(mut self, allow_duplicate: bool) -> Self {
+		self.args.allow_duplicate = Some(allow_duplicate);
+		self
+	}
+
+	/// Refuse to pay an invoice costing more than this in fees
+	pub fn max_invoice_fee(mut self, max_invoice_fee: impl Into<Option<u64>>) -> Self {
+		self.args.max_invoice_fee = max_invoice_fee.into();
+		self
+	}
+
+	/// Pay this payment request, see
+	/// [`InitTxArgs::payment_request`](struct.InitTxArgs.html#structfield.payment_request)
+	pub fn payment_request(mut self, payment_request: impl Into<Option<PaymentRequest>>) -> Self {
+		self.args.payment_request = payment_request.into();
+		self
+	}
+
+	/// The arguments, unless they're missing the amount or contradict
+	/// each other
+	pub fn build(self) -> Result<InitTxArgs, Error> {
+		let mut args = self.args;
+		let send_max = args.send_max.unwrap_or(false);
+		let amount_sources = [
+			self.amount.is_some(),
+			send_max,
+			args.template.is_some(),
+			self.amount_from_invoice,
+		];
+		match amount_sources.iter().filter(|s| **s).count() {
+			0 => return Err(tx_args_error("an amount is required")),
+			1 => {}
+			_ => {
+				return Err(tx_args_error(
+					"only one of an amount, send max, a template or an invoice can be given",
+				))
+			}
+		}
+		if let Some(a) = self.amount {
+			if a.is_zero() {
+				return Err(tx_args_error("the amount must be more than zero"));
+			}
+			args.amount = a;
+		}
+		if args.max_outputs == 0 {
+			return Err(tx_args_error("at least one input must be allowed"));
+		}
+		if args.ttl_blocks == Some(0) {
+			return Err(tx_args_error("ttl_blocks must be more than zero"));
+		}
+		if args.wait_for_maturity.is_some() && args.send_args.is_none() {
+			return Err(tx_args_error(
+				"waiting for maturity needs send arguments, to make the send later",
+			));
+		}
+		if args.estimate_only.unwrap_or(false) && args.send_args.is_some() {
+			return Err(tx_args_error("an estimate can't be sent"));
+		}
+		Ok(args)
+	}
+}
+
+impl IssueInvoiceTxArgs {
+	/// A builder for invoice arguments, which checks on
+	/// [`build`](struct.IssueInvoiceTxArgsBuilder.html#method.build) that an
+	/// amount is given
+	pub fn builder() -> IssueInvoiceTxArgsBuilder {
+		IssueInvoiceTxArgsBuilder {
+			args: IssueInvoiceTxArgs::default(),
+			amount: None,
+		}
+	}
+}
+
+/// Builds [`IssueInvoiceTxArgs`](struct.IssueInvoiceTxArgs.html), see
+/// [`IssueInvoiceTxArgs::builder`](struct.IssueInvoiceTxArgs.html#method.builder)
+#[derive(Clone)]
+pub struct IssueInvoiceTxArgsBuilder {
+	args: IssueInvoiceTxArgs,
+	amount: Option<Amount>,
+}
+
+impl IssueInvoiceTxArgsBuilder {
+	/// The amount invoiced
+	pub fn amount(mut self, amount: Amount) -> Self {
+		self.amount = Some(amount);
+		self
+	}
+
+	/// Account the payment is received into, rather than the active one
+	pub fn dest_acct_name(mut self, name: impl Into<Option<String>>) -> Self {
+		self.args.dest_acct_name = name.into();
+		self
+	}
+
+	/// Participant message, which will be signed
+	pub fn message(mut self, message: impl Into<Option<String>>) -> Self {
+		self.args.message = message.into();
+		self
+	}
+
+	/// Slate version to create
+	pub fn target_slate_version(mut self, version: impl Into<Option<u16>>) -> Self {
+		self.args.target_slate_version = version.into();
+		self
+	}
+
+	/// The arguments, unless the amount is missing or zero
+	pub fn build(self) -> Result<IssueInvoiceTxArgs, Error> {
+		let mut args = self.args;
+		match self.amount {
+			None => return Err(tx_args_error("an amount is required")),
+			Some(a) if a.is_zero() => {
+				return Err(tx_args_error("the amount must be more than zero"))
+			}
+			Some(a) => args.amount = a,
+		}
+		Ok(args)
+	}
+}
+
+fn tx_args_error(reason: &str) -> Error {
+	ErrorKind::TxArgs(reason.to_owned()).into()
+}
+
 /// Map Outputdata to commits
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OutputCommitMapping {
@@ -196,6 +660,631 @@ pub struct NodeHeightResult {
 	pub updated_from_node: bool,
 }
 
+/// Status of the wallet's configured node relative to the wallet itself
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodeStatus {
+	/// Whether the node could be contacted
+	pub node_reachable: bool,
+	/// Height of the node's chain tip, if the node could be contacted
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub node_height: Option<u64>,
+	/// Hash of the node's chain tip, if the node could be contacted
+	pub header_hash: Option<String>,
+	/// Height at which the wallet's outputs were last refreshed
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub last_confirmed_height: u64,
+	/// Number of blocks the wallet is behind the node's chain tip
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub sync_lag: Option<u64>,
+	/// Version information reported by the node, if available
+	pub node_version: Option<NodeVersionInfo>,
+	/// Timestamp (UTC) of the node's chain tip, if the node could be contacted
+	#[serde(default)]
+	pub tip_timestamp: Option<DateTime<Utc>>,
+	/// Seconds the wallet's clock is ahead of the chain tip's timestamp,
+	/// negative if it is behind
+	#[serde(default)]
+	pub clock_skew_secs: Option<i64>,
+	/// Whether the skew exceeds `CLOCK_SKEW_WARNING_SECS`, in which case
+	/// times and lock heights shown by the wallet may be misleading
+	#[serde(default)]
+	pub clock_skewed: bool,
+}
+
+/// What became of a queued send once the chain reached the height its funds
+/// were expected to be spendable at
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QueuedSendResult {
+	/// The send, as it was queued
+	pub queued: QueuedSend,
+	/// Slate id of the transaction made, if the send went ahead
+	pub tx_slate_id: Option<Uuid>,
+	/// Why the send was dropped from the queue, if it failed
+	pub error: Option<String>,
+}
+
+/// What became of a queued job once it was run
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JobResult {
+	/// The job, as it's left in the queue if it failed
+	pub job: Job,
+	/// Why the job failed, if it did. It's tried again later unless it's dead
+	pub error: Option<String>,
+}
+
+/// Result of pinging the wallet's configured node
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodePing {
+	/// Whether the node answered
+	pub reachable: bool,
+	/// Height of the node's chain tip, if it answered
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub height: Option<u64>,
+	/// Hash of the node's chain tip, if it answered
+	pub header_hash: Option<String>,
+	/// How long the node took to answer or fail, in milliseconds
+	pub latency_ms: u64,
+	/// Why the node couldn't be reached
+	pub error: Option<String>,
+}
+
+/// Public key material of an account, exported so an auditor can check the
+/// wallet's key usage without being given its seed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountKeyExport {
+	/// Label of the account
+	pub label: String,
+	/// Derivation path of the account, its output keys being its children
+	pub path: Identifier,
+	/// Public key of the account's derivation key, in hex
+	pub public_key: String,
+	/// Number of output keys derived under the account so far
+	pub child_count: u32,
+}
+
+/// Commitment an output of an account is expected to have, derived from the
+/// wallet's keys for a child index and value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExpectedCommitment {
+	/// Key id of the child
+	pub key_id: Identifier,
+	/// Index of the child under the account's path
+	pub child_index: u32,
+	/// Value committed to
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub value: u64,
+	/// The commitment, with the switch commitment applied as for the
+	/// wallet's outputs
+	#[serde(
+		serialize_with = "secp_ser::as_hex",
+		deserialize_with = "secp_ser::commitment_from_hex"
+	)]
+	pub commit: pedersen::Commitment,
+	/// Whether the wallet holds an output with this commitment
+	pub in_wallet: bool,
+}
+
+/// What rewinding an output's range proof with the wallet's keys revealed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RewoundOutput {
+	/// Commitment of the output
+	#[serde(
+		serialize_with = "secp_ser::as_hex",
+		deserialize_with = "secp_ser::commitment_from_hex"
+	)]
+	pub commit: pedersen::Commitment,
+	/// Whether the output belongs to this wallet
+	pub is_ours: bool,
+	/// Value of the output, if it's ours
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub value: Option<u64>,
+	/// Key id of the output, if it's ours
+	pub key_id: Option<Identifier>,
+	/// Derivation path of the output's key, such as `m/0/0/4`, if it's ours
+	pub key_path: Option<String>,
+	/// Label of the account the output's key is under, if the wallet has it
+	pub account: Option<String>,
+	/// The wallet's record of the output, if it has one
+	pub output: Option<OutputData>,
+}
+
+/// A page of transaction log entries, along with the cursor to pass back to
+/// retrieve the next page
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TxLogPage {
+	/// The entries in this page
+	pub txs: Vec<TxLogEntry>,
+	/// Cursor to retrieve the next page, `None` if this page is the last one
+	pub next_cursor: Option<String>,
+}
+
+/// A page of outputs, along with the cursor to pass back to retrieve the
+/// next page
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutputPage {
+	/// The outputs in this page
+	pub outputs: Vec<OutputCommitMapping>,
+	/// Cursor to retrieve the next page, `None` if this page is the last one
+	pub next_cursor: Option<String>,
+}
+
+/// A coinbase output removed from the wallet because it was still unconfirmed
+/// after the cleanup window, along with the tx log entry recording its removal
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CleanedCoinbase {
+	/// The reverted coinbase tx log entry
+	pub tx: TxLogEntry,
+	/// The removed output, as recorded in the output history
+	pub output: Option<OutputData>,
+}
+
+/// An output grouped with the event that spent or deleted it and the earlier
+/// states recorded for it in the output history
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutputLineage {
+	/// The output in its latest state, taken from the history if deleted
+	pub output: OutputCommitMapping,
+	/// The tx log entry that spent or deleted the output, if any
+	pub spent_by: Option<TxLogEntry>,
+	/// Confirmation height of the spending tx if known, otherwise the height
+	/// the spend was first seen at
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub spent_height: Option<u64>,
+	/// Earlier states of the output, oldest first
+	pub history: Vec<OutputData>,
+}
+
+/// A locked output with the transaction holding the lock, for finding locks
+/// that keep funds unavailable
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedOutput {
+	/// The locked output
+	pub output: OutputCommitMapping,
+	/// The tx log entry that locked the output, if it's still in the log
+	pub locked_by: Option<TxLogEntry>,
+	/// When the lock was taken, which is when the locking transaction was
+	/// created
+	pub locked_since: Option<DateTime<Utc>>,
+	/// Whether nothing holds the lock any more, because the locking
+	/// transaction is gone from the log or was cancelled
+	pub stale: bool,
+}
+
+/// Everything recorded about a transaction, gathered in one place
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TxDetails {
+	/// The tx log entry
+	pub tx: TxLogEntry,
+	/// Outputs the transaction spent, or locked to spend
+	pub inputs: Vec<OutputCommitMapping>,
+	/// Outputs the transaction created
+	pub outputs: Vec<OutputCommitMapping>,
+	/// The transaction body, if it was stored
+	pub stored_tx: Option<TransactionV3>,
+	/// Kernel excess, from the log entry or failing that the stored body
+	#[serde(with = "secp_ser::option_commitment_serde")]
+	#[serde(default)]
+	pub kernel_excess: Option<pedersen::Commitment>,
+	/// The payment proof, once both parties have signed it
+	pub payment_proof: Option<PaymentProof>,
+	/// The other tx log entries recorded for the same slate, such as the
+	/// other side of a self-send
+	pub slate_history: Vec<TxLogEntry>,
+}
+
+/// What cancelling a transaction would do, for checking before going ahead
+/// that the transaction hasn't gone through after all
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CancelPreview {
+	/// The tx log entry that would be cancelled
+	pub tx: TxLogEntry,
+	/// Inputs locked by the transaction that would become spendable again
+	pub unlocked_outputs: Vec<OutputCommitMapping>,
+	/// Change or received outputs of the transaction that would be deleted
+	pub deleted_outputs: Vec<OutputCommitMapping>,
+	/// Height of the block holding the transaction kernel, if the node
+	/// already has it on chain
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub kernel_height: Option<u64>,
+	/// Whether the node holds the transaction in its pool, waiting to be
+	/// mined. Not known if the kernel excess isn't, or the node couldn't say
+	pub in_pool: Option<bool>,
+	/// Whether this wallet posted the transaction, or was about to
+	pub posted: bool,
+	/// Whether the transaction might still confirm after being cancelled,
+	/// because its kernel is on chain or in the pool, it was posted, or the
+	/// other party holds everything needed to post it
+	pub may_confirm: bool,
+}
+
+/// Notice of a transaction received through the foreign API, posted to the
+/// configured receive callback. Signed with the receiving wallet's payment
+/// proof address, so the merchant backend can check it came from the wallet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiveNotification {
+	/// Slate id of the received transaction
+	pub slate_id: Uuid,
+	/// Amount received
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub amount: u64,
+	/// Payment proof address of the receiving account
+	#[serde(with = "dalek_ser::dalek_pubkey_serde")]
+	pub proof_address: DalekPublicKey,
+	/// Payment proof address of the sender, if a payment proof was requested
+	#[serde(with = "dalek_ser::option_dalek_pubkey_serde")]
+	pub sender_proof_address: Option<DalekPublicKey>,
+	/// Signature of the above by the receiving account's proof address
+	#[serde(with = "dalek_ser::dalek_sig_serde")]
+	pub signature: DalekSignature,
+}
+
+impl ReceiveNotification {
+	/// The signed message: slate id, amount and the sender's proof address
+	/// if present
+	pub fn message(
+		slate_id: &Uuid,
+		amount: u64,
+		sender_proof_address: &Option<DalekPublicKey>,
+	) -> Vec<u8> {
+		let mut msg = slate_id.as_bytes().to_vec();
+		msg.extend_from_slice(&amount.to_be_bytes());
+		if let Some(a) = sender_proof_address {
+			msg.extend_from_slice(a.as_bytes());
+		}
+		msg
+	}
+
+	/// Check the notification was signed by its proof address
+	pub fn verify(&self) -> Result<(), Error> {
+		let msg =
+			ReceiveNotification::message(&self.slate_id, self.amount, &self.sender_proof_address);
+		if let Err(_) = self.proof_address.verify(&msg, &self.signature) {
+			return Err(ErrorKind::PaymentProof(
+				"Invalid receive notification signature".to_owned(),
+			))?;
+		}
+		Ok(())
+	}
+}
+
+/// A payment request along with its armored and QR encodings, as created
+/// through the foreign API
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncodedPaymentRequest {
+	/// The signed request
+	pub request: PaymentRequest,
+	/// The request armored, for the payer's `pay_request`
+	pub armored: String,
+	/// The armored request as a QR code, rendered in unicode blocks. Empty
+	/// when the wallet was built without QR support
+	pub qr: String,
+}
+
+/// An unspent output in a wallet state export
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedOutput {
+	/// Key id of the output
+	pub key_id: Identifier,
+	/// Value of the output
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub value: u64,
+	/// Height of the block the output was created in
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub height: u64,
+	/// Whether the output is a coinbase reward
+	pub is_coinbase: bool,
+}
+
+/// An account in a wallet state export
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportedAccount {
+	/// Label of the account
+	pub label: String,
+	/// Derivation path of the account
+	pub path: Identifier,
+	/// Next child index to derive output keys at, so keys already used
+	/// aren't used again
+	pub child_index: u32,
+}
+
+/// The state of a wallet, as exported for another instance with the same
+/// seed to start from instead of scanning the whole chain
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WalletState {
+	/// Version of the export's format
+	pub version: u16,
+	/// When the state was exported
+	pub created_at: DateTime<Utc>,
+	/// Height of the last block the exporting wallet had scanned
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub height: u64,
+	/// Hash of that block
+	pub header_hash: String,
+	/// The wallet's accounts
+	pub accounts: Vec<ExportedAccount>,
+	/// The wallet's confirmed unspent outputs
+	pub outputs: Vec<ExportedOutput>,
+}
+
+/// A wallet state export, signed with the payment proof address of the
+/// exporting wallet's default account. Only a wallet with the same seed has
+/// the same address, so only it can import the export
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WalletStateExport {
+	/// The exported state
+	pub state: WalletState,
+	/// Payment proof address of the exporting wallet's default account
+	#[serde(with = "dalek_ser::dalek_pubkey_serde")]
+	pub proof_address: DalekPublicKey,
+	/// Signature of the state by the proof address
+	#[serde(with = "dalek_ser::dalek_sig_serde")]
+	pub signature: DalekSignature,
+}
+
+impl WalletStateExport {
+	/// The signed message: a hash of the state as JSON, after a domain tag
+	pub fn message(state: &WalletState) -> Result<Vec<u8>, Error> {
+		let json = serde_json::to_vec(state)
+			.map_err(|e| ErrorKind::StateImport(format!("state can't be serialized: {}", e)))?;
+		let mut msg = WALLET_STATE_SIGNATURE_DOMAIN.to_vec();
+		msg.extend_from_slice(blake2b(32, &[], &json).as_bytes());
+		Ok(msg)
+	}
+
+	/// Check the export was signed by its proof address
+	pub fn verify(&self) -> Result<(), Error> {
+		let msg = WalletStateExport::message(&self.state)?;
+		if let Err(_) = self.proof_address.verify(&msg, &self.signature) {
+			return Err(ErrorKind::StateImport(
+				"the export's signature doesn't verify".to_owned(),
+			))?;
+		}
+		Ok(())
+	}
+}
+
+/// Outcome of importing a wallet state export
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WalletStateImport {
+	/// Height the wallet will carry on scanning from
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub height: u64,
+	/// Labels of the accounts added
+	pub accounts: Vec<String>,
+	/// The outputs found unspent on chain as exported, and added
+	pub imported: Vec<OutputData>,
+	/// The outputs not found unspent on chain as exported, which a scan will
+	/// pick up if they're still the wallet's
+	pub skipped: Vec<ExportedOutput>,
+}
+
+/// A row of a CSV export of another wallet that wasn't imported
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CsvSkippedRow {
+	/// Line of the CSV the row starts on
+	pub line: usize,
+	/// Why the row wasn't imported
+	pub reason: String,
+}
+
+/// Outcome of importing the outputs or transactions another wallet exported
+/// as CSV
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CsvImport<T> {
+	/// The records imported, or filled in from the CSV
+	pub imported: Vec<T>,
+	/// The rows that weren't imported
+	pub skipped: Vec<CsvSkippedRow>,
+}
+
+/// Payment status of an invoice issued through the merchant API
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MerchantInvoiceStatus {
+	/// Waiting for the customer to pay
+	Unpaid,
+	/// Paid and finalized, waiting to be confirmed on chain
+	Paid,
+	/// Payment confirmed on chain
+	Confirmed,
+	/// Cancelled before being paid
+	Cancelled,
+}
+
+/// Balance of one of the accounts a busy wallet's load is sharded across
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardBalance {
+	/// Name of the account
+	pub account: String,
+	/// Amount that can be spent now
+	pub amount_currently_spendable: Amount,
+	/// Amount received but not yet confirmed
+	pub amount_awaiting_confirmation: Amount,
+	/// Amount locked by transactions in progress
+	pub amount_locked: Amount,
+}
+
+/// An invoice issued through the merchant API. Invoices are kept in the
+/// transaction log, and identified by the id of their slate.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MerchantInvoice {
+	/// Invoice id, which is the id of its slate
+	pub id: Uuid,
+	/// Amount invoiced
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub amount: u64,
+	/// Memo given when the invoice was issued
+	pub memo: Option<String>,
+	/// Payment status
+	pub status: MerchantInvoiceStatus,
+	/// When the invoice was issued
+	pub created_at: DateTime<Utc>,
+	/// When the payment was confirmed, if it has been
+	pub confirmed_at: Option<DateTime<Utc>>,
+	/// The invoice slate for the customer to pay, only given when the invoice
+	/// is issued
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub slate: Option<VersionedSlate>,
+}
+
+impl MerchantInvoice {
+	/// The invoice recorded by a transaction log entry
+	pub fn from_tx_log(entry: &TxLogEntry) -> Result<MerchantInvoice, Error> {
+		let id = match entry.tx_slate_id {
+			Some(id) => id,
+			None => return Err(ErrorKind::TransactionDoesntExist(entry.id.to_string()).into()),
+		};
+		let status = match entry.tx_type {
+			TxLogEntryType::TxReceivedCancelled => MerchantInvoiceStatus::Cancelled,
+			TxLogEntryType::TxReceived if entry.confirmed => MerchantInvoiceStatus::Confirmed,
+			TxLogEntryType::TxReceived if entry.stored_tx.is_some() => MerchantInvoiceStatus::Paid,
+			TxLogEntryType::TxReceived => MerchantInvoiceStatus::Unpaid,
+			_ => return Err(ErrorKind::TransactionDoesntExist(id.to_string()).into()),
+		};
+		// the invoicer is participant 1
+		let memo = entry.messages.as_ref().and_then(|m| {
+			m.messages
+				.iter()
+				.find(|d| d.id == 1)
+				.and_then(|d| d.message.clone())
+		});
+		Ok(MerchantInvoice {
+			id,
+			amount: entry.amount_credited.freemans(),
+			memo,
+			status,
+			created_at: entry.creation_ts,
+			confirmed_at: match entry.confirmed {
+				true => entry.confirmation_ts,
+				false => None,
+			},
+			slate: None,
+		})
+	}
+}
+
+/// Mining rewards received by the wallet for blocks of a given proof of work
+/// algorithm
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MiningRewardSummary {
+	/// Proof of work algorithm of the rewarded blocks, if known
+	pub pow_type: Option<PoWType>,
+	/// Number of rewarded blocks
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub num_blocks: u64,
+	/// Total amount of the rewards
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub amount: u64,
+}
+
+/// Status of a transaction kernel on the chain, as reported by the node
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KernelStatus {
+	/// The kernel excess
+	#[serde(
+		serialize_with = "secp_ser::as_hex",
+		deserialize_with = "secp_ser::commitment_from_hex"
+	)]
+	pub excess: pedersen::Commitment,
+	/// Height of the block the kernel was included in, if it was found on the chain
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub height: Option<u64>,
+	/// MMR index of the kernel, if it was found on the chain
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub mmr_index: Option<u64>,
+	/// Id of the wallet transaction log entry with this kernel excess, if any
+	pub tx_log_entry: Option<u32>,
+	/// Whether the transaction log entry is confirmed
+	pub confirmed: bool,
+}
+
+/// How far a transaction has got towards a target number of confirmations
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum FinalityState {
+	/// Not on chain yet, or with fewer confirmations than the target
+	Pending,
+	/// Confirmed at least the target number of times
+	Final,
+	/// Cancelled without reaching the chain, or its kernel left the chain in
+	/// a reorg after being seen there
+	Invalidated,
+}
+
+/// A transaction's progress towards finality, as found on the chain
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TxFinality {
+	/// Id of the transaction log entry
+	pub tx_id: u32,
+	/// Slate id of the transaction, if known
+	pub tx_slate_id: Option<Uuid>,
+	/// Number of confirmations the transaction counts as final at
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub target_confirmations: u64,
+	/// Height of the block holding the transaction kernel, if it's on chain
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	pub kernel_height: Option<u64>,
+	/// Number of confirmations so far
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub confirmations: u64,
+	/// Chain height when the transaction was checked
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub height: u64,
+	/// Where the transaction stands
+	pub state: FinalityState,
+}
+
+/// Confirmed balance of an account at a given height
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BalanceHistoryEntry {
+	/// Block height
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub height: u64,
+	/// Total confirmed balance at this height
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub total: u64,
+}
+
+/// Whether an optional subsystem is part of this build of the wallet, and
+/// whether it can be used as the wallet is set up
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Capability {
+	/// Built into the wallet
+	pub compiled: bool,
+	/// Built in and ready for use
+	pub enabled: bool,
+}
+
+/// The optional subsystems of a wallet, so clients can adapt to the build
+/// they're talking to rather than probing for missing methods
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Capabilities {
+	/// Sending and listening over tor, enabled once a tor configuration is set
+	pub tor: Capability,
+	/// Showing and reading slates and payment requests as QR codes, the qr
+	/// feature
+	pub qr: Capability,
+	/// TLS certificates for the foreign listener obtained with ACME, the
+	/// acme feature
+	pub acme: Capability,
+	/// Atomic swaps, not yet part of any build
+	pub swaps: Capability,
+	/// Callbacks posted to URLs, such as receive notices and finality
+	/// updates, the http feature, enabled unless the owner API disallows them
+	pub webhooks: Capability,
+	/// A gRPC API alongside the JSON-RPC ones, not yet part of any build
+	pub grpc: Capability,
+	/// Signing transactions on a hardware device, not yet part of any build
+	pub hardware_signing: Capability,
+	/// Wallet passwords and seeds in the OS keystore, the os_keystore feature
+	pub os_keystore: Capability,
+	/// Wallet seeds on a PKCS#11 token, the pkcs11 feature
+	pub pkcs11: Capability,
+	/// Partial signature nonces derived from the kernel message, the
+	/// deterministic_nonces feature
+	pub deterministic_nonces: Capability,
+}
+
 /// Version request result
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VersionInfo {
@@ -203,6 +1292,137 @@ pub struct VersionInfo {
 	pub foreign_api_version: u16,
 	/// Slate version
 	pub supported_slate_versions: Vec<SlateVersion>,
+	/// If the wallet contributes an input to transactions it receives, when
+	/// the sender asks it to via the slate's `receiver_fee`, the largest part
+	/// of the fee it pays
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub receiver_fee_contribution: Option<u64>,
+	/// Release of the wallet, e.g. `3.0.0`. Missing from older wallets
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub wallet_version: Option<String>,
+	/// The upgrade advisory the wallet last fetched, if it's set up to fetch
+	/// them, so counterparties can warn users of releases it finds outdated
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub version_advisory: Option<VersionAdvisory>,
+}
+
+/// A notice that wallet releases before a version have known problems, such
+/// as vulnerabilities fixed since, signed by the payment proof address it's
+/// published under
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionAdvisory {
+	/// Oldest release still recommended, e.g. `3.0.1`
+	pub min_recommended_version: String,
+	/// What's wrong with older releases
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
+	/// Address the advisory is signed by
+	#[serde(with = "dalek_ser::dalek_pubkey_serde")]
+	pub signer: DalekPublicKey,
+	/// Signature of the version and message by the signer
+	#[serde(with = "dalek_ser::dalek_sig_serde")]
+	pub signature: DalekSignature,
+}
+
+impl VersionAdvisory {
+	/// The signed message: the version and message, after a domain tag
+	pub fn message(min_recommended_version: &str, message: &Option<String>) -> Vec<u8> {
+		let mut msg = VERSION_ADVISORY_SIGNATURE_DOMAIN.to_vec();
+		msg.extend_from_slice(min_recommended_version.as_bytes());
+		// the version can't hold a nul, so can't run into the message
+		msg.push(0);
+		if let Some(m) = message {
+			msg.extend_from_slice(m.as_bytes());
+		}
+		msg
+	}
+
+	/// Check the advisory was signed by its signer
+	pub fn verify(&self) -> Result<(), Error> {
+		if self.min_recommended_version.contains('\0') {
+			return Err(ErrorKind::VersionAdvisory("invalid version".to_owned()))?;
+		}
+		let msg = VersionAdvisory::message(&self.min_recommended_version, &self.message);
+		if let Err(_) = self.signer.verify(&msg, &self.signature) {
+			return Err(ErrorKind::VersionAdvisory(
+				"the advisory's signature doesn't verify".to_owned(),
+			))?;
+		}
+		Ok(())
+	}
+
+	/// Whether a release, e.g. `3.0.0`, is older than the recommended one.
+	/// Releases that can't be compared aren't taken to be outdated
+	pub fn is_outdated(&self, version: &str) -> bool {
+		match (
+			release_numbers(version),
+			release_numbers(&self.min_recommended_version),
+		) {
+			(Some(v), Some(min)) => v < min,
+			_ => false,
+		}
+	}
+}
+
+/// The numbers of a release, ignoring any leading `v` and pre-release suffix
+fn release_numbers(version: &str) -> Option<Vec<u64>> {
+	let version = version.trim().trim_start_matches('v');
+	let release = version.split(|c| c == '-' || c == '+').next()?;
+	release.split('.').map(|n| n.parse().ok()).collect()
+}
+
+/// How a destination is sent to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum DestinationTransport {
+	/// A wallet listening at an http(s) URL
+	Http,
+	/// A wallet listening at a Tor onion address, reached through a local Tor process
+	Tor,
+	/// A slatepack address, recognised but not yet sent to by this wallet
+	Slatepack,
+}
+
+/// Outcome of checking a destination before sending to it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DestinationValidation {
+	/// The destination as a send would use it, e.g. an onion address completed to its URL
+	pub dest: String,
+	/// How the destination is sent to
+	pub transport: DestinationTransport,
+	/// Whether a send to the destination can be made
+	pub valid: bool,
+	/// Whether a wallet listening at the destination answered
+	pub reachable: bool,
+	/// Versions that wallet reported, if it answered
+	pub version_info: Option<VersionInfo>,
+	/// Version of the slate a send would use, the newest both wallets support
+	pub slate_version: Option<SlateVersion>,
+	/// Why a send can't be made, if it can't
+	pub error: Option<String>,
+}
+
+/// Outcome of checking a slate supplied by another party, without acting on it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SlateValidation {
+	/// Id of the slate
+	pub slate_id: Uuid,
+	/// Whether every check passed
+	pub valid: bool,
+	/// Whether the participant data and kernel are consistent with the slate
+	pub structure_ok: bool,
+	/// Whether the slate and block header versions are supported by this wallet
+	pub version_supported: bool,
+	/// Whether the fee covers the transaction's weight without exceeding the amount
+	pub fee_ok: bool,
+	/// Whether the partial signatures and participant message signatures verify
+	pub signatures_ok: bool,
+	/// Why each failed check failed
+	pub errors: Vec<String>,
 }
 
 /// Packaged Payment Proof
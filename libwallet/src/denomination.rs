@@ -0,0 +1,261 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Units amounts can be expressed in, from whole epics down to the atomic
+//! freeman. The wallet always stores and transmits amounts as a number of
+//! freemans; denominations are only used to read and print them.
+
+use crate::epic_core::consensus::EPIC_BASE;
+use crate::{Error, ErrorKind};
+use std::fmt;
+use std::str::FromStr;
+
+/// A unit amounts can be given or displayed in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Denomination {
+	/// Whole epics
+	Epic,
+	/// Thousandths of an epic
+	Milli,
+	/// Millionths of an epic
+	Micro,
+	/// The atomic unit, `EPIC_BASE` of which make an epic
+	Freeman,
+}
+
+impl Default for Denomination {
+	fn default() -> Denomination {
+		Denomination::Epic
+	}
+}
+
+impl Denomination {
+	/// Number of freemans in one unit
+	pub fn freemans(&self) -> u64 {
+		match self {
+			Denomination::Epic => EPIC_BASE,
+			Denomination::Milli => EPIC_BASE / 1_000,
+			Denomination::Micro => EPIC_BASE / 1_000_000,
+			Denomination::Freeman => 1,
+		}
+	}
+
+	/// Number of decimal places a unit can be divided into
+	pub fn decimals(&self) -> usize {
+		let mut decimals = 0;
+		let mut f = self.freemans();
+		while f >= 10 {
+			f /= 10;
+			decimals += 1;
+		}
+		decimals
+	}
+
+	/// Convert a whole number of units to freemans
+	pub fn to_freemans(&self, amount: u64) -> Result<u64, Error> {
+		amount
+			.checked_mul(self.freemans())
+			.ok_or_else(|| ErrorKind::Amount(format!("{} {} is too large", amount, self)).into())
+	}
+
+	/// Parse a decimal amount in this unit, e.g. `1.5`, into freemans
+	pub fn parse_amount(&self, amount: &str) -> Result<u64, Error> {
+		let invalid = |reason: &str| -> Error {
+			ErrorKind::Amount(format!("'{}' {}", amount, reason)).into()
+		};
+		let mut parts = amount.trim().splitn(2, '.');
+		let whole = parts.next().unwrap_or("");
+		let frac = parts.next().unwrap_or("");
+		if whole.is_empty() && frac.is_empty() {
+			return Err(invalid("is not a number"));
+		}
+		if !whole
+			.chars()
+			.chain(frac.chars())
+			.all(|c| c.is_ascii_digit())
+		{
+			return Err(invalid("is not a number with an optional decimal point"));
+		}
+		if frac.len() > self.decimals() {
+			return Err(invalid(&format!(
+				"has more than {} decimal places for {}",
+				self.decimals(),
+				self
+			)));
+		}
+
+		let whole = match whole {
+			"" => 0,
+			w => w.parse::<u64>().map_err(|_| invalid("is too large"))?,
+		};
+		let frac = match frac {
+			"" => 0,
+			f => format!("{:0<width$}", f, width = self.decimals())
+				.parse::<u64>()
+				.map_err(|_| invalid("is not a number"))?,
+		};
+		whole
+			.checked_mul(self.freemans())
+			.and_then(|w| w.checked_add(frac))
+			.ok_or_else(|| invalid("is too large"))
+	}
+
+	/// Format an amount of freemans in this unit. With `truncate`, trailing
+	/// zeros are dropped, leaving at least one decimal place.
+	pub fn format_amount(&self, amount: u64, truncate: bool) -> String {
+		let decimals = self.decimals();
+		if decimals == 0 {
+			return amount.to_string();
+		}
+		let hr = format!(
+			"{}.{:0>width$}",
+			amount / self.freemans(),
+			amount % self.freemans(),
+			width = decimals
+		);
+		if !truncate {
+			return hr;
+		}
+		let hr = hr.trim_end_matches('0');
+		match hr.ends_with('.') {
+			true => format!("{}0", hr),
+			false => hr.to_owned(),
+		}
+	}
+}
+
+impl fmt::Display for Denomination {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Denomination::Epic => "epic",
+			Denomination::Milli => "milli",
+			Denomination::Micro => "micro",
+			Denomination::Freeman => "freeman",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+impl FromStr for Denomination {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Denomination, Error> {
+		match s.trim().to_lowercase().as_str() {
+			"epic" | "epics" => Ok(Denomination::Epic),
+			"milli" | "millis" | "mepic" => Ok(Denomination::Milli),
+			"micro" | "micros" | "uepic" => Ok(Denomination::Micro),
+			"freeman" | "freemans" | "atomic" => Ok(Denomination::Freeman),
+			_ => Err(ErrorKind::Amount(format!(
+				"unknown units '{}', expected epic, milli, micro or freeman",
+				s
+			))
+			.into()),
+		}
+	}
+}
+
+/// Parse an amount with optional units after it, e.g. `1.5`, `1500milli` or
+/// `250 freemans`, into freemans. Amounts without units are read in
+/// `default` units.
+pub fn parse_amount(amount: &str, default: Denomination) -> Result<u64, Error> {
+	let amount = amount.trim();
+	match amount.find(|c: char| c.is_ascii_alphabetic()) {
+		Some(i) => {
+			let units = Denomination::from_str(&amount[i..])?;
+			units.parse_amount(&amount[..i])
+		}
+		None => default.parse_amount(amount),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse_denominations() {
+		assert_eq!(Denomination::Freeman.decimals(), 0);
+		assert_eq!(Denomination::Epic.parse_amount("1").unwrap(), EPIC_BASE);
+		assert_eq!(
+			Denomination::Epic.parse_amount("1.5").unwrap(),
+			EPIC_BASE * 3 / 2
+		);
+		assert_eq!(
+			Denomination::Epic.parse_amount(".5").unwrap(),
+			EPIC_BASE / 2
+		);
+		assert_eq!(
+			Denomination::Milli.parse_amount("1500").unwrap(),
+			EPIC_BASE * 3 / 2
+		);
+		assert_eq!(Denomination::Freeman.parse_amount("250").unwrap(), 250);
+
+		// an atomic amount given in epics, or the other way round
+		assert_eq!(
+			parse_amount("100000000", Denomination::Freeman).unwrap(),
+			100_000_000
+		);
+		assert_eq!(
+			parse_amount("1 epic", Denomination::Freeman).unwrap(),
+			EPIC_BASE
+		);
+		assert_eq!(
+			parse_amount("250freemans", Denomination::Epic).unwrap(),
+			250
+		);
+		assert_eq!(
+			parse_amount("1.5 MILLI", Denomination::Epic).unwrap(),
+			EPIC_BASE / 1_000 * 3 / 2
+		);
+
+		assert!(Denomination::Freeman.parse_amount("1.5").is_err());
+		assert!(Denomination::Epic.parse_amount("1.000000001").is_err());
+		assert!(Denomination::Epic.parse_amount("-1").is_err());
+		assert!(Denomination::Epic.parse_amount("1e8").is_err());
+		assert!(Denomination::Epic.parse_amount(".").is_err());
+		assert!(Denomination::Epic
+			.parse_amount(&u64::max_value().to_string())
+			.is_err());
+		assert!(parse_amount("1 nanoepic", Denomination::Epic).is_err());
+		assert!(Denomination::Epic.to_freemans(u64::max_value()).is_err());
+	}
+
+	#[test]
+	fn format_denominations() {
+		let amount = EPIC_BASE * 3 / 2;
+		assert_eq!(Denomination::Epic.format_amount(amount, true), "1.5");
+		assert_eq!(
+			Denomination::Epic.format_amount(amount, false),
+			format!("1.5{}", "0".repeat(Denomination::Epic.decimals() - 1))
+		);
+		assert_eq!(Denomination::Epic.format_amount(EPIC_BASE, true), "1.0");
+		assert_eq!(Denomination::Milli.format_amount(amount, true), "1500.0");
+		assert_eq!(
+			Denomination::Freeman.format_amount(amount, true),
+			amount.to_string()
+		);
+
+		for units in &[
+			Denomination::Epic,
+			Denomination::Milli,
+			Denomination::Micro,
+			Denomination::Freeman,
+		] {
+			let hr = units.format_amount(123_456_789, false);
+			assert_eq!(units.parse_amount(&hr).unwrap(), 123_456_789);
+			assert_eq!(Denomination::from_str(&units.to_string()).unwrap(), *units);
+		}
+	}
+}
@@ -17,8 +17,11 @@
 use crate::epic_core::core::{committed, transaction};
 use crate::epic_core::libtx;
 use crate::epic_keychain;
+#[cfg(feature = "store")]
 use crate::epic_store;
 use crate::epic_util::secp;
+use crate::types::TxState;
+use epic_wallet_util::RngError;
 use failure::{Backtrace, Context, Fail};
 use std::env;
 use std::fmt::{self, Display};
@@ -47,6 +50,19 @@ pub enum ErrorKind {
 		needed: u64,
 		/// Display friendly
 		needed_disp: String,
+		/// Funds spendable now with the requested number of confirmations
+		spendable: u64,
+		/// Coinbase funds that haven't reached maturity yet
+		immature: u64,
+		/// Funds locked by transactions in progress
+		locked: u64,
+		/// Funds with fewer than the requested number of confirmations
+		awaiting_confirmation: u64,
+		/// How much more than available is needed
+		shortfall: u64,
+		/// Earliest height at which enough funds are spendable, counting only
+		/// confirmed outputs, if any is
+		enough_at_height: Option<u64>,
 	},
 
 	/// Fee error
@@ -177,6 +193,34 @@ pub enum ErrorKind {
 	#[fail(display = "Unknown Account Label '{}'", _0)]
 	UnknownAccountLabel(String),
 
+	/// Spending from a frozen account
+	#[fail(display = "Account '{}' is frozen, spends from it are blocked", _0)]
+	AccountFrozen(String),
+
+	/// Freezing or unfreezing an account refused
+	#[fail(display = "Account freeze refused: {}", _0)]
+	AccountFreeze(String),
+
+	/// Exporting an account's public key material refused
+	#[fail(display = "Account key export refused: {}", _0)]
+	AccountKeyExport(String),
+
+	/// Range proof given to rewind isn't usable
+	#[fail(display = "Range proof rewind failed: {}", _0)]
+	RangeProofRewind(String),
+
+	/// Importing an output refused
+	#[fail(display = "Output import refused: {}", _0)]
+	ImportOutput(String),
+
+	/// Importing a wallet state export refused
+	#[fail(display = "Wallet state import refused: {}", _0)]
+	StateImport(String),
+
+	/// A CSV export of another wallet that can't be read
+	#[fail(display = "CSV import error: {}", _0)]
+	CsvImport(String),
+
 	/// Error from summing commitments via committed trait.
 	#[fail(display = "Committed Error")]
 	Committed(committed::Error),
@@ -245,6 +289,166 @@ pub enum ErrorKind {
 	#[fail(display = "Transaction Expired")]
 	TransactionExpired,
 
+	/// Transaction no longer matches the template it was created from
+	#[fail(display = "Transaction template error: {}", _0)]
+	TxTemplate(String),
+
+	/// Foundation outputs requested from a wallet not set up as the foundation wallet
+	#[fail(display = "This wallet is not configured as the foundation wallet")]
+	NotFoundationWallet,
+
+	/// Output stored without its commitment
+	#[fail(display = "Output {} has no stored commitment", _0)]
+	MissingOutputCommit(String),
+
+	/// Continuation cursor not issued by this wallet
+	#[fail(display = "Invalid continuation cursor: {}", _0)]
+	InvalidCursor(String),
+
+	/// Wallet database written by a newer version of the wallet, which this
+	/// version can't read
+	#[fail(
+		display = "Wallet database schema version {} is newer than the supported version {}",
+		_0, _1
+	)]
+	UnsupportedSchemaVersion(u32, u32),
+
+	/// Write attempted to a wallet database opened read-only, because it was
+	/// written by a newer wallet version or is opened as a replica
+	#[fail(display = "Wallet database is open read-only")]
+	ReadOnlyWallet,
+
+	/// Node is running a different chain than the wallet is configured for
+	#[fail(
+		display = "Wallet is configured for {} but the node is running {}",
+		_0, _1
+	)]
+	ChainTypeMismatch(String, String),
+
+	/// Wallet provisioning refused, e.g. because of a wrong provisioning token
+	#[fail(display = "Wallet provisioning refused: {}", _0)]
+	ProvisioningDenied(String),
+
+	/// Manual change of an output's status refused
+	#[fail(display = "Output status override refused: {}", _0)]
+	OutputStatusOverride(String),
+
+	/// An output lock was not released because doing so could be unsafe
+	#[fail(display = "Refusing to unlock output: {}", _0)]
+	UnlockRefused(String),
+
+	/// Cancelling a transaction that may have gone through
+	#[fail(display = "Refusing to cancel transaction: {}", _0)]
+	CancelRefused(String),
+
+	/// Watching a transaction for finality
+	#[fail(display = "Unable to watch transaction: {}", _0)]
+	FinalityWatch(String),
+
+	/// Tagging outputs
+	#[fail(display = "Output tag error: {}", _0)]
+	OutputTag(String),
+
+	/// Reserving funds, or spending reserved ones
+	#[fail(display = "Reservation error: {}", _0)]
+	Reservation(String),
+
+	/// Parsing an amount or its units
+	#[fail(display = "Amount error: {}", _0)]
+	Amount(String),
+
+	/// A slate is malformed
+	#[fail(display = "Invalid slate: {}", _0)]
+	InvalidSlate(String),
+
+	/// A call was refused by one of the wallet's policies
+	#[fail(display = "Refused by wallet policy: {}", _0)]
+	PolicyRefused(String),
+
+	/// Error encoding a slate as QR codes, or reading it back
+	#[fail(display = "QR code error: {}", _0)]
+	QrCode(String),
+
+	/// An `epic:` payment link that can't be read or paid
+	#[fail(display = "Invalid payment URI: {}", _0)]
+	PaymentUri(String),
+
+	/// A payment request that can't be read, or paid
+	#[fail(display = "Invalid payment request: {}", _0)]
+	PaymentRequest(String),
+
+	/// A deposit tag that can't be used in a listener path
+	#[fail(display = "Invalid deposit tag: {}", _0)]
+	DepositTag(String),
+
+	/// Spreading load across shard accounts
+	#[fail(display = "Shard error: {}", _0)]
+	Sharding(String),
+
+	/// Foreign API response signature missing or invalid
+	#[fail(display = "Response signature error: {}", _0)]
+	ResponseSignature(String),
+
+	/// An upgrade advisory that can't be used
+	#[fail(display = "Version advisory error: {}", _0)]
+	VersionAdvisory(String),
+
+	/// Transaction arguments missing something required, or contradicting
+	/// each other
+	#[fail(display = "Transaction arguments error: {}", _0)]
+	TxArgs(String),
+
+	/// Receiver unable or unwilling to contribute to a transaction as asked
+	#[fail(display = "Receiver contribution error: {}", _0)]
+	ReceiverContribution(String),
+
+	/// Not enough funds yet, so the send was queued until they mature
+	#[fail(
+		display = "Send {} queued until funds mature at height {}",
+		id, ready_height
+	)]
+	SendQueued {
+		/// Id of the queued send
+		id: String,
+		/// Height at which the send is expected to be made
+		ready_height: u64,
+	},
+
+	/// Queueing a send until funds mature, or making it
+	#[fail(display = "Send queue error: {}", _0)]
+	SendQueue(String),
+
+	/// Queueing a job, or running it
+	#[fail(display = "Job queue error: {}", _0)]
+	JobQueue(String),
+
+	/// A send would likely repeat an unconfirmed or recent one
+	#[fail(
+		display = "Transaction {} already sends this amount to {}",
+		id, destination
+	)]
+	DuplicatePayment {
+		/// Id of the tx log entry of the earlier send
+		id: u32,
+		/// Destination both sends are to
+		destination: String,
+	},
+
+	/// The OS random number generator failed, or failed a health check
+	#[fail(display = "Random number generator error: {}", _0)]
+	Rng(String),
+
+	/// A transaction log entry can't move to a state from the one it's in
+	#[fail(display = "Transaction {} can't go from {} to {}", _0, _1, _2)]
+	InvalidTxTransition(u32, TxState, TxState),
+
+	/// An idempotency key given again, for a call with other arguments
+	#[fail(
+		display = "Idempotency key {} already used for a {} call with other arguments",
+		_1, _0
+	)]
+	IdempotencyKeyReused(String, String),
+
 	/// Other
 	#[fail(display = "Generic error: {}", _0)]
 	GenericError(String),
@@ -360,6 +564,14 @@ impl From<secp::Error> for Error {
 	}
 }
 
+impl From<RngError> for Error {
+	fn from(error: RngError) -> Error {
+		Error {
+			inner: Context::new(ErrorKind::Rng(error.0)),
+		}
+	}
+}
+
 impl From<committed::Error> for Error {
 	fn from(error: committed::Error) -> Error {
 		Error {
@@ -368,6 +580,7 @@ impl From<committed::Error> for Error {
 	}
 }
 
+#[cfg(feature = "store")]
 impl From<epic_store::Error> for Error {
 	fn from(error: epic_store::Error) -> Error {
 		Error::from(ErrorKind::Backend(format!("{}", error)))
@@ -0,0 +1,136 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Events a wallet emits as its state changes, for applications embedding it
+//! to follow without comparing the results of successive queries.
+//!
+//! Each open wallet has an [EventBus](struct.EventBus.html), see
+//! [WalletBackend::events](../trait.WalletBackend.html#tymethod.events).
+//! Every subscriber gets its own channel receiving all events emitted after
+//! it subscribed, once the changes they report have been committed.
+
+use crate::amount::Amount;
+use crate::epic_keychain::Identifier;
+use crate::epic_util::Mutex;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A change to the wallet's state
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum WalletEvent {
+	/// An output the wallet was waiting for was found on the chain
+	OutputConfirmed {
+		/// Account the output belongs to
+		root_key_id: Identifier,
+		/// Commitment of the output, if stored
+		commit: Option<String>,
+		/// Value of the output
+		value: Amount,
+		/// Height of the block the output was found in
+		height: u64,
+		/// Id of the transaction log entry the output is part of
+		tx_log_entry: Option<u32>,
+	},
+	/// A transaction was cancelled, by request or as its TTL expired, and
+	/// its outputs released
+	TxCancelled {
+		/// Account the transaction belongs to
+		parent_key_id: Identifier,
+		/// Id of the transaction log entry
+		tx_id: u32,
+		/// Id of the transaction's slate
+		tx_slate_id: Option<Uuid>,
+	},
+	/// The node's chain was found shorter than the height the wallet was
+	/// last refreshed at, as when it's been reorganized or is still syncing.
+	/// Outputs aren't refreshed until the node catches up
+	ReorgDetected {
+		/// Account being refreshed
+		parent_key_id: Identifier,
+		/// Height the wallet was last refreshed at
+		wallet_height: u64,
+		/// Height of the node's chain
+		node_height: u64,
+	},
+	/// A refresh against the node has finished and its changes are stored
+	RefreshCompleted {
+		/// Account refreshed
+		parent_key_id: Identifier,
+		/// Height of the node's chain the wallet was refreshed to
+		height: u64,
+	},
+}
+
+/// Broadcasts wallet events to any number of subscribers. Clones share
+/// their subscribers
+#[derive(Clone, Default)]
+pub struct EventBus {
+	subscribers: Arc<Mutex<Vec<Sender<WalletEvent>>>>,
+}
+
+impl EventBus {
+	/// An event bus without subscribers
+	pub fn new() -> EventBus {
+		EventBus::default()
+	}
+
+	/// A receiver for every event emitted from now on. Dropping it
+	/// unsubscribes
+	pub fn subscribe(&self) -> Receiver<WalletEvent> {
+		let (tx, rx) = channel();
+		self.subscribers.lock().push(tx);
+		rx
+	}
+
+	/// Send the event to every subscriber, forgetting those that have gone
+	pub fn emit(&self, event: WalletEvent) {
+		self.subscribers
+			.lock()
+			.retain(|s| s.send(event.clone()).is_ok());
+	}
+
+	/// Number of subscribers, counting those gone since the last event
+	pub fn subscriber_count(&self) -> usize {
+		self.subscribers.lock().len()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_keychain::ExtKeychain;
+
+	#[test]
+	fn broadcast_events() {
+		let bus = EventBus::new();
+		let first = bus.subscribe();
+		let second = bus.clone().subscribe();
+		let event = WalletEvent::RefreshCompleted {
+			parent_key_id: ExtKeychain::derive_key_id(2, 0, 0, 0, 0),
+			height: 10,
+		};
+		bus.emit(event.clone());
+		assert_eq!(first.try_recv().unwrap(), event);
+		assert_eq!(second.try_recv().unwrap(), event);
+
+		// gone subscribers are dropped with the next event
+		drop(first);
+		assert_eq!(bus.subscriber_count(), 2);
+		bus.emit(event.clone());
+		assert_eq!(bus.subscriber_count(), 1);
+		assert_eq!(second.try_recv().unwrap(), event);
+		assert!(second.try_recv().is_err());
+	}
+}
@@ -0,0 +1,434 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading the outputs and transaction logs other wallets export as CSV,
+//! such as grin-wallet or other forks of this wallet, so a wallet restored
+//! from the same seed can take over their history.
+//!
+//! Columns are matched by their header, ignoring case, spaces and
+//! punctuation, so both the field names of the owner API (`tx_slate_id`)
+//! and the headings of the `outputs` and `txs` tables
+//! (`Shared Transaction Id`) are understood. Other columns are ignored.
+//! Empty fields, and fields of `None`, are taken as not given. Amounts are
+//! read in the units they're followed by, e.g. `1500milli`, or otherwise in
+//! the units of the import.
+
+use crate::api_impl::owner::parse_output_key_path;
+use crate::api_impl::types::CsvSkippedRow;
+use crate::denomination::{self, Denomination};
+use crate::epic_keychain::Identifier;
+use crate::epic_util::from_hex;
+use crate::epic_util::secp::{constants, pedersen};
+use crate::types::{OutputStatus, TxLogEntryType};
+use crate::{Error, ErrorKind};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::mem;
+use uuid::Uuid;
+
+/// An output read from a CSV export
+#[derive(Debug, Clone)]
+pub struct OutputRow {
+	/// Line of the CSV the row starts on
+	pub line: usize,
+	/// Commitment of the output
+	pub commit: Option<pedersen::Commitment>,
+	/// Key id of the output
+	pub key_id: Option<Identifier>,
+	/// Value of the output
+	pub value: Option<u64>,
+	/// Height of the block the output was created in
+	pub height: Option<u64>,
+	/// Status of the output in the exporting wallet
+	pub status: Option<OutputStatus>,
+}
+
+/// A transaction log entry read from a CSV export
+#[derive(Debug, Clone)]
+pub struct TxRow {
+	/// Line of the CSV the row starts on
+	pub line: usize,
+	/// Type of the transaction
+	pub tx_type: TxLogEntryType,
+	/// Slate transaction id
+	pub tx_slate_id: Option<Uuid>,
+	/// When the exporting wallet created the entry
+	pub creation_ts: DateTime<Utc>,
+	/// Whether the transaction was confirmed
+	pub confirmed: bool,
+	/// When the exporting wallet saw the transaction confirmed
+	pub confirmation_ts: Option<DateTime<Utc>>,
+	/// Height of the block the transaction was confirmed in
+	pub confirmation_height: Option<u64>,
+	/// Number of inputs
+	pub num_inputs: usize,
+	/// Number of outputs
+	pub num_outputs: usize,
+	/// Amount credited
+	pub amount_credited: u64,
+	/// Amount debited
+	pub amount_debited: u64,
+	/// Fee
+	pub fee: Option<u64>,
+	/// Kernel excess of the transaction
+	pub kernel_excess: Option<pedersen::Commitment>,
+}
+
+/// Read the outputs of a CSV export. It needs a commitment column, or key id
+/// and value columns. Rows that can't be read are returned as skipped
+pub fn parse_outputs(
+	csv: &str,
+	units: Denomination,
+) -> Result<(Vec<OutputRow>, Vec<CsvSkippedRow>), Error> {
+	let (header, rows) = split_header(csv)?;
+	let commit = column(&header, &["commit", "commitment", "outputcommitment"]);
+	let key_id = column(&header, &["keyid", "keypath"]);
+	let value = column(&header, &["value"]);
+	let height = column(&header, &["height", "blockheight"]);
+	let status = column(&header, &["status"]);
+	if commit.is_none() && (key_id.is_none() || value.is_none()) {
+		return Err(ErrorKind::CsvImport(
+			"outputs need a commitment column, or key id and value columns".to_owned(),
+		)
+		.into());
+	}
+
+	let mut outputs = vec![];
+	let mut skipped = vec![];
+	for (line, row) in rows {
+		let output = || -> Result<OutputRow, String> {
+			Ok(OutputRow {
+				line,
+				commit: opt(field(&row, commit), parse_commit)?,
+				key_id: opt(field(&row, key_id), parse_key_id)?,
+				value: opt(field(&row, value), |s| parse_amount(s, units))?,
+				height: opt(field(&row, height), parse_u64)?,
+				status: opt(field(&row, status), parse_status)?,
+			})
+		};
+		match output() {
+			Ok(o) => outputs.push(o),
+			Err(reason) => skipped.push(CsvSkippedRow { line, reason }),
+		}
+	}
+	Ok((outputs, skipped))
+}
+
+/// Read the transaction log entries of a CSV export. Rows that can't be
+/// read are returned as skipped
+pub fn parse_txs(
+	csv: &str,
+	units: Denomination,
+) -> Result<(Vec<TxRow>, Vec<CsvSkippedRow>), Error> {
+	let (header, rows) = split_header(csv)?;
+	let required = |names: &[&str]| -> Result<Option<usize>, Error> {
+		match column(&header, names) {
+			Some(c) => Ok(Some(c)),
+			None => Err(
+				ErrorKind::CsvImport(format!("transactions need a '{}' column", names[0])).into(),
+			),
+		}
+	};
+	let tx_type = required(&["type", "txtype"])?;
+	let creation_ts = required(&["creationts", "creationtime"])?;
+	let amount_credited = required(&["amountcredited"])?;
+	let amount_debited = required(&["amountdebited"])?;
+	let confirmed = required(&["confirmed"])?;
+	let tx_slate_id = column(&header, &["txslateid", "sharedtransactionid"]);
+	let confirmation_ts = column(&header, &["confirmationts", "confirmationtime"]);
+	let confirmation_height = column(&header, &["confirmationheight"]);
+	let num_inputs = column(&header, &["numinputs"]);
+	let num_outputs = column(&header, &["numoutputs"]);
+	let fee = column(&header, &["fee"]);
+	let kernel_excess = column(&header, &["kernelexcess", "kernel"]);
+
+	let mut txs = vec![];
+	let mut skipped = vec![];
+	for (line, row) in rows {
+		let tx = || -> Result<TxRow, String> {
+			Ok(TxRow {
+				line,
+				tx_type: parse_tx_type(given(&row, tx_type, "type")?)?,
+				tx_slate_id: opt(field(&row, tx_slate_id), |s| {
+					Uuid::parse_str(s).map_err(|_| format!("'{}' isn't a transaction id", s))
+				})?,
+				creation_ts: parse_time(given(&row, creation_ts, "creation time")?)?,
+				confirmed: parse_bool(given(&row, confirmed, "confirmed")?)?,
+				confirmation_ts: opt(field(&row, confirmation_ts), parse_time)?,
+				confirmation_height: opt(field(&row, confirmation_height), parse_u64)?,
+				num_inputs: opt(field(&row, num_inputs), parse_u64)?.unwrap_or(0) as usize,
+				num_outputs: opt(field(&row, num_outputs), parse_u64)?.unwrap_or(0) as usize,
+				amount_credited: parse_amount(
+					given(&row, amount_credited, "amount credited")?,
+					units,
+				)?,
+				amount_debited: parse_amount(
+					given(&row, amount_debited, "amount debited")?,
+					units,
+				)?,
+				fee: opt(field(&row, fee), |s| parse_amount(s, units))?,
+				kernel_excess: opt(field(&row, kernel_excess), parse_commit)?,
+			})
+		};
+		match tx() {
+			Ok(t) => txs.push(t),
+			Err(reason) => skipped.push(CsvSkippedRow { line, reason }),
+		}
+	}
+	Ok((txs, skipped))
+}
+
+/// Split CSV text into records of fields, with the line each starts on.
+/// Fields may be quoted, with `""` for a quote, to hold commas and line
+/// breaks. Blank lines are dropped
+fn records(csv: &str) -> Result<Vec<(usize, Vec<String>)>, Error> {
+	let mut records = vec![];
+	let mut record = vec![];
+	let mut field = String::new();
+	let mut quoted = false;
+	let mut line = 1;
+	let mut start = 1;
+	let mut chars = csv.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted => match chars.peek() {
+				Some('"') => {
+					chars.next();
+					field.push('"');
+				}
+				_ => quoted = false,
+			},
+			'"' if field.trim().is_empty() => {
+				field.clear();
+				quoted = true;
+			}
+			',' if !quoted => record.push(mem::replace(&mut field, String::new())),
+			'\r' if !quoted => {}
+			'\n' if !quoted => {
+				record.push(mem::replace(&mut field, String::new()));
+				records.push((start, mem::replace(&mut record, vec![])));
+				line += 1;
+				start = line;
+			}
+			c => {
+				if c == '\n' {
+					line += 1;
+				}
+				field.push(c);
+			}
+		}
+	}
+	if quoted {
+		return Err(ErrorKind::CsvImport(format!(
+			"the quoted field on line {} isn't closed",
+			start
+		))
+		.into());
+	}
+	record.push(field);
+	records.push((start, record));
+	Ok(records
+		.into_iter()
+		.filter(|(_, r)| r.iter().any(|f| !f.trim().is_empty()))
+		.collect())
+}
+
+/// The CSV's normalized header, and the records after it
+fn split_header(csv: &str) -> Result<(Vec<String>, Vec<(usize, Vec<String>)>), Error> {
+	let mut records = records(csv)?;
+	if records.is_empty() {
+		return Err(ErrorKind::CsvImport("the CSV is empty".to_owned()).into());
+	}
+	let (_, header) = records.remove(0);
+	Ok((header.iter().map(|h| normalize(h)).collect(), records))
+}
+
+/// Lowercase alphanumerics of a header or name, so `Shared Transaction Id`
+/// and `shared_transaction_id` compare equal
+fn normalize(s: &str) -> String {
+	s.chars()
+		.filter(|c| c.is_ascii_alphanumeric())
+		.map(|c| c.to_ascii_lowercase())
+		.collect()
+}
+
+/// Index of the first column known by one of `names`
+fn column(header: &[String], names: &[&str]) -> Option<usize> {
+	names
+		.iter()
+		.find_map(|n| header.iter().position(|h| h == n))
+}
+
+/// The trimmed field of a row in a column, if given
+fn field(row: &[String], column: Option<usize>) -> Option<&str> {
+	let f = row.get(column?)?.trim();
+	match f.is_empty() || f.eq_ignore_ascii_case("none") || f.eq_ignore_ascii_case("null") {
+		true => None,
+		false => Some(f),
+	}
+}
+
+/// The field of a row in a column that must be given
+fn given<'r>(row: &'r [String], column: Option<usize>, name: &str) -> Result<&'r str, String> {
+	field(row, column).ok_or_else(|| format!("{} isn't given", name))
+}
+
+fn opt<T, F>(field: Option<&str>, parse: F) -> Result<Option<T>, String>
+where
+	F: Fn(&str) -> Result<T, String>,
+{
+	field.map(parse).transpose()
+}
+
+fn parse_u64(s: &str) -> Result<u64, String> {
+	s.parse::<u64>()
+		.map_err(|_| format!("'{}' isn't a whole number", s))
+}
+
+fn parse_amount(s: &str, units: Denomination) -> Result<u64, String> {
+	denomination::parse_amount(s, units).map_err(|e| format!("{}", e))
+}
+
+fn parse_bool(s: &str) -> Result<bool, String> {
+	match normalize(s).as_str() {
+		"true" | "yes" | "1" => Ok(true),
+		"false" | "no" | "0" => Ok(false),
+		_ => Err(format!("'{}' is neither true nor false", s)),
+	}
+}
+
+fn parse_commit(s: &str) -> Result<pedersen::Commitment, String> {
+	match from_hex(s.to_owned()) {
+		Ok(c) if c.len() == constants::PEDERSEN_COMMITMENT_SIZE => {
+			Ok(pedersen::Commitment::from_vec(c))
+		}
+		_ => Err(format!("'{}' isn't a commitment", s)),
+	}
+}
+
+/// Key ids are given as hex, as in the owner API, or as key paths
+fn parse_key_id(s: &str) -> Result<Identifier, String> {
+	match s.starts_with("m/") {
+		true => parse_output_key_path(s)
+			.map_err(|_| format!("'{}' isn't an output's key path, such as m/0/0/4", s)),
+		false => Identifier::from_hex(s).map_err(|_| format!("'{}' isn't a key id", s)),
+	}
+}
+
+fn parse_status(s: &str) -> Result<OutputStatus, String> {
+	match normalize(s).as_str() {
+		"unconfirmed" => Ok(OutputStatus::Unconfirmed),
+		"unspent" => Ok(OutputStatus::Unspent),
+		"locked" => Ok(OutputStatus::Locked),
+		"spent" => Ok(OutputStatus::Spent),
+		"deleted" => Ok(OutputStatus::Deleted),
+		_ => Err(format!("'{}' isn't an output status", s)),
+	}
+}
+
+/// Transaction types are given as in the owner API (`TxReceived`) or as
+/// shown in the `txs` table (`Received Tx`)
+pub(crate) fn parse_tx_type(s: &str) -> Result<TxLogEntryType, String> {
+	match normalize(s).as_str() {
+		"confirmedcoinbase" => Ok(TxLogEntryType::ConfirmedCoinbase),
+		"txreceived" | "receivedtx" => Ok(TxLogEntryType::TxReceived),
+		"txsent" | "senttx" => Ok(TxLogEntryType::TxSent),
+		"txreceivedcancelled" | "receivedtxcancelled" => Ok(TxLogEntryType::TxReceivedCancelled),
+		"txsentcancelled" | "senttxcancelled" => Ok(TxLogEntryType::TxSentCancelled),
+		"revertedcoinbase" => Ok(TxLogEntryType::RevertedCoinbase),
+		_ => Err(format!("'{}' isn't a transaction type", s)),
+	}
+}
+
+/// Times are given in RFC3339, as in the owner API, with an offset as shown
+/// in the `txs` table, or without one in UTC
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+	DateTime::parse_from_rfc3339(s)
+		.or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S %:z"))
+		.map(|t| t.with_timezone(&Utc))
+		.or_else(|_| {
+			NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+				.map(|t| DateTime::<Utc>::from_utc(t, Utc))
+		})
+		.map_err(|_| format!("'{}' isn't a time", s))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_core::consensus::EPIC_BASE;
+
+	#[test]
+	fn read_quoted_records() {
+		let csv = "a,b\r\n\"1,5\",\"say \"\"hi\"\"\"\n\n\"two\nlines\",x\n";
+		let records = records(csv).unwrap();
+		assert_eq!(
+			records,
+			vec![
+				(1, vec!["a".to_owned(), "b".to_owned()]),
+				(2, vec!["1,5".to_owned(), "say \"hi\"".to_owned()]),
+				(4, vec!["two\nlines".to_owned(), "x".to_owned()]),
+			]
+		);
+		assert!(records("a,\"b\n").is_err());
+	}
+
+	#[test]
+	fn read_table_and_api_txs() {
+		let table = "Id,Type,Shared Transaction Id,Creation Time,Confirmed?,\
+		             Amount Credited,Amount Debited,Fee,Kernel\n\
+		             0,Confirmed Coinbase,None,2020-01-02 03:04:05 +00:00,true,\
+		             10.0,0.0,None,None\n\
+		             1,\"Sent Tx\n- Cancelled\",0436430c-2b02-624c-2032-570501212b00,\
+		             2020-01-02 03:04:05,false,0.0,1.5,0.008,None\n\
+		             2,Mined,None,yesterday,true,1.0,0.0,None,None\n";
+		let (txs, skipped) = parse_txs(table, Denomination::Epic).unwrap();
+		assert_eq!(txs.len(), 2);
+		assert_eq!(txs[0].tx_type, TxLogEntryType::ConfirmedCoinbase);
+		assert_eq!(txs[0].amount_credited, 10 * EPIC_BASE);
+		assert_eq!(txs[0].creation_ts, txs[1].creation_ts);
+		assert_eq!(txs[1].line, 3);
+		assert_eq!(txs[1].tx_type, TxLogEntryType::TxSentCancelled);
+		assert!(txs[1].tx_slate_id.is_some());
+		assert_eq!(txs[1].amount_debited, EPIC_BASE * 3 / 2);
+		assert_eq!(txs[1].fee, Some(800_000));
+		assert_eq!(skipped.len(), 1);
+		assert_eq!(skipped[0].line, 5);
+
+		let api = "tx_type,creation_ts,confirmed,amount_credited,amount_debited\n\
+		           TxReceived,2020-01-02T03:04:05Z,true,150000000,0\n";
+		let (txs, _) = parse_txs(api, Denomination::Freeman).unwrap();
+		assert_eq!(txs[0].tx_type, TxLogEntryType::TxReceived);
+		assert_eq!(txs[0].amount_credited, EPIC_BASE * 3 / 2);
+
+		assert!(parse_txs("Id,Type\n0,Received Tx\n", Denomination::Epic).is_err());
+	}
+
+	#[test]
+	fn read_outputs() {
+		let csv = "Key Id,Value,Block Height,Status\n\
+		           m/0/0/4,1.5,10,Unspent\n\
+		           0300000000000000000000000400000000,2.0,None,Spent\n\
+		           m/0/0,1.0,10,Unspent\n";
+		let (outputs, skipped) = parse_outputs(csv, Denomination::Epic).unwrap();
+		assert_eq!(outputs.len(), 2);
+		assert_eq!(outputs[0].key_id, outputs[1].key_id);
+		assert_eq!(outputs[0].value, Some(EPIC_BASE * 3 / 2));
+		assert_eq!(outputs[0].height, Some(10));
+		assert_eq!(outputs[1].height, None);
+		assert_eq!(outputs[1].status, Some(OutputStatus::Spent));
+		assert_eq!(skipped[0].line, 4);
+
+		assert!(parse_outputs("Key Id,Height\nm/0/0/4,10\n", Denomination::Epic).is_err());
+	}
+}
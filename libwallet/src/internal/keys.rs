@@ -96,6 +96,7 @@ where
 	let save_path = AcctPathMapping {
 		label: label.to_owned(),
 		path: return_id.clone(),
+		frozen: false,
 	};
 
 	let mut batch = wallet.batch(keychain_mask)?;
@@ -117,9 +118,14 @@ where
 	K: Keychain + 'a,
 {
 	let label = label.to_owned();
+	// a frozen account stays frozen
+	let frozen = wallet
+		.acct_path_iter()
+		.any(|m| m.label == label && m.frozen);
 	let save_path = AcctPathMapping {
 		label: label.to_owned(),
 		path: path.clone(),
+		frozen,
 	};
 
 	let mut batch = wallet.batch(keychain_mask)?;
@@ -13,18 +13,24 @@
 // limitations under the License.
 //! Functions to restore a wallet's outputs from just the master seed
 
+use crate::amount::Amount;
 use crate::api_impl::owner_updater::StatusMessage;
-use crate::epic_core::consensus::{valid_header_version, WEEK_HEIGHT};
+use crate::epic_core::consensus::{
+	cumulative_reward_foundation, is_foundation_height, valid_header_version, WEEK_HEIGHT,
+};
 use crate::epic_core::core::HeaderVersion;
 use crate::epic_core::global;
 use crate::epic_core::libtx::proof;
 use crate::epic_keychain::{Identifier, Keychain, SwitchCommitmentType};
+use crate::epic_util;
 use crate::epic_util::secp::key::SecretKey;
 use crate::epic_util::secp::pedersen;
 use crate::epic_util::Mutex;
 use crate::internal::{keys, updater};
 use crate::types::*;
-use crate::{wallet_lock, Error, OutputCommitMapping};
+use crate::{wallet_lock, Error, ErrorKind, ExportedOutput, OutputCommitMapping};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::cmp;
 use std::collections::HashMap;
 use std::sync::mpsc::Sender;
@@ -63,9 +69,115 @@ struct RestoredTxStats {
 	pub num_outputs: usize,
 }
 
+/// Number of range proofs each worker rewinds in one go. Handing out proofs in
+/// contiguous batches keeps each thread working through the same secp context
+/// and proof builders rather than contending for individual items
+const REWIND_BATCH_SIZE: usize = 64;
+
+/// An output as retrieved from the chain: commitment, range proof,
+/// whether it's a coinbase output, block height and MMR index
+type ChainOutput = (pedersen::Commitment, pedersen::RangeProof, bool, u64, u64);
+
+/// Amount, key id and switch commitment type recovered from a range proof
+pub type RewindInfo = (u64, Identifier, SwitchCommitmentType);
+
+/// Attempt to unwind the message from an output's range proof, which
+/// will fail if the output isn't ours
+fn rewind_output<K>(
+	keychain: &K,
+	legacy_builder: &proof::LegacyProofBuilder<'_, K>,
+	builder: &proof::ProofBuilder<'_, K>,
+	output: &ChainOutput,
+) -> Result<Option<RewindInfo>, Error>
+where
+	K: Keychain,
+{
+	let (commit, proof, _, height, _) = output;
+	// Before HF+2wk, try legacy rewind first
+	let info_legacy = if valid_header_version(*height, HeaderVersion(6)) {
+		proof::rewind(keychain.secp(), legacy_builder, *commit, None, *proof)?
+	} else {
+		None
+	};
+
+	// If legacy didn't work, try new rewind
+	if info_legacy.is_none() {
+		Ok(proof::rewind(
+			keychain.secp(),
+			builder,
+			*commit,
+			None,
+			*proof,
+		)?)
+	} else {
+		Ok(info_legacy)
+	}
+}
+
+/// Rewind the range proof of a single output, whose height isn't known, so
+/// both the legacy and current proof builders are tried
+pub fn rewind_single_output<K>(
+	keychain: &K,
+	commit: pedersen::Commitment,
+	proof: pedersen::RangeProof,
+) -> Result<Option<RewindInfo>, Error>
+where
+	K: Keychain,
+{
+	let legacy_builder = proof::LegacyProofBuilder::new(keychain);
+	let info_legacy = proof::rewind(keychain.secp(), &legacy_builder, commit, None, proof)?;
+	if info_legacy.is_some() {
+		return Ok(info_legacy);
+	}
+	let builder = proof::ProofBuilder::new(keychain);
+	Ok(proof::rewind(
+		keychain.secp(),
+		&builder,
+		commit,
+		None,
+		proof,
+	)?)
+}
+
+/// Rewind the range proofs of the given outputs in batches across the
+/// threads of `pool`, returning the recovered info for each output in order
+fn rewind_outputs<K>(
+	keychain: &K,
+	pool: &ThreadPool,
+	outputs: &[ChainOutput],
+) -> Result<Vec<Option<RewindInfo>>, Error>
+where
+	K: Keychain,
+{
+	let legacy_builder = proof::LegacyProofBuilder::new(keychain);
+	let builder = proof::ProofBuilder::new(keychain);
+	let batches = pool.install(|| {
+		outputs
+			.par_chunks(REWIND_BATCH_SIZE)
+			.map(|batch| {
+				batch
+					.iter()
+					.map(|o| rewind_output(keychain, &legacy_builder, &builder, o))
+					.collect::<Result<Vec<_>, Error>>()
+			})
+			.collect::<Result<Vec<_>, Error>>()
+	})?;
+	Ok(batches.into_iter().flatten().collect())
+}
+
+/// Thread pool used to rewind range proofs during a scan. Sized to the
+/// number of available cores unless overridden by `RAYON_NUM_THREADS`
+fn rewind_thread_pool() -> Result<ThreadPool, Error> {
+	ThreadPoolBuilder::new()
+		.thread_name(|i| format!("scan-rewind-{}", i))
+		.build()
+		.map_err(|e| ErrorKind::GenericError(format!("Unable to start scan threads: {}", e)).into())
+}
+
 fn identify_utxo_outputs<'a, K>(
 	keychain: &K,
-	outputs: Vec<(pedersen::Commitment, pedersen::RangeProof, bool, u64, u64)>,
+	pool: &ThreadPool,
+	outputs: Vec<ChainOutput>,
 	status_send_channel: &Option<Sender<StatusMessage>>,
 	percentage_complete: u8,
 ) -> Result<Vec<OutputResult>, Error>
@@ -74,29 +186,10 @@ where
 {
 	let mut wallet_outputs: Vec<OutputResult> = Vec::new();
 
-	let legacy_builder = proof::LegacyProofBuilder::new(keychain);
-	let builder = proof::ProofBuilder::new(keychain);
-	let legacy_version = HeaderVersion(6);
-
-	for output in outputs.iter() {
-		let (commit, proof, is_coinbase, height, mmr_index) = output;
-		// attempt to unwind message from the RP and get a value
-		// will fail if it's not ours
-		let info = {
-			// Before HF+2wk, try legacy rewind first
-			let info_legacy = if valid_header_version(*height, legacy_version) {
-				proof::rewind(keychain.secp(), &legacy_builder, *commit, None, *proof)?
-			} else {
-				None
-			};
-
-			// If legacy didn't work, try new rewind
-			if info_legacy.is_none() {
-				proof::rewind(keychain.secp(), &builder, *commit, None, *proof)?
-			} else {
-				info_legacy
-			}
-		};
+	let infos = rewind_outputs(keychain, pool, &outputs)?;
+
+	for (output, info) in outputs.iter().zip(infos.into_iter()) {
+		let (commit, _, is_coinbase, height, mmr_index) = output;
 
 		let (amount, key_id, switch) = match info {
 			Some(i) => i,
@@ -157,6 +250,7 @@ where
 	let mut start_index = start_index;
 	let mut result_vec: Vec<OutputResult> = vec![];
 	let last_retrieved_return_index;
+	let pool = rewind_thread_pool()?;
 	loop {
 		let (highest_index, last_retrieved_index, outputs) =
 			client.get_outputs_by_pmmr_index(start_index, end_index, batch_size)?;
@@ -177,7 +271,8 @@ where
 
 		result_vec.append(&mut identify_utxo_outputs(
 			keychain,
-			outputs.clone(),
+			&pool,
+			outputs,
 			status_send_channel,
 			perc_complete as u8,
 		)?);
@@ -191,6 +286,12 @@ where
 	Ok((result_vec, last_retrieved_return_index))
 }
 
+/// Whether a restored output looks like a foundation reward, i.e. a coinbase
+/// output at a foundation height carrying exactly the foundation amount
+fn is_foundation_output(is_coinbase: bool, height: u64, value: u64) -> bool {
+	is_coinbase && is_foundation_height(height) && value == cumulative_reward_foundation(height)
+}
+
 ///
 fn restore_missing_output<'a, L, C, K>(
 	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
@@ -206,7 +307,7 @@ where
 {
 	wallet_lock!(wallet_inst, w);
 
-	let commit = w.calc_commit_for_cache(keychain_mask, output.value, &output.key_id)?;
+	let commit = Some(w.calc_commit_for_cache(keychain_mask, output.value, &output.key_id)?);
 	let mut batch = w.batch(keychain_mask)?;
 
 	let parent_key_id = output.key_id.parent_path();
@@ -231,10 +332,9 @@ where
 			false => TxLogEntryType::TxReceived,
 		};
 		let mut t = TxLogEntry::new(parent_key_id.clone(), entry_type, log_id);
-		t.confirmed = true;
-		t.amount_credited = output.value;
+		t.confirm(output.height)?;
+		t.amount_credited = Amount::from_freemans(output.value);
 		t.num_outputs = 1;
-		t.update_confirmation_ts();
 		batch.save_tx_log_entry(t, &parent_key_id)?;
 		log_id
 	} else {
@@ -266,6 +366,11 @@ where
 		lock_height: output.lock_height,
 		is_coinbase: output.is_coinbase,
 		tx_log_entry: Some(log_id),
+		is_foundation: is_foundation_output(output.is_coinbase, output.height, output.value),
+		spent_by_tx: None,
+		spent_height: None,
+		tags: vec![],
+		missing_since: None,
 	});
 
 	let max_child_index = found_parents.get(&parent_key_id).unwrap().clone();
@@ -300,12 +405,14 @@ where
 		)?;
 		if entries.len() > 0 {
 			let mut entry = entries[0].clone();
-			match entry.tx_type {
-				TxLogEntryType::TxSent => entry.tx_type = TxLogEntryType::TxSentCancelled,
-				TxLogEntryType::TxReceived => entry.tx_type = TxLogEntryType::TxReceivedCancelled,
-				_ => {}
+			// an entry that's already confirmed or rolled back is left as it is
+			match entry.cancel(false) {
+				Ok(()) => Some(entry),
+				Err(e) => {
+					debug!("Not cancelling transaction {}: {}", entry.id, e);
+					None
+				}
 			}
-			Some(entry)
 		} else {
 			None
 		}
@@ -320,6 +427,205 @@ where
 	Ok(())
 }
 
+/// Restore the account paths of restored outputs the wallet doesn't have,
+/// and move each account's next child index past its restored outputs
+fn restore_account_paths<'a, T: ?Sized, C, K>(
+	w: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	found_parents: &HashMap<Identifier, u32>,
+	status_send_channel: &Option<Sender<StatusMessage>>,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let label_base = "account";
+	let accounts: Vec<Identifier> = w.acct_path_iter().map(|m| m.path).collect();
+	let mut acct_index = accounts.len();
+	for (path, max_child_index) in found_parents.iter() {
+		// Only restore paths that don't exist
+		if !accounts.contains(path) {
+			let label = format!("{}_{}", label_base, acct_index);
+			let msg = format!("Setting account {} at path {}", label, path);
+			if let Some(ref s) = status_send_channel {
+				let _ = s.send(StatusMessage::Scanning(msg, 99));
+			}
+			keys::set_acct_path(&mut *w, keychain_mask, &label, path)?;
+			acct_index += 1;
+		}
+		let current_child_index = w.current_child_index(&path)?;
+		if *max_child_index >= current_child_index {
+			let mut batch = w.batch(keychain_mask)?;
+			debug!("Next child for account {} is {}", path, max_child_index + 1);
+			batch.save_child_index(path, max_child_index + 1)?;
+			batch.commit()?;
+		}
+	}
+	Ok(())
+}
+
+/// Add a single output the wallet is missing, given its key and value, once
+/// it's been found unspent on chain at the height given. For recovering
+/// outputs known to the user without scanning the whole chain
+pub fn import_output<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	key_id: &Identifier,
+	value: u64,
+	height: u64,
+) -> Result<OutputData, Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let refused = |msg: String| -> Error { ErrorKind::ImportOutput(msg).into() };
+	let (client, commit) = {
+		wallet_lock!(wallet_inst, w);
+		let commit =
+			w.keychain(keychain_mask)?
+				.commit(value, key_id, &SwitchCommitmentType::Regular)?;
+		let commit_hex = epic_util::to_hex(commit.0.to_vec());
+		if w.iter().any(|o| o.commit.as_ref() == Some(&commit_hex)) {
+			return Err(refused(format!(
+				"output {} is already in the wallet",
+				commit_hex
+			)));
+		}
+		(w.w2n_client().clone(), commit)
+	};
+	let commit_hex = epic_util::to_hex(commit.0.to_vec());
+
+	// the node only knows unspent outputs by commitment
+	let (_, chain_height, mmr_index) = client
+		.get_outputs_from_node(vec![commit])?
+		.remove(&commit)
+		.ok_or_else(|| {
+			refused(format!(
+				"no unspent output {} of that key and value on chain",
+				commit_hex
+			))
+		})?;
+	if chain_height != height {
+		return Err(refused(format!(
+			"output {} is at height {}, not {}",
+			commit_hex, chain_height, height
+		)));
+	}
+	let (_, _, chain_outputs) = client.get_outputs_by_pmmr_index(mmr_index, Some(mmr_index), 1)?;
+	let is_coinbase = chain_outputs
+		.iter()
+		.find(|o| o.0 == commit)
+		.map(|o| o.2)
+		.ok_or_else(|| {
+			refused(format!(
+				"output {} missing from the node's UTXO set",
+				commit_hex
+			))
+		})?;
+	let lock_height = if is_coinbase {
+		height + global::coinbase_maturity()
+	} else {
+		height
+	};
+
+	info!(
+		"Importing output {} of value {} at height {} with ID {}",
+		commit_hex, value, height, key_id
+	);
+	let mut found_parents = HashMap::new();
+	restore_missing_output(
+		wallet_inst.clone(),
+		keychain_mask,
+		OutputResult {
+			commit,
+			key_id: key_id.clone(),
+			n_child: key_id.to_path().last_path_index(),
+			mmr_index,
+			value,
+			height,
+			lock_height,
+			is_coinbase,
+		},
+		&mut found_parents,
+		&mut None,
+	)?;
+
+	wallet_lock!(wallet_inst, w);
+	restore_account_paths(&mut **w, keychain_mask, &found_parents, &None)?;
+	w.iter()
+		.find(|o| o.commit.as_ref() == Some(&commit_hex))
+		.ok_or_else(|| refused(format!("output {} wasn't saved", commit_hex)))
+}
+
+/// Add the outputs of a wallet state export found unspent on chain at the
+/// heights exported, returning those added and those skipped
+pub fn import_exported_outputs<'a, L, C, K>(
+	wallet_inst: Arc<Mutex<Box<dyn WalletInst<'a, L, C, K>>>>,
+	keychain_mask: Option<&SecretKey>,
+	outputs: &[ExportedOutput],
+) -> Result<(Vec<OutputData>, Vec<ExportedOutput>), Error>
+where
+	L: WalletLCProvider<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (client, commits) = {
+		wallet_lock!(wallet_inst, w);
+		let k = w.keychain(keychain_mask)?;
+		let commits = outputs
+			.iter()
+			.map(|o| k.commit(o.value, &o.key_id, &SwitchCommitmentType::Regular))
+			.collect::<Result<Vec<_>, _>>()?;
+		(w.w2n_client().clone(), commits)
+	};
+	let on_chain = match commits.is_empty() {
+		true => HashMap::new(),
+		false => client.get_outputs_from_node(commits.clone())?,
+	};
+
+	let mut found_parents = HashMap::new();
+	let mut imported = vec![];
+	let mut skipped = vec![];
+	for (o, commit) in outputs.iter().zip(commits.into_iter()) {
+		let mmr_index = match on_chain.get(&commit) {
+			Some((_, height, mmr_index)) if *height == o.height => *mmr_index,
+			_ => {
+				skipped.push(o.clone());
+				continue;
+			}
+		};
+		let lock_height = if o.is_coinbase {
+			o.height + global::coinbase_maturity()
+		} else {
+			o.height
+		};
+		restore_missing_output(
+			wallet_inst.clone(),
+			keychain_mask,
+			OutputResult {
+				commit,
+				key_id: o.key_id.clone(),
+				n_child: o.key_id.to_path().last_path_index(),
+				mmr_index,
+				value: o.value,
+				height: o.height,
+				lock_height,
+				is_coinbase: o.is_coinbase,
+			},
+			&mut found_parents,
+			&mut None,
+		)?;
+		imported.push(Some(epic_util::to_hex(commit.0.to_vec())));
+	}
+
+	wallet_lock!(wallet_inst, w);
+	restore_account_paths(&mut **w, keychain_mask, &found_parents, &None)?;
+	let imported = w.iter().filter(|o| imported.contains(&o.commit)).collect();
+	Ok((imported, skipped))
+}
+
 /// Check / repair wallet contents by scanning against chain
 /// assume wallet contents have been freshly updated with contents
 /// of latest block
@@ -367,7 +673,7 @@ where
 	// Now, get all outputs owned by this wallet (regardless of account)
 	let wallet_outputs = {
 		wallet_lock!(wallet_inst, w);
-		updater::retrieve_outputs(&mut **w, keychain_mask, true, false, None, None)?
+		updater::retrieve_outputs(&mut **w, true, false, None, None)?
 	};
 
 	let mut missing_outs = vec![];
@@ -402,6 +708,8 @@ where
 			let _ = s.send(StatusMessage::Scanning(msg, 99));
 		}
 		o.status = OutputStatus::Unspent;
+		o.spent_by_tx = None;
+		o.spent_height = None;
 		// any transactions associated with this should be cancelled
 		cancel_tx_log_entry(wallet_inst.clone(), keychain_mask, &o)?;
 		wallet_lock!(wallet_inst, w);
@@ -444,6 +752,7 @@ where
 				let _ = s.send(StatusMessage::Scanning(msg, 99));
 			}
 			o.status = OutputStatus::Unspent;
+			o.spent_by_tx = None;
 			cancel_tx_log_entry(wallet_inst.clone(), keychain_mask, &o)?;
 			wallet_lock!(wallet_inst, w);
 			let mut batch = w.batch(keychain_mask)?;
@@ -476,28 +785,7 @@ where
 
 	// restore labels, account paths and child derivation indices
 	wallet_lock!(wallet_inst, w);
-	let label_base = "account";
-	let accounts: Vec<Identifier> = w.acct_path_iter().map(|m| m.path).collect();
-	let mut acct_index = accounts.len();
-	for (path, max_child_index) in found_parents.iter() {
-		// Only restore paths that don't exist
-		if !accounts.contains(path) {
-			let label = format!("{}_{}", label_base, acct_index);
-			let msg = format!("Setting account {} at path {}", label, path);
-			if let Some(ref s) = status_send_channel {
-				let _ = s.send(StatusMessage::Scanning(msg, 99));
-			}
-			keys::set_acct_path(&mut **w, keychain_mask, &label, path)?;
-			acct_index += 1;
-		}
-		let current_child_index = w.current_child_index(&path)?;
-		if *max_child_index >= current_child_index {
-			let mut batch = w.batch(keychain_mask)?;
-			debug!("Next child for account {} is {}", path, max_child_index + 1);
-			batch.save_child_index(path, max_child_index + 1)?;
-			batch.commit()?;
-		}
-	}
+	restore_account_paths(&mut **w, keychain_mask, &found_parents, status_send_channel)?;
 
 	if let Some(ref s) = status_send_channel {
 		let _ = s.send(StatusMessage::ScanningComplete(
@@ -512,3 +800,84 @@ where
 		last_pmmr_index: last_index,
 	})
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_keychain::ExtKeychain;
+	use std::time::Instant;
+
+	/// Build `n` outputs, every other one belonging to `keychain`
+	fn chain_outputs(keychain: &ExtKeychain, n: u64) -> Vec<ChainOutput> {
+		let other = ExtKeychain::from_random_seed(false).unwrap();
+		let switch = SwitchCommitmentType::Regular;
+		(0..n)
+			.map(|i| {
+				let k = if i % 2 == 0 { keychain } else { &other };
+				let builder = proof::ProofBuilder::new(k);
+				let key_id = ExtKeychain::derive_key_id(3, 0, 0, i as u32, 0);
+				let commit = k.commit(i + 1, &key_id, &switch).unwrap();
+				let proof =
+					proof::create(k, &builder, i + 1, &key_id, &switch, commit, None).unwrap();
+				(commit, proof, false, i + 1, i + 1)
+			})
+			.collect()
+	}
+
+	fn pool(threads: usize) -> ThreadPool {
+		ThreadPoolBuilder::new()
+			.num_threads(threads)
+			.build()
+			.unwrap()
+	}
+
+	#[test]
+	fn parallel_rewind_matches_sequential() {
+		let keychain = ExtKeychain::from_random_seed(false).unwrap();
+		let outputs = chain_outputs(&keychain, (REWIND_BATCH_SIZE * 2 + 5) as u64);
+
+		let sequential = rewind_outputs(&keychain, &pool(1), &outputs).unwrap();
+		let parallel = rewind_outputs(&keychain, &pool(4), &outputs).unwrap();
+		assert_eq!(sequential, parallel);
+		assert_eq!(parallel.len(), outputs.len());
+		for (i, info) in parallel.iter().enumerate() {
+			match info {
+				Some((amount, _, _)) => {
+					assert_eq!(i % 2, 0);
+					assert_eq!(*amount, i as u64 + 1);
+				}
+				None => assert_eq!(i % 2, 1),
+			}
+		}
+	}
+
+	#[test]
+	#[ignore]
+	// cargo test --release -p epic_wallet_libwallet rewind_benchmark -- --ignored --nocapture
+	fn rewind_benchmark() {
+		let keychain = ExtKeychain::from_random_seed(false).unwrap();
+		let outputs = chain_outputs(&keychain, 2000);
+
+		let single = pool(1);
+		let start = Instant::now();
+		let sequential = rewind_outputs(&keychain, &single, &outputs).unwrap();
+		let sequential_time = start.elapsed();
+
+		let all = rewind_thread_pool().unwrap();
+		let start = Instant::now();
+		let parallel = rewind_outputs(&keychain, &all, &outputs).unwrap();
+		let parallel_time = start.elapsed();
+
+		println!(
+			"Rewound {} proofs: {:?} on 1 thread, {:?} on {} threads",
+			outputs.len(),
+			sequential_time,
+			parallel_time,
+			all.current_num_threads()
+		);
+		assert_eq!(sequential, parallel);
+		if all.current_num_threads() > 1 {
+			assert!(parallel_time < sequential_time);
+		}
+	}
+}
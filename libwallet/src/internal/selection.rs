@@ -15,6 +15,7 @@
 //! Selection of inputs for building transactions
 
 use crate::address;
+use crate::amount::Amount;
 use crate::epic_core::core::amount_to_hr_string;
 use crate::epic_core::libtx::{
 	build,
@@ -25,8 +26,9 @@ use crate::epic_keychain::{Identifier, Keychain};
 use crate::epic_util::secp::key::SecretKey;
 use crate::error::{Error, ErrorKind};
 use crate::internal::keys;
-use crate::slate::Slate;
+use crate::slate::{FeeBreakdown, Slate};
 use crate::types::*;
+use std::cmp;
 use std::collections::HashMap;
 
 /// Initialize a transaction on the sender side, returns a corresponding
@@ -43,6 +45,7 @@ pub fn build_send_tx<'a, T: ?Sized, C, K>(
 	max_outputs: usize,
 	change_outputs: usize,
 	selection_strategy_is_use_all: bool,
+	selection_tag: Option<&str>,
 	parent_key_id: Identifier,
 	use_test_nonce: bool,
 ) -> Result<Context, Error>
@@ -51,20 +54,29 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let (elems, inputs, change_amounts_derivations, fee) = select_send_tx(
+	let (elems, inputs, change_amounts_derivations, fee, receiver_fee) = select_send_tx(
 		wallet,
 		keychain_mask,
-		slate.amount,
+		slate.amount.freemans(),
 		slate.height,
 		minimum_confirmations,
 		max_outputs,
 		change_outputs,
 		selection_strategy_is_use_all,
+		selection_tag,
 		&parent_key_id,
+		slate.receiver_fee,
 	)?;
 
 	// Update the fee on the slate so we account for this when building the tx.
 	slate.fee = fee;
+	slate.receiver_fee = receiver_fee;
+	// the receiver's output is counted along with any change
+	slate.fee_breakdown = Some(FeeBreakdown::new(
+		inputs.len(),
+		change_amounts_derivations.len() + 1,
+		1,
+	));
 
 	let blinding = slate.add_transaction_elements(keychain, &ProofBuilder::new(keychain), elems)?;
 
@@ -75,9 +87,10 @@ where
 		&parent_key_id,
 		use_test_nonce,
 		0,
-	);
+	)?;
 
 	context.fee = fee;
+	context.selection_tag = selection_tag.map(|t| t.to_owned());
 
 	// Store our private identifiers for each input
 	for input in inputs {
@@ -91,7 +104,7 @@ where
 		context.add_output(&id, &mmr_index, *change_amount);
 		commits.insert(
 			id.clone(),
-			wallet.calc_commit_for_cache(keychain_mask, *change_amount, &id)?,
+			Some(wallet.calc_commit_for_cache(keychain_mask, *change_amount, &id)?),
 		);
 	}
 
@@ -118,7 +131,7 @@ where
 		output_commits.insert(
 			id.clone(),
 			(
-				wallet.calc_commit_for_cache(keychain_mask, *change_amount, &id)?,
+				Some(wallet.calc_commit_for_cache(keychain_mask, *change_amount, &id)?),
 				*change_amount,
 			),
 		);
@@ -138,10 +151,14 @@ where
 		let mut batch = wallet.batch(keychain_mask)?;
 		let log_id = batch.next_tx_log_id(&parent_key_id)?;
 		let mut t = TxLogEntry::new(parent_key_id.clone(), TxLogEntryType::TxSent, log_id);
+		t.transition(TxState::Sent)?;
 		t.tx_slate_id = Some(slate_id.clone());
 		let filename = format!("{}.epictx", slate_id);
 		t.stored_tx = Some(filename);
 		t.fee = Some(slate.fee);
+		t.fee_breakdown = slate.fee_breakdown.clone();
+		t.destination = context.destination.clone();
+		t.slate_version = Some(slate.version_info.orig_version);
 		t.ttl_cutoff_height = slate.ttl_cutoff_height;
 
 		match slate.calc_excess(&keychain) {
@@ -155,11 +172,12 @@ where
 		for id in lock_inputs {
 			let mut coin = batch.get(&id.0, &id.1).unwrap();
 			coin.tx_log_entry = Some(log_id);
+			coin.spent_by_tx = Some(log_id);
 			amount_debited = amount_debited + coin.value;
 			batch.lock_output(&mut coin)?;
 		}
 
-		t.amount_debited = amount_debited;
+		t.amount_debited = Amount::from_freemans(amount_debited);
 		t.messages = messages;
 
 		// store extra payment proof info, if required
@@ -191,7 +209,7 @@ where
 		for (id, _, _) in &context.get_outputs() {
 			t.num_outputs += 1;
 			let (commit, change_amount) = output_commits.get(&id).unwrap().clone();
-			t.amount_credited += change_amount;
+			t.amount_credited += Amount::from_freemans(change_amount);
 			batch.save(OutputData {
 				root_key_id: parent_key_id.clone(),
 				key_id: id.clone(),
@@ -204,6 +222,11 @@ where
 				lock_height: 0,
 				is_coinbase: false,
 				tx_log_entry: Some(log_id),
+				is_foundation: false,
+				spent_by_tx: None,
+				spent_height: None,
+				tags: context.selection_tag.iter().cloned().collect(),
+				missing_since: None,
 			})?;
 		}
 		batch.save_tx_log_entry(t.clone(), &parent_key_id)?;
@@ -217,11 +240,17 @@ where
 /// Creates a new output in the wallet for the recipient,
 /// returning the key of the fresh output
 /// Also creates a new transaction containing the output
+///
+/// If the sender asks the recipient to contribute, and the part of the fee
+/// asked is no more than `fee_contribution` offers, one of the recipient's
+/// outputs is added as an input, with its value less that part of the fee
+/// going to the new output.
 pub fn build_recipient_output<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
 	keychain_mask: Option<&SecretKey>,
 	slate: &mut Slate,
 	parent_key_id: Identifier,
+	fee_contribution: Option<&FeeContribution>,
 	use_test_rng: bool,
 ) -> Result<(Identifier, Context), Error>
 where
@@ -229,19 +258,30 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
+	let contribution = receiver_input(wallet, slate, &parent_key_id, fee_contribution)?;
+
 	// Create a potential output for this transaction
 	let key_id = keys::next_available_key(wallet, keychain_mask).unwrap();
 	let keychain = wallet.keychain(keychain_mask)?;
 	let key_id_inner = key_id.clone();
-	let amount = slate.amount;
+	let mut elems = vec![];
+	let amount = match contribution {
+		Some((ref input, receiver_fee)) => {
+			if input.is_coinbase {
+				elems.push(build::coinbase_input(input.value, input.key_id.clone()));
+			} else {
+				elems.push(build::input(input.value, input.key_id.clone()));
+			}
+			slate.amount.freemans() + input.value - receiver_fee
+		}
+		None => slate.amount.freemans(),
+	};
+	elems.push(build::output(amount, key_id.clone()));
 	let height = slate.height;
 
 	let slate_id = slate.id.clone();
-	let blinding = slate.add_transaction_elements(
-		&keychain,
-		&ProofBuilder::new(&keychain),
-		vec![build::output(amount, key_id.clone())],
-	)?;
+	let blinding =
+		slate.add_transaction_elements(&keychain, &ProofBuilder::new(&keychain), elems)?;
 
 	// Add blinding sum to our context
 	let mut context = Context::new(
@@ -252,19 +292,32 @@ where
 		&parent_key_id,
 		use_test_rng,
 		1,
-	);
+	)?;
 
 	context.add_output(&key_id, &None, amount);
 	let messages = Some(slate.participant_messages());
-	let commit = wallet.calc_commit_for_cache(keychain_mask, amount, &key_id_inner)?;
+	let commit = Some(wallet.calc_commit_for_cache(keychain_mask, amount, &key_id_inner)?);
 	let mut batch = wallet.batch(keychain_mask)?;
 	let log_id = batch.next_tx_log_id(&parent_key_id)?;
 	let mut t = TxLogEntry::new(parent_key_id.clone(), TxLogEntryType::TxReceived, log_id);
+	t.transition(TxState::Sent)?;
 	t.tx_slate_id = Some(slate_id);
-	t.amount_credited = amount;
+	t.amount_credited = Amount::from_freemans(amount);
 	t.num_outputs = 1;
+	if let Some((input, receiver_fee)) = contribution {
+		context.add_input(&input.key_id, &input.mmr_index, input.value);
+		let mut coin = input;
+		coin.tx_log_entry = Some(log_id);
+		coin.spent_by_tx = Some(log_id);
+		batch.lock_output(&mut coin)?;
+		t.amount_debited = Amount::from_freemans(coin.value);
+		t.num_inputs = 1;
+		t.fee = Some(receiver_fee);
+	}
 	t.messages = messages;
+	t.sender_proof_address = slate.payment_proof.as_ref().map(|p| p.sender_address);
 	t.ttl_cutoff_height = slate.ttl_cutoff_height;
+	t.slate_version = Some(slate.version_info.orig_version);
 	// when invoicing, this will be invalid
 	match slate.calc_excess(&keychain) {
 		Ok(e) => t.kernel_excess = Some(e),
@@ -283,6 +336,11 @@ where
 		lock_height: 0,
 		is_coinbase: false,
 		tx_log_entry: Some(log_id),
+		is_foundation: false,
+		spent_by_tx: None,
+		spent_height: None,
+		tags: vec![],
+		missing_since: None,
 	})?;
 	batch.save_tx_log_entry(t, &parent_key_id)?;
 	batch.commit()?;
@@ -290,9 +348,93 @@ where
 	Ok((key_id, context))
 }
 
+/// The output the recipient contributes as an input to a transaction it
+/// receives, with the part of the fee it pays, if the sender asked for one.
+/// The smallest output covering the fee is picked, out of those confirmed as
+/// the contribution asks at the wallet's last confirmed height, and not
+/// already claimed by another transaction. When the sender only asks for an
+/// input, the recipient carries on without one if it has none to give, if
+/// its account is frozen, or if too many of its contributions are pending.
+fn receiver_input<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	slate: &Slate,
+	parent_key_id: &Identifier,
+	fee_contribution: Option<&FeeContribution>,
+) -> Result<Option<(OutputData, u64)>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let receiver_fee = match slate.receiver_fee {
+		Some(f) => f,
+		None => return Ok(None),
+	};
+	let contribution = match fee_contribution {
+		Some(c) if receiver_fee <= c.max_fee => c,
+		_ if receiver_fee == 0 => return Ok(None),
+		Some(c) => {
+			return Err(ErrorKind::ReceiverContribution(format!(
+				"asked to pay {} of the fee, more than the {} offered",
+				amount_to_hr_string(receiver_fee, false),
+				amount_to_hr_string(c.max_fee, false)
+			)))?;
+		}
+		None => {
+			return Err(ErrorKind::ReceiverContribution(
+				"asked to pay part of the fee, but no contribution is offered".to_owned(),
+			))?;
+		}
+	};
+	// a frozen account still receives, but gives none of its outputs to do so
+	if let Err(e) = check_not_frozen(wallet, parent_key_id) {
+		return match receiver_fee {
+			0 => Ok(None),
+			_ => Err(e),
+		};
+	}
+	// anyone can ask for a contribution, so senders that never finalize
+	// mustn't be able to lock up every output of the wallet
+	let pending = wallet
+		.tx_log_iter()
+		.filter(|t| t.tx_type == TxLogEntryType::TxReceived && !t.confirmed && t.num_inputs > 0)
+		.count();
+	if pending >= contribution.max_pending {
+		return match receiver_fee {
+			0 => Ok(None),
+			_ => Err(ErrorKind::ReceiverContribution(format!(
+				"{} contributed inputs are already waiting on their transactions",
+				pending
+			)))?,
+		};
+	}
+	let current_height = wallet.last_confirmed_height()?;
+	let input = wallet
+		.iter()
+		.filter(|out| {
+			out.root_key_id == *parent_key_id
+				&& out.value >= receiver_fee
+				&& out.spent_by_tx.is_none()
+				&& out.eligible_to_spend(current_height, contribution.min_confirmations)
+		})
+		.min_by_key(|out| out.value);
+	match input {
+		Some(i) => Ok(Some((i, receiver_fee))),
+		None if receiver_fee == 0 => Ok(None),
+		None => Err(ErrorKind::ReceiverContribution(format!(
+			"no spendable output covers {} of the fee",
+			amount_to_hr_string(receiver_fee, false)
+		)))?,
+	}
+}
+
 /// Builds a transaction to send to someone from the HD seed associated with the
 /// wallet and the amount to send. Handles reading through the wallet data file,
 /// selecting outputs to spend and building the change.
+///
+/// Any part of the fee asked of the receiver is left in the change, up to the
+/// whole fee. Without change there's nowhere to leave it, so the receiver is
+/// only asked for an input.
 pub fn select_send_tx<'a, T: ?Sized, C, K, B>(
 	wallet: &mut T,
 	keychain_mask: Option<&SecretKey>,
@@ -302,13 +444,16 @@ pub fn select_send_tx<'a, T: ?Sized, C, K, B>(
 	max_outputs: usize,
 	change_outputs: usize,
 	selection_strategy_is_use_all: bool,
+	selection_tag: Option<&str>,
 	parent_key_id: &Identifier,
+	receiver_fee: Option<u64>,
 ) -> Result<
 	(
 		Vec<Box<build::Append<K, B>>>,
 		Vec<OutputData>,
 		Vec<(u64, Identifier, Option<u64>)>, // change amounts and derivations
 		u64,                                 // fee
+		Option<u64>,                         // part of the fee paid by the receiver
 	),
 	Error,
 >
@@ -326,14 +471,27 @@ where
 		max_outputs,
 		change_outputs,
 		selection_strategy_is_use_all,
+		selection_tag,
 		&parent_key_id,
 	)?;
 
+	let total: u64 = coins.iter().map(|c| c.value).sum();
+	let receiver_fee = receiver_fee.map(|f| match total == amount + fee {
+		true => 0,
+		false => cmp::min(f, fee),
+	});
+
 	// build transaction skeleton with inputs and change
-	let (parts, change_amounts_derivations) =
-		inputs_and_change(&coins, wallet, keychain_mask, amount, fee, change_outputs)?;
+	let (parts, change_amounts_derivations) = inputs_and_change(
+		&coins,
+		wallet,
+		keychain_mask,
+		amount,
+		fee - receiver_fee.unwrap_or(0),
+		change_outputs,
+	)?;
 
-	Ok((parts, coins, change_amounts_derivations, fee))
+	Ok((parts, coins, change_amounts_derivations, fee, receiver_fee))
 }
 
 /// Select outputs and calculating fee.
@@ -345,6 +503,7 @@ pub fn select_coins_and_fee<'a, T: ?Sized, C, K>(
 	max_outputs: usize,
 	change_outputs: usize,
 	selection_strategy_is_use_all: bool,
+	selection_tag: Option<&str>,
 	parent_key_id: &Identifier,
 ) -> Result<
 	(
@@ -360,6 +519,8 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
+	check_not_frozen(wallet, parent_key_id)?;
+
 	// select some spendable coins from the wallet
 	let (max_outputs, mut coins) = select_coins(
 		wallet,
@@ -368,6 +529,7 @@ where
 		minimum_confirmations,
 		max_outputs,
 		selection_strategy_is_use_all,
+		selection_tag,
 		parent_key_id,
 	);
 
@@ -384,22 +546,28 @@ where
 	let mut amount_with_fee = amount + fee;
 
 	if total == 0 {
-		return Err(ErrorKind::NotEnoughFunds {
-			available: 0,
-			available_disp: amount_to_hr_string(0, false),
-			needed: amount_with_fee as u64,
-			needed_disp: amount_to_hr_string(amount_with_fee as u64, false),
-		})?;
+		return Err(not_enough_funds(
+			wallet,
+			0,
+			amount_with_fee,
+			current_height,
+			minimum_confirmations,
+			selection_tag,
+			parent_key_id,
+		))?;
 	}
 
 	// The amount with fee is more than the total values of our max outputs
 	if total < amount_with_fee && coins.len() == max_outputs {
-		return Err(ErrorKind::NotEnoughFunds {
-			available: total,
-			available_disp: amount_to_hr_string(total, false),
-			needed: amount_with_fee as u64,
-			needed_disp: amount_to_hr_string(amount_with_fee as u64, false),
-		})?;
+		return Err(not_enough_funds(
+			wallet,
+			total,
+			amount_with_fee,
+			current_height,
+			minimum_confirmations,
+			selection_tag,
+			parent_key_id,
+		))?;
 	}
 
 	let num_outputs = change_outputs + 1;
@@ -414,12 +582,15 @@ where
 		while total < amount_with_fee {
 			// End the loop if we have selected all the outputs and still not enough funds
 			if coins.len() == max_outputs {
-				return Err(ErrorKind::NotEnoughFunds {
-					available: total as u64,
-					available_disp: amount_to_hr_string(total, false),
-					needed: amount_with_fee as u64,
-					needed_disp: amount_to_hr_string(amount_with_fee as u64, false),
-				})?;
+				return Err(not_enough_funds(
+					wallet,
+					total,
+					amount_with_fee,
+					current_height,
+					minimum_confirmations,
+					selection_tag,
+					parent_key_id,
+				))?;
 			}
 
 			// select some spendable coins from the wallet
@@ -430,6 +601,7 @@ where
 				minimum_confirmations,
 				max_outputs,
 				selection_strategy_is_use_all,
+				selection_tag,
 				parent_key_id,
 			)
 			.1;
@@ -441,6 +613,145 @@ where
 	Ok((coins, total, amount, fee))
 }
 
+/// What a transaction spending every eligible output of an account sends,
+/// returned with the number of inputs and the fee. Such a transaction has no
+/// change output, so the fee only depends on the number of inputs
+pub fn sweep_amount<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	current_height: u64,
+	minimum_confirmations: u64,
+	selection_tag: Option<&str>,
+	parent_key_id: &Identifier,
+) -> Result<(usize, u64, u64), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	check_not_frozen(wallet, parent_key_id)?;
+
+	let eligible = wallet
+		.iter()
+		.filter(|out| {
+			out.root_key_id == *parent_key_id
+				&& selectable(out, selection_tag)
+				&& out.eligible_to_spend(current_height, minimum_confirmations)
+		})
+		.collect::<Vec<OutputData>>();
+	let total: u64 = eligible.iter().map(|c| c.value).sum();
+	let fee = tx_fee(eligible.len(), 1, 1, None);
+	if total <= fee {
+		// at least one freeman has to be sent
+		return Err(not_enough_funds(
+			wallet,
+			total,
+			fee + 1,
+			current_height,
+			minimum_confirmations,
+			selection_tag,
+			parent_key_id,
+		))?;
+	}
+	Ok((eligible.len(), total - fee, fee))
+}
+
+/// Refuse to select coins from a frozen account
+fn check_not_frozen<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	match wallet
+		.acct_path_iter()
+		.find(|m| m.path == *parent_key_id && m.frozen)
+	{
+		Some(m) => Err(ErrorKind::AccountFrozen(m.label).into()),
+		None => Ok(()),
+	}
+}
+
+/// The error for a transaction needing more than the selected coins hold,
+/// with a breakdown of the account's funds and the earliest height at which
+/// enough of them are spendable. That height only takes outputs that are
+/// already confirmed into account, and not the higher fee for more inputs
+fn not_enough_funds<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	available: u64,
+	needed: u64,
+	current_height: u64,
+	minimum_confirmations: u64,
+	selection_tag: Option<&str>,
+	parent_key_id: &Identifier,
+) -> ErrorKind
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut spendable = 0;
+	let mut immature = 0;
+	let mut locked = 0;
+	let mut awaiting_confirmation = 0;
+	// heights at which confirmed outputs become spendable
+	let mut maturing = vec![];
+	for out in wallet
+		.iter()
+		.filter(|out| out.root_key_id == *parent_key_id && selectable(out, selection_tag))
+	{
+		if out.eligible_to_spend(current_height, minimum_confirmations) {
+			spendable += out.value;
+			continue;
+		}
+		match out.status {
+			OutputStatus::Unspent => {
+				if out.is_coinbase && out.lock_height > current_height {
+					immature += out.value;
+				} else {
+					awaiting_confirmation += out.value;
+				}
+				let confirmed_at = out.height + minimum_confirmations.saturating_sub(1);
+				maturing.push((out.lock_height.max(confirmed_at), out.value));
+			}
+			OutputStatus::Unconfirmed if !out.is_coinbase => awaiting_confirmation += out.value,
+			OutputStatus::Locked => locked += out.value,
+			_ => {}
+		}
+	}
+
+	maturing.sort();
+	let mut total = spendable;
+	let mut enough_at_height = match total >= needed {
+		true => Some(current_height),
+		false => None,
+	};
+	for (height, value) in maturing {
+		if enough_at_height.is_some() {
+			break;
+		}
+		total += value;
+		if total >= needed {
+			enough_at_height = Some(height);
+		}
+	}
+
+	ErrorKind::NotEnoughFunds {
+		available,
+		available_disp: amount_to_hr_string(available, false),
+		needed,
+		needed_disp: amount_to_hr_string(needed, false),
+		spendable,
+		immature,
+		locked,
+		awaiting_confirmation,
+		shortfall: needed.saturating_sub(available),
+		enough_at_height,
+	}
+}
+
 /// Selects inputs and change for a transaction
 pub fn inputs_and_change<'a, T: ?Sized, C, K, B>(
 	coins: &Vec<OutputData>,
@@ -491,17 +802,7 @@ where
 			change, num_change_outputs
 		);
 
-		let part_change = change / num_change_outputs as u64;
-		let remainder_change = change % part_change;
-
-		for x in 0..num_change_outputs {
-			// n-1 equal change_outputs and a final one accounting for any remainder
-			let change_amount = if x == (num_change_outputs - 1) {
-				part_change + remainder_change
-			} else {
-				part_change
-			};
-
+		for change_amount in split_change(change, num_change_outputs) {
 			let change_key = wallet.next_child(keychain_mask).unwrap();
 
 			change_amounts_derivations.push((change_amount, change_key.clone(), None));
@@ -512,6 +813,26 @@ where
 	Ok((parts, change_amounts_derivations))
 }
 
+/// Splits the total change into the amounts of the individual change outputs:
+/// n-1 equal change outputs and a final one accounting for any remainder
+pub fn split_change(change: u64, num_change_outputs: usize) -> Vec<u64> {
+	if change == 0 {
+		return vec![];
+	}
+	let part_change = change / num_change_outputs as u64;
+	let remainder_change = change % part_change;
+
+	(0..num_change_outputs)
+		.map(|x| {
+			if x == (num_change_outputs - 1) {
+				part_change + remainder_change
+			} else {
+				part_change
+			}
+		})
+		.collect()
+}
+
 /// Select spendable coins from a wallet.
 /// Default strategy is to spend the maximum number of outputs (up to
 /// max_outputs). Alternative strategy is to spend smallest outputs first
@@ -526,6 +847,7 @@ pub fn select_coins<'a, T: ?Sized, C, K>(
 	minimum_confirmations: u64,
 	max_outputs: usize,
 	select_all: bool,
+	selection_tag: Option<&str>,
 	parent_key_id: &Identifier,
 ) -> (usize, Vec<OutputData>)
 //    max_outputs_available, Outputs
@@ -539,6 +861,7 @@ where
 		.iter()
 		.filter(|out| {
 			out.root_key_id == *parent_key_id
+				&& selectable(out, selection_tag)
 				&& out.eligible_to_spend(current_height, minimum_confirmations)
 		})
 		.collect::<Vec<OutputData>>();
@@ -589,6 +912,14 @@ where
 	)
 }
 
+/// Whether an output may be selected when inputs are restricted to a tag
+fn selectable(out: &OutputData, selection_tag: Option<&str>) -> bool {
+	match selection_tag {
+		Some(t) => out.has_tag(t),
+		None => true,
+	}
+}
+
 fn select_from(amount: u64, select_all: bool, outputs: Vec<OutputData>) -> Option<Vec<OutputData>> {
 	let total = outputs.iter().fold(0, |acc, x| acc + x.value);
 	if total >= amount {
@@ -15,19 +15,27 @@
 //! Transaction building functions
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use chrono::{DateTime, Duration, Utc};
 use std::io::Cursor;
 use uuid::Uuid;
 
+use crate::amount::Amount;
 use crate::epic_core::consensus::valid_header_version;
-use crate::epic_core::core::HeaderVersion;
+use crate::epic_core::core::{amount_to_hr_string, HeaderVersion};
 use crate::epic_keychain::{Identifier, Keychain};
 use crate::epic_util::secp::key::SecretKey;
 use crate::epic_util::secp::pedersen;
 use crate::epic_util::Mutex;
 use crate::internal::{selection, updater};
-use crate::slate::Slate;
-use crate::types::{Context, NodeClient, StoredProofInfo, TxLogEntryType, WalletBackend};
+use crate::slate::{FeeBreakdown, Slate};
+use crate::types::{
+	Context, FeeContribution, NodeClient, OutputStatus, Reservation, StoredProofInfo, TxLogEntry,
+	TxLogEntryType, TxPostState, TxState, WalletBackend, DUPLICATE_PAYMENT_WINDOW_SECS,
+};
 use crate::{address, Error, ErrorKind};
+use crate::{
+	CancelPreview, InitTxArgs, OutputCommitMapping, TxTemplate, TxTemplateInput, TxTemplateOutput,
+};
 use ed25519_dalek::Keypair as DalekKeypair;
 use ed25519_dalek::PublicKey as DalekPublicKey;
 use ed25519_dalek::SecretKey as DalekSecretKey;
@@ -42,7 +50,7 @@ lazy_static! {
 /// the transaction (sender(s), receiver(s))
 pub fn new_tx_slate<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
-	amount: u64,
+	amount: Amount,
 	num_participants: usize,
 	use_test_rng: bool,
 	ttl_blocks: Option<u64>,
@@ -52,7 +60,7 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let current_height = wallet.w2n_client().get_chain_tip()?.0;
+	let (current_height, _) = updater::current_height(wallet)?;
 	let mut slate = Slate::blank(num_participants);
 	if let Some(b) = ttl_blocks {
 		slate.ttl_cutoff_height = Some(current_height + b);
@@ -83,7 +91,8 @@ where
 	Ok(slate)
 }
 
-/// Estimates locked amount and fee for the transaction without creating one
+/// Estimates locked amount and fee for the transaction without creating one,
+/// along with how the fee was worked out
 pub fn estimate_send_tx<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
 	keychain_mask: Option<&SecretKey>,
@@ -92,11 +101,13 @@ pub fn estimate_send_tx<'a, T: ?Sized, C, K>(
 	max_outputs: usize,
 	num_change_outputs: usize,
 	selection_strategy_is_use_all: bool,
+	selection_tag: Option<&str>,
 	parent_key_id: &Identifier,
 ) -> Result<
 	(
 		u64, // total
 		u64, // fee
+		FeeBreakdown,
 	),
 	Error,
 >
@@ -106,9 +117,11 @@ where
 	K: Keychain + 'a,
 {
 	// Get lock height
-	let current_height = wallet.w2n_client().get_chain_tip()?.0;
+	let (current_height, online) = updater::current_height(wallet)?;
 	// ensure outputs we're selecting are up to date
-	updater::refresh_outputs(wallet, keychain_mask, parent_key_id, false)?;
+	if online {
+		updater::refresh_outputs(wallet, keychain_mask, parent_key_id, false)?;
+	}
 
 	// Sender selects outputs into a new slate and save our corresponding keys in
 	// a transaction context. The secret key in our transaction context will be
@@ -117,7 +130,7 @@ where
 	// according to plan
 	// This function is just a big helper to do all of that, in theory
 	// this process can be split up in any way
-	let (_coins, total, _amount, fee) = selection::select_coins_and_fee(
+	let (coins, total, _amount, fee) = selection::select_coins_and_fee(
 		wallet,
 		amount,
 		current_height,
@@ -125,9 +138,272 @@ where
 		max_outputs,
 		num_change_outputs,
 		selection_strategy_is_use_all,
+		selection_tag,
+		parent_key_id,
+	)?;
+	// no change output is needed for an exact match
+	let num_outputs = match total == amount + fee {
+		true => 1,
+		false => num_change_outputs + 1,
+	};
+	Ok((total, fee, FeeBreakdown::new(coins.len(), num_outputs, 1)))
+}
+
+/// Selects inputs and calculates the fee and outputs for a transaction without
+/// creating it or locking anything, returning a template of the transaction
+pub fn create_tx_template<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	args: &InitTxArgs,
+	parent_key_id: &Identifier,
+) -> Result<TxTemplate, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (current_height, online) = updater::current_height(wallet)?;
+	// ensure outputs we're selecting are up to date
+	if online {
+		updater::refresh_outputs(wallet, keychain_mask, parent_key_id, false)?;
+	}
+
+	let (coins, total, amount, fee) = selection::select_coins_and_fee(
+		wallet,
+		args.amount.freemans(),
+		current_height,
+		args.minimum_confirmations,
+		args.max_outputs as usize,
+		args.num_change_outputs as usize,
+		args.selection_strategy_is_use_all,
+		args.selection_tag.as_ref().map(|t| t.as_str()),
+		parent_key_id,
+	)?;
+
+	let mut inputs = vec![];
+	for coin in coins.iter() {
+		let commit = coin
+			.commit
+			.clone()
+			.ok_or_else(|| ErrorKind::MissingOutputCommit(coin.key_id.to_string()))?;
+		inputs.push(TxTemplateInput {
+			commit,
+			value: coin.value,
+		});
+	}
+
+	let mut outputs = vec![TxTemplateOutput {
+		value: amount,
+		is_change: false,
+	}];
+	for change_amount in
+		selection::split_change(total - amount - fee, args.num_change_outputs as usize)
+	{
+		outputs.push(TxTemplateOutput {
+			value: change_amount,
+			is_change: true,
+		});
+	}
+
+	Ok(TxTemplate {
+		src_acct_name: args.src_acct_name.clone(),
+		amount,
+		fee,
+		height: current_height,
+		minimum_confirmations: args.minimum_confirmations,
+		max_outputs: args.max_outputs,
+		num_change_outputs: args.num_change_outputs,
+		selection_strategy_is_use_all: args.selection_strategy_is_use_all,
+		selection_tag: args.selection_tag.clone(),
+		message: args.message.clone(),
+		ttl_blocks: args.ttl_blocks,
+		payment_proof_recipient_address: args.payment_proof_recipient_address,
+		inputs,
+		outputs,
+	})
+}
+
+/// Sets the amount of a transaction sending everything the account can spend,
+/// with the fee deducted, and makes the transaction select all of those
+/// outputs as inputs so no change is left
+pub fn prepare_send_max<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	args: &mut InitTxArgs,
+	parent_key_id: &Identifier,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (current_height, online) = updater::current_height(wallet)?;
+	// ensure outputs we're selecting are up to date
+	if online {
+		updater::refresh_outputs(wallet, keychain_mask, parent_key_id, false)?;
+	}
+
+	let (num_inputs, amount, fee) = selection::sweep_amount(
+		wallet,
+		current_height,
+		args.minimum_confirmations,
+		args.selection_tag.as_ref().map(|t| t.as_str()),
 		parent_key_id,
 	)?;
-	Ok((total, fee))
+	debug!(
+		"Sending max: {} from {} inputs with fee {}",
+		amount, num_inputs, fee
+	);
+	args.amount = Amount::from_freemans(amount);
+	args.max_outputs = num_inputs as u32;
+	args.selection_strategy_is_use_all = true;
+	Ok(())
+}
+
+/// Funds of an account that become spendable without any outside input:
+/// unspent outputs other than immature coinbase, and unconfirmed change
+pub fn reservable_balance<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	current_height: u64,
+	parent_key_id: &Identifier,
+) -> u64
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	wallet
+		.iter()
+		.filter(|out| out.root_key_id == *parent_key_id)
+		.filter(|out| match out.status {
+			OutputStatus::Unspent => !out.is_coinbase || out.lock_height <= current_height,
+			OutputStatus::Unconfirmed => !out.is_coinbase,
+			_ => false,
+		})
+		.map(|out| out.value)
+		.sum()
+}
+
+/// Checks that spending `amount_with_fee` leaves the account enough funds for
+/// its reservations. The reservation given by `reservation_id` is the one the
+/// transaction draws on, which doesn't need covering, and is returned
+pub fn check_reservations<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	current_height: u64,
+	amount_with_fee: u64,
+	reservation_id: Option<Uuid>,
+	parent_key_id: &Identifier,
+) -> Result<Option<Reservation>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (drawn, others): (Vec<_>, Vec<_>) = wallet
+		.reservation_iter(parent_key_id)
+		.partition(|r| Some(r.id) == reservation_id);
+	let drawn = drawn.into_iter().next();
+	if let (Some(id), None) = (reservation_id, drawn.as_ref()) {
+		return Err(ErrorKind::Reservation(format!(
+			"no reservation {} in this account",
+			id
+		)))?;
+	}
+
+	let reserved: u64 = others.iter().map(|r| r.amount).sum();
+	if reserved == 0 {
+		return Ok(drawn);
+	}
+	let balance = reservable_balance(wallet, current_height, parent_key_id);
+	if balance < amount_with_fee + reserved {
+		return Err(ErrorKind::Reservation(format!(
+			"spending {} would leave {} for {} reserved in {} reservation(s)",
+			amount_to_hr_string(amount_with_fee, false),
+			amount_to_hr_string(balance.saturating_sub(amount_with_fee), false),
+			amount_to_hr_string(reserved, false),
+			others.len(),
+		)))?;
+	}
+	Ok(drawn)
+}
+
+/// Sent transactions of an account that a send of `amount` to `destination`
+/// would likely repeat: ones to the same destination and of the same amount
+/// that are unconfirmed, or were created less than
+/// `DUPLICATE_PAYMENT_WINDOW_SECS` ago
+pub fn find_duplicate_payments<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	amount: u64,
+	destination: &str,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let cutoff = Utc::now() - Duration::seconds(DUPLICATE_PAYMENT_WINDOW_SECS);
+	let mut txs: Vec<TxLogEntry> = wallet
+		.tx_log_entries_by_type(parent_key_id, &TxLogEntryType::TxSent)?
+		.into_iter()
+		.filter(|tx| {
+			// what a send debits beyond its change pays the amount and fee
+			let sent = tx
+				.amount_debited
+				.saturating_sub(tx.amount_credited)
+				.freemans()
+				.saturating_sub(tx.fee.unwrap_or(0));
+			tx.destination.as_ref().map(|d| d.as_str()) == Some(destination)
+				&& sent == amount
+				&& (!tx.confirmed || tx.creation_ts >= cutoff)
+		})
+		.collect();
+	txs.sort_by_key(|tx| tx.id);
+	Ok(txs)
+}
+
+/// Checks that the wallet would still build the transaction described by
+/// a template, i.e. that it selects the same inputs and creates the same
+/// outputs with the same fee
+pub fn verify_tx_template<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	template: &TxTemplate,
+	parent_key_id: &Identifier,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let args = InitTxArgs {
+		src_acct_name: template.src_acct_name.clone(),
+		amount: Amount::from_freemans(template.amount),
+		minimum_confirmations: template.minimum_confirmations,
+		max_outputs: template.max_outputs,
+		num_change_outputs: template.num_change_outputs,
+		selection_strategy_is_use_all: template.selection_strategy_is_use_all,
+		selection_tag: template.selection_tag.clone(),
+		..Default::default()
+	};
+	let current = create_tx_template(wallet, keychain_mask, &args, parent_key_id)?;
+	if current.fee != template.fee {
+		return Err(ErrorKind::TxTemplate(format!(
+			"fee is now {}, template specifies {}",
+			current.fee, template.fee
+		)))?;
+	}
+	if current.inputs != template.inputs {
+		return Err(ErrorKind::TxTemplate(
+			"selected inputs no longer match the template".to_owned(),
+		))?;
+	}
+	if current.outputs != template.outputs {
+		return Err(ErrorKind::TxTemplate(
+			"outputs no longer match the template".to_owned(),
+		))?;
+	}
+	Ok(())
 }
 
 /// Add inputs to the slate (effectively becoming the sender)
@@ -139,6 +415,7 @@ pub fn add_inputs_to_slate<'a, T: ?Sized, C, K>(
 	max_outputs: usize,
 	num_change_outputs: usize,
 	selection_strategy_is_use_all: bool,
+	selection_tag: Option<&str>,
 	parent_key_id: &Identifier,
 	participant_id: usize,
 	message: Option<String>,
@@ -150,8 +427,11 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	// sender should always refresh outputs
-	updater::refresh_outputs(wallet, keychain_mask, parent_key_id, false)?;
+	// sender should always refresh outputs, unless the node can't be reached
+	// and the outputs already known have to do
+	if updater::current_height(wallet)?.1 {
+		updater::refresh_outputs(wallet, keychain_mask, parent_key_id, false)?;
+	}
 
 	// Sender selects outputs into a new slate and save our corresponding keys in
 	// a transaction context. The secret key in our transaction context will be
@@ -169,6 +449,7 @@ where
 		max_outputs,
 		num_change_outputs,
 		selection_strategy_is_use_all,
+		selection_tag,
 		parent_key_id.clone(),
 		use_test_rng,
 	)?;
@@ -176,6 +457,13 @@ where
 	// Generate a kernel offset and subtract from our context's secret key. Store
 	// the offset in the slate's transaction kernel, and adds our public key
 	// information to the slate
+	#[cfg(feature = "deterministic_nonces")]
+	derive_context_nonce(
+		&wallet.keychain(keychain_mask)?,
+		slate,
+		&mut context,
+		use_test_rng,
+	)?;
 	let _ = slate.fill_round_1(
 		&wallet.keychain(keychain_mask)?,
 		&mut context.sec_key,
@@ -207,6 +495,7 @@ pub fn add_output_to_slate<'a, T: ?Sized, C, K>(
 	participant_id: usize,
 	message: Option<String>,
 	is_initiator: bool,
+	fee_contribution: Option<&FeeContribution>,
 	use_test_rng: bool,
 ) -> Result<Context, Error>
 where
@@ -214,16 +503,25 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	// create an output using the amount in the slate
+	// create an output using the amount in the slate, contributing an input
+	// if asked to and offering to
 	let (_, mut context) = selection::build_recipient_output(
 		wallet,
 		keychain_mask,
 		slate,
 		parent_key_id.clone(),
+		fee_contribution,
 		use_test_rng,
 	)?;
 
 	// fill public keys
+	#[cfg(feature = "deterministic_nonces")]
+	derive_context_nonce(
+		&wallet.keychain(keychain_mask)?,
+		slate,
+		&mut context,
+		use_test_rng,
+	)?;
 	let _ = slate.fill_round_1(
 		&wallet.keychain(keychain_mask)?,
 		&mut context.sec_key,
@@ -246,6 +544,28 @@ where
 	Ok(context)
 }
 
+/// With the `deterministic_nonces` feature, replaces the context's nonce with
+/// one derived from its secret key and the slate, where the kernel message is
+/// already fixed. The offset is taken off the key first, so the nonce is
+/// derived from the key it signs with. Reproducible test slates keep theirs
+#[cfg(feature = "deterministic_nonces")]
+fn derive_context_nonce<K>(
+	keychain: &K,
+	slate: &mut Slate,
+	context: &mut Context,
+	use_test_rng: bool,
+) -> Result<(), Error>
+where
+	K: Keychain,
+{
+	if !use_test_rng && slate.nonce_derivable() {
+		slate.fill_offset(keychain, &mut context.sec_key, use_test_rng)?;
+		context.sec_nonce =
+			slate.derive_nonce(keychain.secp(), &context.sec_key, context.participant_id)?;
+	}
+	Ok(())
+}
+
 /// Complete a transaction
 pub fn complete_tx<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
@@ -279,6 +599,118 @@ pub fn cancel_tx<'a, T: ?Sized, C, K>(
 	tx_id: Option<u32>,
 	tx_slate_id: Option<Uuid>,
 ) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (tx, res) = cancellable_tx(wallet, parent_key_id, tx_id, tx_slate_id)?;
+	let outputs = res.iter().map(|m| m.output.clone()).collect();
+	updater::cancel_tx_and_outputs(wallet, keychain_mask, tx, outputs, parent_key_id, false)?;
+	Ok(())
+}
+
+/// Rollback outputs associated with a transaction whose TTL cutoff height has
+/// passed, as [cancel_tx](fn.cancel_tx.html) does, marking it expired
+pub fn expire_tx<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	parent_key_id: &Identifier,
+	tx_id: u32,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (tx, res) = cancellable_tx(wallet, parent_key_id, Some(tx_id), None)?;
+	let outputs = res.iter().map(|m| m.output.clone()).collect();
+	updater::cancel_tx_and_outputs(wallet, keychain_mask, tx, outputs, parent_key_id, true)?;
+	Ok(())
+}
+
+/// Work out what [cancel_tx](fn.cancel_tx.html) would do to a transaction
+/// without changing anything, looking its kernel up on the node
+pub fn preview_cancel_tx<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	tx_id: Option<u32>,
+	tx_slate_id: Option<Uuid>,
+) -> Result<CancelPreview, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let (tx, outputs) = cancellable_tx(wallet, parent_key_id, tx_id, tx_slate_id)?;
+	let (unlocked_outputs, deleted_outputs): (Vec<_>, Vec<_>) = outputs
+		.into_iter()
+		.filter(|m| {
+			m.output.status == OutputStatus::Locked || m.output.status == OutputStatus::Unconfirmed
+		})
+		.partition(|m| m.output.status == OutputStatus::Locked);
+	let kernel_height = match tx.kernel_excess {
+		Some(ref e) => wallet
+			.w2n_client()
+			.get_kernel(e, tx.kernel_lookup_min_height, None)?
+			.map(|(_, height, _)| height),
+		None => None,
+	};
+	let in_pool = match tx.kernel_excess {
+		Some(_) if kernel_height.is_some() => Some(false),
+		Some(ref e) => match wallet.w2n_client().kernel_in_pool(e) {
+			Ok(found) => Some(found),
+			Err(e) => {
+				warn!(
+					"Unable to look for transaction {} in the pool: {}",
+					tx.id, e
+				);
+				None
+			}
+		},
+		None => None,
+	};
+	let posted = match tx.post_state {
+		Some(TxPostState::Posted) | Some(TxPostState::ReadyToPost) => true,
+		_ => false,
+	};
+	// the sender finalizes and posts, whatever the receiver does
+	let may_confirm = kernel_height.is_some()
+		|| in_pool == Some(true)
+		|| posted
+		|| tx.tx_type == TxLogEntryType::TxReceived;
+	Ok(CancelPreview {
+		tx,
+		unlocked_outputs,
+		deleted_outputs,
+		kernel_height,
+		in_pool,
+		posted,
+		may_confirm,
+	})
+}
+
+/// Why the node shows a transaction went through or may yet, if it does:
+/// its kernel is on chain or in the pool, or it was posted and the pool
+/// couldn't be checked
+pub fn seen_by_node(preview: &CancelPreview) -> Option<String> {
+	match (preview.kernel_height, preview.in_pool) {
+		(Some(h), _) => Some(format!("its kernel is on chain at height {}", h)),
+		(None, Some(true)) => Some("it's waiting to be mined in the node's pool".to_owned()),
+		(None, None) if preview.posted => {
+			Some("it was posted and the node's pool couldn't be checked".to_owned())
+		}
+		_ => None,
+	}
+}
+
+/// The unconfirmed send or receive to cancel, with its outputs
+fn cancellable_tx<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	tx_id: Option<u32>,
+	tx_slate_id: Option<Uuid>,
+) -> Result<(TxLogEntry, Vec<OutputCommitMapping>), Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
@@ -302,17 +734,112 @@ where
 		return Err(ErrorKind::TransactionNotCancellable(tx_id_string))?;
 	}
 	// get outputs associated with tx
-	let res = updater::retrieve_outputs(
-		wallet,
-		keychain_mask,
-		false,
-		false,
-		Some(tx.id),
-		Some(&parent_key_id),
-	)?;
-	let outputs = res.iter().map(|m| m.output.clone()).collect();
-	updater::cancel_tx_and_outputs(wallet, keychain_mask, tx, outputs, parent_key_id)?;
-	Ok(())
+	let outputs =
+		updater::retrieve_outputs(wallet, false, false, Some(tx.id), Some(&parent_key_id))?;
+	Ok((tx, outputs))
+}
+
+/// Find the transaction log entry, in any account, holding the stored
+/// transaction whose kernel has the given excess
+pub fn tx_log_entry_by_kernel<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	excess: &pedersen::Commitment,
+) -> Result<Option<TxLogEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let tx_vec = updater::retrieve_txs(wallet, None, None, None, false)?;
+	Ok(tx_vec
+		.into_iter()
+		.find(|t| t.stored_tx.is_some() && t.kernel_excess.as_ref() == Some(excess)))
+}
+
+/// Record how far posting the transaction of a log entry got, returning
+/// the state it replaced
+pub fn update_tx_post_state<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	tx: &mut TxLogEntry,
+	post_state: Option<TxPostState>,
+) -> Result<Option<TxPostState>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let prev_state = tx.post_state.clone();
+	// acknowledgment of a transaction already confirmed or rolled back
+	// doesn't move it back
+	if post_state == Some(TxPostState::Posted) && !tx.state.is_final() {
+		tx.transition(TxState::Posted)?;
+	}
+	tx.post_state = post_state;
+	let parent_key_id = tx.parent_key_id.clone();
+	let mut batch = wallet.batch(keychain_mask)?;
+	batch.save_tx_log_entry(tx.clone(), &parent_key_id)?;
+	batch.commit()?;
+	Ok(prev_state)
+}
+
+/// Transaction log entries, in any account, whose transaction was queued to
+/// post while the node was unreachable, or was about to be posted but never
+/// had the node's acknowledgment recorded
+pub fn pending_posts<'a, T: ?Sized, C, K>(wallet: &mut T) -> Result<Vec<TxLogEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let tx_vec = updater::retrieve_txs(wallet, None, None, None, false)?;
+	Ok(tx_vec
+		.into_iter()
+		.filter(|t| {
+			(t.post_state == Some(TxPostState::ReadyToPost)
+				|| t.post_state == Some(TxPostState::Queued))
+				&& (t.tx_type == TxLogEntryType::TxSent || t.tx_type == TxLogEntryType::TxReceived)
+		})
+		.collect())
+}
+
+/// Rollback all unconfirmed sends and receives created before `cutoff`, along
+/// with their outputs, in a single batch. Returns the cancelled entries
+pub fn cancel_stale_txs<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	parent_key_id: &Identifier,
+	cutoff: DateTime<Utc>,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let stale = updater::retrieve_txs(wallet, None, None, Some(&parent_key_id), true)?
+		.into_iter()
+		.filter(|tx| {
+			(tx.tx_type == TxLogEntryType::TxSent || tx.tx_type == TxLogEntryType::TxReceived)
+				&& !tx.confirmed
+				&& tx.creation_ts < cutoff
+		})
+		.collect::<Vec<_>>();
+	if stale.is_empty() {
+		return Ok(vec![]);
+	}
+	let outputs = updater::retrieve_outputs(wallet, false, false, None, Some(&parent_key_id))?;
+	let txs = stale
+		.into_iter()
+		.map(|tx| {
+			let tx_outputs = outputs
+				.iter()
+				.filter(|m| m.output.tx_log_entry == Some(tx.id))
+				.map(|m| m.output.clone())
+				.collect();
+			(tx, tx_outputs)
+		})
+		.collect();
+	updater::cancel_txs_and_outputs(wallet, keychain_mask, txs, parent_key_id, false)
 }
 
 /// Update the stored transaction (this update needs to happen when the TX is finalised)
@@ -346,6 +873,7 @@ where
 		Some(t) => t,
 		None => return Err(ErrorKind::TransactionDoesntExist(slate.id.to_string()))?,
 	};
+	tx.transition(TxState::Finalized)?;
 	wallet.store_tx(&format!("{}", tx.tx_slate_id.unwrap()), &slate.tx)?;
 	let parent_key = tx.parent_key_id.clone();
 	tx.kernel_excess = Some(slate.tx.body.kernels[0].excess);
@@ -361,8 +889,12 @@ where
 		let sender_key =
 			address::address_from_derivation_path(&keychain, &parent_key_id, derivation_index)?;
 		let sender_address = address::ed25519_keypair(&sender_key)?.1;
-		let sig =
-			create_payment_proof_signature(slate.amount, &excess, p.sender_address, sender_key)?;
+		let sig = create_payment_proof_signature(
+			slate.amount.freemans(),
+			&excess,
+			p.sender_address,
+			sender_key,
+		)?;
 		tx.payment_proof = Some(StoredProofInfo {
 			receiver_address: p.receiver_address,
 			receiver_signature: p.receiver_signature,
@@ -519,7 +1051,7 @@ where
 			))?;
 		}
 		let msg = payment_proof_message(
-			slate.amount,
+			slate.amount.freemans(),
 			&slate.calc_excess(&keychain)?,
 			orig_sender_address,
 		)?;
@@ -624,4 +1156,34 @@ mod test {
 
 		assert!(address.verify(&msg, &sig).is_ok());
 	}
+
+	#[test]
+	fn tx_state_transitions() {
+		let parent_key_id = ExtKeychainPath::new(2, 0, 0, 0, 0).to_identifier();
+		let mut t = TxLogEntry::new(parent_key_id.clone(), TxLogEntryType::TxSent, 0);
+		assert_eq!(t.state, TxState::Created);
+		t.transition(TxState::Sent).unwrap();
+		t.transition(TxState::Finalized).unwrap();
+		assert!(t.transition(TxState::Sent).is_err());
+		t.confirm(10).unwrap();
+		assert!(t.confirmed);
+		assert_eq!(t.confirmation_height, Some(10));
+
+		// final states stay put
+		assert!(t.cancel(false).is_err());
+		assert_eq!(t.tx_type, TxLogEntryType::TxSent);
+		assert!(t.transition(TxState::Confirmed).is_ok());
+
+		let mut t = TxLogEntry::new(parent_key_id, TxLogEntryType::TxReceived, 1);
+		t.transition(TxState::Sent).unwrap();
+		t.cancel(true).unwrap();
+		assert_eq!(t.state, TxState::Expired);
+		assert_eq!(t.tx_type, TxLogEntryType::TxReceivedCancelled);
+		assert!(t.confirm(10).is_err());
+		assert!(!t.confirmed);
+
+		// states written by later versions are kept as unknown
+		let state: TxState = serde_json::from_str("\"Disputed\"").unwrap();
+		assert_eq!(state, TxState::Unknown);
+	}
 }
@@ -15,31 +15,36 @@
 //! Utilities to check the status of all the outputs we have stored in
 //! the wallet storage and update them.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
 use uuid::Uuid;
 
+use crate::amount::Amount;
 use crate::epic_core::consensus::{cumulative_reward_foundation, header_version, reward};
 use crate::epic_core::core::block::HeaderVersion;
 use crate::epic_core::core::{Output, TxKernel};
 use crate::epic_core::global;
 use crate::epic_core::libtx::proof::{LegacyProofBuilder, ProofBuilder};
 use crate::epic_core::libtx::reward;
-use crate::epic_keychain::{Identifier, Keychain, SwitchCommitmentType};
-use crate::epic_util as util;
+use crate::epic_keychain::{Identifier, Keychain};
 use crate::epic_util::secp::key::SecretKey;
 use crate::epic_util::secp::pedersen;
 use crate::epic_util::static_secp_instance;
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
+use crate::events::WalletEvent;
 use crate::internal::keys;
 use crate::types::{
-	NodeClient, OutputData, OutputStatus, TxLogEntry, TxLogEntryType, WalletBackend, WalletInfo,
+	MaturityBucket, NodeClient, OutputData, OutputStatus, TxLogEntry, TxLogEntryType, TxState,
+	WalletBackend, WalletInfo, WalletInfoBreakdown, WalletOutputBatch, NODE_SYNCED_STATUS,
+};
+use crate::{
+	BalanceHistoryEntry, BlockFees, CbData, CleanedCoinbase, LockedOutput, OutputCommitMapping,
+	OutputLineage, OutputPage, TxFilter, TxLogPage,
 };
-use crate::{BlockFees, CbData, OutputCommitMapping};
 
 /// Retrieve all of the outputs (doesn't attempt to update from node)
 pub fn retrieve_outputs<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
-	keychain_mask: Option<&SecretKey>,
 	show_spent: bool,
 	show_full_history: bool,
 	tx_id: Option<u32>,
@@ -82,21 +87,97 @@ where
 	}
 
 	outputs.sort_by_key(|out| (out.n_child, out.tx_log_entry));
-	let keychain = wallet.keychain(keychain_mask)?;
 
-	let res = outputs
+	outputs
 		.into_iter()
 		.map(|output| {
-			let commit = match output.commit.clone() {
-				Some(c) => pedersen::Commitment::from_vec(util::from_hex(c).unwrap()),
-				None => keychain
-					.commit(output.value, &output.key_id, &SwitchCommitmentType::Regular)
-					.unwrap(), // TODO: proper support for different switch commitment schemes
-			};
-			OutputCommitMapping { output, commit }
+			let commit = output.commitment()?;
+			Ok(OutputCommitMapping { output, commit })
 		})
-		.collect();
-	Ok(res)
+		.collect()
+}
+
+/// Retrieve up to `limit` outputs, starting after the output the given cursor
+/// was returned with. Outputs are streamed from the backend, so only the
+/// requested page is held in memory
+pub fn retrieve_outputs_page<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	show_spent: bool,
+	parent_key_id: &Identifier,
+	cursor: Option<&str>,
+	limit: usize,
+) -> Result<OutputPage, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if limit == 0 {
+		return Err(ErrorKind::GenericError(
+			"Page limit must be greater than 0".to_owned(),
+		))?;
+	}
+	let mut page = wallet
+		.iter_from(cursor)?
+		.filter(|(_, out)| out.root_key_id == *parent_key_id)
+		.filter(|(_, out)| show_spent || out.status != OutputStatus::Spent)
+		.take(limit + 1)
+		.collect::<Vec<_>>();
+	let next_cursor = match page.len() > limit {
+		true => {
+			page.truncate(limit);
+			page.last().map(|(c, _)| c.clone())
+		}
+		false => None,
+	};
+	let outputs = page
+		.into_iter()
+		.map(|(_, output)| {
+			let commit = output.commitment()?;
+			Ok(OutputCommitMapping { output, commit })
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+	Ok(OutputPage {
+		outputs,
+		next_cursor,
+	})
+}
+
+/// Retrieve up to `limit` transaction entries of the given account in the
+/// order they were created, starting after the entry the given cursor was
+/// returned with. Entries are streamed from the backend, so only the
+/// requested page is held in memory
+pub fn retrieve_txs_page<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	cursor: Option<&str>,
+	limit: usize,
+) -> Result<TxLogPage, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if limit == 0 {
+		return Err(ErrorKind::GenericError(
+			"Page limit must be greater than 0".to_owned(),
+		))?;
+	}
+	let mut page = wallet
+		.tx_log_iter_from(parent_key_id, cursor)?
+		.take(limit + 1)
+		.collect::<Vec<_>>();
+	let next_cursor = match page.len() > limit {
+		true => {
+			page.truncate(limit);
+			page.last().map(|(c, _)| c.clone())
+		}
+		false => None,
+	};
+	Ok(TxLogPage {
+		txs: page.into_iter().map(|(_, t)| t).collect(),
+		next_cursor,
+	})
 }
 
 /// Retrieve all of the transaction entries, or a particular entry
@@ -113,8 +194,15 @@ where
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
-	let mut txs: Vec<TxLogEntry> = wallet
-		.tx_log_iter()
+	// use the indexes where the lookup allows it, rather than iterating
+	// over the whole tx log
+	let candidates: Box<dyn Iterator<Item = TxLogEntry> + '_> =
+		match (tx_slate_id, tx_id, parent_key_id) {
+			(Some(s), _, _) => Box::new(wallet.tx_log_entries_by_slate_id(&s)?.into_iter()),
+			(None, Some(i), Some(k)) => Box::new(wallet.tx_log_entry(k, i)?.into_iter()),
+			_ => wallet.tx_log_iter(),
+		};
+	let mut txs: Vec<TxLogEntry> = candidates
 		.filter(|tx_entry| {
 			let f_pk = match parent_key_id {
 				Some(k) => tx_entry.parent_key_id == *k,
@@ -139,10 +227,284 @@ where
 			f_pk && f_tx_id && f_txs && f_outstanding
 		})
 		.collect();
-	txs.sort_by_key(|tx| tx.creation_ts);
+	// entries written by older versions have no position, and come first
+	txs.sort_by_key(|tx| (tx.seq, tx.creation_ts));
 	Ok(txs)
 }
 
+/// Retrieve the transaction entries of the given account matching a filter,
+/// looking them up in the slate id or type index if the filter allows it
+pub fn retrieve_txs_by_filter<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	filter: &TxFilter,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut candidates = vec![];
+	if let Some(slate_ids) = filter.slate_ids() {
+		for s in slate_ids.iter() {
+			candidates.extend(wallet.tx_log_entries_by_slate_id(s)?);
+		}
+	} else if let Some(tx_types) = filter.tx_types() {
+		for t in tx_types.iter() {
+			candidates.extend(wallet.tx_log_entries_by_type(parent_key_id, t)?);
+		}
+	} else {
+		candidates.extend(
+			wallet
+				.tx_log_iter_from(parent_key_id, None)?
+				.map(|(_, t)| t),
+		);
+	}
+	let mut txs: Vec<TxLogEntry> = candidates
+		.into_iter()
+		.filter(|t| t.parent_key_id == *parent_key_id && filter.matches(t))
+		.collect();
+	// a filter may name the same slate or type more than once
+	txs.sort_by_key(|tx| (tx.seq, tx.creation_ts, tx.id));
+	txs.dedup_by_key(|tx| tx.id);
+	Ok(txs)
+}
+
+/// Retrieve the unconfirmed coinbase outputs removed from the given account,
+/// along with the tx log entries recording their removal
+pub fn retrieve_cleaned_coinbase<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+) -> Result<Vec<CleanedCoinbase>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let removed: Vec<OutputData> = wallet
+		.history_iter()
+		.filter(|o| {
+			o.status == OutputStatus::Deleted && o.is_coinbase && o.root_key_id == *parent_key_id
+		})
+		.collect();
+	let cleaned = retrieve_txs(wallet, None, None, Some(parent_key_id), false)?
+		.into_iter()
+		.filter(|t| t.tx_type == TxLogEntryType::RevertedCoinbase)
+		.map(|tx| {
+			let output = removed
+				.iter()
+				.find(|o| o.tx_log_entry == Some(tx.id))
+				.cloned();
+			CleanedCoinbase { tx, output }
+		})
+		.collect();
+	Ok(cleaned)
+}
+
+/// Retrieve every output of the given account, including deleted ones, grouped
+/// with the tx log entry that spent or deleted it and the states it went
+/// through before its latest one. If `tx_id` is given, only outputs that
+/// transaction was involved with in any of their recorded states are returned
+pub fn retrieve_output_lineage<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	tx_id: Option<u32>,
+) -> Result<Vec<OutputLineage>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut history: HashMap<(Identifier, Option<u64>), Vec<OutputData>> = HashMap::new();
+	for o in wallet
+		.history_iter()
+		.filter(|o| o.root_key_id == *parent_key_id)
+	{
+		history
+			.entry((o.key_id.clone(), o.mmr_index))
+			.or_insert_with(Vec::new)
+			.push(o);
+	}
+
+	let mut outputs = wallet
+		.iter()
+		.filter(|o| o.root_key_id == *parent_key_id)
+		.collect::<Vec<_>>();
+	// deleted outputs are only left in the history, as their last recorded state
+	for (key, states) in history.iter_mut() {
+		if !outputs
+			.iter()
+			.any(|o| o.key_id == key.0 && o.mmr_index == key.1)
+		{
+			if let Some(o) = states.pop() {
+				outputs.push(o);
+			}
+		}
+	}
+	outputs.sort_by_key(|out| (out.n_child, out.tx_log_entry));
+
+	let mut lineage = vec![];
+	for output in outputs {
+		let states = history
+			.remove(&(output.key_id.clone(), output.mmr_index))
+			.unwrap_or_default();
+		let spender = match output.status {
+			OutputStatus::Deleted => output.tx_log_entry,
+			_ => output.spent_by_tx,
+		};
+		if let Some(id) = tx_id {
+			let involved = |o: &OutputData| o.tx_log_entry == Some(id) || o.spent_by_tx == Some(id);
+			if !involved(&output) && !states.iter().any(involved) {
+				continue;
+			}
+		}
+		let spent_by = match spender {
+			Some(id) => wallet.tx_log_entry(parent_key_id, id)?,
+			None => None,
+		};
+		let spent_height = spent_by
+			.as_ref()
+			.and_then(|t| t.confirmation_height)
+			.or(output.spent_height);
+		let commit = output.commitment()?;
+		lineage.push(OutputLineage {
+			output: OutputCommitMapping { output, commit },
+			spent_by,
+			spent_height,
+			history: states,
+		});
+	}
+	Ok(lineage)
+}
+
+/// Retrieve the locked outputs of the given account, each with the tx log
+/// entry that locked it
+pub fn retrieve_locked_outputs<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+) -> Result<Vec<LockedOutput>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let mut outputs = wallet
+		.iter()
+		.filter(|o| o.root_key_id == *parent_key_id && o.status == OutputStatus::Locked)
+		.collect::<Vec<_>>();
+	outputs.sort_by_key(|out| out.n_child);
+
+	let mut locked = vec![];
+	for output in outputs {
+		let locked_by = match output.spent_by_tx {
+			Some(id) => wallet.tx_log_entry(parent_key_id, id)?,
+			None => None,
+		};
+		let stale = match locked_by.as_ref() {
+			Some(t) => t.tx_type == TxLogEntryType::TxSentCancelled,
+			None => true,
+		};
+		let locked_since = locked_by.as_ref().map(|t| t.creation_ts);
+		let commit = output.commitment()?;
+		locked.push(LockedOutput {
+			output: OutputCommitMapping { output, commit },
+			locked_by,
+			locked_since,
+			stale,
+		});
+	}
+	Ok(locked)
+}
+
+/// Fill in the confirmation count for each entry relative to the wallet's
+/// last confirmed height. If `lookup_hashes` is set, the hash of the
+/// confirming block is requested from the node for any confirmed entry
+/// that doesn't yet have one, along with the proof of work algorithm of
+/// the block for confirmed coinbase entries, and stored for later retrievals
+pub fn apply_confirmation_info<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	txs: &mut Vec<TxLogEntry>,
+	lookup_hashes: bool,
+) -> Result<(), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let tip_height = wallet.last_confirmed_height()?;
+	let mut updated = vec![];
+	if lookup_hashes {
+		let client = wallet.w2n_client().clone();
+		for tx in txs.iter_mut() {
+			let h = match (tx.confirmed, tx.confirmation_height) {
+				(true, Some(h)) => h,
+				_ => continue,
+			};
+			let mut changed = false;
+			if tx.confirmation_hash.is_none() {
+				match client.get_header_hash(h) {
+					Ok(hash) => {
+						tx.confirmation_hash = Some(hash);
+						changed = true;
+					}
+					Err(e) => warn!("Unable to retrieve header hash at height {}: {}", h, e),
+				}
+			}
+			if tx.tx_type == TxLogEntryType::ConfirmedCoinbase && tx.pow_type.is_none() {
+				match client.get_header_pow_type(h) {
+					Ok(pow_type) => {
+						tx.pow_type = Some(pow_type);
+						changed = true;
+					}
+					Err(e) => warn!("Unable to retrieve PoW type at height {}: {}", h, e),
+				}
+			}
+			if changed {
+				updated.push(tx.clone());
+			}
+		}
+	}
+	if !updated.is_empty() {
+		let mut batch = wallet.batch(keychain_mask)?;
+		for tx in updated {
+			let parent_key_id = tx.parent_key_id.clone();
+			batch.save_tx_log_entry(tx, &parent_key_id)?;
+		}
+		batch.commit()?;
+	}
+	for tx in txs.iter_mut() {
+		tx.confirmations = tx.num_confirmations(tip_height);
+	}
+	Ok(())
+}
+
+/// Height of the chain tip reported by the node, along with whether it could
+/// be reached. When it can't, the last height the wallet confirmed outputs at
+/// is given instead, so transactions can still be built offline from the
+/// outputs already known
+pub fn current_height<'a, T: ?Sized, C, K>(wallet: &mut T) -> Result<(u64, bool), Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let e = match wallet.w2n_client().get_chain_tip() {
+		Ok((height, _)) => return Ok((height, true)),
+		Err(e) => e,
+	};
+	let height = wallet.last_confirmed_height()?;
+	if height == 0 {
+		// never seen the chain, so there's nothing to fall back on
+		return Err(e);
+	}
+	warn!(
+		"Unable to contact node, using last known height {}: {}",
+		height, e
+	);
+	Ok((height, false))
+}
+
 /// Refreshes the outputs in a wallet with the latest information
 /// from a node
 pub fn refresh_outputs<'a, T: ?Sized, C, K>(
@@ -165,7 +527,6 @@ where
 /// and a list of outputs we want to query the node for
 pub fn map_wallet_outputs<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
-	keychain_mask: Option<&SecretKey>,
 	parent_key_id: &Identifier,
 	update_all: bool,
 ) -> Result<HashMap<pedersen::Commitment, (Identifier, Option<u64>)>, Error>
@@ -176,11 +537,19 @@ where
 {
 	let mut wallet_outputs: HashMap<pedersen::Commitment, (Identifier, Option<u64>)> =
 		HashMap::new();
-	let keychain = wallet.keychain(keychain_mask)?;
-	let unspents: Vec<OutputData> = wallet
-		.iter()
-		.filter(|x| x.root_key_id == *parent_key_id && x.status != OutputStatus::Spent)
-		.collect();
+	let mut unspents: Vec<OutputData> = vec![];
+	for status in &[
+		OutputStatus::Unconfirmed,
+		OutputStatus::Unspent,
+		OutputStatus::Locked,
+	] {
+		unspents.extend(
+			wallet
+				.outputs_by_status(status.clone())?
+				.into_iter()
+				.filter(|x| x.root_key_id == *parent_key_id),
+		);
+	}
 
 	let tx_entries = retrieve_txs(wallet, None, None, Some(&parent_key_id), true)?;
 
@@ -203,13 +572,7 @@ where
 	};
 
 	for out in unspents {
-		let commit = match out.commit.clone() {
-			Some(c) => pedersen::Commitment::from_vec(util::from_hex(c).unwrap()),
-			None => keychain
-				.commit(out.value, &out.key_id, &SwitchCommitmentType::Regular)
-				.unwrap(), // TODO: proper support for different switch commitment schemes
-		};
-		wallet_outputs.insert(commit, (out.key_id.clone(), out.mmr_index));
+		wallet_outputs.insert(out.commitment()?, (out.key_id.clone(), out.mmr_index));
 	}
 	Ok(wallet_outputs)
 }
@@ -221,123 +584,203 @@ pub fn cancel_tx_and_outputs<'a, T: ?Sized, C, K>(
 	tx: TxLogEntry,
 	outputs: Vec<OutputData>,
 	parent_key_id: &Identifier,
+	expired: bool,
 ) -> Result<(), Error>
 where
 	T: WalletBackend<'a, C, K>,
 	C: NodeClient + 'a,
 	K: Keychain + 'a,
 {
+	cancel_txs_and_outputs(
+		wallet,
+		keychain_mask,
+		vec![(tx, outputs)],
+		parent_key_id,
+		expired,
+	)?;
+	Ok(())
+}
+
+/// Cancel several transactions and their associated outputs in a single
+/// batch, returning the cancelled transaction log entries. With `expired`,
+/// the transactions are marked as having expired rather than been cancelled
+pub fn cancel_txs_and_outputs<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	keychain_mask: Option<&SecretKey>,
+	txs: Vec<(TxLogEntry, Vec<OutputData>)>,
+	parent_key_id: &Identifier,
+	expired: bool,
+) -> Result<Vec<TxLogEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	let events = wallet.events().clone();
 	let mut batch = wallet.batch(keychain_mask)?;
+	let mut cancelled = vec![];
 
-	for mut o in outputs {
-		// unlock locked outputs
-		if o.status == OutputStatus::Unconfirmed {
-			batch.delete(&o.key_id, &o.mmr_index, &Some(tx.id))?;
-		}
-		if o.status == OutputStatus::Locked {
-			o.status = OutputStatus::Unspent;
-			batch.save(o)?;
+	for (mut tx, outputs) in txs {
+		tx.cancel(expired)?;
+		for mut o in outputs {
+			// unlock locked outputs
+			if o.status == OutputStatus::Unconfirmed {
+				batch.delete(&o.key_id, &o.mmr_index, &Some(tx.id))?;
+			}
+			if o.status == OutputStatus::Locked {
+				o.status = OutputStatus::Unspent;
+				o.spent_by_tx = None;
+				batch.save(o)?;
+			}
 		}
+		batch.save_tx_log_entry(tx.clone(), parent_key_id)?;
+		cancelled.push(tx);
 	}
-	let mut tx = tx.clone();
-	if tx.tx_type == TxLogEntryType::TxSent {
-		tx.tx_type = TxLogEntryType::TxSentCancelled;
+	batch.commit()?;
+	for tx in cancelled.iter() {
+		events.emit(WalletEvent::TxCancelled {
+			parent_key_id: parent_key_id.clone(),
+			tx_id: tx.id,
+			tx_slate_id: tx.tx_slate_id,
+		});
 	}
-	if tx.tx_type == TxLogEntryType::TxReceived {
-		tx.tx_type = TxLogEntryType::TxReceivedCancelled;
+	Ok(cancelled)
+}
+
+/// What a refresh does with unspent outputs the node doesn't have
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MissingOutputs {
+	/// Leave them be, as the node isn't synced
+	Ignore,
+	/// Mark them spent once they've been missing from two refreshes in a row
+	Confirm,
+	/// Mark them spent
+	Spend,
+}
+
+/// Writes made to the wallet by a single refresh, logged along with the time
+/// taken to make and commit them
+#[derive(Debug, Default)]
+struct RefreshWrites {
+	outputs_saved: usize,
+	outputs_deleted: usize,
+	tx_entries_saved: usize,
+	/// Outputs found on the chain, reported once the writes are committed
+	confirmed: Vec<WalletEvent>,
+}
+
+impl RefreshWrites {
+	fn total(&self) -> usize {
+		self.outputs_saved + self.outputs_deleted + self.tx_entries_saved
 	}
-	batch.save_tx_log_entry(tx, parent_key_id)?;
-	batch.commit()?;
-	Ok(())
 }
 
-/// Apply refreshed API output data to the wallet
-pub fn apply_api_outputs<'a, T: ?Sized, C, K>(
-	wallet: &mut T,
-	keychain_mask: Option<&SecretKey>,
+/// Apply refreshed API output data to the given batch. Outputs whose state
+/// hasn't changed aren't rewritten, and a transaction log entry confirmed by
+/// several outputs is only written once
+fn apply_api_outputs<K>(
+	batch: &mut dyn WalletOutputBatch<K>,
 	wallet_outputs: &HashMap<pedersen::Commitment, (Identifier, Option<u64>)>,
 	api_outputs: &HashMap<pedersen::Commitment, (String, u64, u64)>,
 	height: u64,
 	parent_key_id: &Identifier,
+	spends: MissingOutputs,
+	writes: &mut RefreshWrites,
 ) -> Result<(), Error>
 where
-	T: WalletBackend<'a, C, K>,
-	C: NodeClient + 'a,
-	K: Keychain + 'a,
+	K: Keychain,
 {
 	// now for each commit, find the output in the wallet and the corresponding
 	// api output (if it exists) and refresh it in-place in the wallet.
-	// Note: minimizing the time we spend holding the wallet lock.
-	{
-		let last_confirmed_height = wallet.last_confirmed_height()?;
-		// If the server height is less than our confirmed height, don't apply
-		// these changes as the chain is syncing, incorrect or forking
-		if height < last_confirmed_height {
-			warn!(
-				"Not updating outputs as the height of the node's chain \
-				 is less than the last reported wallet update height."
-			);
-			warn!("Please wait for sync on node to complete or fork to resolve and try again.");
-			return Ok(());
-		}
-		let mut batch = wallet.batch(keychain_mask)?;
-		for (commit, (id, mmr_index)) in wallet_outputs.iter() {
-			if let Ok(mut output) = batch.get(id, mmr_index) {
-				match api_outputs.get(&commit) {
-					Some(o) => {
-						// if this is a coinbase tx being confirmed, it's recordable in tx log
-						if output.is_coinbase && output.status == OutputStatus::Unconfirmed {
-							let log_id = batch.next_tx_log_id(parent_key_id)?;
-							let mut t = TxLogEntry::new(
-								parent_key_id.clone(),
-								TxLogEntryType::ConfirmedCoinbase,
-								log_id,
-							);
-							t.confirmed = true;
-							t.amount_credited = output.value;
-							t.amount_debited = 0;
-							t.num_outputs = 1;
-							// calculate kernel excess for coinbase
-							{
-								let secp = static_secp_instance();
-								let secp = secp.lock();
-								let over_commit = secp.commit_value(output.value)?;
-								let excess =
-									secp.commit_sum(vec![commit.clone()], vec![over_commit])?;
-								t.kernel_excess = Some(excess);
-								t.kernel_lookup_min_height = Some(height);
-							}
-							t.update_confirmation_ts();
-							output.tx_log_entry = Some(log_id);
-							batch.save_tx_log_entry(t, &parent_key_id)?;
+	let mut tx_entries: HashMap<u32, TxLogEntry> = HashMap::new();
+	for (commit, (id, mmr_index)) in wallet_outputs.iter() {
+		if let Ok(mut output) = batch.get(id, mmr_index) {
+			let original = output.clone();
+			match api_outputs.get(&commit) {
+				Some(o) => {
+					// if this is a coinbase tx being confirmed, it's recordable in tx log
+					if output.is_coinbase && output.status == OutputStatus::Unconfirmed {
+						let log_id = batch.next_tx_log_id(parent_key_id)?;
+						let mut t = TxLogEntry::new(
+							parent_key_id.clone(),
+							TxLogEntryType::ConfirmedCoinbase,
+							log_id,
+						);
+						t.confirm(o.1)?;
+						t.amount_credited = Amount::from_freemans(output.value);
+						t.amount_debited = Amount::ZERO;
+						t.num_outputs = 1;
+						// calculate kernel excess for coinbase
+						{
+							let secp = static_secp_instance();
+							let secp = secp.lock();
+							let over_commit = secp.commit_value(output.value)?;
+							let excess =
+								secp.commit_sum(vec![commit.clone()], vec![over_commit])?;
+							t.kernel_excess = Some(excess);
+							t.kernel_lookup_min_height = Some(height);
 						}
-						// also mark the transaction in which this output is involved as confirmed
-						// note that one involved input/output confirmation SHOULD be enough
-						// to reliably confirm the tx
-						if !output.is_coinbase && output.status == OutputStatus::Unconfirmed {
-							let tx = batch.tx_log_iter().find(|t| {
-								Some(t.id) == output.tx_log_entry
-									&& t.parent_key_id == *parent_key_id
-							});
+						output.tx_log_entry = Some(log_id);
+						tx_entries.insert(log_id, t);
+					}
+					// also mark the transaction in which this output is involved as confirmed
+					// note that one involved input/output confirmation SHOULD be enough
+					// to reliably confirm the tx
+					if !output.is_coinbase && output.status == OutputStatus::Unconfirmed {
+						if let Some(log_id) = output.tx_log_entry {
+							let tx = match tx_entries.remove(&log_id) {
+								Some(t) => Some(t),
+								None => batch.get_tx_log_entry(parent_key_id, log_id)?,
+							};
+							// an entry that's already been rolled back is left as it is
 							if let Some(mut t) = tx {
-								t.update_confirmation_ts();
-								t.confirmed = true;
-								batch.save_tx_log_entry(t, &parent_key_id)?;
+								match t.confirm(o.1) {
+									Ok(()) => {
+										tx_entries.insert(log_id, t);
+									}
+									Err(e) => warn!("Not confirming transaction {}: {}", log_id, e),
+								}
 							}
 						}
-						output.height = o.1;
-						output.mark_unspent();
 					}
-					None => output.mark_spent(),
-				};
+					output.height = o.1;
+					output.missing_since = None;
+					output.mark_unspent();
+					if original.status == OutputStatus::Unconfirmed {
+						writes.confirmed.push(WalletEvent::OutputConfirmed {
+							root_key_id: output.root_key_id.clone(),
+							commit: output.commit.clone(),
+							value: Amount::from_freemans(output.value),
+							height: o.1,
+							tx_log_entry: output.tx_log_entry,
+						});
+					}
+				}
+				None => match output.status {
+					OutputStatus::Unspent | OutputStatus::Locked => match spends {
+						MissingOutputs::Ignore => {}
+						MissingOutputs::Confirm if output.missing_since.is_none() => {
+							output.missing_since = Some(height);
+						}
+						_ => {
+							output.missing_since = None;
+							output.mark_spent(height);
+						}
+					},
+					_ => {}
+				},
+			};
+			if output != original {
 				batch.save(output)?;
+				writes.outputs_saved += 1;
 			}
 		}
-		{
-			batch.save_last_confirmed_height(parent_key_id, height)?;
-		}
-		batch.commit()?;
 	}
+	for (_, t) in tx_entries {
+		batch.save_tx_log_entry(t, parent_key_id)?;
+		writes.tx_entries_saved += 1;
+	}
+	batch.save_last_confirmed_height(parent_key_id, height)?;
 	Ok(())
 }
 
@@ -359,7 +802,7 @@ where
 
 	// build a local map of wallet outputs keyed by commit
 	// and a list of outputs we want to query the node for
-	let wallet_outputs = map_wallet_outputs(wallet, keychain_mask, parent_key_id, update_all)?;
+	let wallet_outputs = map_wallet_outputs(wallet, parent_key_id, update_all)?;
 
 	let wallet_output_keys = wallet_outputs.keys().map(|commit| commit.clone()).collect();
 
@@ -367,55 +810,140 @@ where
 		.w2n_client()
 		.get_outputs_from_node(wallet_output_keys)?;
 
+	// If the server height is less than our confirmed height, don't apply
+	// these changes as the chain is syncing, incorrect or forking
+	let last_confirmed_height = wallet.last_confirmed_height()?;
+	if height < last_confirmed_height {
+		warn!(
+			"Not updating outputs as the height of the node's chain \
+			 is less than the last reported wallet update height."
+		);
+		warn!("Please wait for sync on node to complete or fork to resolve and try again.");
+		wallet.events().emit(WalletEvent::ReorgDetected {
+			parent_key_id: parent_key_id.clone(),
+			wallet_height: last_confirmed_height,
+			node_height: height,
+		});
+		return Ok(());
+	}
+
+	// outputs missing from a node that isn't synced may just not have reached
+	// it yet, so are only taken as spent once it's caught up
+	let spends = match wallet.w2n_client().get_sync_status() {
+		Ok(ref s) if s == NODE_SYNCED_STATUS => match wallet.confirm_missing_outputs() {
+			true => MissingOutputs::Confirm,
+			false => MissingOutputs::Spend,
+		},
+		Ok(s) => {
+			warn!(
+				"Not marking outputs missing from the node as spent while its sync status is {}",
+				s
+			);
+			MissingOutputs::Ignore
+		}
+		Err(e) => {
+			warn!(
+				"Not marking outputs missing from the node as spent, as its sync status is unknown: {}",
+				e
+			);
+			MissingOutputs::Ignore
+		}
+	};
+
+	// all the changes of a refresh are made in a single batch, minimizing the
+	// time we spend holding the wallet lock
+	let cleanup_window = wallet.coinbase_cleanup_window();
+	let events = wallet.events().clone();
+	let start = Instant::now();
+	let mut writes = RefreshWrites::default();
+	let mut batch = wallet.batch(keychain_mask)?;
 	apply_api_outputs(
-		wallet,
-		keychain_mask,
+		&mut *batch,
 		&wallet_outputs,
 		&api_outputs,
 		height,
 		parent_key_id,
+		spends,
+		&mut writes,
 	)?;
-	clean_old_unconfirmed(wallet, keychain_mask, height)?;
+	clean_old_unconfirmed(&mut *batch, height, cleanup_window, &mut writes)?;
+	batch.commit()?;
+	debug!(
+		"Refresh committed {} writes ({} outputs saved, {} outputs deleted, {} tx entries saved) in {}ms",
+		writes.total(),
+		writes.outputs_saved,
+		writes.outputs_deleted,
+		writes.tx_entries_saved,
+		start.elapsed().as_millis()
+	);
+	for e in writes.confirmed {
+		events.emit(e);
+	}
 	Ok(())
 }
 
-fn clean_old_unconfirmed<'a, T: ?Sized, C, K>(
-	wallet: &mut T,
-	keychain_mask: Option<&SecretKey>,
+/// Remove coinbase outputs that are still unconfirmed more than `window`
+/// blocks after the block they were built for, recording each removal in
+/// the tx log of the output's account as a reverted coinbase
+fn clean_old_unconfirmed<K>(
+	batch: &mut dyn WalletOutputBatch<K>,
 	height: u64,
+	window: u64,
+	writes: &mut RefreshWrites,
 ) -> Result<(), Error>
 where
-	T: WalletBackend<'a, C, K>,
-	C: NodeClient + 'a,
-	K: Keychain + 'a,
+	K: Keychain,
 {
-	if height < 50 {
+	if window == 0 || height < window {
 		return Ok(());
 	}
-	let mut ids_to_del = vec![];
-	for out in wallet.iter() {
-		if out.status == OutputStatus::Unconfirmed
+	let is_old_unconfirmed = |out: &OutputData| {
+		out.status == OutputStatus::Unconfirmed
 			&& out.height > 0
-			&& out.height < height - 50
+			&& out.height < height - window
 			&& out.is_coinbase
-		{
-			ids_to_del.push(out.key_id.clone())
-		}
-	}
-	let mut batch = wallet.batch(keychain_mask)?;
+	};
+	// the batch iterator doesn't see the changes made earlier in this batch,
+	// so check each candidate again before deleting it
+	let ids_to_del: Vec<Identifier> = batch
+		.iter()
+		.filter(|out| is_old_unconfirmed(out))
+		.map(|out| out.key_id)
+		.collect();
 	for id in ids_to_del {
-		batch.delete(&id, &None, &None)?;
+		let out = match batch.get(&id, &None) {
+			Ok(out) if is_old_unconfirmed(&out) => out,
+			_ => continue,
+		};
+		warn!(
+			"Removing coinbase output {} for block {}, unconfirmed after {} blocks",
+			out.key_id, out.height, window
+		);
+		let log_id = batch.next_tx_log_id(&out.root_key_id)?;
+		let mut t = TxLogEntry::new(
+			out.root_key_id.clone(),
+			TxLogEntryType::RevertedCoinbase,
+			log_id,
+		);
+		t.transition(TxState::Cancelled)?;
+		t.amount_credited = Amount::from_freemans(out.value);
+		t.num_outputs = 1;
+		batch.save_tx_log_entry(t, &out.root_key_id)?;
+		batch.delete(&id, &None, &Some(log_id))?;
+		writes.tx_entries_saved += 1;
+		writes.outputs_deleted += 1;
 	}
-	batch.commit()?;
 	Ok(())
 }
 
-/// Retrieve summary info about the wallet
+/// Retrieve summary info about the wallet, optionally broken down by the
+/// source of the outputs
 /// caller should refresh first if desired
 pub fn retrieve_info<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
 	parent_key_id: &Identifier,
 	minimum_confirmations: u64,
+	include_breakdown: bool,
 ) -> Result<WalletInfo, Error>
 where
 	T: WalletBackend<'a, C, K>,
@@ -427,42 +955,85 @@ where
 		.iter()
 		.filter(|out| out.root_key_id == *parent_key_id);
 
-	let mut unspent_total = 0;
-	let mut immature_total = 0;
-	let mut awaiting_finalization_total = 0;
-	let mut unconfirmed_total = 0;
-	let mut locked_total = 0;
+	let mut unspent_total = Amount::ZERO;
+	let mut immature_total = Amount::ZERO;
+	let mut awaiting_finalization_total = Amount::ZERO;
+	let mut unconfirmed_total = Amount::ZERO;
+	let mut locked_total = Amount::ZERO;
+	let mut breakdown = WalletInfoBreakdown::default();
+	let mut schedule: BTreeMap<u64, MaturityBucket> = BTreeMap::new();
 
 	for out in outputs {
+		let source = if out.is_foundation {
+			&mut breakdown.foundation
+		} else if out.is_coinbase {
+			&mut breakdown.coinbase
+		} else {
+			&mut breakdown.regular
+		};
+		let value = Amount::from_freemans(out.value);
 		match out.status {
 			OutputStatus::Unspent => {
 				if out.is_coinbase && out.lock_height > current_height {
-					immature_total += out.value;
+					immature_total += value;
+					source.amount_immature += value;
+					let bucket =
+						schedule
+							.entry(out.lock_height)
+							.or_insert_with(|| MaturityBucket {
+								lock_height: out.lock_height,
+								num_outputs: 0,
+								amount: Amount::ZERO,
+							});
+					bucket.num_outputs += 1;
+					bucket.amount += value;
 				} else if out.num_confirmations(current_height) < minimum_confirmations {
 					// Treat anything less than minimum confirmations as "unconfirmed".
-					unconfirmed_total += out.value;
+					unconfirmed_total += value;
+					source.amount_awaiting_confirmation += value;
 				} else {
-					unspent_total += out.value;
+					unspent_total += value;
+					source.amount_currently_spendable += value;
 				}
 			}
 			OutputStatus::Unconfirmed => {
 				// We ignore unconfirmed coinbase outputs completely.
 				if !out.is_coinbase {
 					if minimum_confirmations == 0 {
-						unconfirmed_total += out.value;
+						unconfirmed_total += value;
+						source.amount_awaiting_confirmation += value;
 					} else {
-						awaiting_finalization_total += out.value;
+						awaiting_finalization_total += value;
 					}
 				}
 			}
 			OutputStatus::Locked => {
-				locked_total += out.value;
+				locked_total += value;
+				source.amount_locked += value;
 			}
 			OutputStatus::Spent => {}
 			OutputStatus::Deleted => {}
+			OutputStatus::Unknown => {}
 		}
 	}
 
+	let breakdown = match include_breakdown {
+		true => {
+			for source in vec![
+				&mut breakdown.coinbase,
+				&mut breakdown.foundation,
+				&mut breakdown.regular,
+			] {
+				source.total = source.amount_currently_spendable
+					+ source.amount_awaiting_confirmation
+					+ source.amount_immature;
+			}
+			breakdown.immature_schedule = schedule.into_iter().map(|(_, b)| b).collect();
+			Some(breakdown)
+		}
+		false => None,
+	};
+
 	Ok(WalletInfo {
 		last_confirmed_height: current_height,
 		minimum_confirmations,
@@ -472,9 +1043,113 @@ where
 		amount_immature: immature_total,
 		amount_locked: locked_total,
 		amount_currently_spendable: unspent_total,
+		breakdown,
 	})
 }
 
+/// Reconstruct the confirmed balance of an account at regular height intervals
+/// from the stored outputs. The height at which a spent output was spent is
+/// taken from the outputs created by the spending transaction, or failing
+/// that from a lookup of the spending transaction's kernel. Spends for which
+/// neither is available are treated as having happened at the last confirmed
+/// height.
+pub fn retrieve_balance_history<'a, T: ?Sized, C, K>(
+	wallet: &mut T,
+	parent_key_id: &Identifier,
+	from_height: u64,
+	to_height: u64,
+	step: u64,
+) -> Result<Vec<BalanceHistoryEntry>, Error>
+where
+	T: WalletBackend<'a, C, K>,
+	C: NodeClient + 'a,
+	K: Keychain + 'a,
+{
+	if step == 0 {
+		return Err(ErrorKind::GenericError(
+			"Balance history step must be greater than 0".to_owned(),
+		))?;
+	}
+	if from_height > to_height {
+		return Err(ErrorKind::GenericError(
+			"Balance history start height must not be greater than end height".to_owned(),
+		))?;
+	}
+
+	let last_confirmed_height = wallet.last_confirmed_height()?;
+	let outputs: Vec<OutputData> = wallet
+		.iter()
+		.filter(|out| out.root_key_id == *parent_key_id)
+		.filter(|out| match out.status {
+			OutputStatus::Unspent | OutputStatus::Locked | OutputStatus::Spent => true,
+			_ => false,
+		})
+		.collect();
+
+	// Height at which each transaction was confirmed, as far as can be told
+	// from the outputs it created
+	let mut tx_heights: HashMap<u32, u64> = HashMap::new();
+	for out in outputs.iter().filter(|o| o.status != OutputStatus::Spent) {
+		if let Some(id) = out.tx_log_entry {
+			let h = tx_heights.entry(id).or_insert(out.height);
+			if out.height > *h {
+				*h = out.height;
+			}
+		}
+	}
+
+	let mut spent: Vec<(u64, u64)> = vec![];
+	for out in outputs.iter().filter(|o| o.status == OutputStatus::Spent) {
+		let spent_height = match out.tx_log_entry {
+			Some(id) => match tx_heights.get(&id).cloned() {
+				Some(h) => Some(h),
+				None => {
+					let tx = wallet.tx_log_entry(parent_key_id, id)?;
+					match tx.and_then(|t| t.kernel_excess.map(|e| (e, t.kernel_lookup_min_height)))
+					{
+						Some((excess, min_height)) => {
+							match wallet.w2n_client().get_kernel(&excess, min_height, None) {
+								Ok(Some((_, h, _))) => {
+									tx_heights.insert(id, h);
+									Some(h)
+								}
+								_ => None,
+							}
+						}
+						None => None,
+					}
+				}
+			},
+			None => None,
+		};
+		spent.push((spent_height.unwrap_or(last_confirmed_height), out.value));
+	}
+
+	let mut history = vec![];
+	let mut height = from_height;
+	loop {
+		let received: u64 = outputs
+			.iter()
+			.filter(|o| o.height <= height)
+			.map(|o| o.value)
+			.sum();
+		let sent: u64 = spent
+			.iter()
+			.filter(|(h, _)| *h <= height)
+			.map(|(_, v)| v)
+			.sum();
+		history.push(BalanceHistoryEntry {
+			height,
+			total: received.saturating_sub(sent),
+		});
+		if height == to_height {
+			break;
+		}
+		height = std::cmp::min(height.saturating_add(step), to_height);
+	}
+	Ok(history)
+}
+
 /// Build a coinbase output and insert into wallet
 pub fn build_coinbase<'a, T: ?Sized, C, K>(
 	wallet: &mut T,
@@ -553,7 +1228,7 @@ where
 
 	{
 		// Now acquire the wallet lock and write the new output.
-		let commit = wallet.calc_commit_for_cache(keychain_mask, amount, &key_id)?;
+		let commit = Some(wallet.calc_commit_for_cache(keychain_mask, amount, &key_id)?);
 		let mut batch = wallet.batch(keychain_mask)?;
 		batch.save(OutputData {
 			root_key_id: parent_key_id,
@@ -567,6 +1242,11 @@ where
 			lock_height: lock_height,
 			is_coinbase: true,
 			tx_log_entry: None,
+			is_foundation: false,
+			spent_by_tx: None,
+			spent_height: None,
+			tags: vec![],
+			missing_since: None,
 		})?;
 		batch.commit()?;
 	}
@@ -623,7 +1303,7 @@ where
 
 	{
 		// Now acquire the wallet lock and write the new output.
-		let commit = wallet.calc_commit_for_cache(keychain_mask, amount, &key_id)?;
+		let commit = Some(wallet.calc_commit_for_cache(keychain_mask, amount, &key_id)?);
 		let mut batch = wallet.batch(keychain_mask)?;
 		batch.save(OutputData {
 			root_key_id: parent_key_id,
@@ -637,6 +1317,11 @@ where
 			lock_height: lock_height,
 			is_coinbase: true,
 			tx_log_entry: None,
+			is_foundation: true,
+			spent_by_tx: None,
+			spent_height: None,
+			tags: vec![],
+			missing_since: None,
 		})?;
 		batch.commit()?;
 	}
@@ -25,6 +25,7 @@
 use epic_wallet_config as config;
 use epic_wallet_util::epic_core;
 use epic_wallet_util::epic_keychain;
+#[cfg(feature = "store")]
 use epic_wallet_util::epic_store;
 use epic_wallet_util::epic_util;
 
@@ -45,30 +46,60 @@ extern crate strum;
 extern crate strum_macros;
 
 pub mod address;
+pub mod amount;
 pub mod api_impl;
+pub mod denomination;
 mod error;
+pub mod events;
+pub mod fork_import;
 mod internal;
+pub mod nonce;
+pub mod payment_request;
+pub mod payment_uri;
 pub mod slate;
 pub mod slate_versions;
+pub mod tx_filter;
 mod types;
 
+pub use crate::amount::Amount;
+pub use crate::denomination::Denomination;
 pub use crate::error::{Error, ErrorKind};
-pub use crate::slate::{ParticipantData, ParticipantMessageData, Slate};
+pub use crate::events::{EventBus, WalletEvent};
+pub use crate::payment_request::{
+	PaymentRequest, PAYMENT_REQUEST_PREFIX, PAYMENT_REQUEST_TTL_SECS,
+};
+pub use crate::payment_uri::{PaymentUri, PAYMENT_URI_SCHEME};
+pub use crate::slate::{FeeBreakdown, ParticipantData, ParticipantMessageData, Slate};
 pub use crate::slate_versions::{
 	SlateVersion, VersionedCoinbase, VersionedSlate, CURRENT_SLATE_VERSION,
 	EPIC_BLOCK_HEADER_VERSION,
 };
+pub use crate::tx_filter::TxFilter;
 pub use api_impl::owner_updater::StatusMessage;
 pub use api_impl::types::{
-	BlockFees, InitTxArgs, InitTxSendArgs, IssueInvoiceTxArgs, NodeHeightResult,
-	OutputCommitMapping, PaymentProof, SendTXArgs, VersionInfo,
+	AccountKeyExport, BalanceHistoryEntry, BlockFees, CancelPreview, Capabilities, Capability,
+	CleanedCoinbase, CsvImport, CsvSkippedRow, DestinationTransport, DestinationValidation,
+	EncodedPaymentRequest, ExpectedCommitment, ExportedAccount, ExportedOutput, FinalityState,
+	InitTxArgs, InitTxArgsBuilder, InitTxSendArgs, IssueInvoiceTxArgs, IssueInvoiceTxArgsBuilder,
+	JobResult, KernelStatus, LockedOutput, MerchantInvoice, MerchantInvoiceStatus,
+	MiningRewardSummary, NodeHeightResult, NodePing, NodeStatus, OutputCommitMapping,
+	OutputLineage, OutputPage, PaymentProof, QueuedSendResult, ReceiveNotification, RewoundOutput,
+	SelectionStrategy, SendTXArgs, ShardBalance, SlateValidation, TxDetails, TxFinality, TxLogPage,
+	TxTemplate, TxTemplateInput, TxTemplateOutput, VersionAdvisory, VersionInfo, WalletState,
+	WalletStateExport, WalletStateImport,
 };
 pub use internal::scan::scan;
 pub use slate_versions::ser as dalek_ser;
 pub use types::{
-	AcctPathMapping, BlockIdentifier, CbData, Context, NodeClient, NodeVersionInfo, OutputData,
-	OutputStatus, ScannedBlockInfo, StoredProofInfo, TxLogEntry, TxLogEntryType, TxWrapper,
-	WalletBackend, WalletInfo, WalletInitStatus, WalletInst, WalletLCProvider, WalletOutputBatch,
+	AcctPathMapping, AuditEntry, BlockIdentifier, CbData, Context, FeeContribution,
+	IdempotentResult, Job, JobAction, MaturityBucket, MigrationReport, MigrationStep, NodeClient,
+	NodeVersionInfo, OutputData, OutputStatus, QueuedSend, Reservation, ScannedBlockInfo,
+	SourceBalance, StoredProofInfo, TxLogEntry, TxLogEntryType, TxPostState, TxState, TxWrapper,
+	WalletBackend, WalletInfo, WalletInfoBreakdown, WalletInitStatus, WalletInst, WalletLCProvider,
+	WalletOutputBatch, ACCOUNT_KEY_EXPORT_WARNING, CLOCK_SKEW_WARNING_SECS,
+	DEFAULT_COINBASE_CLEANUP_WINDOW, DUPLICATE_PAYMENT_WINDOW_SECS, JOB_MAX_ATTEMPTS,
+	JOB_RETRY_SECS, MAX_PENDING_RECEIVER_INPUTS, NODE_SYNCED_STATUS,
+	RECEIVER_INPUT_MIN_CONFIRMATIONS,
 };
 
 /// Helper for taking a lock on the wallet instance
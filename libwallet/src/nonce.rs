@@ -0,0 +1,108 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secret nonces for partial signatures derived from the secret key and the
+//! message signed, RFC6979-style, rather than drawn from a random number
+//! generator, which a broken one on an embedded device could make repeat.
+//!
+//! The derivation is RFC6979's HMAC_DRBG, with keyed blake2b standing in for
+//! HMAC-SHA256. Slates only use it, with the `deterministic_nonces` feature,
+//! once the kernel message is fixed, see
+//! [Slate::derive_nonce](../slate/struct.Slate.html#method.derive_nonce)
+
+use crate::blake2::blake2b::Blake2b;
+use crate::epic_util::secp::key::SecretKey;
+use crate::epic_util::secp::{Message, Secp256k1};
+use crate::error::{Error, ErrorKind};
+use zeroize::Zeroize;
+
+/// Separates these nonces from any other use of the same keys
+const NONCE_DOMAIN: &'static [u8] = b"epic-wallet partial signature nonce";
+
+/// Candidates tried before giving up. Each falls outside the curve order
+/// with a chance of about 2^-128
+const MAX_CANDIDATES: usize = 8;
+
+/// The secret nonce for signing the message with the secret key. The same
+/// key, message and additional data always give the same nonce, and a
+/// change to any of them a nonce unrelated to it. The additional data
+/// should tell apart signatures that may share a key and message
+pub fn deterministic_nonce(
+	secp: &Secp256k1,
+	sec_key: &SecretKey,
+	msg: &Message,
+	extra: &[u8],
+) -> Result<SecretKey, Error> {
+	let mut k = [0u8; 32];
+	let mut v = [1u8; 32];
+	k = mac(&k, &[&v, &[0], &sec_key.0, &msg[..], NONCE_DOMAIN, extra]);
+	v = mac(&k, &[&v]);
+	k = mac(&k, &[&v, &[1], &sec_key.0, &msg[..], NONCE_DOMAIN, extra]);
+	v = mac(&k, &[&v]);
+	let mut nonce = None;
+	for _ in 0..MAX_CANDIDATES {
+		v = mac(&k, &[&v]);
+		if let Ok(n) = SecretKey::from_slice(secp, &v) {
+			nonce = Some(n);
+			break;
+		}
+		k = mac(&k, &[&v, &[0]]);
+		v = mac(&k, &[&v]);
+	}
+	k.zeroize();
+	v.zeroize();
+	nonce.ok_or_else(|| ErrorKind::GenericError("No valid nonce derived".to_owned()).into())
+}
+
+// keyed blake2b over the parts, in place of HMAC
+fn mac(key: &[u8; 32], parts: &[&[u8]]) -> [u8; 32] {
+	let mut hasher = Blake2b::with_key(32, key);
+	for p in parts {
+		hasher.update(p);
+	}
+	let mut out = [0u8; 32];
+	out.copy_from_slice(hasher.finalize().as_bytes());
+	out
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_core::core::transaction::KernelFeatures;
+	use crate::epic_util::secp::ContextFlag;
+
+	#[test]
+	fn derive_nonces() {
+		let secp = Secp256k1::with_caps(ContextFlag::Commit);
+		let key = SecretKey::from_slice(&secp, &[1; 32]).unwrap();
+		let other_key = SecretKey::from_slice(&secp, &[2; 32]).unwrap();
+		let msg = KernelFeatures::Plain { fee: 8_000_000 }
+			.kernel_sig_msg()
+			.unwrap();
+		let other_msg = KernelFeatures::Plain { fee: 9_000_000 }
+			.kernel_sig_msg()
+			.unwrap();
+
+		let nonce = deterministic_nonce(&secp, &key, &msg, b"a").unwrap();
+		assert_eq!(nonce, deterministic_nonce(&secp, &key, &msg, b"a").unwrap());
+		assert!(nonce != key);
+		for other in vec![
+			deterministic_nonce(&secp, &other_key, &msg, b"a").unwrap(),
+			deterministic_nonce(&secp, &key, &other_msg, b"a").unwrap(),
+			deterministic_nonce(&secp, &key, &msg, b"b").unwrap(),
+		] {
+			assert!(other != nonce);
+		}
+	}
+}
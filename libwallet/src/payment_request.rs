@@ -0,0 +1,330 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signed payment requests, with which a wallet asks to be paid an amount.
+//!
+//! A request is signed with the payment proof address of the requesting
+//! account, so the payer knows who they're paying, and the payment proof of
+//! the transaction paying it names that same address. The transaction takes
+//! the request's id as its slate id, so the requester finds the payment under
+//! it and a payer's wallet won't pay the same request twice. Requests expire
+//! after [`PAYMENT_REQUEST_TTL_SECS`](constant.PAYMENT_REQUEST_TTL_SECS.html).
+//!
+//! Requests are passed on armored, as a single line short enough for one QR
+//! code:
+//!
+//! ```text
+//! EPICREQUEST:<data>
+//! ```
+//!
+//! where the data is the request's fields in binary, base32 encoded so the
+//! code can use the compact alphanumeric mode.
+
+use crate::amount::Amount;
+use crate::slate_versions::ser as dalek_ser;
+use crate::{Error, ErrorKind};
+use byteorder::{BigEndian, ByteOrder};
+use chrono::{DateTime, TimeZone, Utc};
+use data_encoding::BASE32_NOPAD;
+use ed25519_dalek::Keypair as DalekKeypair;
+use ed25519_dalek::PublicKey as DalekPublicKey;
+use ed25519_dalek::Signature as DalekSignature;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Prefix of armored payment requests
+pub const PAYMENT_REQUEST_PREFIX: &str = "EPICREQUEST";
+
+/// How long a payment request can be paid for after it's created
+pub const PAYMENT_REQUEST_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Longest memo a payment request may carry, in bytes
+pub const PAYMENT_REQUEST_MEMO_MAX_LEN: usize = 100;
+
+/// Version of the binary encoding
+const PAYMENT_REQUEST_VERSION: u8 = 1;
+
+/// Prefix of the message signed for a payment request, so the signature
+/// can't be passed off as any other
+const PAYMENT_REQUEST_DOMAIN: &[u8] = b"epic-wallet-payment-request";
+
+/// Length of the encoded fields ahead of the memo: version, id, amount,
+/// expiry, proof address and signature
+const FIXED_LEN: usize = 1 + 16 + 8 + 8 + 32 + 64;
+
+/// A request for payment, signed by the requesting account
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+	/// Id of the request, taken as the slate id of the transaction paying it
+	pub id: Uuid,
+	/// Amount requested
+	pub amount: Amount,
+	/// What the payment is for, passed on as the payer's message
+	#[serde(default)]
+	pub memo: Option<String>,
+	/// Time after which the request is no longer paid
+	pub expires_at: DateTime<Utc>,
+	/// Payment proof address of the requesting account
+	#[serde(with = "dalek_ser::dalek_pubkey_serde")]
+	pub proof_address: DalekPublicKey,
+	/// Signature of the above by the proof address
+	#[serde(with = "dalek_ser::dalek_sig_serde")]
+	pub signature: DalekSignature,
+}
+
+impl PaymentRequest {
+	/// Whether a destination is given as an armored payment request, rather
+	/// than an address or endpoint
+	pub fn is_payment_request(dest: &str) -> bool {
+		dest.get(..PAYMENT_REQUEST_PREFIX.len() + 1)
+			.map(|s| s.eq_ignore_ascii_case("EPICREQUEST:"))
+			.unwrap_or(false)
+	}
+
+	/// A request for the amount, signed with the keypair of the requesting
+	/// account's payment proof address. The expiry is kept to whole seconds
+	/// and an empty memo dropped, as they're encoded
+	pub fn new(
+		id: Uuid,
+		amount: Amount,
+		memo: Option<String>,
+		expires_at: DateTime<Utc>,
+		keypair: &DalekKeypair,
+	) -> Result<PaymentRequest, Error> {
+		let expires_at = Utc.timestamp(expires_at.timestamp(), 0);
+		let memo = memo.filter(|m| !m.is_empty());
+		check_fields(amount, &memo)?;
+		let msg = PaymentRequest::message(&id, amount, &memo, &expires_at);
+		Ok(PaymentRequest {
+			id,
+			amount,
+			memo,
+			expires_at,
+			proof_address: keypair.public,
+			signature: keypair.sign(&msg),
+		})
+	}
+
+	/// The signed message: id, amount, expiry and memo
+	pub fn message(
+		id: &Uuid,
+		amount: Amount,
+		memo: &Option<String>,
+		expires_at: &DateTime<Utc>,
+	) -> Vec<u8> {
+		let mut msg = PAYMENT_REQUEST_DOMAIN.to_vec();
+		msg.extend_from_slice(id.as_bytes());
+		msg.extend_from_slice(&amount.freemans().to_be_bytes());
+		msg.extend_from_slice(&expires_at.timestamp().to_be_bytes());
+		if let Some(m) = memo {
+			msg.extend_from_slice(m.as_bytes());
+		}
+		msg
+	}
+
+	/// Check the request is well formed and signed by its proof address
+	pub fn verify(&self) -> Result<(), Error> {
+		check_fields(self.amount, &self.memo)?;
+		let msg = PaymentRequest::message(&self.id, self.amount, &self.memo, &self.expires_at);
+		if self.proof_address.verify(&msg, &self.signature).is_err() {
+			return Err(invalid("signature doesn't match the request"));
+		}
+		Ok(())
+	}
+
+	/// Whether the request can no longer be paid
+	pub fn is_expired(&self) -> bool {
+		self.expires_at <= Utc::now()
+	}
+
+	/// The request armored, for passing on as text or a QR code
+	pub fn armor(&self) -> String {
+		let mut data = vec![0u8; FIXED_LEN];
+		data[0] = PAYMENT_REQUEST_VERSION;
+		data[1..17].copy_from_slice(self.id.as_bytes());
+		BigEndian::write_u64(&mut data[17..25], self.amount.freemans());
+		BigEndian::write_i64(&mut data[25..33], self.expires_at.timestamp());
+		data[33..65].copy_from_slice(self.proof_address.as_bytes());
+		data[65..129].copy_from_slice(&self.signature.to_bytes());
+		if let Some(ref m) = self.memo {
+			data.extend_from_slice(m.as_bytes());
+		}
+		format!("{}:{}", PAYMENT_REQUEST_PREFIX, BASE32_NOPAD.encode(&data))
+	}
+}
+
+fn invalid(reason: &str) -> Error {
+	ErrorKind::PaymentRequest(reason.to_owned()).into()
+}
+
+fn check_fields(amount: Amount, memo: &Option<String>) -> Result<(), Error> {
+	if amount.is_zero() {
+		return Err(invalid("amount must be greater than zero"));
+	}
+	if let Some(m) = memo {
+		if m.len() > PAYMENT_REQUEST_MEMO_MAX_LEN {
+			return Err(invalid(&format!(
+				"memo is longer than {} bytes",
+				PAYMENT_REQUEST_MEMO_MAX_LEN
+			)));
+		}
+	}
+	Ok(())
+}
+
+/// Reads an armored request, checking its signature but not its expiry
+impl FromStr for PaymentRequest {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<PaymentRequest, Error> {
+		let s = s.trim();
+		let data = match s.find(':') {
+			Some(i) if s[..i].eq_ignore_ascii_case(PAYMENT_REQUEST_PREFIX) => &s[i + 1..],
+			_ => return Err(invalid("not an armored payment request")),
+		};
+		let data = BASE32_NOPAD
+			.decode(data.to_uppercase().as_bytes())
+			.map_err(|_| invalid("request is not base32 encoded"))?;
+		if data.len() < FIXED_LEN {
+			return Err(invalid("request is too short"));
+		}
+		if data[0] != PAYMENT_REQUEST_VERSION {
+			return Err(invalid(&format!("unsupported version {}", data[0])));
+		}
+		let memo = match data.len() > FIXED_LEN {
+			true => Some(
+				String::from_utf8(data[FIXED_LEN..].to_vec())
+					.map_err(|_| invalid("memo is not valid UTF-8"))?,
+			),
+			false => None,
+		};
+		let request = PaymentRequest {
+			id: Uuid::from_slice(&data[1..17]).map_err(|_| invalid("invalid id"))?,
+			amount: Amount::from_freemans(BigEndian::read_u64(&data[17..25])),
+			memo,
+			expires_at: Utc
+				.timestamp_opt(BigEndian::read_i64(&data[25..33]), 0)
+				.single()
+				.ok_or_else(|| invalid("invalid expiry"))?,
+			proof_address: DalekPublicKey::from_bytes(&data[33..65])
+				.map_err(|_| invalid("invalid proof address"))?,
+			signature: DalekSignature::from_bytes(&data[65..129])
+				.map_err(|_| invalid("invalid signature"))?,
+		};
+		request.verify()?;
+		Ok(request)
+	}
+}
+
+impl fmt::Display for PaymentRequest {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.armor())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use chrono::Duration;
+	use ed25519_dalek::SecretKey as DalekSecretKey;
+
+	fn keypair(seed: u8) -> DalekKeypair {
+		let secret = DalekSecretKey::from_bytes(&[seed; 32]).unwrap();
+		DalekKeypair {
+			public: (&secret).into(),
+			secret,
+		}
+	}
+
+	#[test]
+	fn payment_request_round_trip() {
+		let expires_at = Utc::now() + Duration::seconds(PAYMENT_REQUEST_TTL_SECS);
+		let request = PaymentRequest::new(
+			Uuid::new_v4(),
+			Amount::from_freemans(150_000_000),
+			Some("order #42".to_owned()),
+			expires_at,
+			&keypair(1),
+		)
+		.unwrap();
+		assert!(request.verify().is_ok());
+		assert!(!request.is_expired());
+
+		let armored = request.armor();
+		assert!(armored.starts_with("EPICREQUEST:"));
+		assert!(armored
+			.chars()
+			.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == ':'));
+		assert_eq!(armored.parse::<PaymentRequest>().unwrap(), request);
+		assert_eq!(
+			armored.to_lowercase().parse::<PaymentRequest>().unwrap(),
+			request
+		);
+
+		let request = PaymentRequest::new(
+			Uuid::new_v4(),
+			Amount::from_freemans(1),
+			None,
+			Utc::now() - Duration::seconds(1),
+			&keypair(2),
+		)
+		.unwrap();
+		assert!(request.is_expired());
+		assert_eq!(
+			request.to_string().parse::<PaymentRequest>().unwrap(),
+			request
+		);
+	}
+
+	#[test]
+	fn payment_request_invalid() {
+		let expires_at = Utc::now() + Duration::seconds(PAYMENT_REQUEST_TTL_SECS);
+		let new = |amount: u64, memo: Option<String>| {
+			PaymentRequest::new(
+				Uuid::new_v4(),
+				Amount::from_freemans(amount),
+				memo,
+				expires_at,
+				&keypair(1),
+			)
+		};
+		assert!(new(0, None).is_err());
+		assert!(new(1, Some("x".repeat(PAYMENT_REQUEST_MEMO_MAX_LEN + 1))).is_err());
+
+		// changing anything signed, or the signer, breaks the signature
+		let request = new(100, Some("order #42".to_owned())).unwrap();
+		let mut changed = request.clone();
+		changed.amount = Amount::from_freemans(1000);
+		assert!(changed.verify().is_err());
+		let mut changed = request.clone();
+		changed.memo = None;
+		assert!(changed.armor().parse::<PaymentRequest>().is_err());
+		let mut changed = request.clone();
+		changed.proof_address = keypair(2).public;
+		assert!(changed.verify().is_err());
+
+		let armored = request.armor();
+		let mut data = BASE32_NOPAD.decode(armored[12..].as_bytes()).unwrap();
+		data[0] = PAYMENT_REQUEST_VERSION + 1;
+		for s in vec![
+			"EPICSLATE:AAAA".to_owned(),
+			"EPICREQUEST:not base32!".to_owned(),
+			armored[..armored.len() - 40].to_owned(),
+			format!("EPICREQUEST:{}", BASE32_NOPAD.encode(&data)),
+		] {
+			assert!(s.parse::<PaymentRequest>().is_err(), "{} was accepted", s);
+		}
+	}
+}
@@ -0,0 +1,258 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `epic:` payment links, carrying everything needed to pay someone in a
+//! single URI that can be clicked, or read from a QR code or NFC tag:
+//!
+//! ```text
+//! epic:<destination>?amount=<epics>&message=<text>&proof=1
+//! ```
+//!
+//! The destination is the recipient's onion v3 address, or the http(s)
+//! endpoint of their listener. Amounts are given in epics, and a `proof`
+//! of `1` asks for a payment proof. Every parameter is optional, and values
+//! are percent-encoded. Unknown parameters are ignored, unless their names
+//! start with `req-`, meaning the link can't be paid without understanding
+//! them.
+
+use crate::address;
+use crate::denomination::Denomination;
+use crate::epic_core::libtx::secp_ser;
+use crate::{Error, ErrorKind};
+use std::fmt;
+use std::str::FromStr;
+
+/// Scheme of payment links
+pub const PAYMENT_URI_SCHEME: &str = "epic";
+
+/// A payment link, as read from or written to an `epic:` URI
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PaymentUri {
+	/// Onion v3 address, or http(s) listener endpoint, of the recipient
+	pub dest: String,
+	/// Amount requested, in freemans
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	pub amount: Option<u64>,
+	/// Message to include with the payment
+	#[serde(default)]
+	pub message: Option<String>,
+	/// Whether the recipient asks for a payment proof
+	#[serde(default)]
+	pub request_payment_proof: bool,
+}
+
+impl PaymentUri {
+	/// Whether a destination is given as a payment link, rather than an
+	/// address or endpoint
+	pub fn is_payment_uri(dest: &str) -> bool {
+		dest.get(..PAYMENT_URI_SCHEME.len() + 1)
+			.map(|s| s.eq_ignore_ascii_case("epic:"))
+			.unwrap_or(false)
+	}
+
+	/// Check the link can be paid, i.e. that it has a destination the wallet
+	/// can send to and any amount isn't zero
+	pub fn validate(&self) -> Result<(), Error> {
+		let dest = self.dest.as_str();
+		let is_http = dest.starts_with("http://") || dest.starts_with("https://");
+		if !is_http && address::pubkey_from_onion_v3(dest).is_err() {
+			return Err(invalid(&format!(
+				"destination '{}' is neither an onion v3 address nor an http(s) endpoint",
+				dest
+			)));
+		}
+		if self.amount == Some(0) {
+			return Err(invalid("amount must be greater than zero"));
+		}
+		Ok(())
+	}
+}
+
+fn invalid(reason: &str) -> Error {
+	ErrorKind::PaymentUri(reason.to_owned()).into()
+}
+
+/// Percent-encode everything but the unreserved characters of RFC 3986,
+/// and any others given
+fn percent_encode(s: &str, keep: &str) -> String {
+	s.bytes()
+		.map(|b| {
+			let c = b as char;
+			if c.is_ascii_alphanumeric() || "-._~".contains(c) || keep.contains(c) {
+				c.to_string()
+			} else {
+				format!("%{:02X}", b)
+			}
+		})
+		.collect()
+}
+
+/// Reverse percent-encoding, also reading `+` as a space in query values
+fn percent_decode(s: &str, plus_as_space: bool) -> Result<String, Error> {
+	let bytes = s.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		match bytes[i] {
+			b'%' => {
+				let hex = s
+					.get(i + 1..i + 3)
+					.and_then(|h| u8::from_str_radix(h, 16).ok())
+					.ok_or_else(|| invalid(&format!("bad percent-encoding in '{}'", s)))?;
+				out.push(hex);
+				i += 3;
+			}
+			b'+' if plus_as_space => {
+				out.push(b' ');
+				i += 1;
+			}
+			b => {
+				out.push(b);
+				i += 1;
+			}
+		}
+	}
+	String::from_utf8(out).map_err(|_| invalid(&format!("'{}' is not valid UTF-8", s)))
+}
+
+impl FromStr for PaymentUri {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<PaymentUri, Error> {
+		let s = s.trim();
+		if !PaymentUri::is_payment_uri(s) {
+			return Err(invalid(&format!("'{}' is not an epic: link", s)));
+		}
+		let rest = &s[PAYMENT_URI_SCHEME.len() + 1..];
+		let mut split = rest.splitn(2, '?');
+		let mut uri = PaymentUri {
+			dest: percent_decode(split.next().unwrap_or(""), false)?,
+			amount: None,
+			message: None,
+			request_payment_proof: false,
+		};
+
+		for param in split.next().unwrap_or("").split('&') {
+			if param.is_empty() {
+				continue;
+			}
+			let mut kv = param.splitn(2, '=');
+			let key = kv.next().unwrap_or("");
+			let value = percent_decode(kv.next().unwrap_or(""), true)?;
+			match key {
+				"amount" => uri.amount = Some(Denomination::Epic.parse_amount(&value)?),
+				"message" => uri.message = Some(value),
+				"proof" => {
+					uri.request_payment_proof = match value.as_str() {
+						"1" | "true" => true,
+						"0" | "false" => false,
+						v => return Err(invalid(&format!("proof of '{}' is not 1 or 0", v))),
+					}
+				}
+				k if k.starts_with("req-") => {
+					return Err(invalid(&format!(
+						"required parameter '{}' not supported",
+						k
+					)));
+				}
+				_ => {}
+			}
+		}
+		uri.validate()?;
+		Ok(uri)
+	}
+}
+
+impl fmt::Display for PaymentUri {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut params = vec![];
+		if let Some(a) = self.amount {
+			params.push(format!(
+				"amount={}",
+				Denomination::Epic.format_amount(a, true)
+			));
+		}
+		if let Some(ref m) = self.message {
+			params.push(format!("message={}", percent_encode(m, "")));
+		}
+		if self.request_payment_proof {
+			params.push("proof=1".to_owned());
+		}
+		write!(
+			f,
+			"{}:{}",
+			PAYMENT_URI_SCHEME,
+			percent_encode(&self.dest, ":/@[]")
+		)?;
+		if !params.is_empty() {
+			write!(f, "?{}", params.join("&"))?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_core::consensus::EPIC_BASE;
+
+	const ONION: &str = "2a6at2obto3uvkpkitqp4wxcg6u36qf534eucbskqciturczzc5suyid";
+
+	#[test]
+	fn payment_uri_round_trip() {
+		let uri = PaymentUri {
+			dest: "https://shop.example.com:3415/pay?order=42".to_owned(),
+			amount: Some(EPIC_BASE * 3 / 2),
+			message: Some("order #42 & more".to_owned()),
+			request_payment_proof: true,
+		};
+		let s = uri.to_string();
+		assert_eq!(
+			s,
+			"epic:https://shop.example.com:3415/pay%3Forder%3D42?amount=1.5&message=order%20%2342%20%26%20more&proof=1"
+		);
+		assert_eq!(s.parse::<PaymentUri>().unwrap(), uri);
+
+		let uri = format!("EPIC:{}", ONION).parse::<PaymentUri>().unwrap();
+		assert_eq!(uri.dest, ONION);
+		assert_eq!(uri.amount, None);
+		assert!(!uri.request_payment_proof);
+		assert_eq!(uri.to_string(), format!("epic:{}", ONION));
+
+		// spaces may be given as '+', and unknown parameters are ignored
+		let uri = format!("epic:{}?message=thanks+again&label=x", ONION)
+			.parse::<PaymentUri>()
+			.unwrap();
+		assert_eq!(uri.message, Some("thanks again".to_owned()));
+	}
+
+	#[test]
+	fn payment_uri_invalid() {
+		for s in vec![
+			"bitcoin:abc".to_owned(),
+			"epic:".to_owned(),
+			"epic:not-an-address".to_owned(),
+			"epic:ftp://example.com".to_owned(),
+			format!("epic:{}?amount=0", ONION),
+			format!("epic:{}?amount=1.5.2", ONION),
+			format!("epic:{}?proof=maybe", ONION),
+			format!("epic:{}?message=%E2%28", ONION),
+			format!("epic:{}?message=%4", ONION),
+			format!("epic:{}?req-expires=100", ONION),
+		] {
+			assert!(s.parse::<PaymentUri>().is_err(), "{} was accepted", s);
+		}
+	}
+}
@@ -15,6 +15,7 @@
 //! Functions for building partial transactions to be passed
 //! around during an interactive wallet exchange
 
+use crate::amount::Amount;
 use crate::blake2::blake2b::blake2b;
 use crate::epic_core::core::amount_to_hr_string;
 use crate::epic_core::core::committed::Committed;
@@ -30,22 +31,25 @@ use crate::epic_util::secp::pedersen::Commitment;
 use crate::epic_util::secp::Signature;
 use crate::epic_util::{self, secp, RwLock};
 use crate::error::{Error, ErrorKind};
+use crate::nonce;
 use crate::slate_versions::ser as dalek_ser;
 use ed25519_dalek::PublicKey as DalekPublicKey;
 use ed25519_dalek::Signature as DalekSignature;
+use epic_wallet_util::random_secret_key;
 use failure::ResultExt;
 use rand::rngs::mock::StepRng;
-use rand::thread_rng;
 use serde::ser::{Serialize, Serializer};
 use serde_json;
+use std::cmp;
 use std::fmt;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::slate_versions::v1::SlateV1;
 use crate::slate_versions::v2::SlateV2;
 use crate::slate_versions::v3::{
-	CoinbaseV3, InputV3, OutputV3, ParticipantDataV3, PaymentInfoV3, SlateV3, TransactionBodyV3,
-	TransactionV3, TxKernelV3, VersionCompatInfoV3,
+	CoinbaseV3, FeeBreakdownV3, InputV3, OutputV3, ParticipantDataV3, PaymentInfoV3, SlateV3,
+	TransactionBodyV3, TransactionV3, TxKernelV3, VersionCompatInfoV3,
 };
 use crate::slate_versions::{CURRENT_SLATE_VERSION, EPIC_BLOCK_HEADER_VERSION};
 use crate::types::CbData;
@@ -60,6 +64,63 @@ pub struct PaymentInfo {
 	pub receiver_signature: Option<DalekSignature>,
 }
 
+/// Weight each input takes from a transaction when working out its fee, as
+/// spending an output shrinks the chain state
+pub const FEE_INPUT_WEIGHT: u64 = 1;
+/// Weight each output adds to a transaction when working out its fee
+pub const FEE_OUTPUT_WEIGHT: u64 = 4;
+/// Weight each kernel adds to a transaction when working out its fee
+pub const FEE_KERNEL_WEIGHT: u64 = 1;
+
+/// How the fee of a transaction was worked out. Outputs and kernels add to
+/// the transaction's weight and inputs take from it, down to a weight of 1,
+/// and the fee is the weight times the fee base. Spending many small outputs
+/// is cheap, while each output created costs several times the fee base
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeeBreakdown {
+	/// Number of inputs
+	pub num_inputs: usize,
+	/// Number of outputs, including the receiver's
+	pub num_outputs: usize,
+	/// Number of kernels
+	pub num_kernels: usize,
+	/// Weight taken from the transaction by each input
+	pub input_weight: u64,
+	/// Weight added to the transaction by each output
+	pub output_weight: u64,
+	/// Weight added to the transaction by each kernel
+	pub kernel_weight: u64,
+	/// Weight of the transaction
+	pub weight: u64,
+	/// Fee per unit of weight
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub fee_base: u64,
+	/// Fee of the transaction
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub fee: u64,
+}
+
+impl FeeBreakdown {
+	/// Work out the fee of a transaction with the given numbers of inputs,
+	/// outputs and kernels
+	pub fn new(num_inputs: usize, num_outputs: usize, num_kernels: usize) -> FeeBreakdown {
+		// at a fee base of 1 the fee is the weight
+		let weight = cmp::max(tx_fee(num_inputs, num_outputs, num_kernels, Some(1)), 1);
+		let fee = tx_fee(num_inputs, num_outputs, num_kernels, None);
+		FeeBreakdown {
+			num_inputs,
+			num_outputs,
+			num_kernels,
+			input_weight: FEE_INPUT_WEIGHT,
+			output_weight: FEE_OUTPUT_WEIGHT,
+			kernel_weight: FEE_KERNEL_WEIGHT,
+			weight,
+			fee_base: fee / weight,
+			fee,
+		}
+	}
+}
+
 /// Public data for each participant in the slate
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ParticipantData {
@@ -174,8 +235,7 @@ pub struct Slate {
 	/// inputs, outputs, kernels, kernel offset
 	pub tx: Transaction,
 	/// base amount (excluding fee)
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount: u64,
+	pub amount: Amount,
 	/// fee amount
 	#[serde(with = "secp_ser::string_or_u64")]
 	pub fee: u64,
@@ -197,6 +257,18 @@ pub struct Slate {
 	/// Payment Proof
 	#[serde(default = "default_payment_none")]
 	pub payment_proof: Option<PaymentInfo>,
+	/// Part of the fee paid by the receiver, out of an input it adds to the
+	/// transaction. Set by senders asking the receiver to contribute, `None`
+	/// otherwise
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub receiver_fee: Option<u64>,
+	/// How the sender worked out the fee, set when the sender selects its
+	/// inputs
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fee_breakdown: Option<FeeBreakdown>,
 }
 
 fn default_payment_none() -> Option<PaymentInfo> {
@@ -243,13 +315,28 @@ impl Slate {
 		Ok(v3.into())
 	}
 
+	/// Recieve a slate like `deserialize_upgrade`, also reading the V0 and V1
+	/// slates of old wallets. The slate keeps its original version, so it's
+	/// serialized back in that version for the sender
+	pub fn deserialize_legacy(slate_json: &str) -> Result<Slate, Error> {
+		match Slate::parse_slate_version(slate_json)? {
+			0 | 1 => {
+				let v1: SlateV1 =
+					serde_json::from_str(slate_json).context(ErrorKind::SlateDeser)?;
+				let v3 = SlateV3::from(SlateV2::from(v1));
+				Ok(v3.into())
+			}
+			_ => Slate::deserialize_upgrade(slate_json),
+		}
+	}
+
 	/// Create a new slate
 	pub fn blank(num_participants: usize) -> Slate {
 		Slate {
 			num_participants: num_participants,
 			id: Uuid::new_v4(),
 			tx: Transaction::empty(),
-			amount: 0,
+			amount: Amount::ZERO,
 			fee: 0,
 			height: 0,
 			lock_height: 0,
@@ -261,6 +348,8 @@ impl Slate {
 				block_header_version: EPIC_BLOCK_HEADER_VERSION,
 			},
 			payment_proof: None,
+			receiver_fee: None,
+			fee_breakdown: None,
 		}
 	}
 
@@ -292,6 +381,25 @@ impl Slate {
 			.replace_kernel(TxKernel::with_features(self.kernel_features()));
 	}
 
+	/// Generates the kernel offset and subtracts it from the secret key, if
+	/// no participant has yet. Done by [fill_round_1](#method.fill_round_1)
+	/// when it hasn't been already
+	pub fn fill_offset<K>(
+		&mut self,
+		keychain: &K,
+		sec_key: &mut SecretKey,
+		use_test_rng: bool,
+	) -> Result<(), Error>
+	where
+		K: Keychain,
+	{
+		// Whoever does this first generates the offset
+		if self.tx.offset == BlindingFactor::zero() {
+			self.generate_offset(keychain, sec_key, use_test_rng)?;
+		}
+		Ok(())
+	}
+
 	/// Completes callers part of round 1, adding public key info
 	/// to the slate
 	pub fn fill_round_1<K>(
@@ -306,10 +414,7 @@ impl Slate {
 	where
 		K: Keychain,
 	{
-		// Whoever does this first generates the offset
-		if self.tx.offset == BlindingFactor::zero() {
-			self.generate_offset(keychain, sec_key, use_test_rng)?;
-		}
+		self.fill_offset(keychain, sec_key, use_test_rng)?;
 		self.add_participant_info(
 			keychain,
 			&sec_key,
@@ -341,6 +446,44 @@ impl Slate {
 		Ok(msg)
 	}
 
+	/// Whether the kernel message is fixed, so a participant's nonce can be
+	/// derived from it with [derive_nonce](#method.derive_nonce). It is once
+	/// the fee is set, which the payer of an invoice only does after the
+	/// issuer has its nonce
+	pub fn nonce_derivable(&self) -> bool {
+		self.fee != 0
+	}
+
+	/// The participant's secret nonce for signing the kernel, derived from
+	/// its secret key and the kernel message rather than drawn from the RNG.
+	///
+	/// The slate id, the participant id, the kernel offset and every other
+	/// participant's public nonce and excess are additional data, so a
+	/// counterparty replaying the slate with a nonce of its own gets a
+	/// different nonce from ours, rather than two signatures with the same
+	/// one that give away the key. So it's derived once the offset has been
+	/// taken off the key, see [fill_offset](#method.fill_offset)
+	pub fn derive_nonce(
+		&self,
+		secp: &secp::Secp256k1,
+		sec_key: &SecretKey,
+		participant_id: usize,
+	) -> Result<SecretKey, Error> {
+		let mut extra = self.id.as_bytes().to_vec();
+		extra.extend_from_slice(&(participant_id as u64).to_be_bytes());
+		extra.extend_from_slice(self.tx.offset.as_ref());
+		for p in self
+			.participant_data
+			.iter()
+			.filter(|p| p.id != participant_id as u64)
+		{
+			extra.extend_from_slice(&p.id.to_be_bytes());
+			extra.extend_from_slice(&p.public_nonce.serialize_vec(secp, true));
+			extra.extend_from_slice(&p.public_blind_excess.serialize_vec(secp, true));
+		}
+		nonce::deterministic_nonce(secp, sec_key, &self.msg_to_sign()?, &extra)
+	}
+
 	/// Completes caller's part of round 2, completing signatures
 	pub fn fill_round_2<K>(
 		&mut self,
@@ -508,9 +651,7 @@ impl Slate {
 		// and subtract it from the blind_sum so we create
 		// the aggsig context with the "split" key
 		self.tx.offset = match use_test_rng {
-			false => {
-				BlindingFactor::from_secret_key(SecretKey::new(&keychain.secp(), &mut thread_rng()))
-			}
+			false => BlindingFactor::from_secret_key(random_secret_key(keychain.secp())?),
 			true => {
 				// allow for consistent test results
 				let mut test_rng = StepRng::new(1234567890u64, 1);
@@ -527,8 +668,52 @@ impl Slate {
 		Ok(())
 	}
 
+	/// Checks the participant data and transaction kernel are consistent with
+	/// the slate's own fields, without checking any signatures
+	pub fn verify_structure(&self) -> Result<(), Error> {
+		let invalid = |reason: String| -> Error { ErrorKind::InvalidSlate(reason).into() };
+		if self.num_participants == 0 {
+			return Err(invalid("no participants expected".to_owned()));
+		}
+		if self.participant_data.len() > self.num_participants {
+			return Err(invalid(format!(
+				"{} participants for a {} party transaction",
+				self.participant_data.len(),
+				self.num_participants
+			)));
+		}
+		let mut ids = vec![];
+		for p in self.participant_data.iter() {
+			if p.id as usize >= self.num_participants || ids.contains(&p.id) {
+				return Err(invalid(format!("unexpected participant id {}", p.id)));
+			}
+			ids.push(p.id);
+		}
+		if self.amount.is_zero() {
+			return Err(invalid("zero amount".to_owned()));
+		}
+		if let Some(h) = self.ttl_cutoff_height {
+			if h < self.height {
+				return Err(invalid(format!(
+					"TTL cutoff height {} is before the slate height {}",
+					h, self.height
+				)));
+			}
+		}
+		match self.tx.kernels().len() {
+			1 => {}
+			n => return Err(invalid(format!("{} kernels, expected 1", n))),
+		}
+		if self.tx.kernels()[0].features != self.kernel_features() {
+			return Err(invalid(
+				"kernel fee or lock height doesn't match the slate".to_owned(),
+			));
+		}
+		Ok(())
+	}
+
 	/// Checks the fees in the transaction in the given slate are valid
-	fn check_fees(&self) -> Result<(), Error> {
+	pub fn check_fees(&self) -> Result<(), Error> {
 		// double check the fee amount included in the partial tx
 		// we don't necessarily want to just trust the sender
 		// we could just overwrite the fee here (but we won't) due to the sig
@@ -545,11 +730,11 @@ impl Slate {
 			))?;
 		}
 
-		if fee > self.amount + self.fee {
+		if fee > self.amount.freemans() + self.fee {
 			let reason = format!(
 				"Rejected the transfer because transaction fee ({}) exceeds received amount ({}).",
 				amount_to_hr_string(fee, false),
-				amount_to_hr_string(self.amount + self.fee, false)
+				amount_to_hr_string(self.amount.freemans() + self.fee, false)
 			);
 			info!("{}", reason);
 			return Err(ErrorKind::Fee(reason.to_string()))?;
@@ -559,7 +744,7 @@ impl Slate {
 	}
 
 	/// Verifies all of the partial signatures in the Slate are valid
-	fn verify_part_sigs(&self, secp: &secp::Secp256k1) -> Result<(), Error> {
+	pub fn verify_part_sigs(&self, secp: &secp::Secp256k1) -> Result<(), Error> {
 		// collect public nonces
 		for p in self.participant_data.iter() {
 			if p.is_complete() {
@@ -730,6 +915,12 @@ impl Serialize for Slate {
 				let v2 = SlateV2::from(&v3);
 				v2.serialize(serializer)
 			}
+			// only from slates received from old wallets
+			0 | 1 => {
+				let v2 = SlateV2::from(&v3);
+				let v1 = SlateV1::from(&v2);
+				v1.serialize(serializer)
+			}
 			v => Err(S::Error::custom(format!("Unknown slate version {}", v))),
 		}
 	}
@@ -783,6 +974,8 @@ impl From<Slate> for SlateV3 {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee,
+			fee_breakdown,
 		} = slate;
 		let participant_data = map_vec!(participant_data, |data| ParticipantDataV3::from(data));
 		let version_info = VersionCompatInfoV3::from(&version_info);
@@ -791,11 +984,12 @@ impl From<Slate> for SlateV3 {
 			None => None,
 		};
 		let tx = TransactionV3::from(tx);
+		let fee_breakdown = fee_breakdown.as_ref().map(FeeBreakdownV3::from);
 		SlateV3 {
 			num_participants,
 			id,
 			tx,
-			amount,
+			amount: amount.freemans(),
 			fee,
 			height,
 			lock_height,
@@ -803,6 +997,8 @@ impl From<Slate> for SlateV3 {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee,
+			fee_breakdown,
 		}
 	}
 }
@@ -821,6 +1017,8 @@ impl From<&Slate> for SlateV3 {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee,
+			fee_breakdown,
 		} = slate;
 		let num_participants = *num_participants;
 		let id = *id;
@@ -830,17 +1028,19 @@ impl From<&Slate> for SlateV3 {
 		let height = *height;
 		let lock_height = *lock_height;
 		let ttl_cutoff_height = *ttl_cutoff_height;
+		let receiver_fee = *receiver_fee;
 		let participant_data = map_vec!(participant_data, |data| ParticipantDataV3::from(data));
 		let version_info = VersionCompatInfoV3::from(version_info);
 		let payment_proof = match payment_proof {
 			Some(p) => Some(PaymentInfoV3::from(p)),
 			None => None,
 		};
+		let fee_breakdown = fee_breakdown.as_ref().map(FeeBreakdownV3::from);
 		SlateV3 {
 			num_participants,
 			id,
 			tx,
-			amount,
+			amount: amount.freemans(),
 			fee,
 			height,
 			lock_height,
@@ -848,6 +1048,8 @@ impl From<&Slate> for SlateV3 {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee,
+			fee_breakdown,
 		}
 	}
 }
@@ -915,6 +1117,22 @@ impl From<&PaymentInfo> for PaymentInfoV3 {
 	}
 }
 
+impl From<&FeeBreakdown> for FeeBreakdownV3 {
+	fn from(data: &FeeBreakdown) -> FeeBreakdownV3 {
+		FeeBreakdownV3 {
+			num_inputs: data.num_inputs,
+			num_outputs: data.num_outputs,
+			num_kernels: data.num_kernels,
+			input_weight: data.input_weight,
+			output_weight: data.output_weight,
+			kernel_weight: data.kernel_weight,
+			weight: data.weight,
+			fee_base: data.fee_base,
+			fee: data.fee,
+		}
+	}
+}
+
 impl From<Transaction> for TransactionV3 {
 	fn from(tx: Transaction) -> TransactionV3 {
 		let Transaction { offset, body } = tx;
@@ -1007,6 +1225,8 @@ impl From<SlateV3> for Slate {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee,
+			fee_breakdown,
 		} = slate;
 		let participant_data = map_vec!(participant_data, |data| ParticipantData::from(data));
 		let version_info = VersionCompatInfo::from(&version_info);
@@ -1015,11 +1235,12 @@ impl From<SlateV3> for Slate {
 			None => None,
 		};
 		let tx = Transaction::from(tx);
+		let fee_breakdown = fee_breakdown.as_ref().map(FeeBreakdown::from);
 		Slate {
 			num_participants,
 			id,
 			tx,
-			amount,
+			amount: Amount::from_freemans(amount),
 			fee,
 			height,
 			lock_height,
@@ -1027,6 +1248,8 @@ impl From<SlateV3> for Slate {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee,
+			fee_breakdown,
 		}
 	}
 }
@@ -1094,6 +1317,22 @@ impl From<&PaymentInfoV3> for PaymentInfo {
 	}
 }
 
+impl From<&FeeBreakdownV3> for FeeBreakdown {
+	fn from(data: &FeeBreakdownV3) -> FeeBreakdown {
+		FeeBreakdown {
+			num_inputs: data.num_inputs,
+			num_outputs: data.num_outputs,
+			num_kernels: data.num_kernels,
+			input_weight: data.input_weight,
+			output_weight: data.output_weight,
+			kernel_weight: data.kernel_weight,
+			weight: data.weight,
+			fee_base: data.fee_base,
+			fee: data.fee,
+		}
+	}
+}
+
 impl From<TransactionV3> for Transaction {
 	fn from(tx: TransactionV3) -> Transaction {
 		let TransactionV3 { offset, body } = tx;
@@ -14,16 +14,19 @@
 
 //! This module contains old slate versions and conversions to the newest slate version
 //! Used for serialization and deserialization of slates in a backwards compatible way.
-//! Versions earlier than V2 are removed for the 2.0.0 release, but versioning code
-//! remains for future needs
+//! Versions earlier than V2 were removed for the 2.0.0 release. V0 and V1 are back
+//! only for receiving from old wallets, where enabled
 
 use crate::slate::Slate;
 use crate::slate_versions::v2::{CoinbaseV2, SlateV2};
 use crate::slate_versions::v3::{CoinbaseV3, SlateV3};
 use crate::types::CbData;
+use strum::IntoEnumIterator;
 
 pub mod ser;
 
+#[allow(missing_docs)]
+pub mod v1;
 #[allow(missing_docs)]
 pub mod v2;
 #[allow(missing_docs)]
@@ -44,7 +47,23 @@ pub enum SlateVersion {
 	V2,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl SlateVersion {
+	/// Number of the version, as in a slate's `version_info`
+	pub fn number(&self) -> u16 {
+		match *self {
+			SlateVersion::V3 => 3,
+			SlateVersion::V2 => 2,
+		}
+	}
+
+	/// The newest version supported by both this wallet and another, which
+	/// supports `theirs`, that isn't newer than `max`
+	pub fn highest_common(theirs: &[SlateVersion], max: u16) -> Option<SlateVersion> {
+		SlateVersion::iter().find(|v| v.number() <= max && theirs.contains(v))
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 /// Versions are ordered newest to oldest so serde attempts to
 /// deserialize newer versions first, then falls back to older versions.
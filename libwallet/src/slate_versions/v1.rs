@@ -0,0 +1,89 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains V1 of the slate (epic-wallet 1.0.1), and V0 (epic-wallet 1.0.0),
+//! which is V1 without the version field. Only read from, and answered to,
+//! wallets receiving from old senders, see `Slate::deserialize_legacy`
+//! Changes from V0:
+//! * Addition of a version field
+
+use crate::epic_core::core::transaction::OutputFeatures;
+use crate::epic_keychain::BlindingFactor;
+use crate::epic_util::secp;
+use crate::epic_util::secp::key::PublicKey;
+use crate::epic_util::secp::pedersen::{Commitment, RangeProof};
+use crate::epic_util::secp::Signature;
+use crate::slate::CompatKernelFeatures;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SlateV1 {
+	pub num_participants: usize,
+	pub id: Uuid,
+	pub tx: TransactionV1,
+	pub amount: u64,
+	pub fee: u64,
+	pub height: u64,
+	pub lock_height: u64,
+	pub participant_data: Vec<ParticipantDataV1>,
+	/// 1, or missing from V0 slates
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub version: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParticipantDataV1 {
+	pub id: u64,
+	pub public_blind_excess: PublicKey,
+	pub public_nonce: PublicKey,
+	pub part_sig: Option<Signature>,
+	pub message: Option<String>,
+	pub message_sig: Option<Signature>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionV1 {
+	pub offset: BlindingFactor,
+	pub body: TransactionBodyV1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionBodyV1 {
+	pub inputs: Vec<InputV1>,
+	pub outputs: Vec<OutputV1>,
+	pub kernels: Vec<TxKernelV1>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputV1 {
+	pub features: OutputFeatures,
+	pub commit: Commitment,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct OutputV1 {
+	pub features: OutputFeatures,
+	pub commit: Commitment,
+	pub proof: RangeProof,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TxKernelV1 {
+	pub features: CompatKernelFeatures,
+	pub fee: u64,
+	pub lock_height: u64,
+	pub excess: Commitment,
+	pub excess_sig: secp::Signature,
+}
@@ -37,13 +37,18 @@
 
 use crate::epic_core::core::transaction::OutputFeatures;
 use crate::epic_core::libtx::secp_ser;
+use crate::epic_core::map_vec;
 use crate::epic_keychain::{BlindingFactor, Identifier};
 use crate::epic_util::secp;
 use crate::epic_util::secp::key::PublicKey;
 use crate::epic_util::secp::pedersen::{Commitment, RangeProof};
 use crate::epic_util::secp::Signature;
 use crate::slate::CompatKernelFeatures;
+use crate::slate_versions::v1::{
+	InputV1, OutputV1, ParticipantDataV1, SlateV1, TransactionBodyV1, TransactionV1, TxKernelV1,
+};
 use crate::slate_versions::v3::{OutputV3, TxKernelV3};
+use crate::slate_versions::EPIC_BLOCK_HEADER_VERSION;
 use crate::types::CbData;
 use uuid::Uuid;
 
@@ -212,3 +217,180 @@ impl From<CbData> for CoinbaseV2 {
 		}
 	}
 }
+
+// V1 to V2 For Slate
+impl From<SlateV1> for SlateV2 {
+	fn from(slate: SlateV1) -> SlateV2 {
+		let SlateV1 {
+			num_participants,
+			id,
+			tx,
+			amount,
+			fee,
+			height,
+			lock_height,
+			participant_data,
+			version,
+		} = slate;
+		let participant_data = map_vec!(participant_data, |data| ParticipantDataV2::from(data));
+		// V1 slates predate the block header version, their transactions
+		// are built the same way
+		let version_info = VersionCompatInfoV2 {
+			version: 2,
+			orig_version: version.map_or(0, |_| 1),
+			block_header_version: EPIC_BLOCK_HEADER_VERSION,
+		};
+		let tx = TransactionV2::from(tx);
+		SlateV2 {
+			version_info,
+			num_participants,
+			id,
+			tx,
+			amount,
+			fee,
+			height,
+			lock_height,
+			participant_data,
+		}
+	}
+}
+
+impl From<&ParticipantDataV1> for ParticipantDataV2 {
+	fn from(data: &ParticipantDataV1) -> ParticipantDataV2 {
+		let ParticipantDataV1 {
+			id,
+			public_blind_excess,
+			public_nonce,
+			part_sig,
+			message,
+			message_sig,
+		} = data;
+		ParticipantDataV2 {
+			id: *id,
+			public_blind_excess: *public_blind_excess,
+			public_nonce: *public_nonce,
+			part_sig: *part_sig,
+			message: message.clone(),
+			message_sig: *message_sig,
+		}
+	}
+}
+
+impl From<TransactionV1> for TransactionV2 {
+	fn from(tx: TransactionV1) -> TransactionV2 {
+		let TransactionV1 { offset, body } = tx;
+		let TransactionBodyV1 {
+			inputs,
+			outputs,
+			kernels,
+		} = body;
+		let inputs = map_vec!(inputs, |inp| InputV2 {
+			features: inp.features,
+			commit: inp.commit,
+		});
+		let outputs = map_vec!(outputs, |out| OutputV2 {
+			features: out.features,
+			commit: out.commit,
+			proof: out.proof,
+		});
+		let kernels = map_vec!(kernels, |kern| TxKernelV2 {
+			features: kern.features,
+			fee: kern.fee,
+			lock_height: kern.lock_height,
+			excess: kern.excess,
+			excess_sig: kern.excess_sig,
+		});
+		TransactionV2 {
+			offset,
+			body: TransactionBodyV2 {
+				inputs,
+				outputs,
+				kernels,
+			},
+		}
+	}
+}
+
+// V2 to V1, answering old senders in the version they sent
+impl From<&SlateV2> for SlateV1 {
+	fn from(slate: &SlateV2) -> SlateV1 {
+		let SlateV2 {
+			version_info,
+			num_participants,
+			id,
+			tx,
+			amount,
+			fee,
+			height,
+			lock_height,
+			participant_data,
+		} = slate;
+		let participant_data = map_vec!(participant_data, |data| ParticipantDataV1::from(data));
+		let version = match version_info.orig_version {
+			0 => None,
+			_ => Some(1),
+		};
+		SlateV1 {
+			num_participants: *num_participants,
+			id: *id,
+			tx: TransactionV1::from(tx),
+			amount: *amount,
+			fee: *fee,
+			height: *height,
+			lock_height: *lock_height,
+			participant_data,
+			version,
+		}
+	}
+}
+
+impl From<&ParticipantDataV2> for ParticipantDataV1 {
+	fn from(data: &ParticipantDataV2) -> ParticipantDataV1 {
+		let ParticipantDataV2 {
+			id,
+			public_blind_excess,
+			public_nonce,
+			part_sig,
+			message,
+			message_sig,
+		} = data;
+		ParticipantDataV1 {
+			id: *id,
+			public_blind_excess: *public_blind_excess,
+			public_nonce: *public_nonce,
+			part_sig: *part_sig,
+			message: message.clone(),
+			message_sig: *message_sig,
+		}
+	}
+}
+
+impl From<&TransactionV2> for TransactionV1 {
+	fn from(tx: &TransactionV2) -> TransactionV1 {
+		let TransactionV2 { offset, body } = tx;
+		let inputs = map_vec!(body.inputs, |inp| InputV1 {
+			features: inp.features,
+			commit: inp.commit,
+		});
+		let outputs = map_vec!(body.outputs, |out| OutputV1 {
+			features: out.features,
+			commit: out.commit,
+			proof: out.proof,
+		});
+		let kernels = map_vec!(body.kernels, |kern| TxKernelV1 {
+			features: kern.features,
+			fee: kern.fee,
+			lock_height: kern.lock_height,
+			excess: kern.excess,
+			excess_sig: kern.excess_sig,
+		});
+		TransactionV1 {
+			offset: offset.clone(),
+			body: TransactionBodyV1 {
+				inputs,
+				outputs,
+				kernels,
+			},
+		}
+	}
+}
@@ -16,6 +16,8 @@
 //! Changes from V2:
 //! * Addition of payment_proof (PaymentInfo struct)
 //! * Addition of a u64 ttl_cutoff_height field
+//! * Addition of an optional u64 receiver_fee field
+//! * Addition of an optional fee_breakdown field (FeeBreakdown struct)
 
 use crate::epic_core::core::transaction::OutputFeatures;
 use crate::epic_core::libtx::secp_ser;
@@ -71,6 +73,16 @@ pub struct SlateV3 {
 	/// Payment Proof
 	#[serde(default = "default_payment_none")]
 	pub payment_proof: Option<PaymentInfoV3>,
+	/// Part of the fee paid by the receiver, out of an input it adds to the
+	/// transaction
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub receiver_fee: Option<u64>,
+	/// How the sender worked out the fee
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fee_breakdown: Option<FeeBreakdownV3>,
 }
 
 fn default_payment_none() -> Option<PaymentInfoV3> {
@@ -118,6 +130,21 @@ pub struct PaymentInfoV3 {
 	pub receiver_signature: Option<DalekSignature>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeBreakdownV3 {
+	pub num_inputs: usize,
+	pub num_outputs: usize,
+	pub num_kernels: usize,
+	pub input_weight: u64,
+	pub output_weight: u64,
+	pub kernel_weight: u64,
+	pub weight: u64,
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub fee_base: u64,
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub fee: u64,
+}
+
 /// A transaction
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransactionV3 {
@@ -238,6 +265,8 @@ impl From<SlateV2> for SlateV3 {
 			participant_data,
 			version_info,
 			payment_proof: None,
+			receiver_fee: None,
+			fee_breakdown: None,
 		}
 	}
 }
@@ -365,6 +394,8 @@ impl From<&SlateV3> for SlateV2 {
 			participant_data,
 			version_info,
 			payment_proof,
+			receiver_fee: _,
+			fee_breakdown: _,
 		} = slate;
 		let num_participants = *num_participants;
 		let id = *id;
@@ -0,0 +1,260 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Filters over the transaction log, evaluated by the wallet so callers
+//! don't have to fetch every entry to find the few they're after.
+//!
+//! A filter is a JSON object with a single field naming a condition, which
+//! may combine others, e.g.
+//!
+//! ```json
+//! {"and": [{"type": "sent"}, {"amount_gt": "100000000"}, {"message": "payroll"}]}
+//! ```
+//!
+//! Filters pinning the slate id or the type of the entries they match are
+//! answered from the wallet's indexes rather than by reading the whole log.
+
+use crate::amount::Amount;
+use crate::epic_core::libtx::secp_ser;
+use crate::fork_import;
+use crate::types::{TxLogEntry, TxLogEntryType};
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A condition on transaction log entries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxFilter {
+	/// Entries matching all of the filters, or any entry if there are none
+	And(Vec<TxFilter>),
+	/// Entries matching any of the filters, or no entry if there are none
+	Or(Vec<TxFilter>),
+	/// Entries not matching the filter
+	Not(Box<TxFilter>),
+	/// Entries of a type, given as in the owner API (`TxSent`) or by a short
+	/// name: `sent`, `received`, `sent_cancelled`, `received_cancelled`,
+	/// `coinbase` or `reverted_coinbase`
+	#[serde(deserialize_with = "tx_type_name")]
+	Type(TxLogEntryType),
+	/// Entries that are, or aren't, confirmed
+	Confirmed(bool),
+	/// Entries of a slate
+	SlateId(Uuid),
+	/// Entries moving more than an amount, in nanoepic. An entry moves the
+	/// difference between the amounts it credits and debits
+	#[serde(with = "secp_ser::string_or_u64")]
+	AmountGt(u64),
+	/// Entries moving less than an amount, in nanoepic
+	#[serde(with = "secp_ser::string_or_u64")]
+	AmountLt(u64),
+	/// Entries created at or after a time
+	CreatedAfter(DateTime<Utc>),
+	/// Entries created before a time
+	CreatedBefore(DateTime<Utc>),
+	/// Entries confirmed at or above a height
+	#[serde(with = "secp_ser::string_or_u64")]
+	MinHeight(u64),
+	/// Entries confirmed at or below a height
+	#[serde(with = "secp_ser::string_or_u64")]
+	MaxHeight(u64),
+	/// Entries with a participant message containing the text, ignoring case
+	Message(String),
+	/// Entries received through the listener path with the deposit tag
+	DepositTag(String),
+}
+
+impl TxFilter {
+	/// Whether the entry matches the filter
+	pub fn matches(&self, tx: &TxLogEntry) -> bool {
+		match self {
+			TxFilter::And(fs) => fs.iter().all(|f| f.matches(tx)),
+			TxFilter::Or(fs) => fs.iter().any(|f| f.matches(tx)),
+			TxFilter::Not(f) => !f.matches(tx),
+			TxFilter::Type(t) => tx.tx_type == *t,
+			TxFilter::Confirmed(c) => tx.confirmed == *c,
+			TxFilter::SlateId(s) => tx.tx_slate_id == Some(*s),
+			TxFilter::AmountGt(a) => amount(tx) > *a,
+			TxFilter::AmountLt(a) => amount(tx) < *a,
+			TxFilter::CreatedAfter(t) => tx.creation_ts >= *t,
+			TxFilter::CreatedBefore(t) => tx.creation_ts < *t,
+			TxFilter::MinHeight(h) => tx.confirmation_height.map_or(false, |c| c >= *h),
+			TxFilter::MaxHeight(h) => tx.confirmation_height.map_or(false, |c| c <= *h),
+			TxFilter::Message(m) => {
+				let m = m.to_lowercase();
+				tx.messages.as_ref().map_or(false, |pm| {
+					pm.messages
+						.iter()
+						.filter_map(|p| p.message.as_ref())
+						.any(|text| text.to_lowercase().contains(&m))
+				})
+			}
+			TxFilter::DepositTag(d) => tx.deposit_tag.as_ref() == Some(d),
+		}
+	}
+
+	/// The slate ids an entry must have to match the filter, if it limits
+	/// them, so the entries can be looked up in the slate id index
+	pub fn slate_ids(&self) -> Option<Vec<Uuid>> {
+		self.pinned(&|f| match f {
+			TxFilter::SlateId(s) => Some(vec![*s]),
+			_ => None,
+		})
+	}
+
+	/// The types an entry must have to match the filter, if it limits them,
+	/// so the entries can be looked up in the type index
+	pub fn tx_types(&self) -> Option<Vec<TxLogEntryType>> {
+		self.pinned(&|f| match f {
+			TxFilter::Type(t) => Some(vec![t.clone()]),
+			_ => None,
+		})
+	}
+
+	// the values a condition pins, through the filters combining it. Any
+	// member of an `and` pinning them will do, while every member of an
+	// `or` has to
+	fn pinned<T>(&self, leaf: &dyn Fn(&TxFilter) -> Option<Vec<T>>) -> Option<Vec<T>> {
+		match self {
+			TxFilter::And(fs) => fs.iter().find_map(|f| f.pinned(leaf)),
+			TxFilter::Or(fs) => {
+				let mut values = vec![];
+				for f in fs {
+					values.extend(f.pinned(leaf)?);
+				}
+				Some(values)
+			}
+			TxFilter::Not(_) => None,
+			f => leaf(f),
+		}
+	}
+}
+
+/// Reads a filter from its JSON form
+impl FromStr for TxFilter {
+	type Err = serde_json::Error;
+
+	fn from_str(s: &str) -> Result<TxFilter, serde_json::Error> {
+		serde_json::from_str(s)
+	}
+}
+
+/// Amount an entry moves, the difference between what it credits and debits
+fn amount(tx: &TxLogEntry) -> Amount {
+	match tx.amount_credited > tx.amount_debited {
+		true => tx.amount_credited - tx.amount_debited,
+		false => tx.amount_debited - tx.amount_credited,
+	}
+}
+
+fn tx_type_name<'de, D>(deserializer: D) -> Result<TxLogEntryType, D::Error>
+where
+	D: serde::de::Deserializer<'de>,
+{
+	let s: String = serde::de::Deserialize::deserialize(deserializer)?;
+	let short = s.to_lowercase().replace(|c: char| !c.is_alphanumeric(), "");
+	match short.as_str() {
+		"sent" => Ok(TxLogEntryType::TxSent),
+		"received" => Ok(TxLogEntryType::TxReceived),
+		"sentcancelled" => Ok(TxLogEntryType::TxSentCancelled),
+		"receivedcancelled" => Ok(TxLogEntryType::TxReceivedCancelled),
+		"coinbase" => Ok(TxLogEntryType::ConfirmedCoinbase),
+		_ => fork_import::parse_tx_type(&s).map_err(serde::de::Error::custom),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_keychain::{ExtKeychain, Keychain};
+	use crate::epic_util::secp::key::{PublicKey, SecretKey};
+	use crate::slate::{ParticipantMessageData, ParticipantMessages};
+
+	fn tx(id: u32, tx_type: TxLogEntryType, credited: u64, debited: u64) -> TxLogEntry {
+		let mut t = TxLogEntry::new(ExtKeychain::derive_key_id(2, 0, 0, 0, 0), tx_type, id);
+		t.amount_credited = Amount::from_freemans(credited);
+		t.amount_debited = Amount::from_freemans(debited);
+		t
+	}
+
+	#[test]
+	fn parse_filters() {
+		let f: TxFilter =
+			r#"{"and": [{"type": "sent"}, {"amount_gt": "1000"}, {"not": {"confirmed": true}}]}"#
+				.parse()
+				.unwrap();
+		assert_eq!(
+			f,
+			TxFilter::And(vec![
+				TxFilter::Type(TxLogEntryType::TxSent),
+				TxFilter::AmountGt(1000),
+				TxFilter::Not(Box::new(TxFilter::Confirmed(true))),
+			])
+		);
+		let f: TxFilter =
+			serde_json::from_str(r#"{"or": [{"type": "TxReceived"}, {"type": "coinbase"}]}"#)
+				.unwrap();
+		assert_eq!(
+			f.tx_types(),
+			Some(vec![
+				TxLogEntryType::TxReceived,
+				TxLogEntryType::ConfirmedCoinbase
+			])
+		);
+		assert!(serde_json::from_str::<TxFilter>(r#"{"type": "mined"}"#).is_err());
+		assert!(serde_json::from_str::<TxFilter>(r#"{"label": "payroll"}"#).is_err());
+	}
+
+	#[test]
+	fn match_entries() {
+		let keychain = ExtKeychain::from_random_seed(false).unwrap();
+		let sec_key = SecretKey::from_slice(keychain.secp(), &[1; 32]).unwrap();
+		let mut sent = tx(0, TxLogEntryType::TxSent, 300, 1300);
+		sent.messages = Some(ParticipantMessages {
+			messages: vec![ParticipantMessageData {
+				id: 0,
+				public_key: PublicKey::from_secret_key(keychain.secp(), &sec_key).unwrap(),
+				message: Some("March Payroll".to_owned()),
+				message_sig: None,
+			}],
+		});
+		let received = tx(1, TxLogEntryType::TxReceived, 500, 0);
+
+		let payroll = TxFilter::And(vec![
+			TxFilter::Type(TxLogEntryType::TxSent),
+			TxFilter::AmountGt(999),
+			TxFilter::Message("payroll".to_owned()),
+		]);
+		assert!(payroll.matches(&sent));
+		assert!(!payroll.matches(&received));
+		assert!(!TxFilter::AmountGt(1000).matches(&sent));
+		assert!(TxFilter::AmountLt(501).matches(&received));
+		assert!(TxFilter::And(vec![]).matches(&sent));
+		assert!(!TxFilter::Or(vec![]).matches(&sent));
+		assert!(!TxFilter::MinHeight(0).matches(&received));
+
+		// only conditions every match has to meet pin the index lookup
+		assert_eq!(payroll.tx_types(), Some(vec![TxLogEntryType::TxSent]));
+		let either = TxFilter::Or(vec![
+			TxFilter::Type(TxLogEntryType::TxSent),
+			TxFilter::Confirmed(true),
+		]);
+		assert_eq!(either.tx_types(), None);
+		assert_eq!(
+			TxFilter::Not(Box::new(TxFilter::Type(TxLogEntryType::TxSent))).tx_types(),
+			None
+		);
+		assert_eq!(payroll.slate_ids(), None);
+	}
+}
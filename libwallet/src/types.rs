@@ -15,28 +15,83 @@
 //! Types and traits that should be provided by a wallet
 //! implementation
 
-use crate::config::{TorConfig, WalletConfig};
+use crate::amount::Amount;
+use crate::api_impl::types::InitTxArgs;
+use crate::blake2::blake2b::blake2b;
+use crate::config::{SeedKdfParams, SeedStorage, TorConfig, WalletConfig};
+use crate::epic_core::core::feijoada::PoWType;
 use crate::epic_core::core::hash::Hash;
 use crate::epic_core::core::{Output, Transaction, TxKernel};
-use crate::epic_core::libtx::{aggsig, secp_ser};
+use crate::epic_core::libtx::secp_ser;
 use crate::epic_core::{global, ser};
 use crate::epic_keychain::{Identifier, Keychain};
 use crate::epic_util::logger::LoggingConfig;
 use crate::epic_util::secp::key::{PublicKey, SecretKey};
 use crate::epic_util::secp::{self, pedersen, Secp256k1};
-use crate::epic_util::ZeroingString;
+use crate::epic_util::{from_hex, to_hex, ZeroingString};
 use crate::error::{Error, ErrorKind};
-use crate::slate::ParticipantMessages;
+use crate::events::EventBus;
+use crate::slate::{FeeBreakdown, ParticipantMessages};
 use crate::slate_versions::ser as dalek_ser;
+use crate::slate_versions::VersionedSlate;
 use chrono::prelude::*;
 use ed25519_dalek::PublicKey as DalekPublicKey;
 use ed25519_dalek::Signature as DalekSignature;
+use epic_wallet_util::{random_secret_key, zeroize_secret_key};
 use failure::ResultExt;
 use serde;
 use serde_json;
 use std::collections::HashMap;
 use std::fmt;
 use uuid::Uuid;
+use zeroize::Zeroize;
+
+/// Default number of blocks after which an unconfirmed coinbase output is
+/// removed from the wallet
+pub const DEFAULT_COINBASE_CLEANUP_WINDOW: u64 = 50;
+
+/// Number of seconds the wallet's clock may differ from the timestamp of the
+/// node's chain tip before the clocks are reported as skewed. Blocks are
+/// usually a minute apart and can't be stamped more than a few minutes in
+/// the future, so a larger gap means one of the clocks is wrong or the node
+/// hasn't seen a recent block.
+pub const CLOCK_SKEW_WARNING_SECS: i64 = 15 * 60;
+
+/// Number of seconds after a send is created during which another send of the
+/// same amount to the same destination is taken as an accidental repeat, even
+/// once the first is confirmed. Retries by a client that didn't see the first
+/// send succeed usually come within minutes
+pub const DUPLICATE_PAYMENT_WINDOW_SECS: i64 = 60 * 60;
+
+/// Number of times a queued job is tried before it's set aside as a dead
+/// letter, to be retried or deleted by hand
+pub const JOB_MAX_ATTEMPTS: u32 = 10;
+
+/// Seconds before a failed job is first tried again. The wait doubles with
+/// each failure, up to an hour
+pub const JOB_RETRY_SECS: i64 = 30;
+
+/// Sync status reported by a node that's caught up with the chain. Outputs
+/// missing from a node reporting anything else may just not have reached it
+/// yet, so aren't taken as spent
+pub const NODE_SYNCED_STATUS: &str = "no_sync";
+
+/// Warning that must be accepted before an account's public key material or
+/// expected commitments are exported. None of it can spend funds, but it lets
+/// whoever holds it pick the account's outputs out of the chain
+pub const ACCOUNT_KEY_EXPORT_WARNING: &str =
+	"the export lets anyone holding it identify and link every output of the account, \
+	 only share it with a trusted auditor and pass accept_warning to go ahead";
+
+/// Confirmations an output needs before the receiver contributes it as an
+/// input to a transaction it receives, unless a reorg-safe depth is configured
+pub const RECEIVER_INPUT_MIN_CONFIRMATIONS: u64 = 10;
+
+/// Most outputs contributed as inputs to received transactions that can be
+/// locked at once, waiting on their senders to finalize. Anyone can send a
+/// slate asking for a contribution, so without a cap senders that never
+/// finalize could lock every output of the wallet
+pub const MAX_PENDING_RECEIVER_INPUTS: usize = 5;
 
 /// Combined trait to allow dynamic wallet dispatch
 pub trait WalletInst<'a, L, C, K>: Send + Sync
@@ -63,6 +118,47 @@ where
 	/// default is assumed to be ~/.epic/main/wallet_data (or floonet equivalent)
 	fn get_top_level_directory(&self) -> Result<String, Error>;
 
+	/// Sets the number of blocks after which an unconfirmed coinbase output is
+	/// removed from the wallet, for the open wallet and any opened later.
+	/// 0 disables the cleanup
+	fn set_coinbase_cleanup_window(&mut self, blocks: u64) -> Result<(), Error>;
+
+	/// Sets whether an output missing from the node is only marked spent once
+	/// two refreshes in a row find it missing, for the open wallet and any
+	/// opened later
+	fn set_confirm_missing_outputs(&mut self, confirm: bool) -> Result<(), Error>;
+
+	/// Sets whether the wallet database is backed up before it's migrated to
+	/// a newer schema version when the wallet is opened
+	fn set_backup_before_migrate(&mut self, backup: bool) -> Result<(), Error>;
+
+	/// Sets where the wallet seed is kept, for wallets created, opened or
+	/// recovered from then on
+	fn set_seed_storage(&mut self, storage: SeedStorage) -> Result<(), Error>;
+
+	/// Sets the costs of deriving the key the wallet seed is encrypted with
+	/// from the password, for seeds stored from then on. A seed stored with
+	/// other costs is stored again with these when its wallet is opened
+	fn set_seed_kdf(&mut self, params: SeedKdfParams) -> Result<(), Error>;
+
+	/// Sets whether wallets opened from then on are opened read-only, as a
+	/// replica of a wallet another process writes to. Such a wallet is never
+	/// migrated, and refuses all writes
+	fn set_read_only(&mut self, read_only: bool) -> Result<(), Error>;
+
+	/// Events of the open wallet and any opened later. Subscriptions last
+	/// across the wallet being closed and opened again
+	fn events(&self) -> EventBus;
+
+	/// Bring the wallet database up to the current schema version, backing it
+	/// up first if configured to. With `dry_run`, nothing is written and the
+	/// migrations that would be applied are only reported
+	fn migrate_wallet(
+		&mut self,
+		name: Option<&str>,
+		dry_run: bool,
+	) -> Result<MigrationReport, Error>;
+
 	/// Output a epic-wallet.toml file into the current top-level system wallet directory
 	fn create_config(
 		&self,
@@ -161,13 +257,21 @@ where
 	/// Return the client being used to communicate with the node
 	fn w2n_client(&mut self) -> &mut C;
 
-	/// return the commit for caching if allowed, none otherwise
+	/// return the hex encoded commit of an output, to be stored alongside it
 	fn calc_commit_for_cache(
 		&mut self,
 		keychain_mask: Option<&SecretKey>,
 		amount: u64,
 		id: &Identifier,
-	) -> Result<Option<String>, Error>;
+	) -> Result<String, Error>;
+
+	/// Calculate and store the commit of every output, current or in the output
+	/// history table, that was saved without one. Returns the number of
+	/// outputs updated
+	fn backfill_output_commits(
+		&mut self,
+		keychain_mask: Option<&SecretKey>,
+	) -> Result<usize, Error>;
 
 	/// Set parent key id by stored account name
 	fn set_parent_key_id_by_name(&mut self, label: &str) -> Result<(), Error>;
@@ -179,15 +283,57 @@ where
 	/// return the parent path
 	fn parent_key_id(&mut self) -> Identifier;
 
+	/// Set the number of blocks after which an unconfirmed coinbase output is
+	/// removed from the wallet. 0 disables the cleanup
+	fn set_coinbase_cleanup_window(&mut self, blocks: u64);
+
+	/// Number of blocks after which an unconfirmed coinbase output is removed
+	/// from the wallet
+	fn coinbase_cleanup_window(&self) -> u64;
+
+	/// Set whether an output missing from the node is only marked spent once
+	/// two refreshes in a row find it missing
+	fn set_confirm_missing_outputs(&mut self, confirm: bool);
+
+	/// Whether an output missing from the node is only marked spent once two
+	/// refreshes in a row find it missing
+	fn confirm_missing_outputs(&self) -> bool;
+
+	/// Set the bus the wallet's events are emitted on
+	fn set_events(&mut self, events: EventBus);
+
+	/// The bus the wallet's events are emitted on
+	fn events(&self) -> &EventBus;
+
 	/// Iterate over all output data stored by the backend
 	fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = OutputData> + 'a>;
 
-	/// Iterate over all outputs available in the output history table
+	/// Iterate over all outputs available in the output history table, in
+	/// the order they were recorded
 	fn history_iter<'a>(&'a self) -> Box<dyn Iterator<Item = OutputData> + 'a>;
 
+	/// Iterate over output data in a stable order, along with an opaque cursor
+	/// for each output. If `cursor` is given, iteration starts after the output
+	/// it was returned with
+	fn iter_from<'a>(
+		&'a self,
+		cursor: Option<&str>,
+	) -> Result<Box<dyn Iterator<Item = (String, OutputData)> + 'a>, Error>;
+
 	/// Get output data by id
 	fn get(&self, id: &Identifier, mmr_index: &Option<u64>) -> Result<OutputData, Error>;
 
+	/// Get the outputs with the given status, using the status index
+	fn outputs_by_status(&self, status: OutputStatus) -> Result<Vec<OutputData>, Error>;
+
+	/// Get the outputs with a height between `start_height` and `end_height`
+	/// inclusive, in height order, using the height index
+	fn outputs_by_height(
+		&self,
+		start_height: u64,
+		end_height: u64,
+	) -> Result<Vec<OutputData>, Error>;
+
 	/// Get an (Optional) tx log entry by uuid
 	fn get_tx_log_entry(&self, uuid: &Uuid) -> Result<Option<TxLogEntry>, Error>;
 
@@ -202,6 +348,34 @@ where
 	/// Iterate over all output data stored by the backend
 	fn tx_log_iter<'a>(&'a self) -> Box<dyn Iterator<Item = TxLogEntry> + 'a>;
 
+	/// Iterate over the tx log entries of the given account in the order they
+	/// were created, along with an opaque cursor for each entry. If `cursor` is
+	/// given, iteration starts after the entry it was returned with
+	fn tx_log_iter_from<'a>(
+		&'a self,
+		parent_key_id: &Identifier,
+		cursor: Option<&str>,
+	) -> Result<Box<dyn Iterator<Item = (String, TxLogEntry)> + 'a>, Error>;
+
+	/// Get a tx log entry by its id within the given account
+	fn tx_log_entry(
+		&self,
+		parent_key_id: &Identifier,
+		id: u32,
+	) -> Result<Option<TxLogEntry>, Error>;
+
+	/// Get the tx log entries for the given slate id, using the slate id index.
+	/// There is one entry per account involved in the transaction
+	fn tx_log_entries_by_slate_id(&self, slate_id: &Uuid) -> Result<Vec<TxLogEntry>, Error>;
+
+	/// Get the tx log entries of the given account and type, using the type
+	/// index
+	fn tx_log_entries_by_type(
+		&self,
+		parent_key_id: &Identifier,
+		tx_type: &TxLogEntryType,
+	) -> Result<Vec<TxLogEntry>, Error>;
+
 	/// Iterate over all stored account paths
 	fn acct_path_iter<'a>(&'a self) -> Box<dyn Iterator<Item = AcctPathMapping> + 'a>;
 
@@ -237,6 +411,29 @@ where
 
 	/// Flag whether the wallet needs a full UTXO scan on next update attempt
 	fn init_status<'a>(&mut self) -> Result<WalletInitStatus, Error>;
+
+	/// Get the stored result of an earlier call to `method` made with the
+	/// given idempotency key
+	fn get_idempotent_result(
+		&self,
+		method: &str,
+		key: &str,
+	) -> Result<Option<IdempotentResult>, Error>;
+
+	/// Iterate over the balance reservations of an account
+	fn reservation_iter<'a>(
+		&'a self,
+		parent_key_id: &Identifier,
+	) -> Box<dyn Iterator<Item = Reservation> + 'a>;
+
+	/// Iterate over the audit trail of manual changes, oldest first
+	fn audit_iter<'a>(&'a self) -> Box<dyn Iterator<Item = AuditEntry> + 'a>;
+
+	/// Iterate over the sends queued until funds mature
+	fn send_queue_iter<'a>(&'a self) -> Box<dyn Iterator<Item = QueuedSend> + 'a>;
+
+	/// Iterate over the queued jobs, dead letters included
+	fn job_iter<'a>(&'a self) -> Box<dyn Iterator<Item = Job> + 'a>;
 }
 
 /// Batch trait to update the output data backend atomically. Trying to use a
@@ -302,6 +499,13 @@ where
 	/// save a tx log entry
 	fn save_tx_log_entry(&mut self, t: TxLogEntry, parent_id: &Identifier) -> Result<(), Error>;
 
+	/// Get a tx log entry by its id within the given account
+	fn get_tx_log_entry(
+		&self,
+		parent_key_id: &Identifier,
+		id: u32,
+	) -> Result<Option<TxLogEntry>, Error>;
+
 	/// save an account label -> path mapping
 	fn save_acct_path(&mut self, mapping: AcctPathMapping) -> Result<(), Error>;
 
@@ -326,6 +530,30 @@ where
 		participant_id: usize,
 	) -> Result<(), Error>;
 
+	/// Save the result of a call made with an idempotency key
+	fn save_idempotent_result(&mut self, result: IdempotentResult) -> Result<(), Error>;
+
+	/// Save a balance reservation
+	fn save_reservation(&mut self, reservation: &Reservation) -> Result<(), Error>;
+
+	/// Delete a balance reservation
+	fn delete_reservation(&mut self, reservation: &Reservation) -> Result<(), Error>;
+
+	/// Add an entry to the audit trail of manual changes
+	fn save_audit_entry(&mut self, entry: &AuditEntry) -> Result<(), Error>;
+
+	/// Save a send queued until funds mature
+	fn save_queued_send(&mut self, queued: &QueuedSend) -> Result<(), Error>;
+
+	/// Remove a send from the queue
+	fn delete_queued_send(&mut self, id: &Uuid) -> Result<(), Error>;
+
+	/// Save a queued job
+	fn save_job(&mut self, job: &Job) -> Result<(), Error>;
+
+	/// Remove a job from the queue
+	fn delete_job(&mut self, id: &Uuid) -> Result<(), Error>;
+
 	/// Write the wallet data to backend file
 	fn commit(&self) -> Result<(), Error>;
 }
@@ -355,6 +583,20 @@ pub trait NodeClient: Send + Sync + Clone {
 	/// retrieves the current tip (height, hash) from the specified epic node
 	fn get_chain_tip(&self) -> Result<(u64, String), Error>;
 
+	/// retrieves the node's sync status, `NODE_SYNCED_STATUS` once it has
+	/// caught up with the chain
+	fn get_sync_status(&self) -> Result<String, Error>;
+
+	/// retrieves the hash of the block header at the given height
+	fn get_header_hash(&self, height: u64) -> Result<String, Error>;
+
+	/// retrieves the proof of work algorithm used to mine the block at the
+	/// given height
+	fn get_header_pow_type(&self, height: u64) -> Result<PoWType, Error>;
+
+	/// retrieves the timestamp of the block header at the given height
+	fn get_header_timestamp(&self, height: u64) -> Result<DateTime<Utc>, Error>;
+
 	/// Get a kernel and the height of the block it's included in. Returns
 	/// (tx_kernel, height, mmr_index)
 	fn get_kernel(
@@ -364,6 +606,10 @@ pub trait NodeClient: Send + Sync + Clone {
 		max_height: Option<u64>,
 	) -> Result<Option<(TxKernel, u64, u64)>, Error>;
 
+	/// Whether a transaction with the given kernel excess is waiting in the
+	/// node's transaction pool
+	fn kernel_in_pool(&self, excess: &pedersen::Commitment) -> Result<bool, Error>;
+
 	/// retrieve a list of outputs from the specified epic node
 	/// need "by_height" and "by_id" variants
 	fn get_outputs_from_node(
@@ -433,6 +679,7 @@ pub struct OutputData {
 	#[serde(with = "secp_ser::string_or_u64")]
 	pub value: u64,
 	/// Current status of the output
+	#[serde(deserialize_with = "stored_output_status")]
 	pub status: OutputStatus,
 	/// Height of the output
 	#[serde(with = "secp_ser::string_or_u64")]
@@ -444,6 +691,24 @@ pub struct OutputData {
 	pub is_coinbase: bool,
 	/// Optional corresponding internal entry in tx entry log
 	pub tx_log_entry: Option<u32>,
+	/// Is this a foundation reward output, built by the foundation wallet?
+	#[serde(default)]
+	pub is_foundation: bool,
+	/// Internal tx log entry of the transaction that spent this output, if any
+	#[serde(default)]
+	pub spent_by_tx: Option<u32>,
+	/// Height at which this output was first seen spent
+	#[serde(default, with = "secp_ser::opt_string_or_u64")]
+	pub spent_height: Option<u64>,
+	/// Labels grouping this output within its account, so sends can be
+	/// restricted to a group of outputs
+	#[serde(default)]
+	pub tags: Vec<String>,
+	/// Height of the refresh that found this output missing from the node,
+	/// while it waits for the next refresh to confirm it's spent
+	#[serde(default, with = "secp_ser::opt_string_or_u64")]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub missing_since: Option<u64>,
 }
 
 impl ser::Writeable for OutputData {
@@ -465,6 +730,24 @@ impl OutputData {
 		self.status = OutputStatus::Locked;
 	}
 
+	/// Whether the output carries the given tag
+	pub fn has_tag(&self, tag: &str) -> bool {
+		self.tags.iter().any(|t| t == tag)
+	}
+
+	/// The stored commitment of this output. Commitments are stored with every
+	/// output and backfilled for older outputs when the wallet is opened, so a
+	/// missing one is an error rather than something to recalculate
+	pub fn commitment(&self) -> Result<pedersen::Commitment, Error> {
+		let commit = self
+			.commit
+			.as_ref()
+			.ok_or_else(|| ErrorKind::MissingOutputCommit(self.key_id.to_string()))?;
+		let bytes = from_hex(commit.clone())
+			.map_err(|_| ErrorKind::MissingOutputCommit(self.key_id.to_string()))?;
+		Ok(pedersen::Commitment::from_vec(bytes))
+	}
+
 	/// How many confirmations has this output received?
 	/// If height == 0 then we are either Unconfirmed or the output was
 	/// cut-through
@@ -511,15 +794,44 @@ impl OutputData {
 		}
 	}
 
-	/// Mark an output as spent
-	pub fn mark_spent(&mut self) {
+	/// Mark an output as spent, recording the height the spend was first seen at
+	pub fn mark_spent(&mut self, height: u64) {
 		match self.status {
-			OutputStatus::Unspent => self.status = OutputStatus::Spent,
-			OutputStatus::Locked => self.status = OutputStatus::Spent,
+			OutputStatus::Unspent | OutputStatus::Locked => {
+				self.status = OutputStatus::Spent;
+				self.spent_height = Some(height);
+			}
 			_ => (),
 		}
 	}
 }
+/// A single migration applied (or, in a dry run, pending) when bringing the
+/// wallet database up to the current schema version
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MigrationStep {
+	/// Schema version the database is at once this migration is applied
+	pub version: u32,
+	/// What the migration does
+	pub description: String,
+	/// Number of records written by the migration
+	pub records: usize,
+}
+
+/// Outcome of bringing the wallet database up to the current schema version
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MigrationReport {
+	/// Schema version the database was at
+	pub from_version: u32,
+	/// Schema version the database is (or, in a dry run, would be) at
+	pub to_version: u32,
+	/// Migrations applied, in order
+	pub steps: Vec<MigrationStep>,
+	/// Where the database was copied before migrating, if it was
+	pub backup_path: Option<String>,
+	/// Whether this was a dry run, leaving the database untouched
+	pub dry_run: bool,
+}
+
 /// Status of an output that's being tracked by the wallet. Can either be
 /// unconfirmed, spent, unspent, or locked (when it's been used to generate
 /// a transaction but we don't have confirmation that the transaction was
@@ -536,6 +848,9 @@ pub enum OutputStatus {
 	Spent,
 	/// Deleted
 	Deleted,
+	/// A status added by a newer wallet version, read from its database
+	#[serde(skip_deserializing)]
+	Unknown,
 }
 
 impl fmt::Display for OutputStatus {
@@ -546,10 +861,46 @@ impl fmt::Display for OutputStatus {
 			OutputStatus::Locked => write!(f, "Locked"),
 			OutputStatus::Spent => write!(f, "Spent"),
 			OutputStatus::Deleted => write!(f, "Deleted"),
+			OutputStatus::Unknown => write!(f, "Unknown"),
 		}
 	}
 }
 
+/// Read a value of a stored record, as `unknown` if it's one this version
+/// doesn't know of, written by a newer wallet. Values given to the API are
+/// still read strictly
+fn stored_or_unknown<'de, D, T>(deserializer: D, unknown: T) -> Result<T, D::Error>
+where
+	D: serde::de::Deserializer<'de>,
+	T: serde::de::Deserialize<'de>,
+{
+	use serde::de::{Deserialize, IntoDeserializer};
+	let s = String::deserialize(deserializer)?;
+	let value: Result<T, serde::de::value::Error> = T::deserialize(s.into_deserializer());
+	Ok(value.unwrap_or(unknown))
+}
+
+fn stored_output_status<'de, D>(deserializer: D) -> Result<OutputStatus, D::Error>
+where
+	D: serde::de::Deserializer<'de>,
+{
+	stored_or_unknown(deserializer, OutputStatus::Unknown)
+}
+
+fn stored_tx_log_entry_type<'de, D>(deserializer: D) -> Result<TxLogEntryType, D::Error>
+where
+	D: serde::de::Deserializer<'de>,
+{
+	stored_or_unknown(deserializer, TxLogEntryType::Unknown)
+}
+
+fn stored_tx_state<'de, D>(deserializer: D) -> Result<TxState, D::Error>
+where
+	D: serde::de::Deserializer<'de>,
+{
+	stored_or_unknown(deserializer, TxState::Unknown)
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 /// Holds the context for a single aggsig transaction
 pub struct Context {
@@ -572,22 +923,29 @@ pub struct Context {
 	pub participant_id: usize,
 	/// Payment proof sender address derivation path, if needed
 	pub payment_proof_derivation_index: Option<u32>,
+	/// Tag inputs were restricted to, which change outputs inherit
+	#[serde(default)]
+	pub selection_tag: Option<String>,
+	/// Destination the transaction is sent to, if the sender gave one
+	#[serde(default)]
+	pub destination: Option<String>,
 }
 
 impl Context {
-	/// Create a new context with defaults
+	/// Create a new context with defaults, and a secret nonce from the OS
+	/// random number generator
 	pub fn new(
 		secp: &secp::Secp256k1,
 		sec_key: SecretKey,
 		parent_key_id: &Identifier,
 		use_test_rng: bool,
 		participant_id: usize,
-	) -> Context {
+	) -> Result<Context, Error> {
 		let sec_nonce = match use_test_rng {
-			false => aggsig::create_secnonce(secp).unwrap(),
+			false => random_secret_key(secp)?,
 			true => SecretKey::from_slice(secp, &[1; 32]).unwrap(),
 		};
-		Context {
+		Ok(Context {
 			parent_key_id: parent_key_id.clone(),
 			sec_key: sec_key,
 			sec_nonce,
@@ -596,7 +954,9 @@ impl Context {
 			fee: 0,
 			participant_id: participant_id,
 			payment_proof_derivation_index: None,
-		}
+			selection_tag: None,
+			destination: None,
+		})
 	}
 }
 
@@ -639,16 +999,29 @@ impl Context {
 	}
 }
 
+/// The secret key and nonce are zeroed once the context is done with
+impl Drop for Context {
+	fn drop(&mut self) {
+		zeroize_secret_key(&mut self.sec_key);
+		zeroize_secret_key(&mut self.sec_nonce);
+	}
+}
+
 impl ser::Writeable for Context {
 	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
-		writer.write_bytes(&serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?)
+		let mut data = serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?;
+		let res = writer.write_bytes(&data);
+		data.zeroize();
+		res
 	}
 }
 
 impl ser::Readable for Context {
 	fn read(reader: &mut dyn ser::Reader) -> Result<Context, ser::Error> {
-		let data = reader.read_bytes_len_prefix()?;
-		serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData)
+		let mut data = reader.read_bytes_len_prefix()?;
+		let res = serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData);
+		data.zeroize();
+		res
 	}
 }
 
@@ -717,23 +1090,60 @@ pub struct WalletInfo {
 	#[serde(with = "secp_ser::string_or_u64")]
 	pub minimum_confirmations: u64,
 	/// total amount in the wallet
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub total: u64,
+	pub total: Amount,
 	/// amount awaiting finalization
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_awaiting_finalization: u64,
+	pub amount_awaiting_finalization: Amount,
 	/// amount awaiting confirmation
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_awaiting_confirmation: u64,
+	pub amount_awaiting_confirmation: Amount,
 	/// coinbases waiting for lock height
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_immature: u64,
+	pub amount_immature: Amount,
 	/// amount currently spendable
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_currently_spendable: u64,
+	pub amount_currently_spendable: Amount,
 	/// amount locked via previous transactions
+	pub amount_locked: Amount,
+	/// Breakdown of the above by output source, only filled in when requested
+	#[serde(default)]
+	pub breakdown: Option<WalletInfoBreakdown>,
+}
+
+/// Detailed breakdown of the wallet summary, by the source of the outputs
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, Clone, Default)]
+pub struct WalletInfoBreakdown {
+	/// Mining rewards
+	pub coinbase: SourceBalance,
+	/// Foundation rewards
+	pub foundation: SourceBalance,
+	/// Outputs received in regular transactions, including change
+	pub regular: SourceBalance,
+	/// Immature coinbase and foundation amounts, by the height at which they mature
+	pub immature_schedule: Vec<MaturityBucket>,
+}
+
+/// Balance of the outputs from a single source
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, Clone, Default)]
+pub struct SourceBalance {
+	/// total amount, as in `WalletInfo::total`
+	pub total: Amount,
+	/// amount awaiting confirmation
+	pub amount_awaiting_confirmation: Amount,
+	/// amount waiting for lock height
+	pub amount_immature: Amount,
+	/// amount currently spendable
+	pub amount_currently_spendable: Amount,
+	/// amount locked via previous transactions
+	pub amount_locked: Amount,
+}
+
+/// Immature amount that becomes spendable at a given height
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, Clone)]
+pub struct MaturityBucket {
+	/// Height at which the outputs become spendable
 	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_locked: u64,
+	pub lock_height: u64,
+	/// Number of outputs maturing at this height
+	pub num_outputs: usize,
+	/// Total value of the outputs
+	pub amount: Amount,
 }
 
 /// Types of transactions that can be contained within a TXLog entry
@@ -749,6 +1159,11 @@ pub enum TxLogEntryType {
 	TxReceivedCancelled,
 	/// Sent transaction that was rolled back by user
 	TxSentCancelled,
+	/// Coinbase output that was never confirmed, removed from the wallet
+	RevertedCoinbase,
+	/// A type added by a newer wallet version, read from its database
+	#[serde(skip_deserializing)]
+	Unknown,
 }
 
 impl fmt::Display for TxLogEntryType {
@@ -759,6 +1174,101 @@ impl fmt::Display for TxLogEntryType {
 			TxLogEntryType::TxSent => write!(f, "Sent Tx"),
 			TxLogEntryType::TxReceivedCancelled => write!(f, "Received Tx\n- Cancelled"),
 			TxLogEntryType::TxSentCancelled => write!(f, "Sent Tx\n- Cancelled"),
+			TxLogEntryType::RevertedCoinbase => write!(f, "Reverted \nCoinbase"),
+			TxLogEntryType::Unknown => write!(f, "Unknown"),
+		}
+	}
+}
+
+/// Progress of handing a finalized transaction to the node. The state is
+/// persisted before the transaction is posted, so a post interrupted by a
+/// crash can be recovered the next time the wallet is opened
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum TxPostState {
+	/// The transaction is stored and about to be posted
+	ReadyToPost,
+	/// The node acknowledged the transaction
+	Posted,
+	/// The node couldn't be reached, so the stored transaction waits to be
+	/// posted once it can
+	Queued,
+}
+
+/// Where a transaction log entry is in its lifecycle. Entries only move
+/// forward through these states, skipping those that don't apply to them,
+/// see [can_become](#method.can_become)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TxState {
+	/// Logged, before its slate was handed to anyone
+	Created,
+	/// The slate was handed to the other party: the inputs of a send are
+	/// locked, or the response to a received transaction returned
+	Sent,
+	/// Finalized, with the transaction stored
+	Finalized,
+	/// The node acknowledged the transaction
+	Posted,
+	/// Found on chain
+	Confirmed,
+	/// Rolled back by the user or the wallet, or a coinbase that was never
+	/// confirmed
+	Cancelled,
+	/// Rolled back as its TTL cutoff height passed before it was confirmed
+	Expired,
+	/// A state added by a newer wallet version, read from its database
+	#[serde(skip_deserializing)]
+	Unknown,
+}
+
+impl Default for TxState {
+	fn default() -> TxState {
+		TxState::Created
+	}
+}
+
+impl TxState {
+	/// Whether the transaction is done with: confirmed, cancelled or expired
+	pub fn is_final(&self) -> bool {
+		match self {
+			TxState::Confirmed | TxState::Cancelled | TxState::Expired => true,
+			_ => false,
+		}
+	}
+
+	/// Whether an entry in this state can move to the other. Entries move
+	/// forward, or stay where they are, until they're final. Received
+	/// transactions, which the other party finalizes and posts, go from
+	/// `Sent` straight to `Confirmed`
+	pub fn can_become(&self, next: &TxState) -> bool {
+		let rank = |s: &TxState| match s {
+			TxState::Created => Some(0),
+			TxState::Sent => Some(1),
+			TxState::Finalized => Some(2),
+			TxState::Posted => Some(3),
+			TxState::Confirmed | TxState::Cancelled | TxState::Expired => Some(4),
+			TxState::Unknown => None,
+		};
+		if self == next {
+			return *self != TxState::Unknown;
+		}
+		match (rank(self), rank(next)) {
+			(Some(from), Some(to)) => !self.is_final() && from < to,
+			_ => false,
+		}
+	}
+}
+
+impl fmt::Display for TxState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			TxState::Created => write!(f, "Created"),
+			TxState::Sent => write!(f, "Sent"),
+			TxState::Finalized => write!(f, "Finalized"),
+			TxState::Posted => write!(f, "Posted"),
+			TxState::Confirmed => write!(f, "Confirmed"),
+			TxState::Cancelled => write!(f, "Cancelled"),
+			TxState::Expired => write!(f, "Expired"),
+			TxState::Unknown => write!(f, "Unknown"),
 		}
 	}
 }
@@ -775,13 +1285,19 @@ pub struct TxLogEntry {
 	/// Slate transaction this entry is associated with, if any
 	pub tx_slate_id: Option<Uuid>,
 	/// Transaction type (as above)
+	#[serde(deserialize_with = "stored_tx_log_entry_type")]
 	pub tx_type: TxLogEntryType,
-	/// Time this tx entry was created
+	/// Time this tx entry was created, in UTC (RFC3339 in the API)
 	/// #[serde(with = "tx_date_format")]
 	pub creation_ts: DateTime<Utc>,
-	/// Time this tx was confirmed (by this wallet)
+	/// Time this tx was confirmed (by this wallet), in UTC (RFC3339 in the API)
 	/// #[serde(default, with = "opt_tx_date_format")]
 	pub confirmation_ts: Option<DateTime<Utc>>,
+	/// Position of this entry in the order entries were created across the wallet,
+	/// assigned when it's first stored. Unlike `creation_ts`, it isn't affected by
+	/// changes to the system clock. Entries stored by older versions have 0
+	#[serde(default)]
+	pub seq: u64,
 	/// Whether the inputs+outputs involved in this transaction have been
 	/// confirmed (In all cases either all outputs involved in a tx should be
 	/// confirmed, or none should be; otherwise there's a deeper problem)
@@ -791,11 +1307,9 @@ pub struct TxLogEntry {
 	/// number of outputs involved in TX
 	pub num_outputs: usize,
 	/// Amount credited via this transaction
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_credited: u64,
+	pub amount_credited: Amount,
 	/// Amount debited via this transaction
-	#[serde(with = "secp_ser::string_or_u64")]
-	pub amount_debited: u64,
+	pub amount_debited: Amount,
 	/// Fee
 	#[serde(with = "secp_ser::opt_string_or_u64")]
 	pub fee: Option<u64>,
@@ -818,6 +1332,53 @@ pub struct TxLogEntry {
 	/// Additional info needed to stored payment proof
 	#[serde(default)]
 	pub payment_proof: Option<StoredProofInfo>,
+	/// Payment proof address of the sender, for received transactions
+	/// that came with a payment proof request
+	#[serde(with = "dalek_ser::option_dalek_pubkey_serde")]
+	#[serde(default)]
+	pub sender_proof_address: Option<DalekPublicKey>,
+	/// Whether this wallet has posted the finalized transaction, if it
+	/// was asked to
+	#[serde(default)]
+	pub post_state: Option<TxPostState>,
+	/// Where the transaction is in its lifecycle. Only changed through
+	/// [transition](#method.transition), so it can't contradict the other
+	/// fields
+	#[serde(default, deserialize_with = "stored_tx_state")]
+	pub state: TxState,
+	/// Height of the block in which this transaction was confirmed
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	pub confirmation_height: Option<u64>,
+	/// Hash of the block in which this transaction was confirmed
+	#[serde(default)]
+	pub confirmation_hash: Option<String>,
+	/// Proof of work algorithm of the block that produced this reward,
+	/// for confirmed coinbase entries
+	#[serde(default)]
+	pub pow_type: Option<PoWType>,
+	/// Number of confirmations as of the wallet's last known chain height.
+	/// Computed when retrieved, not stored
+	#[serde(with = "secp_ser::opt_string_or_u64")]
+	#[serde(default)]
+	pub confirmations: Option<u64>,
+	/// Tag of the listener path a received transaction came in through, used
+	/// as a per-user deposit address
+	#[serde(default)]
+	pub deposit_tag: Option<String>,
+	/// How the fee of a sent transaction was worked out
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fee_breakdown: Option<FeeBreakdown>,
+	/// Destination a sent transaction was sent to, if the sender gave one
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub destination: Option<String>,
+	/// Version of the slate exchanged with the other party, which for a send
+	/// over http is the newest both wallets support
+	#[serde(default)]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub slate_version: Option<u16>,
 }
 
 impl ser::Writeable for TxLogEntry {
@@ -843,9 +1404,10 @@ impl TxLogEntry {
 			tx_slate_id: None,
 			creation_ts: Utc::now(),
 			confirmation_ts: None,
+			seq: 0,
 			confirmed: false,
-			amount_credited: 0,
-			amount_debited: 0,
+			amount_credited: Amount::ZERO,
+			amount_debited: Amount::ZERO,
 			num_inputs: 0,
 			num_outputs: 0,
 			fee: None,
@@ -855,21 +1417,96 @@ impl TxLogEntry {
 			kernel_excess: None,
 			kernel_lookup_min_height: None,
 			payment_proof: None,
+			sender_proof_address: None,
+			post_state: None,
+			state: TxState::Created,
+			confirmation_height: None,
+			confirmation_hash: None,
+			pow_type: None,
+			confirmations: None,
+			deposit_tag: None,
+			fee_breakdown: None,
+			destination: None,
+			slate_version: None,
 		}
 	}
 
 	/// Given a vec of TX log entries, return credited + debited sums
-	pub fn sum_confirmed(txs: &Vec<TxLogEntry>) -> (u64, u64) {
-		txs.iter().fold((0, 0), |acc, tx| match tx.confirmed {
-			true => (acc.0 + tx.amount_credited, acc.1 + tx.amount_debited),
-			false => acc,
-		})
+	pub fn sum_confirmed(txs: &Vec<TxLogEntry>) -> (Amount, Amount) {
+		txs.iter()
+			.fold((Amount::ZERO, Amount::ZERO), |acc, tx| match tx.confirmed {
+				true => (acc.0 + tx.amount_credited, acc.1 + tx.amount_debited),
+				false => acc,
+			})
 	}
 
 	/// Update confirmation TS with now
 	pub fn update_confirmation_ts(&mut self) {
 		self.confirmation_ts = Some(Utc::now());
 	}
+
+	/// Move the entry to the given state, failing if it can't get there from
+	/// the state it's in
+	pub fn transition(&mut self, next: TxState) -> Result<(), Error> {
+		if !self.state.can_become(&next) {
+			return Err(ErrorKind::InvalidTxTransition(self.id, self.state, next))?;
+		}
+		self.state = next;
+		Ok(())
+	}
+
+	/// Mark the transaction confirmed in the block at the given height
+	pub fn confirm(&mut self, height: u64) -> Result<(), Error> {
+		self.transition(TxState::Confirmed)?;
+		self.confirmed = true;
+		self.confirmation_height = Some(height);
+		self.update_confirmation_ts();
+		Ok(())
+	}
+
+	/// Mark the transaction rolled back, as expired if its TTL cutoff height
+	/// passed, and a send or receive as cancelled
+	pub fn cancel(&mut self, expired: bool) -> Result<(), Error> {
+		self.transition(match expired {
+			true => TxState::Expired,
+			false => TxState::Cancelled,
+		})?;
+		match self.tx_type {
+			TxLogEntryType::TxSent => self.tx_type = TxLogEntryType::TxSentCancelled,
+			TxLogEntryType::TxReceived => self.tx_type = TxLogEntryType::TxReceivedCancelled,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// The state of an entry stored before states were recorded, worked out
+	/// from its type and flags. Entries that expired were cancelled then
+	pub fn infer_state(&self) -> TxState {
+		match self.tx_type {
+			TxLogEntryType::TxSentCancelled
+			| TxLogEntryType::TxReceivedCancelled
+			| TxLogEntryType::RevertedCoinbase => return TxState::Cancelled,
+			TxLogEntryType::Unknown => return TxState::Unknown,
+			_ => {}
+		}
+		if self.confirmed {
+			return TxState::Confirmed;
+		}
+		match (&self.tx_type, &self.post_state) {
+			(_, Some(TxPostState::Posted)) => TxState::Posted,
+			(_, Some(_)) => TxState::Finalized,
+			(TxLogEntryType::TxSent, None) | (TxLogEntryType::TxReceived, None) => TxState::Sent,
+			_ => TxState::Created,
+		}
+	}
+
+	/// Number of confirmations of this entry given the current chain height
+	pub fn num_confirmations(&self, tip_height: u64) -> Option<u64> {
+		match (self.confirmed, self.confirmation_height) {
+			(true, Some(h)) if tip_height >= h => Some(tip_height - h + 1),
+			_ => None,
+		}
+	}
 }
 
 /// Payment proof information. Differs from what is sent via
@@ -912,6 +1549,10 @@ pub struct AcctPathMapping {
 	pub label: String,
 	/// Corresponding parent BIP32 derivation path
 	pub path: Identifier,
+	/// Whether spends from the account are blocked, e.g. for a compliance hold.
+	/// It can still receive and be queried
+	#[serde(default)]
+	pub frozen: bool,
 }
 
 impl ser::Writeable for AcctPathMapping {
@@ -960,6 +1601,235 @@ impl ser::Readable for ScannedBlockInfo {
 	}
 }
 
+/// The result of an Owner API call made with an idempotency key, returned
+/// again when the call is retried with the same key
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdempotentResult {
+	/// Name of the API method called
+	pub method: String,
+	/// Idempotency key supplied by the caller
+	pub key: String,
+	/// Hash of the call's arguments, as hex. Not stored by earlier versions
+	#[serde(default)]
+	pub args_hash: Option<String>,
+	/// The call's result, as json
+	pub result: serde_json::Value,
+	/// Time the call was first made
+	pub creation_ts: DateTime<Utc>,
+}
+
+impl IdempotentResult {
+	/// Hash of the arguments of a call, as hex, matched when the call is retried
+	pub fn hash_args<A: serde::Serialize>(args: &A) -> Result<String, Error> {
+		let json = serde_json::to_vec(args).map_err(|e| {
+			ErrorKind::GenericError(format!("Unable to serialize call arguments: {}", e))
+		})?;
+		Ok(to_hex(blake2b(32, &[], &json).as_bytes().to_vec()))
+	}
+}
+
+impl ser::Writeable for IdempotentResult {
+	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+		writer.write_bytes(&serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?)
+	}
+}
+
+impl ser::Readable for IdempotentResult {
+	fn read(reader: &mut dyn ser::Reader) -> Result<IdempotentResult, ser::Error> {
+		let data = reader.read_bytes_len_prefix()?;
+		serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData)
+	}
+}
+
+/// What a receiver offers when the sender of a transaction asks it to
+/// contribute one of its outputs as an input, and to pay part of the fee out
+/// of it
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeContribution {
+	/// Largest part of the fee paid, in freemans. With 0 an input is
+	/// contributed, but none of the fee
+	pub max_fee: u64,
+	/// Confirmations an output needs before it's contributed
+	pub min_confirmations: u64,
+	/// Most contributed outputs locked at once by transactions that haven't
+	/// confirmed or been cancelled yet. Once reached, inputs are only
+	/// contributed again as those transactions complete
+	pub max_pending: usize,
+}
+
+impl FeeContribution {
+	/// Contribute up to `max_fee` of the fee, from outputs with
+	/// `RECEIVER_INPUT_MIN_CONFIRMATIONS`, and with at most
+	/// `MAX_PENDING_RECEIVER_INPUTS` of them locked at once
+	pub fn new(max_fee: u64) -> FeeContribution {
+		FeeContribution {
+			max_fee,
+			min_confirmations: RECEIVER_INPUT_MIN_CONFIRMATIONS,
+			max_pending: MAX_PENDING_RECEIVER_INPUTS,
+		}
+	}
+}
+
+/// An amount of an account's balance set aside for a payment that hasn't been
+/// built yet. Transactions are only built while the account keeps enough funds
+/// to cover all of its reservations, other than the one they are drawn from
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Reservation {
+	/// Reservation id
+	pub id: Uuid,
+	/// Account the amount is reserved in
+	pub parent_key_id: Identifier,
+	/// Amount reserved, in freemans
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub amount: u64,
+	/// Caller supplied label, such as a withdrawal request id
+	pub label: Option<String>,
+	/// Time the reservation was made
+	pub creation_ts: DateTime<Utc>,
+}
+
+impl ser::Writeable for Reservation {
+	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+		writer.write_bytes(&serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?)
+	}
+}
+
+impl ser::Readable for Reservation {
+	fn read(reader: &mut dyn ser::Reader) -> Result<Reservation, ser::Error> {
+		let data = reader.read_bytes_len_prefix()?;
+		serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData)
+	}
+}
+
+/// A change made by hand to the wallet's state, such as overriding the
+/// status of an output, kept so support staff can review it later
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+	/// Entry id
+	pub id: Uuid,
+	/// Time the change was made
+	pub timestamp: DateTime<Utc>,
+	/// Owner API method that made the change
+	pub action: String,
+	/// What was changed, such as an output's commitment
+	pub target: String,
+	/// State before the change
+	pub before: String,
+	/// State after the change
+	pub after: String,
+	/// Reason given for the change
+	pub reason: String,
+}
+
+impl ser::Writeable for AuditEntry {
+	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+		writer.write_bytes(&serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?)
+	}
+}
+
+impl ser::Readable for AuditEntry {
+	fn read(reader: &mut dyn ser::Reader) -> Result<AuditEntry, ser::Error> {
+		let data = reader.read_bytes_len_prefix()?;
+		serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData)
+	}
+}
+
+/// A send the wallet couldn't fund yet, queued until enough of its coinbase
+/// outputs mature
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QueuedSend {
+	/// Queued send id
+	pub id: Uuid,
+	/// Arguments the send is made with
+	pub args: InitTxArgs,
+	/// Height at which enough funds are expected to be spendable
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub ready_height: u64,
+	/// Last height the send waits for, after which it's dropped if the funds
+	/// still fall short
+	#[serde(with = "secp_ser::string_or_u64")]
+	pub expiry_height: u64,
+	/// Time the send was queued
+	pub creation_ts: DateTime<Utc>,
+}
+
+impl ser::Writeable for QueuedSend {
+	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+		writer.write_bytes(&serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?)
+	}
+}
+
+impl ser::Readable for QueuedSend {
+	fn read(reader: &mut dyn ser::Reader) -> Result<QueuedSend, ser::Error> {
+		let data = reader.read_bytes_len_prefix()?;
+		serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData)
+	}
+}
+
+/// Work left to do once a transaction is finalized or received, such as
+/// posting it or notifying someone of it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum JobAction {
+	/// Post the finalized transaction of a slate to the node, unless it's
+	/// been posted or cancelled since
+	PostTx {
+		/// Id of the transaction's slate
+		tx_slate_id: Uuid,
+		/// Whether to skip the Dandelion phase
+		fluff: bool,
+	},
+	/// Return a received slate to its sender
+	SendResponseSlate {
+		/// How the slate is returned, `keybase` or `file`
+		method: String,
+		/// Where it's returned to, a keybase channel or a file path
+		dest: String,
+		/// The slate
+		slate: VersionedSlate,
+	},
+	/// Post a JSON notification to a URL
+	Webhook {
+		/// http(s) URL the notification is posted to
+		url: String,
+		/// The notification
+		body: serde_json::Value,
+	},
+}
+
+/// An action kept in the wallet until it's been done, so work interrupted by
+/// a crash or a failure is picked up again. A job that's failed
+/// `JOB_MAX_ATTEMPTS` times is dead, and kept without being tried again
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Job {
+	/// Job id
+	pub id: Uuid,
+	/// What's to be done
+	pub action: JobAction,
+	/// Number of times the action has failed
+	pub attempts: u32,
+	/// Time from which the action is tried next
+	pub next_attempt_ts: DateTime<Utc>,
+	/// Error the action last failed with
+	pub last_error: Option<String>,
+	/// Whether the job has failed too many times to be tried again
+	pub dead: bool,
+	/// Time the job was queued
+	pub creation_ts: DateTime<Utc>,
+}
+
+impl ser::Writeable for Job {
+	fn write<W: ser::Writer>(&self, writer: &mut W) -> Result<(), ser::Error> {
+		writer.write_bytes(&serde_json::to_vec(self).map_err(|_| ser::Error::CorruptedData)?)
+	}
+}
+
+impl ser::Readable for Job {
+	fn read(reader: &mut dyn ser::Reader) -> Result<Job, ser::Error> {
+		let data = reader.read_bytes_len_prefix()?;
+		serde_json::from_slice(&data[..]).map_err(|_| ser::Error::CorruptedData)
+	}
+}
+
 /// Wrapper for reward output and kernel used when building a coinbase for a mining node.
 /// Note: Not serializable, must be converted to necesssary "versioned" representation
 /// before serializing to json to ensure compatibility with mining node.
@@ -0,0 +1,191 @@
+// Copyright 2019 The Epic Developers
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secret nonces and kernel offsets of slates never repeat, and nonces
+//! derived from the kernel message sign along with drawn ones
+use epic_wallet_libwallet::{Context, Slate};
+use epic_wallet_util::epic_core::core::transaction::KernelFeatures;
+use epic_wallet_util::epic_core::libtx::aggsig;
+use epic_wallet_util::epic_keychain::{
+	BlindingFactor, ExtKeychain, ExtKeychainPath, Keychain, SwitchCommitmentType,
+};
+use epic_wallet_util::epic_util::secp::key::{PublicKey, SecretKey};
+use std::collections::HashSet;
+
+const SLATES: u32 = 10_000;
+
+#[test]
+fn nonce_uniqueness() {
+	let keychain = ExtKeychain::from_random_seed(true).unwrap();
+	let secp = keychain.secp();
+	let parent = ExtKeychainPath::new(1, 1, 0, 0, 0).to_identifier();
+	let mut nonces = HashSet::new();
+	let mut public_nonces = HashSet::new();
+	let mut offsets = HashSet::new();
+
+	for i in 0..SLATES {
+		let key_id = ExtKeychain::derive_key_id(2, 1, i, 0, 0);
+		let blind = keychain
+			.derive_key(0, &key_id, &SwitchCommitmentType::Regular)
+			.unwrap();
+		let mut cx = Context::new(secp, blind, &parent, false, 0).unwrap();
+		let mut slate = Slate::blank(2);
+		slate
+			.fill_round_1(&keychain, &mut cx.sec_key, &cx.sec_nonce, 0, None, false)
+			.unwrap();
+
+		assert!(nonces.insert(cx.sec_nonce.0));
+		let public_nonce = slate.participant_data[0].public_nonce;
+		assert!(public_nonces.insert(public_nonce.serialize_vec(secp, true).to_vec()));
+		assert!(offsets.insert(slate.tx.offset.secret_key(secp).unwrap().0));
+	}
+	assert_eq!(nonces.len(), SLATES as usize);
+	assert_eq!(offsets.len(), SLATES as usize);
+}
+
+#[test]
+fn test_nonces_repeat() {
+	// only the test rng, for reproducible slates, gives the same nonce twice
+	let keychain = ExtKeychain::from_random_seed(true).unwrap();
+	let parent = ExtKeychainPath::new(1, 1, 0, 0, 0).to_identifier();
+	let key_id = ExtKeychain::derive_key_id(2, 1, 0, 0, 0);
+	let blind = keychain
+		.derive_key(0, &key_id, &SwitchCommitmentType::Regular)
+		.unwrap();
+	let a = Context::new(keychain.secp(), blind.clone(), &parent, true, 0).unwrap();
+	let b = Context::new(keychain.secp(), blind, &parent, true, 0).unwrap();
+	assert_eq!(a.sec_nonce, b.sec_nonce);
+}
+
+#[test]
+fn deterministic_nonce_signatures() {
+	let keychain = ExtKeychain::from_random_seed(true).unwrap();
+	let secp = keychain.secp();
+	let parent = ExtKeychainPath::new(1, 1, 0, 0, 0).to_identifier();
+	let switch = &SwitchCommitmentType::Regular;
+	let mut slate = Slate::blank(2);
+	assert!(!slate.nonce_derivable());
+	slate.fee = 8_000_000;
+	assert!(slate.nonce_derivable());
+	let msg = KernelFeatures::Plain { fee: slate.fee }
+		.kernel_sig_msg()
+		.unwrap();
+
+	let s_key = keychain
+		.derive_key(0, &ExtKeychain::derive_key_id(1, 1, 0, 0, 0), switch)
+		.unwrap();
+	let r_key = keychain
+		.derive_key(0, &ExtKeychain::derive_key_id(1, 2, 0, 0, 0), switch)
+		.unwrap();
+	let s_nonce = slate.derive_nonce(secp, &s_key, 0).unwrap();
+	assert_eq!(s_nonce, slate.derive_nonce(secp, &s_key, 0).unwrap());
+	assert!(s_nonce != slate.derive_nonce(secp, &s_key, 1).unwrap());
+	assert!(s_nonce != Slate::blank(2).derive_nonce(secp, &s_key, 0).unwrap());
+	// the receiver draws its nonce, as wallets without the feature do
+	let r_cx = Context::new(secp, r_key, &parent, false, 1).unwrap();
+
+	let public = |k: &SecretKey| PublicKey::from_secret_key(secp, k).unwrap();
+	let nonce_sum =
+		PublicKey::from_combination(secp, vec![&public(&s_nonce), &public(&r_cx.sec_nonce)])
+			.unwrap();
+	let key_sum =
+		PublicKey::from_combination(secp, vec![&public(&s_key), &public(&r_cx.sec_key)]).unwrap();
+	let s_sig =
+		aggsig::calculate_partial_sig(secp, &s_key, &s_nonce, &nonce_sum, Some(&key_sum), &msg)
+			.unwrap();
+	let r_sig = aggsig::calculate_partial_sig(
+		secp,
+		&r_cx.sec_key,
+		&r_cx.sec_nonce,
+		&nonce_sum,
+		Some(&key_sum),
+		&msg,
+	)
+	.unwrap();
+	aggsig::verify_partial_sig(
+		secp,
+		&s_sig,
+		&nonce_sum,
+		&public(&s_key),
+		Some(&key_sum),
+		&msg,
+	)
+	.unwrap();
+	aggsig::verify_partial_sig(
+		secp,
+		&r_sig,
+		&nonce_sum,
+		&public(&r_cx.sec_key),
+		Some(&key_sum),
+		&msg,
+	)
+	.unwrap();
+	let sig = aggsig::add_signatures(secp, vec![&s_sig, &r_sig], &nonce_sum).unwrap();
+	aggsig::verify_completed_sig(secp, &sig, &key_sum, Some(&key_sum), &msg).unwrap();
+
+	// signing again with the same key and message gives the same signature
+	let nonce = slate.derive_nonce(secp, &s_key, 0).unwrap();
+	let again =
+		aggsig::calculate_partial_sig(secp, &s_key, &nonce, &nonce_sum, Some(&key_sum), &msg)
+			.unwrap();
+	assert_eq!(again, s_sig);
+}
+
+#[test]
+fn derived_nonces_bind_counterparty() {
+	// a counterparty replaying a slate with another nonce or offset of its
+	// own can't get two signatures out of the same nonce
+	let keychain = ExtKeychain::from_random_seed(true).unwrap();
+	let secp = keychain.secp();
+	let parent = ExtKeychainPath::new(1, 1, 0, 0, 0).to_identifier();
+	let switch = &SwitchCommitmentType::Regular;
+	let s_key = keychain
+		.derive_key(0, &ExtKeychain::derive_key_id(1, 1, 0, 0, 0), switch)
+		.unwrap();
+	let r_key = keychain
+		.derive_key(0, &ExtKeychain::derive_key_id(1, 2, 0, 0, 0), switch)
+		.unwrap();
+
+	let mut slate = Slate::blank(2);
+	slate.fee = 8_000_000;
+	let mut s_cx = Context::new(secp, s_key, &parent, false, 0).unwrap();
+	slate
+		.fill_round_1(
+			&keychain,
+			&mut s_cx.sec_key,
+			&s_cx.sec_nonce,
+			0,
+			None,
+			false,
+		)
+		.unwrap();
+	let nonce = slate.derive_nonce(secp, &r_key, 1).unwrap();
+	assert_eq!(nonce, slate.derive_nonce(secp, &r_key, 1).unwrap());
+
+	let other_nonce = Context::new(secp, s_cx.sec_key.clone(), &parent, false, 0)
+		.unwrap()
+		.sec_nonce;
+	let mut replayed = slate.clone();
+	replayed.participant_data[0].public_nonce =
+		PublicKey::from_secret_key(secp, &other_nonce).unwrap();
+	assert!(nonce != replayed.derive_nonce(secp, &r_key, 1).unwrap());
+
+	let mut replayed = slate.clone();
+	replayed.participant_data[0].public_blind_excess =
+		PublicKey::from_secret_key(secp, &other_nonce).unwrap();
+	assert!(nonce != replayed.derive_nonce(secp, &r_key, 1).unwrap());
+
+	let mut replayed = slate.clone();
+	replayed.tx.offset = BlindingFactor::from_secret_key(other_nonce);
+	assert!(nonce != replayed.derive_nonce(secp, &r_key, 1).unwrap());
+}
@@ -12,7 +12,39 @@
 // limitations under the License.
 
 //! core::libtx specific tests
-//use epic_wallet_libwallet::Slate;
+use epic_wallet_libwallet::{ErrorKind, Slate};
+
+// V0 and V1 slates of old wallets, only read when receiving from them
+#[test]
+fn legacy_slate_conversions() {
+	let v2 = include_str!("slates/v2.slate");
+	let slate = Slate::deserialize_upgrade(&v2).unwrap();
+	let as_v2 = serde_json::to_string(&slate).unwrap();
+	for version in 0..2 {
+		// as an old wallet sends it
+		let mut old = slate.clone();
+		old.version_info.orig_version = version;
+		let s = serde_json::to_string(&old).unwrap();
+		assert_eq!(Slate::parse_slate_version(&s).unwrap(), version);
+		assert!(!s.contains("version_info"));
+		assert_eq!(s.contains("\"version\":1"), version == 1);
+
+		match Slate::deserialize_upgrade(&s) {
+			Err(e) => assert_eq!(e.kind(), ErrorKind::SlateVersion(version)),
+			Ok(_) => panic!("V{} slate read without asking for old versions", version),
+		}
+		let mut res = Slate::deserialize_legacy(&s).unwrap();
+		assert_eq!(res.version_info.orig_version, version);
+		// answered in the version it was sent in
+		assert_eq!(serde_json::to_string(&res).unwrap(), s);
+		res.version_info.orig_version = 2;
+		assert_eq!(serde_json::to_string(&res).unwrap(), as_v2);
+	}
+
+	// newer slates are read as usual
+	let res = Slate::deserialize_legacy(&v2).unwrap();
+	assert_eq!(res.version_info.orig_version, 2);
+}
 
 // test all slate conversions
 /* TODO: Turn back on upon release of new slate version
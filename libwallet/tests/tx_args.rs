@@ -0,0 +1,119 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Transaction and invoice arguments built with their builders
+use epic_wallet_libwallet::{
+	Amount, Denomination, Error, ErrorKind, InitTxArgs, InitTxSendArgs, IssueInvoiceTxArgs,
+	SelectionStrategy,
+};
+
+fn refused<T>(result: Result<T, Error>) -> bool {
+	match result {
+		Err(e) => match e.kind() {
+			ErrorKind::TxArgs(_) => true,
+			_ => false,
+		},
+		Ok(_) => false,
+	}
+}
+
+#[test]
+fn build_init_tx_args() {
+	let args = InitTxArgs::builder()
+		.amount(Amount::from_freemans(2_000_000_000))
+		.strategy(SelectionStrategy::Smallest)
+		.ttl_blocks(60)
+		.message("thanks".to_owned())
+		.build()
+		.unwrap();
+	assert_eq!(args.amount, 2_000_000_000);
+	assert!(!args.selection_strategy_is_use_all);
+	assert_eq!(args.ttl_blocks, Some(60));
+	assert_eq!(args.message, Some("thanks".to_owned()));
+	// the rest as by default
+	let default = InitTxArgs::default();
+	assert_eq!(args.minimum_confirmations, default.minimum_confirmations);
+	assert_eq!(args.max_outputs, default.max_outputs);
+	assert_eq!(args.num_change_outputs, default.num_change_outputs);
+
+	let args = InitTxArgs::builder()
+		.amount(Amount::from_units(2, Denomination::Epic).unwrap())
+		.strategy(SelectionStrategy::All)
+		.build()
+		.unwrap();
+	assert_eq!(args.amount, 200_000_000);
+	assert_eq!(args.units, None);
+	assert!(args.selection_strategy_is_use_all);
+	let args = InitTxArgs::builder().send_max(true).build().unwrap();
+	assert_eq!(args.send_max, Some(true));
+	assert!(InitTxArgs::builder().amount_from_invoice().build().is_ok());
+
+	// the amount is missing, zero or given twice
+	let one = Amount::from_freemans(1);
+	for b in vec![
+		InitTxArgs::builder(),
+		InitTxArgs::builder().send_max(false),
+		InitTxArgs::builder().amount(Amount::ZERO),
+		InitTxArgs::builder().amount(one).send_max(true),
+		InitTxArgs::builder().amount(one).amount_from_invoice(),
+	] {
+		assert!(refused(b.build()));
+	}
+
+	// settings that can't be used
+	let send_args = InitTxSendArgs {
+		method: "http".to_owned(),
+		dest: "http://127.0.0.1:3415".to_owned(),
+		finalize: true,
+		post_tx: true,
+		fluff: false,
+	};
+	for b in vec![
+		InitTxArgs::builder().amount(one).max_outputs(0),
+		InitTxArgs::builder().amount(one).ttl_blocks(0),
+		InitTxArgs::builder().amount(one).wait_for_maturity(10),
+		InitTxArgs::builder()
+			.amount(one)
+			.estimate_only(true)
+			.send_args(send_args.clone()),
+	] {
+		assert!(refused(b.build()));
+	}
+	let args = InitTxArgs::builder()
+		.amount(one)
+		.send_args(send_args)
+		.wait_for_maturity(10)
+		.build()
+		.unwrap();
+	assert_eq!(args.wait_for_maturity, Some(10));
+}
+
+#[test]
+fn build_issue_invoice_tx_args() {
+	let args = IssueInvoiceTxArgs::builder()
+		.amount(Amount::from_freemans(60_000_000_000))
+		.dest_acct_name("savings".to_owned())
+		.target_slate_version(3)
+		.build()
+		.unwrap();
+	assert_eq!(args.amount, 60_000_000_000);
+	assert_eq!(args.dest_acct_name, Some("savings".to_owned()));
+	assert_eq!(args.target_slate_version, Some(3));
+	assert_eq!(args.message, None);
+
+	assert!(refused(IssueInvoiceTxArgs::builder().build()));
+	assert!(refused(
+		IssueInvoiceTxArgs::builder().amount(Amount::ZERO).build()
+	));
+}
@@ -24,7 +24,7 @@ use crate::util::init_logger;
 use clap::App;
 use epic_wallet::cmd;
 use epic_wallet_config as config;
-use epic_wallet_impls::HTTPNodeClient;
+use epic_wallet_impls::{HTTPNodeClient, NodeRetryPolicy};
 use epic_wallet_util::epic_core as core;
 use epic_wallet_util::epic_util as util;
 use std::env;
@@ -111,7 +111,11 @@ fn real_main() -> i32 {
 	//config.members.as_mut().unwrap().wallet.chain_type = Some(chain_type);
 
 	// Load logging config
-	let l = config.members.as_mut().unwrap().logging.clone().unwrap();
+	let mut l = config.members.as_mut().unwrap().logging.clone().unwrap();
+	// keep stdout clean for scripts reading the JSON output
+	if args.is_present("json") {
+		l.log_to_stdout = false;
+	}
 	init_logger(Some(l), None);
 	info!(
 		"Using wallet configuration file at {}",
@@ -133,7 +137,8 @@ fn real_main() -> i32 {
 	);
 
 	let wallet_config = config.clone().members.unwrap().wallet;
-	let node_client = HTTPNodeClient::new(&wallet_config.check_node_api_http_addr, None);
+	let mut node_client = HTTPNodeClient::new(&wallet_config.check_node_api_http_addr, None);
+	node_client.set_retry_policy(NodeRetryPolicy::from_config(&wallet_config));
 
 	cmd::wallet_command(&args, config, node_client)
 }
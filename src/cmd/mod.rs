@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod password;
 mod wallet;
 pub mod wallet_args;
 
@@ -0,0 +1,120 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Ways of getting the wallet password without putting it on the command
+/// line, where it shows up in process listings
+use crate::cmd::wallet_args::ParseError;
+use crate::config::EPIC_WALLET_DIR;
+use crate::util::ZeroingString;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+
+/// Service name wallet passwords are stored under in the OS keyring
+pub const KEYRING_SERVICE: &str = "epic-wallet";
+
+/// Keyring entries are keyed by the wallet's top level directory, with or
+/// without the legacy `wallet_data` suffix
+#[cfg_attr(not(feature = "keyring"), allow(dead_code))]
+fn keyring_user(data_dir: &str) -> String {
+	let mut dir = PathBuf::from(data_dir);
+	if dir.ends_with(EPIC_WALLET_DIR) {
+		dir.pop();
+	}
+	dir.to_string_lossy().into_owned()
+}
+
+fn strip_line_ending(line: &str) -> &str {
+	line.trim_end_matches(|c| c == '\n' || c == '\r')
+}
+
+/// Read the password from the first line of a file
+pub fn from_file(path: &str) -> Result<ZeroingString, ParseError> {
+	let contents = ZeroingString::from(fs::read_to_string(path).map_err(|e| {
+		ParseError::ArgumentError(format!("Unable to read password file {}: {}", path, e))
+	})?);
+	let line = contents.lines().next().unwrap_or("");
+	Ok(ZeroingString::from(strip_line_ending(line)))
+}
+
+/// Whether stdin is piped or redirected rather than a terminal, in which
+/// case the password is read from it instead of being prompted for
+pub fn stdin_is_piped() -> bool {
+	!atty::is(atty::Stream::Stdin)
+}
+
+/// Read the password from a line of stdin
+pub fn from_stdin() -> Result<ZeroingString, ParseError> {
+	let mut buf = String::new();
+	io::stdin().lock().read_line(&mut buf)?;
+	let buf = ZeroingString::from(buf);
+	Ok(ZeroingString::from(strip_line_ending(&buf)))
+}
+
+/// Look up the password of the wallet in the given data directory in the OS
+/// keyring
+#[cfg(feature = "keyring")]
+pub fn from_keyring(data_dir: &str) -> Result<ZeroingString, ParseError> {
+	let user = keyring_user(data_dir);
+	match keyring::Keyring::new(KEYRING_SERVICE, &user).get_password() {
+		Ok(p) => Ok(ZeroingString::from(p)),
+		Err(e) => Err(ParseError::ArgumentError(format!(
+			"No password for wallet {} in the OS keyring ({}), store one with \
+			 `epic-wallet keyring`",
+			user, e
+		))),
+	}
+}
+
+/// Store the password of the wallet in the given data directory in the OS
+/// keyring
+#[cfg(feature = "keyring")]
+pub fn save_to_keyring(data_dir: &str, password: &str) -> Result<(), ParseError> {
+	let user = keyring_user(data_dir);
+	keyring::Keyring::new(KEYRING_SERVICE, &user)
+		.set_password(password)
+		.map_err(|e| ParseError::IOError(format!("Unable to write to the OS keyring: {}", e)))
+}
+
+/// Remove the password of the wallet in the given data directory from the OS
+/// keyring
+#[cfg(feature = "keyring")]
+pub fn remove_from_keyring(data_dir: &str) -> Result<(), ParseError> {
+	let user = keyring_user(data_dir);
+	keyring::Keyring::new(KEYRING_SERVICE, &user)
+		.delete_password()
+		.map_err(|e| ParseError::IOError(format!("Unable to write to the OS keyring: {}", e)))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_unsupported() -> ParseError {
+	ParseError::ArgumentError(
+		"This wallet was built without OS keyring support (the `keyring` feature)".to_owned(),
+	)
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn from_keyring(_data_dir: &str) -> Result<ZeroingString, ParseError> {
+	Err(keyring_unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn save_to_keyring(_data_dir: &str, _password: &str) -> Result<(), ParseError> {
+	Err(keyring_unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn remove_from_keyring(_data_dir: &str) -> Result<(), ParseError> {
+	Err(keyring_unsupported())
+}
@@ -17,6 +17,7 @@ use crate::config::GlobalWalletConfig;
 use clap::ArgMatches;
 use epic_wallet_libwallet::NodeClient;
 use semver::Version;
+use serde_json::json;
 use std::thread;
 use std::time::Duration;
 
@@ -37,8 +38,13 @@ where
 
 	// Check the node version info, and exit with report if we're not compatible
 	//let mut node_client = HTTPNodeClient::new(&wallet_config.check_node_api_http_addr, None);
-	let global_wallet_args = wallet_args::parse_global_args(&wallet_config, &wallet_args)
-		.expect("Can't read configuration file");
+	let global_wallet_args = match wallet_args::parse_global_args(&wallet_config, &wallet_args) {
+		Ok(a) => a,
+		Err(e) => {
+			println!("Wallet command failed: {}", e);
+			return 1;
+		}
+	};
 	node_client.set_node_api_secret(global_wallet_args.node_api_secret.clone());
 
 	// This will also cache the node version info for calls to foreign API check middleware
@@ -71,9 +77,16 @@ where
 	// we need to give log output a chance to catch up before exiting
 	thread::sleep(Duration::from_millis(100));
 
+	let json_output = wallet_args.is_present("json");
 	if let Err(e) = res {
-		println!("Wallet command failed: {}", e);
+		if json_output {
+			println!("{}", json!({ "error": format!("{}", e) }));
+		} else {
+			println!("Wallet command failed: {}", e);
+		}
 		1
+	} else if json_output {
+		0
 	} else {
 		println!(
 			"Command '{}' completed successfully",
@@ -13,30 +13,38 @@
 // limitations under the License.
 
 use crate::api::TLSConfig;
-use crate::config::EPIC_WALLET_DIR;
+use crate::cmd::password;
+use crate::config::{EPIC_WALLET_DIR, WALLET_CONFIG_FILE_NAME};
 use crate::util::file::get_first_line;
-use crate::util::{to_hex, Mutex, ZeroingString};
+use crate::util::secp::pedersen;
+use crate::util::{from_hex, to_hex, Mutex, ZeroingString};
 /// Argument parsing and error handling for wallet commands
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use clap::ArgMatches;
-use epic_wallet_config::{TorConfig, WalletConfig};
-use epic_wallet_controller::command;
+use epic_wallet_config::{
+	SeedKdfParams, SeedStorage, SupervisedWalletConfig, SupervisorConfig, TorConfig, WalletConfig,
+};
+use epic_wallet_controller::{command, display};
 use epic_wallet_controller::{Error, ErrorKind};
 use epic_wallet_impls::tor::config::is_tor_address;
 use epic_wallet_impls::{DefaultLCProvider, DefaultWalletImpl};
 use epic_wallet_impls::{PathToSlate, SlateGetter as _};
 use epic_wallet_libwallet::Slate;
 use epic_wallet_libwallet::{
-	address, IssueInvoiceTxArgs, NodeClient, WalletInst, WalletLCProvider,
+	address, denomination, Amount, Denomination, IssueInvoiceTxArgs, NodeClient, PaymentRequest,
+	PaymentUri, TxFilter, WalletInst, WalletLCProvider, DEFAULT_COINBASE_CLEANUP_WINDOW,
 };
 use epic_wallet_util::epic_core as core;
-use epic_wallet_util::epic_core::core::amount_to_hr_string;
+use epic_wallet_util::epic_core::core::feijoada::PoWType;
 use epic_wallet_util::epic_core::global;
 use epic_wallet_util::epic_keychain as keychain;
 use failure::Fail;
 use linefeed::terminal::Signal;
 use linefeed::{Interface, ReadResult};
 use rpassword;
+use std::env;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
 // define what to do on argument error
@@ -69,6 +77,10 @@ impl From<std::io::Error> for ParseError {
 }
 
 fn prompt_password_stdout(prompt: &str) -> ZeroingString {
+	// scripts and daemons pipe the password in rather than typing it
+	if password::stdin_is_piped() {
+		return password::from_stdin().unwrap_or(ZeroingString::from(""));
+	}
 	ZeroingString::from(rpassword::prompt_password_stdout(prompt).unwrap_or("".to_string()))
 }
 
@@ -80,6 +92,9 @@ pub fn prompt_password(password: &Option<ZeroingString>) -> ZeroingString {
 }
 
 fn prompt_password_confirm() -> ZeroingString {
+	if password::stdin_is_piped() {
+		return prompt_password_stdout("Password: ");
+	}
 	let mut first = ZeroingString::from("first");
 	let mut second = ZeroingString::from("second");
 	while first != second {
@@ -133,9 +148,14 @@ where
 	Ok(phrase)
 }
 
-fn prompt_pay_invoice(slate: &Slate, method: &str, dest: &str) -> Result<bool, ParseError> {
+fn prompt_pay_invoice(
+	slate: &Slate,
+	method: &str,
+	dest: &str,
+	units: Denomination,
+) -> Result<bool, ParseError> {
 	let interface = Arc::new(Interface::new("pay")?);
-	let amount = amount_to_hr_string(slate.amount, false);
+	let amount = slate.amount.format(units, false);
 	interface.set_report_signal(Signal::Interrupt, true);
 	interface.set_prompt(
 		"To proceed, type the exact amount of the invoice as displayed above (or Q/q to quit) > ",
@@ -147,8 +167,8 @@ fn prompt_pay_invoice(slate: &Slate, method: &str, dest: &str) -> Result<bool, P
 	println!("After you confirm, the following will occur: ");
 	println!();
 	println!(
-		"* {} of your wallet funds will be added to the transaction to pay this invoice.",
-		amount
+		"* {} {} of your wallet funds will be added to the transaction to pay this invoice.",
+		amount, units
 	);
 	if method == "http" {
 		println!("* The resulting transaction will IMMEDIATELY be sent to the wallet listening at: '{}'.", dest);
@@ -205,9 +225,65 @@ where
 		as Box<dyn WalletInst<'static, L, C, K>>;
 	let lc = wallet.lc_provider().unwrap();
 	let _ = lc.set_top_level_directory(&config.data_file_dir);
+	let _ = lc.set_coinbase_cleanup_window(
+		config
+			.unconfirmed_coinbase_cleanup_window
+			.unwrap_or(DEFAULT_COINBASE_CLEANUP_WINDOW),
+	);
+	let _ = lc.set_confirm_missing_outputs(config.confirm_missing_outputs.unwrap_or(false));
+	let _ = lc.set_backup_before_migrate(config.backup_before_migrate.unwrap_or(true));
+	let _ = lc.set_seed_storage(seed_storage(&config)?);
+	let _ = lc.set_seed_kdf(seed_kdf(&config)?);
 	Ok(Arc::new(Mutex::new(wallet)))
 }
 
+// where the configuration keeps the wallet seed
+fn seed_storage(config: &WalletConfig) -> Result<SeedStorage, ParseError> {
+	match config.seed_storage.as_ref().map(|s| s.as_str()) {
+		None | Some("file") => Ok(SeedStorage::File),
+		Some("os_keystore") => Ok(SeedStorage::OsKeystore),
+		Some("pkcs11") => match config.pkcs11_module.clone() {
+			Some(module) => Ok(SeedStorage::Pkcs11 {
+				module,
+				slot: config.pkcs11_slot,
+			}),
+			None => {
+				let msg = format!("seed_storage \"pkcs11\" needs pkcs11_module to be set");
+				Err(ParseError::ArgumentError(msg))
+			}
+		},
+		Some(s) => {
+			let msg = format!(
+				"Unknown seed_storage \"{}\", use \"file\", \"os_keystore\" or \"pkcs11\"",
+				s
+			);
+			Err(ParseError::ArgumentError(msg))
+		}
+	}
+}
+
+// the argon2id costs the seed is encrypted with
+fn seed_kdf(config: &WalletConfig) -> Result<SeedKdfParams, ParseError> {
+	let default = SeedKdfParams::default();
+	let params = SeedKdfParams {
+		memory_kib: config.seed_kdf_memory_kib.unwrap_or(default.memory_kib),
+		iterations: config.seed_kdf_iterations.unwrap_or(default.iterations),
+		parallelism: config.seed_kdf_parallelism.unwrap_or(default.parallelism),
+	};
+	if params.iterations < 1 || params.parallelism < 1 {
+		let msg = format!("seed_kdf_iterations and seed_kdf_parallelism must be at least 1");
+		return Err(ParseError::ArgumentError(msg));
+	}
+	if params.memory_kib < 8 * params.parallelism {
+		let msg = format!(
+			"seed_kdf_memory_kib must be at least 8 times seed_kdf_parallelism, {}",
+			8 * params.parallelism
+		);
+		return Err(ParseError::ArgumentError(msg));
+	}
+	Ok(params)
+}
+
 // parses a required value, or throws error with message otherwise
 fn parse_required<'a>(args: &'a ArgMatches, name: &str) -> Result<&'a str, ParseError> {
 	let arg = args.value_of(name);
@@ -220,6 +296,37 @@ fn parse_required<'a>(args: &'a ArgMatches, name: &str) -> Result<&'a str, Parse
 	}
 }
 
+// parses an amount, with optional units after it, into freemans
+fn parse_amount(arg: &str, units: Denomination) -> Result<u64, ParseError> {
+	denomination::parse_amount(arg, units).map_err(|e| {
+		let msg = format!("Could not parse amount. e={}", e);
+		ParseError::ArgumentError(msg)
+	})
+}
+
+// parses an RFC3339 time, or a date taken as local midnight
+fn parse_time(arg: &str, name: &str) -> Result<DateTime<Utc>, ParseError> {
+	if let Ok(t) = DateTime::parse_from_rfc3339(arg) {
+		return Ok(t.with_timezone(&Utc));
+	}
+	match NaiveDate::parse_from_str(arg, "%Y-%m-%d") {
+		Ok(d) => match Local.from_local_datetime(&d.and_hms(0, 0, 0)).earliest() {
+			Some(t) => Ok(t.with_timezone(&Utc)),
+			None => {
+				let msg = format!("{} {} has no local midnight", name, arg);
+				Err(ParseError::ArgumentError(msg))
+			}
+		},
+		Err(e) => {
+			let msg = format!(
+				"Could not parse {} as an RFC3339 time or a YYYY-MM-DD date. e={}",
+				name, e
+			);
+			Err(ParseError::ArgumentError(msg))
+		}
+	}
+}
+
 // parses a number, or throws error with message otherwise
 fn parse_u64(arg: &str, name: &str) -> Result<u64, ParseError> {
 	let val = arg.parse::<u64>();
@@ -255,9 +362,14 @@ pub fn parse_global_args(
 	}
 	let api_secret = get_first_line(config.api_secret_path.clone());
 	let node_api_secret = get_first_line(config.node_api_secret_path.clone());
-	let password = match args.value_of("pass") {
-		None => None,
-		Some(p) => Some(ZeroingString::from(p)),
+	let password = if let Some(p) = args.value_of("pass") {
+		Some(ZeroingString::from(p))
+	} else if let Some(f) = args.value_of("pass_file") {
+		Some(password::from_file(f)?)
+	} else if args.is_present("keyring") {
+		Some(password::from_keyring(&config.data_file_dir)?)
+	} else {
+		None
 	};
 
 	let tls_conf = match config.tls_certificate_file.clone() {
@@ -282,6 +394,13 @@ pub fn parse_global_args(
 		Some(c) => c,
 	};
 
+	let units = match args.value_of("units") {
+		Some(u) => Denomination::from_str(u).map_err(|e| {
+			ParseError::ArgumentError(format!("Could not parse units. e={}", e))
+		})?,
+		None => Denomination::Epic,
+	};
+
 	Ok(command::GlobalArgs {
 		account: account.to_owned(),
 		show_spent: show_spent,
@@ -290,6 +409,8 @@ pub fn parse_global_args(
 		node_api_secret: node_api_secret,
 		password: password,
 		tls_conf: tls_conf,
+		json_output: args.is_present("json"),
+		units,
 	})
 }
 
@@ -345,6 +466,15 @@ where
 	})
 }
 
+pub fn parse_seed_show_args() -> Result<command::SeedShowArgs, ParseError> {
+	// always asked for, even if the wallet was opened with a password given
+	// on the command line or from the keyring
+	println!("Please enter your wallet password again to show the recovery phrase");
+	Ok(command::SeedShowArgs {
+		password: prompt_password_stdout("Password: "),
+	})
+}
+
 pub fn parse_listen_args(
 	config: &mut WalletConfig,
 	tor_config: &mut TorConfig,
@@ -359,6 +489,178 @@ pub fn parse_listen_args(
 	}
 	Ok(command::ListenArgs {
 		method: method.to_owned(),
+		daemon: args.is_present("daemon"),
+		pid_file: args.value_of("pid_file").map(|p| p.to_owned()),
+		merchant_api_secret: parse_merchant_api_secret(config)?,
+	})
+}
+
+fn parse_merchant_api_secret(config: &WalletConfig) -> Result<Option<String>, ParseError> {
+	match config.merchant_api_secret_path {
+		None => Ok(None),
+		Some(ref p) => match get_first_line(Some(p.clone())) {
+			Some(s) => Ok(Some(s)),
+			None => {
+				let msg = format!("Unable to read the merchant API secret from {}", p);
+				Err(ParseError::ArgumentError(msg))
+			}
+		},
+	}
+}
+
+pub fn parse_service_args(
+	config: &WalletConfig,
+	g_args: &ArgMatches,
+	args: &ArgMatches,
+) -> Result<command::ServiceArgs, ParseError> {
+	let (action, args) = match args.subcommand() {
+		(action, Some(args)) => (action, args),
+		_ => {
+			let msg = "A service action is required, see 'epic-wallet service help'".to_owned();
+			return Err(ParseError::ArgumentError(msg));
+		}
+	};
+	let name = parse_required(args, "name")?.to_owned();
+	let action = match action {
+		"install" => command::ServiceAction::Install(service_launch_args(config, g_args, &name)?),
+		"uninstall" => command::ServiceAction::Uninstall,
+		"start" => command::ServiceAction::Start,
+		"stop" => command::ServiceAction::Stop,
+		"run" => command::ServiceAction::Run(command::ListenArgs {
+			method: "http".to_owned(),
+			daemon: false,
+			pid_file: None,
+			merchant_api_secret: parse_merchant_api_secret(config)?,
+		}),
+		a => {
+			let msg = format!("Unknown service action {}", a);
+			return Err(ParseError::ArgumentError(msg));
+		}
+	};
+	Ok(command::ServiceArgs { name, action })
+}
+
+/// Arguments the service manager runs the wallet with, finding the same
+/// configuration, wallet and password as the wallet installing the service
+/// whatever directory and user it's started in
+fn service_launch_args(
+	config: &WalletConfig,
+	g_args: &ArgMatches,
+	name: &str,
+) -> Result<Vec<String>, ParseError> {
+	let cwd = env::current_dir()?;
+	let absolute = |p: &str| cwd.join(p).to_string_lossy().into_owned();
+	let pass_file = match g_args.value_of("pass_file") {
+		Some(f) => absolute(f),
+		None => {
+			let msg = "A service can't be asked for the wallet password, install it with \
+			           --pass-file"
+				.to_owned();
+			return Err(ParseError::ArgumentError(msg));
+		}
+	};
+	// the top level wallet directory, holding the configuration file unless
+	// it was found elsewhere
+	let data_dir = absolute(&config.data_file_dir);
+	let config_dir = match g_args.value_of("current_dir") {
+		Some(d) => absolute(d),
+		None => data_dir.clone(),
+	};
+	if !Path::new(&config_dir).join(WALLET_CONFIG_FILE_NAME).exists() {
+		let msg = format!(
+			"No {} in {}, give the directory it's in with --current_dir",
+			WALLET_CONFIG_FILE_NAME, config_dir
+		);
+		return Err(ParseError::ArgumentError(msg));
+	}
+
+	let mut launch_args = vec![
+		"--current_dir".to_owned(),
+		config_dir,
+		"--top_level_dir".to_owned(),
+		data_dir,
+		"--pass-file".to_owned(),
+		pass_file,
+	];
+	for flag in &["floonet", "usernet", "external"] {
+		if g_args.is_present(flag) {
+			launch_args.push(format!("--{}", flag));
+		}
+	}
+	if let Some(addr) = g_args.value_of("api_server_address") {
+		launch_args.push("--api_server_address".to_owned());
+		launch_args.push(addr.to_owned());
+	}
+	for arg in &["service", "run", "--name", name] {
+		launch_args.push(arg.to_string());
+	}
+	Ok(launch_args)
+}
+
+pub fn parse_supervise_args(args: &ArgMatches) -> Result<SupervisorConfig, ParseError> {
+	let path = parse_required(args, "config")?;
+	SupervisorConfig::new(path).map_err(|e| ParseError::ArgumentError(format!("{}", e)))
+}
+
+// instantiate and open one of the wallets served by the supervisor, which
+// shares the rest of its configuration with the main wallet
+fn open_supervised_wallet<C>(
+	main_config: &WalletConfig,
+	node_client: C,
+	w: &SupervisedWalletConfig,
+) -> Result<
+	command::SupervisedWallet<DefaultLCProvider<'static, C, keychain::ExtKeychain>, C, keychain::ExtKeychain>,
+	Error,
+>
+where
+	C: NodeClient + 'static + Clone,
+{
+	let mut config = main_config.clone();
+	config.data_file_dir = w.data_file_dir.clone();
+	config.api_listen_port = w.api_listen_port;
+	config.owner_api_listen_port = w.owner_api_listen_port;
+	config.owner_api_socket_path = None;
+	config.tls_acme_domain = None;
+	let api_secret = match w.api_secret_path {
+		None => None,
+		Some(ref p) => match get_first_line(Some(p.clone())) {
+			Some(s) => Some(s),
+			None => {
+				let msg = format!("Unable to read the API secret of wallet {} from {}", w.name, p);
+				return Err(ErrorKind::ArgumentError(msg).into());
+			}
+		},
+	};
+
+	let wallet = inst_wallet::<DefaultLCProvider<C, keychain::ExtKeychain>, C, keychain::ExtKeychain>(
+		config.clone(),
+		node_client,
+	)
+	.map_err(|e| ErrorKind::ArgumentError(format!("Wallet {}: {}", w.name, e)))?;
+	let password = match w.password_file {
+		Some(ref f) => password::from_file(f)
+			.map_err(|e| ErrorKind::ArgumentError(format!("Wallet {}: {}", w.name, e)))?,
+		None => prompt_password_stdout(&format!("Password for wallet {}: ", w.name)),
+	};
+	let keychain_mask = {
+		let mut wallet_lock = wallet.lock();
+		let lc = wallet_lock.lc_provider()?;
+		let mask = lc.open_wallet(None, password, false, false)?;
+		if let Some(ref account) = w.account {
+			lc.wallet_inst()?.set_parent_key_id_by_name(account)?;
+		}
+		mask
+	};
+	if !command::node_ping(wallet.clone(), keychain_mask.as_ref())? {
+		println!("Wallet {}: node unreachable, data may be out of date", w.name);
+	}
+	println!("Opened wallet {} in {}", w.name, w.data_file_dir);
+	Ok(command::SupervisedWallet {
+		name: w.name.clone(),
+		wallet,
+		keychain_mask,
+		config,
+		api_secret,
 	})
 }
 
@@ -372,6 +674,12 @@ pub fn parse_owner_api_args(
 	if args.is_present("run_foreign") {
 		config.owner_api_include_foreign = Some(true);
 	}
+	if let Some(path) = args.value_of("socket") {
+		config.owner_api_socket_path = Some(path.to_owned());
+	}
+	if args.is_present("read_only") {
+		config.owner_api_read_only = Some(true);
+	}
 	Ok(())
 }
 
@@ -380,28 +688,81 @@ pub fn parse_account_args(account_args: &ArgMatches) -> Result<command::AccountA
 		None => None,
 		Some(s) => Some(s.to_owned()),
 	};
-	Ok(command::AccountArgs { create: create })
+	Ok(command::AccountArgs {
+		create: create,
+		freeze: account_args.value_of("freeze").map(|s| s.to_owned()),
+		unfreeze: account_args.value_of("unfreeze").map(|s| s.to_owned()),
+		reason: account_args.value_of("reason").map(|s| s.to_owned()),
+	})
 }
 
-pub fn parse_send_args(args: &ArgMatches) -> Result<command::SendArgs, ParseError> {
+pub fn parse_send_args(
+	args: &ArgMatches,
+	units: Denomination,
+) -> Result<command::SendArgs, ParseError> {
+	// send everything spendable, in place of an amount
+	let send_max = args.is_present("max");
+
+	// payment link given as the destination, which may fill in the amount,
+	// message and payment proof request
+	let payment_uri = match args.value_of("dest") {
+		Some(d) if PaymentUri::is_payment_uri(d) => match d.parse::<PaymentUri>() {
+			Ok(u) => Some(u),
+			Err(e) => {
+				let msg = format!("Could not parse payment link. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+		_ => None,
+	};
+
+	// payment request given as the destination, paid to the requester for
+	// the amount requested
+	let payment_request = match args.value_of("dest") {
+		Some(d) if PaymentRequest::is_payment_request(d) => match d.parse::<PaymentRequest>() {
+			Ok(r) => Some(r),
+			Err(e) => {
+				let msg = format!("Could not parse payment request. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+		_ => None,
+	};
+	if send_max && payment_request.is_some() {
+		let msg = format!("Payment requests are paid the amount requested, not --max");
+		return Err(ParseError::ArgumentError(msg));
+	}
+	let requested_amount = match payment_request {
+		Some(ref r) => Some(r.amount.freemans()),
+		None => payment_uri.as_ref().and_then(|u| u.amount),
+	};
+
 	// amount
-	let amount = parse_required(args, "amount")?;
-	let amount = core::core::amount_from_hr_string(amount);
-	let amount = match amount {
-		Ok(a) => a,
-		Err(e) => {
-			let msg = format!(
-				"Could not parse amount as a number with optional decimal point. e={}",
-				e
-			);
-			return Err(ParseError::ArgumentError(msg));
+	let amount = match (send_max, args.value_of("amount"), requested_amount) {
+		(true, _, _) => 0,
+		(false, None, Some(requested)) => requested,
+		(false, Some(a), Some(requested)) => {
+			let amount = parse_amount(a, units)?;
+			if amount != requested {
+				let msg = format!(
+					"Amount {} differs from the {} requested by the payment link or request",
+					a,
+					units.format_amount(requested, true)
+				);
+				return Err(ParseError::ArgumentError(msg));
+			}
+			amount
+		}
+		(false, _, None) => {
+			let amount = parse_required(args, "amount")?;
+			parse_amount(amount, units)?
 		}
 	};
 
 	// message
 	let message = match args.is_present("message") {
 		true => Some(args.value_of("message").unwrap().to_owned()),
-		false => None,
+		false => payment_uri.as_ref().and_then(|u| u.message.clone()),
 	};
 
 	// minimum_confirmations
@@ -414,17 +775,61 @@ pub fn parse_send_args(args: &ArgMatches) -> Result<command::SendArgs, ParseErro
 	// estimate_selection_strategies
 	let estimate_selection_strategies = args.is_present("estimate_selection_strategies");
 
+	// override_reorg_safety
+	let override_reorg_safety = args.is_present("override_reorg_safety");
+
+	// selection tag
+	let selection_tag = args.value_of("tag").map(|t| t.to_owned());
+
+	// reservation the send pays out
+	let reservation_id = match args.value_of("reservation") {
+		None => None,
+		Some(r) => match r.parse() {
+			Ok(id) => Some(id),
+			Err(e) => {
+				let msg = format!("Could not parse reservation parameter. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+	};
+
 	// method
 	let method = parse_required(args, "method")?;
 
+	// payment links and requests are paid directly, over http or tor
+	if (payment_uri.is_some() || payment_request.is_some()) && method != "http" {
+		let msg = format!(
+			"Payment links and requests can't be paid with the {} method",
+			method
+		);
+		return Err(ParseError::ArgumentError(msg));
+	}
+
+	// requests are paid to the onion address of the requester's proof address
+	let request_dest = match payment_request {
+		Some(ref r) => match address::onion_v3_from_pubkey(&r.proof_address) {
+			Ok(a) => Some(a),
+			Err(e) => {
+				let msg = format!("Could not get the payment request's address. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+		None => None,
+	};
+
 	// dest
-	let dest = {
-		if method == "self" {
+	let dest = match payment_uri {
+		Some(ref u) => u.dest.as_str(),
+		None if request_dest.is_some() => request_dest.as_ref().unwrap().as_str(),
+		None if method == "self" => {
 			match args.value_of("dest") {
 				Some(d) => d,
 				None => "default",
 			}
-		} else {
+		}
+		// passed on to the transport program, which may not need one
+		None if method == "exec" => args.value_of("dest").unwrap_or(""),
+		None => {
 			if !estimate_selection_strategies {
 				parse_required(args, "dest")?
 			} else {
@@ -446,6 +851,12 @@ pub fn parse_send_args(args: &ArgMatches) -> Result<command::SendArgs, ParseErro
 		return Err(ParseError::ArgumentError(msg));
 	}
 
+	// program
+	let program = match method {
+		"exec" => Some(parse_required(args, "program")?.to_owned()),
+		_ => None,
+	};
+
 	// change_outputs
 	let change_outputs = parse_required(args, "change_outputs")?;
 	let change_outputs = parse_u64(change_outputs, "change_outputs")? as usize;
@@ -471,7 +882,11 @@ pub fn parse_send_args(args: &ArgMatches) -> Result<command::SendArgs, ParseErro
 	};
 
 	let payment_proof_address = {
-		match args.is_present("request_payment_proof") {
+		let requested = payment_uri
+			.as_ref()
+			.map(|u| u.request_payment_proof)
+			.unwrap_or(payment_request.is_some());
+		match args.is_present("request_payment_proof") || requested {
 			true => {
 				// if the destination address is a TOR address, we don't need the address
 				// separately
@@ -484,6 +899,22 @@ pub fn parse_send_args(args: &ArgMatches) -> Result<command::SendArgs, ParseErro
 		}
 	};
 
+	let verify_recipient = args.is_present("verify_recipient");
+	if verify_recipient && payment_proof_address.is_none() {
+		let msg = format!("verify_recipient needs a payment proof to be requested");
+		return Err(ParseError::ArgumentError(msg));
+	}
+
+	// queued sends are made by the wallet on its own, over http or keybase
+	let wait_for_maturity = parse_u64_or_none(args.value_of("wait_for_maturity"));
+	if wait_for_maturity.is_some() && method != "http" && method != "keybase" {
+		let msg = format!(
+			"Only http and keybase sends can wait for funds to mature, not {}",
+			method
+		);
+		return Err(ParseError::ArgumentError(msg));
+	}
+
 	Ok(command::SendArgs {
 		amount: amount,
 		message: message,
@@ -492,15 +923,32 @@ pub fn parse_send_args(args: &ArgMatches) -> Result<command::SendArgs, ParseErro
 		estimate_selection_strategies,
 		method: method.to_owned(),
 		dest: dest.to_owned(),
+		program,
 		change_outputs: change_outputs,
 		fluff: fluff,
 		max_outputs: max_outputs,
 		payment_proof_address,
 		ttl_blocks,
 		target_slate_version: target_slate_version,
+		override_reorg_safety,
+		selection_tag,
+		reservation_id,
+		send_max,
+		verify_recipient,
+		payjoin: args.is_present("payjoin"),
+		wait_for_maturity,
+		force: args.is_present("force"),
+		payment_request,
 	})
 }
 
+/// Whether a slate can be read from the input, which for QR codes may be a
+/// directory of captured frames
+fn input_exists(method: &str, input: &str) -> bool {
+	let path = Path::new(input);
+	path.is_file() || (method == "qr" && path.is_dir())
+}
+
 pub fn parse_receive_args(receive_args: &ArgMatches) -> Result<command::ReceiveArgs, ParseError> {
 	// message
 	let message = match receive_args.is_present("message") {
@@ -508,16 +956,20 @@ pub fn parse_receive_args(receive_args: &ArgMatches) -> Result<command::ReceiveA
 		false => None,
 	};
 
+	// method
+	let method = parse_required(receive_args, "method")?;
+
 	// input
 	let tx_file = parse_required(receive_args, "input")?;
 
 	// validate input
-	if !Path::new(&tx_file).is_file() {
+	if !input_exists(method, &tx_file) {
 		let msg = format!("File {} not found.", &tx_file);
 		return Err(ParseError::ArgumentError(msg));
 	}
 
 	Ok(command::ReceiveArgs {
+		method: method.to_owned(),
 		input: tx_file.to_owned(),
 		message: message,
 	})
@@ -526,9 +978,10 @@ pub fn parse_receive_args(receive_args: &ArgMatches) -> Result<command::ReceiveA
 pub fn parse_finalize_args(args: &ArgMatches) -> Result<command::FinalizeArgs, ParseError> {
 	let fluff = args.is_present("fluff");
 	let nopost = args.is_present("nopost");
+	let method = parse_required(args, "method")?;
 	let tx_file = parse_required(args, "input")?;
 
-	if !Path::new(&tx_file).is_file() {
+	if !input_exists(method, &tx_file) {
 		let msg = format!("File {} not found.", tx_file);
 		return Err(ParseError::ArgumentError(msg));
 	}
@@ -539,6 +992,7 @@ pub fn parse_finalize_args(args: &ArgMatches) -> Result<command::FinalizeArgs, P
 	};
 
 	Ok(command::FinalizeArgs {
+		method: method.to_owned(),
 		input: tx_file.to_owned(),
 		fluff: fluff,
 		nopost: nopost,
@@ -548,19 +1002,10 @@ pub fn parse_finalize_args(args: &ArgMatches) -> Result<command::FinalizeArgs, P
 
 pub fn parse_issue_invoice_args(
 	args: &ArgMatches,
+	units: Denomination,
 ) -> Result<command::IssueInvoiceArgs, ParseError> {
 	let amount = parse_required(args, "amount")?;
-	let amount = core::core::amount_from_hr_string(amount);
-	let amount = match amount {
-		Ok(a) => a,
-		Err(e) => {
-			let msg = format!(
-				"Could not parse amount as a number with optional decimal point. e={}",
-				e
-			);
-			return Err(ParseError::ArgumentError(msg));
-		}
-	};
+	let amount = parse_amount(amount, units)?;
 	// message
 	let message = match args.is_present("message") {
 		true => Some(args.value_of("message").unwrap().to_owned()),
@@ -578,20 +1023,34 @@ pub fn parse_issue_invoice_args(
 	};
 	// dest (output file)
 	let dest = parse_required(args, "dest")?;
+	let issue_args = IssueInvoiceTxArgs::builder()
+		.amount(Amount::from_freemans(amount))
+		.message(message)
+		.target_slate_version(target_slate_version)
+		.build()
+		.map_err(|e| ParseError::ArgumentError(format!("{}", e)))?;
 	Ok(command::IssueInvoiceArgs {
 		dest: dest.into(),
-		issue_args: IssueInvoiceTxArgs {
-			dest_acct_name: None,
-			amount,
-			message,
-			target_slate_version,
-		},
+		issue_args,
+	})
+}
+
+pub fn parse_payment_request_args(
+	args: &ArgMatches,
+	units: Denomination,
+) -> Result<command::PaymentRequestArgs, ParseError> {
+	let amount = parse_required(args, "amount")?;
+	let amount = parse_amount(amount, units)?;
+	Ok(command::PaymentRequestArgs {
+		amount,
+		memo: args.value_of("memo").map(|m| m.to_owned()),
 	})
 }
 
 pub fn parse_process_invoice_args(
 	args: &ArgMatches,
 	prompt: bool,
+	units: Denomination,
 ) -> Result<command::ProcessInvoiceArgs, ParseError> {
 	// TODO: display and prompt for confirmation of what we're doing
 	// message
@@ -610,6 +1069,9 @@ pub fn parse_process_invoice_args(
 	// estimate_selection_strategies
 	let estimate_selection_strategies = args.is_present("estimate_selection_strategies");
 
+	// override_reorg_safety
+	let override_reorg_safety = args.is_present("override_reorg_safety");
+
 	// method
 	let method = parse_required(args, "method")?;
 
@@ -620,6 +1082,9 @@ pub fn parse_process_invoice_args(
 				Some(d) => d,
 				None => "default",
 			}
+		} else if method == "exec" {
+			// passed on to the transport program, which may not need one
+			args.value_of("dest").unwrap_or("")
 		} else {
 			if !estimate_selection_strategies {
 				parse_required(args, "dest")?
@@ -640,9 +1105,21 @@ pub fn parse_process_invoice_args(
 		return Err(ParseError::ArgumentError(msg));
 	}
 
+	// program
+	let program = match method {
+		"exec" => Some(parse_required(args, "program")?.to_owned()),
+		_ => None,
+	};
+
 	// ttl_blocks
 	let ttl_blocks = parse_u64_or_none(args.value_of("ttl_blocks"));
 
+	// max_fee
+	let max_fee = match args.value_of("max_fee") {
+		Some(f) => Some(parse_amount(f, units)?),
+		None => None,
+	};
+
 	// max_outputs
 	let max_outputs = 500;
 
@@ -658,7 +1135,7 @@ pub fn parse_process_invoice_args(
 			Err(e) => return Err(ParseError::ArgumentError(format!("{}", e))),
 		};
 
-		prompt_pay_invoice(&slate, method, dest)?;
+		prompt_pay_invoice(&slate, method, dest, units)?;
 	}
 
 	Ok(command::ProcessInvoiceArgs {
@@ -668,9 +1145,12 @@ pub fn parse_process_invoice_args(
 		estimate_selection_strategies,
 		method: method.to_owned(),
 		dest: dest.to_owned(),
+		program,
 		max_outputs: max_outputs,
 		input: tx_file.to_owned(),
 		ttl_blocks,
+		override_reorg_safety,
+		max_fee,
 	})
 }
 
@@ -680,13 +1160,22 @@ pub fn parse_info_args(args: &ArgMatches) -> Result<command::InfoArgs, ParseErro
 	let mc = parse_u64(mc, "minimum_confirmations")?;
 	Ok(command::InfoArgs {
 		minimum_confirmations: mc,
+		detailed: args.is_present("detailed"),
+	})
+}
+
+pub fn parse_migrate_args(args: &ArgMatches) -> Result<command::MigrateArgs, ParseError> {
+	Ok(command::MigrateArgs {
+		dry_run: args.is_present("dry_run"),
 	})
 }
 
 pub fn parse_outputs_args(args: &ArgMatches) -> Result<command::OutputsArgs, ParseError> {
 	let show_full_history = args.is_present("show_full_history");
+	let include_history = args.is_present("include_history");
 	Ok(command::OutputsArgs {
 		show_full_history: show_full_history,
+		include_history: include_history,
 	})
 }
 
@@ -699,6 +1188,27 @@ pub fn parse_check_args(args: &ArgMatches) -> Result<command::CheckArgs, ParseEr
 	})
 }
 
+pub fn parse_rewards_args(args: &ArgMatches) -> Result<command::RewardsArgs, ParseError> {
+	let pow_type = match args.value_of("pow_type") {
+		None => None,
+		Some("cuckatoo") => Some(PoWType::Cuckatoo),
+		Some("cuckaroo") => Some(PoWType::Cuckaroo),
+		Some("randomx") => Some(PoWType::RandomX),
+		Some("progpow") => Some(PoWType::ProgPow),
+		Some(t) => {
+			let msg = format!("Unknown PoW algorithm '{}'", t);
+			return Err(ParseError::ArgumentError(msg));
+		}
+	};
+	let start_height = parse_u64_or_none(args.value_of("start_height"));
+	let end_height = parse_u64_or_none(args.value_of("end_height"));
+	Ok(command::RewardsArgs {
+		pow_type: pow_type,
+		start_height: start_height,
+		end_height: end_height,
+	})
+}
+
 pub fn parse_txs_args(args: &ArgMatches) -> Result<command::TxsArgs, ParseError> {
 	let tx_id = match args.value_of("id") {
 		None => None,
@@ -718,12 +1228,68 @@ pub fn parse_txs_args(args: &ArgMatches) -> Result<command::TxsArgs, ParseError>
 		let msg = format!("At most one of 'id' (-i) or 'txid' (-t) may be provided.");
 		return Err(ParseError::ArgumentError(msg));
 	}
+	let check_kernel = match args.value_of("check_kernel") {
+		None => None,
+		Some(k) => match from_hex(k.to_owned()) {
+			Ok(b) => Some(pedersen::Commitment::from_vec(b)),
+			Err(e) => {
+				let msg = format!("Could not parse check_kernel parameter. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+	};
+	let since = match args.value_of("since") {
+		None => None,
+		Some(t) => Some(parse_time(t, "since")?),
+	};
+	let until = match args.value_of("until") {
+		None => None,
+		Some(t) => Some(parse_time(t, "until")?),
+	};
+	let filter = match args.value_of("filter") {
+		None => None,
+		Some(f) => match TxFilter::from_str(f) {
+			Ok(f) => Some(f),
+			Err(e) => {
+				let msg = format!("Could not parse filter parameter. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+	};
 	Ok(command::TxsArgs {
 		id: tx_id,
 		tx_slate_id: tx_slate_id,
+		check_kernel: check_kernel,
+		since,
+		until,
+		filter,
 	})
 }
 
+pub fn parse_tx_args(args: &ArgMatches) -> Result<command::TxArgs, ParseError> {
+	let id = parse_required(args, "id")?;
+	// either a local transaction id or a slate id
+	if let Ok(i) = id.parse::<u32>() {
+		return Ok(command::TxArgs {
+			id: Some(i),
+			tx_slate_id: None,
+		});
+	}
+	match id.parse() {
+		Ok(u) => Ok(command::TxArgs {
+			id: None,
+			tx_slate_id: Some(u),
+		}),
+		Err(e) => {
+			let msg = format!(
+				"Could not parse id parameter as a transaction id or TxID UUID. e={}",
+				e
+			);
+			Err(ParseError::ArgumentError(msg))
+		}
+	}
+}
+
 pub fn parse_post_args(args: &ArgMatches) -> Result<command::PostArgs, ParseError> {
 	let tx_file = parse_required(args, "input")?;
 	let fluff = args.is_present("fluff");
@@ -772,16 +1338,151 @@ pub fn parse_cancel_args(args: &ArgMatches) -> Result<command::CancelArgs, Parse
 			}
 		},
 	};
-	if (tx_id.is_none() && tx_slate_id.is_none()) || (tx_id.is_some() && tx_slate_id.is_some()) {
-		let msg = format!("'id' (-i) or 'txid' (-t) argument is required.");
+	let stale_minutes = match args.value_of("stale") {
+		None => None,
+		Some(m) => Some(parse_u64(m, "stale")?),
+	};
+	let given = [tx_id.is_some(), tx_slate_id.is_some(), stale_minutes.is_some()];
+	if given.iter().filter(|g| **g).count() != 1 {
+		let msg = format!("One of 'id' (-i), 'txid' (-t) or 'stale' (-s) arguments is required.");
+		return Err(ParseError::ArgumentError(msg));
+	}
+	let dry_run = args.is_present("dry_run");
+	if dry_run && stale_minutes.is_some() {
+		let msg = format!("'dry_run' previews a single transaction, it can't be used with 'stale'.");
 		return Err(ParseError::ArgumentError(msg));
 	}
 	Ok(command::CancelArgs {
 		tx_id: tx_id,
 		tx_slate_id: tx_slate_id,
 		tx_id_string: tx_id_string.to_owned(),
+		stale_minutes: stale_minutes,
+		dry_run: dry_run,
+		force: args.is_present("force"),
+	})
+}
+
+pub fn parse_doctor_args(args: &ArgMatches) -> Result<command::DoctorArgs, ParseError> {
+	Ok(command::DoctorArgs {
+		fix: args.is_present("fix"),
+	})
+}
+
+pub fn parse_unlock_args(args: &ArgMatches) -> Result<command::UnlockArgs, ParseError> {
+	let commit = parse_required(args, "commit")?;
+	if from_hex(commit.to_owned()).is_err() {
+		let msg = format!("Could not parse commit parameter, expected hex");
+		return Err(ParseError::ArgumentError(msg));
+	}
+	Ok(command::UnlockArgs {
+		commit: commit.to_owned(),
+		include_pending: args.is_present("include_pending"),
 	})
 }
+
+pub fn parse_tag_args(args: &ArgMatches) -> Result<command::TagArgs, ParseError> {
+	let tag = parse_required(args, "tag")?;
+	let mut commits = vec![];
+	for commit in args.values_of("commits").into_iter().flatten() {
+		if from_hex(commit.to_owned()).is_err() {
+			let msg = format!("Could not parse commit parameter {}, expected hex", commit);
+			return Err(ParseError::ArgumentError(msg));
+		}
+		commits.push(commit.to_owned());
+	}
+	Ok(command::TagArgs {
+		commits,
+		tag: tag.to_owned(),
+		remove: args.is_present("remove"),
+	})
+}
+
+pub fn parse_reserve_args(
+	args: &ArgMatches,
+	units: Denomination,
+) -> Result<command::ReserveArgs, ParseError> {
+	let amount = parse_required(args, "amount")?;
+	let amount = parse_amount(amount, units)?;
+	Ok(command::ReserveArgs {
+		amount,
+		label: args.value_of("label").map(|l| l.to_owned()),
+	})
+}
+
+pub fn parse_reservations_args(
+	args: &ArgMatches,
+) -> Result<command::ReservationsArgs, ParseError> {
+	let release = match args.value_of("release") {
+		None => None,
+		Some(r) => match r.parse() {
+			Ok(id) => Some(id),
+			Err(e) => {
+				let msg = format!("Could not parse release parameter. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+	};
+	Ok(command::ReservationsArgs { release })
+}
+
+pub fn parse_send_queue_args(args: &ArgMatches) -> Result<command::SendQueueArgs, ParseError> {
+	let cancel = match args.value_of("cancel") {
+		None => None,
+		Some(c) => match c.parse() {
+			Ok(id) => Some(id),
+			Err(e) => {
+				let msg = format!("Could not parse cancel parameter. e={}", e);
+				return Err(ParseError::ArgumentError(msg));
+			}
+		},
+	};
+	Ok(command::SendQueueArgs {
+		cancel,
+		process: args.is_present("process"),
+	})
+}
+
+pub fn parse_jobs_args(args: &ArgMatches) -> Result<command::JobsArgs, ParseError> {
+	let parse_id = |name: &str| match args.value_of(name) {
+		None => Ok(None),
+		Some(i) => i.parse().map(Some).map_err(|e| {
+			let msg = format!("Could not parse {} parameter. e={}", name, e);
+			ParseError::ArgumentError(msg)
+		}),
+	};
+	Ok(command::JobsArgs {
+		retry: parse_id("retry")?,
+		delete: parse_id("delete")?,
+		process: args.is_present("process"),
+	})
+}
+
+pub fn parse_export_account_key_args(
+	args: &ArgMatches,
+	units: Denomination,
+) -> Result<command::ExportAccountKeyArgs, ParseError> {
+	let mut values = vec![];
+	for value in args.values_of("values").into_iter().flatten() {
+		values.push(parse_amount(value, units)?);
+	}
+	let parse_u32 = |name: &str| -> Result<u32, ParseError> {
+		let arg = parse_required(args, name)?;
+		match arg.parse::<u32>() {
+			Ok(v) => Ok(v),
+			Err(e) => {
+				let msg = format!("Could not parse {} as a child index. e={}", name, e);
+				Err(ParseError::ArgumentError(msg))
+			}
+		}
+	};
+	Ok(command::ExportAccountKeyArgs {
+		values,
+		from_index: parse_u32("from")?,
+		count: parse_u32("count")?,
+		accept_warning: args.is_present("accept_warning"),
+	})
+}
+
 pub fn parse_export_proof_args(args: &ArgMatches) -> Result<command::ProofExportArgs, ParseError> {
 	let output_file = parse_required(args, "output")?;
 	let tx_id = match args.value_of("id") {
@@ -820,6 +1521,37 @@ pub fn parse_verify_proof_args(args: &ArgMatches) -> Result<command::ProofVerify
 	})
 }
 
+pub fn parse_export_state_args(args: &ArgMatches) -> Result<command::StateExportArgs, ParseError> {
+	let output_file = parse_required(args, "output")?;
+	Ok(command::StateExportArgs {
+		output_file: output_file.to_owned(),
+	})
+}
+
+pub fn parse_import_csv_args(
+	args: &ArgMatches,
+	units: Denomination,
+) -> Result<command::CsvImportArgs, ParseError> {
+	let outputs_file = args.value_of("outputs").map(|f| f.to_owned());
+	let txs_file = args.value_of("txs").map(|f| f.to_owned());
+	if outputs_file.is_none() && txs_file.is_none() {
+		let msg = format!("At least one of 'outputs' (-o) or 'txs' (-t) must be provided.");
+		return Err(ParseError::ArgumentError(msg));
+	}
+	Ok(command::CsvImportArgs {
+		outputs_file,
+		txs_file,
+		units,
+	})
+}
+
+pub fn parse_import_state_args(args: &ArgMatches) -> Result<command::StateImportArgs, ParseError> {
+	let input_file = parse_required(args, "input")?;
+	Ok(command::StateImportArgs {
+		input_file: input_file.to_owned(),
+	})
+}
+
 pub fn wallet_command<C, F>(
 	wallet_args: &ArgMatches,
 	mut wallet_config: WalletConfig,
@@ -862,6 +1594,7 @@ where
 	}
 
 	let global_wallet_args = arg_parse!(parse_global_args(&wallet_config, &wallet_args));
+	display::set_units(global_wallet_args.units);
 
 	node_client.set_node_url(&wallet_config.check_node_api_http_addr);
 	node_client.set_node_api_secret(global_wallet_args.node_api_secret.clone());
@@ -890,7 +1623,7 @@ where
 	let wallet =
 		inst_wallet::<DefaultLCProvider<C, keychain::ExtKeychain>, C, keychain::ExtKeychain>(
 			wallet_config.clone(),
-			node_client,
+			node_client.clone(),
 		)
 		.unwrap_or_else(|e| {
 			println!("{}", e);
@@ -901,6 +1634,17 @@ where
 		let mut wallet_lock = wallet.lock();
 		let lc = wallet_lock.lc_provider().unwrap();
 		let _ = lc.set_top_level_directory(&wallet_config.data_file_dir);
+		// a read-only owner API opens the wallet as a replica
+		let read_only = wallet_config.owner_api_read_only.unwrap_or(false);
+		match wallet_args.subcommand() {
+			("owner_api", Some(args)) if read_only || args.is_present("read_only") => {
+				let _ = lc.set_read_only(true);
+			}
+			("web", _) if read_only => {
+				let _ = lc.set_read_only(true);
+			}
+			_ => {}
+		}
 	}
 
 	// provide wallet instance back to the caller (handy for testing with
@@ -912,7 +1656,12 @@ where
 	match wallet_args.subcommand() {
 		("init", Some(_)) => open_wallet = false,
 		("recover", _) => open_wallet = false,
-		("owner_api", _) => {
+		("migrate", _) => open_wallet = false,
+		("supervise", _) => open_wallet = false,
+		("keyring", Some(args)) if args.is_present("remove") => open_wallet = false,
+		// only running the service needs the wallet, not managing it
+		("service", Some(args)) if args.subcommand().0 != "run" => open_wallet = false,
+		("owner_api", _) | ("doctor", _) => {
 			// If wallet exists, open it. Otherwise, that's fine too.
 			let mut wallet_lock = wallet.lock();
 			let lc = wallet_lock.lc_provider().unwrap();
@@ -921,20 +1670,18 @@ where
 		_ => {}
 	}
 
+	let mut wallet_password = None;
 	let keychain_mask = match open_wallet {
 		true => {
 			let mut wallet_lock = wallet.lock();
 			let lc = wallet_lock.lc_provider().unwrap();
-			let mask = lc.open_wallet(
-				None,
-				prompt_password(&global_wallet_args.password),
-				false,
-				false,
-			)?;
+			let password = prompt_password(&global_wallet_args.password);
+			let mask = lc.open_wallet(None, password.clone(), false, false)?;
 			if let Some(account) = wallet_args.value_of("account") {
 				let wallet_inst = lc.wallet_inst()?;
 				wallet_inst.set_parent_key_id_by_name(account)?;
 			}
+			wallet_password = Some(password);
 			mask
 		}
 		false => None,
@@ -942,6 +1689,11 @@ where
 
 	let km = (&keychain_mask).as_ref();
 
+	// the doctor reports on the node itself
+	if open_wallet && wallet_args.subcommand().0 != "doctor" {
+		command::node_ping(wallet.clone(), km)?;
+	}
+
 	let res = match wallet_args.subcommand() {
 		("init", Some(args)) => {
 			let a = arg_parse!(parse_init_args(
@@ -956,6 +1708,10 @@ where
 			let a = arg_parse!(parse_recover_args(&global_wallet_args,));
 			command::recover(wallet, a)
 		}
+		("migrate", Some(args)) => {
+			let a = arg_parse!(parse_migrate_args(&args));
+			command::migrate(wallet, a)
+		}
 		("listen", Some(args)) => {
 			let mut c = wallet_config.clone();
 			let mut t = tor_config.clone();
@@ -969,6 +1725,29 @@ where
 				&global_wallet_args.clone(),
 			)
 		}
+		("service", Some(args)) => {
+			let a = arg_parse!(parse_service_args(&wallet_config, &wallet_args, &args));
+			command::service(
+				wallet,
+				keychain_mask,
+				&wallet_config,
+				&tor_config,
+				a,
+				&global_wallet_args,
+			)
+		}
+		("supervise", Some(args)) => {
+			let a = arg_parse!(parse_supervise_args(&args));
+			let mut wallets = vec![];
+			for w in a.wallets.iter() {
+				wallets.push(open_supervised_wallet(
+					&wallet_config,
+					node_client.clone(),
+					w,
+				)?);
+			}
+			command::supervise(wallets, &global_wallet_args)
+		}
 		("owner_api", Some(args)) => {
 			let mut c = wallet_config.clone();
 			let mut g = global_wallet_args.clone();
@@ -988,11 +1767,13 @@ where
 			command::account(wallet, km, a)
 		}
 		("send", Some(args)) => {
-			let a = arg_parse!(parse_send_args(&args));
+			let a = arg_parse!(parse_send_args(&args, global_wallet_args.units));
 			command::send(
 				wallet,
 				km,
+				&global_wallet_args,
 				Some(tor_config),
+				wallet_config.min_reorg_safe_confirmations,
 				a,
 				wallet_config.dark_background_color_scheme.unwrap_or(true),
 			)
@@ -1003,18 +1784,27 @@ where
 		}
 		("finalize", Some(args)) => {
 			let a = arg_parse!(parse_finalize_args(&args));
-			command::finalize(wallet, km, a)
+			command::finalize(wallet, km, &global_wallet_args, a)
 		}
 		("invoice", Some(args)) => {
-			let a = arg_parse!(parse_issue_invoice_args(&args));
+			let a = arg_parse!(parse_issue_invoice_args(
+				&args,
+				global_wallet_args.units
+			));
 			command::issue_invoice_tx(wallet, km, a)
 		}
 		("pay", Some(args)) => {
-			let a = arg_parse!(parse_process_invoice_args(&args, !test_mode));
+			let a = arg_parse!(parse_process_invoice_args(
+				&args,
+				!test_mode,
+				global_wallet_args.units
+			));
 			command::process_invoice(
 				wallet,
 				km,
 				Some(tor_config),
+				wallet_config.min_reorg_safe_confirmations,
+				wallet_config.max_invoice_fee,
 				a,
 				wallet_config.dark_background_color_scheme.unwrap_or(true),
 			)
@@ -1029,6 +1819,13 @@ where
 				wallet_config.dark_background_color_scheme.unwrap_or(true),
 			)
 		}
+		("request", Some(args)) => {
+			let a = arg_parse!(parse_payment_request_args(
+				&args,
+				global_wallet_args.units
+			));
+			command::payment_request(wallet, km, &global_wallet_args, a)
+		}
 		("outputs", Some(args)) => {
 			let a = arg_parse!(parse_outputs_args(&args));
 			command::outputs(
@@ -1049,6 +1846,26 @@ where
 				wallet_config.dark_background_color_scheme.unwrap_or(true),
 			)
 		}
+		("tx", Some(args)) => match args.subcommand() {
+			("show", Some(args)) => {
+				let a = arg_parse!(parse_tx_args(&args));
+				command::tx_show(
+					wallet,
+					km,
+					&global_wallet_args,
+					a,
+					wallet_config.dark_background_color_scheme.unwrap_or(true),
+				)
+			}
+			("resume", Some(args)) => {
+				let a = arg_parse!(parse_tx_args(&args));
+				command::tx_resume(wallet, km, &global_wallet_args, a)
+			}
+			_ => {
+				let msg = format!("Unknown tx command, use 'epic-wallet tx help' for details");
+				return Err(ErrorKind::ArgumentError(msg).into());
+			}
+		},
 		("post", Some(args)) => {
 			let a = arg_parse!(parse_post_args(&args));
 			command::post(wallet, km, a)
@@ -1059,7 +1876,75 @@ where
 		}
 		("cancel", Some(args)) => {
 			let a = arg_parse!(parse_cancel_args(&args));
-			command::cancel(wallet, km, a)
+			command::cancel(
+				wallet,
+				km,
+				&global_wallet_args,
+				wallet_config.dark_background_color_scheme.unwrap_or(true),
+				a,
+			)
+		}
+		("locks", Some(_)) => command::locks(
+			wallet,
+			km,
+			&global_wallet_args,
+			wallet_config.dark_background_color_scheme.unwrap_or(true),
+		),
+		("unlock", Some(args)) => {
+			let a = arg_parse!(parse_unlock_args(&args));
+			command::unlock(wallet, km, &global_wallet_args, a)
+		}
+		("tag", Some(args)) => {
+			let a = arg_parse!(parse_tag_args(&args));
+			command::tag(wallet, km, &global_wallet_args, a)
+		}
+		("reserve", Some(args)) => {
+			let a = arg_parse!(parse_reserve_args(&args, global_wallet_args.units));
+			command::reserve(wallet, km, &global_wallet_args, a)
+		}
+		("reservations", Some(args)) => {
+			let a = arg_parse!(parse_reservations_args(&args));
+			command::reservations(
+				wallet,
+				km,
+				&global_wallet_args,
+				wallet_config.dark_background_color_scheme.unwrap_or(true),
+				a,
+			)
+		}
+		("send_queue", Some(args)) => {
+			let a = arg_parse!(parse_send_queue_args(&args));
+			command::send_queue(
+				wallet,
+				km,
+				&global_wallet_args,
+				Some(tor_config),
+				wallet_config.dark_background_color_scheme.unwrap_or(true),
+				a,
+			)
+		}
+		("jobs", Some(args)) => {
+			let a = arg_parse!(parse_jobs_args(&args));
+			command::jobs(
+				wallet,
+				km,
+				&global_wallet_args,
+				wallet_config.dark_background_color_scheme.unwrap_or(true),
+				a,
+			)
+		}
+		("export_account_key", Some(args)) => {
+			let a = arg_parse!(parse_export_account_key_args(
+				&args,
+				global_wallet_args.units
+			));
+			command::export_account_key(
+				wallet,
+				km,
+				&global_wallet_args,
+				wallet_config.dark_background_color_scheme.unwrap_or(true),
+				a,
+			)
 		}
 		("export_proof", Some(args)) => {
 			let a = arg_parse!(parse_export_proof_args(&args));
@@ -1069,7 +1954,62 @@ where
 			let a = arg_parse!(parse_verify_proof_args(&args));
 			command::proof_verify(wallet, km, a)
 		}
+		("export_state", Some(args)) => {
+			let a = arg_parse!(parse_export_state_args(&args));
+			command::state_export(wallet, km, a)
+		}
+		("import_state", Some(args)) => {
+			let a = arg_parse!(parse_import_state_args(&args));
+			command::state_import(wallet, km, a)
+		}
+		("import_csv", Some(args)) => {
+			let a = arg_parse!(parse_import_csv_args(&args, global_wallet_args.units));
+			command::csv_import(wallet, km, a)
+		}
 		("address", Some(_)) => command::address(wallet, &global_wallet_args, km),
+		("status", Some(_)) => command::status(wallet, km),
+		("doctor", Some(args)) => {
+			let a = arg_parse!(parse_doctor_args(&args));
+			command::doctor(
+				wallet,
+				km,
+				&wallet_config,
+				&global_wallet_args,
+				a,
+				open_wallet,
+			)
+		}
+		("rewards", Some(args)) => {
+			let a = arg_parse!(parse_rewards_args(&args));
+			command::rewards(wallet, km, &global_wallet_args, a)
+		}
+		("seed", Some(args)) => match args.subcommand() {
+			("show", Some(_)) => {
+				let a = arg_parse!(parse_seed_show_args());
+				command::seed_show(wallet, km, a)
+			}
+			_ => {
+				let msg = format!("Unknown seed command, use 'epic-wallet seed help' for details");
+				return Err(ErrorKind::ArgumentError(msg).into());
+			}
+		},
+		("keyring", Some(args)) => {
+			let res = match args.is_present("remove") {
+				true => password::remove_from_keyring(&wallet_config.data_file_dir),
+				false => password::save_to_keyring(
+					&wallet_config.data_file_dir,
+					&wallet_password.unwrap(),
+				),
+			};
+			arg_parse!(res);
+			match args.is_present("remove") {
+				true => println!("Wallet password removed from the OS keyring"),
+				false => println!(
+					"Wallet password stored in the OS keyring, use --keyring to open the wallet with it"
+				),
+			}
+			Ok(())
+		}
 		("scan", Some(args)) => {
 			let a = arg_parse!(parse_check_args(&args));
 			command::scan(wallet, km, a)
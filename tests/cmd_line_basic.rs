@@ -400,6 +400,9 @@ fn command_line_test_impl(test_dir: &str) -> Result<(), epic_wallet_controller::
 	let arg_vec = vec!["epic-wallet", "-p", "password", "scan", "-d"];
 	execute_command(&app, test_dir, "wallet1", &client1, arg_vec)?;
 
+	let arg_vec = vec!["epic-wallet", "-p", "password", "doctor", "--fix"];
+	execute_command(&app, test_dir, "wallet1", &client1, arg_vec)?;
+
 	// Another file exchange, cancel this time
 	let arg_vec = vec![
 		"epic-wallet",
@@ -19,7 +19,7 @@ extern crate log;
 
 extern crate epic_wallet;
 
-use epic_wallet_api::{ECDHPubkey, RpcId};
+use epic_wallet_api::{ECDHPubkey, EncryptedBody, EncryptedRequest, RpcId};
 use epic_wallet_impls::test_framework::{self, LocalWalletClient, WalletProxy};
 
 use clap::App;
@@ -31,12 +31,13 @@ use epic_wallet_util::epic_keychain::ExtKeychain;
 use epic_wallet_util::epic_util::secp::key::SecretKey;
 use epic_wallet_util::epic_util::{from_hex, static_secp_instance};
 use serde_json;
+use url::Url;
 
 #[macro_use]
 mod common;
 use common::{
 	clean_output_dir, derive_ecdh_key, execute_command, initial_setup_wallet, instantiate_wallet,
-	send_request, send_request_enc, setup, RetrieveSummaryInfoResp,
+	post, send_request, send_request_enc, setup, RetrieveSummaryInfoResp,
 };
 
 #[test]
@@ -237,6 +238,48 @@ fn owner_v3_init_secure() -> Result<(), epic_wallet_controller::Error> {
 	assert!(res.is_err());
 	assert_eq!(res.unwrap_err().code, -32099);
 
+	// 14) An encrypted request whose nonce is valid hex of the wrong length
+	let req = serde_json::json!({
+		"jsonrpc": "2.0",
+		"id": 14,
+		"method": "encrypted_request_v3",
+		"params": {
+			"nonce": "000102",
+			"body_enc": "thisiswrong",
+		}
+	});
+	let res = send_request::<String>(14, "http://127.0.0.1:33420/v3/owner", &req.to_string())?;
+	println!("RES 14: {:?}", res);
+	assert!(res.is_err());
+	assert_eq!(res.unwrap_err().code, -32002);
+
+	// 15) The same encrypted request sent twice is refused the second time
+	let req: serde_json::Value =
+		serde_json::from_str(include_str!("data/v3_reqs/retrieve_info.req.json")).unwrap();
+	let enc_req = EncryptedRequest::from_json(RpcId::Integer(15), &req, &shared_key).unwrap();
+	let url = Url::parse("http://127.0.0.1:33420/v3/owner").unwrap();
+	let res: serde_json::Value = serde_json::from_str(&post(&url, None, &enc_req)?).unwrap();
+	println!("RES 15: {:?}", res);
+	assert!(res["error"].is_null());
+	let res: serde_json::Value = serde_json::from_str(&post(&url, None, &enc_req)?).unwrap();
+	println!("RES 15: {:?}", res);
+	assert_eq!(res["error"]["code"], -32004);
+	assert_eq!(res["id"], 15);
+
+	// 16) A request from an older client, without a timestamp, is still
+	// answered, and refused when sent again
+	let enc_req = EncryptedRequest {
+		id: RpcId::Integer(16),
+		params: EncryptedBody::from_json(&req, &shared_key).unwrap(),
+		..enc_req
+	};
+	let res: serde_json::Value = serde_json::from_str(&post(&url, None, &enc_req)?).unwrap();
+	println!("RES 16: {:?}", res);
+	assert!(res["error"].is_null());
+	let res: serde_json::Value = serde_json::from_str(&post(&url, None, &enc_req)?).unwrap();
+	println!("RES 16: {:?}", res);
+	assert_eq!(res["error"]["code"], -32004);
+
 	clean_output_dir(test_dir);
 
 	Ok(())
@@ -0,0 +1,131 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hardened building blocks for handling secrets: comparisons that take as
+//! long wherever the inputs differ, for tokens and MACs, and randomness for
+//! keys and nonces drawn from the OS random number generator alone and
+//! checked before it's used
+
+use std::collections::hash_map::DefaultHasher;
+use std::error;
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::epic_util::secp::key::SecretKey;
+use crate::epic_util::secp::Secp256k1;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// Outputs at least this long are checked against being stuck or repeated,
+/// shorter ones may well repeat by chance
+const HEALTH_CHECK_MIN_LEN: usize = 16;
+
+/// How many draws of a secret key may fall outside the curve order in a row.
+/// Any draw does with a chance of about 2^-128
+const SECRET_KEY_DRAWS: usize = 3;
+
+/// Fingerprint of the last checked output, for the repetition check
+static LAST_OUTPUT: AtomicU64 = AtomicU64::new(0);
+
+/// The OS random number generator couldn't be read, or its output failed a
+/// health check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RngError(pub String);
+
+impl fmt::Display for RngError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "random number generator: {}", self.0)
+	}
+}
+
+impl error::Error for RngError {}
+
+/// Whether two secrets, such as API tokens or MACs, are equal, taking as
+/// long wherever they differ. Only their lengths may be told apart
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && bool::from(a.ct_eq(b))
+}
+
+/// Fill the buffer from the OS random number generator. Outputs of 16 bytes
+/// or more are refused if all their bytes are the same, or if they repeat
+/// the output checked before them, as a failing source would produce
+pub fn os_random(buf: &mut [u8]) -> Result<(), RngError> {
+	let mut rng = OsRng::new().map_err(|e| RngError(format!("unavailable, {}", e)))?;
+	rng.try_fill_bytes(buf)
+		.map_err(|e| RngError(format!("read failed, {}", e)))?;
+	if buf.len() < HEALTH_CHECK_MIN_LEN {
+		return Ok(());
+	}
+	if buf.iter().all(|b| *b == buf[0]) {
+		buf.zeroize();
+		return Err(RngError("output is stuck".to_owned()));
+	}
+	let mut hasher = DefaultHasher::new();
+	hasher.write(buf);
+	let fingerprint = hasher.finish();
+	if LAST_OUTPUT.swap(fingerprint, Ordering::SeqCst) == fingerprint {
+		buf.zeroize();
+		return Err(RngError("output repeats".to_owned()));
+	}
+	Ok(())
+}
+
+/// A secret key, such as a signing nonce or a kernel offset, drawn from the
+/// OS random number generator through [os_random](fn.os_random.html)
+pub fn random_secret_key(secp: &Secp256k1) -> Result<SecretKey, RngError> {
+	let mut bytes = [0u8; 32];
+	for _ in 0..SECRET_KEY_DRAWS {
+		os_random(&mut bytes)?;
+		let key = SecretKey::from_slice(secp, &bytes);
+		bytes.zeroize();
+		if let Ok(k) = key {
+			return Ok(k);
+		}
+	}
+	Err(RngError("output is out of the curve order".to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::epic_util::secp::ContextFlag;
+
+	#[test]
+	fn constant_time_eq() {
+		assert!(ct_eq(b"token", b"token"));
+		assert!(!ct_eq(b"token", b"tokem"));
+		assert!(!ct_eq(b"token", b"token2"));
+		assert!(ct_eq(b"", b""));
+	}
+
+	#[test]
+	fn checked_randomness() {
+		let mut a = [0u8; 32];
+		let mut b = [0u8; 32];
+		os_random(&mut a).unwrap();
+		os_random(&mut b).unwrap();
+		assert_ne!(a, b);
+		// short outputs aren't checked
+		let mut short = [0u8; 4];
+		os_random(&mut short).unwrap();
+
+		let secp = Secp256k1::with_caps(ContextFlag::SignOnly);
+		let k1 = random_secret_key(&secp).unwrap();
+		let k2 = random_secret_key(&secp).unwrap();
+		assert_ne!(k1, k2);
+	}
+}
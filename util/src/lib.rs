@@ -20,9 +20,21 @@
 #![deny(unused_mut)]
 #![warn(missing_docs)]
 
+#[cfg(unix)]
+#[macro_use]
+extern crate lazy_static;
+
+#[cfg(feature = "api")]
 pub use epic_api;
+#[cfg(feature = "chain")]
 pub use epic_chain;
 pub use epic_core;
 pub use epic_keychain;
+#[cfg(feature = "store")]
 pub use epic_store;
 pub use epic_util;
+
+pub mod crypto;
+pub mod secret;
+pub use crypto::{ct_eq, os_random, random_secret_key, RngError};
+pub use secret::{zeroize_secret_key, SecretBytes};
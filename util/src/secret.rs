@@ -0,0 +1,220 @@
+// Copyright 2019 The Epic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Holding secrets in memory: buffers locked out of swap and zeroed when
+//! dropped, and zeroing of secret keys, which are copied about freely
+
+#[cfg(unix)]
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::epic_util::secp::key::SecretKey;
+#[cfg(unix)]
+use crate::epic_util::Mutex;
+use zeroize::Zeroize;
+
+/// Bytes of a secret, such as a wallet seed. The memory holding them is
+/// locked, so it isn't swapped to disk, where the OS allows it (see
+/// [is_locked](#method.is_locked)), and zeroed when they're dropped
+pub struct SecretBytes {
+	bytes: Box<[u8]>,
+	locked: bool,
+}
+
+impl SecretBytes {
+	/// Zeroed secret bytes of the given length
+	pub fn new(len: usize) -> SecretBytes {
+		let bytes = vec![0u8; len].into_boxed_slice();
+		let locked = lock(&bytes);
+		SecretBytes { bytes, locked }
+	}
+
+	/// Secret bytes holding a copy of the slice, which the caller still has
+	/// to zero
+	pub fn from_slice(bytes: &[u8]) -> SecretBytes {
+		let mut secret = SecretBytes::new(bytes.len());
+		secret.copy_from_slice(bytes);
+		secret
+	}
+
+	/// Secret bytes taking over the vector's contents, which is zeroed.
+	/// Copies left by the vector growing earlier can't be reached anymore
+	pub fn from_vec(mut bytes: Vec<u8>) -> SecretBytes {
+		let secret = SecretBytes::from_slice(&bytes);
+		bytes.zeroize();
+		secret
+	}
+
+	/// Whether the memory is locked. It isn't on platforms without support
+	/// for it, or when the process is over its limit of locked memory
+	pub fn is_locked(&self) -> bool {
+		self.locked
+	}
+}
+
+impl Deref for SecretBytes {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+impl DerefMut for SecretBytes {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		&mut self.bytes
+	}
+}
+
+impl Clone for SecretBytes {
+	fn clone(&self) -> SecretBytes {
+		SecretBytes::from_slice(&self.bytes)
+	}
+}
+
+impl PartialEq for SecretBytes {
+	fn eq(&self, other: &SecretBytes) -> bool {
+		self.bytes == other.bytes
+	}
+}
+
+/// Never shows the secret
+impl fmt::Debug for SecretBytes {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "SecretBytes({} bytes)", self.bytes.len())
+	}
+}
+
+impl Drop for SecretBytes {
+	fn drop(&mut self) {
+		self.bytes.zeroize();
+		if self.locked {
+			unlock(&self.bytes);
+		}
+	}
+}
+
+/// Zero a secret key, such as a keychain mask or a transaction's secret
+/// nonce, once it's no longer needed. Secret keys are `Copy`, so any copies
+/// have to be zeroed in turn
+pub fn zeroize_secret_key(key: &mut SecretKey) {
+	key.0.zeroize();
+}
+
+#[cfg(unix)]
+lazy_static! {
+	/// How many live buffers each locked page holds, by address. `munlock`
+	/// unlocks whole pages, so a page is only unlocked once no buffer on it
+	/// is left
+	static ref LOCKED_PAGES: Mutex<HashMap<usize, usize>> = Mutex::new(HashMap::new());
+	static ref PAGE_SIZE: usize = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+		n if n > 0 => n as usize,
+		_ => 4096,
+	};
+}
+
+/// Addresses of the pages the bytes are on
+#[cfg(unix)]
+fn pages(bytes: &[u8]) -> impl Iterator<Item = usize> {
+	let page_size = *PAGE_SIZE;
+	let start = bytes.as_ptr() as usize / page_size;
+	let end = (bytes.as_ptr() as usize + bytes.len() - 1) / page_size;
+	(start..=end).map(move |p| p * page_size)
+}
+
+#[cfg(unix)]
+fn lock(bytes: &[u8]) -> bool {
+	if bytes.is_empty() {
+		return false;
+	}
+	let page_size = *PAGE_SIZE;
+	let mut locked_pages = LOCKED_PAGES.lock();
+	let mut newly_locked = vec![];
+	for page in pages(bytes) {
+		if locked_pages.contains_key(&page) {
+			continue;
+		}
+		if unsafe { libc::mlock(page as *const libc::c_void, page_size) } != 0 {
+			for page in newly_locked {
+				unsafe { libc::munlock(page as *const libc::c_void, page_size) };
+			}
+			return false;
+		}
+		newly_locked.push(page);
+	}
+	for page in pages(bytes) {
+		*locked_pages.entry(page).or_insert(0) += 1;
+	}
+	true
+}
+
+#[cfg(unix)]
+fn unlock(bytes: &[u8]) {
+	let page_size = *PAGE_SIZE;
+	let mut locked_pages = LOCKED_PAGES.lock();
+	for page in pages(bytes) {
+		let count = match locked_pages.get_mut(&page) {
+			Some(count) => count,
+			None => continue,
+		};
+		*count -= 1;
+		if *count == 0 {
+			locked_pages.remove(&page);
+			unsafe { libc::munlock(page as *const libc::c_void, page_size) };
+		}
+	}
+}
+
+#[cfg(not(unix))]
+fn lock(_bytes: &[u8]) -> bool {
+	false
+}
+
+#[cfg(not(unix))]
+fn unlock(_bytes: &[u8]) {}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn secret_bytes() {
+		let secret = SecretBytes::from_vec(vec![7; 32]);
+		assert_eq!(&secret[..], &[7; 32][..]);
+		assert_eq!(secret.clone(), secret);
+		assert_eq!(format!("{:?}", secret), "SecretBytes(32 bytes)");
+		assert!(!SecretBytes::new(0).is_locked());
+
+		let mut key = SecretKey([3; 32]);
+		zeroize_secret_key(&mut key);
+		assert_eq!(key.0, [0; 32]);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn shared_pages_stay_locked() {
+		// small buffers end up on the same page, which stays locked until
+		// the last of them is dropped
+		let first = SecretBytes::new(16);
+		let second = SecretBytes::new(16);
+		if !first.is_locked() || !second.is_locked() {
+			return;
+		}
+		let second_pages: Vec<usize> = pages(&second).collect();
+		drop(first);
+		let locked_pages = LOCKED_PAGES.lock();
+		assert!(second_pages.iter().all(|p| locked_pages.contains_key(p)));
+	}
+}